@@ -138,6 +138,7 @@ export const sidebar = [
       { text: "Cluster Manager", link: "/en/RobustMQ-Command/CLI_CLUSTER" },
       { text: "MQTT Manager", link: "/en/RobustMQ-Command/CLI_MQTT" },
       { text: "Journal Manager", link: "/en/RobustMQ-Command/CLI_JOURNAL" },
+      { text: "MQTT Benchmark", link: "/en/RobustMQ-Command/BENCH_MQTT" },
     ],
   },
   {
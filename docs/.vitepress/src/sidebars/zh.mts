@@ -149,6 +149,7 @@ export const sidebar = [
       { text: "集群管理", link: "/zh/RobustMQ-Command/CLI_CLUSTER" },
       { text: "MQTT 管理", link: "/zh/RobustMQ-Command/CLI_MQTT" },
       { text: "Journal 管理", link: "/zh/RobustMQ-Command/CLI_JOURNAL" },
+      { text: "MQTT 压测", link: "/zh/RobustMQ-Command/BENCH_MQTT" },
     ],
   },
   {
@@ -404,6 +404,15 @@ pub async fn create_schema<T>(&self, request: &T) -> Result<String, HttpClientEr
             .await
     }
 
+    /// Update schema
+    pub async fn update_schema<T>(&self, request: &T) -> Result<String, HttpClientError>
+    where
+        T: Serialize,
+    {
+        self.post_raw(&api_path(MQTT_SCHEMA_UPDATE_PATH), request)
+            .await
+    }
+
     /// Delete schema
     pub async fn delete_schema<T>(&self, request: &T) -> Result<String, HttpClientError>
     where
@@ -475,6 +484,136 @@ pub async fn get_cluster_config<T>(&self, request: &T) -> Result<String, HttpCli
             .await
     }
 
+    /// Start draining the broker
+    pub async fn drain_broker<T>(&self, request: &T) -> Result<String, HttpClientError>
+    where
+        T: Serialize,
+    {
+        self.post(&api_path(CLUSTER_DRAIN_START_PATH), request)
+            .await
+    }
+
+    /// Get the drain progress of the broker
+    pub async fn get_drain_status<T, R>(&self, request: &T) -> Result<R, HttpClientError>
+    where
+        T: Serialize,
+        R: for<'de> Deserialize<'de>,
+    {
+        self.post(&api_path(CLUSTER_DRAIN_STATUS_PATH), request)
+            .await
+    }
+
+    /// Get the connection load of every broker
+    pub async fn get_cluster_load<T, R>(&self, request: &T) -> Result<R, HttpClientError>
+    where
+        T: Serialize,
+        R: for<'de> Deserialize<'de>,
+    {
+        self.post(&api_path(CLUSTER_LOAD_PATH), request).await
+    }
+
+    /// Move a percentage of the clients of the broker to less loaded brokers
+    pub async fn rebalance_connections<T, R>(&self, request: &T) -> Result<R, HttpClientError>
+    where
+        T: Serialize,
+        R: for<'de> Deserialize<'de>,
+    {
+        self.post(&api_path(CLUSTER_REBALANCE_PATH), request).await
+    }
+
+    /// Get the log filters of the broker and the levels changed at runtime
+    pub async fn get_log_levels<T, R>(&self, request: &T) -> Result<R, HttpClientError>
+    where
+        T: Serialize,
+        R: for<'de> Deserialize<'de>,
+    {
+        self.post(&api_path(CLUSTER_LOG_LEVEL_LIST_PATH), request)
+            .await
+    }
+
+    /// Change the log level of a target at runtime
+    pub async fn set_log_level<T>(&self, request: &T) -> Result<String, HttpClientError>
+    where
+        T: Serialize,
+    {
+        self.post_raw(&api_path(CLUSTER_LOG_LEVEL_SET_PATH), request)
+            .await
+    }
+
+    /// Revert the log level of a target to the configured one
+    pub async fn reset_log_level<T>(&self, request: &T) -> Result<String, HttpClientError>
+    where
+        T: Serialize,
+    {
+        self.post_raw(&api_path(CLUSTER_LOG_LEVEL_RESET_PATH), request)
+            .await
+    }
+
+    /// Get meta service raft status
+    pub async fn get_meta_status<T, R>(&self, request: &T) -> Result<R, HttpClientError>
+    where
+        T: Serialize,
+        R: for<'de> Deserialize<'de>,
+    {
+        self.post(&api_path(CLUSTER_META_STATUS_PATH), request)
+            .await
+    }
+
+    /// Add a meta service node as learner
+    pub async fn add_meta_learner<T>(&self, request: &T) -> Result<String, HttpClientError>
+    where
+        T: Serialize,
+    {
+        self.post(&api_path(CLUSTER_META_ADD_LEARNER_PATH), request)
+            .await
+    }
+
+    /// Promote a meta service learner to voter
+    pub async fn promote_meta_node<T>(&self, request: &T) -> Result<String, HttpClientError>
+    where
+        T: Serialize,
+    {
+        self.post(&api_path(CLUSTER_META_PROMOTE_PATH), request)
+            .await
+    }
+
+    /// Remove a meta service node from the cluster
+    pub async fn remove_meta_node<T>(&self, request: &T) -> Result<String, HttpClientError>
+    where
+        T: Serialize,
+    {
+        self.post(&api_path(CLUSTER_META_REMOVE_PATH), request)
+            .await
+    }
+
+    /// Transfer meta service leadership to another voter
+    pub async fn transfer_meta_leader<T>(&self, request: &T) -> Result<String, HttpClientError>
+    where
+        T: Serialize,
+    {
+        self.post(&api_path(CLUSTER_META_TRANSFER_LEADER_PATH), request)
+            .await
+    }
+
+    /// Export a metadata archive of the meta service
+    pub async fn backup_meta<T>(&self, request: &T) -> Result<String, HttpClientError>
+    where
+        T: Serialize,
+    {
+        self.post(&api_path(CLUSTER_META_BACKUP_PATH), request)
+            .await
+    }
+
+    /// Restore or import a metadata archive
+    pub async fn restore_meta<T, R>(&self, request: &T) -> Result<R, HttpClientError>
+    where
+        T: Serialize,
+        R: for<'de> Deserialize<'de>,
+    {
+        self.post(&api_path(CLUSTER_META_RESTORE_PATH), request)
+            .await
+    }
+
     /// Get flapping detection list
     pub async fn get_flapping_detect_list<T, R>(
         &self,
@@ -563,6 +702,219 @@ pub async fn get_slow_subscribe_list<T, R>(
             .await
     }
 
+    /// Get audit log list
+    pub async fn get_audit_log_list<T, R>(
+        &self,
+        request: &T,
+    ) -> Result<PageReplyData<R>, HttpClientError>
+    where
+        T: Serialize,
+        R: for<'de> Deserialize<'de>,
+    {
+        self.post(&api_path(MQTT_AUDIT_LOG_LIST_PATH), request)
+            .await
+    }
+
+    /// Export audit log as JSON lines
+    pub async fn export_audit_log<T>(&self, request: &T) -> Result<String, HttpClientError>
+    where
+        T: Serialize,
+    {
+        self.post_raw(&api_path(MQTT_AUDIT_LOG_EXPORT_PATH), request)
+            .await
+    }
+
+    /// Get packet trace list
+    pub async fn get_packet_trace_list<T, R>(
+        &self,
+        request: &T,
+    ) -> Result<PageReplyData<R>, HttpClientError>
+    where
+        T: Serialize,
+        R: for<'de> Deserialize<'de>,
+    {
+        self.post(&api_path(MQTT_TRACE_LIST_PATH), request).await
+    }
+
+    /// Start a packet trace
+    pub async fn create_packet_trace<T>(&self, request: &T) -> Result<String, HttpClientError>
+    where
+        T: Serialize,
+    {
+        self.post_raw(&api_path(MQTT_TRACE_CREATE_PATH), request)
+            .await
+    }
+
+    /// Stop a running packet trace
+    pub async fn stop_packet_trace<T>(&self, request: &T) -> Result<String, HttpClientError>
+    where
+        T: Serialize,
+    {
+        self.post_raw(&api_path(MQTT_TRACE_STOP_PATH), request)
+            .await
+    }
+
+    /// Delete a packet trace together with its captured packets
+    pub async fn delete_packet_trace<T>(&self, request: &T) -> Result<String, HttpClientError>
+    where
+        T: Serialize,
+    {
+        self.post_raw(&api_path(MQTT_TRACE_DELETE_PATH), request)
+            .await
+    }
+
+    /// Download the captured packets of a trace as JSON lines
+    pub async fn download_packet_trace<T>(&self, request: &T) -> Result<String, HttpClientError>
+    where
+        T: Serialize,
+    {
+        self.post_raw(&api_path(MQTT_TRACE_DOWNLOAD_PATH), request)
+            .await
+    }
+
+    /// Get the delivery timeline of a message
+    pub async fn get_delivery_message_timeline<T>(
+        &self,
+        request: &T,
+    ) -> Result<String, HttpClientError>
+    where
+        T: Serialize,
+    {
+        self.post_raw(&api_path(MQTT_DELIVERY_MESSAGE_PATH), request)
+            .await
+    }
+
+    /// Get the delivery events of a client
+    pub async fn get_delivery_client_timeline<T, R>(
+        &self,
+        request: &T,
+    ) -> Result<PageReplyData<R>, HttpClientError>
+    where
+        T: Serialize,
+        R: for<'de> Deserialize<'de>,
+    {
+        self.post(&api_path(MQTT_DELIVERY_CLIENT_PATH), request)
+            .await
+    }
+
+    /// Get the topic metrics rules
+    pub async fn get_topic_metrics_list<T, R>(
+        &self,
+        request: &T,
+    ) -> Result<PageReplyData<R>, HttpClientError>
+    where
+        T: Serialize,
+        R: for<'de> Deserialize<'de>,
+    {
+        self.post(&api_path(MQTT_TOPIC_METRICS_LIST_PATH), request)
+            .await
+    }
+
+    /// Opt a topic or topic filter in for per-topic metrics
+    pub async fn create_topic_metrics<T>(&self, request: &T) -> Result<String, HttpClientError>
+    where
+        T: Serialize,
+    {
+        self.post_raw(&api_path(MQTT_TOPIC_METRICS_CREATE_PATH), request)
+            .await
+    }
+
+    /// Stop exporting per-topic metrics for a topic filter
+    pub async fn delete_topic_metrics<T>(&self, request: &T) -> Result<String, HttpClientError>
+    where
+        T: Serialize,
+    {
+        self.post_raw(&api_path(MQTT_TOPIC_METRICS_DELETE_PATH), request)
+            .await
+    }
+
+    /// Get the current metrics of the tracked topics
+    pub async fn get_topic_metrics_data<T, R>(
+        &self,
+        request: &T,
+    ) -> Result<PageReplyData<R>, HttpClientError>
+    where
+        T: Serialize,
+        R: for<'de> Deserialize<'de>,
+    {
+        self.post(&api_path(MQTT_TOPIC_METRICS_DATA_PATH), request)
+            .await
+    }
+
+    /// Get the alarm rules, built-in rules included
+    pub async fn get_alarm_rule_list<T, R>(
+        &self,
+        request: &T,
+    ) -> Result<PageReplyData<R>, HttpClientError>
+    where
+        T: Serialize,
+        R: for<'de> Deserialize<'de>,
+    {
+        self.post(&api_path(MQTT_ALARM_RULE_LIST_PATH), request)
+            .await
+    }
+
+    /// Create or replace an alarm rule
+    pub async fn create_alarm_rule<T>(&self, request: &T) -> Result<String, HttpClientError>
+    where
+        T: Serialize,
+    {
+        self.post_raw(&api_path(MQTT_ALARM_RULE_CREATE_PATH), request)
+            .await
+    }
+
+    /// Delete an alarm rule
+    pub async fn delete_alarm_rule<T>(&self, request: &T) -> Result<String, HttpClientError>
+    where
+        T: Serialize,
+    {
+        self.post_raw(&api_path(MQTT_ALARM_RULE_DELETE_PATH), request)
+            .await
+    }
+
+    /// Get the alarm activation and deactivation history
+    pub async fn get_alarm_history_list<T, R>(
+        &self,
+        request: &T,
+    ) -> Result<PageReplyData<R>, HttpClientError>
+    where
+        T: Serialize,
+        R: for<'de> Deserialize<'de>,
+    {
+        self.post(&api_path(MQTT_ALARM_HISTORY_LIST_PATH), request)
+            .await
+    }
+
+    /// Get the webhooks with their delivery health on the requested broker
+    pub async fn get_webhook_list<T, R>(
+        &self,
+        request: &T,
+    ) -> Result<PageReplyData<R>, HttpClientError>
+    where
+        T: Serialize,
+        R: for<'de> Deserialize<'de>,
+    {
+        self.post(&api_path(MQTT_WEBHOOK_LIST_PATH), request).await
+    }
+
+    /// Create or replace a webhook
+    pub async fn create_webhook<T>(&self, request: &T) -> Result<String, HttpClientError>
+    where
+        T: Serialize,
+    {
+        self.post_raw(&api_path(MQTT_WEBHOOK_CREATE_PATH), request)
+            .await
+    }
+
+    /// Delete a webhook
+    pub async fn delete_webhook<T>(&self, request: &T) -> Result<String, HttpClientError>
+    where
+        T: Serialize,
+    {
+        self.post_raw(&api_path(MQTT_WEBHOOK_DELETE_PATH), request)
+            .await
+    }
+
     /// Get subscribe detail
     pub async fn get_subscribe_detail<T>(&self, request: &T) -> Result<String, HttpClientError>
     where
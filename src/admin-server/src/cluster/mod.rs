@@ -15,21 +15,65 @@
 use std::sync::Arc;
 
 use crate::{
-    request::cluster::{ClusterConfigGetReq, ClusterConfigSetReq},
+    request::cluster::{
+        ClusterConfigGetReq, ClusterConfigSetReq, ClusterLoadReq, ClusterRebalanceReq,
+        DrainStartReq, DrainStatusReq, LogLevelListReq, LogLevelResetReq, LogLevelSetReq,
+    },
+    response::cluster::{
+        ClusterLoadResp, ClusterRebalanceResp, DrainStatusResp, LogLevelListResp, NodeLoadRow,
+    },
     state::HttpState,
+    tool::audit::{audit_value, record_admin_audit, AuditContext},
 };
 use axum::{extract::State, Json};
 use common_base::{
     enum_type::feature_type::FeatureType,
     http_response::{error_response, success_response},
+    logging::dynamic_filter::{
+        appender_filters, log_level_overrides, reset_log_level, set_log_level,
+    },
 };
+use common_config::broker::broker_config;
+use common_config::config::MqttSharedSubscription;
+use mqtt_broker::handler::audit_log::AuditAction;
+use mqtt_broker::handler::balance::{is_rebalancing, start_rebalance, RebalanceContext};
+use mqtt_broker::handler::drain::{drain_status, start_drain, DrainContext};
+use mqtt_broker::handler::dynamic_config::{save_cluster_dynamic_config, ClusterDynamicConfig};
 use std::str::FromStr;
+use std::time::Duration;
 
 pub async fn cluster_config_set(
-    State(_state): State<Arc<HttpState>>,
+    State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
     Json(params): Json<ClusterConfigSetReq>,
 ) -> String {
-    match FeatureType::from_str(params.config_type.as_str()) {
+    let feature_type = FeatureType::from_str(params.config_type.as_str());
+    let before = match &feature_type {
+        Ok(feature) => {
+            let cluster = state.broker_cache.get_cluster_config().await;
+            match feature {
+                FeatureType::SlowSubscribe => audit_value(&cluster.mqtt_slow_subscribe_config),
+                FeatureType::OfflineMessage => audit_value(&cluster.mqtt_offline_message),
+                FeatureType::SystemAlarm => audit_value(&cluster.mqtt_system_monitor),
+                FeatureType::FlappingDetect => audit_value(&cluster.mqtt_flapping_detect),
+                FeatureType::SharedSubscription => audit_value(&cluster.mqtt_shared_subscription),
+            }
+        }
+        Err(_) => None,
+    };
+    let result = feature_type.as_ref().map(|_| ()).map_err(|e| e.clone());
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::SetClusterConfig,
+        &params.config_type,
+        before,
+        Some(params.config.clone()),
+        &result,
+    )
+    .await;
+
+    match feature_type {
         Ok(FeatureType::SlowSubscribe) => {
             // let mut config = cache_manager.get_slow_sub_config();
             // config.enable = request.is_enable;
@@ -58,6 +102,31 @@ pub async fn cluster_config_set(
 
         Ok(FeatureType::FlappingDetect) => {}
 
+        Ok(FeatureType::SharedSubscription) => {
+            let config = match serde_json::from_str::<MqttSharedSubscription>(&params.config) {
+                Ok(config) => config,
+                Err(e) => {
+                    return error_response(format!(
+                        "Failed to parse shared subscription config: {e}"
+                    ));
+                }
+            };
+            state
+                .mqtt_context
+                .cache_manager
+                .update_shared_subscription_config(config.clone())
+                .await;
+            if let Err(e) = save_cluster_dynamic_config(
+                &state.client_pool,
+                ClusterDynamicConfig::MqttSharedSubscription,
+                serde_json::to_vec(&config).unwrap(),
+            )
+            .await
+            {
+                return error_response(e.to_string());
+            }
+        }
+
         Err(e) => {
             return error_response(format!("Failed to parse feature type: {e}"));
         }
@@ -72,3 +141,168 @@ pub async fn cluster_config_get(
     let broker_config = state.broker_cache.get_cluster_config().await;
     success_response(broker_config)
 }
+
+pub async fn cluster_drain_start(
+    State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
+    Json(_params): Json<DrainStartReq>,
+) -> String {
+    let result: Result<(), String> = Ok(());
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::DrainBroker,
+        &broker_config().broker_id.to_string(),
+        None,
+        None,
+        &result,
+    )
+    .await;
+
+    start_drain(build_drain_context(&state));
+    success_response("success")
+}
+
+pub async fn cluster_drain_status(
+    State(state): State<Arc<HttpState>>,
+    Json(_params): Json<DrainStatusReq>,
+) -> String {
+    let status = drain_status(&build_drain_context(&state));
+    success_response(DrainStatusResp {
+        broker_id: broker_config().broker_id,
+        draining: status.draining,
+        connection_num: status.connection_num,
+        connector_num: status.connector_num,
+    })
+}
+
+pub async fn cluster_load(
+    State(state): State<Arc<HttpState>>,
+    Json(_params): Json<ClusterLoadReq>,
+) -> String {
+    let broker_id = broker_config().broker_id;
+    let broker_cache = &state.broker_cache;
+    let mut nodes: Vec<NodeLoadRow> = broker_cache
+        .node_loads
+        .iter()
+        .map(|load| NodeLoadRow {
+            node_id: *load.key(),
+            connection_num: load.connection_num,
+            draining: load.draining,
+        })
+        .collect();
+    // the local count is fresher than the last heartbeat
+    match nodes.iter_mut().find(|row| row.node_id == broker_id) {
+        Some(row) => row.connection_num = broker_cache.get_connection_num(),
+        None => nodes.push(NodeLoadRow {
+            node_id: broker_id,
+            connection_num: broker_cache.get_connection_num(),
+            draining: broker_cache.is_draining(),
+        }),
+    }
+    nodes.sort_by_key(|row| row.node_id);
+
+    success_response(ClusterLoadResp {
+        broker_id,
+        rebalancing: is_rebalancing(),
+        nodes,
+    })
+}
+
+pub async fn cluster_rebalance(
+    State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
+    Json(params): Json<ClusterRebalanceReq>,
+) -> String {
+    let context = RebalanceContext {
+        cache_manager: state.mqtt_context.cache_manager.clone(),
+        client_pool: state.client_pool.clone(),
+        connection_manager: state.connection_manager.clone(),
+    };
+    let result = start_rebalance(context, params.percent);
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::RebalanceConnections,
+        &broker_config().broker_id.to_string(),
+        None,
+        audit_value(&params),
+        &result,
+    )
+    .await;
+
+    match result {
+        Ok(moving_num) => success_response(ClusterRebalanceResp { moving_num }),
+        Err(e) => error_response(e.to_string()),
+    }
+}
+
+fn build_drain_context(state: &Arc<HttpState>) -> DrainContext {
+    DrainContext {
+        cache_manager: state.mqtt_context.cache_manager.clone(),
+        client_pool: state.client_pool.clone(),
+        connection_manager: state.connection_manager.clone(),
+        subscribe_manager: state.mqtt_context.subscribe_manager.clone(),
+        connector_manager: state.mqtt_context.connector_manager.clone(),
+    }
+}
+
+// The log level is changed on the broker serving the request only
+pub async fn log_level_list(Json(_params): Json<LogLevelListReq>) -> String {
+    success_response(LogLevelListResp {
+        appenders: appender_filters(),
+        overrides: log_level_overrides(),
+    })
+}
+
+pub async fn log_level_set(
+    State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
+    Json(params): Json<LogLevelSetReq>,
+) -> String {
+    let result = set_log_level(
+        &params.target,
+        &params.level,
+        params.appender.as_deref(),
+        params.ttl_sec.map(Duration::from_secs),
+    );
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::SetLogLevel,
+        &params.target,
+        None,
+        audit_value(&params),
+        &result,
+    )
+    .await;
+
+    match result {
+        Ok(info) => success_response(info),
+        Err(e) => error_response(e.to_string()),
+    }
+}
+
+pub async fn log_level_reset(
+    State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
+    Json(params): Json<LogLevelResetReq>,
+) -> String {
+    let result = reset_log_level(&params.target, params.appender.as_deref());
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::ResetLogLevel,
+        &params.target,
+        None,
+        None,
+        &result,
+    )
+    .await;
+
+    match result {
+        Ok(true) => success_response("success"),
+        Ok(false) => error_response(format!("Log level of {} was not changed", params.target)),
+        Err(e) => error_response(e.to_string()),
+    }
+}
@@ -11,3 +11,364 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+
+use crate::{
+    request::meta::{
+        MetaAddLearnerReq, MetaBackupReq, MetaPromoteReq, MetaRemoveNodeReq, MetaRestoreReq,
+        MetaStatusReq, MetaTransferLeaderReq,
+    },
+    response::meta::{MetaMemberRow, MetaRestoreResp, MetaStatusResp},
+    state::HttpState,
+    tool::audit::{audit_value, record_admin_audit, AuditContext},
+};
+use axum::{extract::State, Json};
+use common_base::{
+    error::common::CommonError,
+    http_response::{error_response, success_response},
+};
+use common_config::broker::broker_config;
+use grpc_clients::{
+    meta::inner::call::{export_metadata, import_metadata},
+    meta::openraft::call::{
+        placement_openraft_add_learner, placement_openraft_change_membership,
+        placement_openraft_raft_status, placement_openraft_transfer_leader,
+    },
+    pool::ClientPool,
+};
+use mqtt_broker::handler::audit_log::AuditAction;
+use protocol::meta::meta_service_inner::{ExportMetadataRequest, ImportMetadataRequest};
+use protocol::meta::meta_service_openraft::{
+    AddLearnerRequest, ChangeMembershipAction, ChangeMembershipRequest, Node, RaftStatusReply,
+    RaftStatusRequest, TransferLeaderRequest,
+};
+use std::sync::Arc;
+
+pub async fn meta_status(
+    State(state): State<Arc<HttpState>>,
+    Json(_params): Json<MetaStatusReq>,
+) -> String {
+    match meta_leader_status(&state.client_pool).await {
+        Ok(status) => success_response(MetaStatusResp::from(status)),
+        Err(e) => error_response(e.to_string()),
+    }
+}
+
+pub async fn meta_add_learner(
+    State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
+    Json(params): Json<MetaAddLearnerReq>,
+) -> String {
+    let result = add_learner(&state.client_pool, &params).await;
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::AddMetaNode,
+        &params.node_id.to_string(),
+        None,
+        audit_value(&params),
+        &result,
+    )
+    .await;
+
+    match result {
+        Ok(_) => success_response("success"),
+        Err(e) => error_response(e.to_string()),
+    }
+}
+
+pub async fn meta_promote(
+    State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
+    Json(params): Json<MetaPromoteReq>,
+) -> String {
+    let (before, result) = match meta_leader_status(&state.client_pool).await {
+        Ok(status) => {
+            let member = find_member(&status, params.node_id);
+            let before = member.as_ref().and_then(audit_value);
+            let result = match member {
+                Some(member) if member.role == "learner" => {
+                    change_membership(
+                        &state.client_pool,
+                        &status.leader_addr,
+                        params.node_id,
+                        ChangeMembershipAction::AddVoters,
+                        true,
+                    )
+                    .await
+                }
+                Some(_) => Err(CommonError::CommonError(format!(
+                    "Meta node {} is already a voter",
+                    params.node_id
+                ))),
+                None => Err(meta_node_not_found(params.node_id)),
+            };
+            (before, result)
+        }
+        Err(e) => (None, Err(e)),
+    };
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::PromoteMetaNode,
+        &params.node_id.to_string(),
+        before,
+        None,
+        &result,
+    )
+    .await;
+
+    match result {
+        Ok(_) => success_response("success"),
+        Err(e) => error_response(e.to_string()),
+    }
+}
+
+pub async fn meta_remove_node(
+    State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
+    Json(params): Json<MetaRemoveNodeReq>,
+) -> String {
+    let (before, result) = match meta_leader_status(&state.client_pool).await {
+        Ok(status) => {
+            let member = find_member(&status, params.node_id);
+            let before = member.as_ref().and_then(audit_value);
+            let result = match member {
+                Some(member) => {
+                    let action = if member.role == "voter" {
+                        ChangeMembershipAction::RemoveVoters
+                    } else {
+                        ChangeMembershipAction::RemoveNodes
+                    };
+                    change_membership(
+                        &state.client_pool,
+                        &status.leader_addr,
+                        params.node_id,
+                        action,
+                        false,
+                    )
+                    .await
+                }
+                None => Err(meta_node_not_found(params.node_id)),
+            };
+            (before, result)
+        }
+        Err(e) => (None, Err(e)),
+    };
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::RemoveMetaNode,
+        &params.node_id.to_string(),
+        before,
+        None,
+        &result,
+    )
+    .await;
+
+    match result {
+        Ok(_) => success_response("success"),
+        Err(e) => error_response(e.to_string()),
+    }
+}
+
+pub async fn meta_transfer_leader(
+    State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
+    Json(params): Json<MetaTransferLeaderReq>,
+) -> String {
+    let (before, result) = match meta_leader_status(&state.client_pool).await {
+        Ok(status) => {
+            let before = Some(status.current_leader.to_string());
+            let result = if status.current_leader == params.node_id {
+                Ok(())
+            } else {
+                match find_member(&status, params.node_id) {
+                    Some(member) if member.role == "voter" => placement_openraft_transfer_leader(
+                        &state.client_pool,
+                        &[status.leader_addr.clone()],
+                        TransferLeaderRequest {
+                            node_id: params.node_id,
+                        },
+                    )
+                    .await
+                    .map(|_| ()),
+                    Some(_) => Err(CommonError::CommonError(format!(
+                        "Meta node {} is a learner and can not become leader",
+                        params.node_id
+                    ))),
+                    None => Err(meta_node_not_found(params.node_id)),
+                }
+            };
+            (before, result)
+        }
+        Err(e) => (None, Err(e)),
+    };
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::TransferMetaLeader,
+        &params.node_id.to_string(),
+        before,
+        Some(params.node_id.to_string()),
+        &result,
+    )
+    .await;
+
+    match result {
+        Ok(_) => success_response("success"),
+        Err(e) => error_response(e.to_string()),
+    }
+}
+
+pub async fn meta_backup(
+    State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
+    Json(params): Json<MetaBackupReq>,
+) -> String {
+    let conf = broker_config();
+    let resources = params.resources.clone().unwrap_or_default();
+    let request = ExportMetadataRequest {
+        cluster_name: conf.cluster_name.clone(),
+        resources: resources.clone(),
+    };
+    let result = export_metadata(&state.client_pool, &conf.get_meta_service_addr(), request)
+        .await
+        .and_then(|reply| {
+            String::from_utf8(reply.archive).map_err(|e| CommonError::CommonError(e.to_string()))
+        });
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::ExportMetadata,
+        &conf.cluster_name,
+        None,
+        audit_value(&resources),
+        &result,
+    )
+    .await;
+
+    match result {
+        Ok(archive) => success_response(archive),
+        Err(e) => error_response(e.to_string()),
+    }
+}
+
+pub async fn meta_restore(
+    State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
+    Json(params): Json<MetaRestoreReq>,
+) -> String {
+    let conf = broker_config();
+    let resources = params.resources.clone().unwrap_or_default();
+    let cluster_name = params.cluster_name.clone().unwrap_or_default();
+    let request = ImportMetadataRequest {
+        archive: params.archive.into_bytes(),
+        cluster_name: cluster_name.clone(),
+        resources: resources.clone(),
+    };
+    let result = import_metadata(&state.client_pool, &conf.get_meta_service_addr(), request).await;
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::ImportMetadata,
+        &cluster_name,
+        None,
+        audit_value(&resources),
+        &result,
+    )
+    .await;
+
+    match result {
+        Ok(reply) => success_response(MetaRestoreResp {
+            record_count: reply.record_count,
+        }),
+        Err(e) => error_response(e.to_string()),
+    }
+}
+
+// Lag is only known by the leader, so the status is always read from it.
+async fn meta_leader_status(client_pool: &Arc<ClientPool>) -> Result<RaftStatusReply, CommonError> {
+    let addrs = broker_config().get_meta_service_addr();
+    let status = placement_openraft_raft_status(client_pool, &addrs, RaftStatusRequest {}).await?;
+    if status.leader_addr.is_empty() {
+        return Err(CommonError::CommonError(
+            "Meta service has no leader at the moment".to_string(),
+        ));
+    }
+
+    if status.current_leader == status.node_id {
+        return Ok(status);
+    }
+
+    placement_openraft_raft_status(
+        client_pool,
+        &[status.leader_addr.clone()],
+        RaftStatusRequest {},
+    )
+    .await
+}
+
+async fn add_learner(
+    client_pool: &Arc<ClientPool>,
+    params: &MetaAddLearnerReq,
+) -> Result<(), CommonError> {
+    let status = meta_leader_status(client_pool).await?;
+    if find_member(&status, params.node_id).is_some() {
+        return Err(CommonError::CommonError(format!(
+            "Meta node {} is already a member of the cluster",
+            params.node_id
+        )));
+    }
+
+    // Blocking until the learner has caught up, so promoting right after is safe.
+    let request = AddLearnerRequest {
+        node_id: params.node_id,
+        node: Some(Node {
+            rpc_addr: params.rpc_addr.clone(),
+            node_id: params.node_id,
+        }),
+        blocking: true,
+    };
+    placement_openraft_add_learner(client_pool, &[status.leader_addr.clone()], request).await?;
+
+    if params.promote {
+        change_membership(
+            client_pool,
+            &status.leader_addr,
+            params.node_id,
+            ChangeMembershipAction::AddVoters,
+            true,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+async fn change_membership(
+    client_pool: &Arc<ClientPool>,
+    leader_addr: &str,
+    node_id: u64,
+    action: ChangeMembershipAction,
+    retain: bool,
+) -> Result<(), CommonError> {
+    let request = ChangeMembershipRequest {
+        members: vec![node_id],
+        retain,
+        action: action.into(),
+    };
+    placement_openraft_change_membership(client_pool, &[leader_addr.to_string()], request).await?;
+    Ok(())
+}
+
+fn find_member(status: &RaftStatusReply, node_id: u64) -> Option<MetaMemberRow> {
+    MetaStatusResp::from(status.clone())
+        .members
+        .into_iter()
+        .find(|member| member.node_id == node_id)
+}
+
+fn meta_node_not_found(node_id: u64) -> CommonError {
+    CommonError::CommonError(format!(
+        "Meta node {node_id} is not a member of the cluster"
+    ))
+}
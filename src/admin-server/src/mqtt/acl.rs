@@ -16,7 +16,10 @@
     request::mqtt::{AclListReq, CreateAclReq, DeleteAclReq},
     response::{mqtt::AclListRow, PageReplyData},
     state::HttpState,
-    tool::query::{apply_filters, apply_pagination, apply_sorting, build_query_params, Queryable},
+    tool::{
+        audit::{audit_value, record_admin_audit, AuditContext},
+        query::{apply_filters, apply_pagination, apply_sorting, build_query_params, Queryable},
+    },
 };
 use axum::{extract::State, Json};
 use common_base::{
@@ -28,7 +31,7 @@
     http_response::{error_response, success_response},
 };
 use metadata_struct::acl::mqtt_acl::MqttAcl;
-use mqtt_broker::security::AuthDriver;
+use mqtt_broker::{handler::audit_log::AuditAction, security::AuthDriver};
 use std::{str::FromStr, sync::Arc};
 
 pub async fn acl_list(
@@ -44,7 +47,7 @@ pub async fn acl_list(
         params.filter_values,
         params.exact_match,
     );
-    let auth_driver = AuthDriver::new(
+    let auth_driver = AuthDriver::new_with_stale_read(
         state.mqtt_context.cache_manager.clone(),
         state.client_pool.clone(),
     );
@@ -91,9 +94,22 @@ fn get_field_str(&self, field: &str) -> Option<String> {
 
 pub async fn acl_create(
     State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
     Json(params): Json<CreateAclReq>,
 ) -> String {
-    match acl_create_inner(&state, &params).await {
+    let result = acl_create_inner(&state, &params).await;
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::CreateAcl,
+        &format!("{}:{}", params.resource_type, params.resource_name),
+        None,
+        audit_value(&params),
+        &result,
+    )
+    .await;
+
+    match result {
         Ok(_) => success_response("success"),
         Err(e) => error_response(e.to_string()),
     }
@@ -141,9 +157,22 @@ async fn acl_create_inner(state: &Arc<HttpState>, params: &CreateAclReq) -> Resu
 
 pub async fn acl_delete(
     State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
     Json(params): Json<DeleteAclReq>,
 ) -> String {
-    match acl_delete_inner(&state, &params).await {
+    let result = acl_delete_inner(&state, &params).await;
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::DeleteAcl,
+        &format!("{}:{}", params.resource_type, params.resource_name),
+        audit_value(&params),
+        None,
+        &result,
+    )
+    .await;
+
+    match result {
         Ok(_) => success_response("success"),
         Err(e) => error_response(e.to_string()),
     }
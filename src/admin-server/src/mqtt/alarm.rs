@@ -0,0 +1,258 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    request::mqtt::{
+        AlarmHistoryListReq, AlarmRuleListReq, CreateAlarmRuleReq, DeleteAlarmRuleReq,
+    },
+    response::{
+        mqtt::{AlarmHistoryRow, AlarmRuleRow},
+        PageReplyData,
+    },
+    state::HttpState,
+    tool::{
+        audit::{audit_value, record_admin_audit, AuditContext},
+        query::{apply_filters, apply_pagination, apply_sorting, build_query_params, Queryable},
+    },
+};
+use axum::{extract::State, Json};
+use common_base::{
+    http_response::{error_response, success_response},
+    tools::now_second,
+    utils::time_util::timestamp_to_local_datetime,
+};
+use common_config::broker::broker_config;
+use metadata_struct::mqtt::alarm::MqttAlarmRule;
+use mqtt_broker::{
+    alarm::{builtin_alarm_rules, validate_alarm_rule},
+    handler::{audit_log::AuditAction, error::MqttBrokerError},
+    storage::alarm::AlarmStorage,
+};
+use std::sync::Arc;
+
+pub async fn alarm_rule_list(
+    State(state): State<Arc<HttpState>>,
+    Json(params): Json<AlarmRuleListReq>,
+) -> String {
+    let options = build_query_params(
+        params.page,
+        params.limit,
+        params.sort_field,
+        params.sort_by,
+        params.filter_field,
+        params.filter_values,
+        params.exact_match,
+    );
+
+    let alarm_storage = AlarmStorage::new(state.client_pool.clone());
+    let rules = match alarm_storage.list_rule().await {
+        Ok(data) => data,
+        Err(e) => {
+            return error_response(e.to_string());
+        }
+    };
+
+    let results = builtin_alarm_rules()
+        .into_iter()
+        .map(|rule| build_alarm_rule_row(rule, true))
+        .chain(
+            rules
+                .into_iter()
+                .map(|rule| build_alarm_rule_row(rule, false)),
+        )
+        .collect();
+
+    let filtered = apply_filters(results, &options);
+    let sorted = apply_sorting(filtered, &options);
+    let pagination = apply_pagination(sorted, &options);
+
+    success_response(PageReplyData {
+        data: pagination.0,
+        total_count: pagination.1,
+    })
+}
+
+fn build_alarm_rule_row(rule: MqttAlarmRule, builtin: bool) -> AlarmRuleRow {
+    AlarmRuleRow {
+        name: rule.name,
+        metric: rule.metric.to_string(),
+        activate_threshold: rule.activate_threshold,
+        deactivate_threshold: rule.deactivate_threshold,
+        sinks: rule.sinks,
+        enable: rule.enable,
+        create_time: if builtin {
+            String::new()
+        } else {
+            timestamp_to_local_datetime(rule.create_time as i64)
+        },
+        builtin,
+    }
+}
+
+impl Queryable for AlarmRuleRow {
+    fn get_field_str(&self, field: &str) -> Option<String> {
+        match field {
+            "name" => Some(self.name.clone()),
+            "metric" => Some(self.metric.clone()),
+            _ => None,
+        }
+    }
+}
+
+pub async fn alarm_rule_create(
+    State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
+    Json(params): Json<CreateAlarmRuleReq>,
+) -> String {
+    let result = create_alarm_rule(&state, &params).await;
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::CreateAlarmRule,
+        &params.name,
+        None,
+        audit_value(&params),
+        &result,
+    )
+    .await;
+
+    match result {
+        Ok(_) => success_response("success"),
+        Err(e) => error_response(e.to_string()),
+    }
+}
+
+async fn create_alarm_rule(
+    state: &Arc<HttpState>,
+    params: &CreateAlarmRuleReq,
+) -> Result<(), MqttBrokerError> {
+    let rule = MqttAlarmRule {
+        cluster_name: broker_config().cluster_name.clone(),
+        name: params.name.clone(),
+        metric: params.metric,
+        activate_threshold: params.activate_threshold,
+        deactivate_threshold: params.deactivate_threshold,
+        sinks: params.sinks.clone(),
+        enable: params.enable,
+        create_time: now_second(),
+    };
+    validate_alarm_rule(&rule)?;
+
+    let alarm_storage = AlarmStorage::new(state.client_pool.clone());
+    alarm_storage.save_rule(&rule).await?;
+    Ok(())
+}
+
+pub async fn alarm_rule_delete(
+    State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
+    Json(params): Json<DeleteAlarmRuleReq>,
+) -> String {
+    let result = delete_alarm_rule(&state, &params).await;
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::DeleteAlarmRule,
+        &params.name,
+        audit_value(&params),
+        None,
+        &result,
+    )
+    .await;
+
+    match result {
+        Ok(_) => success_response("success"),
+        Err(e) => error_response(e.to_string()),
+    }
+}
+
+async fn delete_alarm_rule(
+    state: &Arc<HttpState>,
+    params: &DeleteAlarmRuleReq,
+) -> Result<(), MqttBrokerError> {
+    let alarm_storage = AlarmStorage::new(state.client_pool.clone());
+    if !alarm_storage
+        .list_rule()
+        .await?
+        .iter()
+        .any(|rule| rule.name == params.name)
+    {
+        return Err(MqttBrokerError::CommonError(format!(
+            "Alarm rule {} does not exist",
+            params.name
+        )));
+    }
+
+    alarm_storage.delete_rule(&params.name).await?;
+    Ok(())
+}
+
+pub async fn alarm_history_list(
+    State(state): State<Arc<HttpState>>,
+    Json(params): Json<AlarmHistoryListReq>,
+) -> String {
+    let options = build_query_params(
+        params.page,
+        params.limit,
+        params.sort_field,
+        params.sort_by,
+        params.filter_field,
+        params.filter_values,
+        params.exact_match,
+    );
+
+    let alarm_storage = AlarmStorage::new(state.client_pool.clone());
+    let data_list = match alarm_storage.list_history().await {
+        Ok(data) => data,
+        Err(e) => {
+            return error_response(e.to_string());
+        }
+    };
+
+    let results = data_list
+        .into_iter()
+        .rev()
+        .map(|history| AlarmHistoryRow {
+            rule_name: history.rule_name,
+            broker_id: history.broker_id,
+            metric: history.metric.to_string(),
+            value: history.value,
+            threshold: history.threshold,
+            activated: history.activated,
+            message: history.message,
+            create_time: timestamp_to_local_datetime(history.create_time as i64),
+        })
+        .collect();
+
+    let filtered = apply_filters(results, &options);
+    let sorted = apply_sorting(filtered, &options);
+    let pagination = apply_pagination(sorted, &options);
+
+    success_response(PageReplyData {
+        data: pagination.0,
+        total_count: pagination.1,
+    })
+}
+
+impl Queryable for AlarmHistoryRow {
+    fn get_field_str(&self, field: &str) -> Option<String> {
+        match field {
+            "rule_name" => Some(self.rule_name.clone()),
+            "metric" => Some(self.metric.clone()),
+            "broker_id" => Some(self.broker_id.to_string()),
+            "activated" => Some(self.activated.to_string()),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,127 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    request::mqtt::{AuditLogExportReq, AuditLogListReq},
+    response::{mqtt::AuditLogListRow, PageReplyData},
+    state::HttpState,
+    tool::query::{apply_filters, apply_pagination, apply_sorting, build_query_params, Queryable},
+};
+use axum::{extract::State, Json};
+use common_base::{
+    http_response::{error_response, success_response},
+    utils::time_util::timestamp_to_local_datetime,
+};
+use mqtt_broker::storage::audit_log::AuditLogStorage;
+use std::sync::Arc;
+
+const AUDIT_LOG_MAX_READ_NUM: u64 = 100000;
+
+pub async fn audit_log_list(
+    State(state): State<Arc<HttpState>>,
+    Json(params): Json<AuditLogListReq>,
+) -> String {
+    let options = build_query_params(
+        params.page,
+        params.limit,
+        params.sort_field,
+        params.sort_by,
+        params.filter_field,
+        params.filter_values,
+        params.exact_match,
+    );
+
+    let storage = AuditLogStorage::new(state.mqtt_context.message_storage_adapter.clone());
+    let data_list = match storage.read_latest(AUDIT_LOG_MAX_READ_NUM).await {
+        Ok(data) => data,
+        Err(e) => {
+            return error_response(e.to_string());
+        }
+    };
+
+    let results = data_list
+        .into_iter()
+        .map(|(offset, entry)| AuditLogListRow {
+            offset,
+            actor: entry.actor,
+            source_ip: entry.source_ip,
+            action: entry.action.to_string(),
+            target: entry.target,
+            before: entry.before,
+            after: entry.after,
+            success: entry.success,
+            reason: entry.reason,
+            create_time: timestamp_to_local_datetime(entry.create_time as i64),
+        })
+        .collect();
+
+    let filtered = apply_filters(results, &options);
+    let sorted = apply_sorting(filtered, &options);
+    let pagination = apply_pagination(sorted, &options);
+
+    success_response(PageReplyData {
+        data: pagination.0,
+        total_count: pagination.1,
+    })
+}
+
+impl Queryable for AuditLogListRow {
+    fn get_field_str(&self, field: &str) -> Option<String> {
+        match field {
+            "actor" => Some(self.actor.clone()),
+            "source_ip" => Some(self.source_ip.clone()),
+            "action" => Some(self.action.clone()),
+            "target" => Some(self.target.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Export audit entries as JSON lines, one entry per line.
+pub async fn audit_log_export(
+    State(state): State<Arc<HttpState>>,
+    Json(params): Json<AuditLogExportReq>,
+) -> String {
+    let start_time = params.start_time.unwrap_or(0);
+    let end_time = params.end_time.unwrap_or(u64::MAX);
+
+    // Without a start time the latest entries are exported
+    let storage = AuditLogStorage::new(state.mqtt_context.message_storage_adapter.clone());
+    let data = if params.start_time.is_some() {
+        storage
+            .read_by_time(start_time, end_time, AUDIT_LOG_MAX_READ_NUM)
+            .await
+    } else {
+        storage.read_latest(AUDIT_LOG_MAX_READ_NUM).await
+    };
+    let data_list = match data {
+        Ok(data) => data,
+        Err(e) => {
+            return error_response(e.to_string());
+        }
+    };
+    let mut lines = Vec::new();
+    for (_, entry) in data_list {
+        if entry.create_time < start_time || entry.create_time > end_time {
+            continue;
+        }
+        match serde_json::to_string(&entry) {
+            Ok(line) => lines.push(line),
+            Err(e) => {
+                return error_response(e.to_string());
+            }
+        }
+    }
+    lines.join("\n")
+}
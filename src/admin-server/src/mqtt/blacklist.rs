@@ -16,7 +16,10 @@
     request::mqtt::{BlackListListReq, CreateBlackListReq, DeleteBlackListReq},
     response::{mqtt::BlackListListRow, PageReplyData},
     state::HttpState,
-    tool::query::{apply_filters, apply_pagination, apply_sorting, build_query_params, Queryable},
+    tool::{
+        audit::{audit_value, record_admin_audit, AuditContext},
+        query::{apply_filters, apply_pagination, apply_sorting, build_query_params, Queryable},
+    },
 };
 use axum::{extract::State, Json};
 use common_base::{
@@ -25,7 +28,7 @@
     utils::time_util::timestamp_to_local_datetime,
 };
 use metadata_struct::acl::mqtt_blacklist::MqttAclBlackList;
-use mqtt_broker::security::AuthDriver;
+use mqtt_broker::{handler::audit_log::AuditAction, security::AuthDriver};
 use std::sync::Arc;
 
 pub async fn blacklist_list(
@@ -42,7 +45,7 @@ pub async fn blacklist_list(
         params.exact_match,
     );
 
-    let auth_driver = AuthDriver::new(
+    let auth_driver = AuthDriver::new_with_stale_read(
         state.mqtt_context.cache_manager.clone(),
         state.client_pool.clone(),
     );
@@ -85,6 +88,7 @@ fn get_field_str(&self, field: &str) -> Option<String> {
 
 pub async fn blacklist_create(
     State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
     Json(params): Json<CreateBlackListReq>,
 ) -> String {
     let blacklist_type = match get_blacklist_type_by_str(&params.blacklist_type) {
@@ -105,7 +109,19 @@ pub async fn blacklist_create(
         state.client_pool.clone(),
     );
 
-    match auth_driver.save_blacklist(mqtt_blacklist).await {
+    let result = auth_driver.save_blacklist(mqtt_blacklist).await;
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::CreateBlacklist,
+        &format!("{}:{}", params.blacklist_type, params.resource_name),
+        None,
+        audit_value(&params),
+        &result,
+    )
+    .await;
+
+    match result {
         Ok(_) => success_response("success"),
         Err(e) => error_response(e.to_string()),
     }
@@ -113,6 +129,7 @@ pub async fn blacklist_create(
 
 pub async fn blacklist_delete(
     State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
     Json(params): Json<DeleteBlackListReq>,
 ) -> String {
     let blacklist_type = match get_blacklist_type_by_str(&params.blacklist_type) {
@@ -132,7 +149,19 @@ pub async fn blacklist_delete(
         state.mqtt_context.cache_manager.clone(),
         state.client_pool.clone(),
     );
-    match auth_driver.delete_blacklist(mqtt_blacklist).await {
+    let result = auth_driver.delete_blacklist(mqtt_blacklist).await;
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::DeleteBlacklist,
+        &format!("{}:{}", params.blacklist_type, params.resource_name),
+        audit_value(&params),
+        None,
+        &result,
+    )
+    .await;
+
+    match result {
         Ok(_) => success_response("success"),
         Err(e) => error_response(e.to_string()),
     }
@@ -16,7 +16,10 @@
     request::mqtt::{ConnectorListReq, CreateConnectorReq, DeleteConnectorReq},
     response::{mqtt::ConnectorListRow, PageReplyData},
     state::HttpState,
-    tool::query::{apply_filters, apply_pagination, apply_sorting, build_query_params, Queryable},
+    tool::{
+        audit::{audit_value, record_admin_audit, redact_config, AuditContext},
+        query::{apply_filters, apply_pagination, apply_sorting, build_query_params, Queryable},
+    },
 };
 use axum::{extract::State, Json};
 use common_base::{
@@ -38,7 +41,10 @@
     connector_type::{connector_type_for_string, ConnectorType},
     status::MQTTStatus,
 };
-use mqtt_broker::storage::connector::ConnectorStorage;
+use mqtt_broker::{
+    handler::{audit_log::AuditAction, consumer_lag::get_consumer_lag},
+    storage::{connector::ConnectorStorage, message::MessageStorage},
+};
 use std::sync::Arc;
 
 pub async fn connector_list(
@@ -55,19 +61,34 @@ pub async fn connector_list(
         params.exact_match,
     );
 
+    let message_storage = MessageStorage::new(state.mqtt_context.message_storage_adapter.clone());
     let mut connectors = Vec::new();
     for connector in state.mqtt_context.connector_manager.get_all_connector() {
+        // connectors commit their offsets under the connector name
+        let lag = get_consumer_lag(
+            &message_storage,
+            &connector.connector_name,
+            &connector.topic_name,
+        )
+        .await
+        .ok()
+        .map(|lag| lag.lag);
         connectors.push(ConnectorListRow {
             connector_name: connector.connector_name.clone(),
             connector_type: connector.connector_type.to_string(),
             config: connector.config.clone(),
             topic_name: connector.topic_name.clone(),
+            payload_format: connector
+                .payload_format
+                .clone()
+                .unwrap_or_else(|| "-".to_string()),
             status: connector.status.to_string(),
             broker_id: if let Some(id) = connector.broker_id {
                 id.to_string()
             } else {
                 "-".to_string()
             },
+            lag,
             create_time: timestamp_to_local_datetime(connector.create_time as i64),
             update_time: timestamp_to_local_datetime(connector.update_time as i64),
         });
@@ -98,9 +119,27 @@ fn get_field_str(&self, field: &str) -> Option<String> {
 
 pub async fn connector_create(
     State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
     Json(params): Json<CreateConnectorReq>,
 ) -> String {
-    if let Err(e) = connector_create_inner(&state, params).await {
+    let connector_name = params.connector_name.clone();
+    // connector configs carry credentials of the target system
+    let mut audit_params = params.clone();
+    audit_params.config = redact_config(&params.config);
+    let after = audit_value(&audit_params);
+    let result = connector_create_inner(&state, params).await;
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::CreateConnector,
+        &connector_name,
+        None,
+        after,
+        &result,
+    )
+    .await;
+
+    if let Err(e) = result {
         return error_response(e.to_string());
     }
     success_response("success")
@@ -108,13 +147,34 @@ pub async fn connector_create(
 
 pub async fn connector_delete(
     State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
     Json(params): Json<DeleteConnectorReq>,
 ) -> String {
+    let before = state
+        .mqtt_context
+        .connector_manager
+        .get_connector(&params.connector_name)
+        .and_then(|mut connector| {
+            connector.config = redact_config(&connector.config);
+            audit_value(&connector)
+        });
+
     let storage = ConnectorStorage::new(state.client_pool.clone());
-    if let Err(e) = storage
+    let result = storage
         .delete_connector(&state.broker_cache.cluster_name, &params.connector_name)
-        .await
-    {
+        .await;
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::DeleteConnector,
+        &params.connector_name,
+        before,
+        None,
+        &result,
+    )
+    .await;
+
+    if let Err(e) = result {
         return error_response(e.to_string());
     }
 
@@ -137,6 +197,7 @@ async fn connector_create_inner(
         topic_name: params.topic_name.clone(),
         status: MQTTStatus::Idle,
         broker_id: None,
+        payload_format: params.payload_format.clone(),
         create_time: now_second(),
         update_time: now_second(),
     };
@@ -0,0 +1,102 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    request::mqtt::{DeliveryClientReq, DeliveryMessageReq},
+    response::{mqtt::DeliveryEventRow, PageReplyData},
+    state::HttpState,
+    tool::query::{apply_filters, apply_pagination, apply_sorting, build_query_params, Queryable},
+};
+use axum::{extract::State, Json};
+use common_base::http_response::{error_response, success_response};
+use mqtt_broker::{handler::delivery_log::DeliveryEvent, storage::local::LocalStorage};
+use std::sync::Arc;
+
+pub async fn delivery_message(
+    State(state): State<Arc<HttpState>>,
+    Json(params): Json<DeliveryMessageReq>,
+) -> String {
+    let local_storage = LocalStorage::new(state.rocksdb_engine_handler.clone());
+    match local_storage
+        .list_delivery_event_by_message(&params.topic_name, params.offset)
+        .await
+    {
+        Ok(events) => {
+            let results: Vec<DeliveryEventRow> = events.into_iter().map(build_row).collect();
+            success_response(results)
+        }
+        Err(e) => error_response(e.to_string()),
+    }
+}
+
+pub async fn delivery_client(
+    State(state): State<Arc<HttpState>>,
+    Json(params): Json<DeliveryClientReq>,
+) -> String {
+    let options = build_query_params(
+        params.page,
+        params.limit,
+        params.sort_field,
+        params.sort_by,
+        params.filter_field,
+        params.filter_values,
+        params.exact_match,
+    );
+
+    let local_storage = LocalStorage::new(state.rocksdb_engine_handler.clone());
+    let events = match local_storage
+        .list_delivery_event_by_client(&params.client_id)
+        .await
+    {
+        Ok(events) => events,
+        Err(e) => {
+            return error_response(e.to_string());
+        }
+    };
+
+    let results: Vec<DeliveryEventRow> = events.into_iter().map(build_row).collect();
+    let filtered = apply_filters(results, &options);
+    let sorted = apply_sorting(filtered, &options);
+    let pagination = apply_pagination(sorted, &options);
+
+    success_response(PageReplyData {
+        data: pagination.0,
+        total_count: pagination.1,
+    })
+}
+
+fn build_row(event: DeliveryEvent) -> DeliveryEventRow {
+    DeliveryEventRow {
+        topic_name: event.topic_name,
+        offset: event.offset,
+        client_id: event.client_id,
+        sub_path: event.sub_path,
+        event_type: event.event_type.to_string(),
+        pkid: event.pkid,
+        reason: event.reason,
+        create_time: event.create_time,
+    }
+}
+
+impl Queryable for DeliveryEventRow {
+    fn get_field_str(&self, field: &str) -> Option<String> {
+        match field {
+            "topic_name" => Some(self.topic_name.clone()),
+            "offset" => Some(self.offset.to_string()),
+            "event_type" => Some(self.event_type.clone()),
+            "create_time" => Some(self.create_time.to_string()),
+            _ => None,
+        }
+    }
+}
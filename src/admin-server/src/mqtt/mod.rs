@@ -14,13 +14,19 @@
 
 pub mod acl;
 pub mod advanced;
+pub mod alarm;
+pub mod audit;
 pub mod blacklist;
 pub mod client;
 pub mod connector;
+pub mod delivery;
 pub mod overview;
 pub mod schema;
 pub mod session;
 pub mod subscribe;
 pub mod system;
 pub mod topic;
+pub mod topic_metrics;
+pub mod trace;
 pub mod user;
+pub mod webhook;
@@ -15,21 +15,27 @@
 use axum::{extract::State, Json};
 use common_base::http_response::{error_response, success_response};
 use common_config::broker::broker_config;
-use metadata_struct::schema::{SchemaData, SchemaResourceBind, SchemaType};
-use mqtt_broker::{handler::error::MqttBrokerError, storage::schema::SchemaStorage};
-use std::sync::Arc;
+use metadata_struct::schema::{SchemaCompatibility, SchemaData, SchemaResourceBind, SchemaType};
+use mqtt_broker::{
+    handler::{audit_log::AuditAction, error::MqttBrokerError},
+    storage::schema::SchemaStorage,
+};
+use std::{str::FromStr, sync::Arc};
 
 use crate::{
     request::mqtt::{
         CreateSchemaBindReq, CreateSchemaReq, DeleteSchemaBindReq, DeleteSchemaReq,
-        SchemaBindListReq, SchemaListReq,
+        SchemaBindListReq, SchemaListReq, UpdateSchemaReq,
     },
     response::{
         mqtt::{SchemaBindListRow, SchemaListRow},
         PageReplyData,
     },
     state::HttpState,
-    tool::query::{apply_filters, apply_pagination, apply_sorting, build_query_params, Queryable},
+    tool::{
+        audit::{audit_value, record_admin_audit, AuditContext},
+        query::{apply_filters, apply_pagination, apply_sorting, build_query_params, Queryable},
+    },
 };
 
 pub async fn schema_list(
@@ -53,6 +59,9 @@ pub async fn schema_list(
             schema_type: schema.schema_type.to_string(),
             desc: schema.desc.clone(),
             schema: schema.schema.clone(),
+            message_name: schema.message_name.clone(),
+            version: schema.version,
+            compatibility: schema.compatibility.to_string(),
         });
     }
 
@@ -71,6 +80,7 @@ fn get_field_str(&self, field: &str) -> Option<String> {
         match field {
             "name" => Some(self.name.clone()),
             "schema_type" => Some(self.schema_type.clone()),
+            "compatibility" => Some(self.compatibility.clone()),
             _ => None,
         }
     }
@@ -78,9 +88,24 @@ fn get_field_str(&self, field: &str) -> Option<String> {
 
 pub async fn schema_create(
     State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
     Json(params): Json<CreateSchemaReq>,
 ) -> String {
-    if let Err(e) = schema_create_inner(state, params).await {
+    let schema_name = params.schema_name.clone();
+    let after = audit_value(&params);
+    let result = schema_create_inner(state.clone(), params).await;
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::CreateSchema,
+        &schema_name,
+        None,
+        after,
+        &result,
+    )
+    .await;
+
+    if let Err(e) = result {
         return error_response(e.to_string());
     }
     success_response("success")
@@ -90,34 +115,146 @@ pub async fn schema_create_inner(
     state: Arc<HttpState>,
     req: CreateSchemaReq,
 ) -> Result<(), MqttBrokerError> {
-    let schema_type = match req.schema_type.as_str() {
-        "json" => SchemaType::JSON,
-        "avro" => SchemaType::AVRO,
-        "protobuf" => SchemaType::PROTOBUF,
-        _ => return Err(MqttBrokerError::InvalidSchemaType(req.schema_type.clone())),
-    };
+    let mut schema_data = build_schema_data(
+        &state,
+        &req.schema_name,
+        &req.schema_type,
+        &req.schema,
+        &req.desc,
+        &req.message_name,
+        &req.compatibility,
+    )?;
+    schema_data.version = 1;
 
-    let schema_data = SchemaData {
-        cluster_name: state.broker_cache.cluster_name.clone(),
-        name: req.schema_name.clone(),
-        schema_type,
-        schema: req.schema.clone(),
-        desc: req.desc.clone(),
+    let schema_storage = SchemaStorage::new(state.client_pool.clone());
+    schema_storage.create(schema_data.clone()).await?;
+
+    state.mqtt_context.schema_manager.add_schema(schema_data);
+    Ok(())
+}
+
+pub async fn schema_update(
+    State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
+    Json(params): Json<UpdateSchemaReq>,
+) -> String {
+    let schema_name = params.schema_name.clone();
+    let before = state
+        .mqtt_context
+        .schema_manager
+        .get_schema(&schema_name)
+        .and_then(|schema| audit_value(&schema));
+    let after = audit_value(&params);
+    let result = schema_update_inner(state.clone(), params).await;
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::UpdateSchema,
+        &schema_name,
+        before,
+        after,
+        &result,
+    )
+    .await;
+
+    if let Err(e) = result {
+        return error_response(e.to_string());
+    }
+    success_response("success")
+}
+
+pub async fn schema_update_inner(
+    state: Arc<HttpState>,
+    req: UpdateSchemaReq,
+) -> Result<(), MqttBrokerError> {
+    let current = state
+        .mqtt_context
+        .schema_manager
+        .get_schema(&req.schema_name);
+
+    // keep the stored compatibility mode unless the request sets a new one
+    let compatibility = match (&current, req.compatibility.is_empty()) {
+        (Some(schema), true) => schema.compatibility.to_string(),
+        _ => req.compatibility.clone(),
     };
 
+    let mut schema_data = build_schema_data(
+        &state,
+        &req.schema_name,
+        &req.schema_type,
+        &req.schema,
+        &req.desc,
+        &req.message_name,
+        &compatibility,
+    )?;
+
+    // the meta service checks compatibility against the stored version and bumps it
     let schema_storage = SchemaStorage::new(state.client_pool.clone());
-    schema_storage.create(schema_data.clone()).await?;
+    schema_storage.update(schema_data.clone()).await?;
 
+    if let Some(old) = current {
+        schema_data.version = old.version + 1;
+    }
     state.mqtt_context.schema_manager.add_schema(schema_data);
     Ok(())
 }
 
+fn build_schema_data(
+    state: &Arc<HttpState>,
+    schema_name: &str,
+    schema_type: &str,
+    schema: &str,
+    desc: &str,
+    message_name: &str,
+    compatibility: &str,
+) -> Result<SchemaData, MqttBrokerError> {
+    let schema_type = match schema_type {
+        "json" => SchemaType::JSON,
+        "avro" => SchemaType::AVRO,
+        "protobuf" => SchemaType::PROTOBUF,
+        _ => return Err(MqttBrokerError::InvalidSchemaType(schema_type.to_string())),
+    };
+
+    let compatibility =
+        SchemaCompatibility::from_str(compatibility).map_err(MqttBrokerError::CommonError)?;
+
+    Ok(SchemaData {
+        cluster_name: state.broker_cache.cluster_name.clone(),
+        name: schema_name.to_string(),
+        schema_type,
+        schema: schema.to_string(),
+        desc: desc.to_string(),
+        message_name: message_name.to_string(),
+        version: 0,
+        compatibility,
+    })
+}
+
 pub async fn schema_delete(
     State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
     Json(params): Json<DeleteSchemaReq>,
 ) -> String {
+    let before = state
+        .mqtt_context
+        .schema_manager
+        .get_schema(&params.schema_name)
+        .and_then(|schema| audit_value(&schema));
+
     let schema_storage = SchemaStorage::new(state.client_pool.clone());
-    if let Err(e) = schema_storage.delete(params.schema_name.clone()).await {
+    let result = schema_storage.delete(params.schema_name.clone()).await;
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::DeleteSchema,
+        &params.schema_name,
+        before,
+        None,
+        &result,
+    )
+    .await;
+
+    if let Err(e) = result {
         return error_response(e.to_string());
     }
     state
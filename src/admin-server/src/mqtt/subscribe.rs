@@ -18,7 +18,10 @@
         SubscribeListReq,
     },
     response::{
-        mqtt::{AutoSubscribeListRow, SlowSubscribeListRow, SubscribeListRow},
+        mqtt::{
+            AutoSubscribeListRow, ShareGroupLagRow, SlowSubscribeListRow, SubscribeDetailReply,
+            SubscribeListRow,
+        },
         PageReplyData,
     },
     state::HttpState,
@@ -30,9 +33,14 @@
     utils::time_util::timestamp_to_local_datetime,
 };
 use metadata_struct::mqtt::{
-    auto_subscribe_rule::MqttAutoSubscribeRule, subscribe_data::is_mqtt_share_subscribe,
+    auto_subscribe_rule::MqttAutoSubscribeRule,
+    subscribe_data::{is_mqtt_share_subscribe, MqttSubscribe},
+};
+use mqtt_broker::{
+    handler::consumer_lag::get_consumer_lag,
+    storage::{auto_subscribe::AutoSubscribeStorage, local::LocalStorage, message::MessageStorage},
+    subscribe::share::leader::build_share_group_id,
 };
-use mqtt_broker::storage::{auto_subscribe::AutoSubscribeStorage, local::LocalStorage};
 use protocol::mqtt::common::{qos, retain_forward_rule};
 use std::sync::Arc;
 
@@ -52,20 +60,7 @@ pub async fn subscribe_list(
 
     let mut subscribes = Vec::new();
     for (_, sub) in state.mqtt_context.subscribe_manager.subscribe_list.clone() {
-        subscribes.push(SubscribeListRow {
-            broker_id: sub.broker_id,
-            client_id: sub.client_id,
-            create_time: timestamp_to_local_datetime(sub.create_time as i64),
-            no_local: if sub.filter.nolocal { 1 } else { 0 },
-            path: sub.path.clone(),
-            pk_id: sub.pkid as u32,
-            preserve_retain: if sub.filter.preserve_retain { 1 } else { 0 },
-            properties: serde_json::to_string(&sub.subscribe_properties).unwrap(),
-            protocol: format!("{:?}", sub.protocol),
-            qos: format!("{:?}", sub.filter.qos),
-            retain_handling: format!("{:?}", sub.filter.retain_handling),
-            is_share_sub: is_mqtt_share_subscribe(&sub.path),
-        });
+        subscribes.push(build_subscribe_row(sub));
     }
     let filtered = apply_filters(subscribes, &options);
     let sorted = apply_sorting(filtered, &options);
@@ -86,11 +81,60 @@ fn get_field_str(&self, field: &str) -> Option<String> {
     }
 }
 
+fn build_subscribe_row(sub: MqttSubscribe) -> SubscribeListRow {
+    SubscribeListRow {
+        broker_id: sub.broker_id,
+        client_id: sub.client_id,
+        create_time: timestamp_to_local_datetime(sub.create_time as i64),
+        no_local: if sub.filter.nolocal { 1 } else { 0 },
+        path: sub.path.clone(),
+        pk_id: sub.pkid as u32,
+        preserve_retain: if sub.filter.preserve_retain { 1 } else { 0 },
+        properties: serde_json::to_string(&sub.subscribe_properties).unwrap(),
+        protocol: format!("{:?}", sub.protocol),
+        qos: format!("{:?}", sub.filter.qos),
+        retain_handling: format!("{:?}", sub.filter.retain_handling),
+        is_share_sub: is_mqtt_share_subscribe(&sub.path),
+    }
+}
+
 pub async fn subscribe_detail(
-    State(_state): State<Arc<HttpState>>,
-    Json(_params): Json<SubscribeDetailReq>,
+    State(state): State<Arc<HttpState>>,
+    Json(params): Json<SubscribeDetailReq>,
 ) -> String {
-    success_response("")
+    let subscribe_manager = &state.mqtt_context.subscribe_manager;
+    let Some(sub) = subscribe_manager.get_subscribe(&params.client_id, &params.path) else {
+        return error_response(format!(
+            "Subscription {} of client {} does not exist",
+            params.path, params.client_id
+        ));
+    };
+
+    let message_storage = MessageStorage::new(state.mqtt_context.message_storage_adapter.clone());
+    let mut share_groups = Vec::new();
+    for (_, raw) in subscribe_manager.share_leader_push.clone() {
+        if raw.path != sub.path {
+            continue;
+        }
+        let group_id = build_share_group_id(&raw.group_name, &raw.sub_name, &raw.topic_name);
+        let lag = match get_consumer_lag(&message_storage, &group_id, &raw.topic_name).await {
+            Ok(lag) => lag,
+            Err(e) => return error_response(e.to_string()),
+        };
+        share_groups.push(ShareGroupLagRow {
+            group_name: raw.group_name.clone(),
+            sub_name: raw.sub_name.clone(),
+            topic_name: raw.topic_name.clone(),
+            end_offset: lag.end_offset,
+            committed_offset: lag.committed_offset,
+            lag: lag.lag,
+        });
+    }
+
+    success_response(SubscribeDetailReply {
+        subscribe: build_subscribe_row(sub),
+        share_groups,
+    })
 }
 
 pub async fn auto_subscribe_list(
@@ -56,6 +56,7 @@ pub async fn system_alarm_list(
         .map(|entry| SystemAlarmListRow {
             name: entry.name.clone(),
             message: entry.message.clone(),
+            activated: entry.activated,
             create_time: entry.create_time,
         })
         .collect();
@@ -0,0 +1,226 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    request::mqtt::{
+        CreateTopicMetricsReq, DeleteTopicMetricsReq, TopicMetricsDataReq, TopicMetricsListReq,
+    },
+    response::{
+        mqtt::{TopicMetricsRow, TopicMetricsRuleRow},
+        PageReplyData,
+    },
+    state::HttpState,
+    tool::{
+        audit::{audit_value, record_admin_audit, AuditContext},
+        query::{apply_filters, apply_pagination, apply_sorting, build_query_params, Queryable},
+    },
+};
+use axum::{extract::State, Json};
+use common_base::{
+    http_response::{error_response, success_response},
+    tools::now_second,
+    utils::time_util::timestamp_to_local_datetime,
+};
+use common_metrics::mqtt::topic::get_topic_metrics;
+use mqtt_broker::{
+    handler::{audit_log::AuditAction, error::MqttBrokerError, topic_metrics::TopicMetricsRule},
+    storage::local::LocalStorage,
+};
+use std::sync::Arc;
+
+pub async fn topic_metrics_list(
+    State(state): State<Arc<HttpState>>,
+    Json(params): Json<TopicMetricsListReq>,
+) -> String {
+    let options = build_query_params(
+        params.page,
+        params.limit,
+        params.sort_field,
+        params.sort_by,
+        params.filter_field,
+        params.filter_values,
+        params.exact_match,
+    );
+
+    let topic_metrics = &state.mqtt_context.cache_manager.topic_metrics;
+    let results = topic_metrics
+        .list_rules()
+        .into_iter()
+        .map(|rule| TopicMetricsRuleRow {
+            topic_num: topic_metrics.rule_topic_num(&rule.topic_filter),
+            topic_filter: rule.topic_filter,
+            create_time: timestamp_to_local_datetime(rule.create_time as i64),
+        })
+        .collect();
+
+    let filtered = apply_filters(results, &options);
+    let sorted = apply_sorting(filtered, &options);
+    let pagination = apply_pagination(sorted, &options);
+
+    success_response(PageReplyData {
+        data: pagination.0,
+        total_count: pagination.1,
+    })
+}
+
+impl Queryable for TopicMetricsRuleRow {
+    fn get_field_str(&self, field: &str) -> Option<String> {
+        match field {
+            "topic_filter" => Some(self.topic_filter.clone()),
+            _ => None,
+        }
+    }
+}
+
+pub async fn topic_metrics_create(
+    State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
+    Json(params): Json<CreateTopicMetricsReq>,
+) -> String {
+    let result = create_topic_metrics(&state, &params).await;
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::CreateTopicMetrics,
+        &params.topic_filter,
+        None,
+        audit_value(&params),
+        &result,
+    )
+    .await;
+
+    match result {
+        Ok(_) => success_response("success"),
+        Err(e) => error_response(e.to_string()),
+    }
+}
+
+async fn create_topic_metrics(
+    state: &Arc<HttpState>,
+    params: &CreateTopicMetricsReq,
+) -> Result<(), MqttBrokerError> {
+    let rule = TopicMetricsRule {
+        topic_filter: params.topic_filter.clone(),
+        create_time: now_second(),
+    };
+    state
+        .mqtt_context
+        .cache_manager
+        .topic_metrics
+        .add_rule(rule.clone())?;
+
+    let local_storage = LocalStorage::new(state.rocksdb_engine_handler.clone());
+    local_storage.save_topic_metrics_rule(&rule).await?;
+    Ok(())
+}
+
+pub async fn topic_metrics_delete(
+    State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
+    Json(params): Json<DeleteTopicMetricsReq>,
+) -> String {
+    let result = delete_topic_metrics(&state, &params).await;
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::DeleteTopicMetrics,
+        &params.topic_filter,
+        audit_value(&params),
+        None,
+        &result,
+    )
+    .await;
+
+    match result {
+        Ok(_) => success_response("success"),
+        Err(e) => error_response(e.to_string()),
+    }
+}
+
+async fn delete_topic_metrics(
+    state: &Arc<HttpState>,
+    params: &DeleteTopicMetricsReq,
+) -> Result<(), MqttBrokerError> {
+    let Some(rule) = state
+        .mqtt_context
+        .cache_manager
+        .topic_metrics
+        .remove_rule(&params.topic_filter)
+    else {
+        return Err(MqttBrokerError::CommonError(format!(
+            "Topic metrics rule {} does not exist",
+            params.topic_filter
+        )));
+    };
+
+    let local_storage = LocalStorage::new(state.rocksdb_engine_handler.clone());
+    local_storage.delete_topic_metrics_rule(&rule).await?;
+    Ok(())
+}
+
+pub async fn topic_metrics_data(
+    State(state): State<Arc<HttpState>>,
+    Json(params): Json<TopicMetricsDataReq>,
+) -> String {
+    let options = build_query_params(
+        params.page,
+        params.limit,
+        params.sort_field,
+        params.sort_by,
+        params.filter_field,
+        params.filter_values,
+        params.exact_match,
+    );
+
+    let results = state
+        .mqtt_context
+        .cache_manager
+        .topic_metrics
+        .list_topics()
+        .into_iter()
+        .map(|(topic_name, topic_filter)| {
+            let value = get_topic_metrics(&topic_name);
+            TopicMetricsRow {
+                topic_name,
+                topic_filter,
+                messages_in: value.messages_written,
+                messages_out: value.messages_sent,
+                bytes_in: value.bytes_written,
+                bytes_out: value.bytes_sent,
+                messages_dropped: value.messages_dropped,
+                messages_retained: value.messages_retained,
+                subscriber_num: value.subscriber_num,
+            }
+        })
+        .collect();
+
+    let filtered = apply_filters(results, &options);
+    let sorted = apply_sorting(filtered, &options);
+    let pagination = apply_pagination(sorted, &options);
+
+    success_response(PageReplyData {
+        data: pagination.0,
+        total_count: pagination.1,
+    })
+}
+
+impl Queryable for TopicMetricsRow {
+    fn get_field_str(&self, field: &str) -> Option<String> {
+        match field {
+            "topic_name" => Some(self.topic_name.clone()),
+            "topic_filter" => Some(self.topic_filter.clone()),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,263 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    request::mqtt::{
+        CreatePacketTraceReq, DeletePacketTraceReq, DownloadPacketTraceReq, PacketTraceListReq,
+        StopPacketTraceReq,
+    },
+    response::{mqtt::PacketTraceListRow, PageReplyData},
+    state::HttpState,
+    tool::{
+        audit::{audit_value, record_admin_audit, AuditContext},
+        query::{apply_filters, apply_pagination, apply_sorting, build_query_params, Queryable},
+    },
+};
+use axum::{extract::State, Json};
+use common_base::{
+    http_response::{error_response, success_response},
+    tools::now_second,
+    utils::time_util::timestamp_to_local_datetime,
+};
+use mqtt_broker::handler::{
+    audit_log::AuditAction,
+    error::MqttBrokerError,
+    packet_trace::{
+        clean_packet_trace_output, export_packet_trace, init_packet_trace_output, PacketTrace,
+        PacketTraceOutput, PacketTracePayloadEncode, PacketTraceType,
+    },
+};
+use std::{str::FromStr, sync::Arc};
+
+const DEFAULT_PAYLOAD_LIMIT: usize = 1024;
+
+pub async fn trace_list(
+    State(state): State<Arc<HttpState>>,
+    Json(params): Json<PacketTraceListReq>,
+) -> String {
+    let options = build_query_params(
+        params.page,
+        params.limit,
+        params.sort_field,
+        params.sort_by,
+        params.filter_field,
+        params.filter_values,
+        params.exact_match,
+    );
+
+    let results = state
+        .mqtt_context
+        .cache_manager
+        .packet_trace
+        .list_traces()
+        .into_iter()
+        .map(|trace| PacketTraceListRow {
+            status: if trace.is_running() {
+                "Running".to_string()
+            } else {
+                "Stopped".to_string()
+            },
+            name: trace.name,
+            trace_type: trace.trace_type.to_string(),
+            target: trace.target,
+            output: trace.output.to_string(),
+            payload_encode: trace.payload_encode.to_string(),
+            payload_limit: trace.payload_limit,
+            start_time: timestamp_to_local_datetime(trace.start_time as i64),
+            end_time: timestamp_to_local_datetime(trace.end_time as i64),
+        })
+        .collect();
+
+    let filtered = apply_filters(results, &options);
+    let sorted = apply_sorting(filtered, &options);
+    let pagination = apply_pagination(sorted, &options);
+
+    success_response(PageReplyData {
+        data: pagination.0,
+        total_count: pagination.1,
+    })
+}
+
+impl Queryable for PacketTraceListRow {
+    fn get_field_str(&self, field: &str) -> Option<String> {
+        match field {
+            "name" => Some(self.name.clone()),
+            "trace_type" => Some(self.trace_type.clone()),
+            "target" => Some(self.target.clone()),
+            "status" => Some(self.status.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Packet traces are node-local: the trace is registered in this broker's cache and only
+/// captures the clients connected to it. The list, stop, delete and download handlers act
+/// on the same local registry, so a cluster-wide trace has to be created on every broker.
+pub async fn trace_create(
+    State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
+    Json(params): Json<CreatePacketTraceReq>,
+) -> String {
+    let result = create_packet_trace(&state, &params).await;
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::CreatePacketTrace,
+        &params.name,
+        None,
+        audit_value(&params),
+        &result,
+    )
+    .await;
+
+    match result {
+        Ok(_) => success_response("success"),
+        Err(e) => error_response(e.to_string()),
+    }
+}
+
+async fn create_packet_trace(
+    state: &Arc<HttpState>,
+    params: &CreatePacketTraceReq,
+) -> Result<(), MqttBrokerError> {
+    let trace_type = PacketTraceType::from_str(&params.trace_type).map_err(|_| {
+        MqttBrokerError::CommonError(format!("Invalid trace type {}", params.trace_type))
+    })?;
+    let output = match &params.output {
+        Some(output) => PacketTraceOutput::from_str(output)
+            .map_err(|_| MqttBrokerError::CommonError(format!("Invalid trace output {output}")))?,
+        None => PacketTraceOutput::File,
+    };
+    let payload_encode = match &params.payload_encode {
+        Some(encode) => PacketTracePayloadEncode::from_str(encode).map_err(|_| {
+            MqttBrokerError::CommonError(format!("Invalid payload encode {encode}"))
+        })?,
+        None => PacketTracePayloadEncode::Text,
+    };
+
+    let start_time = now_second();
+    let trace = PacketTrace {
+        name: params.name.clone(),
+        trace_type,
+        target: params.target.clone(),
+        output,
+        payload_encode,
+        payload_limit: params.payload_limit.unwrap_or(DEFAULT_PAYLOAD_LIMIT),
+        start_time,
+        end_time: start_time + params.duration_secs,
+    };
+    trace.validate()?;
+    if state
+        .mqtt_context
+        .cache_manager
+        .packet_trace
+        .get_trace(&trace.name)
+        .is_some()
+    {
+        return Err(MqttBrokerError::CommonError(format!(
+            "Trace {} already exists",
+            trace.name
+        )));
+    }
+
+    init_packet_trace_output(&state.mqtt_context.message_storage_adapter, &trace).await?;
+    state
+        .mqtt_context
+        .cache_manager
+        .packet_trace
+        .add_trace(trace)
+}
+
+pub async fn trace_stop(
+    State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
+    Json(params): Json<StopPacketTraceReq>,
+) -> String {
+    let result = state
+        .mqtt_context
+        .cache_manager
+        .packet_trace
+        .stop_trace(&params.name);
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::StopPacketTrace,
+        &params.name,
+        None,
+        None,
+        &result,
+    )
+    .await;
+
+    match result {
+        Ok(_) => success_response("success"),
+        Err(e) => error_response(e.to_string()),
+    }
+}
+
+pub async fn trace_delete(
+    State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
+    Json(params): Json<DeletePacketTraceReq>,
+) -> String {
+    let result = match state
+        .mqtt_context
+        .cache_manager
+        .packet_trace
+        .remove_trace(&params.name)
+    {
+        Some(trace) => {
+            clean_packet_trace_output(&state.mqtt_context.message_storage_adapter, &trace).await
+        }
+        None => Err(MqttBrokerError::CommonError(format!(
+            "Trace {} does not exist",
+            params.name
+        ))),
+    };
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::DeletePacketTrace,
+        &params.name,
+        audit_value(&params),
+        None,
+        &result,
+    )
+    .await;
+
+    match result {
+        Ok(_) => success_response("success"),
+        Err(e) => error_response(e.to_string()),
+    }
+}
+
+/// Download the captured packets of a trace as JSON lines, one packet per line.
+pub async fn trace_download(
+    State(state): State<Arc<HttpState>>,
+    Json(params): Json<DownloadPacketTraceReq>,
+) -> String {
+    let Some(trace) = state
+        .mqtt_context
+        .cache_manager
+        .packet_trace
+        .get_trace(&params.name)
+    else {
+        return error_response(format!("Trace {} does not exist", params.name));
+    };
+
+    match export_packet_trace(&state.mqtt_context.message_storage_adapter, &trace).await {
+        Ok(data) => data,
+        Err(e) => error_response(e.to_string()),
+    }
+}
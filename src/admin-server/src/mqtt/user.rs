@@ -16,12 +16,15 @@
     request::mqtt::{CreateUserReq, DeleteUserReq, UserListReq},
     response::{mqtt::UserListRow, PageReplyData},
     state::HttpState,
-    tool::query::{apply_filters, apply_pagination, apply_sorting, build_query_params, Queryable},
+    tool::{
+        audit::{audit_value, record_admin_audit, AuditContext},
+        query::{apply_filters, apply_pagination, apply_sorting, build_query_params, Queryable},
+    },
 };
 use axum::{extract::State, Json};
 use common_base::http_response::{error_response, success_response};
 use metadata_struct::mqtt::user::MqttUser;
-use mqtt_broker::security::AuthDriver;
+use mqtt_broker::{handler::audit_log::AuditAction, security::AuthDriver};
 use std::sync::Arc;
 
 pub async fn user_list(
@@ -38,7 +41,7 @@ pub async fn user_list(
         params.exact_match,
     );
 
-    let auth_driver = AuthDriver::new(
+    let auth_driver = AuthDriver::new_with_stale_read(
         state.mqtt_context.cache_manager.clone(),
         state.client_pool.clone(),
     );
@@ -81,6 +84,7 @@ fn get_field_str(&self, field: &str) -> Option<String> {
 
 pub async fn user_create(
     State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
     Json(params): Json<CreateUserReq>,
 ) -> String {
     let mqtt_user = MqttUser {
@@ -94,7 +98,22 @@ pub async fn user_create(
         state.mqtt_context.cache_manager.clone(),
         state.client_pool.clone(),
     );
-    match auth_driver.save_user(mqtt_user).await {
+    let result = auth_driver.save_user(mqtt_user).await;
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::CreateUser,
+        &params.username,
+        None,
+        audit_value(&UserListRow {
+            username: params.username.clone(),
+            is_superuser: params.is_superuser,
+        }),
+        &result,
+    )
+    .await;
+
+    match result {
         Ok(_) => success_response("success"),
         Err(e) => error_response(e.to_string()),
     }
@@ -102,6 +121,7 @@ pub async fn user_create(
 
 pub async fn user_delete(
     State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
     Json(params): Json<DeleteUserReq>,
 ) -> String {
     let auth_driver = AuthDriver::new(
@@ -109,7 +129,31 @@ pub async fn user_delete(
         state.client_pool.clone(),
     );
 
-    match auth_driver.delete_user(params.username.clone()).await {
+    let before = state
+        .mqtt_context
+        .cache_manager
+        .user_info
+        .get(&params.username)
+        .and_then(|user| {
+            audit_value(&UserListRow {
+                username: user.username.clone(),
+                is_superuser: user.is_superuser,
+            })
+        });
+
+    let result = auth_driver.delete_user(params.username.clone()).await;
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::DeleteUser,
+        &params.username,
+        before,
+        None,
+        &result,
+    )
+    .await;
+
+    match result {
         Ok(_) => success_response("success"),
         Err(e) => error_response(e.to_string()),
     }
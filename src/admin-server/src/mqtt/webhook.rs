@@ -0,0 +1,212 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+    request::mqtt::{CreateWebhookReq, DeleteWebhookReq, WebhookListReq},
+    response::{mqtt::WebhookListRow, PageReplyData},
+    state::HttpState,
+    tool::{
+        audit::{audit_value, record_admin_audit, AuditContext},
+        query::{apply_filters, apply_pagination, apply_sorting, build_query_params, Queryable},
+    },
+};
+use axum::{extract::State, Json};
+use common_base::{
+    http_response::{error_response, success_response},
+    tools::now_second,
+    utils::time_util::timestamp_to_local_datetime,
+};
+use common_config::broker::broker_config;
+use metadata_struct::mqtt::webhook::MqttWebhook;
+use mqtt_broker::{
+    handler::{audit_log::AuditAction, error::MqttBrokerError, webhook::validate_webhook},
+    storage::webhook::WebhookStorage,
+};
+use std::sync::Arc;
+
+pub async fn webhook_list(
+    State(state): State<Arc<HttpState>>,
+    Json(params): Json<WebhookListReq>,
+) -> String {
+    let options = build_query_params(
+        params.page,
+        params.limit,
+        params.sort_field,
+        params.sort_by,
+        params.filter_field,
+        params.filter_values,
+        params.exact_match,
+    );
+
+    let webhook_storage = WebhookStorage::new(state.client_pool.clone());
+    let webhooks = match webhook_storage.list().await {
+        Ok(data) => data,
+        Err(e) => {
+            return error_response(e.to_string());
+        }
+    };
+
+    let manager = &state.mqtt_context.cache_manager.webhook;
+    let results = webhooks
+        .into_iter()
+        .map(|webhook| {
+            let health = manager.get_health(&webhook.name);
+            WebhookListRow {
+                pending: manager.pending_num(&webhook.name),
+                name: webhook.name,
+                url: webhook.url,
+                signed: !webhook.secret.is_empty(),
+                events: webhook
+                    .events
+                    .iter()
+                    .map(|event| event.to_string())
+                    .collect(),
+                topic_filters: webhook.topic_filters,
+                enable: webhook.enable,
+                status: health.status.to_string(),
+                delivered: health.delivered,
+                failed: health.failed,
+                dropped: health.dropped,
+                consecutive_failures: health.consecutive_failures,
+                last_success_time: health.last_success_time,
+                last_failure_time: health.last_failure_time,
+                last_error: health.last_error,
+                create_time: timestamp_to_local_datetime(webhook.create_time as i64),
+            }
+        })
+        .collect();
+
+    let filtered = apply_filters(results, &options);
+    let sorted = apply_sorting(filtered, &options);
+    let pagination = apply_pagination(sorted, &options);
+
+    success_response(PageReplyData {
+        data: pagination.0,
+        total_count: pagination.1,
+    })
+}
+
+impl Queryable for WebhookListRow {
+    fn get_field_str(&self, field: &str) -> Option<String> {
+        match field {
+            "name" => Some(self.name.clone()),
+            "url" => Some(self.url.clone()),
+            "status" => Some(self.status.clone()),
+            _ => None,
+        }
+    }
+}
+
+pub async fn webhook_create(
+    State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
+    Json(params): Json<CreateWebhookReq>,
+) -> String {
+    let result = create_webhook(&state, &params).await;
+    // the secret must not end up in the audit log
+    let mut audit_params = params.clone();
+    audit_params.secret = None;
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::CreateWebhook,
+        &params.name,
+        None,
+        audit_value(&audit_params),
+        &result,
+    )
+    .await;
+
+    match result {
+        Ok(_) => success_response("success"),
+        Err(e) => error_response(e.to_string()),
+    }
+}
+
+async fn create_webhook(
+    state: &Arc<HttpState>,
+    params: &CreateWebhookReq,
+) -> Result<(), MqttBrokerError> {
+    let webhook = MqttWebhook {
+        cluster_name: broker_config().cluster_name.clone(),
+        name: params.name.clone(),
+        url: params.url.clone(),
+        secret: params.secret.clone().unwrap_or_default(),
+        events: params.events.clone(),
+        topic_filters: params.topic_filters.clone(),
+        enable: params.enable,
+        create_time: now_second(),
+    };
+    validate_webhook(&webhook)?;
+
+    let webhook_storage = WebhookStorage::new(state.client_pool.clone());
+    webhook_storage.save(&webhook).await?;
+
+    // other brokers pick the webhook up on their next reload
+    state
+        .mqtt_context
+        .cache_manager
+        .webhook
+        .add_webhook(webhook);
+    Ok(())
+}
+
+pub async fn webhook_delete(
+    State(state): State<Arc<HttpState>>,
+    audit: AuditContext,
+    Json(params): Json<DeleteWebhookReq>,
+) -> String {
+    let result = delete_webhook(&state, &params).await;
+    record_admin_audit(
+        &state,
+        &audit,
+        AuditAction::DeleteWebhook,
+        &params.name,
+        audit_value(&params),
+        None,
+        &result,
+    )
+    .await;
+
+    match result {
+        Ok(_) => success_response("success"),
+        Err(e) => error_response(e.to_string()),
+    }
+}
+
+async fn delete_webhook(
+    state: &Arc<HttpState>,
+    params: &DeleteWebhookReq,
+) -> Result<(), MqttBrokerError> {
+    let webhook_storage = WebhookStorage::new(state.client_pool.clone());
+    if !webhook_storage
+        .list()
+        .await?
+        .iter()
+        .any(|webhook| webhook.name == params.name)
+    {
+        return Err(MqttBrokerError::CommonError(format!(
+            "Webhook {} does not exist",
+            params.name
+        )));
+    }
+
+    webhook_storage.delete(&params.name).await?;
+    state
+        .mqtt_context
+        .cache_manager
+        .webhook
+        .remove_webhook(&params.name);
+    Ok(())
+}
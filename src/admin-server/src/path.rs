@@ -24,6 +24,24 @@
 // Cluster API paths
 pub const CLUSTER_CONFIG_SET_PATH: &str = "/cluster/config/set";
 pub const CLUSTER_CONFIG_GET_PATH: &str = "/cluster/config/get";
+pub const CLUSTER_DRAIN_START_PATH: &str = "/cluster/drain/start";
+pub const CLUSTER_DRAIN_STATUS_PATH: &str = "/cluster/drain/status";
+pub const CLUSTER_LOAD_PATH: &str = "/cluster/load";
+pub const CLUSTER_REBALANCE_PATH: &str = "/cluster/rebalance";
+
+// Log Level API paths
+pub const CLUSTER_LOG_LEVEL_LIST_PATH: &str = "/cluster/log/level/list";
+pub const CLUSTER_LOG_LEVEL_SET_PATH: &str = "/cluster/log/level/set";
+pub const CLUSTER_LOG_LEVEL_RESET_PATH: &str = "/cluster/log/level/reset";
+
+// Meta Service Membership API paths
+pub const CLUSTER_META_STATUS_PATH: &str = "/cluster/meta/status";
+pub const CLUSTER_META_ADD_LEARNER_PATH: &str = "/cluster/meta/add-learner";
+pub const CLUSTER_META_PROMOTE_PATH: &str = "/cluster/meta/promote";
+pub const CLUSTER_META_REMOVE_PATH: &str = "/cluster/meta/remove";
+pub const CLUSTER_META_TRANSFER_LEADER_PATH: &str = "/cluster/meta/transfer-leader";
+pub const CLUSTER_META_BACKUP_PATH: &str = "/cluster/meta/backup";
+pub const CLUSTER_META_RESTORE_PATH: &str = "/cluster/meta/restore";
 
 // MQTT Overview API paths
 pub const MQTT_OVERVIEW_PATH: &str = "/mqtt/overview";
@@ -82,6 +100,7 @@
 // MQTT Schema API paths
 pub const MQTT_SCHEMA_LIST_PATH: &str = "/mqtt/schema/list";
 pub const MQTT_SCHEMA_CREATE_PATH: &str = "/mqtt/schema/create";
+pub const MQTT_SCHEMA_UPDATE_PATH: &str = "/mqtt/schema/update";
 pub const MQTT_SCHEMA_DELETE_PATH: &str = "/mqtt/schema/delete";
 pub const MQTT_SCHEMA_BIND_LIST_PATH: &str = "/mqtt/schema-bind/list";
 pub const MQTT_SCHEMA_BIND_CREATE_PATH: &str = "/mqtt/schema-bind/create";
@@ -91,6 +110,37 @@
 pub const MQTT_SYSTEM_ALARM_LIST_PATH: &str = "/mqtt/system-alarm/list";
 pub const MQTT_BAN_LOG_LIST_PATH: &str = "/mqtt/ban-log/list";
 
+pub const MQTT_AUDIT_LOG_LIST_PATH: &str = "/mqtt/audit-log/list";
+pub const MQTT_AUDIT_LOG_EXPORT_PATH: &str = "/mqtt/audit-log/export";
+
+// MQTT Packet Trace API paths
+pub const MQTT_TRACE_LIST_PATH: &str = "/mqtt/trace/list";
+pub const MQTT_TRACE_CREATE_PATH: &str = "/mqtt/trace/create";
+pub const MQTT_TRACE_STOP_PATH: &str = "/mqtt/trace/stop";
+pub const MQTT_TRACE_DELETE_PATH: &str = "/mqtt/trace/delete";
+pub const MQTT_TRACE_DOWNLOAD_PATH: &str = "/mqtt/trace/download";
+
+// MQTT Delivery Log API paths
+pub const MQTT_DELIVERY_MESSAGE_PATH: &str = "/mqtt/delivery/message";
+pub const MQTT_DELIVERY_CLIENT_PATH: &str = "/mqtt/delivery/client";
+
+// MQTT Topic Metrics API paths
+pub const MQTT_TOPIC_METRICS_LIST_PATH: &str = "/mqtt/topic-metrics/list";
+pub const MQTT_TOPIC_METRICS_CREATE_PATH: &str = "/mqtt/topic-metrics/create";
+pub const MQTT_TOPIC_METRICS_DELETE_PATH: &str = "/mqtt/topic-metrics/delete";
+pub const MQTT_TOPIC_METRICS_DATA_PATH: &str = "/mqtt/topic-metrics/data";
+
+// MQTT Alarm API paths
+pub const MQTT_ALARM_RULE_LIST_PATH: &str = "/mqtt/alarm/rule/list";
+pub const MQTT_ALARM_RULE_CREATE_PATH: &str = "/mqtt/alarm/rule/create";
+pub const MQTT_ALARM_RULE_DELETE_PATH: &str = "/mqtt/alarm/rule/delete";
+pub const MQTT_ALARM_HISTORY_LIST_PATH: &str = "/mqtt/alarm/history/list";
+
+// MQTT Webhook API paths
+pub const MQTT_WEBHOOK_LIST_PATH: &str = "/mqtt/webhook/list";
+pub const MQTT_WEBHOOK_CREATE_PATH: &str = "/mqtt/webhook/create";
+pub const MQTT_WEBHOOK_DELETE_PATH: &str = "/mqtt/webhook/delete";
+
 // Utility functions for building API paths with prefix
 pub const API_PREFIX: &str = "/api";
 
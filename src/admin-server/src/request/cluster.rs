@@ -22,3 +22,36 @@ pub struct ClusterConfigSetReq {
     pub config_type: String,
     pub config: String,
 }
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct DrainStartReq {}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct DrainStatusReq {}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct ClusterLoadReq {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ClusterRebalanceReq {
+    pub percent: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct LogLevelListReq {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LogLevelSetReq {
+    pub target: String,
+    pub level: String,
+    // only change this appender, all appenders receiving the target by default
+    pub appender: Option<String>,
+    // revert the change after this many seconds
+    pub ttl_sec: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LogLevelResetReq {
+    pub target: String,
+    pub appender: Option<String>,
+}
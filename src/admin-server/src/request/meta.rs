@@ -11,3 +11,46 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct MetaStatusReq {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MetaAddLearnerReq {
+    pub node_id: u64,
+    pub rpc_addr: String,
+    // Promote the node to a voter once it has caught up with the leader.
+    pub promote: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MetaPromoteReq {
+    pub node_id: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MetaRemoveNodeReq {
+    pub node_id: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MetaTransferLeaderReq {
+    pub node_id: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct MetaBackupReq {
+    // Only export these MQTT resources, e.g. "user", "acl", "topic". Everything when empty.
+    pub resources: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MetaRestoreReq {
+    pub archive: String,
+    // Target cluster for imported MQTT resources, defaults to the cluster of the archive.
+    pub cluster_name: Option<String>,
+    // Only import these MQTT resources. The whole archive is restored when empty.
+    pub resources: Option<Vec<String>>,
+}
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use metadata_struct::mqtt::alarm::{AlarmMetric, AlarmSink};
+use metadata_struct::mqtt::webhook::WebhookEventType;
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize)]
@@ -61,8 +63,11 @@ pub struct SubscribeListReq {
     pub filter_values: Option<Vec<String>>,
     pub exact_match: Option<String>,
 }
-#[derive(Deserialize, Debug)]
-pub struct SubscribeDetailReq {}
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SubscribeDetailReq {
+    pub client_id: String,
+    pub path: String,
+}
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct AutoSubscribeListReq {
@@ -211,6 +216,8 @@ pub struct CreateConnectorReq {
     pub connector_type: String,
     pub config: String,
     pub topic_name: String,
+    #[serde(default)]
+    pub payload_format: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -235,6 +242,22 @@ pub struct CreateSchemaReq {
     pub schema_type: String,
     pub schema: String,
     pub desc: String,
+    #[serde(default)]
+    pub message_name: String,
+    #[serde(default)]
+    pub compatibility: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UpdateSchemaReq {
+    pub schema_name: String,
+    pub schema_type: String,
+    pub schema: String,
+    pub desc: String,
+    #[serde(default)]
+    pub message_name: String,
+    #[serde(default)]
+    pub compatibility: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -290,3 +313,181 @@ pub struct SystemAlarmListReq {
     pub filter_values: Option<Vec<String>>,
     pub exact_match: Option<String>,
 }
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AuditLogListReq {
+    pub limit: Option<u32>,
+    pub page: Option<u32>,
+    pub sort_field: Option<String>,
+    pub sort_by: Option<String>,
+    pub filter_field: Option<String>,
+    pub filter_values: Option<Vec<String>>,
+    pub exact_match: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AuditLogExportReq {
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PacketTraceListReq {
+    pub limit: Option<u32>,
+    pub page: Option<u32>,
+    pub sort_field: Option<String>,
+    pub sort_by: Option<String>,
+    pub filter_field: Option<String>,
+    pub filter_values: Option<Vec<String>>,
+    pub exact_match: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CreatePacketTraceReq {
+    pub name: String,
+    // ClientId, Username, Topic or Ip
+    pub trace_type: String,
+    pub target: String,
+    pub duration_secs: u64,
+    // File (default) or Journal
+    pub output: Option<String>,
+    // Text (default), Hex or Hidden
+    pub payload_encode: Option<String>,
+    pub payload_limit: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StopPacketTraceReq {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeletePacketTraceReq {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DownloadPacketTraceReq {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeliveryMessageReq {
+    pub topic_name: String,
+    pub offset: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeliveryClientReq {
+    pub client_id: String,
+    pub limit: Option<u32>,
+    pub page: Option<u32>,
+    pub sort_field: Option<String>,
+    pub sort_by: Option<String>,
+    pub filter_field: Option<String>,
+    pub filter_values: Option<Vec<String>>,
+    pub exact_match: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TopicMetricsListReq {
+    pub limit: Option<u32>,
+    pub page: Option<u32>,
+    pub sort_field: Option<String>,
+    pub sort_by: Option<String>,
+    pub filter_field: Option<String>,
+    pub filter_values: Option<Vec<String>>,
+    pub exact_match: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CreateTopicMetricsReq {
+    pub topic_filter: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeleteTopicMetricsReq {
+    pub topic_filter: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TopicMetricsDataReq {
+    pub limit: Option<u32>,
+    pub page: Option<u32>,
+    pub sort_field: Option<String>,
+    pub sort_by: Option<String>,
+    pub filter_field: Option<String>,
+    pub filter_values: Option<Vec<String>>,
+    pub exact_match: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AlarmRuleListReq {
+    pub limit: Option<u32>,
+    pub page: Option<u32>,
+    pub sort_field: Option<String>,
+    pub sort_by: Option<String>,
+    pub filter_field: Option<String>,
+    pub filter_values: Option<Vec<String>>,
+    pub exact_match: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CreateAlarmRuleReq {
+    pub name: String,
+    pub metric: AlarmMetric,
+    pub activate_threshold: f64,
+    pub deactivate_threshold: f64,
+    #[serde(default)]
+    pub sinks: Vec<AlarmSink>,
+    #[serde(default = "default_true")]
+    pub enable: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeleteAlarmRuleReq {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct WebhookListReq {
+    pub limit: Option<u32>,
+    pub page: Option<u32>,
+    pub sort_field: Option<String>,
+    pub sort_by: Option<String>,
+    pub filter_field: Option<String>,
+    pub filter_values: Option<Vec<String>>,
+    pub exact_match: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CreateWebhookReq {
+    pub name: String,
+    pub url: String,
+    pub secret: Option<String>,
+    pub events: Vec<WebhookEventType>,
+    #[serde(default)]
+    pub topic_filters: Vec<String>,
+    #[serde(default = "default_true")]
+    pub enable: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeleteWebhookReq {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AlarmHistoryListReq {
+    pub limit: Option<u32>,
+    pub page: Option<u32>,
+    pub sort_field: Option<String>,
+    pub sort_by: Option<String>,
+    pub filter_field: Option<String>,
+    pub filter_values: Option<Vec<String>>,
+    pub exact_match: Option<String>,
+}
@@ -0,0 +1,49 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::logging::dynamic_filter::{AppenderFilterInfo, LogLevelOverride};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct DrainStatusResp {
+    pub broker_id: u64,
+    pub draining: bool,
+    pub connection_num: usize,
+    pub connector_num: usize,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ClusterLoadResp {
+    pub broker_id: u64,
+    pub rebalancing: bool,
+    pub nodes: Vec<NodeLoadRow>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct NodeLoadRow {
+    pub node_id: u64,
+    pub connection_num: u64,
+    pub draining: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct ClusterRebalanceResp {
+    pub moving_num: usize,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct LogLevelListResp {
+    pub appenders: Vec<AppenderFilterInfo>,
+    pub overrides: Vec<LogLevelOverride>,
+}
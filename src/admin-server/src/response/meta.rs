@@ -11,3 +11,59 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+
+use protocol::meta::meta_service_openraft::RaftStatusReply;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct MetaStatusResp {
+    pub node_id: u64,
+    pub state: String,
+    pub current_term: u64,
+    pub leader_id: u64,
+    pub leader_addr: String,
+    pub last_log_index: u64,
+    pub commit_index: u64,
+    pub last_applied: u64,
+    pub members: Vec<MetaMemberRow>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct MetaRestoreResp {
+    pub record_count: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct MetaMemberRow {
+    pub node_id: u64,
+    pub rpc_addr: String,
+    pub role: String,
+    pub matched_index: u64,
+    pub lag: u64,
+}
+
+impl From<RaftStatusReply> for MetaStatusResp {
+    fn from(reply: RaftStatusReply) -> Self {
+        MetaStatusResp {
+            node_id: reply.node_id,
+            state: reply.state,
+            current_term: reply.current_term,
+            leader_id: reply.current_leader,
+            leader_addr: reply.leader_addr,
+            last_log_index: reply.last_log_index,
+            commit_index: reply.commit_index,
+            last_applied: reply.last_applied,
+            members: reply
+                .members
+                .into_iter()
+                .map(|member| MetaMemberRow {
+                    node_id: member.node_id,
+                    rpc_addr: member.rpc_addr,
+                    role: if member.voter { "voter" } else { "learner" }.to_string(),
+                    matched_index: member.matched_index,
+                    lag: member.lag,
+                })
+                .collect(),
+        }
+    }
+}
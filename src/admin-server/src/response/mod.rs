@@ -14,6 +14,7 @@
 
 use serde::{Deserialize, Serialize};
 
+pub mod cluster;
 pub mod journal;
 pub mod meta;
 pub mod mqtt;
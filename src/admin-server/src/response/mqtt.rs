@@ -16,7 +16,7 @@
 
 use metadata_struct::{
     connection::NetworkConnection,
-    mqtt::{connection::MQTTConnection, session::MqttSession, topic::MQTTTopic},
+    mqtt::{alarm::AlarmSink, connection::MQTTConnection, session::MqttSession, topic::MQTTTopic},
     placement::node::BrokerNode,
 };
 use mqtt_broker::{handler::cache::ConnectionLiveTime, subscribe::manager::TopicSubscribeInfo};
@@ -84,8 +84,11 @@ pub struct ConnectorListRow {
     pub connector_type: String,
     pub config: String,
     pub topic_name: String,
+    pub payload_format: String,
     pub status: String,
     pub broker_id: String,
+    // None when the message storage cannot report the topic end offset
+    pub lag: Option<u64>,
     pub create_time: String,
     pub update_time: String,
 }
@@ -96,6 +99,9 @@ pub struct SchemaListRow {
     pub schema_type: String,
     pub desc: String,
     pub schema: String,
+    pub message_name: String,
+    pub version: u32,
+    pub compatibility: String,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -120,6 +126,23 @@ pub struct SubscribeListRow {
     pub is_share_sub: bool,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SubscribeDetailReply {
+    pub subscribe: SubscribeListRow,
+    // Share groups of the subscription led by this broker
+    pub share_groups: Vec<ShareGroupLagRow>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ShareGroupLagRow {
+    pub group_name: String,
+    pub sub_name: String,
+    pub topic_name: String,
+    pub end_offset: u64,
+    pub committed_offset: u64,
+    pub lag: u64,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct AutoSubscribeListRow {
     pub topic: String,
@@ -156,6 +179,7 @@ pub struct SessionListRow {
 pub struct SystemAlarmListRow {
     pub name: String,
     pub message: String,
+    pub activated: bool,
     pub create_time: u64,
 }
 
@@ -207,3 +231,108 @@ pub struct OverViewMetricsResp {
     pub message_out_num: Vec<HashMap<String, u64>>,
     pub message_drop_num: Vec<HashMap<String, u64>>,
 }
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AuditLogListRow {
+    pub offset: u64,
+    pub actor: String,
+    pub source_ip: String,
+    pub action: String,
+    pub target: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub success: bool,
+    pub reason: Option<String>,
+    pub create_time: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PacketTraceListRow {
+    pub name: String,
+    pub trace_type: String,
+    pub target: String,
+    pub output: String,
+    pub payload_encode: String,
+    pub payload_limit: usize,
+    pub status: String,
+    pub start_time: String,
+    pub end_time: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DeliveryEventRow {
+    pub topic_name: String,
+    pub offset: u64,
+    pub client_id: String,
+    pub sub_path: String,
+    pub event_type: String,
+    pub pkid: u16,
+    pub reason: Option<String>,
+    // milliseconds, events of one delivery are often within the same second
+    pub create_time: u128,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TopicMetricsRuleRow {
+    pub topic_filter: String,
+    pub topic_num: usize,
+    pub create_time: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TopicMetricsRow {
+    pub topic_name: String,
+    pub topic_filter: String,
+    pub messages_in: u64,
+    pub messages_out: u64,
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+    pub messages_dropped: u64,
+    pub messages_retained: u64,
+    pub subscriber_num: i64,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AlarmRuleRow {
+    pub name: String,
+    pub metric: String,
+    pub activate_threshold: f64,
+    pub deactivate_threshold: f64,
+    pub sinks: Vec<AlarmSink>,
+    pub enable: bool,
+    pub builtin: bool,
+    pub create_time: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WebhookListRow {
+    pub name: String,
+    pub url: String,
+    // whether requests carry an HMAC signature, the secret itself is never returned
+    pub signed: bool,
+    pub events: Vec<String>,
+    pub topic_filters: Vec<String>,
+    pub enable: bool,
+    pub status: String,
+    pub pending: usize,
+    pub delivered: u64,
+    pub failed: u64,
+    pub dropped: u64,
+    pub consecutive_failures: u64,
+    pub last_success_time: u64,
+    pub last_failure_time: u64,
+    pub last_error: Option<String>,
+    pub create_time: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AlarmHistoryRow {
+    pub rule_name: String,
+    pub broker_id: u64,
+    pub metric: String,
+    pub value: f64,
+    pub threshold: f64,
+    pub activated: bool,
+    pub message: String,
+    pub create_time: String,
+}
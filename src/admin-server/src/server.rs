@@ -13,16 +13,26 @@
 // limitations under the License.
 
 use crate::{
-    cluster::{cluster_config_get, cluster_config_set},
+    cluster::{
+        cluster_config_get, cluster_config_set, cluster_drain_start, cluster_drain_status,
+        cluster_load, cluster_rebalance, log_level_list, log_level_reset, log_level_set,
+    },
+    meta::{
+        meta_add_learner, meta_backup, meta_promote, meta_remove_node, meta_restore, meta_status,
+        meta_transfer_leader,
+    },
     mqtt::{
         acl::{acl_create, acl_delete, acl_list},
+        alarm::{alarm_history_list, alarm_rule_create, alarm_rule_delete, alarm_rule_list},
+        audit::{audit_log_export, audit_log_list},
         blacklist::{blacklist_create, blacklist_delete, blacklist_list},
         client::client_list,
         connector::{connector_create, connector_delete, connector_list},
+        delivery::{delivery_client, delivery_message},
         overview::{overview, overview_metrics},
         schema::{
             schema_bind_create, schema_bind_delete, schema_bind_list, schema_create, schema_delete,
-            schema_list,
+            schema_list, schema_update,
         },
         session::session_list,
         subscribe::{
@@ -31,7 +41,12 @@
         },
         system::{ban_log_list, flapping_detect_list, system_alarm_list},
         topic::{topic_detail, topic_list, topic_rewrite_create, topic_rewrite_list},
+        topic_metrics::{
+            topic_metrics_create, topic_metrics_data, topic_metrics_delete, topic_metrics_list,
+        },
+        trace::{trace_create, trace_delete, trace_download, trace_list, trace_stop},
         user::{user_create, user_delete, user_list},
+        webhook::{webhook_create, webhook_delete, webhook_list},
     },
     path::*,
     state::HttpState,
@@ -39,7 +54,7 @@
 use axum::response::Html;
 use axum::response::IntoResponse;
 use axum::{
-    extract::{ConnectInfo, Request, State},
+    extract::{ConnectInfo, DefaultBodyLimit, Request, State},
     http::{HeaderMap, Method, Uri},
     middleware::{self, Next},
     response::Response,
@@ -55,6 +70,8 @@
 use tower_http::{cors::CorsLayer, services::ServeDir};
 use tracing::{info, warn};
 
+const META_RESTORE_BODY_LIMIT: usize = 256 * 1024 * 1024;
+
 pub struct AdminServer {}
 
 impl Default for AdminServer {
@@ -110,6 +127,29 @@ fn common_route(&self) -> Router<Arc<HttpState>> {
             // config
             .route(CLUSTER_CONFIG_SET_PATH, post(cluster_config_set))
             .route(CLUSTER_CONFIG_GET_PATH, post(cluster_config_get))
+            .route(CLUSTER_DRAIN_START_PATH, post(cluster_drain_start))
+            .route(CLUSTER_DRAIN_STATUS_PATH, post(cluster_drain_status))
+            .route(CLUSTER_LOAD_PATH, post(cluster_load))
+            .route(CLUSTER_REBALANCE_PATH, post(cluster_rebalance))
+            // log level
+            .route(CLUSTER_LOG_LEVEL_LIST_PATH, post(log_level_list))
+            .route(CLUSTER_LOG_LEVEL_SET_PATH, post(log_level_set))
+            .route(CLUSTER_LOG_LEVEL_RESET_PATH, post(log_level_reset))
+            // meta service membership
+            .route(CLUSTER_META_STATUS_PATH, post(meta_status))
+            .route(CLUSTER_META_ADD_LEARNER_PATH, post(meta_add_learner))
+            .route(CLUSTER_META_PROMOTE_PATH, post(meta_promote))
+            .route(CLUSTER_META_REMOVE_PATH, post(meta_remove_node))
+            .route(
+                CLUSTER_META_TRANSFER_LEADER_PATH,
+                post(meta_transfer_leader),
+            )
+            .route(CLUSTER_META_BACKUP_PATH, post(meta_backup))
+            // metadata archives are well above the default 2MB body limit
+            .route(
+                CLUSTER_META_RESTORE_PATH,
+                post(meta_restore).layer(DefaultBodyLimit::max(META_RESTORE_BODY_LIMIT)),
+            )
     }
 
     fn mqtt_route(&self) -> Router<Arc<HttpState>> {
@@ -158,6 +198,7 @@ fn mqtt_route(&self) -> Router<Arc<HttpState>> {
             // schema
             .route(MQTT_SCHEMA_LIST_PATH, post(schema_list))
             .route(MQTT_SCHEMA_CREATE_PATH, post(schema_create))
+            .route(MQTT_SCHEMA_UPDATE_PATH, post(schema_update))
             .route(MQTT_SCHEMA_DELETE_PATH, post(schema_delete))
             .route(MQTT_SCHEMA_BIND_LIST_PATH, post(schema_bind_list))
             .route(MQTT_SCHEMA_BIND_CREATE_PATH, post(schema_bind_create))
@@ -165,6 +206,32 @@ fn mqtt_route(&self) -> Router<Arc<HttpState>> {
             // system alarm
             .route(MQTT_SYSTEM_ALARM_LIST_PATH, post(system_alarm_list))
             .route(MQTT_BAN_LOG_LIST_PATH, post(ban_log_list))
+            // audit log
+            .route(MQTT_AUDIT_LOG_LIST_PATH, post(audit_log_list))
+            .route(MQTT_AUDIT_LOG_EXPORT_PATH, post(audit_log_export))
+            // packet trace
+            .route(MQTT_TRACE_LIST_PATH, post(trace_list))
+            .route(MQTT_TRACE_CREATE_PATH, post(trace_create))
+            .route(MQTT_TRACE_STOP_PATH, post(trace_stop))
+            .route(MQTT_TRACE_DELETE_PATH, post(trace_delete))
+            .route(MQTT_TRACE_DOWNLOAD_PATH, post(trace_download))
+            // delivery log
+            .route(MQTT_DELIVERY_MESSAGE_PATH, post(delivery_message))
+            .route(MQTT_DELIVERY_CLIENT_PATH, post(delivery_client))
+            // topic metrics
+            .route(MQTT_TOPIC_METRICS_LIST_PATH, post(topic_metrics_list))
+            .route(MQTT_TOPIC_METRICS_CREATE_PATH, post(topic_metrics_create))
+            .route(MQTT_TOPIC_METRICS_DELETE_PATH, post(topic_metrics_delete))
+            .route(MQTT_TOPIC_METRICS_DATA_PATH, post(topic_metrics_data))
+            // alarm
+            .route(MQTT_ALARM_RULE_LIST_PATH, post(alarm_rule_list))
+            .route(MQTT_ALARM_RULE_CREATE_PATH, post(alarm_rule_create))
+            .route(MQTT_ALARM_RULE_DELETE_PATH, post(alarm_rule_delete))
+            .route(MQTT_ALARM_HISTORY_LIST_PATH, post(alarm_history_list))
+            // webhook
+            .route(MQTT_WEBHOOK_LIST_PATH, post(webhook_list))
+            .route(MQTT_WEBHOOK_CREATE_PATH, post(webhook_create))
+            .route(MQTT_WEBHOOK_DELETE_PATH, post(webhook_delete))
     }
 
     fn kafka_route(&self) -> Router<Arc<HttpState>> {
@@ -348,7 +415,7 @@ fn format_size(size: Option<f64>) -> String {
     }
 }
 
-fn extract_client_ip(headers: &HeaderMap, socket_addr: SocketAddr) -> String {
+pub(crate) fn extract_client_ip(headers: &HeaderMap, socket_addr: SocketAddr) -> String {
     if let Some(forwarded_for) = headers.get("x-forwarded-for") {
         if let Ok(forwarded_str) = forwarded_for.to_str() {
             if let Some(first_ip) = forwarded_str.split(',').next() {
@@ -23,6 +23,7 @@
 use network_server::common::connection_manager::ConnectionManager;
 use rate_limit::RateLimiterManager;
 use schema_register::schema::SchemaRegisterManager;
+use storage_adapter::storage::ArcStorageAdapter;
 
 #[derive(Clone)]
 pub struct HttpState {
@@ -41,4 +42,5 @@ pub struct MQTTContext {
     pub metrics_manager: Arc<MetricsCacheManager>,
     pub connector_manager: Arc<ConnectorManager>,
     pub schema_manager: Arc<SchemaRegisterManager>,
+    pub message_storage_adapter: ArcStorageAdapter,
 }
@@ -0,0 +1,146 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{server::extract_client_ip, state::HttpState};
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::{header::AUTHORIZATION, request::Parts, HeaderMap},
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use mqtt_broker::{
+    handler::audit_log::{record_audit_log, AuditAction, AuditLogEntry},
+    security::password::verify_user_password,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::{convert::Infallible, net::SocketAddr, sync::Arc};
+
+const ANONYMOUS_ACTOR: &str = "anonymous";
+
+const REDACTED_VALUE: &str = "******";
+
+// Config fields holding credentials, matched as a substring of the field name
+const SECRET_FIELDS: [&str; 4] = ["password", "token", "oauth", "secret"];
+
+#[derive(Clone, Debug)]
+pub struct AuditContext {
+    pub actor: String,
+    pub source_ip: String,
+}
+
+#[async_trait]
+impl FromRequestParts<Arc<HttpState>> for AuditContext {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &Arc<HttpState>,
+    ) -> Result<Self, Self::Rejection> {
+        let source_ip = match parts.extensions.get::<ConnectInfo<SocketAddr>>() {
+            Some(ConnectInfo(addr)) => extract_client_ip(&parts.headers, *addr),
+            None => "unknown".to_string(),
+        };
+
+        let actor = verified_actor(state, &parts.headers).unwrap_or(ANONYMOUS_ACTOR.to_string());
+        Ok(AuditContext { actor, source_ip })
+    }
+}
+
+/// The actor is only taken from HTTP Basic credentials that match a user of
+/// the broker, any other request is recorded as anonymous.
+fn verified_actor(state: &Arc<HttpState>, headers: &HeaderMap) -> Option<String> {
+    let encoded = headers
+        .get(AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Basic ")?;
+    let decoded = String::from_utf8(STANDARD.decode(encoded.trim()).ok()?).ok()?;
+    let (username, password) = decoded.split_once(':')?;
+
+    let user = state.mqtt_context.cache_manager.user_info.get(username)?;
+    match verify_user_password(&user, password) {
+        Ok(true) => Some(username.to_string()),
+        _ => None,
+    }
+}
+
+pub fn audit_value<T: Serialize>(value: &T) -> Option<String> {
+    serde_json::to_string(value).ok()
+}
+
+/// Mask the credential fields of a JSON config before it is written to the
+/// audit log. A config that is not valid JSON is masked as a whole.
+pub fn redact_config(config: &str) -> String {
+    match serde_json::from_str::<Value>(config) {
+        Ok(mut value) => {
+            redact_value(&mut value);
+            value.to_string()
+        }
+        Err(_) => REDACTED_VALUE.to_string(),
+    }
+}
+
+fn redact_value(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                let key = key.to_lowercase();
+                if SECRET_FIELDS.iter().any(|secret| key.contains(secret)) {
+                    if !field.is_null() {
+                        *field = Value::String(REDACTED_VALUE.to_string());
+                    }
+                } else {
+                    redact_value(field);
+                }
+            }
+        }
+        Value::Array(list) => list.iter_mut().for_each(redact_value),
+        _ => {}
+    }
+}
+
+pub async fn record_admin_audit<T, E: ToString>(
+    state: &Arc<HttpState>,
+    audit: &AuditContext,
+    action: AuditAction,
+    target: &str,
+    before: Option<String>,
+    after: Option<String>,
+    result: &Result<T, E>,
+) {
+    let entry = AuditLogEntry::new(&audit.actor, &audit.source_ip, action, target)
+        .with_before(before)
+        .with_after(after)
+        .with_result(result);
+    record_audit_log(&state.mqtt_context.message_storage_adapter, entry).await;
+}
+
+#[cfg(test)]
+mod test {
+    use super::{redact_config, REDACTED_VALUE};
+
+    #[test]
+    fn redact_config_test() {
+        let config = r#"{"server":"127.0.0.1:5432","username":"root","password":"pwd","auth":{"basic_password":"p","token":null}}"#;
+        let redacted: serde_json::Value = serde_json::from_str(&redact_config(config)).unwrap();
+        assert_eq!(redacted["server"], "127.0.0.1:5432");
+        assert_eq!(redacted["username"], "root");
+        assert_eq!(redacted["password"], REDACTED_VALUE);
+        assert_eq!(redacted["auth"]["basic_password"], REDACTED_VALUE);
+        assert!(redacted["auth"]["token"].is_null());
+
+        assert_eq!(redact_config("password=pwd"), REDACTED_VALUE);
+    }
+}
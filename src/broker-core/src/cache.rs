@@ -12,13 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 
 use common_base::{node_status::NodeStatus, tools::now_second};
 use common_config::config::BrokerConfig;
 use dashmap::DashMap;
 use metadata_struct::placement::node::BrokerNode;
-use tokio::sync::RwLock;
+use tokio::sync::{Notify, RwLock};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NodeLoad {
+    pub connection_num: u64,
+    pub draining: bool,
+}
 
 pub struct BrokerCacheManager {
     // start_time
@@ -35,6 +42,18 @@ pub struct BrokerCacheManager {
 
     // (cluster_name, Status)
     pub status: Arc<RwLock<NodeStatus>>,
+
+    // The node is being drained: new connections are redirected to other nodes
+    pub draining: Arc<AtomicBool>,
+
+    // Notified once a draining node holds no more connections and connectors
+    pub drained: Arc<Notify>,
+
+    // Number of client connections held by this node, reported by heartbeat
+    pub connection_num: Arc<AtomicU64>,
+
+    // (node_id, NodeLoad) of every node in the cluster, refreshed by heartbeat
+    pub node_loads: DashMap<u64, NodeLoad>,
 }
 impl BrokerCacheManager {
     pub fn new(cluster: BrokerConfig) -> Self {
@@ -44,6 +63,10 @@ pub fn new(cluster: BrokerConfig) -> Self {
             node_lists: DashMap::with_capacity(2),
             cluster_config: Arc::new(RwLock::new(cluster.clone())),
             status: Arc::new(RwLock::new(NodeStatus::Starting)),
+            draining: Arc::new(AtomicBool::new(false)),
+            drained: Arc::new(Notify::new()),
+            connection_num: Arc::new(AtomicU64::new(0)),
+            node_loads: DashMap::with_capacity(2),
         }
     }
 
@@ -82,6 +105,44 @@ pub async fn is_stop(&self) -> bool {
         self.get_status().await == NodeStatus::Stopping
     }
 
+    // drain
+    pub fn set_draining(&self, draining: bool) {
+        self.draining.store(draining, Ordering::SeqCst);
+    }
+
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    pub fn notify_drained(&self) {
+        self.drained.notify_one();
+    }
+
+    pub async fn wait_drained(&self) {
+        self.drained.notified().await;
+    }
+
+    // load
+    pub fn set_connection_num(&self, num: u64) {
+        self.connection_num.store(num, Ordering::Relaxed);
+    }
+
+    pub fn get_connection_num(&self) -> u64 {
+        self.connection_num.load(Ordering::Relaxed)
+    }
+
+    pub fn update_node_loads(&self, loads: Vec<(u64, NodeLoad)>) {
+        self.node_loads
+            .retain(|node_id, _| loads.iter().any(|(id, _)| id == node_id));
+        for (node_id, load) in loads {
+            self.node_loads.insert(node_id, load);
+        }
+    }
+
+    pub fn get_node_load(&self, node_id: u64) -> Option<NodeLoad> {
+        self.node_loads.get(&node_id).map(|load| load.clone())
+    }
+
     // cluster config
     pub async fn set_cluster_config(&self, config: BrokerConfig) {
         let mut data = self.cluster_config.write().await;
@@ -133,4 +194,36 @@ async fn node_operations() {
         let nodes = cache_manager.node_list();
         assert!(nodes.is_empty());
     }
+
+    #[tokio::test]
+    async fn drain_operations() {
+        let cache_manager = BrokerCacheManager::new(default_broker_config());
+        assert!(!cache_manager.is_draining());
+
+        cache_manager.set_draining(true);
+        assert!(cache_manager.is_draining());
+
+        // the permit is kept until someone waits for it
+        cache_manager.notify_drained();
+        cache_manager.wait_drained().await;
+    }
+
+    #[test]
+    fn node_load_operations() {
+        let cache_manager = BrokerCacheManager::new(default_broker_config());
+        cache_manager.set_connection_num(10);
+        assert_eq!(cache_manager.get_connection_num(), 10);
+
+        let load = NodeLoad {
+            connection_num: 5,
+            draining: false,
+        };
+        cache_manager.update_node_loads(vec![(1, load.clone()), (2, NodeLoad::default())]);
+        assert_eq!(cache_manager.get_node_load(1), Some(load));
+
+        // nodes missing from the latest report are forgotten
+        cache_manager.update_node_loads(vec![(2, NodeLoad::default())]);
+        assert!(cache_manager.get_node_load(1).is_none());
+        assert!(cache_manager.get_node_load(2).is_some());
+    }
 }
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::cache::BrokerCacheManager;
+use crate::cache::{BrokerCacheManager, NodeLoad};
 use common_base::error::common::CommonError;
 use common_base::tools::{get_local_ip, now_second};
 use common_config::broker::broker_config;
@@ -119,21 +119,39 @@ pub async fn unregister_node(&self, config: &BrokerConfig) -> Result<(), CommonE
         Ok(())
     }
 
-    pub async fn heartbeat(&self) -> Result<(), CommonError> {
+    pub async fn heartbeat(
+        &self,
+        draining: bool,
+        connection_num: u64,
+    ) -> Result<Vec<(u64, NodeLoad)>, CommonError> {
         let config = broker_config();
         let req = HeartbeatRequest {
             cluster_name: config.cluster_name.clone(),
             node_id: config.broker_id,
+            draining,
+            connection_num,
         };
 
-        heartbeat(
+        let reply = heartbeat(
             &self.client_pool,
             &config.get_meta_service_addr(),
             req.clone(),
         )
         .await?;
 
-        Ok(())
+        Ok(reply
+            .node_loads
+            .into_iter()
+            .map(|load| {
+                (
+                    load.node_id,
+                    NodeLoad {
+                        connection_num: load.connection_num,
+                        draining: load.draining,
+                    },
+                )
+            })
+            .collect())
     }
 
     pub async fn set_dynamic_config(
@@ -19,8 +19,9 @@
     metrics_rocksdb_list_ms, metrics_rocksdb_save_ms,
 };
 use rocksdb_engine::engine::{
-    rocksdb_engine_delete, rocksdb_engine_exists, rocksdb_engine_get,
-    rocksdb_engine_list_by_prefix, rocksdb_engine_save,
+    rocksdb_engine_count_by_prefix, rocksdb_engine_delete, rocksdb_engine_exists,
+    rocksdb_engine_get, rocksdb_engine_list_by_prefix, rocksdb_engine_list_by_prefix_limit,
+    rocksdb_engine_save,
 };
 use rocksdb_engine::warp::StorageDataWrap;
 use rocksdb_engine::RocksDBEngine;
@@ -96,3 +97,35 @@ pub fn engine_prefix_list_by_broker(
     metrics_rocksdb_list_ms("broker", duration);
     result
 }
+
+pub fn engine_prefix_list_limit_by_broker(
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+    prefix_key_name: String,
+    limit: usize,
+) -> Result<Vec<StorageDataWrap>, CommonError> {
+    let start_time = now_mills();
+    let result = rocksdb_engine_list_by_prefix_limit(
+        rocksdb_engine_handler,
+        DB_COLUMN_FAMILY_BROKER,
+        prefix_key_name,
+        limit,
+    );
+    let duration = (now_mills() - start_time) as f64;
+    metrics_rocksdb_list_ms("broker", duration);
+    result
+}
+
+pub fn engine_prefix_count_by_broker(
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+    prefix_key_name: String,
+) -> Result<u64, CommonError> {
+    let start_time = now_mills();
+    let result = rocksdb_engine_count_by_prefix(
+        rocksdb_engine_handler,
+        DB_COLUMN_FAMILY_BROKER,
+        prefix_key_name,
+    );
+    let duration = (now_mills() - start_time) as f64;
+    metrics_rocksdb_list_ms("broker", duration);
+    result
+}
@@ -45,15 +45,25 @@ pub async fn report_heartbeat(
 ) {
     let ac_fn = async || -> ResultCommonError {
         let cluster_storage = ClusterStorage::new(client_pool.clone());
-        if let Err(e) = cluster_storage.heartbeat().await {
-            if e.to_string().contains("Node") && e.to_string().contains("does not exist") {
-                if let Err(e) = register_node(client_pool, cache_manager).await {
-                    error!("{}", e);
+        match cluster_storage
+            .heartbeat(
+                cache_manager.is_draining(),
+                cache_manager.get_connection_num(),
+            )
+            .await
+        {
+            Ok(loads) => {
+                cache_manager.update_node_loads(loads);
+                debug!("heartbeat report success");
+            }
+            Err(e) => {
+                if e.to_string().contains("Node") && e.to_string().contains("does not exist") {
+                    if let Err(e) = register_node(client_pool, cache_manager).await {
+                        error!("{}", e);
+                    }
                 }
+                error!("{}", e);
             }
-            error!("{}", e);
-        } else {
-            debug!("heartbeat report success");
         }
         Ok(())
     };
@@ -131,6 +131,7 @@ fn get_place_kv_handler(place_params: &MetaServiceServerParams) -> GrpcKvService
     GrpcKvService::new(
         place_params.storage_driver.clone(),
         place_params.rocksdb_engine_handler.clone(),
+        place_params.kv_watch_manager.clone(),
     )
 }
 
@@ -165,6 +166,7 @@ fn get_mqtt_inner_handler(mqtt_params: &MqttBrokerServerParams) -> GrpcInnerServ
         mqtt_params.connector_manager.clone(),
         mqtt_params.schema_manager.clone(),
         mqtt_params.client_pool.clone(),
+        mqtt_params.connection_manager.clone(),
         mqtt_params.message_storage_adapter.clone(),
     )
 }
@@ -23,8 +23,10 @@
     rocksdb::{column_family_list, storage_data_fold, RocksDBEngine},
 };
 use common_base::runtime::create_runtime;
+use common_base::telemetry::trace::{init_tracer_provider, stop_tracer_provider};
 use common_config::{broker::broker_config, config::BrokerConfig};
 use common_metrics::core::server::register_prometheus_export;
+use common_security::encryption::{init_encryption, start_master_key_reload_thread};
 use delay_message::DelayMessageManager;
 use grpc_clients::pool::ClientPool;
 use journal_server::{
@@ -37,6 +39,7 @@
         journal::call_node::JournalInnerCallManager, mqtt::call_broker::MQTTInnerCallManager,
     },
     core::cache::CacheManager as PlacementCacheManager,
+    core::watch::{KvWatchManager, DEFAULT_WATCH_HISTORY_SIZE},
     raft::{
         raft_node::create_raft_node,
         route::{apply::StorageDriver, DataRoute},
@@ -96,12 +99,16 @@ fn default() -> Self {
 impl BrokerServer {
     pub fn new() -> Self {
         let config = broker_config();
+        if let Err(e) = init_encryption(&config.encryption) {
+            panic!("Failed to initialize encryption at rest, error: {e}");
+        }
         let client_pool = Arc::new(ClientPool::new(100));
         let rocksdb_engine_handler = Arc::new(RocksDBEngine::new(
             &storage_data_fold(&config.rocksdb.data_path),
             config.rocksdb.max_open_files,
             column_family_list(),
         ));
+        rewrap_rocksdb_values(&rocksdb_engine_handler, &column_family_list());
         let rate_limiter_manager = Arc::new(RateLimiterManager::new());
         let main_runtime = create_runtime("init_runtime", config.runtime.runtime_worker_threads);
         let broker_cache = Arc::new(BrokerCacheManager::new(config.clone()));
@@ -168,6 +175,7 @@ pub fn start(&self) {
                 metrics_manager: self.mqtt_params.metrics_cache_manager.clone(),
                 connector_manager: self.mqtt_params.connector_manager.clone(),
                 schema_manager: self.mqtt_params.schema_manager.clone(),
+                message_storage_adapter: self.mqtt_params.message_storage_adapter.clone(),
             },
             rocksdb_engine_handler: self.rocksdb_engine_handler.clone(),
             broker_cache: broker_cache.clone(),
@@ -185,10 +193,26 @@ pub fn start(&self) {
         server_runtime.spawn(async move {
             let conf = broker_config();
             if conf.p_prof.enable {
-                start_pprof_monitor(conf.p_prof.port, conf.p_prof.frequency).await;
+                start_pprof_monitor(
+                    conf.p_prof.port,
+                    conf.p_prof.frequency,
+                    conf.p_prof.lock_hold_threshold_ms,
+                )
+                .await;
             }
         });
 
+        // start opentelemetry tracing
+        let telemetry = self.config.telemetry.clone();
+        server_runtime.block_on(async move {
+            init_tracer_provider(
+                telemetry.enable,
+                &telemetry.exporter_type,
+                &telemetry.exporter_endpoint,
+            )
+            .await;
+        });
+
         // start prometheus
         let prometheus_port = self.config.prometheus.port;
         if self.config.prometheus.enable {
@@ -262,6 +286,13 @@ pub fn start(&self) {
         server_runtime
             .spawn(async move { network_connection_gc(connection_manager, raw_stop_send).await });
 
+        // reload master keys of encryption at rest
+        let raw_stop_send = stop_send.clone();
+        let key_reload_interval_sec = self.config.encryption.key_reload_interval_sec;
+        server_runtime.spawn(async move {
+            start_master_key_reload_thread(key_reload_interval_sec, raw_stop_send).await
+        });
+
         // awaiting stop
         self.awaiting_stop(place_stop_send, mqtt_stop_send, journal_stop_send);
     }
@@ -278,9 +309,11 @@ async fn build_meta_service(
             broker_cache,
         ));
 
+        let kv_watch_manager = Arc::new(KvWatchManager::new(DEFAULT_WATCH_HISTORY_SIZE));
         let data_route = Arc::new(DataRoute::new(
             rocksdb_engine_handler.clone(),
             cache_manager.clone(),
+            kv_watch_manager.clone(),
         ));
         let raf_node: Raft<TypeConfig> = create_raft_node(client_pool.clone(), data_route).await;
         let storage_driver: Arc<StorageDriver> = Arc::new(StorageDriver::new(raf_node.clone()));
@@ -292,6 +325,7 @@ async fn build_meta_service(
             mqtt_call_manager,
             raf_node,
             storage_driver,
+            kv_watch_manager,
         }
     }
 
@@ -351,6 +385,7 @@ fn build_journal_server(client_pool: Arc<ClientPool>) -> JournalServerParams {
             10000,
             column_family_list(),
         ));
+        rewrap_rocksdb_values(&rocksdb_engine_handler, &column_family_list());
 
         let segment_file_manager =
             Arc::new(SegmentFileManager::new(rocksdb_engine_handler.clone()));
@@ -375,11 +410,18 @@ pub fn awaiting_stop(
                 .set_status(common_base::node_status::NodeStatus::Running)
                 .await;
             // Wait for all the request packets in the TCP Channel to be processed completely before starting to stop other processing threads.
-            signal::ctrl_c().await.expect("failed to listen for event");
-            info!(
-                "{}",
-                "When ctrl + c is received, the service starts to stop"
-            );
+            tokio::select! {
+                res = signal::ctrl_c() => {
+                    res.expect("failed to listen for event");
+                    info!(
+                        "{}",
+                        "When ctrl + c is received, the service starts to stop"
+                    );
+                }
+                _ = self.broker_cache.wait_drained() => {
+                    info!("The broker has been drained, the service starts to stop");
+                }
+            }
 
             self.broker_cache
                 .set_status(common_base::node_status::NodeStatus::Stopping)
@@ -405,6 +447,8 @@ pub fn awaiting_stop(
                 }
             }
             sleep(Duration::from_secs(3));
+
+            stop_tracer_provider().await;
         });
     }
 
@@ -503,3 +547,16 @@ async fn register_node(&self, main_stop: broadcast::Sender<bool>) {
         }
     }
 }
+
+// Values sealed by a retired master key are re-wrapped once at startup, before the db
+// is shared with the services writing to it.
+fn rewrap_rocksdb_values(rocksdb_engine_handler: &RocksDBEngine, cf_list: &[String]) {
+    match rocksdb_engine_handler.rewrap_sealed_values(cf_list) {
+        Ok(0) => {}
+        Ok(num) => info!("Re-wrapped the data keys of {} rocksdb values", num),
+        Err(e) => error!(
+            "Failed to re-wrap the data keys of rocksdb values, error: {}",
+            e
+        ),
+    }
+}
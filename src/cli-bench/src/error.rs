@@ -32,6 +32,9 @@ pub enum BenchMarkError {
     #[error("Common error: {0}")]
     CommonError(#[from] Box<CommonError>),
 
+    #[error("MQTT client error: {0}")]
+    MqttClientError(#[from] paho_mqtt::Error),
+
     #[error("Unknown error occurred: {0}")]
     Unknown(String),
 }
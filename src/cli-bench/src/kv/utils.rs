@@ -56,6 +56,7 @@ pub async fn do_placement_set<T>(
     let req = SetRequest {
         key,
         value: serde_json::to_string(&value)?,
+        ..Default::default()
     };
 
     placement_set(client_pool, addrs, req)
@@ -0,0 +1,488 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{fs::File, io::BufReader, net::SocketAddr, sync::Arc, time::Duration};
+
+use network_server::quic::stream::{QuicFramedReadStream, QuicFramedWriteStream};
+use paho_mqtt::{
+    AsyncClient, AsyncReceiver, ConnectOptionsBuilder, CreateOptionsBuilder, Message,
+    SslOptionsBuilder,
+};
+use protocol::{
+    codec::{RobustMQCodec, RobustMQCodecWrapper},
+    mqtt::{
+        codec::{MqttCodec, MqttPacketWrapper},
+        common::{
+            qos, Connect, ConnectReturnCode, Disconnect, Filter, Login, MqttPacket, PingReq,
+            PubAck, PubComp, PubRec, PubRel, Publish, QoS, Subscribe,
+        },
+    },
+    robust::RobustMQProtocol,
+};
+use quinn::{ClientConfig, Connection, Endpoint, VarInt};
+use tokio::sync::{mpsc, Mutex};
+
+use super::common::{MqttConnectArgs, MqttTransport};
+use crate::BenchMarkError;
+
+const MESSAGE_BUFFER_SIZE: usize = 10000;
+
+// A connected bench client, paho covers tcp/tls/ws/wss and quic goes through quinn directly
+pub enum BenchClient {
+    Paho(PahoClient),
+    Quic(QuicClient),
+}
+
+impl BenchClient {
+    pub async fn connect(
+        args: &MqttConnectArgs,
+        index: usize,
+        receive: bool,
+    ) -> Result<BenchClient, BenchMarkError> {
+        if args.transport == MqttTransport::Quic {
+            return Ok(BenchClient::Quic(QuicClient::connect(args, index).await?));
+        }
+        Ok(BenchClient::Paho(
+            PahoClient::connect(args, index, receive).await?,
+        ))
+    }
+
+    // Publish one message and wait until the QoS flow completes
+    pub async fn publish(
+        &mut self,
+        topic: &str,
+        qos: i32,
+        payload: Vec<u8>,
+    ) -> Result<(), BenchMarkError> {
+        match self {
+            BenchClient::Paho(client) => client.publish(topic, qos, payload).await,
+            BenchClient::Quic(client) => client.publish(topic, qos, payload).await,
+        }
+    }
+
+    pub async fn subscribe(&mut self, topic: &str, qos: i32) -> Result<(), BenchMarkError> {
+        match self {
+            BenchClient::Paho(client) => client.subscribe(topic, qos).await,
+            BenchClient::Quic(client) => client.subscribe(topic, qos).await,
+        }
+    }
+
+    // Wait for the payload of the next incoming message, None once the connection is gone
+    pub async fn recv(&mut self) -> Option<Vec<u8>> {
+        match self {
+            BenchClient::Paho(client) => client.recv().await,
+            BenchClient::Quic(client) => client.recv().await,
+        }
+    }
+
+    pub async fn disconnect(self) {
+        match self {
+            BenchClient::Paho(client) => client.disconnect().await,
+            BenchClient::Quic(client) => client.disconnect().await,
+        }
+    }
+}
+
+pub struct PahoClient {
+    client: AsyncClient,
+    stream: Option<AsyncReceiver<Option<Message>>>,
+}
+
+impl PahoClient {
+    async fn connect(
+        args: &MqttConnectArgs,
+        index: usize,
+        receive: bool,
+    ) -> Result<PahoClient, BenchMarkError> {
+        let uri = match args.transport {
+            MqttTransport::Tcp => format!("tcp://{}:{}", args.host, args.port()),
+            MqttTransport::Tls => format!("mqtts://{}:{}", args.host, args.port()),
+            MqttTransport::Ws => format!("ws://{}:{}/mqtt", args.host, args.port()),
+            MqttTransport::Wss => format!("wss://{}:{}/mqtt", args.host, args.port()),
+            MqttTransport::Quic => {
+                return Err(BenchMarkError::InvalidConfiguration(
+                    "quic is not supported by the paho client".to_string(),
+                ))
+            }
+        };
+
+        let create_opts = CreateOptionsBuilder::new()
+            .server_uri(uri)
+            .client_id(args.client_id(index))
+            .mqtt_version(args.mqtt_version as u32)
+            .finalize();
+        let mut client = AsyncClient::new(create_opts)?;
+
+        // The stream must be registered before connecting so that no message is missed
+        let stream = if receive {
+            Some(client.get_stream(MESSAGE_BUFFER_SIZE))
+        } else {
+            None
+        };
+
+        let ws = matches!(args.transport, MqttTransport::Ws | MqttTransport::Wss);
+        let mut conn_opts = match (args.mqtt_version, ws) {
+            (5, true) => ConnectOptionsBuilder::new_ws_v5(),
+            (5, false) => ConnectOptionsBuilder::new_v5(),
+            (version, true) => {
+                let mut builder = ConnectOptionsBuilder::new_ws();
+                builder.mqtt_version(version as u32);
+                builder
+            }
+            (version, false) => ConnectOptionsBuilder::with_mqtt_version(version as u32),
+        };
+
+        if matches!(args.transport, MqttTransport::Tls | MqttTransport::Wss) {
+            let mut ssl_opts = SslOptionsBuilder::new();
+            if let Some(ca_file) = &args.ca_file {
+                ssl_opts.trust_store(ca_file)?;
+            } else {
+                ssl_opts.enable_server_cert_auth(false).verify(false);
+            }
+            conn_opts.ssl_options(ssl_opts.finalize());
+        }
+
+        if let Some(username) = &args.username {
+            conn_opts.user_name(username.clone());
+        }
+        if let Some(password) = &args.password {
+            conn_opts.password(password.clone());
+        }
+
+        conn_opts
+            .keep_alive_interval(Duration::from_secs(args.keep_alive_secs))
+            .connect_timeout(Duration::from_secs(30));
+        if args.mqtt_version == 5 {
+            conn_opts.clean_start(true);
+        } else {
+            conn_opts.clean_session(true);
+        }
+
+        client.connect(conn_opts.finalize()).await?;
+        Ok(PahoClient { client, stream })
+    }
+
+    async fn publish(
+        &mut self,
+        topic: &str,
+        qos: i32,
+        payload: Vec<u8>,
+    ) -> Result<(), BenchMarkError> {
+        let message = Message::new(topic, payload, qos);
+        self.client.publish(message).await?;
+        Ok(())
+    }
+
+    async fn subscribe(&mut self, topic: &str, qos: i32) -> Result<(), BenchMarkError> {
+        self.client.subscribe(topic, qos).await?;
+        Ok(())
+    }
+
+    async fn recv(&mut self) -> Option<Vec<u8>> {
+        let stream = self.stream.as_ref()?;
+        // None is pushed when the connection is lost, paho reconnects are not enabled
+        match stream.recv().await {
+            Ok(Some(message)) => Some(message.payload().to_vec()),
+            Ok(None) | Err(_) => None,
+        }
+    }
+
+    async fn disconnect(self) {
+        let _ = self.client.disconnect(None).await;
+    }
+}
+
+pub struct QuicClient {
+    endpoint: Endpoint,
+    connection: Connection,
+    protocol_version: u8,
+    write_stream: Arc<Mutex<QuicFramedWriteStream>>,
+    packet_recv: mpsc::Receiver<MqttPacket>,
+    pkid: u16,
+}
+
+impl QuicClient {
+    async fn connect(args: &MqttConnectArgs, index: usize) -> Result<QuicClient, BenchMarkError> {
+        let endpoint = build_quic_endpoint(args)?;
+        let addr = format!("{}:{}", args.host, args.port())
+            .parse()
+            .map_err(|e| BenchMarkError::InvalidConfiguration(format!("{e}")))?;
+        let connection = endpoint
+            .connect(addr, &args.server_name)
+            .map_err(|e| BenchMarkError::ExecutionError(e.to_string()))?
+            .await
+            .map_err(|e| BenchMarkError::ExecutionError(e.to_string()))?;
+        let (send, recv) = connection
+            .open_bi()
+            .await
+            .map_err(|e| BenchMarkError::ExecutionError(e.to_string()))?;
+
+        let protocol = match args.mqtt_version {
+            3 => RobustMQProtocol::MQTT3,
+            4 => RobustMQProtocol::MQTT4,
+            _ => RobustMQProtocol::MQTT5,
+        };
+        let codec = RobustMQCodec {
+            protocol: Some(protocol.clone()),
+            mqtt_codec: MqttCodec::new(Some(protocol.to_u8())),
+            kafka_codec: Default::default(),
+        };
+        let write_stream = Arc::new(Mutex::new(QuicFramedWriteStream::new(send, codec.clone())));
+        let read_stream = QuicFramedReadStream::new(recv, codec);
+
+        // Reading from the stream is not cancel safe, so a dedicated task owns it
+        let (packet_send, packet_recv) = mpsc::channel(MESSAGE_BUFFER_SIZE);
+        tokio::spawn(read_quic_packets(read_stream, packet_send));
+
+        let mut client = QuicClient {
+            endpoint,
+            connection,
+            protocol_version: protocol.to_u8(),
+            write_stream,
+            packet_recv,
+            pkid: 0,
+        };
+
+        let login = args.username.as_ref().map(|username| Login {
+            username: username.clone(),
+            password: args.password.clone().unwrap_or_default(),
+        });
+        let connect = Connect {
+            keep_alive: args.keep_alive_secs as u16,
+            client_id: args.client_id(index),
+            clean_session: true,
+        };
+        client
+            .send(MqttPacket::Connect(
+                client.protocol_version,
+                connect,
+                None,
+                None,
+                None,
+                login,
+            ))
+            .await?;
+
+        match client.packet_recv.recv().await {
+            Some(MqttPacket::ConnAck(conn_ack, _)) => {
+                if conn_ack.code != ConnectReturnCode::Success {
+                    return Err(BenchMarkError::ExecutionError(format!(
+                        "connect failed, code: {:?}",
+                        conn_ack.code
+                    )));
+                }
+            }
+            packet => {
+                return Err(BenchMarkError::ExecutionError(format!(
+                    "expected ConnAck, got {packet:?}"
+                )));
+            }
+        }
+
+        // Keep the session alive even when a subscriber receives nothing
+        let ping_stream = client.write_stream.clone();
+        let protocol_version = client.protocol_version;
+        let keep_alive = Duration::from_secs((args.keep_alive_secs / 2).max(1));
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(keep_alive).await;
+                let packet = RobustMQCodecWrapper::MQTT(MqttPacketWrapper {
+                    protocol_version,
+                    packet: MqttPacket::PingReq(PingReq),
+                });
+                if ping_stream.lock().await.send(packet).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(client)
+    }
+
+    async fn send(&self, packet: MqttPacket) -> Result<(), BenchMarkError> {
+        let wrapper = RobustMQCodecWrapper::MQTT(MqttPacketWrapper {
+            protocol_version: self.protocol_version,
+            packet,
+        });
+        self.write_stream
+            .lock()
+            .await
+            .send(wrapper)
+            .await
+            .map_err(|e| BenchMarkError::CommonError(Box::new(e)))
+    }
+
+    fn next_pkid(&mut self) -> u16 {
+        self.pkid = self.pkid.wrapping_add(1).max(1);
+        self.pkid
+    }
+
+    async fn publish(
+        &mut self,
+        topic: &str,
+        qos_num: i32,
+        payload: Vec<u8>,
+    ) -> Result<(), BenchMarkError> {
+        let qos = parse_qos(qos_num)?;
+        let pkid = if qos == QoS::AtMostOnce {
+            0
+        } else {
+            self.next_pkid()
+        };
+        let publish = Publish {
+            dup: false,
+            qos,
+            p_kid: pkid,
+            retain: false,
+            topic: topic.to_string().into(),
+            payload: payload.into(),
+        };
+        self.send(MqttPacket::Publish(publish, None)).await?;
+
+        if qos == QoS::AtMostOnce {
+            return Ok(());
+        }
+
+        loop {
+            let Some(packet) = self.packet_recv.recv().await else {
+                return Err(BenchMarkError::ExecutionError(
+                    "connection closed while waiting for publish ack".to_string(),
+                ));
+            };
+            match packet {
+                MqttPacket::PubAck(ack, _) if ack.pkid == pkid => return Ok(()),
+                MqttPacket::PubRec(rec, _) if rec.pkid == pkid => {
+                    self.send(MqttPacket::PubRel(PubRel { pkid, reason: None }, None))
+                        .await?;
+                }
+                MqttPacket::PubComp(comp, _) if comp.pkid == pkid => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+
+    async fn subscribe(&mut self, topic: &str, qos_num: i32) -> Result<(), BenchMarkError> {
+        let pkid = self.next_pkid();
+        let subscribe = Subscribe {
+            packet_identifier: pkid,
+            filters: vec![Filter {
+                path: topic.to_string(),
+                qos: parse_qos(qos_num)?,
+                ..Default::default()
+            }],
+        };
+        self.send(MqttPacket::Subscribe(subscribe, None)).await?;
+
+        loop {
+            match self.packet_recv.recv().await {
+                Some(MqttPacket::SubAck(ack, _)) if ack.pkid == pkid => return Ok(()),
+                Some(_) => {}
+                None => {
+                    return Err(BenchMarkError::ExecutionError(
+                        "connection closed while waiting for suback".to_string(),
+                    ))
+                }
+            }
+        }
+    }
+
+    async fn recv(&mut self) -> Option<Vec<u8>> {
+        loop {
+            match self.packet_recv.recv().await? {
+                MqttPacket::Publish(publish, _) => {
+                    let ack = match publish.qos {
+                        QoS::AtMostOnce => None,
+                        QoS::AtLeastOnce => Some(MqttPacket::PubAck(
+                            PubAck {
+                                pkid: publish.p_kid,
+                                reason: None,
+                            },
+                            None,
+                        )),
+                        QoS::ExactlyOnce => Some(MqttPacket::PubRec(
+                            PubRec {
+                                pkid: publish.p_kid,
+                                reason: None,
+                            },
+                            None,
+                        )),
+                    };
+                    if let Some(ack) = ack {
+                        self.send(ack).await.ok()?;
+                    }
+                    return Some(publish.payload.to_vec());
+                }
+                MqttPacket::PubRel(rel, _) => {
+                    let comp = PubComp {
+                        pkid: rel.pkid,
+                        reason: None,
+                    };
+                    self.send(MqttPacket::PubComp(comp, None)).await.ok()?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    async fn disconnect(self) {
+        let disconnect = Disconnect { reason_code: None };
+        let _ = self.send(MqttPacket::Disconnect(disconnect, None)).await;
+        self.connection
+            .close(VarInt::from_u32(0), b"bench completed");
+        self.endpoint.wait_idle().await;
+    }
+}
+
+async fn read_quic_packets(
+    mut read_stream: QuicFramedReadStream,
+    sender: mpsc::Sender<MqttPacket>,
+) {
+    while let Ok(Some(wrapper)) = read_stream.receive().await {
+        if let RobustMQCodecWrapper::MQTT(wrapper) = wrapper {
+            if sender.send(wrapper.packet).await.is_err() {
+                break;
+            }
+        }
+    }
+}
+
+fn parse_qos(qos_num: i32) -> Result<QoS, BenchMarkError> {
+    qos(qos_num as u8)
+        .ok_or_else(|| BenchMarkError::InvalidConfiguration(format!("invalid qos {qos_num}")))
+}
+
+fn build_quic_endpoint(args: &MqttConnectArgs) -> Result<Endpoint, BenchMarkError> {
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
+    let ca_file = args.ca_file.as_ref().ok_or_else(|| {
+        BenchMarkError::InvalidConfiguration("quic transport requires --ca-file".to_string())
+    })?;
+    let mut roots = rustls::RootCertStore::empty();
+    let mut cert_reader = BufReader::new(File::open(ca_file)?);
+    for cert in rustls_pemfile::certs(&mut cert_reader) {
+        roots
+            .add(cert?)
+            .map_err(|e| BenchMarkError::InvalidConfiguration(e.to_string()))?;
+    }
+
+    let client_crypto = rustls::ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let quic_client_config = quinn::crypto::rustls::QuicClientConfig::try_from(client_crypto)
+        .map_err(|e| BenchMarkError::InvalidConfiguration(e.to_string()))?;
+
+    let mut endpoint = Endpoint::client(SocketAddr::from(([0, 0, 0, 0], 0)))?;
+    endpoint.set_default_client_config(ClientConfig::new(Arc::new(quic_client_config)));
+    Ok(endpoint)
+}
@@ -0,0 +1,383 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use clap::{Args, ValueEnum};
+use prettytable::{row, Table};
+use serde::Serialize;
+
+use crate::BenchMarkError;
+
+// Every bench payload starts with the send time in microseconds since the epoch
+pub const PAYLOAD_TIMESTAMP_LEN: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MqttTransport {
+    Tcp,
+    Tls,
+    Ws,
+    Wss,
+    Quic,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BenchOutput {
+    Table,
+    Json,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct MqttConnectArgs {
+    /// The host of the MQTT broker
+    #[clap(long, default_value = "127.0.0.1")]
+    pub host: String,
+
+    /// The port of the MQTT broker, defaults to the port of the selected transport
+    #[clap(long)]
+    pub port: Option<u16>,
+
+    /// The transport used to connect to the broker
+    #[clap(long, value_enum, default_value = "tcp")]
+    pub transport: MqttTransport,
+
+    /// MQTT protocol version: 3 (3.1), 4 (3.1.1) or 5
+    #[clap(long, default_value = "5")]
+    pub mqtt_version: u8,
+
+    /// The number of concurrent clients to simulate
+    #[clap(long, default_value = "100")]
+    pub num_clients: usize,
+
+    /// New connections per second, 0 connects all clients at once
+    #[clap(long, default_value = "0")]
+    pub connect_rate: u64,
+
+    /// Prefix of the client ids, the client index is appended
+    #[clap(long, default_value = "robust-bench")]
+    pub client_id_prefix: String,
+
+    #[clap(long, default_value = "60")]
+    pub keep_alive_secs: u64,
+
+    #[clap(long)]
+    pub username: Option<String>,
+
+    #[clap(long)]
+    pub password: Option<String>,
+
+    /// CA certificate used to verify the broker for tls, wss and quic
+    #[clap(long)]
+    pub ca_file: Option<String>,
+
+    /// Server name checked against the broker certificate for quic
+    #[clap(long, default_value = "localhost")]
+    pub server_name: String,
+
+    /// The number of worker threads to run the benchmark
+    #[clap(long, default_value = "4")]
+    pub worker_threads: usize,
+
+    /// Print the result as a table or as JSON
+    #[clap(long, value_enum, default_value = "table")]
+    pub output: BenchOutput,
+}
+
+impl MqttConnectArgs {
+    pub fn validate(&self) -> Result<(), BenchMarkError> {
+        if ![3, 4, 5].contains(&self.mqtt_version) {
+            return Err(BenchMarkError::InvalidConfiguration(format!(
+                "mqtt version must be 3, 4 or 5, got {}",
+                self.mqtt_version
+            )));
+        }
+        if self.num_clients == 0 {
+            return Err(BenchMarkError::InvalidConfiguration(
+                "num clients must be greater than 0".to_string(),
+            ));
+        }
+        if self.transport == MqttTransport::Quic && self.ca_file.is_none() {
+            return Err(BenchMarkError::InvalidConfiguration(
+                "quic transport requires --ca-file".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn port(&self) -> u16 {
+        if let Some(port) = self.port {
+            return port;
+        }
+        match self.transport {
+            MqttTransport::Tcp => 1883,
+            MqttTransport::Tls => 1884,
+            MqttTransport::Ws => 8083,
+            MqttTransport::Wss => 8084,
+            MqttTransport::Quic => 9083,
+        }
+    }
+
+    pub fn client_id(&self, index: usize) -> String {
+        format!("{}-{}", self.client_id_prefix, index)
+    }
+
+    // Delay before starting the client at `index` so that clients connect at `connect_rate`
+    pub fn connect_delay(&self, index: usize) -> Duration {
+        if self.connect_rate == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_micros(index as u64 * 1_000_000 / self.connect_rate)
+    }
+}
+
+// Expand the topic pattern for one client, `%i` is the client index and `%c` the client id
+pub fn format_topic(pattern: &str, index: usize, client_id: &str) -> String {
+    pattern
+        .replace("%i", &index.to_string())
+        .replace("%c", client_id)
+}
+
+pub fn now_micros() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_micros() as u64)
+        .unwrap_or(0)
+}
+
+pub fn build_payload(payload_size: usize) -> Vec<u8> {
+    let mut payload = vec![b'x'; payload_size.max(PAYLOAD_TIMESTAMP_LEN)];
+    payload[..PAYLOAD_TIMESTAMP_LEN].copy_from_slice(&now_micros().to_be_bytes());
+    payload
+}
+
+pub fn payload_timestamp(payload: &[u8]) -> Option<u64> {
+    let bytes: [u8; PAYLOAD_TIMESTAMP_LEN] =
+        payload.get(..PAYLOAD_TIMESTAMP_LEN)?.try_into().ok()?;
+    Some(u64::from_be_bytes(bytes))
+}
+
+#[derive(Default)]
+pub struct BenchStats {
+    pub connected: AtomicU64,
+    pub messages: AtomicU64,
+    pub bytes: AtomicU64,
+    pub errors: AtomicU64,
+    latencies: Mutex<Vec<u64>>,
+}
+
+impl BenchStats {
+    pub fn new() -> Arc<Self> {
+        Arc::new(BenchStats::default())
+    }
+
+    pub fn record_connected(&self) {
+        self.connected.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_error(&self) {
+        self.errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_message(&self, bytes: usize, latency_us: Option<u64>) {
+        self.messages.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes as u64, Ordering::Relaxed);
+        if let Some(latency) = latency_us {
+            self.record_latency(latency);
+        }
+    }
+
+    pub fn record_latency(&self, latency_us: u64) {
+        if let Ok(mut latencies) = self.latencies.lock() {
+            latencies.push(latency_us);
+        }
+    }
+
+    pub fn report(&self, action: &str, num_clients: usize, elapsed: Duration) -> BenchReport {
+        let latencies = self
+            .latencies
+            .lock()
+            .map(|latencies| latencies.clone())
+            .unwrap_or_default();
+        let elapsed_secs = elapsed.as_secs_f64();
+        let messages = self.messages.load(Ordering::Relaxed);
+        let bytes = self.bytes.load(Ordering::Relaxed);
+        BenchReport {
+            action: action.to_string(),
+            num_clients,
+            connected: self.connected.load(Ordering::Relaxed),
+            messages,
+            errors: self.errors.load(Ordering::Relaxed),
+            elapsed_secs,
+            msgs_per_sec: rate(messages, elapsed_secs),
+            bytes_per_sec: rate(bytes, elapsed_secs),
+            latency_us: LatencySummary::from_latencies(latencies),
+        }
+    }
+}
+
+fn rate(num: u64, elapsed_secs: f64) -> f64 {
+    if elapsed_secs <= 0.0 {
+        return 0.0;
+    }
+    num as f64 / elapsed_secs
+}
+
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct LatencySummary {
+    pub count: usize,
+    pub min: u64,
+    pub avg: u64,
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+    pub p999: u64,
+    pub max: u64,
+}
+
+impl LatencySummary {
+    pub fn from_latencies(mut latencies: Vec<u64>) -> Self {
+        if latencies.is_empty() {
+            return LatencySummary::default();
+        }
+        latencies.sort_unstable();
+        let len = latencies.len();
+        let percentile = |p: usize| latencies[((len * p) / 1000).min(len - 1)];
+        LatencySummary {
+            count: len,
+            min: latencies[0],
+            avg: latencies.iter().sum::<u64>() / len as u64,
+            p50: percentile(500),
+            p90: percentile(900),
+            p99: percentile(990),
+            p999: percentile(999),
+            max: latencies[len - 1],
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BenchReport {
+    pub action: String,
+    pub num_clients: usize,
+    pub connected: u64,
+    pub messages: u64,
+    pub errors: u64,
+    pub elapsed_secs: f64,
+    pub msgs_per_sec: f64,
+    pub bytes_per_sec: f64,
+    pub latency_us: LatencySummary,
+}
+
+impl BenchReport {
+    pub fn print(&self, output: BenchOutput) -> Result<(), BenchMarkError> {
+        match output {
+            BenchOutput::Json => {
+                println!("{}", serde_json::to_string_pretty(self)?);
+            }
+            BenchOutput::Table => {
+                let mut table = Table::new();
+                table.add_row(row![
+                    "Action",
+                    "Clients",
+                    "Connected",
+                    "Messages",
+                    "Errors",
+                    "Elapsed (s)",
+                    "Msg per second",
+                    "Bytes per second"
+                ]);
+                table.add_row(row![
+                    self.action,
+                    self.num_clients,
+                    self.connected,
+                    self.messages,
+                    self.errors,
+                    format!("{:.2}", self.elapsed_secs),
+                    format!("{:.2}", self.msgs_per_sec),
+                    format!("{:.2}", self.bytes_per_sec)
+                ]);
+                table.printstd();
+
+                let latency = &self.latency_us;
+                let mut table_latency = Table::new();
+                table_latency.add_row(row![
+                    "Samples",
+                    "Min Latency (us)",
+                    "Average Latency (us)",
+                    "P50 Latency (us)",
+                    "P90 Latency (us)",
+                    "P99 Latency (us)",
+                    "P999 Latency (us)",
+                    "Max Latency (us)"
+                ]);
+                table_latency.add_row(row![
+                    latency.count,
+                    latency.min,
+                    latency.avg,
+                    latency.p50,
+                    latency.p90,
+                    latency.p99,
+                    latency.p999,
+                    latency.max
+                ]);
+                table_latency.printstd();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_payload, format_topic, payload_timestamp, LatencySummary};
+
+    #[test]
+    fn payload_timestamp_test() {
+        let payload = build_payload(2);
+        assert_eq!(payload.len(), 8);
+        assert!(payload_timestamp(&payload).unwrap() > 0);
+
+        let payload = build_payload(64);
+        assert_eq!(payload.len(), 64);
+        assert!(payload_timestamp(&payload[..4]).is_none());
+    }
+
+    #[test]
+    fn format_topic_test() {
+        assert_eq!(format_topic("bench/%i/%c", 3, "c-3"), "bench/3/c-3");
+        assert_eq!(format_topic("bench/fixed", 3, "c-3"), "bench/fixed");
+    }
+
+    #[test]
+    fn latency_summary_test() {
+        assert_eq!(
+            LatencySummary::from_latencies(Vec::new()),
+            LatencySummary::default()
+        );
+
+        let summary = LatencySummary::from_latencies((1..=1000).rev().collect());
+        assert_eq!(summary.count, 1000);
+        assert_eq!(summary.min, 1);
+        assert_eq!(summary.max, 1000);
+        assert_eq!(summary.p50, 501);
+        assert_eq!(summary.p99, 991);
+        assert_eq!(summary.p999, 1000);
+    }
+}
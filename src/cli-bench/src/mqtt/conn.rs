@@ -0,0 +1,94 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::{sync::Arc, time::Duration};
+
+use clap::Parser;
+use futures::future;
+use tokio::{sync::Barrier, time::Instant};
+
+use crate::{
+    error::BenchMarkError,
+    mqtt::{
+        client::BenchClient,
+        common::{BenchStats, MqttConnectArgs},
+    },
+    BenchMark,
+};
+
+#[derive(Debug, Clone, Parser)]
+pub struct MqttConnBenchArgs {
+    #[command(flatten)]
+    pub connect: MqttConnectArgs,
+
+    /// How long to keep the connections open once all clients are connected, in seconds
+    #[clap(long, default_value = "10")]
+    pub hold_secs: u64,
+}
+
+#[axum::async_trait]
+impl BenchMark for MqttConnBenchArgs {
+    fn validate(&self) -> Result<(), BenchMarkError> {
+        self.connect.validate()
+    }
+
+    async fn do_bench(&self) -> Result<(), BenchMarkError> {
+        self.validate()?;
+
+        let num_clients = self.connect.num_clients;
+        println!(
+            "Starting MQTT Connect Benchmark with {} clients over {:?}, connect rate: {}/s, hold: {}s",
+            num_clients, self.connect.transport, self.connect.connect_rate, self.hold_secs
+        );
+
+        let stats = BenchStats::new();
+        let barrier = Arc::new(Barrier::new(num_clients + 1));
+        let mut handles = Vec::with_capacity(num_clients);
+        let total_now = Instant::now();
+
+        for index in 0..num_clients {
+            let args = self.clone();
+            let stats = stats.clone();
+            let barrier = barrier.clone();
+            handles.push(tokio::spawn(async move {
+                tokio::time::sleep(args.connect.connect_delay(index)).await;
+                let now = Instant::now();
+                let client = BenchClient::connect(&args.connect, index, false).await;
+                match &client {
+                    // Each successful connection counts as one message, so the rate is connections per second
+                    Ok(_) => {
+                        stats.record_connected();
+                        stats.record_message(0, Some(now.elapsed().as_micros() as u64));
+                    }
+                    Err(e) => {
+                        stats.record_error();
+                        eprintln!("client {index} failed to connect: {e}");
+                    }
+                }
+                barrier.wait().await;
+
+                if let Ok(client) = client {
+                    tokio::time::sleep(Duration::from_secs(args.hold_secs)).await;
+                    client.disconnect().await;
+                }
+            }));
+        }
+
+        barrier.wait().await;
+        let report = stats.report("conn", num_clients, total_now.elapsed());
+        future::join_all(handles).await;
+
+        report.print(self.connect.output)
+    }
+}
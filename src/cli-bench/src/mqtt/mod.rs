@@ -12,10 +12,54 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod client;
+pub mod common;
+pub mod conn;
 pub mod publish;
 pub mod subscribe;
 
-use clap::Parser;
+use crate::{error::BenchMarkError, BenchMark};
+use clap::{Parser, Subcommand};
+use common_base::runtime::create_runtime;
+use conn::MqttConnBenchArgs;
+use publish::MqttPubBenchArgs;
+use subscribe::MqttSubBenchArgs;
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum MqttBenchAction {
+    Pub(MqttPubBenchArgs),
+    Sub(MqttSubBenchArgs),
+    Conn(MqttConnBenchArgs),
+}
 
 #[derive(Debug, Parser)]
-pub struct MqttBenchArgs {}
+pub struct MqttBenchArgs {
+    #[command(subcommand)]
+    pub action: MqttBenchAction,
+}
+
+pub fn handle_mqtt_bench(args: MqttBenchArgs) -> Result<(), BenchMarkError> {
+    let (num_threads, thread_name) = match args.action {
+        MqttBenchAction::Pub(ref pub_args) => (pub_args.connect.worker_threads, "bench-mqtt-pub"),
+        MqttBenchAction::Sub(ref sub_args) => (sub_args.connect.worker_threads, "bench-mqtt-sub"),
+        MqttBenchAction::Conn(ref conn_args) => {
+            (conn_args.connect.worker_threads, "bench-mqtt-conn")
+        }
+    };
+
+    let rt = create_runtime(thread_name, num_threads);
+
+    match args.action {
+        MqttBenchAction::Pub(pub_args) => {
+            rt.block_on(pub_args.do_bench())?;
+        }
+        MqttBenchAction::Sub(sub_args) => {
+            rt.block_on(sub_args.do_bench())?;
+        }
+        MqttBenchAction::Conn(conn_args) => {
+            rt.block_on(conn_args.do_bench())?;
+        }
+    }
+
+    Ok(())
+}
@@ -11,3 +11,117 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+
+use std::{sync::Arc, time::Duration};
+
+use clap::Parser;
+use futures::future;
+use tokio::{sync::Barrier, time::Instant};
+
+use crate::{
+    error::BenchMarkError,
+    mqtt::{
+        client::BenchClient,
+        common::{build_payload, format_topic, BenchStats, MqttConnectArgs},
+    },
+    BenchMark,
+};
+
+#[derive(Debug, Clone, Parser)]
+pub struct MqttPubBenchArgs {
+    #[command(flatten)]
+    pub connect: MqttConnectArgs,
+
+    /// Topic pattern, `%i` is replaced by the client index and `%c` by the client id
+    #[clap(long, default_value = "robust-bench/%i")]
+    pub topic: String,
+
+    #[clap(long, default_value = "0")]
+    pub qos: i32,
+
+    /// The size of each payload in bytes, the first 8 bytes carry the send timestamp
+    #[clap(long, default_value = "64")]
+    pub payload_size: usize,
+
+    /// The number of messages published by each client
+    #[clap(long, default_value = "1000")]
+    pub num_messages: u64,
+
+    /// Interval between two messages of one client in milliseconds, 0 publishes without pause
+    #[clap(long, default_value = "0")]
+    pub interval_ms: u64,
+}
+
+#[axum::async_trait]
+impl BenchMark for MqttPubBenchArgs {
+    fn validate(&self) -> Result<(), BenchMarkError> {
+        self.connect.validate()?;
+        if !(0..=2).contains(&self.qos) {
+            return Err(BenchMarkError::InvalidConfiguration(format!(
+                "qos must be 0, 1 or 2, got {}",
+                self.qos
+            )));
+        }
+        Ok(())
+    }
+
+    async fn do_bench(&self) -> Result<(), BenchMarkError> {
+        self.validate()?;
+
+        let num_clients = self.connect.num_clients;
+        println!(
+            "Starting MQTT Publish Benchmark with {} clients over {:?}, topic: {}, qos: {}, payload size: {}, {} messages per client",
+            num_clients, self.connect.transport, self.topic, self.qos, self.payload_size, self.num_messages
+        );
+
+        let stats = BenchStats::new();
+        // Publishing starts once every client has tried to connect
+        let barrier = Arc::new(Barrier::new(num_clients + 1));
+        let mut handles = Vec::with_capacity(num_clients);
+
+        for index in 0..num_clients {
+            let args = self.clone();
+            let stats = stats.clone();
+            let barrier = barrier.clone();
+            handles.push(tokio::spawn(async move {
+                tokio::time::sleep(args.connect.connect_delay(index)).await;
+                let client = BenchClient::connect(&args.connect, index, false).await;
+                barrier.wait().await;
+
+                let mut client = match client {
+                    Ok(client) => client,
+                    Err(e) => {
+                        stats.record_error();
+                        eprintln!("client {index} failed to connect: {e}");
+                        return;
+                    }
+                };
+                stats.record_connected();
+
+                let topic = format_topic(&args.topic, index, &args.connect.client_id(index));
+                for _ in 0..args.num_messages {
+                    let payload = build_payload(args.payload_size);
+                    let payload_len = payload.len();
+                    let now = Instant::now();
+                    match client.publish(&topic, args.qos, payload).await {
+                        Ok(()) => stats
+                            .record_message(payload_len, Some(now.elapsed().as_micros() as u64)),
+                        Err(_) => stats.record_error(),
+                    }
+                    if args.interval_ms > 0 {
+                        tokio::time::sleep(Duration::from_millis(args.interval_ms)).await;
+                    }
+                }
+                client.disconnect().await;
+            }));
+        }
+
+        barrier.wait().await;
+        let total_now = Instant::now();
+        future::join_all(handles).await;
+
+        stats
+            .report("pub", num_clients, total_now.elapsed())
+            .print(self.connect.output)
+    }
+}
@@ -11,3 +11,127 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+
+use std::{sync::Arc, time::Duration};
+
+use clap::Parser;
+use futures::future;
+use tokio::{sync::Barrier, time::Instant};
+
+use crate::{
+    error::BenchMarkError,
+    mqtt::{
+        client::BenchClient,
+        common::{format_topic, now_micros, payload_timestamp, BenchStats, MqttConnectArgs},
+    },
+    BenchMark,
+};
+
+#[derive(Debug, Clone, Parser)]
+pub struct MqttSubBenchArgs {
+    #[command(flatten)]
+    pub connect: MqttConnectArgs,
+
+    /// Topic filter pattern, `%i` is replaced by the client index and `%c` by the client id
+    #[clap(long, default_value = "robust-bench/#")]
+    pub topic: String,
+
+    #[clap(long, default_value = "0")]
+    pub qos: i32,
+
+    /// Subscribe as members of this shared subscription group
+    #[clap(long)]
+    pub share_group: Option<String>,
+
+    /// How long to receive messages, in seconds
+    #[clap(long, default_value = "60")]
+    pub duration_secs: u64,
+}
+
+impl MqttSubBenchArgs {
+    fn topic_filter(&self, index: usize) -> String {
+        let topic = format_topic(&self.topic, index, &self.connect.client_id(index));
+        match &self.share_group {
+            Some(group) => format!("$share/{group}/{topic}"),
+            None => topic,
+        }
+    }
+}
+
+#[axum::async_trait]
+impl BenchMark for MqttSubBenchArgs {
+    fn validate(&self) -> Result<(), BenchMarkError> {
+        self.connect.validate()?;
+        if !(0..=2).contains(&self.qos) {
+            return Err(BenchMarkError::InvalidConfiguration(format!(
+                "qos must be 0, 1 or 2, got {}",
+                self.qos
+            )));
+        }
+        if self.share_group.is_some() && self.connect.mqtt_version != 5 {
+            return Err(BenchMarkError::InvalidConfiguration(
+                "shared subscriptions require mqtt version 5".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn do_bench(&self) -> Result<(), BenchMarkError> {
+        self.validate()?;
+
+        let num_clients = self.connect.num_clients;
+        println!(
+            "Starting MQTT Subscribe Benchmark with {} clients over {:?}, topic: {}, qos: {}, share group: {:?}, duration: {}s",
+            num_clients, self.connect.transport, self.topic, self.qos, self.share_group, self.duration_secs
+        );
+
+        let stats = BenchStats::new();
+        // Receiving is timed from the moment every client has subscribed
+        let barrier = Arc::new(Barrier::new(num_clients + 1));
+        let mut handles = Vec::with_capacity(num_clients);
+
+        for index in 0..num_clients {
+            let args = self.clone();
+            let stats = stats.clone();
+            let barrier = barrier.clone();
+            handles.push(tokio::spawn(async move {
+                tokio::time::sleep(args.connect.connect_delay(index)).await;
+                let client = match BenchClient::connect(&args.connect, index, true).await {
+                    Ok(mut client) => client
+                        .subscribe(&args.topic_filter(index), args.qos)
+                        .await
+                        .map(|_| client),
+                    Err(e) => Err(e),
+                };
+                barrier.wait().await;
+
+                let mut client = match client {
+                    Ok(client) => client,
+                    Err(e) => {
+                        stats.record_error();
+                        eprintln!("client {index} failed to subscribe: {e}");
+                        return;
+                    }
+                };
+                stats.record_connected();
+
+                let deadline = Instant::now() + Duration::from_secs(args.duration_secs);
+                while let Ok(Some(payload)) = tokio::time::timeout_at(deadline, client.recv()).await
+                {
+                    let latency = payload_timestamp(&payload)
+                        .map(|send_time| now_micros().saturating_sub(send_time));
+                    stats.record_message(payload.len(), latency);
+                }
+                client.disconnect().await;
+            }));
+        }
+
+        barrier.wait().await;
+        let total_now = Instant::now();
+        future::join_all(handles).await;
+
+        stats
+            .report("sub", num_clients, total_now.elapsed())
+            .print(self.connect.output)
+    }
+}
@@ -13,8 +13,26 @@
 // limitations under the License.
 
 use crate::mqtt::pub_sub::error_info;
-use admin_server::{client::AdminHttpClient, request::cluster::ClusterConfigSetReq};
+use admin_server::{
+    client::AdminHttpClient,
+    request::{
+        cluster::{
+            ClusterConfigSetReq, ClusterLoadReq, ClusterRebalanceReq, DrainStartReq,
+            DrainStatusReq, LogLevelListReq, LogLevelResetReq, LogLevelSetReq,
+        },
+        meta::{
+            MetaAddLearnerReq, MetaBackupReq, MetaPromoteReq, MetaRemoveNodeReq, MetaRestoreReq,
+            MetaStatusReq, MetaTransferLeaderReq,
+        },
+    },
+    response::{
+        cluster::{ClusterLoadResp, ClusterRebalanceResp, DrainStatusResp, LogLevelListResp},
+        meta::{MetaRestoreResp, MetaStatusResp},
+    },
+};
 use common_config::config::BrokerConfig;
+use prettytable::{row, Table};
+use std::time::Duration;
 
 #[derive(Clone)]
 pub struct ClusterCliCommandParam {
@@ -26,8 +44,26 @@ pub struct ClusterCliCommandParam {
 pub enum ClusterActionType {
     GetConfig,
     SetConfig(ClusterConfigSetReq),
+    MetaStatus,
+    MetaAddLearner(MetaAddLearnerReq),
+    MetaPromote(MetaPromoteReq),
+    MetaRemove(MetaRemoveNodeReq),
+    MetaTransferLeader(MetaTransferLeaderReq),
+    // request, archive file
+    MetaBackup(MetaBackupReq, String),
+    MetaRestore(MetaRestoreReq, String),
+    DrainStart,
+    DrainStatus,
+    Load,
+    Rebalance(ClusterRebalanceReq),
+    LogLevelList,
+    LogLevelSet(LogLevelSetReq),
+    LogLevelReset(LogLevelResetReq),
 }
 
+// backup and restore move the whole metadata, the default 30s is not enough
+const META_ARCHIVE_TIMEOUT_SEC: u64 = 600;
+
 pub struct ClusterCommand {}
 
 impl Default for ClusterCommand {
@@ -48,6 +84,48 @@ pub async fn start(&self, params: ClusterCliCommandParam) {
             ClusterActionType::SetConfig(request) => {
                 self.set_cluster_config(params, request.clone()).await;
             }
+            ClusterActionType::MetaStatus => {
+                self.meta_status(params).await;
+            }
+            ClusterActionType::MetaAddLearner(request) => {
+                self.meta_add_learner(params, request).await;
+            }
+            ClusterActionType::MetaPromote(request) => {
+                self.meta_promote(params, request).await;
+            }
+            ClusterActionType::MetaRemove(request) => {
+                self.meta_remove(params, request).await;
+            }
+            ClusterActionType::MetaTransferLeader(request) => {
+                self.meta_transfer_leader(params, request).await;
+            }
+            ClusterActionType::MetaBackup(request, file) => {
+                self.meta_backup(params, request, file).await;
+            }
+            ClusterActionType::MetaRestore(request, file) => {
+                self.meta_restore(params, request, file).await;
+            }
+            ClusterActionType::DrainStart => {
+                self.drain_start(params).await;
+            }
+            ClusterActionType::DrainStatus => {
+                self.drain_status(params).await;
+            }
+            ClusterActionType::Load => {
+                self.cluster_load(params).await;
+            }
+            ClusterActionType::Rebalance(request) => {
+                self.rebalance(params, request).await;
+            }
+            ClusterActionType::LogLevelList => {
+                self.log_level_list(params).await;
+            }
+            ClusterActionType::LogLevelSet(request) => {
+                self.log_level_set(params, request).await;
+            }
+            ClusterActionType::LogLevelReset(request) => {
+                self.log_level_reset(params, request).await;
+            }
         }
     }
 
@@ -104,4 +182,343 @@ async fn get_cluster_config(&self, params: ClusterCliCommandParam) {
             }
         }
     }
+
+    // -------------- broker drain --------------
+    async fn drain_start(&self, params: ClusterCliCommandParam) {
+        let admin_client = AdminHttpClient::new(format!("http://{}", params.server));
+
+        match admin_client.drain_broker(&DrainStartReq::default()).await {
+            Ok(_) => {
+                println!("Broker drain started, it will stop once all clients and connectors have moved away!");
+            }
+            Err(e) => {
+                println!("MQTT broker start drain exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn drain_status(&self, params: ClusterCliCommandParam) {
+        let admin_client = AdminHttpClient::new(format!("http://{}", params.server));
+
+        match admin_client
+            .get_drain_status::<DrainStatusReq, DrainStatusResp>(&DrainStatusReq::default())
+            .await
+        {
+            Ok(status) => {
+                let mut table = Table::new();
+                table.set_titles(row![
+                    "broker_id",
+                    "draining",
+                    "connection_num",
+                    "connector_num"
+                ]);
+                table.add_row(row![
+                    status.broker_id,
+                    status.draining,
+                    status.connection_num,
+                    status.connector_num
+                ]);
+                table.printstd();
+            }
+            Err(e) => {
+                println!("MQTT broker get drain status exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    // -------------- connection balance --------------
+    async fn cluster_load(&self, params: ClusterCliCommandParam) {
+        let admin_client = AdminHttpClient::new(format!("http://{}", params.server));
+
+        match admin_client
+            .get_cluster_load::<ClusterLoadReq, ClusterLoadResp>(&ClusterLoadReq::default())
+            .await
+        {
+            Ok(load) => {
+                if load.rebalancing {
+                    println!("broker {} is rebalancing connections", load.broker_id);
+                }
+                let mut table = Table::new();
+                table.set_titles(row!["node_id", "connection_num", "draining"]);
+                for node in load.nodes {
+                    table.add_row(row![node.node_id, node.connection_num, node.draining]);
+                }
+                table.printstd();
+            }
+            Err(e) => {
+                println!("MQTT broker get cluster load exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn rebalance(&self, params: ClusterCliCommandParam, request: ClusterRebalanceReq) {
+        let admin_client = AdminHttpClient::new(format!("http://{}", params.server));
+
+        match admin_client
+            .rebalance_connections::<ClusterRebalanceReq, ClusterRebalanceResp>(&request)
+            .await
+        {
+            Ok(reply) => {
+                println!(
+                    "Rebalance started, {} clients will be moved to less loaded brokers!",
+                    reply.moving_num
+                );
+            }
+            Err(e) => {
+                println!("MQTT broker rebalance exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    // -------------- log level --------------
+    async fn log_level_list(&self, params: ClusterCliCommandParam) {
+        let admin_client = AdminHttpClient::new(format!("http://{}", params.server));
+
+        match admin_client
+            .get_log_levels::<LogLevelListReq, LogLevelListResp>(&LogLevelListReq::default())
+            .await
+        {
+            Ok(reply) => {
+                let mut table = Table::new();
+                table.set_titles(row!["appender", "filter"]);
+                for appender in reply.appenders {
+                    table.add_row(row![appender.appender, appender.filter]);
+                }
+                table.printstd();
+
+                let mut overrides = Table::new();
+                overrides.set_titles(row!["target", "level", "appender", "expire_time"]);
+                for raw in reply.overrides {
+                    overrides.add_row(row![
+                        raw.target,
+                        raw.level,
+                        raw.appender.unwrap_or_else(|| "-".to_string()),
+                        raw.expire_time
+                            .map(|time| time.to_string())
+                            .unwrap_or_else(|| "-".to_string())
+                    ]);
+                }
+                overrides.printstd();
+            }
+            Err(e) => {
+                println!("MQTT broker list log levels exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn log_level_set(&self, params: ClusterCliCommandParam, request: LogLevelSetReq) {
+        let admin_client = AdminHttpClient::new(format!("http://{}", params.server));
+
+        match admin_client.set_log_level(&request).await {
+            Ok(_) => match request.ttl_sec {
+                Some(ttl_sec) => println!(
+                    "Log level of {} set to {}, it will be reverted in {}s!",
+                    request.target, request.level, ttl_sec
+                ),
+                None => println!("Log level of {} set to {}!", request.target, request.level),
+            },
+            Err(e) => {
+                println!("MQTT broker set log level exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn log_level_reset(&self, params: ClusterCliCommandParam, request: LogLevelResetReq) {
+        let admin_client = AdminHttpClient::new(format!("http://{}", params.server));
+
+        match admin_client.reset_log_level(&request).await {
+            Ok(_) => {
+                println!("Log level of {} reverted!", request.target);
+            }
+            Err(e) => {
+                println!("MQTT broker reset log level exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    // -------------- meta service membership --------------
+    async fn meta_status(&self, params: ClusterCliCommandParam) {
+        let admin_client = AdminHttpClient::new(format!("http://{}", params.server));
+
+        match admin_client
+            .get_meta_status::<MetaStatusReq, MetaStatusResp>(&MetaStatusReq::default())
+            .await
+        {
+            Ok(status) => {
+                println!("meta service raft status:");
+                let mut table = Table::new();
+                table.set_titles(row![
+                    "leader_id",
+                    "leader_addr",
+                    "term",
+                    "last_log_index",
+                    "commit_index",
+                    "last_applied"
+                ]);
+                table.add_row(row![
+                    status.leader_id,
+                    status.leader_addr.as_str(),
+                    status.current_term,
+                    status.last_log_index,
+                    status.commit_index,
+                    status.last_applied
+                ]);
+                table.printstd();
+
+                let mut members = Table::new();
+                members.set_titles(row!["node_id", "rpc_addr", "role", "matched_index", "lag"]);
+                for member in status.members {
+                    members.add_row(row![
+                        member.node_id,
+                        member.rpc_addr.as_str(),
+                        member.role.as_str(),
+                        member.matched_index,
+                        member.lag
+                    ]);
+                }
+                members.printstd();
+            }
+            Err(e) => {
+                println!("Meta service get raft status exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn meta_add_learner(&self, params: ClusterCliCommandParam, request: MetaAddLearnerReq) {
+        let admin_client = AdminHttpClient::new(format!("http://{}", params.server));
+
+        match admin_client.add_meta_learner(&request).await {
+            Ok(_) => {
+                if request.promote {
+                    println!("Meta node {} added and promoted to voter!", request.node_id);
+                } else {
+                    println!("Meta node {} added as learner!", request.node_id);
+                }
+            }
+            Err(e) => {
+                println!("Meta service add learner exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn meta_promote(&self, params: ClusterCliCommandParam, request: MetaPromoteReq) {
+        let admin_client = AdminHttpClient::new(format!("http://{}", params.server));
+
+        match admin_client.promote_meta_node(&request).await {
+            Ok(_) => {
+                println!("Meta node {} promoted to voter!", request.node_id);
+            }
+            Err(e) => {
+                println!("Meta service promote node exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn meta_remove(&self, params: ClusterCliCommandParam, request: MetaRemoveNodeReq) {
+        let admin_client = AdminHttpClient::new(format!("http://{}", params.server));
+
+        match admin_client.remove_meta_node(&request).await {
+            Ok(_) => {
+                println!("Meta node {} removed from the cluster!", request.node_id);
+            }
+            Err(e) => {
+                println!("Meta service remove node exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn meta_transfer_leader(
+        &self,
+        params: ClusterCliCommandParam,
+        request: MetaTransferLeaderReq,
+    ) {
+        let admin_client = AdminHttpClient::new(format!("http://{}", params.server));
+
+        match admin_client.transfer_meta_leader(&request).await {
+            Ok(_) => {
+                println!(
+                    "Leadership transfer to meta node {} triggered!",
+                    request.node_id
+                );
+            }
+            Err(e) => {
+                println!("Meta service transfer leader exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn meta_backup(
+        &self,
+        params: ClusterCliCommandParam,
+        request: MetaBackupReq,
+        file: String,
+    ) {
+        let admin_client = AdminHttpClient::with_timeout(
+            format!("http://{}", params.server),
+            Duration::from_secs(META_ARCHIVE_TIMEOUT_SEC),
+        );
+
+        match admin_client.backup_meta(&request).await {
+            Ok(archive) => match tokio::fs::write(&file, archive).await {
+                Ok(_) => println!("Metadata archive written to {file}"),
+                Err(e) => {
+                    println!("Meta service write metadata archive exception");
+                    error_info(e.to_string());
+                }
+            },
+            Err(e) => {
+                println!("Meta service backup metadata exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn meta_restore(
+        &self,
+        params: ClusterCliCommandParam,
+        mut request: MetaRestoreReq,
+        file: String,
+    ) {
+        request.archive = match tokio::fs::read_to_string(&file).await {
+            Ok(archive) => archive,
+            Err(e) => {
+                println!("Meta service read metadata archive exception");
+                error_info(e.to_string());
+                return;
+            }
+        };
+
+        let admin_client = AdminHttpClient::with_timeout(
+            format!("http://{}", params.server),
+            Duration::from_secs(META_ARCHIVE_TIMEOUT_SEC),
+        );
+
+        match admin_client
+            .restore_meta::<MetaRestoreReq, MetaRestoreResp>(&request)
+            .await
+        {
+            Ok(reply) => {
+                println!(
+                    "Restored {} metadata records from {file}",
+                    reply.record_count
+                );
+            }
+            Err(e) => {
+                println!("Meta service restore metadata exception");
+                error_info(e.to_string());
+            }
+        }
+    }
 }
@@ -15,14 +15,22 @@
 use crate::cluster::command::{ClusterActionType, ClusterCliCommandParam, ClusterCommand};
 use crate::mqtt::command::{MqttBrokerCommand, MqttCliCommandParam};
 use crate::mqtt::params::{
-    process_acl_args, process_auto_subscribe_args, process_blacklist_args, process_connection_args,
-    process_connector_args, process_flapping_detect_args, process_publish_args,
-    process_schema_args, process_session_args, process_slow_sub_args, process_subscribe_args,
-    process_subscribes_args, process_system_alarm_args, process_topic_args,
-    process_topic_rewrite_args, process_user_args, AclArgs, AutoSubscribeRuleCommand,
-    BlacklistArgs, ClientsArgs, ClusterConfigActionType, ClusterConfigArgs, ConnectorArgs,
-    FlappingDetectArgs, PubSubArgs, SchemaArgs, SessionArgs, SlowSubscribeArgs, SubscribesArgs,
-    SystemAlarmArgs, TopicArgs, TopicRewriteArgs, UserArgs,
+    process_acl_args, process_audit_log_args, process_auto_subscribe_args, process_blacklist_args,
+    process_connection_args, process_connector_args, process_flapping_detect_args,
+    process_packet_trace_args, process_publish_args, process_schema_args, process_session_args,
+    process_slow_sub_args, process_subscribe_args, process_subscribes_args,
+    process_system_alarm_args, process_topic_args, process_topic_rewrite_args, process_user_args,
+    AclArgs, AuditLogArgs, AutoSubscribeRuleCommand, BlacklistArgs, ClientsArgs,
+    ClusterConfigActionType, ClusterConfigArgs, ClusterDrainActionType, ClusterDrainArgs,
+    ClusterLogLevelActionType, ClusterLogLevelArgs, ClusterMetaActionType, ClusterMetaArgs,
+    ClusterRebalanceArgs, ConnectorArgs, FlappingDetectArgs, PacketTraceArgs, PubSubArgs,
+    SchemaArgs, SessionArgs, SlowSubscribeArgs, SubscribesArgs, SystemAlarmArgs, TopicArgs,
+    TopicRewriteArgs, UserArgs,
+};
+use admin_server::request::cluster::{ClusterRebalanceReq, LogLevelResetReq, LogLevelSetReq};
+use admin_server::request::meta::{
+    MetaAddLearnerReq, MetaBackupReq, MetaPromoteReq, MetaRemoveNodeReq, MetaRestoreReq,
+    MetaTransferLeaderReq,
 };
 use clap::{arg, Parser, Subcommand};
 
@@ -86,6 +94,10 @@ pub enum MQTTAction {
     SlowSubscribe(SlowSubscribeArgs),
     // ---- system alarm ----
     SystemAlarm(SystemAlarmArgs),
+    // ---- audit log ----
+    AuditLog(AuditLogArgs),
+    // ---- packet trace ----
+    Trace(PacketTraceArgs),
 
     // list topic
     Topic(TopicArgs),
@@ -121,6 +133,12 @@ pub struct ClusterArgs {
 #[derive(Debug, Subcommand)]
 pub enum ClusterAction {
     Config(ClusterConfigArgs),
+    Meta(ClusterMetaArgs),
+    Drain(ClusterDrainArgs),
+    #[command(about = "show the connection load of every broker")]
+    Load,
+    Rebalance(ClusterRebalanceArgs),
+    LogLevel(ClusterLogLevelArgs),
 }
 
 #[derive(clap::Args, Debug)]
@@ -172,6 +190,10 @@ pub async fn handle_mqtt(args: MqttArgs, cmd: MqttBrokerCommand) {
             MQTTAction::FlappingDetect(args) => process_flapping_detect_args(args),
             // system alarm
             MQTTAction::SystemAlarm(args) => process_system_alarm_args(args),
+            // audit log
+            MQTTAction::AuditLog(args) => process_audit_log_args(args),
+            // packet trace
+            MQTTAction::Trace(args) => process_packet_trace_args(args),
             // Connections
             MQTTAction::Client(args) => process_connection_args(args),
             // connector
@@ -200,6 +222,70 @@ pub async fn handle_cluster(args: ClusterArgs, cmd: ClusterCommand) {
             ClusterAction::Config(config_args) => match config_args.action {
                 ClusterConfigActionType::Get => ClusterActionType::GetConfig,
             },
+            ClusterAction::Meta(meta_args) => match meta_args.action {
+                ClusterMetaActionType::Status => ClusterActionType::MetaStatus,
+                ClusterMetaActionType::AddLearner(arg) => {
+                    ClusterActionType::MetaAddLearner(MetaAddLearnerReq {
+                        node_id: arg.node_id,
+                        rpc_addr: arg.rpc_addr,
+                        promote: arg.promote,
+                    })
+                }
+                ClusterMetaActionType::Promote(arg) => {
+                    ClusterActionType::MetaPromote(MetaPromoteReq {
+                        node_id: arg.node_id,
+                    })
+                }
+                ClusterMetaActionType::Remove(arg) => {
+                    ClusterActionType::MetaRemove(MetaRemoveNodeReq {
+                        node_id: arg.node_id,
+                    })
+                }
+                ClusterMetaActionType::TransferLeader(arg) => {
+                    ClusterActionType::MetaTransferLeader(MetaTransferLeaderReq {
+                        node_id: arg.node_id,
+                    })
+                }
+                ClusterMetaActionType::Backup(arg) => ClusterActionType::MetaBackup(
+                    MetaBackupReq {
+                        resources: (!arg.resources.is_empty()).then_some(arg.resources),
+                    },
+                    arg.file,
+                ),
+                ClusterMetaActionType::Restore(arg) => ClusterActionType::MetaRestore(
+                    MetaRestoreReq {
+                        archive: String::new(),
+                        cluster_name: arg.cluster_name,
+                        resources: (!arg.resources.is_empty()).then_some(arg.resources),
+                    },
+                    arg.file,
+                ),
+            },
+            ClusterAction::Drain(drain_args) => match drain_args.action {
+                ClusterDrainActionType::Start => ClusterActionType::DrainStart,
+                ClusterDrainActionType::Status => ClusterActionType::DrainStatus,
+            },
+            ClusterAction::Load => ClusterActionType::Load,
+            ClusterAction::Rebalance(arg) => ClusterActionType::Rebalance(ClusterRebalanceReq {
+                percent: arg.percent,
+            }),
+            ClusterAction::LogLevel(log_level_args) => match log_level_args.action {
+                ClusterLogLevelActionType::List => ClusterActionType::LogLevelList,
+                ClusterLogLevelActionType::Set(arg) => {
+                    ClusterActionType::LogLevelSet(LogLevelSetReq {
+                        target: arg.target,
+                        level: arg.level,
+                        appender: arg.appender,
+                        ttl_sec: arg.ttl_sec,
+                    })
+                }
+                ClusterLogLevelActionType::Reset(arg) => {
+                    ClusterActionType::LogLevelReset(LogLevelResetReq {
+                        target: arg.target,
+                        appender: arg.appender,
+                    })
+                }
+            },
         },
     };
     cmd.start(params).await;
@@ -65,6 +65,23 @@ pub enum MqttActionType {
     // system alarm
     ListSystemAlarm,
 
+    // audit log
+    ListAuditLog,
+    ExportAuditLog(
+        admin_server::request::mqtt::AuditLogExportReq,
+        Option<String>,
+    ),
+
+    // packet trace
+    ListPacketTrace,
+    CreatePacketTrace(admin_server::request::mqtt::CreatePacketTraceReq),
+    StopPacketTrace(admin_server::request::mqtt::StopPacketTraceReq),
+    DeletePacketTrace(admin_server::request::mqtt::DeletePacketTraceReq),
+    DownloadPacketTrace(
+        admin_server::request::mqtt::DownloadPacketTraceReq,
+        Option<String>,
+    ),
+
     // topic rewrite rule
     ListTopicRewrite,
     CreateTopicRewrite(admin_server::request::mqtt::CreateTopicRewriteReq),
@@ -90,6 +107,7 @@ pub enum MqttActionType {
     // schema
     ListSchema,
     CreateSchema(admin_server::request::mqtt::CreateSchemaReq),
+    UpdateSchema(admin_server::request::mqtt::UpdateSchemaReq),
     DeleteSchema(admin_server::request::mqtt::DeleteSchemaReq),
     ListBindSchema,
     BindSchema(admin_server::request::mqtt::CreateSchemaBindReq),
@@ -175,6 +193,35 @@ pub async fn start(&self, params: MqttCliCommandParam) {
                 self.list_system_alarm(params_clone.clone()).await;
             }
 
+            // audit log
+            MqttActionType::ListAuditLog => {
+                self.list_audit_log(params_clone.clone()).await;
+            }
+            MqttActionType::ExportAuditLog(request, output) => {
+                self.export_audit_log(params_clone.clone(), request, output)
+                    .await;
+            }
+
+            // packet trace
+            MqttActionType::ListPacketTrace => {
+                self.list_packet_trace(params_clone.clone()).await;
+            }
+            MqttActionType::CreatePacketTrace(request) => {
+                self.create_packet_trace(params_clone.clone(), request)
+                    .await;
+            }
+            MqttActionType::StopPacketTrace(request) => {
+                self.stop_packet_trace(params_clone.clone(), request).await;
+            }
+            MqttActionType::DeletePacketTrace(request) => {
+                self.delete_packet_trace(params_clone.clone(), request)
+                    .await;
+            }
+            MqttActionType::DownloadPacketTrace(request, output) => {
+                self.download_packet_trace(params_clone.clone(), request, output)
+                    .await;
+            }
+
             // user
             MqttActionType::ListUser => {
                 self.list_user(params_clone.clone()).await;
@@ -231,6 +278,9 @@ pub async fn start(&self, params: MqttCliCommandParam) {
             MqttActionType::CreateSchema(request) => {
                 self.create_schema(params_clone.clone(), request).await;
             }
+            MqttActionType::UpdateSchema(request) => {
+                self.update_schema(params_clone.clone(), request).await;
+            }
             MqttActionType::DeleteSchema(request) => {
                 self.delete_schema(params_clone.clone(), request).await;
             }
@@ -909,6 +959,235 @@ async fn list_topic(&self, params: MqttCliCommandParam) {
     }
 
     // ---- system alarms ----
+    async fn list_audit_log(&self, params: MqttCliCommandParam) {
+        // Create admin HTTP client
+        let admin_client = AdminHttpClient::new(format!("http://{}", params.server));
+
+        // Create request for audit log list
+        let request = admin_server::request::mqtt::AuditLogListReq {
+            limit: Some(DEFAULT_PAGE_SIZE),
+            page: Some(DEFAULT_PAGE_NUM),
+            sort_field: None,
+            sort_by: None,
+            filter_field: None,
+            filter_values: None,
+            exact_match: None,
+        };
+
+        match admin_client
+            .get_audit_log_list::<admin_server::request::mqtt::AuditLogListReq, Vec<admin_server::response::mqtt::AuditLogListRow>>(
+                &request,
+            )
+            .await
+        {
+            Ok(page_data) => {
+                println!("audit log list result:");
+                let mut table = Table::new();
+                table.set_titles(row![
+                    "offset",
+                    "actor",
+                    "source_ip",
+                    "action",
+                    "target",
+                    "before",
+                    "after",
+                    "success",
+                    "reason",
+                    "create_time"
+                ]);
+                for log in page_data.data {
+                    table.add_row(row![
+                        log.offset,
+                        log.actor,
+                        log.source_ip,
+                        log.action,
+                        log.target,
+                        log.before.unwrap_or_default(),
+                        log.after.unwrap_or_default(),
+                        log.success,
+                        log.reason.unwrap_or_default(),
+                        log.create_time,
+                    ]);
+                }
+                // output cmd
+                table.printstd()
+            }
+            Err(e) => {
+                println!("MQTT broker list audit log exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn export_audit_log(
+        &self,
+        params: MqttCliCommandParam,
+        cli_request: admin_server::request::mqtt::AuditLogExportReq,
+        output: Option<String>,
+    ) {
+        // Create admin HTTP client
+        let admin_client = AdminHttpClient::new(format!("http://{}", params.server));
+
+        match admin_client.export_audit_log(&cli_request).await {
+            Ok(data) => match output {
+                Some(path) => match tokio::fs::write(&path, data).await {
+                    Ok(_) => println!("Audit log exported to {path}"),
+                    Err(e) => {
+                        println!("MQTT broker export audit log exception");
+                        error_info(e.to_string());
+                    }
+                },
+                None => println!("{data}"),
+            },
+            Err(e) => {
+                println!("MQTT broker export audit log exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    // ---- packet trace ----
+    async fn list_packet_trace(&self, params: MqttCliCommandParam) {
+        // Create admin HTTP client
+        let admin_client = AdminHttpClient::new(format!("http://{}", params.server));
+
+        let request = admin_server::request::mqtt::PacketTraceListReq {
+            limit: Some(DEFAULT_PAGE_SIZE),
+            page: Some(DEFAULT_PAGE_NUM),
+            sort_field: None,
+            sort_by: None,
+            filter_field: None,
+            filter_values: None,
+            exact_match: None,
+        };
+
+        match admin_client
+            .get_packet_trace_list::<admin_server::request::mqtt::PacketTraceListReq, Vec<admin_server::response::mqtt::PacketTraceListRow>>(
+                &request,
+            )
+            .await
+        {
+            Ok(page_data) => {
+                println!("packet trace list result:");
+                let mut table = Table::new();
+                table.set_titles(row![
+                    "name",
+                    "trace_type",
+                    "target",
+                    "output",
+                    "payload_encode",
+                    "payload_limit",
+                    "status",
+                    "start_time",
+                    "end_time"
+                ]);
+                for trace in page_data.data {
+                    table.add_row(row![
+                        trace.name,
+                        trace.trace_type,
+                        trace.target,
+                        trace.output,
+                        trace.payload_encode,
+                        trace.payload_limit,
+                        trace.status,
+                        trace.start_time,
+                        trace.end_time,
+                    ]);
+                }
+                // output cmd
+                table.printstd()
+            }
+            Err(e) => {
+                println!("MQTT broker list packet trace exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn create_packet_trace(
+        &self,
+        params: MqttCliCommandParam,
+        cli_request: admin_server::request::mqtt::CreatePacketTraceReq,
+    ) {
+        // Create admin HTTP client
+        let admin_client = AdminHttpClient::new(format!("http://{}", params.server));
+
+        match admin_client.create_packet_trace(&cli_request).await {
+            Ok(_) => {
+                println!("Created successfully!")
+            }
+            Err(e) => {
+                println!("MQTT broker create packet trace exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn stop_packet_trace(
+        &self,
+        params: MqttCliCommandParam,
+        cli_request: admin_server::request::mqtt::StopPacketTraceReq,
+    ) {
+        // Create admin HTTP client
+        let admin_client = AdminHttpClient::new(format!("http://{}", params.server));
+
+        match admin_client.stop_packet_trace(&cli_request).await {
+            Ok(_) => {
+                println!("Stopped successfully!")
+            }
+            Err(e) => {
+                println!("MQTT broker stop packet trace exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn delete_packet_trace(
+        &self,
+        params: MqttCliCommandParam,
+        cli_request: admin_server::request::mqtt::DeletePacketTraceReq,
+    ) {
+        // Create admin HTTP client
+        let admin_client = AdminHttpClient::new(format!("http://{}", params.server));
+
+        match admin_client.delete_packet_trace(&cli_request).await {
+            Ok(_) => {
+                println!("Deleted successfully!");
+            }
+            Err(e) => {
+                println!("MQTT broker delete packet trace exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn download_packet_trace(
+        &self,
+        params: MqttCliCommandParam,
+        cli_request: admin_server::request::mqtt::DownloadPacketTraceReq,
+        output: Option<String>,
+    ) {
+        // Create admin HTTP client
+        let admin_client = AdminHttpClient::new(format!("http://{}", params.server));
+
+        match admin_client.download_packet_trace(&cli_request).await {
+            Ok(data) => match output {
+                Some(path) => match tokio::fs::write(&path, data).await {
+                    Ok(_) => println!("Packet trace downloaded to {path}"),
+                    Err(e) => {
+                        println!("MQTT broker download packet trace exception");
+                        error_info(e.to_string());
+                    }
+                },
+                None => println!("{data}"),
+            },
+            Err(e) => {
+                println!("MQTT broker download packet trace exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
     async fn list_system_alarm(&self, params: MqttCliCommandParam) {
         // Create admin HTTP client
         let admin_client = AdminHttpClient::new(format!("http://{}", params.server));
@@ -933,11 +1212,12 @@ async fn list_system_alarm(&self, params: MqttCliCommandParam) {
             Ok(page_data) => {
                 println!("system alarm list result:");
                 let mut table = Table::new();
-                table.set_titles(row!["name", "message", "create_time"]);
+                table.set_titles(row!["name", "message", "activated", "create_time"]);
                 for alarm in page_data.data {
                     table.add_row(row![
                         alarm.name,
                         alarm.message,
+                        alarm.activated,
                         alarm.create_time,
                     ]);
                 }
@@ -1049,8 +1329,10 @@ async fn list_connectors(&self, params: MqttCliCommandParam) {
                     "connector type",
                     "connector config",
                     "topic id",
+                    "payload format",
                     "status",
                     "broker id",
+                    "lag",
                     "create time",
                     "update time",
                 ]);
@@ -1061,8 +1343,13 @@ async fn list_connectors(&self, params: MqttCliCommandParam) {
                         connector.connector_type,
                         connector.config,
                         connector.topic_name,
+                        connector.payload_format,
                         connector.status,
                         connector.broker_id,
+                        connector
+                            .lag
+                            .map(|lag| lag.to_string())
+                            .unwrap_or_else(|| "-".to_string()),
                         connector.create_time,
                         connector.update_time
                     ]);
@@ -1234,12 +1521,18 @@ async fn list_schema(&self, params: MqttCliCommandParam) {
                             "schema name: {}\n",
                             "schema type: {}\n",
                             "schema desc: {}\n",
-                            "schema: {}\n"
+                            "schema: {}\n",
+                            "message name: {}\n",
+                            "version: {}\n",
+                            "compatibility: {}\n"
                         ),
                         schema.name,
                         schema.schema_type,
                         schema.desc,
-                        schema.schema
+                        schema.schema,
+                        schema.message_name,
+                        schema.version,
+                        schema.compatibility
                     );
                 }
             }
@@ -1269,6 +1562,25 @@ async fn create_schema(
         }
     }
 
+    async fn update_schema(
+        &self,
+        params: MqttCliCommandParam,
+        cli_request: admin_server::request::mqtt::UpdateSchemaReq,
+    ) {
+        // Create admin HTTP client
+        let admin_client = AdminHttpClient::new(format!("http://{}", params.server));
+
+        match admin_client.update_schema(&cli_request).await {
+            Ok(_) => {
+                println!("Updated successfully!")
+            }
+            Err(e) => {
+                println!("MQTT broker update schema exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
     async fn delete_schema(
         &self,
         params: MqttCliCommandParam,
@@ -88,6 +88,166 @@ pub enum ClusterConfigActionType {
     Get,
 }
 
+// cluster drain
+#[derive(clap::Args, Debug)]
+#[command(author = "RobustMQ", about = "related operations of broker drain, such as starting and checking progress", long_about = None
+)]
+#[command(next_line_help = true)]
+pub struct ClusterDrainArgs {
+    #[command(subcommand)]
+    pub action: ClusterDrainActionType,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum ClusterDrainActionType {
+    #[command(author = "RobustMQ", about = "action: stop accepting clients and move everything to other brokers", long_about = None)]
+    Start,
+    #[command(author = "RobustMQ", about = "action: show drain progress of the broker", long_about = None)]
+    Status,
+}
+
+// cluster connection rebalance
+#[derive(clap::Args, Debug)]
+#[command(author = "RobustMQ", about = "move a percentage of the MQTT 5 clients of the broker to less loaded brokers", long_about = None
+)]
+#[command(next_line_help = true)]
+pub struct ClusterRebalanceArgs {
+    #[arg(short, long, required = true, value_parser = clap::value_parser!(u32).range(1..=100))]
+    pub percent: u32,
+}
+
+// cluster log level
+#[derive(clap::Args, Debug)]
+#[command(author = "RobustMQ", about = "related operations of broker log levels, such as listing, changing and resetting", long_about = None
+)]
+#[command(next_line_help = true)]
+pub struct ClusterLogLevelArgs {
+    #[command(subcommand)]
+    pub action: ClusterLogLevelActionType,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum ClusterLogLevelActionType {
+    #[command(author = "RobustMQ", about = "action: list log appenders and changed levels", long_about = None)]
+    List,
+    #[command(author = "RobustMQ", about = "action: change the log level of a target", long_about = None)]
+    Set(LogLevelSetArgs),
+    #[command(author = "RobustMQ", about = "action: revert the log level of a target to the configured one", long_about = None)]
+    Reset(LogLevelResetArgs),
+}
+
+#[derive(clap::Args, Debug)]
+#[command(next_line_help = true)]
+pub struct LogLevelSetArgs {
+    #[arg(
+        short,
+        long,
+        required = true,
+        help = "log target, e.g. mqtt_broker::security"
+    )]
+    pub target: String,
+    #[arg(
+        short,
+        long,
+        required = true,
+        help = "off, error, warn, info, debug or trace"
+    )]
+    pub level: String,
+    #[arg(short, long, help = "only change this appender, e.g. server")]
+    pub appender: Option<String>,
+    #[arg(long, help = "revert the change after this many seconds")]
+    pub ttl_sec: Option<u64>,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(next_line_help = true)]
+pub struct LogLevelResetArgs {
+    #[arg(short, long, required = true)]
+    pub target: String,
+    #[arg(short, long)]
+    pub appender: Option<String>,
+}
+
+// cluster meta service membership
+#[derive(clap::Args, Debug)]
+#[command(author = "RobustMQ", about = "related operations of meta service membership, such as status, adding, promoting and removing nodes", long_about = None
+)]
+#[command(next_line_help = true)]
+pub struct ClusterMetaArgs {
+    #[command(subcommand)]
+    pub action: ClusterMetaActionType,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum ClusterMetaActionType {
+    #[command(author = "RobustMQ", about = "action: show raft status of the meta service", long_about = None)]
+    Status,
+    #[command(author = "RobustMQ", about = "action: add a meta node as learner", long_about = None)]
+    AddLearner(MetaAddLearnerArgs),
+    #[command(author = "RobustMQ", about = "action: promote a learner to voter", long_about = None)]
+    Promote(MetaNodeArgs),
+    #[command(author = "RobustMQ", about = "action: remove a meta node from the cluster", long_about = None)]
+    Remove(MetaNodeArgs),
+    #[command(author = "RobustMQ", about = "action: transfer leadership to another voter", long_about = None)]
+    TransferLeader(MetaNodeArgs),
+    #[command(author = "RobustMQ", about = "action: export a consistent metadata archive", long_about = None)]
+    Backup(MetaBackupArgs),
+    #[command(author = "RobustMQ", about = "action: restore or import a metadata archive", long_about = None)]
+    Restore(MetaRestoreArgs),
+}
+
+#[derive(clap::Args, Debug)]
+#[command(next_line_help = true)]
+pub struct MetaAddLearnerArgs {
+    #[arg(short, long, required = true)]
+    pub node_id: u64,
+    #[arg(short, long, required = true)]
+    pub rpc_addr: String,
+    #[arg(short, long, default_value_t = false)]
+    pub promote: bool,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(next_line_help = true)]
+pub struct MetaNodeArgs {
+    #[arg(short, long, required = true)]
+    pub node_id: u64,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(next_line_help = true)]
+pub struct MetaBackupArgs {
+    #[arg(short, long, required = true, help = "archive file to write")]
+    pub file: String,
+    #[arg(
+        short,
+        long,
+        value_delimiter = ',',
+        help = "only export these mqtt resources, e.g. user,acl,topic"
+    )]
+    pub resources: Vec<String>,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(next_line_help = true)]
+pub struct MetaRestoreArgs {
+    #[arg(short, long, required = true, help = "archive file to read")]
+    pub file: String,
+    #[arg(
+        short,
+        long,
+        help = "target cluster of imported mqtt resources, defaults to the cluster of the archive"
+    )]
+    pub cluster_name: Option<String>,
+    #[arg(
+        short,
+        long,
+        value_delimiter = ',',
+        help = "only import these mqtt resources, the whole archive is restored if not set"
+    )]
+    pub resources: Vec<String>,
+}
+
 // user
 #[derive(clap::Args, Debug)]
 #[command(author = "RobustMQ", about = "related operations of mqtt users, such as listing, creating, and deleting", long_about = None
@@ -320,6 +480,118 @@ pub enum SystemAlarmActionType {
 }
 
 // topic rewrite rule
+#[derive(clap::Args, Debug)]
+#[command(author = "RobustMQ", about = "related operations of audit log, such as listing and exporting", long_about = None
+)]
+#[command(next_line_help = true)]
+pub struct AuditLogArgs {
+    #[command(subcommand)]
+    pub action: AuditLogActionType,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum AuditLogActionType {
+    #[command(author = "RobustMQ", about = "action: list audit log", long_about = None)]
+    List,
+    #[command(author = "RobustMQ", about = "action: export audit log as json lines", long_about = None)]
+    Export(ExportAuditLogArgs),
+}
+
+#[derive(clap::Args, Debug)]
+#[command(next_line_help = true)]
+pub struct ExportAuditLogArgs {
+    #[arg(short, long, required = false)]
+    pub start_time: Option<u64>,
+    #[arg(short, long, required = false)]
+    pub end_time: Option<u64>,
+    #[arg(
+        short,
+        long,
+        required = false,
+        help = "output file, print to stdout if not set"
+    )]
+    pub output: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(author = "RobustMQ", about = "related operations of packet trace, such as starting a capture and downloading it", long_about = None
+)]
+#[command(next_line_help = true)]
+pub struct PacketTraceArgs {
+    #[command(subcommand)]
+    pub action: PacketTraceActionType,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum PacketTraceActionType {
+    #[command(author = "RobustMQ", about = "action: list packet trace", long_about = None)]
+    List,
+    #[command(author = "RobustMQ", about = "action: start a packet trace", long_about = None)]
+    Create(CreatePacketTraceArgs),
+    #[command(author = "RobustMQ", about = "action: stop a running packet trace", long_about = None)]
+    Stop(PacketTraceNameArgs),
+    #[command(author = "RobustMQ", about = "action: delete a packet trace and its captured packets", long_about = None)]
+    Delete(PacketTraceNameArgs),
+    #[command(author = "RobustMQ", about = "action: download captured packets as json lines", long_about = None)]
+    Download(DownloadPacketTraceArgs),
+}
+
+#[derive(clap::Args, Debug)]
+#[command(next_line_help = true)]
+pub struct CreatePacketTraceArgs {
+    #[arg(short, long, required = true)]
+    pub name: String,
+    #[arg(
+        long,
+        required = true,
+        help = "what the trace is scoped to: ClientId, Username, Topic or Ip"
+    )]
+    pub trace_type: String,
+    #[arg(
+        long,
+        required = true,
+        help = "client id, username, topic filter or ip/cidr to trace"
+    )]
+    pub target: String,
+    #[arg(
+        long,
+        default_value_t = 300,
+        help = "how long the trace runs, in seconds"
+    )]
+    pub duration_secs: u64,
+    #[arg(long, required = false, help = "File (default) or Journal")]
+    pub output: Option<String>,
+    #[arg(long, required = false, help = "Text (default), Hex or Hidden")]
+    pub payload_encode: Option<String>,
+    #[arg(
+        long,
+        required = false,
+        help = "truncate payloads to this many bytes, 0 keeps the whole payload"
+    )]
+    pub payload_limit: Option<usize>,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(next_line_help = true)]
+pub struct PacketTraceNameArgs {
+    #[arg(short, long, required = true)]
+    pub name: String,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(next_line_help = true)]
+pub struct DownloadPacketTraceArgs {
+    #[arg(short, long, required = true)]
+    pub name: String,
+    #[arg(
+        short,
+        long,
+        required = false,
+        help = "output file, print to stdout if not set"
+    )]
+    pub output: Option<String>,
+}
+
 #[derive(clap::Args, Debug)]
 #[command(author = "RobustMQ", about = "related operations of topic rewrite, such as creating and deleting", long_about = None
 )]
@@ -391,6 +663,8 @@ pub struct CreateConnectorArgs {
     pub config: String,
     #[arg(short, long, required = true)]
     pub topic_name: String,
+    #[arg(short = 'p', long)]
+    pub payload_format: Option<String>,
 }
 
 #[derive(clap::Args, Debug)]
@@ -423,6 +697,8 @@ pub enum SchemaActionType {
     List(ListSchemaArgs),
     #[command(author = "RobustMQ", about = "action: create schema", long_about = None)]
     Create(CreateSchemaArgs),
+    #[command(author = "RobustMQ", about = "action: update schema", long_about = None)]
+    Update(UpdateSchemaArgs),
     #[command(author = "RobustMQ", about = "action: delete schema", long_about = None)]
     Delete(DeleteSchemaArgs),
     #[command(author = "RobustMQ", about = "action: list bind schemas", long_about = None)]
@@ -453,6 +729,28 @@ pub struct CreateSchemaArgs {
     pub schema: String,
     #[arg(short = 'd', long, required = true)]
     pub desc: String,
+    #[arg(short = 'm', long, default_value = "")]
+    pub message_name: String,
+    #[arg(short = 'c', long, default_value = "none")]
+    pub compatibility: String,
+}
+
+#[derive(Debug, Parser)]
+#[command(author="RobustMQ", about="action: update schema", long_about = None)]
+#[command(next_line_help = true)]
+pub struct UpdateSchemaArgs {
+    #[arg(short = 'n', long, required = true)]
+    pub schema_name: String,
+    #[arg(short = 't', long, required = true)]
+    pub schema_type: String,
+    #[arg(short = 's', long, required = true)]
+    pub schema: String,
+    #[arg(short = 'd', long, required = true)]
+    pub desc: String,
+    #[arg(short = 'm', long, default_value = "")]
+    pub message_name: String,
+    #[arg(short = 'c', long, default_value = "")]
+    pub compatibility: String,
 }
 
 #[derive(Debug, Parser)]
@@ -509,6 +807,50 @@ pub fn process_system_alarm_args(args: SystemAlarmArgs) -> MqttActionType {
     }
 }
 
+pub fn process_audit_log_args(args: AuditLogArgs) -> MqttActionType {
+    match args.action {
+        AuditLogActionType::List => MqttActionType::ListAuditLog,
+        AuditLogActionType::Export(arg) => MqttActionType::ExportAuditLog(
+            admin_server::request::mqtt::AuditLogExportReq {
+                start_time: arg.start_time,
+                end_time: arg.end_time,
+            },
+            arg.output,
+        ),
+    }
+}
+
+pub fn process_packet_trace_args(args: PacketTraceArgs) -> MqttActionType {
+    match args.action {
+        PacketTraceActionType::List => MqttActionType::ListPacketTrace,
+        PacketTraceActionType::Create(arg) => {
+            MqttActionType::CreatePacketTrace(admin_server::request::mqtt::CreatePacketTraceReq {
+                name: arg.name,
+                trace_type: arg.trace_type,
+                target: arg.target,
+                duration_secs: arg.duration_secs,
+                output: arg.output,
+                payload_encode: arg.payload_encode,
+                payload_limit: arg.payload_limit,
+            })
+        }
+        PacketTraceActionType::Stop(arg) => {
+            MqttActionType::StopPacketTrace(admin_server::request::mqtt::StopPacketTraceReq {
+                name: arg.name,
+            })
+        }
+        PacketTraceActionType::Delete(arg) => {
+            MqttActionType::DeletePacketTrace(admin_server::request::mqtt::DeletePacketTraceReq {
+                name: arg.name,
+            })
+        }
+        PacketTraceActionType::Download(arg) => MqttActionType::DownloadPacketTrace(
+            admin_server::request::mqtt::DownloadPacketTraceReq { name: arg.name },
+            arg.output,
+        ),
+    }
+}
+
 pub fn process_session_args(args: SessionArgs) -> MqttActionType {
     match args.action {
         SessionActionType::List => MqttActionType::ListSession,
@@ -608,6 +950,7 @@ pub fn process_connector_args(args: ConnectorArgs) -> MqttActionType {
                 connector_type: arg.connector_type,
                 config: arg.config,
                 topic_name: arg.topic_name,
+                payload_format: arg.payload_format,
             })
         }
         ConnectorActionType::Delete(arg) => {
@@ -646,6 +989,18 @@ pub fn process_schema_args(args: SchemaArgs) -> MqttActionType {
                 schema_type: arg.schema_type,
                 schema: arg.schema,
                 desc: arg.desc,
+                message_name: arg.message_name,
+                compatibility: arg.compatibility,
+            })
+        }
+        SchemaActionType::Update(arg) => {
+            MqttActionType::UpdateSchema(admin_server::request::mqtt::UpdateSchemaReq {
+                schema_name: arg.schema_name,
+                schema_type: arg.schema_type,
+                schema: arg.schema,
+                desc: arg.desc,
+                message_name: arg.message_name,
+                compatibility: arg.compatibility,
             })
         }
         SchemaActionType::List(_) => MqttActionType::ListSchema,
@@ -24,6 +24,7 @@ pub enum FeatureType {
     OfflineMessage,
     SystemAlarm,
     FlappingDetect,
+    SharedSubscription,
 }
 
 impl FromStr for FeatureType {
@@ -54,6 +55,7 @@ fn value_variants<'a>() -> &'a [Self] {
             Self::OfflineMessage,
             Self::SlowSubscribe,
             Self::FlappingDetect,
+            Self::SharedSubscription,
         ]
     }
 
@@ -63,6 +65,7 @@ fn to_possible_value(&self) -> Option<PossibleValue> {
             FeatureType::OfflineMessage => PossibleValue::new("OfflineMessage"),
             FeatureType::SystemAlarm => PossibleValue::new("SystemAlarm"),
             FeatureType::FlappingDetect => PossibleValue::new("FlappingDetect"),
+            FeatureType::SharedSubscription => PossibleValue::new("SharedSubscription"),
         })
     }
 }
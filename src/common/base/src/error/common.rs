@@ -141,6 +141,12 @@ pub enum CommonError {
     #[error("RocksDB Family {0} not available")]
     RocksDBFamilyNotAvailable(String),
 
+    #[error("Master key {0} was not found in the encryption key file")]
+    MasterKeyNotFound(u32),
+
+    #[error("Data encryption failed, error message: {0}")]
+    DataEncryptionError(String),
+
     #[error("CRC check for the message data failed")]
     CrcCheckByMessage,
 
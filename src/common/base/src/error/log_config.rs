@@ -28,4 +28,19 @@ pub enum LogConfigError {
 
     #[error(transparent)]
     Addr(#[from] std::net::AddrParseError),
+
+    #[error("Log target cannot be empty")]
+    EmptyLogTarget,
+
+    #[error("Invalid log level {0}, expected one of off, error, warn, info, debug, trace")]
+    InvalidLogLevel(String),
+
+    #[error("Log appender {0} does not exist")]
+    AppenderNotFound(String),
+
+    #[error("No log appender receives target {0}, an appender has to be given")]
+    NoAppenderForTarget(String),
+
+    #[error("Failed to reload log filter: {0}")]
+    FilterReload(String),
 }
@@ -17,6 +17,7 @@
 pub mod error;
 pub mod http_error;
 pub mod http_response;
+pub mod lock_profile;
 pub mod logging;
 pub mod network;
 pub mod node_status;
@@ -0,0 +1,157 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Sampling of long lock holds. Guards wrapped with `timed` record where they
+// were taken and, when the hold reached the threshold, how long it lasted.
+// Nothing but an atomic load happens while sampling is disabled.
+
+use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
+use std::panic::Location;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use lazy_static::lazy_static;
+
+static LOCK_PROFILE_ENABLE: AtomicBool = AtomicBool::new(false);
+static LOCK_HOLD_THRESHOLD_NS: AtomicU64 = AtomicU64::new(0);
+
+lazy_static! {
+    static ref LOCK_HOLDS: Mutex<HashMap<&'static Location<'static>, LockHoldStat>> =
+        Mutex::new(HashMap::new());
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LockHoldStat {
+    pub file: &'static str,
+    pub line: u32,
+    // Holds that reached the threshold
+    pub count: u64,
+    pub total_ns: u64,
+    pub max_ns: u64,
+}
+
+pub fn set_lock_profile(enable: bool, threshold: Duration) {
+    LOCK_HOLD_THRESHOLD_NS.store(threshold.as_nanos() as u64, Ordering::Relaxed);
+    LOCK_PROFILE_ENABLE.store(enable, Ordering::Relaxed);
+}
+
+pub fn is_lock_profile_enable() -> bool {
+    LOCK_PROFILE_ENABLE.load(Ordering::Relaxed)
+}
+
+pub fn lock_hold_stats() -> Vec<LockHoldStat> {
+    match LOCK_HOLDS.lock() {
+        Ok(holds) => holds.values().cloned().collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+pub fn reset_lock_hold_stats() {
+    if let Ok(mut holds) = LOCK_HOLDS.lock() {
+        holds.clear();
+    }
+}
+
+fn record_lock_hold(location: &'static Location<'static>, hold: Duration) {
+    let hold_ns = hold.as_nanos() as u64;
+    if hold_ns < LOCK_HOLD_THRESHOLD_NS.load(Ordering::Relaxed) {
+        return;
+    }
+    if let Ok(mut holds) = LOCK_HOLDS.lock() {
+        let stat = holds.entry(location).or_insert_with(|| LockHoldStat {
+            file: location.file(),
+            line: location.line(),
+            ..Default::default()
+        });
+        stat.count += 1;
+        stat.total_ns += hold_ns;
+        stat.max_ns = stat.max_ns.max(hold_ns);
+    }
+}
+
+pub struct Timed<G> {
+    guard: G,
+    location: &'static Location<'static>,
+    start: Option<Instant>,
+}
+
+// Wrap a MutexGuard, RwLock guard or DashMap Ref/RefMut right after it was taken
+#[track_caller]
+pub fn timed<G>(guard: G) -> Timed<G> {
+    Timed {
+        guard,
+        location: Location::caller(),
+        start: is_lock_profile_enable().then(Instant::now),
+    }
+}
+
+impl<G: Deref> Deref for Timed<G> {
+    type Target = G::Target;
+
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+impl<G: DerefMut> DerefMut for Timed<G> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+
+impl<G> Drop for Timed<G> {
+    fn drop(&mut self) {
+        if let Some(start) = self.start {
+            record_lock_hold(self.location, start.elapsed());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use std::thread::sleep;
+    use std::time::Duration;
+
+    use super::{lock_hold_stats, reset_lock_hold_stats, set_lock_profile, timed};
+
+    #[test]
+    fn lock_hold_test() {
+        let lock = Mutex::new(0);
+        set_lock_profile(true, Duration::from_millis(5));
+
+        {
+            let mut value = timed(lock.lock().unwrap());
+            *value += 1;
+        }
+        {
+            let _value = timed(lock.lock().unwrap());
+            sleep(Duration::from_millis(10));
+        }
+
+        let stats: Vec<_> = lock_hold_stats()
+            .into_iter()
+            .filter(|stat| stat.file.ends_with("lock_profile.rs"))
+            .collect();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].count, 1);
+        assert!(stats[0].max_ns >= 10_000_000);
+        assert_eq!(*lock.lock().unwrap(), 1);
+
+        set_lock_profile(false, Duration::ZERO);
+        reset_lock_hold_stats();
+    }
+}
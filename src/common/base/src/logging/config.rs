@@ -32,8 +32,10 @@ pub(super) trait AppenderConfig<S = Registry>
 where
     S: tracing::Subscriber,
 {
-    fn create_layer_and_guard(self)
-        -> Result<(BoxedLayer<S>, Option<WorkerGuard>), LogConfigError>;
+    fn create_layer_and_guard(
+        self,
+        name: &str,
+    ) -> Result<(BoxedLayer<S>, Option<WorkerGuard>), LogConfigError>;
 }
 
 /// Supported configurations for log appenders.
@@ -48,19 +50,20 @@ pub(super) enum Appender {
 impl Appender {
     pub(super) fn create_layer_and_guard<S>(
         self,
+        name: &str,
     ) -> Result<(BoxedLayer<S>, Option<WorkerGuard>), LogConfigError>
     where
-        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a> + 'static,
     {
         match self {
             Appender::Console(console_appender_config) => {
-                console_appender_config.create_layer_and_guard()
+                console_appender_config.create_layer_and_guard(name)
             }
             Appender::RollingFile(rolling_file_appender_config) => {
-                rolling_file_appender_config.create_layer_and_guard()
+                rolling_file_appender_config.create_layer_and_guard(name)
             }
             Appender::TokioConsole(tokio_console_appender_config) => {
-                tokio_console_appender_config.create_layer_and_guard()
+                tokio_console_appender_config.create_layer_and_guard(name)
             }
         }
     }
@@ -31,14 +31,15 @@ pub(super) struct ConsoleAppenderConfig {
 
 impl<S> AppenderConfig<S> for ConsoleAppenderConfig
 where
-    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+    S: tracing::Subscriber + for<'a> LookupSpan<'a> + 'static,
 {
     fn create_layer_and_guard(
         self,
+        name: &str,
     ) -> Result<(BoxedLayer<S>, Option<WorkerGuard>), LogConfigError> {
         let writer = std::io::stdout();
         let (non_blocking, guard) = tracing_appender::non_blocking(writer);
-        let fmt_layer = self.fmt.create_layer(non_blocking);
+        let fmt_layer = self.fmt.create_layer(name, non_blocking);
 
         Ok((fmt_layer, Some(guard)))
     }
@@ -0,0 +1,435 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Filters of the log appenders that can be changed while the broker runs.
+// Every fmt appender registers the filter from the logging configuration,
+// level overrides set at runtime are applied on top of it and are dropped
+// again when removed or expired.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::filter::Targets;
+
+use crate::error::log_config::LogConfigError;
+use crate::tools::now_second;
+
+type ReloadFn = Box<dyn Fn(Targets) -> Result<(), String> + Send + Sync>;
+
+pub(super) struct AppenderFilter {
+    default: Option<LevelFilter>,
+    targets: Vec<(String, LevelFilter)>,
+    reload: ReloadFn,
+}
+
+impl AppenderFilter {
+    pub(super) fn new(
+        default: Option<LevelFilter>,
+        targets: Vec<(String, LevelFilter)>,
+        reload: ReloadFn,
+    ) -> Self {
+        AppenderFilter {
+            default,
+            targets,
+            reload,
+        }
+    }
+
+    // Whether events of the target reach the appender with its configured filter
+    fn receives(&self, target: &str) -> bool {
+        self.default.is_some()
+            || self
+                .targets
+                .iter()
+                .any(|(path, _)| target.starts_with(path.as_str()))
+    }
+
+    fn build(&self, overrides: &[&ActiveOverride]) -> Targets {
+        build_targets(self.default, &self.targets, overrides)
+    }
+}
+
+pub(super) fn build_targets(
+    default: Option<LevelFilter>,
+    targets: &[(String, LevelFilter)],
+    overrides: &[&ActiveOverride],
+) -> Targets {
+    let mut filter = Targets::new();
+    if let Some(level) = default {
+        filter = filter.with_default(level);
+    }
+    for (path, level) in targets {
+        filter = filter.with_target(path.clone(), *level);
+    }
+    for active in overrides {
+        filter = filter.with_target(active.info.target.clone(), active.level);
+    }
+    filter
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LogLevelOverride {
+    pub target: String,
+    pub level: String,
+    // Only this appender is changed, otherwise every appender receiving the target
+    pub appender: Option<String>,
+    // Unix time in seconds at which the override is reverted
+    pub expire_time: Option<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AppenderFilterInfo {
+    pub appender: String,
+    pub filter: String,
+}
+
+pub(super) struct ActiveOverride {
+    info: LogLevelOverride,
+    level: LevelFilter,
+    revision: u64,
+}
+
+type OverrideKey = (String, Option<String>);
+
+#[derive(Default)]
+struct DynamicFilters {
+    appenders: HashMap<String, AppenderFilter>,
+    overrides: HashMap<OverrideKey, ActiveOverride>,
+}
+
+impl DynamicFilters {
+    fn applicable(&self, name: &str, appender: &AppenderFilter) -> Vec<&ActiveOverride> {
+        self.overrides
+            .values()
+            .filter(|active| match &active.info.appender {
+                Some(only) => only == name,
+                None => appender.receives(&active.info.target),
+            })
+            .collect()
+    }
+
+    fn apply(&self) -> Result<(), LogConfigError> {
+        for (name, appender) in self.appenders.iter() {
+            let filter = appender.build(&self.applicable(name, appender));
+            (appender.reload)(filter).map_err(LogConfigError::FilterReload)?;
+        }
+        Ok(())
+    }
+
+    // Apply the overrides, or put the previous override of the key back when an appender
+    // rejects the new filter so that the appenders keep the filters they had
+    fn apply_or_restore(
+        &mut self,
+        key: OverrideKey,
+        previous: Option<ActiveOverride>,
+    ) -> Result<(), LogConfigError> {
+        let Err(e) = self.apply() else {
+            return Ok(());
+        };
+        let target = key.0.clone();
+        match previous {
+            Some(active) => self.overrides.insert(key, active),
+            None => self.overrides.remove(&key),
+        };
+        if let Err(restore_err) = self.apply() {
+            tracing::warn!(
+                "Failed to restore log filters of {}, error: {}",
+                target,
+                restore_err
+            );
+        }
+        Err(e)
+    }
+}
+
+static OVERRIDE_REVISION: AtomicU64 = AtomicU64::new(0);
+
+lazy_static! {
+    static ref DYNAMIC_FILTERS: Mutex<DynamicFilters> = Mutex::new(DynamicFilters::default());
+}
+
+// A panic while the lock was held leaves the maps intact, so keep using them
+fn lock_filters() -> MutexGuard<'static, DynamicFilters> {
+    DYNAMIC_FILTERS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+pub(super) fn register_appender_filter(name: &str, filter: AppenderFilter) {
+    lock_filters().appenders.insert(name.to_string(), filter);
+}
+
+/// Change the level of a target, e.g. `mqtt_broker::security`, at runtime.
+/// Without an appender the change applies to every appender that already
+/// receives the target; naming an appender also routes the target to it.
+/// With a ttl the override is reverted after it elapsed.
+pub fn set_log_level(
+    target: &str,
+    level: &str,
+    appender: Option<&str>,
+    ttl: Option<Duration>,
+) -> Result<LogLevelOverride, LogConfigError> {
+    if target.is_empty() {
+        return Err(LogConfigError::EmptyLogTarget);
+    }
+    let level_filter = LevelFilter::from_str(level)
+        .map_err(|_| LogConfigError::InvalidLogLevel(level.to_string()))?;
+
+    let mut filters = lock_filters();
+    match appender {
+        Some(name) => {
+            if !filters.appenders.contains_key(name) {
+                return Err(LogConfigError::AppenderNotFound(name.to_string()));
+            }
+        }
+        None => {
+            if !filters
+                .appenders
+                .values()
+                .any(|filter| filter.receives(target))
+            {
+                return Err(LogConfigError::NoAppenderForTarget(target.to_string()));
+            }
+        }
+    }
+
+    let info = LogLevelOverride {
+        target: target.to_string(),
+        level: level_filter.to_string().to_lowercase(),
+        appender: appender.map(|name| name.to_string()),
+        expire_time: ttl.map(|ttl| now_second() + ttl.as_secs()),
+    };
+    let key = (info.target.clone(), info.appender.clone());
+    let revision = OVERRIDE_REVISION.fetch_add(1, Ordering::Relaxed) + 1;
+    let previous = filters.overrides.insert(
+        key.clone(),
+        ActiveOverride {
+            info: info.clone(),
+            level: level_filter,
+            revision,
+        },
+    );
+    filters.apply_or_restore(key.clone(), previous)?;
+    drop(filters);
+
+    if let Some(ttl) = ttl {
+        thread::spawn(move || {
+            thread::sleep(ttl);
+            expire_log_level(key, revision);
+        });
+    }
+    Ok(info)
+}
+
+/// Remove the override of a target and return to the configured filter.
+/// Returns false if the target had no override.
+pub fn reset_log_level(target: &str, appender: Option<&str>) -> Result<bool, LogConfigError> {
+    let key = (target.to_string(), appender.map(|name| name.to_string()));
+    let mut filters = lock_filters();
+    let Some(previous) = filters.overrides.remove(&key) else {
+        return Ok(false);
+    };
+    filters.apply_or_restore(key, Some(previous))?;
+    Ok(true)
+}
+
+// An override set again in the meantime has a newer revision and is kept
+fn expire_log_level(key: OverrideKey, revision: u64) {
+    let mut filters = lock_filters();
+    if filters
+        .overrides
+        .get(&key)
+        .is_some_and(|active| active.revision == revision)
+    {
+        filters.overrides.remove(&key);
+        if let Err(e) = filters.apply() {
+            tracing::warn!("Failed to revert log level of {}, error: {}", key.0, e);
+        }
+    }
+}
+
+pub fn log_level_overrides() -> Vec<LogLevelOverride> {
+    let filters = lock_filters();
+    let mut overrides: Vec<LogLevelOverride> = filters
+        .overrides
+        .values()
+        .map(|active| active.info.clone())
+        .collect();
+    overrides.sort_by(|a, b| a.target.cmp(&b.target).then(a.appender.cmp(&b.appender)));
+    overrides
+}
+
+/// The filter each appender currently uses, overrides included
+pub fn appender_filters() -> Vec<AppenderFilterInfo> {
+    let filters = lock_filters();
+    let mut list: Vec<AppenderFilterInfo> = filters
+        .appenders
+        .iter()
+        .map(|(name, appender)| AppenderFilterInfo {
+            appender: name.clone(),
+            filter: appender
+                .build(&filters.applicable(name, appender))
+                .to_string(),
+        })
+        .collect();
+    list.sort_by(|a, b| a.appender.cmp(&b.appender));
+    list
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn register(
+        name: &str,
+        default: Option<LevelFilter>,
+        targets: Vec<(String, LevelFilter)>,
+    ) -> Arc<Mutex<String>> {
+        let current = Arc::new(Mutex::new(String::new()));
+        let raw = current.clone();
+        register_appender_filter(
+            name,
+            AppenderFilter::new(
+                default,
+                targets,
+                Box::new(move |filter| {
+                    *raw.lock().unwrap() = filter.to_string();
+                    Ok(())
+                }),
+            ),
+        );
+        current
+    }
+
+    #[test]
+    fn set_log_level_test() {
+        let console = register("test_console", Some(LevelFilter::INFO), Vec::new());
+        let raft = register(
+            "test_raft",
+            None,
+            vec![("test_openraft".to_string(), LevelFilter::INFO)],
+        );
+
+        let info = set_log_level("test_broker::security", "DEBUG", None, None).unwrap();
+        assert_eq!(info.level, "debug");
+        assert!(console
+            .lock()
+            .unwrap()
+            .contains("test_broker::security=debug"));
+        assert!(!raft.lock().unwrap().contains("test_broker::security"));
+
+        // routed to an appender that does not receive it by configuration
+        set_log_level("test_broker::security", "trace", Some("test_raft"), None).unwrap();
+        assert!(raft.lock().unwrap().contains("test_broker::security=trace"));
+        assert!(log_level_overrides()
+            .iter()
+            .any(|o| o.appender == Some("test_raft".to_string())));
+
+        assert!(reset_log_level("test_broker::security", None).unwrap());
+        assert!(!console.lock().unwrap().contains("test_broker::security"));
+        assert!(!reset_log_level("test_broker::security", None).unwrap());
+        assert!(reset_log_level("test_broker::security", Some("test_raft")).unwrap());
+        assert!(!raft.lock().unwrap().contains("test_broker::security"));
+    }
+
+    #[test]
+    fn set_log_level_error_test() {
+        register("test_error_console", Some(LevelFilter::INFO), Vec::new());
+        assert!(matches!(
+            set_log_level("test_error", "verbose", None, None),
+            Err(LogConfigError::InvalidLogLevel(_))
+        ));
+        assert!(matches!(
+            set_log_level("", "debug", None, None),
+            Err(LogConfigError::EmptyLogTarget)
+        ));
+        assert!(matches!(
+            set_log_level("test_error", "debug", Some("test_not_exist"), None),
+            Err(LogConfigError::AppenderNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn rollback_log_level_test() {
+        let fail = Arc::new(AtomicBool::new(false));
+        let reload_fail = fail.clone();
+        register_appender_filter(
+            "test_rollback_console",
+            AppenderFilter::new(
+                None,
+                Vec::new(),
+                // only rejects changes of its own override, other tests apply concurrently
+                Box::new(move |filter| {
+                    if reload_fail.load(Ordering::Relaxed)
+                        && !filter.to_string().contains("test_rollback=debug")
+                    {
+                        return Err("reload failed".to_string());
+                    }
+                    Ok(())
+                }),
+            ),
+        );
+        let only = Some("test_rollback_console");
+        let has_override = |level: &str| {
+            log_level_overrides()
+                .iter()
+                .any(|o| o.target == "test_rollback" && o.level == level)
+        };
+
+        set_log_level("test_rollback", "debug", only, None).unwrap();
+        fail.store(true, Ordering::Relaxed);
+
+        // the previous override is kept when the new filter cannot be applied
+        assert!(matches!(
+            set_log_level("test_rollback", "trace", only, None),
+            Err(LogConfigError::FilterReload(_))
+        ));
+        assert!(has_override("debug"));
+        assert!(!has_override("trace"));
+
+        assert!(reset_log_level("test_rollback", only).is_err());
+        assert!(has_override("debug"));
+
+        fail.store(false, Ordering::Relaxed);
+        assert!(reset_log_level("test_rollback", only).unwrap());
+        assert!(!has_override("debug"));
+    }
+
+    #[test]
+    fn expire_log_level_test() {
+        let console = register("test_expire_console", Some(LevelFilter::INFO), Vec::new());
+        let info = set_log_level(
+            "test_expire",
+            "debug",
+            Some("test_expire_console"),
+            Some(Duration::from_millis(100)),
+        )
+        .unwrap();
+        assert!(info.expire_time.is_some());
+        assert!(console.lock().unwrap().contains("test_expire=debug"));
+
+        thread::sleep(Duration::from_millis(500));
+        assert!(!console.lock().unwrap().contains("test_expire"));
+    }
+}
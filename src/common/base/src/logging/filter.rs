@@ -14,9 +14,12 @@
 
 use serde::Deserialize;
 use tracing::{level_filters::LevelFilter, Subscriber};
-use tracing_subscriber::{registry::LookupSpan, Layer};
+use tracing_subscriber::{registry::LookupSpan, reload, Layer};
 
-use crate::logging::config::BoxedLayer;
+use crate::logging::{
+    config::BoxedLayer,
+    dynamic_filter::{build_targets, register_appender_filter, AppenderFilter},
+};
 
 #[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 #[serde(rename_all = "snake_case")]
@@ -62,26 +65,35 @@ pub(super) enum Filter {
 
 impl Filter {
     /// Append the filter to the provided layer and return a boxed layer.
-    pub(super) fn append_and_box<S, L>(self, layer: L) -> BoxedLayer<S>
+    /// The filter is registered under the appender name so that it can be
+    /// changed at runtime.
+    pub(super) fn append_and_box<S, L>(self, name: &str, layer: L) -> BoxedLayer<S>
     where
-        S: Subscriber + for<'span> LookupSpan<'span>,
+        S: Subscriber + for<'span> LookupSpan<'span> + 'static,
         L: Layer<S> + Send + Sync + 'static,
     {
-        match self {
-            Filter::Target(target) => {
-                let filter = tracing_subscriber::filter::Targets::new()
-                    .with_target(target.path, target.level);
-                layer.with_filter(filter).boxed()
-            }
-            Filter::Targets(targets) => {
-                let mut filter = tracing_subscriber::filter::Targets::new();
-                for target in targets {
-                    filter = filter.with_target(target.path, target.level);
-                }
-                layer.with_filter(filter).boxed()
-            }
-            Filter::Level(level) => layer.with_filter(LevelFilter::from(level)).boxed(),
-        }
+        let (default, targets) = match self {
+            Filter::Target(target) => (None, vec![(target.path, target.level.into())]),
+            Filter::Targets(targets) => (
+                None,
+                targets
+                    .into_iter()
+                    .map(|target| (target.path, target.level.into()))
+                    .collect(),
+            ),
+            Filter::Level(level) => (Some(LevelFilter::from(level)), Vec::new()),
+        };
+
+        let (filter, handle) = reload::Layer::new(build_targets(default, &targets, &[]));
+        register_appender_filter(
+            name,
+            AppenderFilter::new(
+                default,
+                targets,
+                Box::new(move |filter| handle.reload(filter).map_err(|e| e.to_string())),
+            ),
+        );
+        layer.with_filter(filter).boxed()
     }
 }
 
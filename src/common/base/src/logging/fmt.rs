@@ -36,9 +36,9 @@ pub(super) struct FmtLayerConfig {
 
 impl FmtLayerConfig {
     /// Creates a new Fmt layer with the specified writer and default ANSI setting.
-    pub(super) fn create_layer<S, W>(self, writer: W) -> BoxedLayer<S>
+    pub(super) fn create_layer<S, W>(self, name: &str, writer: W) -> BoxedLayer<S>
     where
-        S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+        S: tracing::Subscriber + for<'a> LookupSpan<'a> + 'static,
         W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
     {
         let mut layer = tracing_subscriber::fmt::layer().with_writer(writer);
@@ -47,10 +47,10 @@ pub(super) fn create_layer<S, W>(self, writer: W) -> BoxedLayer<S>
         layer = layer.with_ansi(ansi);
 
         match self.formatter {
-            Some(Formatter::Compact) => self.filter.append_and_box(layer.compact()),
-            Some(Formatter::Pretty) => self.filter.append_and_box(layer.pretty()),
-            Some(Formatter::Json) => self.filter.append_and_box(layer.json()),
-            None => self.filter.append_and_box(layer),
+            Some(Formatter::Compact) => self.filter.append_and_box(name, layer.compact()),
+            Some(Formatter::Pretty) => self.filter.append_and_box(name, layer.pretty()),
+            Some(Formatter::Json) => self.filter.append_and_box(name, layer.json()),
+            None => self.filter.append_and_box(name, layer),
         }
     }
 }
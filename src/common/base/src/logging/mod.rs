@@ -22,6 +22,7 @@
 
 mod config;
 mod console;
+pub mod dynamic_filter;
 mod filter;
 mod fmt;
 mod rolling_file;
@@ -68,8 +69,8 @@ fn init_tracing_subscriber_with_config(
     let mut layers = Vec::with_capacity(config.appenders.len());
     let mut guards = Vec::with_capacity(config.appenders.len());
 
-    for (_name, conf) in config.appenders {
-        let (layer, guard) = conf.create_layer_and_guard()?;
+    for (name, conf) in config.appenders {
+        let (layer, guard) = conf.create_layer_and_guard(&name)?;
 
         layers.push(layer);
 
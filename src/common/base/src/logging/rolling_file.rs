@@ -59,10 +59,11 @@ pub(super) struct RollingFileAppenderConfig {
 
 impl<S> AppenderConfig<S> for RollingFileAppenderConfig
 where
-    S: Subscriber + for<'a> LookupSpan<'a>,
+    S: Subscriber + for<'a> LookupSpan<'a> + 'static,
 {
     fn create_layer_and_guard(
         self,
+        name: &str,
     ) -> Result<(BoxedLayer<S>, Option<WorkerGuard>), LogConfigError> {
         let mut builder = tracing_appender::rolling::Builder::new();
 
@@ -82,7 +83,7 @@ fn create_layer_and_guard(
         let writer = builder.build(&self.directory)?;
 
         let (non_blocking, guard) = tracing_appender::non_blocking(writer);
-        let fmt_layer = self.fmt.create_layer(non_blocking);
+        let fmt_layer = self.fmt.create_layer(name, non_blocking);
         Ok((fmt_layer, Some(guard)))
     }
 }
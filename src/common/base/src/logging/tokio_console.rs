@@ -37,6 +37,7 @@ impl<S> AppenderConfig<S> for TokioConsoleAppenderConfig
 {
     fn create_layer_and_guard(
         self,
+        _name: &str,
     ) -> Result<(BoxedLayer<S>, Option<WorkerGuard>), LogConfigError> {
         let mut builder = console_subscriber::ConsoleLayer::builder();
         if let Some(bind) = &self.bind {
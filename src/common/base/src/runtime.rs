@@ -13,10 +13,13 @@
 // limitations under the License.
 
 use std::sync::atomic::AtomicU32;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 use lazy_static::lazy_static;
 use prometheus::{register_int_gauge_vec, IntGaugeVec};
-use tokio::runtime::{Builder, Runtime};
+use serde::{Deserialize, Serialize};
+use tokio::runtime::{Builder, Handle, Runtime};
 
 static GLOBAL_RUNTIME_ID: AtomicU32 = AtomicU32::new(0);
 pub const THREAD_NAME_LABEL: &str = "thread_name";
@@ -34,6 +37,9 @@
         &[THREAD_NAME_LABEL]
     )
     .unwrap();
+
+    // (runtime name, handle) of every runtime built by RuntimeBuilder
+    static ref RUNTIME_HANDLES: Mutex<Vec<(String, Handle)>> = Mutex::new(Vec::new());
 }
 
 struct RuntimeBuilder {
@@ -89,6 +95,9 @@ pub fn build(&mut self) -> Runtime {
             .build()
             .unwrap();
         let _ = rt.enter();
+        if let Ok(mut handles) = RUNTIME_HANDLES.lock() {
+            handles.push((self.runtime_name.clone(), rt.handle().clone()));
+        }
         rt
     }
 }
@@ -137,12 +146,105 @@ pub fn get_runtime_worker_threads() -> usize {
     64
 }
 
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RuntimeMetricsSample {
+    pub runtime_name: String,
+    pub workers: usize,
+    pub alive_tasks: usize,
+    pub global_queue_depth: usize,
+    // Share of the sampling window the workers spent polling tasks, 0.0 to 1.0
+    pub busy_ratio: f64,
+    // The following need the runtime to be built with `--cfg tokio_unstable`
+    pub spawned_tasks: Option<u64>,
+    pub poll_count: Option<u64>,
+    pub mean_poll_time_us: Option<u64>,
+}
+
+struct WorkerCounters {
+    busy: Duration,
+    #[cfg_attr(not(tokio_unstable), allow(dead_code))]
+    polls: u64,
+}
+
+fn worker_counters(handle: &Handle) -> WorkerCounters {
+    let metrics = handle.metrics();
+    let mut counters = WorkerCounters {
+        busy: Duration::ZERO,
+        polls: 0,
+    };
+    for worker in 0..metrics.num_workers() {
+        counters.busy += metrics.worker_total_busy_duration(worker);
+        #[cfg(tokio_unstable)]
+        {
+            counters.polls += metrics.worker_poll_count(worker);
+        }
+    }
+    counters
+}
+
+// Sample the runtimes built by create_runtime over the window, the busy ratio
+// and poll count are the differences between the start and the end of it
+pub async fn sample_runtime_metrics(window: Duration) -> Vec<RuntimeMetricsSample> {
+    let handles = match RUNTIME_HANDLES.lock() {
+        Ok(handles) => handles.clone(),
+        Err(_) => return Vec::new(),
+    };
+
+    let start = Instant::now();
+    let before: Vec<WorkerCounters> = handles
+        .iter()
+        .map(|(_, handle)| worker_counters(handle))
+        .collect();
+    tokio::time::sleep(window).await;
+    let elapsed = start.elapsed();
+
+    handles
+        .iter()
+        .zip(before)
+        .map(|((runtime_name, handle), before)| {
+            let metrics = handle.metrics();
+            let after = worker_counters(handle);
+            let workers = metrics.num_workers();
+            let capacity = elapsed.as_secs_f64() * workers as f64;
+            let busy = after.busy.saturating_sub(before.busy).as_secs_f64();
+
+            #[allow(unused_mut)]
+            let mut sample = RuntimeMetricsSample {
+                runtime_name: runtime_name.clone(),
+                workers,
+                alive_tasks: metrics.num_alive_tasks(),
+                global_queue_depth: metrics.global_queue_depth(),
+                busy_ratio: if capacity > 0.0 {
+                    (busy / capacity).min(1.0)
+                } else {
+                    0.0
+                },
+                spawned_tasks: None,
+                poll_count: None,
+                mean_poll_time_us: None,
+            };
+
+            #[cfg(tokio_unstable)]
+            {
+                sample.spawned_tasks = Some(metrics.spawned_tasks_count());
+                sample.poll_count = Some(after.polls.saturating_sub(before.polls));
+                let mean_poll_time: Duration = (0..workers)
+                    .map(|worker| metrics.worker_mean_poll_time(worker))
+                    .sum();
+                sample.mean_poll_time_us =
+                    Some(mean_poll_time.as_micros() as u64 / workers.max(1) as u64);
+            }
+            sample
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::thread::{self, sleep};
     use std::time::Duration;
 
-    use super::create_runtime;
+    use super::{create_runtime, sample_runtime_metrics};
 
     #[test]
     fn test_metric() {
@@ -180,6 +282,18 @@ fn test_runtime_in_runtime() {
         sleep(Duration::from_secs(5));
     }
 
+    #[test]
+    fn test_sample_runtime_metrics() {
+        let rt = create_runtime("sample-test", 2);
+        let samples = rt.block_on(sample_runtime_metrics(Duration::from_millis(100)));
+        let sample = samples
+            .iter()
+            .find(|sample| sample.runtime_name == "sample-test")
+            .unwrap();
+        assert_eq!(sample.workers, 2);
+        assert!(sample.busy_ratio >= 0.0 && sample.busy_ratio <= 1.0);
+    }
+
     #[test]
     fn test_get_cpu_num() {
         let num_threads = thread::available_parallelism().unwrap().get();
@@ -12,16 +12,23 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::config::broker_mqtt::BrokerMqttConfig;
-use opentelemetry::{global, propagation::Extractor, trace::noop::NoopTracerProvider};
+use opentelemetry::{
+    global,
+    propagation::{Extractor, Injector},
+    trace::{noop::NoopTracerProvider, SpanKind, Status, TraceContextExt, Tracer},
+    Context, KeyValue,
+};
 use opentelemetry_otlp::{SpanExporter, WithExportConfig};
 use opentelemetry_sdk::{propagation::TraceContextPropagator, trace as sdktrace, Resource};
-use std::{collections::HashMap, sync::OnceLock};
+use std::{collections::HashMap, ops::Deref, sync::OnceLock};
+use tracing::{error, info};
+
+// W3C trace context keys carried in MQTT 5 user properties
+pub const TRACE_PARENT: &str = "traceparent";
+pub const TRACE_STATE: &str = "tracestate";
+
+const TRACER_NAME: &str = "robustmq";
 
-// Prepare to support three types
-// 1. For performance, turn it off
-// 2. For debugging, output to standard output
-// 3. For release, output to otlp
 #[derive(Debug)]
 pub enum TraceExporterProvider {
     Noop(NoopTracerProvider),
@@ -31,27 +38,44 @@ pub enum TraceExporterProvider {
 
 static GLOBAL_PROVIDER: OnceLock<TraceExporterProvider> = OnceLock::new();
 
-pub async fn init_tracer_provider(broker_config: &'static BrokerMqttConfig) {
-    if !broker_config.telemetry.enable {
+pub async fn init_tracer_provider(enable: bool, exporter_type: &str, exporter_endpoint: &str) {
+    if !enable {
         global::set_tracer_provider(NoopTracerProvider::new());
         return;
     }
-    match broker_config.telemetry.exporter_type.as_str() {
+    match exporter_type {
         "otlp" => {
-            let exporter = SpanExporter::builder()
+            let exporter = match SpanExporter::builder()
                 .with_tonic()
-                .with_endpoint(broker_config.telemetry.exporter_endpoint.as_str())
+                .with_endpoint(exporter_endpoint)
                 .build()
-                .unwrap();
+            {
+                Ok(exporter) => exporter,
+                Err(e) => {
+                    error!(
+                        "Failed to build OTLP span exporter for {}, tracing is disabled, error message: {}",
+                        exporter_endpoint, e
+                    );
+                    global::set_tracer_provider(NoopTracerProvider::new());
+                    return;
+                }
+            };
             global::set_text_map_propagator(TraceContextPropagator::new());
             let provider = sdktrace::SdkTracerProvider::builder()
                 .with_batch_exporter(exporter)
                 .with_resource(Resource::builder().with_service_name("robustmq").build())
                 .build();
-            GLOBAL_PROVIDER
+            if GLOBAL_PROVIDER
                 .set(TraceExporterProvider::Otlp(provider.clone()))
-                .unwrap();
-            global::set_tracer_provider(provider.clone());
+                .is_err()
+            {
+                return;
+            }
+            global::set_tracer_provider(provider);
+            info!(
+                "OpenTelemetry tracing exports spans to {}",
+                exporter_endpoint
+            );
         }
         _ => {
             global::set_tracer_provider(NoopTracerProvider::new());
@@ -63,7 +87,9 @@ pub async fn stop_tracer_provider() {
     if let Some(provider) = GLOBAL_PROVIDER.get() {
         match provider {
             TraceExporterProvider::Otlp(provider) => {
-                provider.shutdown().unwrap();
+                if let Err(e) = provider.shutdown() {
+                    error!("Failed to stop tracer provider, error message: {}", e);
+                }
             }
             TraceExporterProvider::Noop(_provider) => {
                 // Ignore
@@ -75,6 +101,82 @@ pub async fn stop_tracer_provider() {
     }
 }
 
+pub fn is_tracing_enable() -> bool {
+    matches!(GLOBAL_PROVIDER.get(), Some(TraceExporterProvider::Otlp(_)))
+}
+
+// Start a span as a child of `parent`. The span ends when the returned context
+// and all its clones are dropped, or when `end_span` is called.
+pub fn start_span(
+    name: &'static str,
+    parent: &Context,
+    kind: SpanKind,
+    attributes: Vec<KeyValue>,
+) -> Context {
+    let tracer = global::tracer(TRACER_NAME);
+    let span = tracer
+        .span_builder(name)
+        .with_kind(kind)
+        .with_attributes(attributes)
+        .start_with_context(&tracer, parent);
+    parent.with_span(span)
+}
+
+pub fn end_span(cx: &Context) {
+    cx.span().end();
+}
+
+// Ends the span when it goes out of scope, so that every return path of a handler
+// ends it.
+pub struct SpanGuard(Context);
+
+impl SpanGuard {
+    pub fn new(cx: Context) -> Self {
+        SpanGuard(cx)
+    }
+}
+
+impl Deref for SpanGuard {
+    type Target = Context;
+
+    fn deref(&self) -> &Context {
+        &self.0
+    }
+}
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        end_span(&self.0);
+    }
+}
+
+pub fn record_span_error(cx: &Context, message: String) {
+    cx.span().set_status(Status::error(message));
+}
+
+// Read the W3C trace context from MQTT 5 user properties
+pub fn extract_context(user_properties: &[(String, String)]) -> Context {
+    if !is_tracing_enable() {
+        return Context::new();
+    }
+    let carrier = CustomContext::from_user_properties(user_properties);
+    global::get_text_map_propagator(|propagator| propagator.extract(&carrier))
+}
+
+// Write the W3C trace context of `cx` into MQTT 5 user properties, replacing the
+// one set by the previous hop. Properties are left untouched when tracing is off.
+pub fn inject_context(cx: &Context, user_properties: &mut Vec<(String, String)>) {
+    let mut carrier = CustomContext::new();
+    global::get_text_map_propagator(|propagator| propagator.inject_context(cx, &mut carrier));
+    if carrier.inner.is_empty() {
+        return;
+    }
+    user_properties.retain(|(key, _)| key != TRACE_PARENT && key != TRACE_STATE);
+    let mut values: Vec<(String, String)> = carrier.inner.into_iter().collect();
+    values.sort();
+    user_properties.extend(values);
+}
+
 pub struct CustomContext {
     pub inner: HashMap<String, String>,
 }
@@ -94,12 +196,27 @@ fn keys(&self) -> Vec<&str> {
     }
 }
 
+impl Injector for CustomContext {
+    fn set(&mut self, key: &str, value: String) {
+        self.inner.insert(key.to_owned(), value);
+    }
+}
+
 impl CustomContext {
     pub fn new() -> Self {
         CustomContext {
             inner: HashMap::new(),
         }
     }
+
+    pub fn from_user_properties(user_properties: &[(String, String)]) -> Self {
+        let inner = user_properties
+            .iter()
+            .filter(|(key, _)| key == TRACE_PARENT || key == TRACE_STATE)
+            .cloned()
+            .collect();
+        CustomContext { inner }
+    }
 }
 
 impl Default for CustomContext {
@@ -110,31 +227,58 @@ fn default() -> Self {
 
 #[cfg(test)]
 mod tests {
-    use crate::config::broker_mqtt::broker_mqtt_conf;
-    use crate::config::broker_mqtt::init_broker_mqtt_conf_by_path;
-    use std::path::PathBuf;
-
-    use super::init_tracer_provider;
+    use super::{inject_context, CustomContext, TRACE_PARENT};
+    use opentelemetry::{
+        global,
+        propagation::TextMapPropagator,
+        trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState},
+        Context,
+    };
+    use opentelemetry_sdk::propagation::TraceContextPropagator;
 
     #[test]
-    fn telemetry_config_test() {
-        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-        path.push("../../../example/mqtt-cluster/mqtt-server/node-1.toml");
-        init_broker_mqtt_conf_by_path(path.to_str().unwrap());
-        let telemetry_config = &broker_mqtt_conf().telemetry;
-        assert!(!telemetry_config.enable);
+    fn user_properties_propagation_test() {
+        global::set_text_map_propagator(TraceContextPropagator::new());
+        let span_context = SpanContext::new(
+            TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap(),
+            SpanId::from_hex("00f067aa0ba902b7").unwrap(),
+            TraceFlags::SAMPLED,
+            true,
+            TraceState::default(),
+        );
+        let cx = Context::new().with_remote_span_context(span_context);
+
+        let mut user_properties = vec![
+            ("k1".to_string(), "v1".to_string()),
+            (TRACE_PARENT.to_string(), "stale".to_string()),
+        ];
+        inject_context(&cx, &mut user_properties);
+        let trace_parents: Vec<&String> = user_properties
+            .iter()
+            .filter(|(key, _)| key == TRACE_PARENT)
+            .map(|(_, value)| value)
+            .collect();
+        assert_eq!(
+            trace_parents,
+            vec!["00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"]
+        );
+        assert!(user_properties.contains(&("k1".to_string(), "v1".to_string())));
+
+        let carrier = CustomContext::from_user_properties(&user_properties);
+        let extracted = TraceContextPropagator::new().extract(&carrier);
         assert_eq!(
-            telemetry_config.exporter_endpoint,
-            "grpc://127.0.0.1:4317".to_string()
+            extracted.span().span_context().trace_id(),
+            TraceId::from_hex("4bf92f3577b34da6a3ce929d0e0e4736").unwrap()
         );
-        assert_eq!(telemetry_config.exporter_type, "otlp".to_string());
     }
 
-    #[tokio::test]
-    async fn telemetry_test_init() {
-        let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-        path.push("../../../example/mqtt-cluster/mqtt-server/node-1.toml");
-        init_broker_mqtt_conf_by_path(path.to_str().unwrap());
-        init_tracer_provider(broker_mqtt_conf()).await
+    #[test]
+    fn inject_without_context_test() {
+        let mut user_properties = vec![(TRACE_PARENT.to_string(), "v1".to_string())];
+        inject_context(&Context::new(), &mut user_properties);
+        assert_eq!(
+            user_properties,
+            vec![(TRACE_PARENT.to_string(), "v1".to_string())]
+        );
     }
 }
@@ -65,11 +65,20 @@ pub fn default_prometheus() -> Prometheus {
     }
 }
 
+pub fn default_telemetry() -> Telemetry {
+    Telemetry {
+        enable: false,
+        exporter_type: "otlp".to_string(),
+        exporter_endpoint: "grpc://127.0.0.1:4317".to_string(),
+    }
+}
+
 pub fn default_pprof() -> PProf {
     PProf {
         enable: false,
         port: default_pprof_port(),
         frequency: default_pprof_frequency(),
+        lock_hold_threshold_ms: default_pprof_lock_hold_threshold_ms(),
     }
 }
 
@@ -85,6 +94,10 @@ pub fn default_pprof_frequency() -> i32 {
     100
 }
 
+pub fn default_pprof_lock_hold_threshold_ms() -> u64 {
+    10
+}
+
 pub fn default_log() -> Log {
     Log {
         log_path: "./logs".to_string(),
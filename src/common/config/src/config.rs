@@ -13,20 +13,27 @@
 // limitations under the License.
 
 use super::default::{
-    default_broker_id, default_cluster_name, default_flapping_detect, default_grpc_port,
-    default_journal_runtime, default_journal_server, default_journal_storage, default_meta_addrs,
-    default_mqtt_auth_config, default_mqtt_keep_alive, default_mqtt_message_storage,
-    default_mqtt_offline_message, default_mqtt_protocol_config, default_mqtt_runtime,
-    default_mqtt_schema, default_mqtt_security, default_mqtt_server,
-    default_mqtt_slow_subscribe_config, default_mqtt_system_monitor, default_network,
+    default_alarm_history_retention_sec, default_broker_id, default_cluster_name,
+    default_encryption, default_flapping_detect, default_grpc_port, default_journal_runtime,
+    default_journal_server, default_journal_storage, default_meta_addrs, default_meta_raft,
+    default_mqtt_auth_config, default_mqtt_connection_balance, default_mqtt_delivery_log,
+    default_mqtt_keep_alive, default_mqtt_message_storage, default_mqtt_offline_message,
+    default_mqtt_protocol_config, default_mqtt_runtime, default_mqtt_schema, default_mqtt_security,
+    default_mqtt_server, default_mqtt_shared_subscription, default_mqtt_slow_subscribe_config,
+    default_mqtt_system_monitor, default_mqtt_topic_metrics, default_mqtt_webhook, default_network,
     default_place_runtime, default_rocksdb, default_roles, default_runtime,
 };
 use super::security::{AuthnConfig, AuthzConfig};
 use crate::common::Log;
 use crate::common::Prometheus;
-use crate::common::{default_log, default_pprof, default_prometheus};
+use crate::common::Telemetry;
+use crate::common::{
+    default_log, default_pprof, default_pprof_lock_hold_threshold_ms, default_prometheus,
+    default_telemetry,
+};
 use common_base::enum_type::delay_type::DelayType;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use toml::Table;
 
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
@@ -62,13 +69,22 @@ pub struct BrokerConfig {
     #[serde(default = "default_pprof")]
     pub p_prof: PProf,
 
+    #[serde(default = "default_telemetry")]
+    pub telemetry: Telemetry,
+
     // meta
     #[serde(default = "default_place_runtime")]
     pub meta_runtime: MetaRuntime,
 
+    #[serde(default = "default_meta_raft")]
+    pub meta_raft: MetaRaft,
+
     #[serde(default = "default_rocksdb")]
     pub rocksdb: Rocksdb,
 
+    #[serde(default = "default_encryption")]
+    pub encryption: Encryption,
+
     // Journal Engine
     #[serde(default = "default_journal_server")]
     pub journal_server: JournalServer,
@@ -115,6 +131,21 @@ pub struct BrokerConfig {
 
     #[serde(default = "default_mqtt_system_monitor")]
     pub mqtt_system_monitor: MqttSystemMonitor,
+
+    #[serde(default = "default_mqtt_connection_balance")]
+    pub mqtt_connection_balance: MqttConnectionBalance,
+
+    #[serde(default = "default_mqtt_shared_subscription")]
+    pub mqtt_shared_subscription: MqttSharedSubscription,
+
+    #[serde(default = "default_mqtt_delivery_log")]
+    pub mqtt_delivery_log: MqttDeliveryLog,
+
+    #[serde(default = "default_mqtt_topic_metrics")]
+    pub mqtt_topic_metrics: MqttTopicMetrics,
+
+    #[serde(default = "default_mqtt_webhook")]
+    pub mqtt_webhook: MqttWebhookConfig,
 }
 
 impl BrokerConfig {
@@ -176,10 +207,43 @@ pub struct Rocksdb {
     pub max_open_files: i32,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct Encryption {
+    pub enable: bool,
+    pub key_file: String,
+    pub key_reload_interval_sec: u64,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct MetaRuntime {
     pub heartbeat_timeout_ms: u64,
     pub heartbeat_check_time_ms: u64,
+    #[serde(default)]
+    pub read_policy: MetaReadPolicy,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct MetaRaft {
+    // Build a new snapshot once this many logs were applied since the last one
+    pub snapshot_logs_since_last: u64,
+    // Size of each chunk when a snapshot is streamed to a lagging follower
+    pub snapshot_max_chunk_size: u64,
+    // Timeout of sending a single snapshot chunk
+    pub install_snapshot_timeout_ms: u64,
+    // Logs already covered by the snapshot that are kept for slow followers
+    pub max_in_snapshot_log_to_keep: u64,
+    // Minimal number of logs purged in one go
+    pub purge_batch_size: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MetaReadPolicy {
+    // Confirm leadership with a quorum round trip before serving the read
+    #[default]
+    ReadIndex,
+    // Trust the leader lease, cheaper but relies on bounded clock drift
+    LeaseRead,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
@@ -223,6 +287,49 @@ pub struct MqttRuntime {
     pub max_connection_num: usize,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct MqttConnectionBalance {
+    pub enable: bool,
+    // A broker is overloaded once it holds this many times the cluster average
+    pub overload_ratio: f64,
+    // Brokers holding fewer connections than this never redirect clients
+    pub min_connection_num: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShareSubStrategy {
+    Random,
+    #[default]
+    RoundRobin,
+    // Keep sending to the same member until it goes away
+    Sticky,
+    // Messages of the same publisher always go to the same member
+    HashClientId,
+    // Messages of the same topic always go to the same member
+    HashTopic,
+    // Send to the member with the fewest unacknowledged QoS 1/2 messages
+    LeastInflight,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct MqttSharedSubscription {
+    #[serde(default)]
+    pub default_strategy: ShareSubStrategy,
+    // (group_name, strategy) for groups that do not use the default strategy
+    #[serde(default)]
+    pub group_strategies: HashMap<String, ShareSubStrategy>,
+}
+
+impl MqttSharedSubscription {
+    pub fn strategy(&self, group_name: &str) -> ShareSubStrategy {
+        self.group_strategies
+            .get(group_name)
+            .copied()
+            .unwrap_or(self.default_strategy)
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct MqttSystemMonitor {
     pub enable: bool,
@@ -230,6 +337,43 @@ pub struct MqttSystemMonitor {
     pub os_cpu_high_watermark: f32,
 
     pub os_memory_high_watermark: f32,
+
+    // Raise an alarm once a share group or connector falls this many messages
+    // behind its topic, 0 disables the check
+    #[serde(default)]
+    pub consumer_lag_watermark: u64,
+
+    // Alarm history kept in meta-service expires after this many seconds
+    #[serde(default = "default_alarm_history_retention_sec")]
+    pub alarm_history_retention_sec: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct MqttDeliveryLog {
+    pub enable: bool,
+    // Events older than this are removed
+    pub retention_sec: u64,
+    // Oldest events are removed once more than this many are kept
+    pub max_events: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct MqttTopicMetrics {
+    // Hard cap on the number of topics exported with per-topic series
+    pub max_topics: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct MqttWebhookConfig {
+    // Events sent to an endpoint in one request
+    pub batch_size: usize,
+    // How often the pending events are sent
+    pub batch_interval_ms: u64,
+    // Retries of a failed request before its events are dropped
+    pub max_retries: u32,
+    pub request_timeout_ms: u64,
+    // Pending events kept per endpoint, the oldest are dropped beyond it
+    pub queue_capacity: usize,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
@@ -314,6 +458,9 @@ pub struct PProf {
     pub enable: bool,
     pub port: u16,
     pub frequency: i32,
+    // Lock holds at least this long are sampled, 0 disables lock sampling
+    #[serde(default = "default_pprof_lock_hold_threshold_ms")]
+    pub lock_hold_threshold_ms: u64,
 }
 
 #[derive(Serialize, Deserialize, Default, Clone, Debug)]
@@ -14,13 +14,16 @@
 
 use super::security::{AuthnConfig, AuthzConfig};
 use crate::config::{
-    JournalRuntime, JournalServer, JournalStorage, MetaRuntime, MqttAuthConfig, MqttFlappingDetect,
+    Encryption, JournalRuntime, JournalServer, JournalStorage, MetaRaft, MetaReadPolicy,
+    MetaRuntime, MqttAuthConfig, MqttConnectionBalance, MqttDeliveryLog, MqttFlappingDetect,
     MqttKeepAlive, MqttMessageStorage, MqttOfflineMessage, MqttProtocolConfig, MqttRuntime,
-    MqttSchema, MqttSecurity, MqttServer, MqttSlowSubscribeConfig, MqttSystemMonitor, Network,
-    Rocksdb, Runtime, SchemaFailedOperation, SchemaStrategy,
+    MqttSchema, MqttSecurity, MqttServer, MqttSharedSubscription, MqttSlowSubscribeConfig,
+    MqttSystemMonitor, MqttTopicMetrics, MqttWebhookConfig, Network, Rocksdb, Runtime,
+    SchemaFailedOperation, SchemaStrategy, ShareSubStrategy,
 };
 use common_base::enum_type::delay_type::DelayType;
 use common_base::runtime::get_runtime_worker_threads;
+use std::collections::HashMap;
 use toml::Table;
 
 pub fn default_roles() -> Vec<String> {
@@ -74,10 +77,29 @@ pub fn default_rocksdb() -> Rocksdb {
     }
 }
 
+pub fn default_encryption() -> Encryption {
+    Encryption {
+        enable: false,
+        key_file: "./config/master.key".to_string(),
+        key_reload_interval_sec: 60,
+    }
+}
+
 pub fn default_place_runtime() -> MetaRuntime {
     MetaRuntime {
         heartbeat_check_time_ms: 1000,
         heartbeat_timeout_ms: 30000,
+        read_policy: MetaReadPolicy::ReadIndex,
+    }
+}
+
+pub fn default_meta_raft() -> MetaRaft {
+    MetaRaft {
+        snapshot_logs_since_last: 5000,
+        snapshot_max_chunk_size: 3 * 1024 * 1024,
+        install_snapshot_timeout_ms: 3000,
+        max_in_snapshot_log_to_keep: 1000,
+        purge_batch_size: 1,
     }
 }
 
@@ -125,6 +147,43 @@ pub fn default_mqtt_runtime() -> MqttRuntime {
     }
 }
 
+pub fn default_mqtt_connection_balance() -> MqttConnectionBalance {
+    MqttConnectionBalance {
+        enable: false,
+        overload_ratio: 1.2,
+        min_connection_num: 1000,
+    }
+}
+
+pub fn default_mqtt_shared_subscription() -> MqttSharedSubscription {
+    MqttSharedSubscription {
+        default_strategy: ShareSubStrategy::RoundRobin,
+        group_strategies: HashMap::new(),
+    }
+}
+
+pub fn default_mqtt_delivery_log() -> MqttDeliveryLog {
+    MqttDeliveryLog {
+        enable: false,
+        retention_sec: 3600,
+        max_events: 1000000,
+    }
+}
+
+pub fn default_mqtt_topic_metrics() -> MqttTopicMetrics {
+    MqttTopicMetrics { max_topics: 1000 }
+}
+
+pub fn default_mqtt_webhook() -> MqttWebhookConfig {
+    MqttWebhookConfig {
+        batch_size: 100,
+        batch_interval_ms: 1000,
+        max_retries: 3,
+        request_timeout_ms: 5000,
+        queue_capacity: 10000,
+    }
+}
+
 pub fn default_mqtt_offline_message() -> MqttOfflineMessage {
     MqttOfflineMessage {
         enable: true,
@@ -185,9 +244,15 @@ pub fn default_mqtt_system_monitor() -> MqttSystemMonitor {
         enable: false,
         os_cpu_high_watermark: 70.0,
         os_memory_high_watermark: 80.0,
+        consumer_lag_watermark: 10000,
+        alarm_history_retention_sec: default_alarm_history_retention_sec(),
     }
 }
 
+pub fn default_alarm_history_retention_sec() -> u64 {
+    7 * 24 * 3600
+}
+
 pub fn default_journal_server() -> JournalServer {
     JournalServer { tcp_port: 1778 }
 }
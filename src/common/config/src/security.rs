@@ -47,7 +47,7 @@ pub struct PasswordBasedConfig {
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct PasswordConfig {
     pub credential_type: String, // username/clientid (only for placement)
-    pub algorithm: String,       // plain/md5/sha/sha256/sha512/bcrypt/pbkdf2
+    pub algorithm: String,       // plain/md5/sha/sha256/sha512/bcrypt/pbkdf2/argon2
     pub salt_position: Option<String>, // disable/prefix/suffix
     pub salt_rounds: Option<u32>, // bcrypt need
     pub mac_fun: Option<String>, // pbkdf2 need
@@ -71,6 +71,11 @@ pub fn set_header(&mut self, headers: Vec<Header>) {
         self.header = headers;
     }
 
+    pub fn set_data(&mut self, data: Vec<u8>) {
+        self.crc_num = calc_crc32(&data);
+        self.data = data;
+    }
+
     pub fn set_key(&mut self, key: String) {
         self.key = key;
     }
@@ -0,0 +1,90 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AlarmMetric {
+    // Current number of MQTT connections of the broker
+    ConnectionNum,
+    // Largest lag of the share groups and connectors on the broker
+    ConsumerLag,
+    // Bytes used by the broker RocksDB data path
+    RocksDBSize,
+    // Highest disk usage percentage among the journal data paths
+    JournalDiskUsage,
+    // Failed MQTT authentications per second
+    AuthFailureRate,
+    // CPU usage percentage of the broker process
+    CpuUsage,
+    // Memory usage percentage of the broker process
+    MemoryUsage,
+}
+
+impl Display for AlarmMetric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "sink_type")]
+pub enum AlarmSink {
+    // POST the alarm event as JSON to the url
+    Webhook { url: String },
+    // Publish the alarm event to an MQTT topic
+    MqttTopic { topic_name: String },
+    // Publish the alarm event to the topic the connector reads from
+    Connector { connector_name: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MqttAlarmRule {
+    pub cluster_name: String,
+    pub name: String,
+    pub metric: AlarmMetric,
+    // The alarm activates once the metric reaches this value
+    pub activate_threshold: f64,
+    // and deactivates once it falls back to this one
+    pub deactivate_threshold: f64,
+    pub sinks: Vec<AlarmSink>,
+    pub enable: bool,
+    pub create_time: u64,
+}
+
+impl MqttAlarmRule {
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MqttAlarmHistory {
+    pub cluster_name: String,
+    pub rule_name: String,
+    pub broker_id: u64,
+    pub metric: AlarmMetric,
+    pub value: f64,
+    pub threshold: f64,
+    pub activated: bool,
+    pub message: String,
+    pub create_time: u64,
+}
+
+impl MqttAlarmHistory {
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+}
@@ -25,6 +25,10 @@ pub struct MQTTConnector {
     pub topic_name: String,
     pub status: MQTTStatus,
     pub broker_id: Option<u64>,
+    // Deliver payloads in this format instead of the published one, see
+    // `SchemaRegisterManager::transcode`
+    #[serde(default)]
+    pub payload_format: Option<String>,
     pub create_time: u64,
     pub update_time: u64,
 }
@@ -19,7 +19,11 @@
 use serde::{Deserialize, Serialize};
 use tracing::error;
 
-use crate::adapter::record::Record;
+use crate::adapter::record::{Header, Record};
+
+// Record header holding the publisher's client id, so it can be read without
+// decoding the message.
+pub const MQTT_RECORD_HEADER_CLIENT_ID: &str = "client_id";
 
 #[derive(Clone, Serialize, Deserialize, Default, Debug)]
 pub struct MqttMessage {
@@ -110,7 +114,14 @@ pub fn build_record(
         let msg =
             MqttMessage::build_message(client_id, publish, publish_properties, expiry_interval);
         match serde_json::to_vec(&msg) {
-            Ok(data) => Some(Record::build_byte(data)),
+            Ok(data) => {
+                let mut record = Record::build_byte(data);
+                record.set_header(vec![Header {
+                    name: MQTT_RECORD_HEADER_CLIENT_ID.to_string(),
+                    value: client_id.to_string(),
+                }]);
+                Some(record)
+            }
 
             Err(e) => {
                 error!("Message encoding failed, error message :{}", e.to_string());
@@ -119,6 +130,20 @@ pub fn build_record(
         }
     }
 
+    // Records written before the header was added have to be decoded.
+    pub fn client_id_of_record(record: &Record) -> Option<String> {
+        if let Some(header) = record
+            .header
+            .iter()
+            .find(|header| header.name == MQTT_RECORD_HEADER_CLIENT_ID)
+        {
+            return Some(header.value.clone());
+        }
+        MqttMessage::decode_record(record.clone())
+            .ok()
+            .map(|msg| msg.client_id)
+    }
+
     pub fn decode_record(record: Record) -> Result<MqttMessage, CommonError> {
         let data: MqttMessage = match serde_json::from_slice(record.data.as_slice()) {
             Ok(da) => da,
@@ -258,7 +283,12 @@ fn test_build_record() {
         let record = MqttMessage::build_record(client_id, &publish, &None, 0);
         assert!(record.is_some());
 
-        let decoded = MqttMessage::decode_record(record.unwrap()).unwrap();
+        let record = record.unwrap();
+        assert_eq!(
+            MqttMessage::client_id_of_record(&record),
+            Some(client_id.to_string())
+        );
+        let decoded = MqttMessage::decode_record(record).unwrap();
         assert_eq!(decoded.client_id, client_id);
         assert_eq!(decoded.topic, publish.topic);
         assert_eq!(decoded.payload, publish.payload);
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod alarm;
 pub mod auto_subscribe_rule;
 pub mod bridge;
 pub mod connection;
@@ -24,3 +25,4 @@
 pub mod topic;
 pub mod topic_rewrite_rule;
 pub mod user;
+pub mod webhook;
@@ -0,0 +1,71 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum WebhookEventType {
+    #[serde(rename = "client.connected")]
+    ClientConnected,
+    #[serde(rename = "client.disconnected")]
+    ClientDisconnected,
+    #[serde(rename = "session.subscribed")]
+    SessionSubscribed,
+    #[serde(rename = "session.unsubscribed")]
+    SessionUnsubscribed,
+    #[serde(rename = "session.expired")]
+    SessionExpired,
+    #[serde(rename = "message.publish")]
+    MessagePublish,
+}
+
+impl WebhookEventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEventType::ClientConnected => "client.connected",
+            WebhookEventType::ClientDisconnected => "client.disconnected",
+            WebhookEventType::SessionSubscribed => "session.subscribed",
+            WebhookEventType::SessionUnsubscribed => "session.unsubscribed",
+            WebhookEventType::SessionExpired => "session.expired",
+            WebhookEventType::MessagePublish => "message.publish",
+        }
+    }
+}
+
+impl Display for WebhookEventType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MqttWebhook {
+    pub cluster_name: String,
+    pub name: String,
+    pub url: String,
+    // Key of the HMAC-SHA256 signature, requests are not signed when empty
+    pub secret: String,
+    pub events: Vec<WebhookEventType>,
+    // Restrict the events carrying a topic to these topic filters, all topics when empty
+    pub topic_filters: Vec<String>,
+    pub enable: bool,
+    pub create_time: u64,
+}
+
+impl MqttWebhook {
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+}
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
 use serde::{Deserialize, Serialize};
 
@@ -23,6 +23,13 @@ pub struct SchemaData {
     pub schema_type: SchemaType,
     pub desc: String,
     pub schema: String,
+    // Fully qualified message name used to validate protobuf payloads
+    #[serde(default)]
+    pub message_name: String,
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
+    pub compatibility: SchemaCompatibility,
 }
 
 impl SchemaData {
@@ -60,3 +67,37 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         }
     }
 }
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Default)]
+pub enum SchemaCompatibility {
+    #[default]
+    None,
+    Backward,
+    Forward,
+    Full,
+}
+
+impl Display for SchemaCompatibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaCompatibility::None => write!(f, "none"),
+            SchemaCompatibility::Backward => write!(f, "backward"),
+            SchemaCompatibility::Forward => write!(f, "forward"),
+            SchemaCompatibility::Full => write!(f, "full"),
+        }
+    }
+}
+
+impl FromStr for SchemaCompatibility {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "" | "none" => Ok(SchemaCompatibility::None),
+            "backward" => Ok(SchemaCompatibility::Backward),
+            "forward" => Ok(SchemaCompatibility::Forward),
+            "full" => Ok(SchemaCompatibility::Full),
+            _ => Err(format!("unsupported schema compatibility: {s}")),
+        }
+    }
+}
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{counter_metric_inc, register_counter_metric};
+use crate::{counter_metric_get, counter_metric_inc, register_counter_metric};
 use prometheus_client::encoding::EncodeLabelSet;
 
 #[derive(Eq, Hash, Clone, EncodeLabelSet, Debug, PartialEq)]
@@ -63,6 +63,13 @@ pub fn record_mqtt_auth_failed() {
     counter_metric_inc!(MQTT_AUTH_FAILED, label);
 }
 
+pub fn get_mqtt_auth_failed() -> u64 {
+    let label = AuthLabel {};
+    let mut result = 0;
+    counter_metric_get!(MQTT_AUTH_FAILED, label, result);
+    result
+}
+
 pub fn record_mqtt_acl_success() {
     let label = AuthLabel {};
     counter_metric_inc!(MQTT_ACL_SUCCESS, label);
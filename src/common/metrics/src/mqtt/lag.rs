@@ -0,0 +1,79 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+
+use crate::{gauge_metric_get, gauge_metric_set, register_gauge_metric};
+use prometheus_client::encoding::EncodeLabelSet;
+
+#[derive(Eq, Hash, Clone, EncodeLabelSet, Debug, PartialEq)]
+pub struct ConsumerLagLabel {
+    // share_group or connector
+    pub consumer_type: String,
+    pub group: String,
+    pub topic: String,
+}
+
+register_gauge_metric!(
+    MQTT_CONSUMER_LAG,
+    "mqtt_consumer_lag",
+    "Number of messages of the topic not yet consumed by the group",
+    ConsumerLagLabel
+);
+
+pub fn record_consumer_lag(consumer_type: &str, group: &str, topic: &str, lag: i64) {
+    let label = ConsumerLagLabel {
+        consumer_type: consumer_type.to_string(),
+        group: group.to_string(),
+        topic: topic.to_string(),
+    };
+    gauge_metric_set!(MQTT_CONSUMER_LAG, label, lag);
+}
+
+pub fn get_consumer_lag(consumer_type: &str, group: &str, topic: &str) -> i64 {
+    let label = ConsumerLagLabel {
+        consumer_type: consumer_type.to_string(),
+        group: group.to_string(),
+        topic: topic.to_string(),
+    };
+    let mut lag = 0;
+    gauge_metric_get!(MQTT_CONSUMER_LAG, label, lag);
+    lag
+}
+
+pub fn remove_consumer_lag(consumer_type: &str, group: &str, topic: &str) {
+    let label = ConsumerLagLabel {
+        consumer_type: consumer_type.to_string(),
+        group: group.to_string(),
+        topic: topic.to_string(),
+    };
+    MQTT_CONSUMER_LAG.write().unwrap().remove(&label);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_consumer_lag() {
+        record_consumer_lag("share_group", "g1", "test/lag", 12);
+        assert_eq!(get_consumer_lag("share_group", "g1", "test/lag"), 12);
+        assert_eq!(get_consumer_lag("connector", "g1", "test/lag"), 0);
+
+        record_consumer_lag("share_group", "g1", "test/lag", 3);
+        assert_eq!(get_consumer_lag("share_group", "g1", "test/lag"), 3);
+
+        remove_consumer_lag("share_group", "g1", "test/lag");
+        assert_eq!(get_consumer_lag("share_group", "g1", "test/lag"), 0);
+    }
+}
@@ -14,6 +14,7 @@
 
 pub mod auth;
 pub mod event;
+pub mod lag;
 pub mod packets;
 pub mod publish;
 pub mod session;
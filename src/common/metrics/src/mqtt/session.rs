@@ -32,6 +32,13 @@ pub struct NetworkLabel {}
     NetworkLabel
 );
 
+register_counter_metric!(
+    MQTT_SESSION_TAKEN_OVER,
+    "mqtt_session_taken_over",
+    "Number of MQTT sessions taken over by another connection",
+    NetworkLabel
+);
+
 pub fn record_mqtt_session_created() {
     let label = NetworkLabel {};
     counter_metric_inc!(MQTT_SESSION_CREATED, label);
@@ -41,3 +48,8 @@ pub fn record_mqtt_session_deleted() {
     let label = NetworkLabel {};
     counter_metric_inc!(MQTT_SESSION_DELETED, label);
 }
+
+pub fn record_mqtt_session_taken_over() {
+    let label = NetworkLabel {};
+    counter_metric_inc!(MQTT_SESSION_TAKEN_OVER, label);
+}
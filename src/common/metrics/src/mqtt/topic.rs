@@ -12,7 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{counter_metric_inc, counter_metric_inc_by, register_counter_metric};
+use crate::{
+    counter_metric_get, counter_metric_inc, counter_metric_inc_by, gauge_metric_get,
+    gauge_metric_set, register_counter_metric, register_gauge_metric,
+};
 use prometheus_client::encoding::EncodeLabelSet;
 
 #[derive(Eq, Hash, Clone, EncodeLabelSet, Debug, PartialEq)]
@@ -48,6 +51,27 @@ pub struct TopicLabel {
     TopicLabel
 );
 
+register_counter_metric!(
+    TOPIC_MESSAGES_DROPPED,
+    "topic_messages_dropped",
+    "Total number of messages of topic that were dropped",
+    TopicLabel
+);
+
+register_counter_metric!(
+    TOPIC_MESSAGES_RETAINED,
+    "topic_messages_retained",
+    "Total number of retained messages written to topic",
+    TopicLabel
+);
+
+register_gauge_metric!(
+    TOPIC_SUBSCRIBER_NUM,
+    "topic_subscriber_num",
+    "Current number of subscribers of topic",
+    TopicLabel
+);
+
 pub fn record_topic_messages_written(topic: &str) {
     let label = TopicLabel {
         topic: topic.to_string(),
@@ -76,6 +100,76 @@ pub fn record_topic_bytes_sent(topic: &str, bytes: u64) {
     counter_metric_inc_by!(TOPIC_BYTES_SENT, label, bytes);
 }
 
+pub fn record_topic_messages_dropped(topic: &str) {
+    let label = TopicLabel {
+        topic: topic.to_string(),
+    };
+    counter_metric_inc!(TOPIC_MESSAGES_DROPPED, label);
+}
+
+pub fn record_topic_messages_retained(topic: &str) {
+    let label = TopicLabel {
+        topic: topic.to_string(),
+    };
+    counter_metric_inc!(TOPIC_MESSAGES_RETAINED, label);
+}
+
+pub fn record_topic_subscriber_num(topic: &str, num: i64) {
+    let label = TopicLabel {
+        topic: topic.to_string(),
+    };
+    gauge_metric_set!(TOPIC_SUBSCRIBER_NUM, label, num);
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TopicMetricsValue {
+    pub messages_written: u64,
+    pub bytes_written: u64,
+    pub messages_sent: u64,
+    pub bytes_sent: u64,
+    pub messages_dropped: u64,
+    pub messages_retained: u64,
+    pub subscriber_num: i64,
+}
+
+pub fn get_topic_metrics(topic: &str) -> TopicMetricsValue {
+    let label = TopicLabel {
+        topic: topic.to_string(),
+    };
+    let mut value = TopicMetricsValue::default();
+    let mut res = 0;
+    counter_metric_get!(TOPIC_MESSAGES_WRITTEN, label, res);
+    value.messages_written = res;
+    counter_metric_get!(TOPIC_BYTES_WRITTEN, label, res);
+    value.bytes_written = res;
+    counter_metric_get!(TOPIC_MESSAGES_SENT, label, res);
+    value.messages_sent = res;
+    counter_metric_get!(TOPIC_BYTES_SENT, label, res);
+    value.bytes_sent = res;
+    counter_metric_get!(TOPIC_MESSAGES_DROPPED, label, res);
+    value.messages_dropped = res;
+    counter_metric_get!(TOPIC_MESSAGES_RETAINED, label, res);
+    value.messages_retained = res;
+    let mut num = 0;
+    gauge_metric_get!(TOPIC_SUBSCRIBER_NUM, label, num);
+    value.subscriber_num = num;
+    value
+}
+
+// Drop every series of the topic so that untracked topics stop being exported
+pub fn remove_topic_metrics(topic: &str) {
+    let label = TopicLabel {
+        topic: topic.to_string(),
+    };
+    TOPIC_MESSAGES_WRITTEN.write().unwrap().remove(&label);
+    TOPIC_BYTES_WRITTEN.write().unwrap().remove(&label);
+    TOPIC_MESSAGES_SENT.write().unwrap().remove(&label);
+    TOPIC_BYTES_SENT.write().unwrap().remove(&label);
+    TOPIC_MESSAGES_DROPPED.write().unwrap().remove(&label);
+    TOPIC_MESSAGES_RETAINED.write().unwrap().remove(&label);
+    TOPIC_SUBSCRIBER_NUM.write().unwrap().remove(&label);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -94,6 +188,28 @@ fn test_topic_metrics() {
         // This is a basic smoke test to ensure functions execute without panic
     }
 
+    #[test]
+    fn test_get_and_remove_topic_metrics() {
+        record_topic_messages_written("test/topic3");
+        record_topic_bytes_written("test/topic3", 10);
+        record_topic_messages_dropped("test/topic3");
+        record_topic_messages_retained("test/topic3");
+        record_topic_subscriber_num("test/topic3", 4);
+
+        let value = get_topic_metrics("test/topic3");
+        assert_eq!(value.messages_written, 1);
+        assert_eq!(value.bytes_written, 10);
+        assert_eq!(value.messages_dropped, 1);
+        assert_eq!(value.messages_retained, 1);
+        assert_eq!(value.subscriber_num, 4);
+
+        remove_topic_metrics("test/topic3");
+        assert_eq!(
+            get_topic_metrics("test/topic3"),
+            TopicMetricsValue::default()
+        );
+    }
+
     #[test]
     fn test_topic_label_equality() {
         let label1 = TopicLabel {
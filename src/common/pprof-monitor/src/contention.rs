@@ -0,0 +1,139 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use common_base::lock_profile::LockHoldStat;
+use pprof::protos::{Function, Line, Location, Profile, Sample, ValueType};
+
+#[derive(Default)]
+struct StringTable {
+    strings: Vec<String>,
+    index: HashMap<String, i64>,
+}
+
+impl StringTable {
+    fn new() -> Self {
+        // pprof requires the first string to be empty
+        let mut table = StringTable::default();
+        table.get("");
+        table
+    }
+
+    fn get(&mut self, value: &str) -> i64 {
+        if let Some(index) = self.index.get(value) {
+            return *index;
+        }
+        let index = self.strings.len() as i64;
+        self.strings.push(value.to_string());
+        self.index.insert(value.to_string(), index);
+        index
+    }
+}
+
+// One sample per call site that took the lock, valued with the number of long
+// holds and their total duration
+pub fn build_lock_hold_profile(stats: &[LockHoldStat]) -> Profile {
+    let mut strings = StringTable::new();
+    let holds_type = ValueType {
+        r#type: strings.get("holds"),
+        unit: strings.get("count"),
+    };
+    let hold_time_type = ValueType {
+        r#type: strings.get("hold_time"),
+        unit: strings.get("nanoseconds"),
+    };
+
+    let mut samples = Vec::with_capacity(stats.len());
+    let mut locations = Vec::with_capacity(stats.len());
+    let mut functions = Vec::with_capacity(stats.len());
+    for (i, stat) in stats.iter().enumerate() {
+        let id = i as u64 + 1;
+        let name = strings.get(&format!("{}:{}", stat.file, stat.line));
+        functions.push(Function {
+            id,
+            name,
+            system_name: name,
+            filename: strings.get(stat.file),
+            start_line: stat.line as i64,
+        });
+        locations.push(Location {
+            id,
+            line: vec![Line {
+                function_id: id,
+                line: stat.line as i64,
+            }],
+            ..Default::default()
+        });
+        samples.push(Sample {
+            location_id: vec![id],
+            value: vec![stat.count as i64, stat.total_ns as i64],
+            ..Default::default()
+        });
+    }
+
+    Profile {
+        sample_type: vec![holds_type.clone(), hold_time_type],
+        sample: samples,
+        location: locations,
+        function: functions,
+        string_table: strings.strings,
+        period_type: Some(holds_type),
+        period: 1,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_base::lock_profile::LockHoldStat;
+
+    use super::build_lock_hold_profile;
+
+    #[test]
+    fn build_lock_hold_profile_test() {
+        let stats = vec![
+            LockHoldStat {
+                file: "src/cache.rs",
+                line: 10,
+                count: 3,
+                total_ns: 30_000_000,
+                max_ns: 20_000_000,
+            },
+            LockHoldStat {
+                file: "src/cache.rs",
+                line: 42,
+                count: 1,
+                total_ns: 15_000_000,
+                max_ns: 15_000_000,
+            },
+        ];
+
+        let profile = build_lock_hold_profile(&stats);
+        assert_eq!(profile.string_table[0], "");
+        assert_eq!(profile.sample.len(), 2);
+        assert_eq!(profile.sample[0].value, vec![3, 30_000_000]);
+        assert_eq!(profile.location.len(), 2);
+
+        let function = &profile.function[1];
+        assert_eq!(
+            profile.string_table[function.name as usize],
+            "src/cache.rs:42"
+        );
+        assert_eq!(
+            profile.string_table[function.filename as usize],
+            "src/cache.rs"
+        );
+    }
+}
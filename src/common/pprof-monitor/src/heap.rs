@@ -0,0 +1,43 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Heap profiles come from jemalloc, which is only the global allocator when
+// built with the heap-profiling feature. jemalloc's dump is converted to the
+// gzipped pprof protobuf format, so `go tool pprof` reads it like the CPU and
+// contention profiles.
+
+#[cfg(feature = "heap-profiling")]
+pub async fn dump_heap_profile() -> Result<Vec<u8>, String> {
+    let Some(prof_ctl) = jemalloc_pprof::PROF_CTL.as_ref() else {
+        return Err("jemalloc profiling is not available".to_string());
+    };
+    let mut prof_ctl = prof_ctl.clone().lock_owned().await;
+    if !prof_ctl.activated() {
+        return Err("jemalloc profiling is not activated".to_string());
+    }
+
+    // the dump writes and parses a file
+    tokio::task::spawn_blocking(move || prof_ctl.dump_pprof())
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "heap-profiling"))]
+pub async fn dump_heap_profile() -> Result<Vec<u8>, String> {
+    Err(
+        "Heap profiling is not enabled, build the broker with --features heap-profiling"
+            .to_string(),
+    )
+}
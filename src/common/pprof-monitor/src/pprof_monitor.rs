@@ -12,23 +12,61 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use axum::http::header;
+use std::sync::{Arc, Mutex};
+use std::{net::SocketAddr, time::Duration};
+
+use axum::extract::{Query, State};
+use axum::http::{header, StatusCode};
 use axum::response::Response;
 use axum::{routing::get, Router};
 use bytes::Bytes;
+use common_base::lock_profile::{lock_hold_stats, reset_lock_hold_stats, set_lock_profile};
+use common_base::runtime::sample_runtime_metrics;
+use pprof::protos::Message;
 use pprof::ProfilerGuard;
-use std::{net::SocketAddr, sync::Arc};
+use serde::Deserialize;
 use tracing::{error, info};
 
-pub async fn start_pprof_monitor(port: u16, frequency: i32) -> Arc<ProfilerGuard<'static>> {
+use crate::contention::build_lock_hold_profile;
+use crate::heap::dump_heap_profile;
+
+const MAX_RUNTIME_SAMPLE_SECS: u64 = 60;
+
+struct PprofState {
+    frequency: i32,
+    // Only one CPU profiler may run in the process
+    guard: Mutex<Option<ProfilerGuard<'static>>>,
+}
+
+#[derive(Deserialize)]
+struct RuntimeQuery {
+    seconds: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct ContentionQuery {
+    reset: Option<bool>,
+}
+
+pub async fn start_pprof_monitor(port: u16, frequency: i32, lock_hold_threshold_ms: u64) {
     info!("Starting pprof HTTP server...");
-    let guard = Arc::new(ProfilerGuard::new(frequency).expect("Failed to start ProfilerGuard"));
-    let guard_clone = Arc::clone(&guard);
+    let guard = ProfilerGuard::new(frequency).expect("Failed to start ProfilerGuard");
+    let state = Arc::new(PprofState {
+        frequency,
+        guard: Mutex::new(Some(guard)),
+    });
 
-    let app = Router::new().route(
-        "/flamegraph",
-        get(move || generate_flamegraph(guard_clone.clone())),
-    );
+    if lock_hold_threshold_ms > 0 {
+        set_lock_profile(true, Duration::from_millis(lock_hold_threshold_ms));
+    }
+
+    let app = Router::new()
+        .route("/flamegraph", get(generate_flamegraph))
+        .route("/debug/pprof/profile", get(cpu_profile))
+        .route("/debug/pprof/heap", get(heap_profile))
+        .route("/debug/pprof/contention", get(contention_profile))
+        .route("/debug/runtime", get(runtime_metrics))
+        .with_state(state);
 
     let addr = SocketAddr::from(([127, 0, 0, 1], port));
     let listener = match tokio::net::TcpListener::bind(addr).await {
@@ -41,17 +79,20 @@ pub async fn start_pprof_monitor(port: u16, frequency: i32) -> Arc<ProfilerGuard
         }
         Err(e) => {
             error!("Failed to bind pprof server: {}", e);
-            return guard;
+            return;
         }
     };
     if let Err(e) = axum::serve(listener, app).await {
         error!("pprof HTTP server failed: {}", e);
     }
-    guard
 }
 
-async fn generate_flamegraph(guard: Arc<ProfilerGuard<'static>>) -> Response {
-    if let Ok(report) = guard.report().build() {
+async fn generate_flamegraph(State(state): State<Arc<PprofState>>) -> Response {
+    let report = match state.guard.lock() {
+        Ok(guard) => guard.as_ref().and_then(|guard| guard.report().build().ok()),
+        Err(_) => None,
+    };
+    if let Some(report) = report {
         let mut buf = Vec::new();
         if report.flamegraph(&mut buf).is_ok() {
             return Response::builder()
@@ -60,10 +101,78 @@ async fn generate_flamegraph(guard: Arc<ProfilerGuard<'static>>) -> Response {
                 .unwrap();
         }
     }
+    error_response(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Failed to generate flamegraph",
+    )
+}
+
+// CPU samples taken since the previous download, the profiler is restarted so
+// that repeated downloads give consecutive windows
+async fn cpu_profile(State(state): State<Arc<PprofState>>) -> Response {
+    let report = match state.guard.lock() {
+        Ok(mut guard) => {
+            let report = guard.as_ref().and_then(|guard| guard.report().build().ok());
+            *guard = None;
+            *guard = ProfilerGuard::new(state.frequency).ok();
+            report
+        }
+        Err(_) => None,
+    };
+
+    match report.map(|report| report.pprof()) {
+        Some(Ok(profile)) => profile_response(profile.encode_to_vec(), "cpu.pb"),
+        _ => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to generate CPU profile",
+        ),
+    }
+}
+
+async fn heap_profile() -> Response {
+    match dump_heap_profile().await {
+        Ok(data) => profile_response(data, "heap.pb.gz"),
+        Err(e) => error_response(StatusCode::NOT_IMPLEMENTED, &e),
+    }
+}
+
+async fn contention_profile(Query(params): Query<ContentionQuery>) -> Response {
+    let profile = build_lock_hold_profile(&lock_hold_stats());
+    if params.reset.unwrap_or(false) {
+        reset_lock_hold_stats();
+    }
+    profile_response(profile.encode_to_vec(), "contention.pb")
+}
+
+async fn runtime_metrics(Query(params): Query<RuntimeQuery>) -> Response {
+    let seconds = params
+        .seconds
+        .unwrap_or(1)
+        .clamp(1, MAX_RUNTIME_SAMPLE_SECS);
+    let samples = sample_runtime_metrics(Duration::from_secs(seconds)).await;
+    match serde_json::to_vec(&samples) {
+        Ok(data) => Response::builder()
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(axum::body::Body::from(data))
+            .unwrap(),
+        Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+    }
+}
+
+fn profile_response(data: Vec<u8>, file_name: &str) -> Response {
+    Response::builder()
+        .header(header::CONTENT_TYPE, "application/octet-stream")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{file_name}\""),
+        )
+        .body(axum::body::Body::from(data))
+        .unwrap()
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response {
     Response::builder()
-        .status(500)
-        .body(axum::body::Body::from(Bytes::from(
-            "Failed to generate flamegraph",
-        )))
+        .status(status)
+        .body(axum::body::Body::from(Bytes::from(message.to_string())))
         .unwrap()
 }
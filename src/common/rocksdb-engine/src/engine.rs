@@ -141,6 +141,45 @@ pub fn rocksdb_engine_list_by_prefix(
     Ok(results)
 }
 
+pub fn rocksdb_engine_list_by_prefix_limit(
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+    column_family: &str,
+    prefix_key_name: String,
+    limit: usize,
+) -> Result<Vec<StorageDataWrap>, CommonError> {
+    let cf = if let Some(cf) = rocksdb_engine_handler.cf_handle(column_family) {
+        cf
+    } else {
+        return Err(CommonError::RocksDBFamilyNotAvailable(
+            column_family.to_string(),
+        ));
+    };
+
+    let mut results = Vec::new();
+    for (_, v) in rocksdb_engine_handler.read_prefix_limit(cf, &prefix_key_name, limit)? {
+        if let Ok(v) = serde_json::from_slice::<StorageDataWrap>(v.as_ref()) {
+            results.push(v);
+        }
+    }
+    Ok(results)
+}
+
+pub fn rocksdb_engine_count_by_prefix(
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+    column_family: &str,
+    prefix_key_name: String,
+) -> Result<u64, CommonError> {
+    let cf = if let Some(cf) = rocksdb_engine_handler.cf_handle(column_family) {
+        cf
+    } else {
+        return Err(CommonError::RocksDBFamilyNotAvailable(
+            column_family.to_string(),
+        ));
+    };
+
+    rocksdb_engine_handler.count_prefix(cf, &prefix_key_name)
+}
+
 pub fn rocksdb_engine_list_by_mode(
     rocksdb_engine_handler: Arc<RocksDBEngine>,
     column_family: &str,
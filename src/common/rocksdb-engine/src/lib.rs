@@ -16,6 +16,7 @@
 use std::sync::Arc;
 
 use common_base::error::common::CommonError;
+use common_security::encryption::{open_value, rewrap_sealed_value, seal_value};
 use rocksdb::{
     BoundColumnFamily, ColumnFamilyDescriptor, DBCompactionStyle, Options, SliceTransform, DB,
 };
@@ -58,9 +59,9 @@ pub fn write<T: Serialize + std::fmt::Debug>(
         key: &str,
         value: &T,
     ) -> Result<(), CommonError> {
-        match serde_json::to_string(&value) {
+        match serde_json::to_vec(&value) {
             Ok(serialized) => {
-                if let Err(e) = self.db.put_cf(&cf.clone(), key, serialized) {
+                if let Err(e) = self.db.put_cf(&cf.clone(), key, seal_value(serialized)?) {
                     return Err(CommonError::CommonError(format!(
                         "Failed to put to ColumnFamily:{e:?}"
                     )));
@@ -90,7 +91,10 @@ pub fn write_raw(
         key: &str,
         value: &[u8],
     ) -> Result<(), CommonError> {
-        if let Err(e) = self.db.put_cf(&cf.clone(), key, value) {
+        if let Err(e) = self
+            .db
+            .put_cf(&cf.clone(), key, seal_value(value.to_vec())?)
+        {
             return Err(CommonError::CommonError(format!(
                 "Failed to put to ColumnFamily:{e:?}"
             )));
@@ -110,7 +114,7 @@ pub fn read<T: DeserializeOwned>(
                     return Ok(None);
                 }
 
-                match serde_json::from_slice::<T>(&found) {
+                match serde_json::from_slice::<T>(&open_value(found)?) {
                     Ok(t) => Ok(Some(t)),
                     Err(err) => Err(CommonError::CommonError(format!(
                         "Failed to deserialize: {err:?}"
@@ -141,7 +145,7 @@ pub fn read_prefix(
                     if !key.starts_with(search_key) {
                         break;
                     }
-                    result.push((key, val.to_vec()));
+                    result.push((key, open_value(val.to_vec())?));
                 }
             }
 
@@ -150,6 +154,54 @@ pub fn read_prefix(
         Ok(result)
     }
 
+    // Search the first `limit` entries by prefix, in key order
+    pub fn read_prefix_limit(
+        &self,
+        cf: Arc<BoundColumnFamily<'_>>,
+        search_key: &str,
+        limit: usize,
+    ) -> Result<Vec<(String, Vec<u8>)>, CommonError> {
+        let mut iter = self.db.raw_iterator_cf(&cf);
+        iter.seek(search_key);
+
+        let mut result = Vec::new();
+        while iter.valid() && result.len() < limit {
+            if let Some(key) = iter.key() {
+                if !key.starts_with(search_key.as_bytes()) {
+                    break;
+                }
+                if let Some(val) = iter.value() {
+                    let key = String::from_utf8(key.to_vec())?;
+                    result.push((key, open_value(val.to_vec())?));
+                }
+            }
+
+            iter.next();
+        }
+        Ok(result)
+    }
+
+    // Count the keys with the prefix without reading the values
+    pub fn count_prefix(
+        &self,
+        cf: Arc<BoundColumnFamily<'_>>,
+        search_key: &str,
+    ) -> Result<u64, CommonError> {
+        let mut iter = self.db.raw_iterator_cf(&cf);
+        iter.seek(search_key);
+
+        let mut count = 0;
+        while iter.valid() {
+            match iter.key() {
+                Some(key) if key.starts_with(search_key.as_bytes()) => count += 1,
+                _ => break,
+            }
+            iter.next();
+        }
+        iter.status()?;
+        Ok(count)
+    }
+
     // Search data by prefix
     pub fn read_list_by_model(
         &self,
@@ -161,7 +213,7 @@ pub fn read_list_by_model(
         for raw in iter {
             let (k, value) = raw?;
             let key = String::from_utf8(k.to_vec())?;
-            result.push((key, value.to_vec()));
+            result.push((key, open_value(value.to_vec())?));
         }
         Ok(result)
     }
@@ -179,7 +231,7 @@ pub fn read_all_by_cf(
             if let Some(key) = iter.key() {
                 if let Some(val) = iter.value() {
                     let key = String::from_utf8(key.to_vec())?;
-                    result.push((key, val.to_vec()));
+                    result.push((key, open_value(val.to_vec())?));
                 }
             }
             iter.next();
@@ -221,6 +273,11 @@ pub fn exist(&self, cf: Arc<BoundColumnFamily<'_>>, key: &str) -> bool {
         self.db.key_may_exist_cf(&cf, key)
     }
 
+    /// See [`rewrap_sealed_values`].
+    pub fn rewrap_sealed_values(&self, cf_list: &[String]) -> Result<u64, CommonError> {
+        rewrap_sealed_values(&self.db, cf_list)
+    }
+
     pub fn cf_handle(&self, name: &str) -> Option<Arc<BoundColumnFamily<'_>>> {
         if let Some(cf) = self.db.cf_handle(name) {
             return Some(cf);
@@ -254,9 +311,35 @@ fn open_db_opts(max_open_files: i32) -> Options {
     }
 }
 
+/// Re-wrap the data keys of the sealed values in `cf_list` with the active master key, so that
+/// retired master keys can be removed from the key file. Values are rewritten in place, which
+/// would race with concurrent deletes, so this only runs right after the db is opened.
+/// Returns the number of re-wrapped values.
+pub fn rewrap_sealed_values(db: &DB, cf_list: &[String]) -> Result<u64, CommonError> {
+    let mut num = 0;
+    for cf_name in cf_list {
+        let Some(cf) = db.cf_handle(cf_name) else {
+            continue;
+        };
+        let mut iter = db.raw_iterator_cf(&cf);
+        iter.seek_to_first();
+        while iter.valid() {
+            if let (Some(key), Some(val)) = (iter.key(), iter.value()) {
+                if let Some(rewrapped) = rewrap_sealed_value(val)? {
+                    db.put_cf(&cf, key, rewrapped)?;
+                    num += 1;
+                }
+            }
+            iter.next();
+        }
+        iter.status()?;
+    }
+    Ok(num)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::RocksDBEngine;
+    use super::{rewrap_sealed_values, RocksDBEngine};
     use common_base::utils::file_utils::test_temp_dir;
     use common_config::broker::default_broker_config;
     use futures::future;
@@ -375,6 +458,26 @@ async fn read_all() {
         }
     }
 
+    #[tokio::test]
+    async fn rewrap_plaintext_values() {
+        let config = default_broker_config();
+
+        let rs = RocksDBEngine::new(
+            &test_temp_dir(),
+            config.rocksdb.max_open_files,
+            vec![cf_name()],
+        );
+        let cf = rs.cf_handle(&cf_name()).unwrap();
+        rs.write_str(cf.clone(), "/v1/v1", "v11".to_string())
+            .unwrap();
+
+        // values written without encryption are left as they are
+        let num = rewrap_sealed_values(&rs.db, &[cf_name(), "unknown".to_string()]).unwrap();
+        assert_eq!(num, 0);
+        let res = rs.read::<String>(cf.clone(), "/v1/v1").unwrap().unwrap();
+        assert_eq!(res, "v11");
+    }
+
     #[tokio::test]
     async fn read_prefix() {
         let config = default_broker_config();
@@ -417,5 +520,14 @@ async fn read_prefix() {
 
         let result = rs.read_prefix(cf.clone(), "/v4").unwrap();
         assert_eq!(result.len(), 1);
+
+        let result = rs.read_prefix_limit(cf.clone(), "/v2", 2).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].0, "/v2/tmp_test/s1");
+        assert_eq!(result[1].0, "/v2/tmp_test/s2");
+
+        assert_eq!(rs.count_prefix(cf.clone(), "/v1").unwrap(), 3);
+        assert_eq!(rs.count_prefix(cf.clone(), "/v3").unwrap(), 2);
+        assert_eq!(rs.count_prefix(cf.clone(), "/v5").unwrap(), 0);
     }
 }
@@ -0,0 +1,333 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::keyring::{MasterKeyring, MASTER_KEY_LEN};
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use common_base::error::common::CommonError;
+use common_base::error::ResultCommonError;
+use common_base::tools::loop_select_ticket;
+use common_config::config::Encryption;
+use std::sync::{Arc, OnceLock, RwLock};
+use tokio::sync::broadcast;
+use tracing::info;
+
+const NONCE_LEN: usize = 12;
+const KEY_ID_LEN: usize = 4;
+
+/// Prefix of a value sealed by `seal_value`, values without it are treated as plaintext
+/// so that data written before encryption was enabled stays readable.
+const ENVELOPE_MAGIC: &[u8] = b"\0RMQENC1";
+
+static MASTER_KEYRING: RwLock<Option<Arc<MasterKeyring>>> = RwLock::new(None);
+static MASTER_KEY_FILE: OnceLock<String> = OnceLock::new();
+
+/// Load the master keys if encryption at rest is enabled. Must be called before any storage is opened.
+pub fn init_encryption(config: &Encryption) -> ResultCommonError {
+    if !config.enable {
+        return Ok(());
+    }
+    let keyring = MasterKeyring::load(&config.key_file)?;
+    if let Some((key_id, _)) = keyring.active() {
+        info!(
+            "Encryption at rest enabled, active master key id: {}",
+            key_id
+        );
+    }
+    let _ = MASTER_KEY_FILE.set(config.key_file.clone());
+    set_master_keyring(Some(keyring));
+    Ok(())
+}
+
+/// Re-read the key file so that a new master key can be rotated in without restarting the process.
+pub fn reload_master_keys() -> ResultCommonError {
+    let key_file = if let Some(key_file) = MASTER_KEY_FILE.get() {
+        key_file
+    } else {
+        return Ok(());
+    };
+
+    let keyring = MasterKeyring::load(key_file)?;
+    let new_active = keyring.active().map(|(key_id, _)| key_id);
+    let old_active = master_keyring().and_then(|k| k.active().map(|(key_id, _)| key_id));
+    if new_active != old_active {
+        info!(
+            "Encryption master key rotated from {:?} to {:?}",
+            old_active, new_active
+        );
+    }
+    set_master_keyring(Some(keyring));
+    Ok(())
+}
+
+pub async fn start_master_key_reload_thread(
+    reload_interval_sec: u64,
+    stop_send: broadcast::Sender<bool>,
+) {
+    if !is_encryption_enable() {
+        return;
+    }
+
+    let ac_fn = async || -> ResultCommonError { reload_master_keys() };
+    loop_select_ticket(ac_fn, reload_interval_sec, &stop_send).await;
+}
+
+pub fn is_encryption_enable() -> bool {
+    master_keyring().is_some()
+}
+
+fn master_keyring() -> Option<Arc<MasterKeyring>> {
+    MASTER_KEYRING.read().unwrap().clone()
+}
+
+fn set_master_keyring(keyring: Option<MasterKeyring>) {
+    *MASTER_KEYRING.write().unwrap() = keyring.map(Arc::new);
+}
+
+/// A random AES-256-GCM key used to encrypt data, persisted only in its wrapped form.
+#[derive(Clone)]
+pub struct DataKey {
+    key: [u8; MASTER_KEY_LEN],
+}
+
+impl DataKey {
+    pub fn generate() -> Self {
+        DataKey {
+            key: Aes256Gcm::generate_key(OsRng).into(),
+        }
+    }
+
+    /// Encrypt the data key with the active master key.
+    ///
+    /// Layout: [master key id: u32][nonce: 12 bytes][encrypted key]
+    pub fn wrap(&self) -> Result<Vec<u8>, CommonError> {
+        let keyring = if let Some(keyring) = master_keyring() {
+            keyring
+        } else {
+            return Err(CommonError::DataEncryptionError(
+                "encryption at rest is not enabled".to_string(),
+            ));
+        };
+
+        let (key_id, master_key) = if let Some(active) = keyring.active() {
+            active
+        } else {
+            return Err(CommonError::DataEncryptionError(
+                "no active master key".to_string(),
+            ));
+        };
+
+        let mut wrapped = key_id.to_be_bytes().to_vec();
+        wrapped.extend(encrypt(master_key, &self.key)?);
+        Ok(wrapped)
+    }
+
+    pub fn unwrap_key(wrapped: &[u8]) -> Result<Self, CommonError> {
+        if wrapped.len() < KEY_ID_LEN {
+            return Err(CommonError::DataEncryptionError(
+                "wrapped data key is truncated".to_string(),
+            ));
+        }
+
+        let key_id = u32::from_be_bytes(wrapped[..KEY_ID_LEN].try_into().unwrap());
+        let keyring = master_keyring().ok_or(CommonError::MasterKeyNotFound(key_id))?;
+        let master_key = keyring
+            .get(key_id)
+            .ok_or(CommonError::MasterKeyNotFound(key_id))?;
+
+        let key = decrypt(master_key, &wrapped[KEY_ID_LEN..])?;
+        let key: [u8; MASTER_KEY_LEN] = key
+            .try_into()
+            .map_err(|_| CommonError::DataEncryptionError("invalid data key length".to_string()))?;
+        Ok(DataKey { key })
+    }
+
+    pub fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, CommonError> {
+        encrypt(&self.key, data)
+    }
+
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, CommonError> {
+        decrypt(&self.key, data)
+    }
+}
+
+/// Re-wrap a data key with the active master key, returns None if it is already wrapped by it.
+pub fn rewrap_data_key(wrapped: &[u8]) -> Result<Option<Vec<u8>>, CommonError> {
+    let active = master_keyring().and_then(|k| k.active().map(|(key_id, _)| key_id));
+    if wrapped.len() >= KEY_ID_LEN
+        && active
+            == Some(u32::from_be_bytes(
+                wrapped[..KEY_ID_LEN].try_into().unwrap(),
+            ))
+    {
+        return Ok(None);
+    }
+    Ok(Some(DataKey::unwrap_key(wrapped)?.wrap()?))
+}
+
+/// Encrypt a value with a fresh data key if encryption at rest is enabled, otherwise return it unchanged.
+///
+/// Layout: [magic][wrapped key len: u16][wrapped key][nonce: 12 bytes][encrypted value]
+pub fn seal_value(data: Vec<u8>) -> Result<Vec<u8>, CommonError> {
+    if !is_encryption_enable() {
+        return Ok(data);
+    }
+
+    let data_key = DataKey::generate();
+    let wrapped = data_key.wrap()?;
+    let mut result = Vec::with_capacity(ENVELOPE_MAGIC.len() + 2 + wrapped.len() + data.len() + 32);
+    result.extend_from_slice(ENVELOPE_MAGIC);
+    result.extend_from_slice(&(wrapped.len() as u16).to_be_bytes());
+    result.extend(wrapped);
+    result.extend(data_key.encrypt(&data)?);
+    Ok(result)
+}
+
+/// Decrypt a value written by `seal_value`, plaintext values are returned unchanged.
+pub fn open_value(data: Vec<u8>) -> Result<Vec<u8>, CommonError> {
+    if !is_sealed_value(&data) {
+        return Ok(data);
+    }
+
+    let (wrapped, encrypted) = split_sealed_value(&data)?;
+    DataKey::unwrap_key(wrapped)?.decrypt(encrypted)
+}
+
+/// Re-wrap the data key of a value written by `seal_value` with the active master key, the
+/// encrypted value is kept as is. Returns None for plaintext values and values whose key is
+/// already wrapped by the active master key.
+pub fn rewrap_sealed_value(data: &[u8]) -> Result<Option<Vec<u8>>, CommonError> {
+    if !is_sealed_value(data) {
+        return Ok(None);
+    }
+
+    let (wrapped, encrypted) = split_sealed_value(data)?;
+    let Some(rewrapped) = rewrap_data_key(wrapped)? else {
+        return Ok(None);
+    };
+    let mut result =
+        Vec::with_capacity(ENVELOPE_MAGIC.len() + 2 + rewrapped.len() + encrypted.len());
+    result.extend_from_slice(ENVELOPE_MAGIC);
+    result.extend_from_slice(&(rewrapped.len() as u16).to_be_bytes());
+    result.extend(rewrapped);
+    result.extend_from_slice(encrypted);
+    Ok(Some(result))
+}
+
+// Split a sealed value into the wrapped data key and the encrypted value.
+fn split_sealed_value(data: &[u8]) -> Result<(&[u8], &[u8]), CommonError> {
+    let body = &data[ENVELOPE_MAGIC.len()..];
+    if body.len() < 2 {
+        return Err(CommonError::DataEncryptionError(
+            "sealed value is truncated".to_string(),
+        ));
+    }
+    let wrapped_len = u16::from_be_bytes([body[0], body[1]]) as usize;
+    if body.len() < 2 + wrapped_len {
+        return Err(CommonError::DataEncryptionError(
+            "sealed value is truncated".to_string(),
+        ));
+    }
+    Ok((&body[2..2 + wrapped_len], &body[2 + wrapped_len..]))
+}
+
+pub fn is_sealed_value(data: &[u8]) -> bool {
+    data.starts_with(ENVELOPE_MAGIC)
+}
+
+fn encrypt(key: &[u8; MASTER_KEY_LEN], data: &[u8]) -> Result<Vec<u8>, CommonError> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, data)
+        .map_err(|e| CommonError::DataEncryptionError(e.to_string()))?;
+
+    let mut result = nonce.to_vec();
+    result.extend(ciphertext);
+    Ok(result)
+}
+
+fn decrypt(key: &[u8; MASTER_KEY_LEN], data: &[u8]) -> Result<Vec<u8>, CommonError> {
+    if data.len() < NONCE_LEN {
+        return Err(CommonError::DataEncryptionError(
+            "encrypted data is truncated".to_string(),
+        ));
+    }
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    cipher
+        .decrypt(Nonce::from_slice(&data[..NONCE_LEN]), &data[NONCE_LEN..])
+        .map_err(|e| CommonError::DataEncryptionError(e.to_string()))
+}
+
+#[cfg(test)]
+pub fn init_test_encryption() {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+
+    let content = format!(
+        "1 = {}\n2 = {}\n",
+        STANDARD.encode([1u8; 32]),
+        STANDARD.encode([2u8; 32])
+    );
+    set_master_keyring(Some(MasterKeyring::parse(&content).unwrap()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        init_test_encryption, is_sealed_value, open_value, rewrap_data_key, rewrap_sealed_value,
+        seal_value, DataKey,
+    };
+
+    #[test]
+    fn seal_open_value_test() {
+        init_test_encryption();
+
+        let value = b"{\"name\":\"robustmq\"}".to_vec();
+        let sealed = seal_value(value.clone()).unwrap();
+        assert!(is_sealed_value(&sealed));
+        assert_ne!(sealed, value);
+        assert_eq!(open_value(sealed).unwrap(), value);
+
+        // plaintext written before encryption was enabled
+        assert_eq!(open_value(value.clone()).unwrap(), value);
+    }
+
+    #[test]
+    fn rewrap_sealed_value_test() {
+        init_test_encryption();
+
+        let value = b"raft log entry".to_vec();
+        let sealed = seal_value(value.clone()).unwrap();
+        // already wrapped by the active master key
+        assert!(rewrap_sealed_value(&sealed).unwrap().is_none());
+        assert!(rewrap_sealed_value(&value).unwrap().is_none());
+        assert!(rewrap_sealed_value(&sealed[..sealed.len().min(9)]).is_err());
+    }
+
+    #[test]
+    fn data_key_wrap_test() {
+        init_test_encryption();
+
+        let data_key = DataKey::generate();
+        let wrapped = data_key.wrap().unwrap();
+        assert_eq!(u32::from_be_bytes(wrapped[..4].try_into().unwrap()), 2);
+
+        let encrypted = data_key.encrypt(b"segment record").unwrap();
+        let data_key = DataKey::unwrap_key(&wrapped).unwrap();
+        assert_eq!(data_key.decrypt(&encrypted).unwrap(), b"segment record");
+
+        assert!(rewrap_data_key(&wrapped).unwrap().is_none());
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use common_base::error::common::CommonError;
+use std::collections::BTreeMap;
+use std::fs;
+
+pub const MASTER_KEY_LEN: usize = 32;
+
+/// Master keys loaded from the local key file.
+///
+/// Each non-empty line of the key file has the form `<key_id> = <base64 encoded 32 bytes key>`,
+/// lines starting with `#` are ignored. The key with the largest id is the active key used to
+/// wrap new data keys, the others are kept so that data wrapped before a rotation can still be read.
+#[derive(Clone, Default)]
+pub struct MasterKeyring {
+    keys: BTreeMap<u32, [u8; MASTER_KEY_LEN]>,
+}
+
+impl MasterKeyring {
+    pub fn load(key_file: &str) -> Result<Self, CommonError> {
+        let content = fs::read_to_string(key_file)?;
+        MasterKeyring::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self, CommonError> {
+        let mut keys = BTreeMap::new();
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key_id, key) = if let Some(raw) = line.split_once('=') {
+                raw
+            } else {
+                return Err(CommonError::InvalidParameterFormat(
+                    "key_file".to_string(),
+                    line.to_string(),
+                ));
+            };
+
+            let key_id = key_id.trim().parse::<u32>()?;
+            let key = STANDARD
+                .decode(key.trim())
+                .map_err(|e| CommonError::DataEncryptionError(e.to_string()))?;
+            let key: [u8; MASTER_KEY_LEN] = key.try_into().map_err(|_| {
+                CommonError::DataEncryptionError(format!(
+                    "master key {key_id} must be {MASTER_KEY_LEN} bytes"
+                ))
+            })?;
+            keys.insert(key_id, key);
+        }
+
+        if keys.is_empty() {
+            return Err(CommonError::DataEncryptionError(
+                "no master key found in the key file".to_string(),
+            ));
+        }
+        Ok(MasterKeyring { keys })
+    }
+
+    pub fn active(&self) -> Option<(u32, &[u8; MASTER_KEY_LEN])> {
+        self.keys.iter().next_back().map(|(id, key)| (*id, key))
+    }
+
+    pub fn get(&self, key_id: u32) -> Option<&[u8; MASTER_KEY_LEN]> {
+        self.keys.get(&key_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MasterKeyring;
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+
+    #[test]
+    fn keyring_parse_test() {
+        let content = format!(
+            "# master keys\n1 = {}\n\n2 = {}\n",
+            STANDARD.encode([1u8; 32]),
+            STANDARD.encode([2u8; 32])
+        );
+        let keyring = MasterKeyring::parse(&content).unwrap();
+        let (key_id, key) = keyring.active().unwrap();
+        assert_eq!(key_id, 2);
+        assert_eq!(key, &[2u8; 32]);
+        assert_eq!(keyring.get(1).unwrap(), &[1u8; 32]);
+        assert!(keyring.get(3).is_none());
+
+        assert!(MasterKeyring::parse("").is_err());
+        assert!(MasterKeyring::parse("1 = abc").is_err());
+        assert!(MasterKeyring::parse("abc").is_err());
+    }
+}
@@ -17,9 +17,10 @@
     BindSchemaReply, BindSchemaRequest, ClusterStatusReply, ClusterStatusRequest,
     CreateSchemaReply, CreateSchemaRequest, DeleteIdempotentDataReply, DeleteIdempotentDataRequest,
     DeleteResourceConfigReply, DeleteResourceConfigRequest, DeleteSchemaReply, DeleteSchemaRequest,
-    ExistsIdempotentDataReply, ExistsIdempotentDataRequest, GetOffsetDataReply,
-    GetOffsetDataRequest, GetResourceConfigReply, GetResourceConfigRequest, HeartbeatReply,
-    HeartbeatRequest, ListBindSchemaReply, ListBindSchemaRequest, ListSchemaReply,
+    ExistsIdempotentDataReply, ExistsIdempotentDataRequest, ExportMetadataReply,
+    ExportMetadataRequest, GetOffsetDataReply, GetOffsetDataRequest, GetResourceConfigReply,
+    GetResourceConfigRequest, HeartbeatReply, HeartbeatRequest, ImportMetadataReply,
+    ImportMetadataRequest, ListBindSchemaReply, ListBindSchemaRequest, ListSchemaReply,
     ListSchemaRequest, NodeListReply, NodeListRequest, RegisterNodeReply, RegisterNodeRequest,
     SaveOffsetDataReply, SaveOffsetDataRequest, SetIdempotentDataReply, SetIdempotentDataRequest,
     SetResourceConfigReply, SetResourceConfigRequest, UnBindSchemaReply, UnBindSchemaRequest,
@@ -150,3 +151,17 @@ pub async fn $fn_name(
     GetOffsetDataReply,
     GetOffsetData
 );
+
+generate_placement_service_call!(
+    export_metadata,
+    ExportMetadataRequest,
+    ExportMetadataReply,
+    ExportMetadata
+);
+
+generate_placement_service_call!(
+    import_metadata,
+    ImportMetadataRequest,
+    ImportMetadataReply,
+    ImportMetadata
+);
@@ -19,9 +19,10 @@
     BindSchemaReply, BindSchemaRequest, ClusterStatusReply, ClusterStatusRequest,
     CreateSchemaReply, CreateSchemaRequest, DeleteIdempotentDataReply, DeleteIdempotentDataRequest,
     DeleteResourceConfigReply, DeleteResourceConfigRequest, DeleteSchemaReply, DeleteSchemaRequest,
-    ExistsIdempotentDataReply, ExistsIdempotentDataRequest, GetOffsetDataReply,
-    GetOffsetDataRequest, GetResourceConfigReply, GetResourceConfigRequest, HeartbeatReply,
-    HeartbeatRequest, ListBindSchemaReply, ListBindSchemaRequest, ListSchemaReply,
+    ExistsIdempotentDataReply, ExistsIdempotentDataRequest, ExportMetadataReply,
+    ExportMetadataRequest, GetOffsetDataReply, GetOffsetDataRequest, GetResourceConfigReply,
+    GetResourceConfigRequest, HeartbeatReply, HeartbeatRequest, ImportMetadataReply,
+    ImportMetadataRequest, ListBindSchemaReply, ListBindSchemaRequest, ListSchemaReply,
     ListSchemaRequest, NodeListReply, NodeListRequest, RegisterNodeReply, RegisterNodeRequest,
     SaveOffsetDataReply, SaveOffsetDataRequest, SetIdempotentDataReply, SetIdempotentDataRequest,
     SetResourceConfigReply, SetResourceConfigRequest, UnBindSchemaReply, UnBindSchemaRequest,
@@ -33,6 +34,8 @@
 
 pub mod call;
 
+const MAX_DECODING_MESSAGE_SIZE: usize = 268435456;
+
 pub struct PlacementServiceManager {
     pub addr: String,
 }
@@ -51,7 +54,8 @@ impl Manager for PlacementServiceManager {
     async fn connect(&self) -> Result<Self::Connection, Self::Error> {
         match MetaServiceServiceClient::connect(format!("http://{}", self.addr.clone())).await {
             Ok(client) => {
-                return Ok(client);
+                // metadata archives can be far larger than the 4MB default
+                return Ok(client.max_decoding_message_size(MAX_DECODING_MESSAGE_SIZE));
             }
             Err(err) => {
                 return Err(CommonError::CommonError(format!(
@@ -247,3 +251,21 @@ async fn check(&self, conn: Self::Connection) -> Result<Self::Connection, Self::
     un_bind_schema,
     true
 );
+
+impl_retriable_request!(
+    ExportMetadataRequest,
+    MetaServiceServiceClient<Channel>,
+    ExportMetadataReply,
+    meta_service_inner_services_client,
+    export_metadata,
+    false
+);
+
+impl_retriable_request!(
+    ImportMetadataRequest,
+    MetaServiceServiceClient<Channel>,
+    ImportMetadataReply,
+    meta_service_inner_services_client,
+    import_metadata,
+    true
+);
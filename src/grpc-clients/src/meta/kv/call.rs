@@ -15,8 +15,11 @@
 use common_base::error::common::CommonError;
 use protocol::meta::meta_service_kv::{
     DeleteReply, DeleteRequest, ExistsReply, ExistsRequest, GetPrefixReply, GetPrefixRequest,
-    GetReply, GetRequest, ListShardReply, ListShardRequest, SetReply, SetRequest,
+    GetReply, GetRequest, LeaseGrantReply, LeaseGrantRequest, LeaseKeepAliveReply,
+    LeaseKeepAliveRequest, LeaseRevokeReply, LeaseRevokeRequest, ListShardReply, ListShardRequest,
+    SetReply, SetRequest, TxnReply, TxnRequest, WatchReply, WatchRequest,
 };
+use tonic::Streaming;
 
 use crate::pool::ClientPool;
 
@@ -48,3 +51,23 @@ pub async fn $fn_name(
     GetPrefixReply,
     GetPrefix
 );
+generate_kv_service_call!(placement_watch, WatchRequest, Streaming<WatchReply>, Watch);
+generate_kv_service_call!(placement_txn, TxnRequest, TxnReply, Txn);
+generate_kv_service_call!(
+    placement_lease_grant,
+    LeaseGrantRequest,
+    LeaseGrantReply,
+    LeaseGrant
+);
+generate_kv_service_call!(
+    placement_lease_revoke,
+    LeaseRevokeRequest,
+    LeaseRevokeReply,
+    LeaseRevoke
+);
+generate_kv_service_call!(
+    placement_lease_keep_alive,
+    LeaseKeepAliveRequest,
+    LeaseKeepAliveReply,
+    LeaseKeepAlive
+);
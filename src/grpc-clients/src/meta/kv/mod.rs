@@ -17,9 +17,12 @@
 use protocol::meta::meta_service_kv::kv_service_client::KvServiceClient;
 use protocol::meta::meta_service_kv::{
     DeleteReply, DeleteRequest, ExistsReply, ExistsRequest, GetPrefixReply, GetPrefixRequest,
-    GetReply, GetRequest, ListShardReply, ListShardRequest, SetReply, SetRequest,
+    GetReply, GetRequest, LeaseGrantReply, LeaseGrantRequest, LeaseKeepAliveReply,
+    LeaseKeepAliveRequest, LeaseRevokeReply, LeaseRevokeRequest, ListShardReply, ListShardRequest,
+    SetReply, SetRequest, TxnReply, TxnRequest, WatchReply, WatchRequest,
 };
 use tonic::transport::Channel;
+use tonic::Streaming;
 
 use crate::macros::impl_retriable_request;
 
@@ -115,5 +118,50 @@ async fn check(&self, conn: Self::Connection) -> Result<Self::Connection, Self::
     true
 );
 
+impl_retriable_request!(
+    WatchRequest,
+    KvServiceClient<Channel>,
+    Streaming<WatchReply>,
+    meta_service_kv_services_client,
+    watch,
+    true
+);
+
+impl_retriable_request!(
+    TxnRequest,
+    KvServiceClient<Channel>,
+    TxnReply,
+    meta_service_kv_services_client,
+    txn,
+    true
+);
+
+impl_retriable_request!(
+    LeaseGrantRequest,
+    KvServiceClient<Channel>,
+    LeaseGrantReply,
+    meta_service_kv_services_client,
+    lease_grant,
+    true
+);
+
+impl_retriable_request!(
+    LeaseRevokeRequest,
+    KvServiceClient<Channel>,
+    LeaseRevokeReply,
+    meta_service_kv_services_client,
+    lease_revoke,
+    true
+);
+
+impl_retriable_request!(
+    LeaseKeepAliveRequest,
+    KvServiceClient<Channel>,
+    LeaseKeepAliveReply,
+    meta_service_kv_services_client,
+    lease_keep_alive,
+    true
+);
+
 #[cfg(test)]
 mod tests {}
@@ -31,7 +31,8 @@
     ListUserRequest, SaveLastWillMessageReply, SaveLastWillMessageRequest,
     SetAutoSubscribeRuleReply, SetAutoSubscribeRuleRequest, SetSubscribeReply, SetSubscribeRequest,
     SetTopicRetainMessageReply, SetTopicRetainMessageRequest, UpdateConnectorReply,
-    UpdateConnectorRequest, UpdateSessionReply, UpdateSessionRequest,
+    UpdateConnectorRequest, UpdateSessionReply, UpdateSessionRequest, UpdateUserReply,
+    UpdateUserRequest,
 };
 use tonic::Streaming;
 
@@ -61,6 +62,12 @@ pub async fn $fn_name(
     CreateUserReply,
     CreateUser
 );
+generate_mqtt_service_call!(
+    placement_update_user,
+    UpdateUserRequest,
+    UpdateUserReply,
+    UpdateUser
+);
 generate_mqtt_service_call!(
     placement_delete_user,
     DeleteUserRequest,
@@ -33,7 +33,8 @@
     ListUserRequest, SaveLastWillMessageReply, SaveLastWillMessageRequest,
     SetAutoSubscribeRuleReply, SetAutoSubscribeRuleRequest, SetSubscribeReply, SetSubscribeRequest,
     SetTopicRetainMessageReply, SetTopicRetainMessageRequest, UpdateConnectorReply,
-    UpdateConnectorRequest, UpdateSessionReply, UpdateSessionRequest,
+    UpdateConnectorRequest, UpdateSessionReply, UpdateSessionRequest, UpdateUserReply,
+    UpdateUserRequest,
 };
 use tonic::transport::Channel;
 use tonic::Streaming;
@@ -96,6 +97,15 @@ async fn check(&self, conn: Self::Connection) -> Result<Self::Connection, Self::
     true
 );
 
+impl_retriable_request!(
+    UpdateUserRequest,
+    MqttServiceClient<Channel>,
+    UpdateUserReply,
+    meta_service_mqtt_services_client,
+    update_user,
+    true
+);
+
 impl_retriable_request!(
     DeleteUserRequest,
     MqttServiceClient<Channel>,
@@ -111,7 +121,7 @@ async fn check(&self, conn: Self::Connection) -> Result<Self::Connection, Self::
     ListUserReply,
     meta_service_mqtt_services_client,
     list_user,
-    true
+    false
 );
 
 impl_retriable_request!(
@@ -138,7 +148,7 @@ async fn check(&self, conn: Self::Connection) -> Result<Self::Connection, Self::
     Streaming<ListTopicReply>,
     meta_service_mqtt_services_client,
     list_topic,
-    true
+    false
 );
 
 impl_retriable_request!(
@@ -183,7 +193,7 @@ async fn check(&self, conn: Self::Connection) -> Result<Self::Connection, Self::
     ListSessionReply,
     meta_service_mqtt_services_client,
     list_session,
-    true
+    false
 );
 
 impl_retriable_request!(
@@ -228,7 +238,7 @@ async fn check(&self, conn: Self::Connection) -> Result<Self::Connection, Self::
     ListAclReply,
     meta_service_mqtt_services_client,
     list_acl,
-    true
+    false
 );
 
 impl_retriable_request!(
@@ -255,7 +265,7 @@ async fn check(&self, conn: Self::Connection) -> Result<Self::Connection, Self::
     ListBlacklistReply,
     meta_service_mqtt_services_client,
     list_blacklist,
-    true
+    false
 );
 
 impl_retriable_request!(
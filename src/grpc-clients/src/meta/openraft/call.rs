@@ -15,7 +15,9 @@
 use common_base::error::common::CommonError;
 use protocol::meta::meta_service_openraft::{
     AddLearnerReply, AddLearnerRequest, AppendReply, AppendRequest, ChangeMembershipReply,
-    ChangeMembershipRequest, SnapshotReply, SnapshotRequest, VoteReply, VoteRequest,
+    ChangeMembershipRequest, RaftStatusReply, RaftStatusRequest, ReadIndexReply, ReadIndexRequest,
+    SnapshotReply, SnapshotRequest, TransferLeaderReply, TransferLeaderRequest, VoteReply,
+    VoteRequest,
 };
 
 use crate::pool::ClientPool;
@@ -57,3 +59,21 @@ pub async fn $fn_name(
     ChangeMembershipReply,
     ChangeMembership
 );
+generate_openraft_service_call!(
+    placement_openraft_read_index,
+    ReadIndexRequest,
+    ReadIndexReply,
+    ReadIndex
+);
+generate_openraft_service_call!(
+    placement_openraft_raft_status,
+    RaftStatusRequest,
+    RaftStatusReply,
+    RaftStatus
+);
+generate_openraft_service_call!(
+    placement_openraft_transfer_leader,
+    TransferLeaderRequest,
+    TransferLeaderReply,
+    TransferLeader
+);
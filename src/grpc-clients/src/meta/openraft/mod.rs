@@ -17,7 +17,9 @@
 use protocol::meta::meta_service_openraft::open_raft_service_client::OpenRaftServiceClient;
 use protocol::meta::meta_service_openraft::{
     AddLearnerReply, AddLearnerRequest, AppendReply, AppendRequest, ChangeMembershipReply,
-    ChangeMembershipRequest, SnapshotReply, SnapshotRequest, VoteReply, VoteRequest,
+    ChangeMembershipRequest, RaftStatusReply, RaftStatusRequest, ReadIndexReply, ReadIndexRequest,
+    SnapshotReply, SnapshotRequest, TransferLeaderReply, TransferLeaderRequest, VoteReply,
+    VoteRequest,
 };
 use tonic::transport::Channel;
 
@@ -101,3 +103,30 @@ async fn check(&self, conn: Self::Connection) -> Result<Self::Connection, Self::
     change_membership,
     true
 );
+
+impl_retriable_request!(
+    ReadIndexRequest,
+    OpenRaftServiceClient<Channel>,
+    ReadIndexReply,
+    meta_service_openraft_services_client,
+    read_index,
+    true
+);
+
+impl_retriable_request!(
+    RaftStatusRequest,
+    OpenRaftServiceClient<Channel>,
+    RaftStatusReply,
+    meta_service_openraft_services_client,
+    raft_status,
+    false
+);
+
+impl_retriable_request!(
+    TransferLeaderRequest,
+    OpenRaftServiceClient<Channel>,
+    TransferLeaderReply,
+    meta_service_openraft_services_client,
+    transfer_leader,
+    true
+);
@@ -15,7 +15,7 @@
 use common_base::error::common::CommonError;
 use protocol::broker::broker_mqtt_inner::{
     DeleteSessionReply, DeleteSessionRequest, SendLastWillMessageReply, SendLastWillMessageRequest,
-    UpdateMqttCacheReply, UpdateMqttCacheRequest,
+    TakeoverSessionReply, TakeoverSessionRequest, UpdateMqttCacheReply, UpdateMqttCacheRequest,
 };
 
 use crate::pool::ClientPool;
@@ -52,3 +52,10 @@ pub async fn $fn_name(
     SendLastWillMessageReply,
     SendLastWillMessage
 );
+
+generate_mqtt_inner_service_call!(
+    broker_mqtt_takeover_session,
+    TakeoverSessionRequest,
+    TakeoverSessionReply,
+    TakeoverSession
+);
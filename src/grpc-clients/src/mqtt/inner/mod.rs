@@ -17,7 +17,7 @@
 use protocol::broker::broker_mqtt_inner::mqtt_broker_inner_service_client::MqttBrokerInnerServiceClient;
 use protocol::broker::broker_mqtt_inner::{
     DeleteSessionReply, DeleteSessionRequest, SendLastWillMessageReply, SendLastWillMessageRequest,
-    UpdateMqttCacheReply, UpdateMqttCacheRequest,
+    TakeoverSessionReply, TakeoverSessionRequest, UpdateMqttCacheReply, UpdateMqttCacheRequest,
 };
 use tonic::transport::Channel;
 
@@ -83,3 +83,11 @@ async fn check(&self, conn: Self::Connection) -> Result<Self::Connection, Self::
     mqtt_broker_mqtt_services_client,
     send_last_will_message
 );
+
+impl_retriable_request!(
+    TakeoverSessionRequest,
+    MqttBrokerInnerServiceClient<Channel>,
+    TakeoverSessionReply,
+    mqtt_broker_mqtt_services_client,
+    takeover_session
+);
@@ -17,10 +17,16 @@ mod tests {
     use std::sync::Arc;
 
     use grpc_clients::meta::kv::call::{
-        placement_delete, placement_exists, placement_get, placement_set,
+        placement_delete, placement_exists, placement_get, placement_lease_grant,
+        placement_lease_keep_alive, placement_lease_revoke, placement_set, placement_txn,
+        placement_watch,
     };
     use grpc_clients::pool::ClientPool;
-    use protocol::meta::meta_service_kv::{DeleteRequest, ExistsRequest, GetRequest, SetRequest};
+    use protocol::meta::meta_service_kv::{
+        Compare, CompareResult, CompareTarget, DeleteRequest, ExistsRequest, GetRequest,
+        LeaseGrantRequest, LeaseKeepAliveRequest, LeaseRevokeRequest, SetRequest, TxnOp, TxnOpType,
+        TxnRequest, WatchEventType, WatchRequest,
+    };
 
     use crate::common::get_placement_addr;
 
@@ -34,6 +40,7 @@ async fn kv_test() {
         let request = SetRequest {
             key: key.clone(),
             value: value.clone(),
+            ..Default::default()
         };
         match placement_set(&client_pool, &addrs, request).await {
             Ok(_) => {}
@@ -45,6 +52,7 @@ async fn kv_test() {
         let request_key_empty = SetRequest {
             key: "".to_string(),
             value: value.clone(),
+            ..Default::default()
         };
         let err = placement_set(&client_pool, &addrs, request_key_empty)
             .await
@@ -54,6 +62,7 @@ async fn kv_test() {
         let request_value_empty = SetRequest {
             key: key.clone(),
             value: "".to_string(),
+            ..Default::default()
         };
         let err = placement_set(&client_pool, &addrs, request_value_empty)
             .await
@@ -98,4 +107,123 @@ async fn kv_test() {
             }
         }
     }
+
+    #[tokio::test]
+    async fn kv_watch_test() {
+        let client_pool: Arc<ClientPool> = Arc::new(ClientPool::new(1));
+        let addrs = vec![get_placement_addr()];
+        let prefix = "/test-watch/".to_string();
+        let key = format!("{prefix}key");
+
+        let request = WatchRequest {
+            prefix: prefix.clone(),
+            start_revision: 0,
+        };
+        let mut stream = placement_watch(&client_pool, &addrs, request)
+            .await
+            .unwrap();
+
+        let request = SetRequest {
+            key: key.clone(),
+            value: "v1".to_string(),
+            ..Default::default()
+        };
+        placement_set(&client_pool, &addrs, request).await.unwrap();
+        placement_delete(&client_pool, &addrs, DeleteRequest { key: key.clone() })
+            .await
+            .unwrap();
+
+        let put = stream.message().await.unwrap().unwrap().events[0].clone();
+        assert_eq!(put.event_type(), WatchEventType::Put);
+        assert_eq!(put.key, key);
+        assert_eq!(put.value, "v1");
+
+        let delete = stream.message().await.unwrap().unwrap().events[0].clone();
+        assert_eq!(delete.event_type(), WatchEventType::Delete);
+        assert!(delete.revision > put.revision);
+
+        // resume from the put revision replays both events
+        let request = WatchRequest {
+            prefix,
+            start_revision: put.revision,
+        };
+        let mut stream = placement_watch(&client_pool, &addrs, request)
+            .await
+            .unwrap();
+        let reply = stream.message().await.unwrap().unwrap();
+        assert_eq!(reply.events.len(), 2);
+        assert_eq!(reply.events[1].revision, delete.revision);
+    }
+
+    #[tokio::test]
+    async fn kv_lease_txn_test() {
+        let client_pool: Arc<ClientPool> = Arc::new(ClientPool::new(1));
+        let addrs = vec![get_placement_addr()];
+        let key = "/test-lease/key".to_string();
+
+        let lease = placement_lease_grant(
+            &client_pool,
+            &addrs,
+            LeaseGrantRequest {
+                ttl: 30,
+                lease_id: 0,
+            },
+        )
+        .await
+        .unwrap();
+        assert!(lease.lease_id > 0);
+
+        // create the key only if it does not exist
+        let txn = TxnRequest {
+            compare: vec![Compare {
+                key: key.clone(),
+                target: CompareTarget::CreateRevision.into(),
+                result: CompareResult::Equal.into(),
+                ..Default::default()
+            }],
+            success: vec![TxnOp {
+                op_type: TxnOpType::Put.into(),
+                key: key.clone(),
+                value: "v1".to_string(),
+                lease_id: lease.lease_id,
+            }],
+            failure: vec![],
+        };
+        let reply = placement_txn(&client_pool, &addrs, txn.clone())
+            .await
+            .unwrap();
+        assert!(reply.succeeded);
+        assert!(
+            !placement_txn(&client_pool, &addrs, txn)
+                .await
+                .unwrap()
+                .succeeded
+        );
+
+        let get = placement_get(&client_pool, &addrs, GetRequest { key: key.clone() })
+            .await
+            .unwrap();
+        assert_eq!(get.value, "v1");
+        assert_eq!(get.mod_revision, reply.revision);
+        assert_eq!(get.lease_id, lease.lease_id);
+
+        let keep_alive = LeaseKeepAliveRequest {
+            lease_id: lease.lease_id,
+        };
+        let reply = placement_lease_keep_alive(&client_pool, &addrs, keep_alive)
+            .await
+            .unwrap();
+        assert_eq!(reply.ttl, 30);
+
+        let revoke = LeaseRevokeRequest {
+            lease_id: lease.lease_id,
+        };
+        placement_lease_revoke(&client_pool, &addrs, revoke)
+            .await
+            .unwrap();
+        let exists = placement_exists(&client_pool, &addrs, ExistsRequest { key })
+            .await
+            .unwrap();
+        assert!(!exists.flag);
+    }
 }
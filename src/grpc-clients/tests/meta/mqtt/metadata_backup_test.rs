@@ -0,0 +1,95 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use grpc_clients::meta::inner::call::{export_metadata, import_metadata};
+    use grpc_clients::meta::mqtt::call::{
+        placement_create_user, placement_delete_user, placement_list_user,
+    };
+    use grpc_clients::pool::ClientPool;
+    use metadata_struct::mqtt::user::MqttUser;
+    use protocol::meta::meta_service_inner::{ExportMetadataRequest, ImportMetadataRequest};
+    use protocol::meta::meta_service_mqtt::{
+        CreateUserRequest, DeleteUserRequest, ListUserRequest,
+    };
+
+    use crate::common::get_placement_addr;
+
+    #[tokio::test]
+    async fn metadata_export_import_test() {
+        let client_pool: Arc<ClientPool> = Arc::new(ClientPool::new(3));
+        let addrs = vec![get_placement_addr()];
+        let source_cluster = "backup_source_cluster".to_string();
+        let target_cluster = "backup_target_cluster".to_string();
+
+        let mqtt_user = MqttUser {
+            username: "backup_user".to_string(),
+            password: "123456".to_string(),
+            salt: None,
+            is_superuser: false,
+        };
+        let request = CreateUserRequest {
+            cluster_name: source_cluster.clone(),
+            user_name: mqtt_user.username.clone(),
+            content: mqtt_user.encode(),
+        };
+        placement_create_user(&client_pool, &addrs, request)
+            .await
+            .unwrap();
+
+        let request = ExportMetadataRequest {
+            cluster_name: source_cluster.clone(),
+            resources: vec!["user".to_string()],
+        };
+        let reply = export_metadata(&client_pool, &addrs, request)
+            .await
+            .unwrap();
+        assert!(!reply.archive.is_empty());
+
+        let request = ImportMetadataRequest {
+            archive: reply.archive,
+            cluster_name: target_cluster.clone(),
+            resources: vec!["user".to_string()],
+        };
+        let reply = import_metadata(&client_pool, &addrs, request)
+            .await
+            .unwrap();
+        assert!(reply.record_count >= 1);
+
+        let request = ListUserRequest {
+            cluster_name: target_cluster.clone(),
+            user_name: mqtt_user.username.clone(),
+            ..Default::default()
+        };
+        let reply = placement_list_user(&client_pool, &addrs, request)
+            .await
+            .unwrap();
+        assert_eq!(reply.users.len(), 1);
+        let user = serde_json::from_slice::<MqttUser>(reply.users[0].as_slice()).unwrap();
+        assert_eq!(user.username, mqtt_user.username);
+
+        for cluster_name in [source_cluster, target_cluster] {
+            let request = DeleteUserRequest {
+                cluster_name,
+                user_name: mqtt_user.username.clone(),
+            };
+            placement_delete_user(&client_pool, &addrs, request)
+                .await
+                .unwrap();
+        }
+    }
+}
@@ -14,6 +14,7 @@
 
 mod cluster_test;
 mod kv_test;
+mod metadata_backup_test;
 mod mqtt_acl_test;
 mod mqtt_blacklist_test;
 mod mqtt_connector_test;
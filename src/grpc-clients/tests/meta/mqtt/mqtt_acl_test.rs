@@ -49,6 +49,7 @@ async fn mqtt_acl_test() {
 
         let request = ListAclRequest {
             cluster_name: cluster_name.clone(),
+            ..Default::default()
         };
 
         match list_acl(&client_pool, &addrs, request).await {
@@ -86,6 +87,7 @@ async fn mqtt_acl_test() {
 
         let request = ListAclRequest {
             cluster_name: cluster_name.clone(),
+            ..Default::default()
         };
 
         match list_acl(&client_pool, &addrs, request).await {
@@ -53,6 +53,7 @@ async fn mqtt_blacklist_test() {
 
         let request = ListBlacklistRequest {
             cluster_name: cluster_name.clone(),
+            ..Default::default()
         };
 
         match list_blacklist(&client_pool, &addrs, request).await {
@@ -89,6 +90,7 @@ async fn mqtt_blacklist_test() {
 
         let request = ListBlacklistRequest {
             cluster_name: cluster_name.clone(),
+            ..Default::default()
         };
 
         match list_blacklist(&client_pool, &addrs, request).await {
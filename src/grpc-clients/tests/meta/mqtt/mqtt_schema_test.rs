@@ -20,7 +20,7 @@ mod test {
         meta::inner::call::{create_schema, delete_schema, list_schema, update_schema},
         pool::ClientPool,
     };
-    use metadata_struct::schema::{SchemaData, SchemaType};
+    use metadata_struct::schema::{SchemaCompatibility, SchemaData, SchemaType};
     use protocol::meta::meta_service_inner::{
         CreateSchemaRequest, DeleteSchemaRequest, ListSchemaRequest, UpdateSchemaRequest,
     };
@@ -61,6 +61,9 @@ async fn schema_test() {
             }"#
             .to_string(),
             desc: "Old schema".to_string(),
+            message_name: "".to_string(),
+            version: 1,
+            compatibility: SchemaCompatibility::None,
         };
 
         let create_request = CreateSchemaRequest {
@@ -66,6 +66,7 @@ async fn mqtt_session_test() {
         let request = ListSessionRequest {
             cluster_name: cluster_name.clone(),
             client_id: mqtt_session.client_id.clone(),
+            ..Default::default()
         };
 
         match placement_list_session(&client_pool, &addrs, request).await {
@@ -107,6 +108,7 @@ async fn mqtt_session_test() {
         let request = ListSessionRequest {
             cluster_name: cluster_name.clone(),
             client_id: mqtt_session.client_id.clone(),
+            ..Default::default()
         };
 
         match placement_list_session(&client_pool, &addrs, request).await {
@@ -140,6 +142,7 @@ async fn mqtt_session_test() {
         let request = ListSessionRequest {
             cluster_name: cluster_name.clone(),
             client_id: mqtt_session.client_id.clone(),
+            ..Default::default()
         };
 
         match placement_list_session(&client_pool, &addrs, request).await {
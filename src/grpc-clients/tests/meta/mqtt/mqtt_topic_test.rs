@@ -91,6 +91,7 @@ async fn contain_topic(
         let request = ListTopicRequest {
             cluster_name,
             topic_name,
+            ..Default::default()
         };
         let mut data_stream = placement_list_topic(client_pool, addrs, request)
             .await
@@ -58,6 +58,7 @@ async fn mqtt_user_test() {
         let request: ListUserRequest = ListUserRequest {
             cluster_name: cluster_name.clone(),
             user_name: mqtt_user.username.clone(),
+            ..Default::default()
         };
 
         match placement_list_user(&client_pool, &addrs, request).await {
@@ -91,6 +92,7 @@ async fn mqtt_user_test() {
         let request: ListUserRequest = ListUserRequest {
             cluster_name: cluster_name.clone(),
             user_name: mqtt_user.username.clone(),
+            ..Default::default()
         };
 
         match placement_list_user(&client_pool, &addrs, request).await {
@@ -18,9 +18,12 @@ mod tests {
 
     use grpc_clients::meta::openraft::call::{
         placement_openraft_add_learner, placement_openraft_change_membership,
+        placement_openraft_raft_status,
     };
     use grpc_clients::pool::ClientPool;
-    use protocol::meta::meta_service_openraft::{AddLearnerRequest, ChangeMembershipRequest, Node};
+    use protocol::meta::meta_service_openraft::{
+        AddLearnerRequest, ChangeMembershipRequest, Node, RaftStatusRequest,
+    };
 
     use crate::common::get_placement_addr;
 
@@ -59,7 +62,11 @@ async fn placement_openraft_change_membership_test() {
         let members = vec![1];
         let retain = false;
 
-        let request = ChangeMembershipRequest { members, retain };
+        let request = ChangeMembershipRequest {
+            members,
+            retain,
+            ..Default::default()
+        };
         match placement_openraft_change_membership(&client_pool, &addrs, request).await {
             Ok(_) => {}
             Err(e) => {
@@ -67,4 +74,21 @@ async fn placement_openraft_change_membership_test() {
             }
         };
     }
+
+    #[tokio::test]
+    #[ignore]
+    async fn placement_openraft_raft_status_test() {
+        let client_pool: Arc<ClientPool> = Arc::new(ClientPool::new(1));
+        let addrs = vec![get_placement_addr()];
+
+        let reply = placement_openraft_raft_status(&client_pool, &addrs, RaftStatusRequest {})
+            .await
+            .unwrap();
+        assert!(reply.node_id > 0);
+        assert!(!reply.state.is_empty());
+        assert!(reply
+            .members
+            .iter()
+            .any(|member| member.node_id == reply.node_id));
+    }
 }
@@ -15,6 +15,7 @@
 use std::sync::Arc;
 
 use common_base::error::common::CommonError;
+use common_security::encryption::open_value;
 use rocksdb_engine::engine::{rocksdb_engine_get, rocksdb_engine_save};
 use rocksdb_engine::warp::StorageDataWrap;
 use rocksdb_engine::RocksDBEngine;
@@ -144,7 +145,8 @@ pub async fn get_last_nearest_position_by_offset(
                         break;
                     }
 
-                    let data = serde_json::from_slice::<StorageDataWrap>(val)?;
+                    let data =
+                        serde_json::from_slice::<StorageDataWrap>(&open_value(val.to_vec())?)?;
                     let index_data = serde_json::from_slice::<IndexData>(data.data.as_ref())?;
 
                     if index_data.offset < start_offset {
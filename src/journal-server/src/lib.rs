@@ -17,9 +17,13 @@
 #![allow(clippy::large_enum_variant)]
 use common_config::broker::broker_config;
 use common_config::config::BrokerConfig;
+use common_security::encryption::is_encryption_enable;
 use core::cache::{load_metadata_cache, CacheManager};
+use core::error::JournalServerError;
+use core::tool::loop_select;
 use grpc_clients::pool::ClientPool;
 use rocksdb_engine::RocksDBEngine;
+use segment::file::rewrap_segment_data_keys;
 use segment::manager::{
     load_local_segment_cache, metadata_and_local_segment_diff_check, SegmentFileManager,
 };
@@ -116,6 +120,24 @@ fn start_daemon_thread(&self) {
         tokio::spawn(async move {
             segment_scroll.trigger_segment_scroll().await;
         });
+
+        if is_encryption_enable() {
+            let data_path = self.config.journal_storage.data_path.clone();
+            let inner_stop = self.inner_stop.clone();
+            let interval = self.config.encryption.key_reload_interval_sec;
+            tokio::spawn(async move {
+                let ac_fn = async || -> Result<(), JournalServerError> {
+                    for path in data_path.iter() {
+                        let num = rewrap_segment_data_keys(Path::new(path))?;
+                        if num > 0 {
+                            info!("Re-wrapped {} segment data keys under {}", num, path);
+                        }
+                    }
+                    Ok(())
+                };
+                loop_select(ac_fn, interval, &inner_stop).await;
+            });
+        }
     }
 
     async fn waiting_stop(&self) {
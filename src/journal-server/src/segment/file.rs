@@ -12,14 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::fs::remove_file;
+use std::fs::{self as std_fs, remove_file};
 use std::io::ErrorKind;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
 
 use bytes::BytesMut;
 use common_base::tools::{file_exists, try_create_fold};
 use common_config::broker::broker_config;
+use common_security::encryption::{is_encryption_enable, rewrap_data_key, DataKey};
+use dashmap::DashMap;
 use prost::Message;
 use protocol::journal::journal_record::JournalRecord;
 use tokio::fs::{self, File, OpenOptions};
@@ -29,6 +31,10 @@
 use crate::core::cache::CacheManager;
 use crate::core::error::JournalServerError;
 
+// Unwrapped data keys of encrypted segments, by key file path. A `SegmentFile` is
+// built for every read and write, so the key is cached here instead of in the struct.
+static SEGMENT_DATA_KEYS: LazyLock<DashMap<String, DataKey>> = LazyLock::new(DashMap::new);
+
 /// The record read from the segment file
 #[derive(Debug, Clone)]
 pub struct ReadData {
@@ -95,6 +101,17 @@ pub async fn try_create(&self) -> Result<(), JournalServerError> {
         if file_exists(&segment_file) {
             return Ok(());
         }
+
+        // each segment is encrypted with its own data key, stored wrapped by the master key.
+        // The key is written first, so an existing segment file always has its key.
+        if is_encryption_enable() {
+            let wrapped = DataKey::generate().wrap()?;
+            fs::write(
+                data_file_segment_key(&self.data_fold, self.segment_no),
+                wrapped,
+            )
+            .await?;
+        }
         File::create(segment_file).await?;
         Ok(())
     }
@@ -106,17 +123,43 @@ pub async fn delete(&self) -> Result<(), JournalServerError> {
             return Err(JournalServerError::SegmentFileNotExists(segment_file));
         }
 
+        let key_file = data_file_segment_key(&self.data_fold, self.segment_no);
+        SEGMENT_DATA_KEYS.remove(&key_file);
+        if file_exists(&key_file) {
+            remove_file(key_file)?;
+        }
         Ok(remove_file(segment_file)?)
     }
 
+    /// the data key of the segment, None if the segment was created without encryption
+    async fn data_key(&self) -> Result<Option<DataKey>, JournalServerError> {
+        let key_file = data_file_segment_key(&self.data_fold, self.segment_no);
+        if let Some(key) = SEGMENT_DATA_KEYS.get(&key_file) {
+            return Ok(Some(key.clone()));
+        }
+
+        let wrapped = match fs::read(&key_file).await {
+            Ok(wrapped) => wrapped,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let key = DataKey::unwrap_key(&wrapped)?;
+        SEGMENT_DATA_KEYS.insert(key_file, key.clone());
+        Ok(Some(key))
+    }
+
     /// append a list of records to the segment file
     pub async fn write(&self, records: &[JournalRecord]) -> Result<(), JournalServerError> {
         let segment_file = data_file_segment(&self.data_fold, self.segment_no);
         let file = OpenOptions::new().append(true).open(segment_file).await?;
         let mut writer = tokio::io::BufWriter::new(file);
+        let data_key = self.data_key().await?;
 
         for record in records {
-            let data = JournalRecord::encode_to_vec(record);
+            let mut data = JournalRecord::encode_to_vec(record);
+            if let Some(key) = &data_key {
+                data = key.encrypt(&data)?;
+            }
             writer.write_u64(record.offset as u64).await?;
             writer.write_u32(data.len() as u32).await?;
             writer.write_all(data.as_ref()).await?;
@@ -143,7 +186,8 @@ pub async fn size(&self) -> Result<u64, JournalServerError> {
     ///
     ///     [offset: u64][len: u32][data: bytes]
     ///
-    /// We only consider `data` when calculating the size of a record.
+    /// We only consider `data` when calculating the size of a record. If the segment is encrypted,
+    /// `data` is the record encrypted with the data key of the segment.
     ///
     /// # Return
     ///
@@ -159,6 +203,7 @@ pub async fn read_by_offset(
         let segment_file = data_file_segment(&self.data_fold, self.segment_no);
         let file = File::open(segment_file).await?;
         let mut reader = tokio::io::BufReader::new(file);
+        let data_key = self.data_key().await?;
 
         reader
             .seek(std::io::SeekFrom::Current(start_position as i64))
@@ -197,7 +242,7 @@ pub async fn read_by_offset(
             reader.read_buf(&mut buf).await?;
 
             already_size += buf.len() as u64;
-            let record = JournalRecord::decode(buf)?;
+            let record = decode_record(&data_key, buf)?;
             results.push(ReadData { position, record });
 
             if results.len() >= max_record as usize {
@@ -218,6 +263,7 @@ pub async fn read_by_positions(
         let segment_file = data_file_segment(&self.data_fold, self.segment_no);
         let file = File::open(segment_file).await?;
         let mut reader = tokio::io::BufReader::new(file);
+        let data_key = self.data_key().await?;
 
         let mut results = Vec::new();
 
@@ -246,7 +292,7 @@ pub async fn read_by_positions(
             let mut buf = BytesMut::with_capacity(len as usize);
             reader.read_buf(&mut buf).await?;
 
-            let record = JournalRecord::decode(buf)?;
+            let record = decode_record(&data_key, buf)?;
 
             results.push(ReadData { position, record });
         }
@@ -269,6 +315,46 @@ pub fn data_file_segment(data_fold: &str, segment_no: u32) -> String {
     format!("{data_fold}/{segment_no}.msg")
 }
 
+pub fn data_file_segment_key(data_fold: &str, segment_no: u32) -> String {
+    format!("{data_fold}/{segment_no}.key")
+}
+
+fn decode_record(
+    data_key: &Option<DataKey>,
+    buf: BytesMut,
+) -> Result<JournalRecord, JournalServerError> {
+    if let Some(key) = data_key {
+        return Ok(JournalRecord::decode(key.decrypt(&buf)?.as_slice())?);
+    }
+    Ok(JournalRecord::decode(buf)?)
+}
+
+/// Re-wrap the data keys of all local segments under `dir` with the active master key,
+/// so that retired master keys can be removed from the key file. Returns the number of re-wrapped keys.
+pub fn rewrap_segment_data_keys(dir: &Path) -> Result<u64, JournalServerError> {
+    let mut num = 0;
+    for entry in std_fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            num += rewrap_segment_data_keys(&path)?;
+            continue;
+        }
+
+        if path.extension().and_then(|ext| ext.to_str()) != Some("key") {
+            continue;
+        }
+
+        let wrapped = std_fs::read(&path)?;
+        if let Some(rewrapped) = rewrap_data_key(&wrapped)? {
+            let tmp_path = path.with_extension("key.tmp");
+            std_fs::write(&tmp_path, rewrapped)?;
+            std_fs::rename(&tmp_path, &path)?;
+            num += 1;
+        }
+    }
+    Ok(num)
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -187,6 +187,10 @@ pub fn load_local_segment_cache(
 
             let file_path = path.display().to_string();
             let segment_file = file_path.split("/").last().unwrap();
+            // skip the data key files of encrypted segments
+            if !segment_file.ends_with(".msg") {
+                continue;
+            }
             let segment = segment_file.replace(".msg", "");
             let segment_no = segment.parse::<u32>()?;
 
@@ -117,6 +117,26 @@ async fn start_stop_connector_thread(
             warn!("Connector {} has an abnormal state, which is Running, but the execution node is empty.", connector.cluster_name);
         }
 
+        // Hand the connector off when its broker is being drained
+        if let Some(broker_id) = connector.broker_id {
+            if cache_manager.is_broker_draining(&connector.cluster_name, broker_id) {
+                info!(
+                    "Broker {} is draining, Connector {} is rescheduled",
+                    broker_id, connector.connector_name
+                );
+                update_connector_status_to_idle(
+                    raft_machine_apply,
+                    call_manager,
+                    client_pool,
+                    cache_manager,
+                    &connector.cluster_name,
+                    &connector.connector_name,
+                )
+                .await?;
+                continue;
+            }
+        }
+
         if connector.broker_id.is_none() {
             connector.broker_id =
                 Some(calc_connector_broker(cache_manager, &connector.cluster_name).await?);
@@ -177,7 +197,7 @@ async fn calc_connector_broker(
     }
 
     let mut all_broker_id_nums = HashMap::new();
-    for broker_id in cache_manager.get_broker_node_id_by_cluster(cluster_name) {
+    for broker_id in cache_manager.get_schedulable_broker_node_id_by_cluster(cluster_name) {
         if let Some(num) = connector_broker_id_nums.get(&broker_id) {
             all_broker_id_nums.insert(broker_id, *num);
         } else {
@@ -185,18 +205,10 @@ async fn calc_connector_broker(
         }
     }
 
-    let mut broker_id_num = 0;
-    let mut broker_id = -1;
-    for (id, num) in all_broker_id_nums {
-        if broker_id_num > num {
-            broker_id_num = num;
-            broker_id = id as i64
-        }
-    }
-
-    if broker_id == -1 {
-        return Err(MetaServiceError::NoAvailableBrokerNode);
-    }
-
-    Ok(broker_id as u64)
+    // the broker running the fewest connectors wins
+    all_broker_id_nums
+        .into_iter()
+        .min_by_key(|(id, num)| (*num, *id))
+        .map(|(id, _)| id)
+        .ok_or(MetaServiceError::NoAvailableBrokerNode)
 }
@@ -20,6 +20,7 @@
 use broker_core::rocksdb::DB_COLUMN_FAMILY_META;
 use common_base::error::common::CommonError;
 use common_base::tools::now_second;
+use common_security::encryption::open_value;
 use metadata_struct::mqtt::lastwill::LastWillData;
 use metadata_struct::mqtt::retain_message::MQTTRetainMessage;
 use rocksdb_engine::warp::StorageDataWrap;
@@ -76,7 +77,17 @@ pub async fn retain_message_expire(&self) {
                 break;
             }
 
-            let result_value = value.unwrap().to_vec();
+            let result_value = match open_value(value.unwrap().to_vec()) {
+                Ok(data) => data,
+                Err(e) => {
+                    error!(
+                        "Failed to decrypt the stored value of {}, error: {}",
+                        result_key, e
+                    );
+                    iter.next();
+                    continue;
+                }
+            };
             let data = serde_json::from_slice::<StorageDataWrap>(&result_value).unwrap();
             let value = serde_json::from_str::<MQTTRetainMessage>(&data.data).unwrap();
             let delete = now_second() >= (value.create_time + value.retain_message_expired_at);
@@ -130,7 +141,17 @@ pub async fn last_will_message_expire(&self) {
                 break;
             }
 
-            let result_value = value.unwrap().to_vec();
+            let result_value = match open_value(value.unwrap().to_vec()) {
+                Ok(data) => data,
+                Err(e) => {
+                    error!(
+                        "Failed to decrypt the stored value of {}, error: {}",
+                        result_key, e
+                    );
+                    iter.next();
+                    continue;
+                }
+            };
             let data = serde_json::from_slice::<StorageDataWrap>(&result_value).unwrap();
             let value = serde_json::from_str::<LastWillData>(&data.data).unwrap();
             if let Some(properties) = value.last_will_properties {
@@ -19,6 +19,7 @@
 use broker_core::rocksdb::DB_COLUMN_FAMILY_META;
 use common_base::error::common::CommonError;
 use common_base::tools::now_second;
+use common_security::encryption::open_value;
 use grpc_clients::mqtt::inner::call::{broker_mqtt_delete_session, send_last_will_message};
 use grpc_clients::pool::ClientPool;
 use metadata_struct::mqtt::lastwill::LastWillData;
@@ -113,8 +114,18 @@ async fn get_expire_session_list(&self) -> Vec<MqttSession> {
                 break;
             }
 
-            let result_value = value.unwrap();
-            let session = match serde_json::from_slice::<StorageDataWrap>(result_value) {
+            let result_value = match open_value(value.unwrap().to_vec()) {
+                Ok(data) => data,
+                Err(e) => {
+                    error!(
+                        "Failed to decrypt the stored value of {}, error: {}",
+                        result_key, e
+                    );
+                    iter.next();
+                    continue;
+                }
+            };
+            let session = match serde_json::from_slice::<StorageDataWrap>(&result_value) {
                 Ok(data) => match serde_json::from_str::<MqttSession>(&data.data) {
                     Ok(da) => da,
                     Err(e) => {
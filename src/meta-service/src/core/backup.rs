@@ -0,0 +1,347 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::core::error::MetaServiceError;
+use crate::storage::keys::{
+    key_kv_lease_prefix, key_node_prefix_all, key_raft_applied_state, storage_key_mqtt_acl_prefix,
+    storage_key_mqtt_auto_subscribe_rule_prefix, storage_key_mqtt_blacklist_prefix,
+    storage_key_mqtt_connector_prefix, storage_key_mqtt_last_will_prefix,
+    storage_key_mqtt_retain_message_cluster_prefix, storage_key_mqtt_schema_bind_prefix_by_cluster,
+    storage_key_mqtt_schema_prefix, storage_key_mqtt_session_cluster_prefix,
+    storage_key_mqtt_subscribe_cluster_prefix, storage_key_mqtt_topic_cluster_prefix,
+    storage_key_mqtt_topic_rewrite_rule_prefix, storage_key_mqtt_user_cluster_prefix,
+};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use common_base::tools::now_second;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+pub const METADATA_ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MetadataArchive {
+    pub version: u32,
+    pub cluster_name: String,
+    pub snapshot_id: String,
+    pub last_log_index: u64,
+    pub create_time: u64,
+    // Empty for a full backup.
+    pub resources: Vec<String>,
+    pub records: Vec<MetadataRecord>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MetadataRecord {
+    pub key: String,
+    // base64 encoded
+    pub value: String,
+}
+
+// One raft entry of an import, the cache is reloaded once after the last batch.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct MetadataImportBatch {
+    pub records: Vec<(String, Vec<u8>)>,
+    pub last: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataResource {
+    User,
+    Acl,
+    Blacklist,
+    Topic,
+    Session,
+    Subscribe,
+    LastWill,
+    Connector,
+    Schema,
+    SchemaBind,
+    TopicRewrite,
+    AutoSubscribe,
+    RetainMessage,
+}
+
+impl MetadataResource {
+    pub fn key_prefix(&self, cluster_name: &str) -> String {
+        let prefix = match self {
+            MetadataResource::User => storage_key_mqtt_user_cluster_prefix(cluster_name),
+            MetadataResource::Acl => storage_key_mqtt_acl_prefix(cluster_name),
+            MetadataResource::Blacklist => storage_key_mqtt_blacklist_prefix(cluster_name),
+            MetadataResource::Topic => storage_key_mqtt_topic_cluster_prefix(cluster_name),
+            MetadataResource::Session => storage_key_mqtt_session_cluster_prefix(cluster_name),
+            MetadataResource::Subscribe => storage_key_mqtt_subscribe_cluster_prefix(cluster_name),
+            MetadataResource::LastWill => storage_key_mqtt_last_will_prefix(cluster_name),
+            MetadataResource::Connector => storage_key_mqtt_connector_prefix(cluster_name),
+            MetadataResource::Schema => storage_key_mqtt_schema_prefix(cluster_name),
+            MetadataResource::SchemaBind => {
+                storage_key_mqtt_schema_bind_prefix_by_cluster(cluster_name)
+            }
+            MetadataResource::TopicRewrite => {
+                storage_key_mqtt_topic_rewrite_rule_prefix(cluster_name)
+            }
+            MetadataResource::AutoSubscribe => {
+                storage_key_mqtt_auto_subscribe_rule_prefix(cluster_name)
+            }
+            MetadataResource::RetainMessage => {
+                storage_key_mqtt_retain_message_cluster_prefix(cluster_name)
+            }
+        };
+        // some prefixes stop at the cluster name, "c1" must not match "c10"
+        if prefix.ends_with('/') {
+            prefix
+        } else {
+            format!("{prefix}/")
+        }
+    }
+}
+
+impl FromStr for MetadataResource {
+    type Err = MetaServiceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "user" => Ok(MetadataResource::User),
+            "acl" => Ok(MetadataResource::Acl),
+            "blacklist" => Ok(MetadataResource::Blacklist),
+            "topic" => Ok(MetadataResource::Topic),
+            "session" => Ok(MetadataResource::Session),
+            "subscribe" => Ok(MetadataResource::Subscribe),
+            "last_will" => Ok(MetadataResource::LastWill),
+            "connector" => Ok(MetadataResource::Connector),
+            "schema" => Ok(MetadataResource::Schema),
+            "schema_bind" => Ok(MetadataResource::SchemaBind),
+            "topic_rewrite" => Ok(MetadataResource::TopicRewrite),
+            "auto_subscribe" => Ok(MetadataResource::AutoSubscribe),
+            "retain_message" => Ok(MetadataResource::RetainMessage),
+            _ => Err(MetaServiceError::UnsupportedMetadataResource(s.to_string())),
+        }
+    }
+}
+
+pub fn parse_resources(resources: &[String]) -> Result<Vec<MetadataResource>, MetaServiceError> {
+    resources
+        .iter()
+        .map(|resource| MetadataResource::from_str(resource.trim()))
+        .collect()
+}
+
+// Broker registrations, kv leases and the raft applied state only make sense for
+// the running cluster they were created in, they are never part of an archive.
+fn is_exportable(key: &str) -> bool {
+    !key.starts_with(&key_node_prefix_all())
+        && !key.starts_with(&key_kv_lease_prefix())
+        && key != key_raft_applied_state()
+}
+
+pub fn build_archive(
+    cluster_name: &str,
+    snapshot_id: String,
+    last_log_index: u64,
+    records: Vec<(String, Vec<u8>)>,
+    resources: &[String],
+) -> Result<MetadataArchive, MetaServiceError> {
+    let selected = parse_resources(resources)?;
+    let prefixes: Vec<String> = selected
+        .iter()
+        .map(|resource| resource.key_prefix(cluster_name))
+        .collect();
+
+    let records = records
+        .into_iter()
+        .filter(|(key, _)| is_exportable(key))
+        .filter(|(key, _)| prefixes.is_empty() || prefixes.iter().any(|p| key.starts_with(p)))
+        .map(|(key, value)| MetadataRecord {
+            key,
+            value: STANDARD.encode(value),
+        })
+        .collect();
+
+    Ok(MetadataArchive {
+        version: METADATA_ARCHIVE_VERSION,
+        cluster_name: cluster_name.to_string(),
+        snapshot_id,
+        last_log_index,
+        create_time: now_second(),
+        resources: resources.to_vec(),
+        records,
+    })
+}
+
+pub fn encode_archive(archive: &MetadataArchive) -> Result<Vec<u8>, MetaServiceError> {
+    Ok(serde_json::to_vec(archive)?)
+}
+
+pub fn decode_archive(data: &[u8]) -> Result<MetadataArchive, MetaServiceError> {
+    let archive: MetadataArchive = serde_json::from_slice(data)?;
+    if archive.version > METADATA_ARCHIVE_VERSION {
+        return Err(MetaServiceError::UnsupportedMetadataArchiveVersion(
+            archive.version,
+        ));
+    }
+    Ok(archive)
+}
+
+// Returns the records to write. Without resources everything in the archive is restored
+// as is, otherwise only the selected MQTT resources are imported and moved from the
+// archive's cluster to target_cluster.
+pub fn prepare_import(
+    archive: &MetadataArchive,
+    target_cluster: &str,
+    resources: &[String],
+) -> Result<Vec<(String, Vec<u8>)>, MetaServiceError> {
+    let mut results = Vec::new();
+    if resources.is_empty() {
+        for record in archive.records.iter() {
+            if !is_exportable(&record.key) {
+                continue;
+            }
+            results.push((record.key.clone(), decode_value(record)?));
+        }
+        return Ok(results);
+    }
+
+    let target_cluster = if target_cluster.is_empty() {
+        archive.cluster_name.as_str()
+    } else {
+        target_cluster
+    };
+
+    for resource in parse_resources(resources)? {
+        let source_prefix = resource.key_prefix(&archive.cluster_name);
+        let target_prefix = resource.key_prefix(target_cluster);
+        for record in archive.records.iter() {
+            let Some(rest) = record.key.strip_prefix(&source_prefix) else {
+                continue;
+            };
+            let value = decode_value(record)?;
+            let value = if target_cluster != archive.cluster_name {
+                rewrite_cluster_name(value, &archive.cluster_name, target_cluster)
+            } else {
+                value
+            };
+            results.push((format!("{target_prefix}{rest}"), value));
+        }
+    }
+    Ok(results)
+}
+
+fn decode_value(record: &MetadataRecord) -> Result<Vec<u8>, MetaServiceError> {
+    STANDARD.decode(&record.value).map_err(|e| {
+        MetaServiceError::CommonError(format!(
+            "Invalid value of key {} in metadata archive: {e}",
+            record.key
+        ))
+    })
+}
+
+// Resources carry their cluster name in a top level cluster_name field.
+fn rewrite_cluster_name(value: Vec<u8>, source: &str, target: &str) -> Vec<u8> {
+    let Ok(mut json) = serde_json::from_slice::<serde_json::Value>(&value) else {
+        return value;
+    };
+    let Some(cluster_name) = json.get_mut("cluster_name") else {
+        return value;
+    };
+    if cluster_name.as_str() != Some(source) {
+        return value;
+    }
+    *cluster_name = serde_json::Value::String(target.to_string());
+    serde_json::to_vec(&json).unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_archive, decode_archive, encode_archive, prepare_import, MetadataResource};
+    use crate::storage::keys::{
+        key_kv_lease, key_node, storage_key_mqtt_acl_prefix, storage_key_mqtt_topic,
+        storage_key_mqtt_user,
+    };
+    use std::str::FromStr;
+
+    fn records() -> Vec<(String, Vec<u8>)> {
+        vec![
+            (
+                storage_key_mqtt_user("c1", "u1"),
+                br#"{"username":"u1","cluster_name":"c1"}"#.to_vec(),
+            ),
+            (
+                storage_key_mqtt_user("c10", "u2"),
+                br#"{"username":"u2"}"#.to_vec(),
+            ),
+            (
+                storage_key_mqtt_topic("c1", "t1"),
+                br#"{"topic_name":"t1"}"#.to_vec(),
+            ),
+            (key_node("c1", 1), b"node".to_vec()),
+            (key_kv_lease(1), b"lease".to_vec()),
+        ]
+    }
+
+    #[test]
+    fn resource_parse_test() {
+        assert_eq!(
+            MetadataResource::from_str("user").unwrap(),
+            MetadataResource::User
+        );
+        assert!(MetadataResource::from_str("shard").is_err());
+        assert_eq!(
+            MetadataResource::Acl.key_prefix("c1"),
+            storage_key_mqtt_acl_prefix("c1")
+        );
+        assert!(MetadataResource::Connector
+            .key_prefix("c1")
+            .ends_with("/c1/"));
+    }
+
+    #[test]
+    fn full_archive_test() {
+        let archive = build_archive("c1", "1-10-1".to_string(), 10, records(), &[]).unwrap();
+        assert_eq!(archive.records.len(), 3);
+
+        let data = encode_archive(&archive).unwrap();
+        let decoded = decode_archive(&data).unwrap();
+        assert_eq!(decoded, archive);
+
+        let restored = prepare_import(&decoded, "", &[]).unwrap();
+        assert_eq!(restored, records()[0..3].to_vec());
+    }
+
+    #[test]
+    fn selective_import_test() {
+        let resources = vec!["user".to_string()];
+        let archive = build_archive("c1", "1-10-1".to_string(), 10, records(), &resources).unwrap();
+        assert_eq!(archive.records.len(), 1);
+
+        let imported = prepare_import(&archive, "c2", &resources).unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].0, storage_key_mqtt_user("c2", "u1"));
+        let value: serde_json::Value = serde_json::from_slice(&imported[0].1).unwrap();
+        assert_eq!(value["cluster_name"], "c2");
+        assert_eq!(value["username"], "u1");
+
+        let imported = prepare_import(&archive, "c2", &["topic".to_string()]).unwrap();
+        assert!(imported.is_empty());
+
+        assert!(prepare_import(&archive, "c2", &["unknown".to_string()]).is_err());
+    }
+
+    #[test]
+    fn archive_version_test() {
+        let mut archive = build_archive("c1", String::new(), 0, Vec::new(), &[]).unwrap();
+        archive.version += 1;
+        let data = encode_archive(&archive).unwrap();
+        assert!(decode_archive(&data).is_err());
+    }
+}
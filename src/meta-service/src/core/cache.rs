@@ -21,6 +21,7 @@
 use crate::storage::mqtt::connector::MqttConnectorStorage;
 use crate::storage::mqtt::user::MqttUserStorage;
 use crate::storage::placement::cluster::ClusterStorage;
+use crate::storage::placement::lease::KvLeaseStorage;
 use crate::storage::placement::node::NodeStorage;
 use crate::{
     controller::mqtt::session_expire::ExpireLastWill, storage::mqtt::topic::MqttTopicStorage,
@@ -66,6 +67,10 @@ pub struct CacheManager {
     //(cluster_connector_name, ConnectorHeartbeat)
     pub connector_heartbeat: DashMap<String, ConnectorHeartbeat>,
 
+    // KV
+    // (lease_id, expire time in seconds)
+    pub kv_lease_expire: DashMap<u64, u64>,
+
     // Journal
     //（cluster_name_namespace_shard_name, JournalShard）
     pub shard_list: DashMap<String, JournalShard>,
@@ -86,6 +91,7 @@ pub fn new(rocksdb_engine_handler: Arc<RocksDBEngine>) -> CacheManager {
             expire_last_wills: DashMap::with_capacity(8),
             connector_list: DashMap::with_capacity(8),
             connector_heartbeat: DashMap::with_capacity(8),
+            kv_lease_expire: DashMap::with_capacity(8),
             shard_list: DashMap::with_capacity(8),
             segment_list: DashMap::with_capacity(256),
             segment_meta_list: DashMap::with_capacity(256),
@@ -173,6 +179,14 @@ pub fn get_broker_node_id_by_cluster(&self, cluster_name: &str) -> Vec<u64> {
         Vec::new()
     }
 
+    // Nodes that can take new connectors and shared subscription groups, draining nodes are left out
+    pub fn get_schedulable_broker_node_id_by_cluster(&self, cluster_name: &str) -> Vec<u64> {
+        self.get_broker_node_id_by_cluster(cluster_name)
+            .into_iter()
+            .filter(|node_id| !self.is_broker_draining(cluster_name, *node_id))
+            .collect()
+    }
+
     pub fn get_broker_node_by_cluster(&self, cluster_name: &str) -> Vec<BrokerNode> {
         if let Some(data) = self.node_list.get(cluster_name) {
             return data.iter().map(|row| row.clone()).collect();
@@ -181,12 +195,20 @@ pub fn get_broker_node_by_cluster(&self, cluster_name: &str) -> Vec<BrokerNode>
     }
 
     // Heartbeat
-    pub fn report_broker_heart(&self, cluster_name: &str, node_id: u64) {
+    pub fn report_broker_heart(
+        &self,
+        cluster_name: &str,
+        node_id: u64,
+        draining: bool,
+        connection_num: u64,
+    ) {
         let key = self.node_key(cluster_name, node_id);
         let data = NodeHeartbeatData {
             cluster_name: cluster_name.to_string(),
             node_id,
             time: now_second(),
+            draining,
+            connection_num,
         };
         self.node_heartbeat.insert(key, data);
     }
@@ -204,6 +226,37 @@ pub fn get_broker_heart(&self, cluster_name: &str, node_id: u64) -> Option<NodeH
         None
     }
 
+    pub fn get_broker_heart_by_cluster(&self, cluster_name: &str) -> Vec<NodeHeartbeatData> {
+        self.get_broker_node_id_by_cluster(cluster_name)
+            .into_iter()
+            .filter_map(|node_id| self.get_broker_heart(cluster_name, node_id))
+            .collect()
+    }
+
+    pub fn is_broker_draining(&self, cluster_name: &str, node_id: u64) -> bool {
+        self.get_broker_heart(cluster_name, node_id)
+            .map(|heart| heart.draining)
+            .unwrap_or(false)
+    }
+
+    // KV Lease
+    pub fn refresh_kv_lease(&self, lease_id: u64, ttl: u64) {
+        self.kv_lease_expire.insert(lease_id, now_second() + ttl);
+    }
+
+    pub fn remove_kv_lease(&self, lease_id: u64) {
+        self.kv_lease_expire.remove(&lease_id);
+    }
+
+    pub fn get_expired_kv_lease(&self) -> Vec<u64> {
+        let now = now_second();
+        self.kv_lease_expire
+            .iter()
+            .filter(|row| *row.value() <= now)
+            .map(|row| *row.key())
+            .collect()
+    }
+
     pub fn load_cache(&mut self, rocksdb_engine_handler: Arc<RocksDBEngine>) {
         let cluster = ClusterStorage::new(rocksdb_engine_handler.clone());
         if let Ok(result) = cluster.list() {
@@ -230,6 +283,11 @@ pub fn load_cache(
     rocksdb_engine_handler: &Arc<RocksDBEngine>,
 ) -> Result<(), MetaServiceError> {
     // placement
+    // lease expiration is not persisted, every lease gets a full ttl after restart
+    let lease_storage = KvLeaseStorage::new(rocksdb_engine_handler.clone());
+    for lease in lease_storage.list()? {
+        cache_manager.refresh_kv_lease(lease.lease_id, lease.ttl);
+    }
 
     // journal
     let shard_storage = ShardStorage::new(rocksdb_engine_handler.clone());
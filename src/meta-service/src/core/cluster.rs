@@ -39,7 +39,7 @@ pub async fn register_node_by_req(
     req: RegisterNodeRequest,
 ) -> Result<RegisterNodeReply, MetaServiceError> {
     let node = serde_json::from_slice::<BrokerNode>(&req.node)?;
-    cluster_cache.report_broker_heart(&node.cluster_name, node.node_id);
+    cluster_cache.report_broker_heart(&node.cluster_name, node.node_id, false, 0);
     sync_save_node(raft_machine_apply, &node).await?;
 
     if cluster_cache.get_cluster(&node.cluster_name).is_none() {
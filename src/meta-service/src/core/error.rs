@@ -155,4 +155,19 @@ pub enum MetaServiceError {
 
     #[error("Schema [{0}] already exist")]
     SchemaAlreadyExist(String),
+
+    #[error("Watch revision {0} has been compacted, the oldest available revision is {1}")]
+    WatchRevisionCompacted(u64, u64),
+
+    #[error("Lease {0} does not exist")]
+    KvLeaseDoesNotExist(u64),
+
+    #[error("Lease {0} already exist")]
+    KvLeaseAlreadyExist(u64),
+
+    #[error("Metadata resource {0} is not supported")]
+    UnsupportedMetadataResource(String),
+
+    #[error("Metadata archive version {0} is newer than this meta service supports")]
+    UnsupportedMetadataArchiveVersion(u32),
 }
@@ -28,6 +28,10 @@ pub struct NodeHeartbeatData {
     pub cluster_name: String,
     pub node_id: u64,
     pub time: u64,
+    #[serde(default)]
+    pub draining: bool,
+    #[serde(default)]
+    pub connection_num: u64,
 }
 
 pub struct BrokerHeartbeat {
@@ -96,7 +100,7 @@ pub async fn start(&self) {
                     }
                 } else {
                     self.cluster_cache
-                        .report_broker_heart(&cluster_name, node.node_id);
+                        .report_broker_heart(&cluster_name, node.node_id, false, 0);
                 }
             }
         }
@@ -0,0 +1,60 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::core::cache::CacheManager;
+use crate::raft::route::apply::StorageDriver;
+use crate::raft::route::data::{StorageData, StorageDataType};
+use common_base::error::ResultCommonError;
+use common_base::tools::loop_select_ticket;
+use prost::Message;
+use protocol::meta::meta_service_kv::LeaseRevokeRequest;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tracing::{error, info};
+
+// Only runs on the leader, the revoke is replicated so every node drops the lease and its keys.
+pub async fn start_kv_lease_expire_check(
+    cache_manager: Arc<CacheManager>,
+    raft_machine_apply: Arc<StorageDriver>,
+    stop_send: broadcast::Sender<bool>,
+) {
+    let ac_fn = async || -> ResultCommonError {
+        revoke_expired_kv_lease(&cache_manager, &raft_machine_apply).await;
+        Ok(())
+    };
+    loop_select_ticket(ac_fn, 1, &stop_send).await;
+}
+
+async fn revoke_expired_kv_lease(
+    cache_manager: &Arc<CacheManager>,
+    raft_machine_apply: &Arc<StorageDriver>,
+) {
+    for lease_id in cache_manager.get_expired_kv_lease() {
+        let req = LeaseRevokeRequest { lease_id };
+        let data = StorageData::new(
+            StorageDataType::KvLeaseRevoke,
+            LeaseRevokeRequest::encode_to_vec(&req),
+        );
+        match raft_machine_apply.client_write(data).await {
+            Ok(_) => info!(
+                "Lease {} has expired, the keys attached to it have been deleted",
+                lease_id
+            ),
+            Err(e) => error!(
+                "Failed to revoke expired lease {}, error message: {}",
+                lease_id, e
+            ),
+        }
+    }
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod backup;
 pub mod cache;
 pub mod cache_journal;
 pub mod cache_mqtt;
@@ -19,6 +20,8 @@
 pub mod controller;
 pub mod error;
 pub mod heartbeat;
+pub mod lease;
 pub mod log;
 pub mod metrics;
 pub mod schema;
+pub mod watch;
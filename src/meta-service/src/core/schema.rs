@@ -33,6 +33,7 @@
     ListSchemaRequest, UnBindSchemaRequest, UpdateSchemaRequest,
 };
 use rocksdb_engine::RocksDBEngine;
+use schema_register::compatibility::{check_compatibility, schema_check};
 use std::sync::Arc;
 
 pub fn list_schema_req(
@@ -91,13 +92,20 @@ pub async fn create_schema_req(
             "schema_name".to_string(),
         ))
     } else {
+        let mut schema = serde_json::from_slice::<SchemaData>(&req.schema)?;
+        schema_check(&schema)?;
+        schema.version = 1;
+
+        let request = CreateSchemaRequest {
+            schema: schema.encode(),
+            ..req.clone()
+        };
         let data = StorageData::new(
             StorageDataType::SchemaSet,
-            CreateSchemaRequest::encode_to_vec(req),
+            CreateSchemaRequest::encode_to_vec(&request),
         );
         raft_machine_apply.client_write(data).await?;
 
-        let schema = serde_json::from_slice::<SchemaData>(&req.schema)?;
         update_cache_by_add_schema(&req.cluster_name, call_manager, client_pool, schema).await?;
         Ok(())
     }
@@ -111,7 +119,7 @@ pub async fn update_schema_req(
     req: &UpdateSchemaRequest,
 ) -> Result<(), MetaServiceError> {
     let storage = SchemaStorage::new(rocksdb_engine_handler.clone());
-    if storage.get(&req.cluster_name, &req.schema_name)?.is_none() {
+    let Some(old_schema) = storage.get(&req.cluster_name, &req.schema_name)? else {
         return Err(MetaServiceError::SchemaNotFound(req.schema_name.clone()));
     };
 
@@ -133,13 +141,21 @@ pub async fn update_schema_req(
         ));
     }
 
+    let mut schema = serde_json::from_slice::<SchemaData>(&req.schema)?;
+    schema_check(&schema)?;
+    check_compatibility(&old_schema, &schema)?;
+    schema.version = old_schema.version + 1;
+
+    let request = UpdateSchemaRequest {
+        schema: schema.encode(),
+        ..req.clone()
+    };
     let data = StorageData::new(
         StorageDataType::SchemaSet,
-        UpdateSchemaRequest::encode_to_vec(req),
+        UpdateSchemaRequest::encode_to_vec(&request),
     );
     raft_machine_apply.client_write(data).await?;
 
-    let schema = serde_json::from_slice::<SchemaData>(&req.schema)?;
     update_cache_by_add_schema(&req.cluster_name, call_manager, client_pool, schema).await?;
     Ok(())
 }
@@ -0,0 +1,213 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+use std::sync::RwLock;
+
+use protocol::meta::meta_service_kv::{WatchEvent, WatchEventType};
+use tokio::sync::broadcast;
+
+use crate::core::error::MetaServiceError;
+
+pub const DEFAULT_WATCH_HISTORY_SIZE: usize = 10000;
+
+const WATCH_CHANNEL_SIZE: usize = 1024;
+
+#[derive(Default)]
+struct WatchHistory {
+    events: VecDeque<WatchEvent>,
+    revision: u64,
+    compact_revision: u64,
+}
+
+// Revisions are the raft log index of the entry that produced the event, so they are
+// identical on every node and keep increasing across restarts.
+pub struct KvWatchManager {
+    history: RwLock<WatchHistory>,
+    history_size: usize,
+    sender: broadcast::Sender<WatchEvent>,
+}
+
+impl KvWatchManager {
+    pub fn new(history_size: usize) -> Self {
+        let (sender, _) = broadcast::channel(WATCH_CHANNEL_SIZE);
+        KvWatchManager {
+            history: RwLock::new(WatchHistory::default()),
+            history_size,
+            sender,
+        }
+    }
+
+    // All events of one log entry (a transaction or a lease revoke) share its revision
+    // and are published together.
+    pub fn publish(&self, revision: u64, events: Vec<(WatchEventType, String, String)>) {
+        if events.is_empty() {
+            return;
+        }
+
+        let mut history = self.history.write().unwrap();
+
+        // log entries may be re-applied after a snapshot is installed
+        if revision <= history.revision {
+            return;
+        }
+
+        // events before the first one seen by this process are unknown
+        if history.revision == 0 {
+            history.compact_revision = revision - 1;
+        }
+
+        for (event_type, key, value) in events {
+            let event = WatchEvent {
+                event_type: event_type.into(),
+                key,
+                value,
+                revision,
+            };
+
+            history.events.push_back(event.clone());
+
+            // sending under the lock keeps history and live events free of gaps and duplicates
+            let _ = self.sender.send(event);
+        }
+        while history.events.len() > self.history_size {
+            if let Some(evicted) = history.events.pop_front() {
+                history.compact_revision = evicted.revision;
+            }
+        }
+        history.revision = revision;
+    }
+
+    pub fn watch(
+        &self,
+        prefix: &str,
+        start_revision: u64,
+    ) -> Result<(Vec<WatchEvent>, broadcast::Receiver<WatchEvent>), MetaServiceError> {
+        let history = self.history.read().unwrap();
+
+        if start_revision > 0 && start_revision <= history.compact_revision {
+            return Err(MetaServiceError::WatchRevisionCompacted(
+                start_revision,
+                history.compact_revision + 1,
+            ));
+        }
+
+        let events = if start_revision > 0 {
+            history
+                .events
+                .iter()
+                .filter(|event| event.revision >= start_revision && event.key.starts_with(prefix))
+                .cloned()
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Ok((events, self.sender.subscribe()))
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.history.read().unwrap().revision
+    }
+
+    pub fn compact_revision(&self) -> u64 {
+        self.history.read().unwrap().compact_revision
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::KvWatchManager;
+    use crate::core::error::MetaServiceError;
+    use protocol::meta::meta_service_kv::WatchEventType;
+
+    #[tokio::test]
+    async fn watch_replay_and_live_test() {
+        let manager = KvWatchManager::new(100);
+        manager.publish(
+            3,
+            vec![(WatchEventType::Put, "/a/1".to_string(), "v1".to_string())],
+        );
+        manager.publish(
+            5,
+            vec![(WatchEventType::Put, "/b/1".to_string(), "v2".to_string())],
+        );
+        manager.publish(
+            7,
+            vec![(WatchEventType::Delete, "/a/1".to_string(), "".to_string())],
+        );
+        assert_eq!(manager.revision(), 7);
+        assert_eq!(manager.compact_revision(), 2);
+
+        let (events, mut recv) = manager.watch("/a/", 4).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].revision, 7);
+        assert_eq!(events[0].event_type(), WatchEventType::Delete);
+
+        let (events, _) = manager.watch("/a/", 0).unwrap();
+        assert!(events.is_empty());
+
+        // duplicated revision is ignored
+        manager.publish(
+            7,
+            vec![(WatchEventType::Put, "/a/2".to_string(), "v3".to_string())],
+        );
+        manager.publish(
+            8,
+            vec![
+                (WatchEventType::Put, "/a/2".to_string(), "v3".to_string()),
+                (WatchEventType::Put, "/a/3".to_string(), "v4".to_string()),
+            ],
+        );
+        manager.publish(
+            8,
+            vec![(WatchEventType::Put, "/a/4".to_string(), "v5".to_string())],
+        );
+        let event = recv.recv().await.unwrap();
+        assert_eq!(event.revision, 8);
+        assert_eq!(event.key, "/a/2");
+        let event = recv.recv().await.unwrap();
+        assert_eq!(event.revision, 8);
+        assert_eq!(event.key, "/a/3");
+        assert!(recv.try_recv().is_err());
+    }
+
+    #[test]
+    fn watch_compacted_test() {
+        let manager = KvWatchManager::new(2);
+        for revision in 1..=5 {
+            manager.publish(
+                revision,
+                vec![(
+                    WatchEventType::Put,
+                    format!("/k/{revision}"),
+                    "v".to_string(),
+                )],
+            );
+        }
+        assert_eq!(manager.compact_revision(), 3);
+
+        let err = manager.watch("/k/", 2).unwrap_err();
+        assert!(matches!(
+            err,
+            MetaServiceError::WatchRevisionCompacted(2, 4)
+        ));
+
+        let (events, _) = manager.watch("/k/", 4).unwrap();
+        assert_eq!(
+            events.iter().map(|e| e.revision).collect::<Vec<u64>>(),
+            vec![4, 5]
+        );
+    }
+}
@@ -18,6 +18,7 @@
 use crate::controller::mqtt::connector::scheduler::start_connector_scheduler;
 use crate::core::cache::{load_cache, CacheManager};
 use crate::core::controller::ClusterController;
+use crate::core::watch::KvWatchManager;
 use crate::raft::raft_node::start_raft_node;
 use crate::raft::route::apply::StorageDriver;
 use crate::raft::type_config::TypeConfig;
@@ -49,6 +50,8 @@ pub struct MetaServiceServerParams {
     pub journal_call_manager: Arc<JournalInnerCallManager>,
     // Global call thread manager
     pub mqtt_call_manager: Arc<MQTTInnerCallManager>,
+    // Revisioned change events of the kv storage
+    pub kv_watch_manager: Arc<KvWatchManager>,
 }
 pub struct MetaServiceServer {
     raf_node: Raft<TypeConfig>,
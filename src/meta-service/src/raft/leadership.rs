@@ -15,7 +15,7 @@
 use super::type_config::TypeConfig;
 use crate::{
     controller::{journal::StorageEngineController, mqtt::MqttController},
-    core::cache::CacheManager,
+    core::{cache::CacheManager, lease::start_kv_lease_expire_check},
     raft::route::apply::StorageDriver,
 };
 use grpc_clients::pool::ClientPool;
@@ -115,6 +115,12 @@ pub fn start_controller(
     tokio::spawn(async move {
         journal_controller.start().await;
     });
+
+    let cache_manager = cache_manager.clone();
+    let raft_machine_apply = raft_machine_apply.clone();
+    tokio::spawn(async move {
+        start_kv_lease_expire_check(cache_manager, raft_machine_apply, stop_send).await;
+    });
 }
 
 pub fn stop_controller(stop_send: Sender<bool>) {
@@ -19,7 +19,7 @@
 use broker_core::rocksdb::storage_raft_fold;
 use common_config::broker::broker_config;
 use grpc_clients::pool::ClientPool;
-use openraft::{Config, Raft};
+use openraft::{Config, Raft, SnapshotPolicy};
 use std::collections::BTreeMap;
 use std::fmt::Display;
 use std::path::Path;
@@ -64,6 +64,16 @@ pub async fn start_raft_node(raft_node: Raft<TypeConfig>) {
 
     info!("Raft Nodes:{:?}", nodes);
 
+    // A node missing from meta_addrs is joining a running cluster, it waits to be
+    // added as a learner instead of bootstrapping a cluster of its own.
+    if !nodes.contains_key(&conf.broker_id) {
+        info!(
+            "Node {} is not in meta_addrs, waiting to be added to the cluster as a learner",
+            conf.broker_id
+        );
+        return;
+    }
+
     match raft_node.is_initialized().await {
         Ok(flag) => {
             info!("Whether nodes should be initialized, flag={}", flag);
@@ -88,15 +98,20 @@ pub async fn create_raft_node(
     client_pool: Arc<ClientPool>,
     route: Arc<DataRoute>,
 ) -> Raft<TypeConfig> {
+    let conf = broker_config();
     let config = Config {
         heartbeat_interval: 250,
         election_timeout_min: 299,
         allow_log_reversion: Some(true),
+        snapshot_policy: SnapshotPolicy::LogsSinceLast(conf.meta_raft.snapshot_logs_since_last),
+        snapshot_max_chunk_size: conf.meta_raft.snapshot_max_chunk_size,
+        install_snapshot_timeout: conf.meta_raft.install_snapshot_timeout_ms,
+        max_in_snapshot_log_to_keep: conf.meta_raft.max_in_snapshot_log_to_keep,
+        purge_batch_size: conf.meta_raft.purge_batch_size,
         ..Default::default()
     };
 
     let config = Arc::new(config.validate().unwrap());
-    let conf = broker_config();
     let path = storage_raft_fold(&conf.rocksdb.data_path);
     let dir = Path::new(&path);
     let (log_store, state_machine_store) = new_storage(&dir, route).await;
@@ -15,11 +15,19 @@
 use crate::core::error::MetaServiceError;
 use crate::raft::route::data::StorageData;
 use crate::raft::type_config::TypeConfig;
+use common_config::broker::broker_config;
+use common_config::config::MetaReadPolicy;
+use grpc_clients::meta::openraft::call::placement_openraft_read_index;
+use grpc_clients::pool::ClientPool;
 use openraft::raft::ClientWriteResponse;
-use openraft::Raft;
+use openraft::{Raft, ReadPolicy};
+use protocol::meta::meta_service_openraft::ReadIndexRequest;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::timeout;
 
+const READ_INDEX_WAIT_TIMEOUT_SEC: u64 = 10;
+
 pub struct StorageDriver {
     pub raft_node: Raft<TypeConfig>,
 }
@@ -39,6 +47,39 @@ pub async fn client_write(
         }
     }
 
+    // Blocks until the local state machine is fresh enough to serve a linearizable read,
+    // followers fetch the read index from the leader.
+    pub async fn linearizable_read(
+        &self,
+        client_pool: &Arc<ClientPool>,
+    ) -> Result<(), MetaServiceError> {
+        let metrics = self.raft_node.metrics().borrow().clone();
+        let Some(leader_id) = metrics.current_leader else {
+            return Err(MetaServiceError::CommonError(
+                "No raft leader is available, linearizable read is not possible".to_string(),
+            ));
+        };
+
+        let read_index = if leader_id == metrics.id {
+            read_index(&self.raft_node).await?
+        } else {
+            let Some(leader) = metrics.membership_config.membership().get_node(&leader_id) else {
+                return Err(MetaServiceError::NodeDoesNotExist(leader_id));
+            };
+            let addrs = vec![leader.rpc_addr.clone()];
+            placement_openraft_read_index(client_pool, &addrs, ReadIndexRequest::default())
+                .await?
+                .read_index
+        };
+
+        self.raft_node
+            .wait(Some(Duration::from_secs(READ_INDEX_WAIT_TIMEOUT_SEC)))
+            .applied_index_at_least(Some(read_index), "linearizable read")
+            .await
+            .map_err(|e| MetaServiceError::CommonError(e.to_string()))?;
+        Ok(())
+    }
+
     async fn raft_write(
         &self,
         data: StorageData,
@@ -47,3 +88,16 @@ async fn raft_write(
         Ok(resp?)
     }
 }
+
+// Leader only, returns the log index a linearizable read has to wait for.
+pub async fn read_index(raft_node: &Raft<TypeConfig>) -> Result<u64, MetaServiceError> {
+    let read_policy = match broker_config().meta_runtime.read_policy {
+        MetaReadPolicy::ReadIndex => ReadPolicy::ReadIndex,
+        MetaReadPolicy::LeaseRead => ReadPolicy::LeaseRead,
+    };
+    let (read_log_id, _) = raft_node
+        .get_read_log_id(read_policy)
+        .await
+        .map_err(|e| MetaServiceError::CommonError(e.to_string()))?;
+    Ok(read_log_id.map(|log_id| log_id.index).unwrap_or(0))
+}
@@ -45,6 +45,10 @@ pub enum StorageDataType {
     // KV
     KvSet,
     KvDelete,
+    KvTxn,
+    KvLeaseGrant,
+    KvLeaseRevoke,
+    KvLeaseKeepAlive,
 
     // Common
     SchemaSet,
@@ -57,6 +61,7 @@ pub enum StorageDataType {
     IdempotentDataDelete,
     OffsetSet,
     OffsetDelete,
+    MetadataImport,
 
     // Journal
     JournalSetShard,
@@ -101,6 +106,10 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 
             StorageDataType::KvSet => write!(f, "KvSet"),
             StorageDataType::KvDelete => write!(f, "KvDelete"),
+            StorageDataType::KvTxn => write!(f, "KvTxn"),
+            StorageDataType::KvLeaseGrant => write!(f, "KvLeaseGrant"),
+            StorageDataType::KvLeaseRevoke => write!(f, "KvLeaseRevoke"),
+            StorageDataType::KvLeaseKeepAlive => write!(f, "KvLeaseKeepAlive"),
 
             StorageDataType::SchemaSet => write!(f, "SchemaSet"),
             StorageDataType::SchemaDelete => write!(f, "SchemaDelete"),
@@ -112,6 +121,7 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             StorageDataType::IdempotentDataDelete => write!(f, "IdempotentDataDelete"),
             StorageDataType::OffsetSet => write!(f, "OffsetSet"),
             StorageDataType::OffsetDelete => write!(f, "OffsetDelete"),
+            StorageDataType::MetadataImport => write!(f, "MetadataImport"),
 
             StorageDataType::JournalSetShard => write!(f, "JournalSetShard"),
             StorageDataType::JournalDeleteShard => write!(f, "JournalDeleteShard"),
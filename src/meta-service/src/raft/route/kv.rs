@@ -12,32 +12,525 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::sync::Arc;
 
+use common_base::tools::now_second;
 use prost::Message as _;
-use protocol::meta::meta_service_kv::{DeleteRequest, SetRequest};
+use protocol::meta::meta_service_kv::{
+    Compare, CompareResult, CompareTarget, DeleteRequest, LeaseGrantRequest, LeaseKeepAliveRequest,
+    LeaseRevokeRequest, SetRequest, TxnOpResult, TxnOpType, TxnReply, TxnRequest, WatchEventType,
+};
 
+use crate::core::cache::CacheManager;
 use crate::core::error::MetaServiceError;
-use crate::storage::placement::kv::KvStorage;
+use crate::core::watch::KvWatchManager;
+use crate::storage::engine_meta::{encode_meta_value, engine_batch_by_meta};
+use crate::storage::keys::key_kv_lease;
+use crate::storage::placement::kv::{KvEntry, KvStorage};
+use crate::storage::placement::lease::{KvLease, KvLeaseStorage};
 use rocksdb_engine::RocksDBEngine;
 
-#[derive(Debug, Clone)]
+// Changes made by one request. The later ops of the request read them back, they are
+// written in one rocksdb batch so that a failed request leaves nothing behind, and the
+// watch events are published only after that write.
+#[derive(Default)]
+struct KvMutations {
+    // (key, entry), None deletes the key
+    entries: HashMap<String, Option<KvEntry>>,
+    // (lease id, lease), None deletes the lease
+    leases: HashMap<u64, Option<KvLease>>,
+    events: Vec<(WatchEventType, String, String)>,
+}
+
+#[derive(Clone)]
 pub struct DataRouteKv {
     kv_storage: KvStorage,
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+    cache_manager: Arc<CacheManager>,
+    watch_manager: Arc<KvWatchManager>,
 }
 
 impl DataRouteKv {
-    pub fn new(rocksdb_engine_handler: Arc<RocksDBEngine>) -> Self {
+    pub fn new(
+        rocksdb_engine_handler: Arc<RocksDBEngine>,
+        cache_manager: Arc<CacheManager>,
+        watch_manager: Arc<KvWatchManager>,
+    ) -> Self {
         let kv_storage = KvStorage::new(rocksdb_engine_handler.clone());
-        DataRouteKv { kv_storage }
+        DataRouteKv {
+            kv_storage,
+            rocksdb_engine_handler,
+            cache_manager,
+            watch_manager,
+        }
     }
-    pub fn set(&self, value: Vec<u8>) -> Result<(), MetaServiceError> {
+    pub fn set(&self, value: Vec<u8>, revision: u64) -> Result<(), MetaServiceError> {
         let req: SetRequest = SetRequest::decode(value.as_ref())?;
-        Ok(self.kv_storage.set(req.key, req.value)?)
+        let mut mutations = KvMutations::default();
+        self.put_key(&mut mutations, &req.key, req.value, req.lease_id, revision)?;
+        self.commit(mutations, revision)
     }
 
-    pub fn delete(&self, value: Vec<u8>) -> Result<(), MetaServiceError> {
+    pub fn delete(&self, value: Vec<u8>, revision: u64) -> Result<(), MetaServiceError> {
         let req: DeleteRequest = DeleteRequest::decode(value.as_ref())?;
-        Ok(self.kv_storage.delete(req.key)?)
+        let mut mutations = KvMutations::default();
+        self.delete_key(&mut mutations, &req.key)?;
+        self.commit(mutations, revision)
+    }
+
+    pub fn txn(&self, value: Vec<u8>, revision: u64) -> Result<Vec<u8>, MetaServiceError> {
+        let req: TxnRequest = TxnRequest::decode(value.as_ref())?;
+        let reply = self.execute_txn(&req, revision)?;
+        Ok(TxnReply::encode_to_vec(&reply))
+    }
+
+    pub fn lease_grant(&self, value: Vec<u8>) -> Result<(), MetaServiceError> {
+        let req: LeaseGrantRequest = LeaseGrantRequest::decode(value.as_ref())?;
+        let lease = KvLease {
+            lease_id: req.lease_id,
+            ttl: req.ttl,
+            keys: Vec::new(),
+            create_time: now_second(),
+        };
+        self.lease_storage().save(&lease)?;
+        self.cache_manager
+            .refresh_kv_lease(lease.lease_id, lease.ttl);
+        Ok(())
+    }
+
+    pub fn lease_revoke(&self, value: Vec<u8>, revision: u64) -> Result<(), MetaServiceError> {
+        let req: LeaseRevokeRequest = LeaseRevokeRequest::decode(value.as_ref())?;
+        if let Some(lease) = self.lease_storage().get(req.lease_id)? {
+            let mut mutations = KvMutations::default();
+            for key in lease.keys {
+                self.delete_key(&mut mutations, &key)?;
+            }
+            mutations.leases.insert(lease.lease_id, None);
+            self.commit(mutations, revision)?;
+        }
+        self.cache_manager.remove_kv_lease(req.lease_id);
+        Ok(())
+    }
+
+    pub fn lease_keep_alive(&self, value: Vec<u8>) -> Result<(), MetaServiceError> {
+        let req: LeaseKeepAliveRequest = LeaseKeepAliveRequest::decode(value.as_ref())?;
+        if let Some(lease) = self.lease_storage().get(req.lease_id)? {
+            self.cache_manager
+                .refresh_kv_lease(lease.lease_id, lease.ttl);
+            return Ok(());
+        }
+        Err(MetaServiceError::KvLeaseDoesNotExist(req.lease_id))
+    }
+
+    fn execute_txn(&self, req: &TxnRequest, revision: u64) -> Result<TxnReply, MetaServiceError> {
+        let mut mutations = KvMutations::default();
+        let mut succeeded = true;
+        for compare in req.compare.iter() {
+            let entry = self.kv_storage.get_entry(compare.key.clone())?;
+            if !compare_entry(entry.as_ref(), compare) {
+                succeeded = false;
+                break;
+            }
+        }
+
+        let ops = if succeeded {
+            &req.success
+        } else {
+            &req.failure
+        };
+
+        let mut results = Vec::new();
+        for op in ops.iter() {
+            match op.op_type() {
+                TxnOpType::Put => {
+                    self.put_key(
+                        &mut mutations,
+                        &op.key,
+                        op.value.clone(),
+                        op.lease_id,
+                        revision,
+                    )?;
+                }
+                TxnOpType::Del => {
+                    self.delete_key(&mut mutations, &op.key)?;
+                }
+                TxnOpType::Get => {
+                    let entry = self.get_entry(&mutations, &op.key)?;
+                    results.push(TxnOpResult {
+                        key: op.key.clone(),
+                        exists: entry.is_some(),
+                        value: entry.clone().map(|e| e.value).unwrap_or_default(),
+                        mod_revision: entry.map(|e| e.mod_revision).unwrap_or_default(),
+                    });
+                }
+            }
+        }
+        self.commit(mutations, revision)?;
+
+        Ok(TxnReply {
+            succeeded,
+            results,
+            revision,
+        })
+    }
+
+    fn put_key(
+        &self,
+        mutations: &mut KvMutations,
+        key: &str,
+        value: String,
+        lease_id: u64,
+        revision: u64,
+    ) -> Result<(), MetaServiceError> {
+        if lease_id > 0 && self.get_lease(mutations, lease_id)?.is_none() {
+            return Err(MetaServiceError::KvLeaseDoesNotExist(lease_id));
+        }
+
+        let old = self.get_entry(mutations, key)?;
+        let (create_revision, version, old_lease_id) = match &old {
+            Some(entry) => (entry.create_revision, entry.version + 1, entry.lease_id),
+            None => (revision, 1, 0),
+        };
+
+        if old_lease_id != lease_id {
+            self.detach_lease(mutations, old_lease_id, key)?;
+            self.attach_lease(mutations, lease_id, key)?;
+        }
+
+        let entry = KvEntry {
+            value: value.clone(),
+            create_revision,
+            mod_revision: revision,
+            version,
+            lease_id,
+        };
+        mutations.entries.insert(key.to_string(), Some(entry));
+        mutations
+            .events
+            .push((WatchEventType::Put, key.to_string(), value));
+        Ok(())
+    }
+
+    fn delete_key(&self, mutations: &mut KvMutations, key: &str) -> Result<(), MetaServiceError> {
+        let Some(old) = self.get_entry(mutations, key)? else {
+            return Ok(());
+        };
+
+        self.detach_lease(mutations, old.lease_id, key)?;
+        mutations.entries.insert(key.to_string(), None);
+        mutations
+            .events
+            .push((WatchEventType::Delete, key.to_string(), String::new()));
+        Ok(())
+    }
+
+    fn attach_lease(
+        &self,
+        mutations: &mut KvMutations,
+        lease_id: u64,
+        key: &str,
+    ) -> Result<(), MetaServiceError> {
+        if lease_id == 0 {
+            return Ok(());
+        }
+        if let Some(mut lease) = self.get_lease(mutations, lease_id)? {
+            if !lease.keys.iter().any(|k| k == key) {
+                lease.keys.push(key.to_string());
+                mutations.leases.insert(lease_id, Some(lease));
+            }
+        }
+        Ok(())
+    }
+
+    fn detach_lease(
+        &self,
+        mutations: &mut KvMutations,
+        lease_id: u64,
+        key: &str,
+    ) -> Result<(), MetaServiceError> {
+        if lease_id == 0 {
+            return Ok(());
+        }
+        if let Some(mut lease) = self.get_lease(mutations, lease_id)? {
+            lease.keys.retain(|k| k != key);
+            mutations.leases.insert(lease_id, Some(lease));
+        }
+        Ok(())
+    }
+
+    fn get_entry(
+        &self,
+        mutations: &KvMutations,
+        key: &str,
+    ) -> Result<Option<KvEntry>, MetaServiceError> {
+        if let Some(entry) = mutations.entries.get(key) {
+            return Ok(entry.clone());
+        }
+        Ok(self.kv_storage.get_entry(key.to_string())?)
+    }
+
+    fn get_lease(
+        &self,
+        mutations: &KvMutations,
+        lease_id: u64,
+    ) -> Result<Option<KvLease>, MetaServiceError> {
+        if let Some(lease) = mutations.leases.get(&lease_id) {
+            return Ok(lease.clone());
+        }
+        Ok(self.lease_storage().get(lease_id)?)
+    }
+
+    fn commit(&self, mutations: KvMutations, revision: u64) -> Result<(), MetaServiceError> {
+        let mut records = Vec::new();
+        for (key, entry) in mutations.entries {
+            records.push((key, entry.map(encode_meta_value).transpose()?));
+        }
+        for (lease_id, lease) in mutations.leases {
+            records.push((
+                key_kv_lease(lease_id),
+                lease.map(encode_meta_value).transpose()?,
+            ));
+        }
+        engine_batch_by_meta(self.rocksdb_engine_handler.clone(), records)?;
+        self.watch_manager.publish(revision, mutations.events);
+        Ok(())
+    }
+
+    fn lease_storage(&self) -> KvLeaseStorage {
+        KvLeaseStorage::new(self.rocksdb_engine_handler.clone())
+    }
+}
+
+// A missing key compares as an empty value with all revisions set to 0.
+fn compare_entry(entry: Option<&KvEntry>, compare: &Compare) -> bool {
+    let missing = KvEntry::default();
+    let entry = entry.unwrap_or(&missing);
+    let ordering = match compare.target() {
+        CompareTarget::Value => entry.value.as_str().cmp(compare.value.as_str()),
+        CompareTarget::ModRevision => entry.mod_revision.cmp(&compare.revision),
+        CompareTarget::CreateRevision => entry.create_revision.cmp(&compare.revision),
+        CompareTarget::Version => entry.version.cmp(&compare.revision),
+    };
+
+    match compare.result() {
+        CompareResult::Equal => ordering == Ordering::Equal,
+        CompareResult::NotEqual => ordering != Ordering::Equal,
+        CompareResult::Greater => ordering == Ordering::Greater,
+        CompareResult::Less => ordering == Ordering::Less,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DataRouteKv;
+    use crate::core::cache::CacheManager;
+    use crate::core::watch::{KvWatchManager, DEFAULT_WATCH_HISTORY_SIZE};
+    use crate::storage::placement::kv::KvStorage;
+    use broker_core::rocksdb::column_family_list;
+    use prost::Message;
+    use protocol::meta::meta_service_kv::{
+        Compare, CompareResult, CompareTarget, LeaseGrantRequest, LeaseRevokeRequest, SetRequest,
+        TxnOp, TxnOpType, TxnReply, TxnRequest,
+    };
+    use rocksdb_engine::RocksDBEngine;
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    fn setup_route() -> (DataRouteKv, KvStorage) {
+        let engine = Arc::new(RocksDBEngine::new(
+            tempdir().unwrap().path().to_str().unwrap(),
+            100,
+            column_family_list(),
+        ));
+        let cache_manager = Arc::new(CacheManager::new(engine.clone()));
+        let watch_manager = Arc::new(KvWatchManager::new(DEFAULT_WATCH_HISTORY_SIZE));
+        (
+            DataRouteKv::new(engine.clone(), cache_manager, watch_manager),
+            KvStorage::new(engine),
+        )
+    }
+
+    fn put_op(key: &str, value: &str) -> TxnOp {
+        TxnOp {
+            op_type: TxnOpType::Put.into(),
+            key: key.to_string(),
+            value: value.to_string(),
+            lease_id: 0,
+        }
+    }
+
+    fn run_txn(route: &DataRouteKv, req: TxnRequest, revision: u64) -> TxnReply {
+        let data = route
+            .txn(TxnRequest::encode_to_vec(&req), revision)
+            .unwrap();
+        TxnReply::decode(data.as_ref()).unwrap()
+    }
+
+    #[test]
+    fn txn_compare_and_swap_test() {
+        let (route, kv_storage) = setup_route();
+
+        // create only if the key does not exist
+        let create = TxnRequest {
+            compare: vec![Compare {
+                key: "/lock".to_string(),
+                target: CompareTarget::CreateRevision.into(),
+                result: CompareResult::Equal.into(),
+                value: "".to_string(),
+                revision: 0,
+            }],
+            success: vec![put_op("/lock", "node-1")],
+            failure: vec![TxnOp {
+                op_type: TxnOpType::Get.into(),
+                key: "/lock".to_string(),
+                ..Default::default()
+            }],
+        };
+        let reply = run_txn(&route, create.clone(), 10);
+        assert!(reply.succeeded);
+        let entry = kv_storage.get_entry("/lock".to_string()).unwrap().unwrap();
+        assert_eq!(entry.create_revision, 10);
+        assert_eq!(entry.mod_revision, 10);
+        assert_eq!(entry.version, 1);
+
+        let reply = run_txn(&route, create, 11);
+        assert!(!reply.succeeded);
+        assert_eq!(reply.results[0].value, "node-1");
+        assert_eq!(reply.results[0].mod_revision, 10);
+
+        // swap on mod revision
+        let swap = TxnRequest {
+            compare: vec![Compare {
+                key: "/lock".to_string(),
+                target: CompareTarget::ModRevision.into(),
+                result: CompareResult::Equal.into(),
+                value: "".to_string(),
+                revision: 10,
+            }],
+            success: vec![put_op("/lock", "node-2"), put_op("/owner", "node-2")],
+            failure: vec![],
+        };
+        assert!(run_txn(&route, swap.clone(), 12).succeeded);
+        let entry = kv_storage.get_entry("/lock".to_string()).unwrap().unwrap();
+        assert_eq!(entry.value, "node-2");
+        assert_eq!(entry.create_revision, 10);
+        assert_eq!(entry.mod_revision, 12);
+        assert_eq!(entry.version, 2);
+        assert!(kv_storage.exists("/owner".to_string()).unwrap());
+
+        assert!(!run_txn(&route, swap, 13).succeeded);
+
+        // a key written before revisions were tracked exists
+        kv_storage
+            .set("/legacy".to_string(), "node-0".to_string())
+            .unwrap();
+        let create = TxnRequest {
+            compare: vec![Compare {
+                key: "/legacy".to_string(),
+                target: CompareTarget::CreateRevision.into(),
+                result: CompareResult::Equal.into(),
+                value: "".to_string(),
+                revision: 0,
+            }],
+            success: vec![put_op("/legacy", "node-1")],
+            failure: vec![],
+        };
+        assert!(!run_txn(&route, create, 14).succeeded);
+        let entry = kv_storage
+            .get_entry("/legacy".to_string())
+            .unwrap()
+            .unwrap();
+        assert_eq!(entry.value, "node-0");
+    }
+
+    #[test]
+    fn lease_revoke_test() {
+        let (route, kv_storage) = setup_route();
+        let grant = LeaseGrantRequest {
+            ttl: 10,
+            lease_id: 100,
+        };
+        route
+            .lease_grant(LeaseGrantRequest::encode_to_vec(&grant))
+            .unwrap();
+        assert!(route.cache_manager.kv_lease_expire.contains_key(&100));
+
+        let set = SetRequest {
+            key: "/session/1".to_string(),
+            value: "v".to_string(),
+            lease_id: 100,
+        };
+        route.set(SetRequest::encode_to_vec(&set), 2).unwrap();
+
+        let set = SetRequest {
+            key: "/session/2".to_string(),
+            value: "v".to_string(),
+            lease_id: 101,
+        };
+        assert!(route.set(SetRequest::encode_to_vec(&set), 3).is_err());
+
+        let revoke = LeaseRevokeRequest { lease_id: 100 };
+        route
+            .lease_revoke(LeaseRevokeRequest::encode_to_vec(&revoke), 4)
+            .unwrap();
+        assert!(!kv_storage.exists("/session/1".to_string()).unwrap());
+        assert!(!route.cache_manager.kv_lease_expire.contains_key(&100));
+    }
+
+    #[test]
+    fn txn_atomic_test() {
+        let (route, kv_storage) = setup_route();
+
+        // a failed op leaves nothing of the txn behind and publishes no event
+        let req = TxnRequest {
+            compare: vec![],
+            success: vec![
+                put_op("/a", "1"),
+                TxnOp {
+                    op_type: TxnOpType::Put.into(),
+                    key: "/b".to_string(),
+                    value: "1".to_string(),
+                    lease_id: 200,
+                },
+            ],
+            failure: vec![],
+        };
+        assert!(route.txn(TxnRequest::encode_to_vec(&req), 2).is_err());
+        assert!(!kv_storage.exists("/a".to_string()).unwrap());
+        assert_eq!(route.watch_manager.revision(), 0);
+
+        // later ops see the earlier ops of the same txn
+        let req = TxnRequest {
+            compare: vec![],
+            success: vec![
+                put_op("/a", "1"),
+                put_op("/a", "2"),
+                TxnOp {
+                    op_type: TxnOpType::Get.into(),
+                    key: "/a".to_string(),
+                    ..Default::default()
+                },
+                TxnOp {
+                    op_type: TxnOpType::Del.into(),
+                    key: "/a".to_string(),
+                    ..Default::default()
+                },
+                TxnOp {
+                    op_type: TxnOpType::Get.into(),
+                    key: "/a".to_string(),
+                    ..Default::default()
+                },
+            ],
+            failure: vec![],
+        };
+        let reply = run_txn(&route, req, 3);
+        assert_eq!(reply.results[0].value, "2");
+        assert!(!reply.results[1].exists);
+        assert!(!kv_storage.exists("/a".to_string()).unwrap());
+        assert_eq!(route.watch_manager.revision(), 3);
+        let (events, _) = route.watch_manager.watch("/a", 3).unwrap();
+        assert_eq!(events.len(), 3);
     }
 }
@@ -12,20 +12,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::core::cache::CacheManager;
+use crate::core::backup::MetadataImportBatch;
+use crate::core::cache::{load_cache, CacheManager};
 use crate::core::error::MetaServiceError;
+use crate::core::watch::KvWatchManager;
 use crate::raft::route::common::DataRouteCluster;
 use crate::raft::route::journal::DataRouteJournal;
 use crate::raft::route::kv::DataRouteKv;
 use crate::raft::route::mqtt::DataRouteMqtt;
-use bincode::{deserialize, serialize};
+use crate::raft::store::snapshot::open_checkpoint;
+use crate::raft::store::AppliedState;
+use crate::storage::keys::key_raft_applied_state;
+use crate::storage::placement::cluster::ClusterStorage;
+use bincode::deserialize;
 use broker_core::rocksdb::DB_COLUMN_FAMILY_META;
+use common_security::encryption::{open_value, seal_value};
 use data::{StorageData, StorageDataType};
+use rocksdb::checkpoint::Checkpoint;
+use rocksdb::WriteBatch;
 use rocksdb_engine::RocksDBEngine;
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Instant;
-use tracing::{error, info};
+use tracing::info;
 
 pub mod apply;
 pub mod common;
@@ -34,9 +44,15 @@
 pub mod kv;
 pub mod mqtt;
 
+const SNAPSHOT_INSTALL_BATCH_SIZE: usize = 1000;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AppResponseData {
     pub value: Option<Vec<u8>>,
+    // The entry is committed either way, the error tells the client that applying it
+    // was rejected by the state machine, e.g. a put with a lease that no longer exists.
+    #[serde(default)]
+    pub error: Option<String>,
 }
 
 #[derive(Clone)]
@@ -46,14 +62,20 @@ pub struct DataRoute {
     route_journal: DataRouteJournal,
     route_cluster: DataRouteCluster,
     rocksdb_engine_handler: Arc<RocksDBEngine>,
+    cache_manager: Arc<CacheManager>,
 }
 
 impl DataRoute {
     pub fn new(
         rocksdb_engine_handler: Arc<RocksDBEngine>,
         cache_manager: Arc<CacheManager>,
+        watch_manager: Arc<KvWatchManager>,
     ) -> DataRoute {
-        let route_kv = DataRouteKv::new(rocksdb_engine_handler.clone());
+        let route_kv = DataRouteKv::new(
+            rocksdb_engine_handler.clone(),
+            cache_manager.clone(),
+            watch_manager,
+        );
         let route_mqtt = DataRouteMqtt::new(rocksdb_engine_handler.clone(), cache_manager.clone());
         let route_cluster =
             DataRouteCluster::new(rocksdb_engine_handler.clone(), cache_manager.clone());
@@ -65,22 +87,38 @@ pub fn new(
             route_journal,
             route_cluster,
             rocksdb_engine_handler,
+            cache_manager,
         }
     }
 
     //Receive write operations performed by the Raft state machine and write subsequent service data after Raft state machine synchronization is complete.
+    //The revision is the raft log index of the entry and is carried by watch events.
     pub async fn route(
         &self,
         storage_data: StorageData,
+        revision: u64,
     ) -> Result<Option<Vec<u8>>, MetaServiceError> {
         match storage_data.data_type {
             // Meta Service
             StorageDataType::KvSet => {
-                self.route_kv.set(storage_data.value)?;
+                self.route_kv.set(storage_data.value, revision)?;
                 Ok(None)
             }
             StorageDataType::KvDelete => {
-                self.route_kv.delete(storage_data.value)?;
+                self.route_kv.delete(storage_data.value, revision)?;
+                Ok(None)
+            }
+            StorageDataType::KvTxn => Ok(Some(self.route_kv.txn(storage_data.value, revision)?)),
+            StorageDataType::KvLeaseGrant => {
+                self.route_kv.lease_grant(storage_data.value)?;
+                Ok(None)
+            }
+            StorageDataType::KvLeaseRevoke => {
+                self.route_kv.lease_revoke(storage_data.value, revision)?;
+                Ok(None)
+            }
+            StorageDataType::KvLeaseKeepAlive => {
+                self.route_kv.lease_keep_alive(storage_data.value)?;
                 Ok(None)
             }
             StorageDataType::ClusterAddNode => {
@@ -124,6 +162,10 @@ pub async fn route(
                 self.route_cluster.delete_offset_data(storage_data.value)?;
                 Ok(None)
             }
+            StorageDataType::MetadataImport => {
+                self.import_metadata(storage_data.value)?;
+                Ok(None)
+            }
             StorageDataType::SchemaSet => {
                 self.route_cluster.set_schema(storage_data.value)?;
                 Ok(None)
@@ -270,65 +312,113 @@ pub async fn route(
         }
     }
 
-    pub fn build_snapshot(&self) -> Vec<u8> {
-        info!("Start building snapshots");
-        let cf = if let Some(cf) = self.rocksdb_engine_handler.cf_handle(DB_COLUMN_FAMILY_META) {
-            cf
-        } else {
-            error!(
-                "{}",
-                MetaServiceError::RocksDBFamilyNotAvailable(DB_COLUMN_FAMILY_META.to_string(),)
-            );
-            return Vec::new();
+    // Takes a rocksdb checkpoint of the meta data, it only hard links the sst files so
+    // it is cheap enough to be taken while the state machine is paused.
+    pub fn get_applied_state(&self) -> Result<Option<AppliedState>, MetaServiceError> {
+        let Some(cf) = self.rocksdb_engine_handler.cf_handle(DB_COLUMN_FAMILY_META) else {
+            return Err(MetaServiceError::RocksDBFamilyNotAvailable(
+                DB_COLUMN_FAMILY_META.to_string(),
+            ));
         };
+        Ok(self
+            .rocksdb_engine_handler
+            .read::<AppliedState>(cf, &key_raft_applied_state())?)
+    }
 
-        let res = match self.rocksdb_engine_handler.read_all_by_cf(cf) {
-            Ok(data) => data,
-            Err(e) => {
-                error!("{}", e.to_string());
-                return Vec::new();
-            }
+    // Written after the data of the entry, a crash in between re-applies only that entry.
+    pub fn save_applied_state(&self, state: &AppliedState) -> Result<(), MetaServiceError> {
+        let Some(cf) = self.rocksdb_engine_handler.cf_handle(DB_COLUMN_FAMILY_META) else {
+            return Err(MetaServiceError::RocksDBFamilyNotAvailable(
+                DB_COLUMN_FAMILY_META.to_string(),
+            ));
         };
+        self.rocksdb_engine_handler
+            .write(cf, &key_raft_applied_state(), state)?;
+        Ok(())
+    }
 
-        let res = match serialize(&res) {
-            Ok(data) => data,
-            Err(e) => {
-                error!("{}", e.to_string());
-                return Vec::new();
-            }
-        };
-        info!("Snapshot built successfully, snapshot size :{}", res.len());
-        res
+    pub fn create_checkpoint(&self, path: &Path) -> Result<(), MetaServiceError> {
+        let checkpoint = Checkpoint::new(&self.rocksdb_engine_handler.db)?;
+        checkpoint.create_checkpoint(path)?;
+        Ok(())
     }
 
-    pub fn recover_snapshot(&self, data: Vec<u8>) -> Result<(), MetaServiceError> {
-        info!("Start restoring snapshot, snapshot length :{}", data.len());
+    // Replaces the meta data with the content of a checkpoint received from the leader.
+    pub fn install_checkpoint(&self, path: &Path) -> Result<u64, MetaServiceError> {
+        info!("Start installing snapshot checkpoint {:?}", path);
         let now = Instant::now();
-        let records = match deserialize::<Vec<(String, Vec<u8>)>>(&data) {
-            Ok(data) => data,
-            Err(e) => {
-                return Err(MetaServiceError::CommonError(e.to_string()));
-            }
+        let source = open_checkpoint(path)?;
+        let Some(source_cf) = source.cf_handle(DB_COLUMN_FAMILY_META) else {
+            return Err(MetaServiceError::RocksDBFamilyNotAvailable(
+                DB_COLUMN_FAMILY_META.to_string(),
+            ));
         };
-
-        let cf = if let Some(cf) = self.rocksdb_engine_handler.cf_handle(DB_COLUMN_FAMILY_META) {
-            cf
-        } else {
+        let Some(cf) = self.rocksdb_engine_handler.cf_handle(DB_COLUMN_FAMILY_META) else {
             return Err(MetaServiceError::RocksDBFamilyNotAvailable(
                 DB_COLUMN_FAMILY_META.to_string(),
             ));
         };
 
-        for raw in records {
-            self.rocksdb_engine_handler
-                .write_raw(cf.clone(), &raw.0, &raw.1)?;
+        // Keys are utf-8 strings, so they all sort before 0xFF.
+        let db = &self.rocksdb_engine_handler.db;
+        db.delete_range_cf(&cf, Vec::<u8>::new(), vec![u8::MAX])?;
+
+        let mut iter = source.raw_iterator_cf(&source_cf);
+        iter.seek_to_first();
+        let mut batch = WriteBatch::default();
+        let mut count = 0;
+        while iter.valid() {
+            if let (Some(key), Some(value)) = (iter.key(), iter.value()) {
+                batch.put_cf(&cf, key, seal_value(open_value(value.to_vec())?)?);
+                count += 1;
+                if batch.len() >= SNAPSHOT_INSTALL_BATCH_SIZE {
+                    db.write(std::mem::take(&mut batch))?;
+                }
+            }
+            iter.next();
         }
+        iter.status()?;
+        db.write(batch)?;
 
+        self.reload_cache()?;
         info!(
-            "Snapshot recovery was successful, snapshot size {}, time: {}",
-            data.len(),
+            "Snapshot checkpoint installed successfully, records: {}, time: {}",
+            count,
             now.elapsed().as_millis()
         );
+        Ok(count)
+    }
+
+    // Writes a batch of records from a metadata archive, the in-memory caches are
+    // reloaded afterwards so imported resources are visible right away.
+    pub fn import_metadata(&self, data: Vec<u8>) -> Result<(), MetaServiceError> {
+        let batch = deserialize::<MetadataImportBatch>(&data)?;
+        let Some(cf) = self.rocksdb_engine_handler.cf_handle(DB_COLUMN_FAMILY_META) else {
+            return Err(MetaServiceError::RocksDBFamilyNotAvailable(
+                DB_COLUMN_FAMILY_META.to_string(),
+            ));
+        };
+
+        // archive values are exported in plaintext and sealed again with the local key
+        let mut write_batch = WriteBatch::default();
+        for (key, value) in batch.records.iter() {
+            write_batch.put_cf(&cf, key, seal_value(value.clone())?);
+        }
+        self.rocksdb_engine_handler.db.write(write_batch)?;
+
+        if batch.last {
+            self.reload_cache()?;
+        }
+        info!("Imported {} metadata records", batch.records.len());
+        Ok(())
+    }
+
+    fn reload_cache(&self) -> Result<(), MetaServiceError> {
+        let cluster_storage = ClusterStorage::new(self.rocksdb_engine_handler.clone());
+        for cluster in cluster_storage.list()? {
+            self.cache_manager.add_broker_cluster(&cluster);
+        }
+        load_cache(&self.cache_manager, &self.rocksdb_engine_handler)?;
         Ok(())
     }
 }
@@ -336,9 +426,13 @@ pub fn recover_snapshot(&self, data: Vec<u8>) -> Result<(), MetaServiceError> {
 #[cfg(test)]
 mod test {
     use crate::core::cache::CacheManager;
+    use crate::core::watch::{KvWatchManager, DEFAULT_WATCH_HISTORY_SIZE};
 
     use super::DataRoute;
-    use broker_core::rocksdb::DB_COLUMN_FAMILY_META;
+    use crate::core::backup::MetadataImportBatch;
+    use crate::raft::store::AppliedState;
+    use bincode::serialize;
+    use broker_core::rocksdb::{column_family_list, DB_COLUMN_FAMILY_META};
     use rocksdb_engine::RocksDBEngine;
     use std::sync::Arc;
     use tempfile::tempdir;
@@ -348,7 +442,7 @@ pub fn snapshot_test() {
         let rocksdb_engine = Arc::new(RocksDBEngine::new(
             tempdir().unwrap().path().to_str().unwrap(),
             100,
-            vec![DB_COLUMN_FAMILY_META.to_string()],
+            column_family_list(),
         ));
 
         let cf = rocksdb_engine.cf_handle(DB_COLUMN_FAMILY_META).unwrap();
@@ -360,22 +454,32 @@ pub fn snapshot_test() {
         }
 
         let cache_manager = Arc::new(CacheManager::new(rocksdb_engine.clone()));
-        let data_route = DataRoute::new(rocksdb_engine.clone(), cache_manager.clone());
-        let snapshot = data_route.build_snapshot();
+        let watch_manager = Arc::new(KvWatchManager::new(DEFAULT_WATCH_HISTORY_SIZE));
+        let data_route = DataRoute::new(
+            rocksdb_engine.clone(),
+            cache_manager.clone(),
+            watch_manager.clone(),
+        );
+        let checkpoint_dir = tempdir().unwrap();
+        let checkpoint = checkpoint_dir.path().join("checkpoint");
+        data_route.create_checkpoint(&checkpoint).unwrap();
 
         // GET A NEW ONE
 
         let new_rocksdb_engine = Arc::new(RocksDBEngine::new(
             tempdir().unwrap().path().to_str().unwrap(),
             100,
-            vec![DB_COLUMN_FAMILY_META.to_string()],
+            column_family_list(),
         ));
+        let cf = new_rocksdb_engine.cf_handle(DB_COLUMN_FAMILY_META).unwrap();
+        new_rocksdb_engine
+            .write(cf.clone(), "stale-key", &100)
+            .unwrap();
 
-        let new_data_route = DataRoute::new(new_rocksdb_engine.clone(), cache_manager);
-
-        new_data_route.recover_snapshot(snapshot).unwrap();
+        let new_data_route =
+            DataRoute::new(new_rocksdb_engine.clone(), cache_manager, watch_manager);
 
-        let cf = new_rocksdb_engine.cf_handle(DB_COLUMN_FAMILY_META).unwrap();
+        assert_eq!(new_data_route.install_checkpoint(&checkpoint).unwrap(), 10);
 
         // check value again
         for i in 0..10 {
@@ -386,5 +490,61 @@ pub fn snapshot_test() {
 
             assert_eq!(i, value);
         }
+        assert!(new_rocksdb_engine
+            .read::<i32>(cf.clone(), "stale-key")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    pub fn applied_state_test() {
+        let rocksdb_engine = Arc::new(RocksDBEngine::new(
+            tempdir().unwrap().path().to_str().unwrap(),
+            100,
+            column_family_list(),
+        ));
+        let cache_manager = Arc::new(CacheManager::new(rocksdb_engine.clone()));
+        let watch_manager = Arc::new(KvWatchManager::new(DEFAULT_WATCH_HISTORY_SIZE));
+        let data_route = DataRoute::new(rocksdb_engine, cache_manager, watch_manager);
+
+        assert!(data_route.get_applied_state().unwrap().is_none());
+        let state = AppliedState {
+            last_applied_log_id: None,
+            last_membership: Default::default(),
+        };
+        data_route.save_applied_state(&state).unwrap();
+        let saved = data_route.get_applied_state().unwrap().unwrap();
+        assert_eq!(saved.last_applied_log_id, None);
+    }
+
+    #[test]
+    pub fn import_metadata_test() {
+        let rocksdb_engine = Arc::new(RocksDBEngine::new(
+            tempdir().unwrap().path().to_str().unwrap(),
+            100,
+            column_family_list(),
+        ));
+        let cache_manager = Arc::new(CacheManager::new(rocksdb_engine.clone()));
+        let watch_manager = Arc::new(KvWatchManager::new(DEFAULT_WATCH_HISTORY_SIZE));
+        let data_route = DataRoute::new(rocksdb_engine.clone(), cache_manager, watch_manager);
+
+        for (i, last) in [(0, false), (1, true)] {
+            let batch = MetadataImportBatch {
+                records: vec![(format!("key-{i}"), serde_json::to_vec(&i).unwrap())],
+                last,
+            };
+            data_route
+                .import_metadata(serialize(&batch).unwrap())
+                .unwrap();
+        }
+
+        let cf = rocksdb_engine.cf_handle(DB_COLUMN_FAMILY_META).unwrap();
+        for i in 0..2 {
+            let value = rocksdb_engine
+                .read::<i32>(cf.clone(), format!("key-{i}").as_str())
+                .unwrap()
+                .unwrap();
+            assert_eq!(i, value);
+        }
     }
 }
@@ -14,13 +14,17 @@
 
 use crate::core::error::MetaServiceError;
 use crate::raft::raft_node::Node;
+use crate::raft::route::apply::read_index;
 use crate::raft::type_config::TypeConfig;
 use bincode::{deserialize, serialize};
-use openraft::Raft;
+use openraft::{ChangeMembers, Raft, ServerState};
 use protocol::meta::meta_service_openraft::{
-    AddLearnerReply, AddLearnerRequest, AppendReply, AppendRequest, ChangeMembershipReply,
-    ChangeMembershipRequest, SnapshotReply, SnapshotRequest, VoteReply, VoteRequest,
+    AddLearnerReply, AddLearnerRequest, AppendReply, AppendRequest, ChangeMembershipAction,
+    ChangeMembershipReply, ChangeMembershipRequest, RaftMember, RaftStatusReply, RaftStatusRequest,
+    ReadIndexReply, ReadIndexRequest, SnapshotReply, SnapshotRequest, TransferLeaderReply,
+    TransferLeaderRequest, VoteReply, VoteRequest,
 };
+use std::collections::BTreeSet;
 
 pub async fn vote_by_req(
     raft_node: &Raft<TypeConfig>,
@@ -98,11 +102,143 @@ pub async fn change_membership_by_req(
     raft_node: &Raft<TypeConfig>,
     req: &ChangeMembershipRequest,
 ) -> Result<ChangeMembershipReply, MetaServiceError> {
-    let members = req.members.clone();
+    let members: BTreeSet<u64> = req.members.iter().copied().collect();
     let retain = req.retain;
 
-    let res = raft_node.change_membership(members, retain).await?;
+    let changes = match req.action() {
+        ChangeMembershipAction::ReplaceAllVoters => ChangeMembers::ReplaceAllVoters(members),
+        ChangeMembershipAction::AddVoters => ChangeMembers::AddVoterIds(members),
+        ChangeMembershipAction::RemoveVoters => ChangeMembers::RemoveVoters(members),
+        ChangeMembershipAction::RemoveNodes => ChangeMembers::RemoveNodes(members),
+    };
+
+    let res = raft_node.change_membership(changes, retain).await?;
     let value = serialize(&res)?;
 
     Ok(ChangeMembershipReply { value })
 }
+
+pub async fn read_index_by_req(
+    raft_node: &Raft<TypeConfig>,
+    _req: &ReadIndexRequest,
+) -> Result<ReadIndexReply, MetaServiceError> {
+    let read_index = read_index(raft_node).await?;
+    Ok(ReadIndexReply { read_index })
+}
+
+pub async fn raft_status_by_req(
+    raft_node: &Raft<TypeConfig>,
+    _req: &RaftStatusRequest,
+) -> Result<RaftStatusReply, MetaServiceError> {
+    let metrics = raft_node.metrics().borrow().clone();
+    let membership = metrics.membership_config.membership();
+    let voters: BTreeSet<u64> = membership.voter_ids().collect();
+    let last_log_index = metrics.last_log_index.unwrap_or(0);
+    let last_applied = metrics.last_applied.map(|log_id| log_id.index).unwrap_or(0);
+    let is_leader = metrics.state == ServerState::Leader;
+
+    let mut members = Vec::new();
+    for (node_id, node) in membership.nodes() {
+        let matched_index = if !is_leader {
+            0
+        } else if *node_id == metrics.id {
+            last_log_index
+        } else {
+            metrics
+                .replication
+                .as_ref()
+                .and_then(|replication| replication.get(node_id).cloned().flatten())
+                .map(|log_id| log_id.index)
+                .unwrap_or(0)
+        };
+        members.push(RaftMember {
+            node_id: *node_id,
+            rpc_addr: node.rpc_addr.clone(),
+            voter: voters.contains(node_id),
+            matched_index,
+            lag: if is_leader {
+                last_log_index.saturating_sub(matched_index)
+            } else {
+                0
+            },
+        });
+    }
+
+    // Followers do not track the commit index, the applied index is the closest they know.
+    let commit_index = if is_leader {
+        quorum_commit_index(
+            members
+                .iter()
+                .filter(|member| member.voter)
+                .map(|member| member.matched_index)
+                .collect(),
+        )
+    } else {
+        last_applied
+    };
+
+    let current_leader = metrics.current_leader.unwrap_or_default();
+    let leader_addr = metrics
+        .current_leader
+        .and_then(|leader_id| membership.get_node(&leader_id))
+        .map(|node| node.rpc_addr.clone())
+        .unwrap_or_default();
+
+    Ok(RaftStatusReply {
+        node_id: metrics.id,
+        state: format!("{:?}", metrics.state),
+        current_term: metrics.current_term,
+        current_leader,
+        leader_addr,
+        last_log_index,
+        commit_index,
+        last_applied,
+        members,
+    })
+}
+
+pub async fn transfer_leader_by_req(
+    raft_node: &Raft<TypeConfig>,
+    req: &TransferLeaderRequest,
+) -> Result<TransferLeaderReply, MetaServiceError> {
+    let metrics = raft_node.metrics().borrow().clone();
+    let is_voter = metrics
+        .membership_config
+        .membership()
+        .voter_ids()
+        .any(|node_id| node_id == req.node_id);
+    if !is_voter {
+        return Err(MetaServiceError::NodeDoesNotExist(req.node_id));
+    }
+
+    raft_node
+        .trigger()
+        .transfer_leader(req.node_id)
+        .await
+        .map_err(|e| MetaServiceError::CommonError(e.to_string()))?;
+    Ok(TransferLeaderReply {})
+}
+
+// The highest log index replicated on a majority of the voters.
+fn quorum_commit_index(mut matched: Vec<u64>) -> u64 {
+    if matched.is_empty() {
+        return 0;
+    }
+    matched.sort_unstable_by(|a, b| b.cmp(a));
+    matched[matched.len() / 2]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::quorum_commit_index;
+
+    #[test]
+    fn quorum_commit_index_test() {
+        assert_eq!(quorum_commit_index(vec![]), 0);
+        assert_eq!(quorum_commit_index(vec![7]), 7);
+        assert_eq!(quorum_commit_index(vec![10, 8, 3]), 8);
+        assert_eq!(quorum_commit_index(vec![10, 3, 3]), 3);
+        assert_eq!(quorum_commit_index(vec![10, 9, 4, 2]), 4);
+        assert_eq!(quorum_commit_index(vec![5, 9, 9, 1, 1]), 5);
+    }
+}
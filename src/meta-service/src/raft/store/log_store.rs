@@ -15,6 +15,7 @@
 use super::{cf_raft_logs, cf_raft_store, id_to_bin, StorageResult};
 use crate::raft::store::bin_to_id;
 use crate::raft::type_config::TypeConfig;
+use common_security::encryption::{open_value, seal_value};
 use openraft::storage::{IOFlushed, RaftLogStorage};
 use openraft::{
     AnyError, Entry, ErrorSubject, ErrorVerb, LogId, LogState, OptionalSend, RaftLogReader,
@@ -128,8 +129,11 @@ async fn try_get_log_entries<RB: RangeBounds<u64> + Clone + Debug + OptionalSend
             )
             .map(|res| {
                 let (id, val) = res.unwrap();
-                let entry: StorageResult<Entry<_>> =
-                    serde_json::from_slice(&val).map_err(|e| StorageError::read_logs(&e));
+                let entry: StorageResult<Entry<_>> = open_value(val.to_vec())
+                    .map_err(|e| StorageError::read_logs(&e))
+                    .and_then(|val| {
+                        serde_json::from_slice(&val).map_err(|e| StorageError::read_logs(&e))
+                    });
                 let id = bin_to_id(&id);
 
                 assert_eq!(Ok(id), entry.as_ref().map(|e| e.log_id.index));
@@ -149,18 +153,20 @@ impl RaftLogStorage<TypeConfig> for LogStore {
     type LogReader = Self;
 
     async fn get_log_state(&mut self) -> StorageResult<LogState<TypeConfig>> {
-        let last = self
+        let last = match self
             .db
             .iterator_cf(&self.logs(), rocksdb::IteratorMode::End)
             .next()
-            .and_then(|res| {
-                let (_, ent) = res.unwrap();
-                Some(
-                    serde_json::from_slice::<Entry<TypeConfig>>(&ent)
-                        .ok()?
-                        .log_id,
-                )
-            });
+        {
+            Some(res) => {
+                let (_, ent) = res.map_err(|e| StorageError::read_logs(&e))?;
+                let ent = open_value(ent.to_vec()).map_err(|e| StorageError::read_logs(&e))?;
+                let ent = serde_json::from_slice::<Entry<TypeConfig>>(&ent)
+                    .map_err(|e| StorageError::read_logs(&e))?;
+                Some(ent.log_id)
+            }
+            None => None,
+        };
 
         let last_purged_log_id = self.get_last_purged_()?;
 
@@ -203,11 +209,12 @@ async fn append<I>(&mut self, entries: I, callback: IOFlushed<TypeConfig>) -> St
         for entry in entries {
             let id = id_to_bin(entry.log_id.index);
             assert_eq!(bin_to_id(&id), entry.log_id.index);
+            let data = serde_json::to_vec(&entry).map_err(|e| StorageError::write_logs(&e))?;
             self.db
                 .put_cf(
                     &self.logs(),
                     id,
-                    serde_json::to_vec(&entry).map_err(|e| StorageError::write_logs(&e))?,
+                    seal_value(data).map_err(|e| StorageError::write_logs(&e))?,
                 )
                 .map_err(|e| StorageError::write_logs(&e))?;
         }
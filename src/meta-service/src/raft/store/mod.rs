@@ -17,10 +17,12 @@
 
 use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
 use log_store::LogStore;
-use openraft::{SnapshotMeta, StorageError};
+use openraft::{LogId, SnapshotMeta, StorageError, StoredMembership};
 use rocksdb::{ColumnFamilyDescriptor, Options, DB};
+use rocksdb_engine::rewrap_sealed_values;
 use serde::{Deserialize, Serialize};
 use state_machine_store::StateMachineStore;
+use tracing::{error, info};
 
 use super::type_config::TypeConfig;
 use crate::raft::route::DataRoute;
@@ -28,13 +30,23 @@
 pub struct StoredSnapshot {
     pub meta: SnapshotMeta<TypeConfig>,
 
-    /// The data of the state machine at the time of this snapshot.
-    pub data: Vec<u8>,
+    /// Name of the packed checkpoint file in the snapshot directory.
+    #[serde(default)]
+    pub file: String,
+}
+
+/// Raft state of the applied entries, it is stored in the meta data rocksdb so that
+/// it always matches the data that is actually there.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AppliedState {
+    pub last_applied_log_id: Option<LogId<TypeConfig>>,
+    pub last_membership: StoredMembership<TypeConfig>,
 }
 
 type StorageResult<T> = Result<T, StorageError<TypeConfig>>;
 
 pub mod log_store;
+pub mod snapshot;
 pub mod state_machine_store;
 
 /// converts an id to a byte vector for storing in the database.
@@ -60,11 +72,25 @@ pub(crate) async fn new_storage<P: AsRef<Path>>(
     let store = ColumnFamilyDescriptor::new(cf_raft_store(), Options::default());
     let logs = ColumnFamilyDescriptor::new(cf_raft_logs(), Options::default());
 
+    let snapshot_dir = snapshot::snapshot_dir(&db_path);
     let db = DB::open_cf_descriptors(&db_opts, db_path, vec![store, logs]).unwrap();
+
+    // raft logs and votes sealed by a retired master key are re-wrapped before openraft
+    // starts writing to the db
+    match rewrap_sealed_values(&db, &[cf_raft_store(), cf_raft_logs()]) {
+        Ok(0) => {}
+        Ok(num) => info!("Re-wrapped the data keys of {} raft values", num),
+        Err(e) => error!(
+            "Failed to re-wrap the data keys of raft values, error: {}",
+            e
+        ),
+    }
     let db = Arc::new(db);
 
     let log_store = LogStore { db: db.clone() };
-    let sm_store = StateMachineStore::new(db, route).await.unwrap();
+    let sm_store = StateMachineStore::new(db, route, snapshot_dir)
+        .await
+        .unwrap();
 
     (log_store, sm_store)
 }
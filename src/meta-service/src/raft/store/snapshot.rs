@@ -0,0 +1,234 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// A snapshot of the meta state machine is a rocksdb checkpoint of the meta data, packed
+// into a single file so openraft can stream it to followers chunk by chunk straight from
+// disk. The file is a magic header followed by `[name len: u16][name][size: u64][content]`
+// entries and ends with an empty name.
+
+use std::io::{Error, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use broker_core::rocksdb::{column_family_list, DB_COLUMN_FAMILY_META};
+use common_base::error::common::CommonError;
+use common_security::encryption::open_value;
+use rocksdb::{Options, DB};
+use tokio::fs::{self, File};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader, BufWriter};
+
+const SNAPSHOT_FILE_MAGIC: &[u8; 8] = b"RMQCKPT1";
+const SNAPSHOT_FILE_SUFFIX: &str = "snap";
+
+pub fn snapshot_dir<P: AsRef<Path>>(raft_path: P) -> PathBuf {
+    raft_path.as_ref().join("snapshot")
+}
+
+pub fn snapshot_file_name(snapshot_id: &str) -> String {
+    format!("{snapshot_id}.{SNAPSHOT_FILE_SUFFIX}")
+}
+
+pub fn checkpoint_dir(dir: &Path, snapshot_id: &str) -> PathBuf {
+    dir.join(format!("{snapshot_id}.checkpoint"))
+}
+
+pub fn restore_dir(dir: &Path, snapshot_id: &str) -> PathBuf {
+    dir.join(format!("{snapshot_id}.restore"))
+}
+
+/// Packs the files of a checkpoint directory into one snapshot file.
+pub async fn pack_checkpoint(checkpoint: &Path, target: &Path) -> Result<u64, Error> {
+    let mut writer = BufWriter::new(File::create(target).await?);
+    writer.write_all(SNAPSHOT_FILE_MAGIC).await?;
+
+    let mut total = 0;
+    let mut entries = fs::read_dir(checkpoint).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        if !entry.file_type().await?.is_file() {
+            continue;
+        }
+
+        let name = entry.file_name().to_string_lossy().to_string();
+        let file = File::open(entry.path()).await?;
+        let size = file.metadata().await?.len();
+
+        writer.write_u16(name.len() as u16).await?;
+        writer.write_all(name.as_bytes()).await?;
+        writer.write_u64(size).await?;
+        let copied = tokio::io::copy(&mut file.take(size), &mut writer).await?;
+        if copied != size {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                format!("checkpoint file {name} changed while packing the snapshot"),
+            ));
+        }
+        total += size;
+    }
+    writer.write_u16(0).await?;
+    writer.flush().await?;
+    writer.into_inner().sync_all().await?;
+    Ok(total)
+}
+
+/// Unpacks a snapshot file into `target`, which can then be opened as a rocksdb.
+pub async fn unpack_checkpoint<R: AsyncRead + Unpin>(
+    reader: R,
+    target: &Path,
+) -> Result<(), Error> {
+    let mut reader = BufReader::new(reader);
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic).await?;
+    if &magic != SNAPSHOT_FILE_MAGIC {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "not a meta service snapshot file",
+        ));
+    }
+
+    if fs::try_exists(target).await? {
+        fs::remove_dir_all(target).await?;
+    }
+    fs::create_dir_all(target).await?;
+
+    loop {
+        let name_len = reader.read_u16().await? as usize;
+        if name_len == 0 {
+            break;
+        }
+
+        let mut name = vec![0u8; name_len];
+        reader.read_exact(&mut name).await?;
+        let name = String::from_utf8(name).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        if name.contains('/') || name.contains('\\') || name == "." || name == ".." {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("invalid file name {name} in snapshot"),
+            ));
+        }
+
+        let size = reader.read_u64().await?;
+        let mut file = File::create(target.join(&name)).await?;
+        let copied = tokio::io::copy(&mut (&mut reader).take(size), &mut file).await?;
+        if copied != size {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                format!("snapshot file is truncated at {name}"),
+            ));
+        }
+        file.flush().await?;
+    }
+    Ok(())
+}
+
+/// Opens an unpacked checkpoint read-only.
+pub fn open_checkpoint(path: &Path) -> Result<DB, CommonError> {
+    Ok(DB::open_cf_for_read_only(
+        &Options::default(),
+        path,
+        column_family_list(),
+        false,
+    )?)
+}
+
+/// Reads all meta records of an unpacked checkpoint, values are returned in plaintext.
+pub fn read_checkpoint_records(path: &Path) -> Result<Vec<(String, Vec<u8>)>, CommonError> {
+    let db = open_checkpoint(path)?;
+    let Some(cf) = db.cf_handle(DB_COLUMN_FAMILY_META) else {
+        return Err(CommonError::CommonError(format!(
+            "RocksDB Family {DB_COLUMN_FAMILY_META} not available"
+        )));
+    };
+
+    let mut iter = db.raw_iterator_cf(&cf);
+    iter.seek_to_first();
+    let mut result = Vec::new();
+    while iter.valid() {
+        if let (Some(key), Some(value)) = (iter.key(), iter.value()) {
+            result.push((
+                String::from_utf8(key.to_vec())?,
+                open_value(value.to_vec())?,
+            ));
+        }
+        iter.next();
+    }
+    iter.status()?;
+    Ok(result)
+}
+
+/// Removes snapshot files other than `current`. Leftover checkpoint and restore
+/// directories are only removed when `remove_dirs` is set, they may belong to a
+/// snapshot that is still being built.
+pub async fn purge_snapshot_dir(dir: &Path, current: &str, remove_dirs: bool) -> Result<(), Error> {
+    let mut entries = fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        if entry.file_name().to_string_lossy() == current {
+            continue;
+        }
+        if entry.file_type().await?.is_dir() {
+            if remove_dirs {
+                fs::remove_dir_all(entry.path()).await?;
+            }
+        } else {
+            fs::remove_file(entry.path()).await?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pack_checkpoint, unpack_checkpoint};
+    use tempfile::tempdir;
+    use tokio::fs::{self, File};
+
+    #[tokio::test]
+    async fn pack_unpack_test() {
+        let dir = tempdir().unwrap();
+        let checkpoint = dir.path().join("1-1-1.checkpoint");
+        fs::create_dir_all(&checkpoint).await.unwrap();
+        fs::write(checkpoint.join("CURRENT"), b"MANIFEST-000001\n")
+            .await
+            .unwrap();
+        fs::write(checkpoint.join("000008.sst"), vec![7u8; 10000])
+            .await
+            .unwrap();
+
+        let snapshot = dir.path().join("1-1-1.snap");
+        let size = pack_checkpoint(&checkpoint, &snapshot).await.unwrap();
+        assert_eq!(size, 10016);
+
+        let restore = dir.path().join("1-1-1.restore");
+        let file = File::open(&snapshot).await.unwrap();
+        unpack_checkpoint(file, &restore).await.unwrap();
+        assert_eq!(
+            fs::read(restore.join("CURRENT")).await.unwrap(),
+            b"MANIFEST-000001\n"
+        );
+        assert_eq!(
+            fs::read(restore.join("000008.sst")).await.unwrap(),
+            vec![7u8; 10000]
+        );
+    }
+
+    #[tokio::test]
+    async fn unpack_invalid_test() {
+        let dir = tempdir().unwrap();
+        let snapshot = dir.path().join("invalid.snap");
+        fs::write(&snapshot, b"not a snapshot").await.unwrap();
+
+        let file = File::open(&snapshot).await.unwrap();
+        assert!(unpack_checkpoint(file, &dir.path().join("restore"))
+            .await
+            .is_err());
+    }
+}
@@ -12,20 +12,28 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::{cf_raft_store, StorageResult, StoredSnapshot};
+use super::snapshot::{
+    checkpoint_dir, pack_checkpoint, purge_snapshot_dir, restore_dir, snapshot_file_name,
+    unpack_checkpoint,
+};
+use super::{cf_raft_store, AppliedState, StorageResult, StoredSnapshot};
 use crate::raft::raft_node::types;
 use crate::raft::route::AppResponseData;
 use crate::raft::route::DataRoute;
 use crate::raft::type_config::{SnapshotData, TypeConfig};
+use common_security::encryption::{open_value, seal_value};
 use openraft::storage::RaftStateMachine;
 use openraft::{
     AnyError, EntryPayload, ErrorSubject, ErrorVerb, LogId, OptionalSend, RaftSnapshotBuilder,
     Snapshot, SnapshotMeta, StorageError, StoredMembership,
 };
 use rocksdb::{BoundColumnFamily, DB};
-use std::io::Cursor;
+use std::io::{ErrorKind, SeekFrom};
+use std::path::PathBuf;
 use std::sync::Arc;
-use tracing::warn;
+use tokio::fs::{self, File};
+use tokio::io::AsyncSeekExt;
+use tracing::{info, warn};
 
 #[derive(Clone)]
 pub struct StateMachineStore {
@@ -37,8 +45,11 @@ pub struct StateMachineStore {
     /// In practice, using a timestamp in micro-second would be good enough.
     snapshot_idx: u64,
 
-    /// State machine stores snapshot in db.
+    /// State machine stores snapshot meta in db.
     db: Arc<DB>,
+
+    /// Directory of the packed snapshot files.
+    snapshot_dir: PathBuf,
 }
 
 #[derive(Clone)]
@@ -51,36 +62,56 @@ pub struct StateMachineData {
     pub route: Arc<DataRoute>,
 }
 
-impl RaftSnapshotBuilder<TypeConfig> for StateMachineStore {
-    async fn build_snapshot(&mut self) -> Result<Snapshot<TypeConfig>, StorageError<TypeConfig>> {
-        let last_applied_log = self.data.last_applied_log_id;
-        let last_membership = self.data.last_membership.clone();
-
-        // todo
-        let kv_json = self.data.route.build_snapshot();
-
-        let snapshot_id = if let Some(last) = last_applied_log {
-            format!("{}-{}-{}", last.leader_id, last.index, self.snapshot_idx)
-        } else {
-            format!("--{}", self.snapshot_idx)
-        };
-
-        let meta = SnapshotMeta {
-            last_log_id: last_applied_log,
-            last_membership,
-            snapshot_id,
-        };
+/// Packs the checkpoint taken in `get_snapshot_builder` into a snapshot file, this
+/// runs in the background while the state machine keeps applying logs.
+pub struct CheckpointSnapshotBuilder {
+    store: StateMachineStore,
+    meta: SnapshotMeta<TypeConfig>,
+    checkpoint: Result<PathBuf, String>,
+}
 
-        let snapshot = StoredSnapshot {
-            meta: meta.clone(),
-            data: kv_json.clone(),
-        };
+impl RaftSnapshotBuilder<TypeConfig> for CheckpointSnapshotBuilder {
+    async fn build_snapshot(&mut self) -> Result<Snapshot<TypeConfig>, StorageError<TypeConfig>> {
+        let signature = self.meta.signature();
+        let checkpoint = self.checkpoint.clone().map_err(|e| {
+            StorageError::new(
+                ErrorSubject::Snapshot(Some(signature.clone())),
+                ErrorVerb::Write,
+                AnyError::error(e),
+            )
+        })?;
+
+        let file_name = snapshot_file_name(&self.meta.snapshot_id);
+        let path = self.store.snapshot_dir.join(&file_name);
+        let size = pack_checkpoint(&checkpoint, &path)
+            .await
+            .map_err(|e| StorageError::write_snapshot(Some(signature.clone()), &e))?;
+        if let Err(e) = fs::remove_dir_all(&checkpoint).await {
+            warn!(
+                "Failed to remove snapshot checkpoint {:?}: {}",
+                checkpoint, e
+            );
+        }
 
-        self.set_current_snapshot_(snapshot)?;
+        self.store.set_current_snapshot_(StoredSnapshot {
+            meta: self.meta.clone(),
+            file: file_name.clone(),
+        })?;
+        info!(
+            "Snapshot {} built successfully, snapshot size :{}",
+            self.meta.snapshot_id, size
+        );
+
+        if let Err(e) = purge_snapshot_dir(&self.store.snapshot_dir, &file_name, false).await {
+            warn!("Failed to purge old snapshot files: {}", e);
+        }
 
+        let file = File::open(&path)
+            .await
+            .map_err(|e| StorageError::read_snapshot(Some(signature), &e))?;
         Ok(Snapshot {
-            meta,
-            snapshot: Cursor::new(kv_json),
+            meta: self.meta.clone(),
+            snapshot: file,
         })
     }
 }
@@ -89,7 +120,12 @@ impl StateMachineStore {
     pub async fn new(
         db: Arc<DB>,
         route: Arc<DataRoute>,
+        snapshot_dir: PathBuf,
     ) -> Result<StateMachineStore, StorageError<TypeConfig>> {
+        fs::create_dir_all(&snapshot_dir)
+            .await
+            .map_err(|e| StorageError::write_snapshot(None, &e))?;
+
         let mut sm = Self {
             data: StateMachineData {
                 last_applied_log_id: None,
@@ -98,44 +134,76 @@ pub async fn new(
             },
             snapshot_idx: 0,
             db,
+            snapshot_dir,
         };
 
+        // The meta data is kept in its own rocksdb and may contain entries applied after
+        // the last snapshot, the applied state stored with it tells where it stands.
         let snapshot = sm.get_current_snapshot_()?;
+        let applied_state = sm.data.route.get_applied_state().map_err(|e| {
+            StorageError::new(
+                ErrorSubject::StateMachine,
+                ErrorVerb::Read,
+                AnyError::new(&e),
+            )
+        })?;
+        if let Some(state) = applied_state {
+            sm.data.last_applied_log_id = state.last_applied_log_id;
+            sm.data.last_membership = state.last_membership;
+        } else if let Some(snap) = &snapshot {
+            // data written before the applied state was stored with it
+            sm.data.last_applied_log_id = snap.meta.last_log_id;
+            sm.data.last_membership = snap.meta.last_membership.clone();
+        }
+
         if let Some(snap) = snapshot {
-            sm.update_state_machine_(snap).await?;
+            if let Err(e) = purge_snapshot_dir(&sm.snapshot_dir, &snap.file, true).await {
+                warn!("Failed to purge old snapshot files: {}", e);
+            }
         }
 
         Ok(sm)
     }
 
-    async fn update_state_machine_(
-        &mut self,
-        snapshot: StoredSnapshot,
-    ) -> Result<(), StorageError<TypeConfig>> {
-        self.data.last_applied_log_id = snapshot.meta.last_log_id;
-        self.data.last_membership = snapshot.meta.last_membership.clone();
-
-        match self.data.route.recover_snapshot(snapshot.data) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(StorageError::read(&e)),
+    fn snapshot_id(&self) -> String {
+        if let Some(last) = self.data.last_applied_log_id {
+            format!("{}-{}-{}", last.leader_id, last.index, self.snapshot_idx)
+        } else {
+            format!("--{}", self.snapshot_idx)
         }
     }
 
+    fn save_applied_state(&self) -> StorageResult<()> {
+        let state = AppliedState {
+            last_applied_log_id: self.data.last_applied_log_id,
+            last_membership: self.data.last_membership.clone(),
+        };
+        self.data.route.save_applied_state(&state).map_err(|e| {
+            StorageError::new(
+                ErrorSubject::StateMachine,
+                ErrorVerb::Write,
+                AnyError::new(&e),
+            )
+        })
+    }
+
     fn get_current_snapshot_(&self) -> StorageResult<Option<StoredSnapshot>> {
-        Ok(self
+        let data = match self
             .db
             .get_cf(&self.store(), b"snapshot")
             .map_err(|e| StorageError::read(&e))?
-            .and_then(|v| serde_json::from_slice(&v).ok()))
+        {
+            Some(data) => open_value(data).map_err(|e| StorageError::read_snapshot(None, &e))?,
+            None => return Ok(None),
+        };
+        Ok(serde_json::from_slice(&data).ok())
     }
 
     fn set_current_snapshot_(&self, snap: StoredSnapshot) -> StorageResult<()> {
+        let data = seal_value(serde_json::to_vec(&snap).unwrap())
+            .map_err(|e| StorageError::write_snapshot(Some(snap.meta.signature()), &e))?;
         self.db
-            .put_cf(
-                &self.store(),
-                b"snapshot",
-                serde_json::to_vec(&snap).unwrap().as_slice(),
-            )
+            .put_cf(&self.store(), b"snapshot", data)
             .map_err(|e| StorageError::write_snapshot(Some(snap.meta.signature()), &e))?;
         self.flush(
             ErrorSubject::Snapshot(Some(snap.meta.signature())),
@@ -161,7 +229,7 @@ fn store(&self) -> Arc<BoundColumnFamily<'_>> {
 }
 
 impl RaftStateMachine<TypeConfig> for StateMachineStore {
-    type SnapshotBuilder = Self;
+    type SnapshotBuilder = CheckpointSnapshotBuilder;
 
     async fn applied_state(
         &mut self,
@@ -186,67 +254,141 @@ async fn apply<I>(
 
         for ent in entries {
             self.data.last_applied_log_id = Some(ent.log_id);
+            let revision = ent.log_id.index;
 
             let mut resp_value = None;
+            let mut resp_error = None;
 
             match ent.payload {
                 EntryPayload::Blank => {}
-                EntryPayload::Normal(req) => match self.data.route.route(req.clone()).await {
-                    Ok(data) => {
-                        resp_value = data;
+                EntryPayload::Normal(req) => {
+                    match self.data.route.route(req.clone(), revision).await {
+                        Ok(data) => {
+                            resp_value = data;
+                        }
+                        Err(e) => {
+                            warn!(
+                                "Raft route failed to process message with error message: {},req:{:?}",
+                                e, req.data_type
+                            );
+                            resp_error = Some(e.to_string());
+                        }
                     }
-                    Err(e) => {
-                        warn!(
-                            "Raft route failed to process message with error message: {},req:{:?}",
-                            e, req.data_type
-                        );
-                    }
-                },
+                }
                 EntryPayload::Membership(mem) => {
                     self.data.last_membership = StoredMembership::new(Some(ent.log_id), mem);
                 }
             }
 
-            replies.push(AppResponseData { value: resp_value });
+            self.save_applied_state()?;
+            replies.push(AppResponseData {
+                value: resp_value,
+                error: resp_error,
+            });
         }
         Ok(replies)
     }
 
     async fn get_snapshot_builder(&mut self) -> Self::SnapshotBuilder {
         self.snapshot_idx += 1;
-        self.clone()
+
+        let meta = SnapshotMeta {
+            last_log_id: self.data.last_applied_log_id,
+            last_membership: self.data.last_membership.clone(),
+            snapshot_id: self.snapshot_id(),
+        };
+
+        // Logs are not applied while the builder is created, so the checkpoint matches
+        // last_applied_log_id exactly. Packing it is left to the background builder.
+        let path = checkpoint_dir(&self.snapshot_dir, &meta.snapshot_id);
+        let checkpoint = self
+            .data
+            .route
+            .create_checkpoint(&path)
+            .map(|_| path)
+            .map_err(|e| e.to_string());
+
+        CheckpointSnapshotBuilder {
+            store: self.clone(),
+            meta,
+            checkpoint,
+        }
     }
 
-    async fn begin_receiving_snapshot(
-        &mut self,
-    ) -> Result<Cursor<Vec<u8>>, StorageError<TypeConfig>> {
-        Ok(Cursor::new(Vec::new()))
+    async fn begin_receiving_snapshot(&mut self) -> Result<SnapshotData, StorageError<TypeConfig>> {
+        // An anonymous file, it is cleaned up automatically if the transfer is aborted.
+        let file = tempfile::tempfile_in(&self.snapshot_dir)
+            .map_err(|e| StorageError::write_snapshot(None, &e))?;
+        Ok(File::from_std(file))
     }
 
     async fn install_snapshot(
         &mut self,
         meta: &SnapshotMeta<TypeConfig>,
-        snapshot: SnapshotData,
+        mut snapshot: SnapshotData,
     ) -> Result<(), StorageError<TypeConfig>> {
-        let new_snapshot = StoredSnapshot {
-            meta: meta.clone(),
-            data: snapshot.into_inner(),
-        };
-
-        self.update_state_machine_(new_snapshot.clone()).await?;
+        let signature = meta.signature();
+        let file_name = snapshot_file_name(&meta.snapshot_id);
+        let path = self.snapshot_dir.join(&file_name);
+        let restore = restore_dir(&self.snapshot_dir, &meta.snapshot_id);
+
+        let result: Result<(), std::io::Error> = async {
+            snapshot.seek(SeekFrom::Start(0)).await?;
+            let mut target = File::create(&path).await?;
+            tokio::io::copy(&mut snapshot, &mut target).await?;
+            target.sync_all().await?;
+            unpack_checkpoint(File::open(&path).await?, &restore).await
+        }
+        .await;
+        result.map_err(|e| StorageError::write_snapshot(Some(signature.clone()), &e))?;
+
+        let route = self.data.route.clone();
+        let checkpoint = restore.clone();
+        let result = tokio::task::spawn_blocking(move || route.install_checkpoint(&checkpoint))
+            .await
+            .map_err(|e| StorageError::write_snapshot(Some(signature.clone()), &e))?;
+        if let Err(e) = fs::remove_dir_all(&restore).await {
+            warn!("Failed to remove snapshot restore dir {:?}: {}", restore, e);
+        }
+        result.map_err(|e| StorageError::write_snapshot(Some(signature), &e))?;
 
-        self.set_current_snapshot_(new_snapshot)?;
+        self.data.last_applied_log_id = meta.last_log_id;
+        self.data.last_membership = meta.last_membership.clone();
+        self.save_applied_state()?;
+        self.set_current_snapshot_(StoredSnapshot {
+            meta: meta.clone(),
+            file: file_name.clone(),
+        })?;
 
+        if let Err(e) = purge_snapshot_dir(&self.snapshot_dir, &file_name, false).await {
+            warn!("Failed to purge old snapshot files: {}", e);
+        }
         Ok(())
     }
 
     async fn get_current_snapshot(
         &mut self,
     ) -> Result<Option<Snapshot<TypeConfig>>, StorageError<TypeConfig>> {
-        let x = self.get_current_snapshot_()?;
-        Ok(x.map(|s| Snapshot {
-            meta: s.meta.clone(),
-            snapshot: Cursor::new(s.data.clone()),
-        }))
+        let Some(snap) = self.get_current_snapshot_()? else {
+            return Ok(None);
+        };
+
+        // Snapshots written by older versions kept the data inline, a new one is built
+        // when it is needed.
+        if snap.file.is_empty() {
+            return Ok(None);
+        }
+
+        match File::open(self.snapshot_dir.join(&snap.file)).await {
+            Ok(file) => Ok(Some(Snapshot {
+                meta: snap.meta,
+                snapshot: file,
+            })),
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                warn!("Snapshot file {} does not exist", snap.file);
+                Ok(None)
+            }
+            Err(e) => Err(StorageError::read_snapshot(Some(snap.meta.signature()), &e)),
+        }
     }
 }
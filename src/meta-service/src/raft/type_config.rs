@@ -15,13 +15,13 @@
 use crate::raft::raft_node::Node;
 use crate::raft::route::data::StorageData;
 use crate::raft::route::AppResponseData;
-use std::io::Cursor;
 
-pub type SnapshotData = Cursor<Vec<u8>>;
+pub type SnapshotData = tokio::fs::File;
 
 openraft::declare_raft_types!(
     pub TypeConfig:
         D = StorageData,
         R = AppResponseData,
         Node = Node,
+        SnapshotData = SnapshotData,
 );
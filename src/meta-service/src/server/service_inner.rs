@@ -23,9 +23,9 @@
 use crate::raft::route::apply::StorageDriver;
 use crate::server::services::inner::{
     cluster_status_by_req, delete_idempotent_data_by_req, delete_resource_config_by_req,
-    exists_idempotent_data_by_req, get_offset_data_by_req, get_resource_config_by_req,
-    heartbeat_by_req, node_list_by_req, save_offset_data_by_req, set_idempotent_data_by_req,
-    set_resource_config_by_req,
+    exists_idempotent_data_by_req, export_metadata_by_req, get_offset_data_by_req,
+    get_resource_config_by_req, heartbeat_by_req, import_metadata_by_req, node_list_by_req,
+    save_offset_data_by_req, set_idempotent_data_by_req, set_resource_config_by_req,
 };
 use grpc_clients::pool::ClientPool;
 use prost_validate::Validator;
@@ -34,9 +34,10 @@
     BindSchemaReply, BindSchemaRequest, ClusterStatusReply, ClusterStatusRequest,
     CreateSchemaReply, CreateSchemaRequest, DeleteIdempotentDataReply, DeleteIdempotentDataRequest,
     DeleteResourceConfigReply, DeleteResourceConfigRequest, DeleteSchemaReply, DeleteSchemaRequest,
-    ExistsIdempotentDataReply, ExistsIdempotentDataRequest, GetOffsetDataReply,
-    GetOffsetDataRequest, GetResourceConfigReply, GetResourceConfigRequest, HeartbeatReply,
-    HeartbeatRequest, ListBindSchemaReply, ListBindSchemaRequest, ListSchemaReply,
+    ExistsIdempotentDataReply, ExistsIdempotentDataRequest, ExportMetadataReply,
+    ExportMetadataRequest, GetOffsetDataReply, GetOffsetDataRequest, GetResourceConfigReply,
+    GetResourceConfigRequest, HeartbeatReply, HeartbeatRequest, ImportMetadataReply,
+    ImportMetadataRequest, ListBindSchemaReply, ListBindSchemaRequest, ListSchemaReply,
     ListSchemaRequest, NodeListReply, NodeListRequest, RegisterNodeReply, RegisterNodeRequest,
     ReportMonitorReply, ReportMonitorRequest, SaveOffsetDataReply, SaveOffsetDataRequest,
     SetIdempotentDataReply, SetIdempotentDataRequest, SetResourceConfigReply,
@@ -408,4 +409,26 @@ async fn un_bind_schema(
         .map_err(|e| Status::cancelled(e.to_string()))?;
         Ok(Response::new(UnBindSchemaReply {}))
     }
+
+    async fn export_metadata(
+        &self,
+        request: Request<ExportMetadataRequest>,
+    ) -> Result<Response<ExportMetadataReply>, Status> {
+        let req = request.into_inner();
+        export_metadata_by_req(&self.raft_machine_apply, &req)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))
+            .map(Response::new)
+    }
+
+    async fn import_metadata(
+        &self,
+        request: Request<ImportMetadataRequest>,
+    ) -> Result<Response<ImportMetadataReply>, Status> {
+        let req = request.into_inner();
+        import_metadata_by_req(&self.raft_machine_apply, &req)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))
+            .map(Response::new)
+    }
 }
@@ -15,31 +15,42 @@
 use std::sync::Arc;
 
 use crate::server::services::kv::{
-    delete_by_req, exists_by_req, get_by_req, get_prefix_by_req, list_shard_by_req, set_by_req,
+    delete_by_req, exists_by_req, get_by_req, get_prefix_by_req, lease_grant_by_req,
+    lease_keep_alive_by_req, lease_revoke_by_req, list_shard_by_req, set_by_req, txn_by_req,
+    watch_by_req,
 };
 use protocol::meta::meta_service_kv::kv_service_server::KvService;
 use protocol::meta::meta_service_kv::{
     DeleteReply, DeleteRequest, ExistsReply, ExistsRequest, GetPrefixReply, GetPrefixRequest,
-    GetReply, GetRequest, ListShardReply, ListShardRequest, SetReply, SetRequest,
+    GetReply, GetRequest, LeaseGrantReply, LeaseGrantRequest, LeaseKeepAliveReply,
+    LeaseKeepAliveRequest, LeaseRevokeReply, LeaseRevokeRequest, ListShardReply, ListShardRequest,
+    SetReply, SetRequest, TxnReply, TxnRequest, WatchReply, WatchRequest,
 };
+use std::pin::Pin;
+use tonic::codegen::tokio_stream::Stream;
 use tonic::{Request, Response, Status};
 
+use crate::core::error::MetaServiceError;
+use crate::core::watch::KvWatchManager;
 use crate::raft::route::apply::StorageDriver;
 use rocksdb_engine::RocksDBEngine;
 
 pub struct GrpcKvService {
     raft_machine_apply: Arc<StorageDriver>,
     rocksdb_engine_handler: Arc<RocksDBEngine>,
+    watch_manager: Arc<KvWatchManager>,
 }
 
 impl GrpcKvService {
     pub fn new(
         raft_machine_apply: Arc<StorageDriver>,
         rocksdb_engine_handler: Arc<RocksDBEngine>,
+        watch_manager: Arc<KvWatchManager>,
     ) -> Self {
         GrpcKvService {
             raft_machine_apply,
             rocksdb_engine_handler,
+            watch_manager,
         }
     }
 }
@@ -49,7 +60,7 @@ impl KvService for GrpcKvService {
     async fn set(&self, request: Request<SetRequest>) -> Result<Response<SetReply>, Status> {
         let req = request.into_inner();
 
-        set_by_req(&self.raft_machine_apply, &req)
+        set_by_req(&self.raft_machine_apply, &self.rocksdb_engine_handler, &req)
             .await
             .map_err(|e| Status::internal(e.to_string()))
             .map(Response::new)
@@ -111,4 +122,67 @@ async fn get_prefix(
             .map_err(|e| Status::internal(e.to_string()))
             .map(Response::new)
     }
+
+    type WatchStream = Pin<Box<dyn Stream<Item = Result<WatchReply, Status>> + Send>>;
+
+    async fn watch(
+        &self,
+        request: Request<WatchRequest>,
+    ) -> Result<Response<Self::WatchStream>, Status> {
+        let req = request.into_inner();
+
+        watch_by_req(&self.watch_manager, &req)
+            .map_err(|e| match e {
+                MetaServiceError::WatchRevisionCompacted(_, _) => {
+                    Status::out_of_range(e.to_string())
+                }
+                _ => Status::internal(e.to_string()),
+            })
+            .map(Response::new)
+    }
+
+    async fn txn(&self, request: Request<TxnRequest>) -> Result<Response<TxnReply>, Status> {
+        let req = request.into_inner();
+
+        txn_by_req(&self.raft_machine_apply, &self.rocksdb_engine_handler, &req)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))
+            .map(Response::new)
+    }
+
+    async fn lease_grant(
+        &self,
+        request: Request<LeaseGrantRequest>,
+    ) -> Result<Response<LeaseGrantReply>, Status> {
+        let req = request.into_inner();
+
+        lease_grant_by_req(&self.raft_machine_apply, &self.rocksdb_engine_handler, &req)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))
+            .map(Response::new)
+    }
+
+    async fn lease_revoke(
+        &self,
+        request: Request<LeaseRevokeRequest>,
+    ) -> Result<Response<LeaseRevokeReply>, Status> {
+        let req = request.into_inner();
+
+        lease_revoke_by_req(&self.raft_machine_apply, &self.rocksdb_engine_handler, &req)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))
+            .map(Response::new)
+    }
+
+    async fn lease_keep_alive(
+        &self,
+        request: Request<LeaseKeepAliveRequest>,
+    ) -> Result<Response<LeaseKeepAliveReply>, Status> {
+        let req = request.into_inner();
+
+        lease_keep_alive_by_req(&self.raft_machine_apply, &self.rocksdb_engine_handler, &req)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))
+            .map(Response::new)
+    }
 }
@@ -37,7 +37,7 @@
     list_topic_rewrite_rule_by_req, save_last_will_message_by_req, set_topic_retain_message_by_req,
 };
 use crate::server::services::mqtt::user::{
-    create_user_by_req, delete_user_by_req, list_user_by_req,
+    create_user_by_req, delete_user_by_req, list_user_by_req, update_user_by_req,
 };
 use grpc_clients::pool::ClientPool;
 use prost_validate::Validator;
@@ -57,10 +57,11 @@
     ListBlacklistReply, ListBlacklistRequest, ListConnectorReply, ListConnectorRequest,
     ListSessionReply, ListSessionRequest, ListSubscribeReply, ListSubscribeRequest, ListTopicReply,
     ListTopicRequest, ListTopicRewriteRuleReply, ListTopicRewriteRuleRequest, ListUserReply,
-    ListUserRequest, SaveLastWillMessageReply, SaveLastWillMessageRequest,
+    ListUserRequest, ReadConsistency, SaveLastWillMessageReply, SaveLastWillMessageRequest,
     SetAutoSubscribeRuleReply, SetAutoSubscribeRuleRequest, SetSubscribeReply, SetSubscribeRequest,
     SetTopicRetainMessageReply, SetTopicRetainMessageRequest, UpdateConnectorReply,
-    UpdateConnectorRequest, UpdateSessionReply, UpdateSessionRequest,
+    UpdateConnectorRequest, UpdateSessionReply, UpdateSessionRequest, UpdateUserReply,
+    UpdateUserRequest,
 };
 use rocksdb_engine::RocksDBEngine;
 use std::pin::Pin;
@@ -92,6 +93,19 @@ pub fn new(
             client_pool,
         }
     }
+
+    async fn ensure_read_consistency(
+        &self,
+        read_consistency: ReadConsistency,
+    ) -> Result<(), Status> {
+        if read_consistency == ReadConsistency::Stale {
+            return Ok(());
+        }
+        self.raft_machine_apply
+            .linearizable_read(&self.client_pool)
+            .await
+            .map_err(|e| Status::unavailable(e.to_string()))
+    }
 }
 
 #[tonic::async_trait]
@@ -103,6 +117,8 @@ async fn list_user(
     ) -> Result<Response<ListUserReply>, Status> {
         let req = request.into_inner();
 
+        self.ensure_read_consistency(req.read_consistency()).await?;
+
         list_user_by_req(&self.rocksdb_engine_handler, &req)
             .map_err(|e| Status::internal(e.to_string()))
             .map(Response::new)
@@ -126,6 +142,24 @@ async fn create_user(
         .map(Response::new)
     }
 
+    async fn update_user(
+        &self,
+        request: Request<UpdateUserRequest>,
+    ) -> Result<Response<UpdateUserReply>, Status> {
+        let req = request.into_inner();
+
+        update_user_by_req(
+            &self.raft_machine_apply,
+            &self.mqtt_call_manager,
+            &self.client_pool,
+            &self.rocksdb_engine_handler,
+            &req,
+        )
+        .await
+        .map_err(|e| Status::internal(e.to_string()))
+        .map(Response::new)
+    }
+
     async fn delete_user(
         &self,
         request: Request<DeleteUserRequest>,
@@ -150,6 +184,8 @@ async fn list_session(
         request: Request<ListSessionRequest>,
     ) -> Result<Response<ListSessionReply>, Status> {
         let req = request.into_inner();
+        self.ensure_read_consistency(req.read_consistency()).await?;
+
         list_session_by_req(&self.rocksdb_engine_handler, &req)
             .map_err(|e| Status::internal(e.to_string()))
             .map(Response::new)
@@ -219,6 +255,8 @@ async fn list_topic(
         req.validate()
             .map_err(|e| Status::invalid_argument(e.to_string()))?;
 
+        self.ensure_read_consistency(req.read_consistency()).await?;
+
         list_topic_by_req(&self.rocksdb_engine_handler, &req)
             .await
             .map_err(|e| Status::internal(e.to_string()))
@@ -323,6 +361,8 @@ async fn list_acl(
     ) -> Result<Response<ListAclReply>, Status> {
         let req = request.into_inner();
 
+        self.ensure_read_consistency(req.read_consistency()).await?;
+
         list_acl_by_req(&self.rocksdb_engine_handler, &req)
             .map_err(|e| Status::internal(e.to_string()))
             .map(Response::new)
@@ -358,6 +398,8 @@ async fn list_blacklist(
     ) -> Result<Response<ListBlacklistReply>, Status> {
         let req = request.into_inner();
 
+        self.ensure_read_consistency(req.read_consistency()).await?;
+
         list_blacklist_by_req(&self.rocksdb_engine_handler, &req)
             .map_err(|e| Status::internal(e.to_string()))
             .map(Response::new)
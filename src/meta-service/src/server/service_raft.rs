@@ -16,12 +16,15 @@
 use protocol::meta::meta_service_openraft::open_raft_service_server::OpenRaftService;
 use protocol::meta::meta_service_openraft::{
     AddLearnerReply, AddLearnerRequest, AppendReply, AppendRequest, ChangeMembershipReply,
-    ChangeMembershipRequest, SnapshotReply, SnapshotRequest, VoteReply, VoteRequest,
+    ChangeMembershipRequest, RaftStatusReply, RaftStatusRequest, ReadIndexReply, ReadIndexRequest,
+    SnapshotReply, SnapshotRequest, TransferLeaderReply, TransferLeaderRequest, VoteReply,
+    VoteRequest,
 };
 use tonic::{Request, Response, Status};
 
 use crate::raft::services::{
-    add_learner_by_req, append_by_req, change_membership_by_req, snapshot_by_req, vote_by_req,
+    add_learner_by_req, append_by_req, change_membership_by_req, raft_status_by_req,
+    read_index_by_req, snapshot_by_req, transfer_leader_by_req, vote_by_req,
 };
 use crate::raft::type_config::TypeConfig;
 
@@ -88,4 +91,37 @@ async fn change_membership(
             .map_err(|e| Status::internal(e.to_string()))
             .map(Response::new)
     }
+
+    async fn read_index(
+        &self,
+        request: Request<ReadIndexRequest>,
+    ) -> Result<Response<ReadIndexReply>, Status> {
+        let req = request.into_inner();
+        read_index_by_req(&self.raft_node, &req)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))
+            .map(Response::new)
+    }
+
+    async fn raft_status(
+        &self,
+        request: Request<RaftStatusRequest>,
+    ) -> Result<Response<RaftStatusReply>, Status> {
+        let req = request.into_inner();
+        raft_status_by_req(&self.raft_node, &req)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))
+            .map(Response::new)
+    }
+
+    async fn transfer_leader(
+        &self,
+        request: Request<TransferLeaderRequest>,
+    ) -> Result<Response<TransferLeaderReply>, Status> {
+        let req = request.into_inner();
+        transfer_leader_by_req(&self.raft_node, &req)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))
+            .map(Response::new)
+    }
 }
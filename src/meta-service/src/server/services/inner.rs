@@ -15,29 +15,39 @@
 use crate::controller::mqtt::call_broker::{
     update_cache_by_set_resource_config, MQTTInnerCallManager,
 };
+use crate::core::backup::{
+    build_archive, decode_archive, encode_archive, prepare_import, MetadataImportBatch,
+};
 use crate::core::cache::CacheManager;
 use crate::core::error::MetaServiceError;
 use crate::raft::route::apply::StorageDriver;
 use crate::raft::route::data::{StorageData, StorageDataType};
+use crate::raft::store::snapshot::{read_checkpoint_records, unpack_checkpoint};
 use crate::storage::placement::config::ResourceConfigStorage;
 use crate::storage::placement::idempotent::IdempotentStorage;
 use crate::storage::placement::offset::OffsetStorage;
+use bincode::serialize;
 use common_base::tools::now_second;
 use grpc_clients::pool::ClientPool;
 use metadata_struct::resource_config::ClusterResourceConfig;
 use prost::Message;
 use protocol::meta::meta_service_inner::{
-    ClusterStatusReply, DeleteIdempotentDataReply, DeleteIdempotentDataRequest,
+    BrokerNodeLoad, ClusterStatusReply, DeleteIdempotentDataReply, DeleteIdempotentDataRequest,
     DeleteResourceConfigReply, DeleteResourceConfigRequest, ExistsIdempotentDataReply,
-    ExistsIdempotentDataRequest, GetOffsetDataReply, GetOffsetDataReplyOffset,
-    GetOffsetDataRequest, GetResourceConfigReply, GetResourceConfigRequest, HeartbeatReply,
-    HeartbeatRequest, NodeListReply, NodeListRequest, SaveOffsetDataReply, SaveOffsetDataRequest,
-    SetIdempotentDataReply, SetIdempotentDataRequest, SetResourceConfigReply,
-    SetResourceConfigRequest,
+    ExistsIdempotentDataRequest, ExportMetadataReply, ExportMetadataRequest, GetOffsetDataReply,
+    GetOffsetDataReplyOffset, GetOffsetDataRequest, GetResourceConfigReply,
+    GetResourceConfigRequest, HeartbeatReply, HeartbeatRequest, ImportMetadataReply,
+    ImportMetadataRequest, NodeListReply, NodeListRequest, SaveOffsetDataReply,
+    SaveOffsetDataRequest, SetIdempotentDataReply, SetIdempotentDataRequest,
+    SetResourceConfigReply, SetResourceConfigRequest,
 };
 use rocksdb_engine::RocksDBEngine;
 use std::sync::Arc;
-use tracing::debug;
+use std::time::Duration;
+use tracing::{debug, info};
+
+const METADATA_SNAPSHOT_WAIT_TIMEOUT_SEC: u64 = 30;
+const METADATA_IMPORT_BATCH_SIZE: usize = 1000;
 
 pub async fn cluster_status_by_req(
     raft_machine_apply: &Arc<StorageDriver>,
@@ -80,9 +90,23 @@ pub async fn heartbeat_by_req(
                 now_second()
             );
 
-            cluster_cache.report_broker_heart(&req.cluster_name, req.node_id);
+            cluster_cache.report_broker_heart(
+                &req.cluster_name,
+                req.node_id,
+                req.draining,
+                req.connection_num,
+            );
 
-            Ok(HeartbeatReply::default())
+            let node_loads = cluster_cache
+                .get_broker_heart_by_cluster(&req.cluster_name)
+                .into_iter()
+                .map(|heart| BrokerNodeLoad {
+                    node_id: heart.node_id,
+                    connection_num: heart.connection_num,
+                    draining: heart.draining,
+                })
+                .collect();
+            Ok(HeartbeatReply { node_loads })
         }
         None => Err(MetaServiceError::NodeDoesNotExist(req.node_id)),
     }
@@ -217,3 +241,102 @@ pub async fn get_offset_data_by_req(
                 .collect(),
         })
 }
+
+// The export is taken from a freshly built raft snapshot, so the archive is a consistent
+// view of the state machine at a single log index.
+pub async fn export_metadata_by_req(
+    raft_machine_apply: &Arc<StorageDriver>,
+    req: &ExportMetadataRequest,
+) -> Result<ExportMetadataReply, MetaServiceError> {
+    if !req.resources.is_empty() && req.cluster_name.is_empty() {
+        return Err(MetaServiceError::RequestParamsNotEmpty(
+            "cluster_name".to_string(),
+        ));
+    }
+
+    let raft_node = &raft_machine_apply.raft_node;
+    let applied = raft_node.metrics().borrow().last_applied;
+    raft_node
+        .trigger()
+        .snapshot()
+        .await
+        .map_err(|e| MetaServiceError::CommonError(e.to_string()))?;
+    raft_node
+        .wait(Some(Duration::from_secs(
+            METADATA_SNAPSHOT_WAIT_TIMEOUT_SEC,
+        )))
+        .metrics(|m| m.snapshot >= applied, "export metadata snapshot")
+        .await
+        .map_err(|e| MetaServiceError::CommonError(e.to_string()))?;
+
+    let Some(snapshot) = raft_node
+        .get_snapshot()
+        .await
+        .map_err(|e| MetaServiceError::CommonError(e.to_string()))?
+    else {
+        return Err(MetaServiceError::CommonError(
+            "No raft snapshot is available for export".to_string(),
+        ));
+    };
+
+    // The snapshot is a packed rocksdb checkpoint, unpack it to read the records.
+    let restore = tempfile::tempdir()?;
+    unpack_checkpoint(snapshot.snapshot, restore.path()).await?;
+    let path = restore.path().to_path_buf();
+    let records = tokio::task::spawn_blocking(move || read_checkpoint_records(&path))
+        .await
+        .map_err(|e| MetaServiceError::CommonError(e.to_string()))??;
+    let archive = build_archive(
+        &req.cluster_name,
+        snapshot.meta.snapshot_id.clone(),
+        snapshot
+            .meta
+            .last_log_id
+            .map(|log_id| log_id.index)
+            .unwrap_or(0),
+        records,
+        &req.resources,
+    )?;
+    info!(
+        "Exported {} metadata records from snapshot {}",
+        archive.records.len(),
+        archive.snapshot_id
+    );
+
+    Ok(ExportMetadataReply {
+        archive: encode_archive(&archive)?,
+    })
+}
+
+pub async fn import_metadata_by_req(
+    raft_machine_apply: &Arc<StorageDriver>,
+    req: &ImportMetadataRequest,
+) -> Result<ImportMetadataReply, MetaServiceError> {
+    let archive = decode_archive(&req.archive)?;
+    if !req.resources.is_empty() && archive.cluster_name.is_empty() {
+        return Err(MetaServiceError::CommonError(
+            "The archive is not bound to a cluster, resources can not be imported selectively"
+                .to_string(),
+        ));
+    }
+
+    let records = prepare_import(&archive, &req.cluster_name, &req.resources)?;
+    let batch_num = records.len().div_ceil(METADATA_IMPORT_BATCH_SIZE);
+    for (i, batch) in records.chunks(METADATA_IMPORT_BATCH_SIZE).enumerate() {
+        let batch = MetadataImportBatch {
+            records: batch.to_vec(),
+            last: i + 1 == batch_num,
+        };
+        let data = StorageData::new(StorageDataType::MetadataImport, serialize(&batch)?);
+        raft_machine_apply.client_write(data).await?;
+    }
+    info!(
+        "Imported {} metadata records from snapshot {}",
+        records.len(),
+        archive.snapshot_id
+    );
+
+    Ok(ImportMetadataReply {
+        record_count: records.len() as u64,
+    })
+}
@@ -13,19 +13,29 @@
 // limitations under the License.
 
 use crate::core::error::MetaServiceError;
+use crate::core::watch::KvWatchManager;
 use crate::raft::route::apply::StorageDriver;
 use crate::raft::route::data::{StorageData, StorageDataType};
 use crate::storage::placement::kv::KvStorage;
+use crate::storage::placement::lease::KvLeaseStorage;
+use common_base::tools::now_nanos;
 use prost::Message;
 use protocol::meta::meta_service_kv::{
     DeleteReply, DeleteRequest, ExistsReply, ExistsRequest, GetPrefixReply, GetPrefixRequest,
-    GetReply, GetRequest, ListShardReply, ListShardRequest, SetReply, SetRequest,
+    GetReply, GetRequest, LeaseGrantReply, LeaseGrantRequest, LeaseKeepAliveReply,
+    LeaseKeepAliveRequest, LeaseRevokeReply, LeaseRevokeRequest, ListShardReply, ListShardRequest,
+    SetReply, SetRequest, TxnOpType, TxnReply, TxnRequest, WatchEvent, WatchReply, WatchRequest,
 };
 use rocksdb_engine::RocksDBEngine;
+use std::pin::Pin;
 use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tonic::codegen::tokio_stream::Stream;
+use tonic::Status;
 
 pub async fn set_by_req(
     raft_machine_apply: &Arc<StorageDriver>,
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
     req: &SetRequest,
 ) -> Result<SetReply, MetaServiceError> {
     if req.key.is_empty() || req.value.is_empty() {
@@ -34,8 +44,15 @@ pub async fn set_by_req(
         ));
     }
 
+    check_lease_exists(rocksdb_engine_handler, req.lease_id)?;
+
+    // the lease may be revoked before the entry is applied, the apply step checks it again
     let data = StorageData::new(StorageDataType::KvSet, SetRequest::encode_to_vec(req));
-    raft_machine_apply.client_write(data).await?;
+    if let Some(resp) = raft_machine_apply.client_write(data).await? {
+        if let Some(err) = resp.data.error {
+            return Err(MetaServiceError::CommonError(err));
+        }
+    }
 
     Ok(SetReply::default())
 }
@@ -51,9 +68,13 @@ pub async fn get_by_req(
     let kv_storage = KvStorage::new(rocksdb_engine_handler.clone());
     let mut reply = GetReply::default();
 
-    match kv_storage.get(req.key.clone()) {
-        Ok(Some(data)) => {
-            reply.value = data;
+    match kv_storage.get_entry(req.key.clone()) {
+        Ok(Some(entry)) => {
+            reply.value = entry.value;
+            reply.create_revision = entry.create_revision;
+            reply.mod_revision = entry.mod_revision;
+            reply.version = entry.version;
+            reply.lease_id = entry.lease_id;
         }
         Ok(None) => {}
         Err(e) => return Err(MetaServiceError::CommonError(e.to_string())),
@@ -122,3 +143,168 @@ pub async fn get_prefix_by_req(
 
     Ok(GetPrefixReply { values })
 }
+
+pub async fn txn_by_req(
+    raft_machine_apply: &Arc<StorageDriver>,
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    req: &TxnRequest,
+) -> Result<TxnReply, MetaServiceError> {
+    for compare in req.compare.iter() {
+        if compare.key.is_empty() {
+            return Err(MetaServiceError::RequestParamsNotEmpty(
+                "compare key".to_string(),
+            ));
+        }
+    }
+
+    for op in req.success.iter().chain(req.failure.iter()) {
+        if op.key.is_empty() {
+            return Err(MetaServiceError::RequestParamsNotEmpty(
+                "op key".to_string(),
+            ));
+        }
+        if op.op_type() == TxnOpType::Put {
+            check_lease_exists(rocksdb_engine_handler, op.lease_id)?;
+        }
+    }
+
+    let data = StorageData::new(StorageDataType::KvTxn, TxnRequest::encode_to_vec(req));
+    if let Some(resp) = raft_machine_apply.client_write(data).await? {
+        if let Some(err) = resp.data.error {
+            return Err(MetaServiceError::CommonError(err));
+        }
+        if let Some(value) = resp.data.value {
+            return Ok(TxnReply::decode(value.as_ref())?);
+        }
+    }
+    Err(MetaServiceError::ExecutionResultIsEmpty)
+}
+
+pub async fn lease_grant_by_req(
+    raft_machine_apply: &Arc<StorageDriver>,
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    req: &LeaseGrantRequest,
+) -> Result<LeaseGrantReply, MetaServiceError> {
+    if req.ttl == 0 {
+        return Err(MetaServiceError::RequestParamsNotEmpty("ttl".to_string()));
+    }
+
+    let lease_storage = KvLeaseStorage::new(rocksdb_engine_handler.clone());
+    let lease_id = if req.lease_id > 0 {
+        if lease_storage.get(req.lease_id)?.is_some() {
+            return Err(MetaServiceError::KvLeaseAlreadyExist(req.lease_id));
+        }
+        req.lease_id
+    } else {
+        now_nanos() as u64
+    };
+
+    let request = LeaseGrantRequest {
+        ttl: req.ttl,
+        lease_id,
+    };
+    let data = StorageData::new(
+        StorageDataType::KvLeaseGrant,
+        LeaseGrantRequest::encode_to_vec(&request),
+    );
+    raft_machine_apply.client_write(data).await?;
+
+    Ok(LeaseGrantReply {
+        lease_id,
+        ttl: req.ttl,
+    })
+}
+
+pub async fn lease_revoke_by_req(
+    raft_machine_apply: &Arc<StorageDriver>,
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    req: &LeaseRevokeRequest,
+) -> Result<LeaseRevokeReply, MetaServiceError> {
+    check_lease_exists(rocksdb_engine_handler, req.lease_id)?;
+
+    let data = StorageData::new(
+        StorageDataType::KvLeaseRevoke,
+        LeaseRevokeRequest::encode_to_vec(req),
+    );
+    raft_machine_apply.client_write(data).await?;
+
+    Ok(LeaseRevokeReply::default())
+}
+
+pub async fn lease_keep_alive_by_req(
+    raft_machine_apply: &Arc<StorageDriver>,
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    req: &LeaseKeepAliveRequest,
+) -> Result<LeaseKeepAliveReply, MetaServiceError> {
+    let lease_storage = KvLeaseStorage::new(rocksdb_engine_handler.clone());
+    let Some(lease) = lease_storage.get(req.lease_id)? else {
+        return Err(MetaServiceError::KvLeaseDoesNotExist(req.lease_id));
+    };
+
+    let data = StorageData::new(
+        StorageDataType::KvLeaseKeepAlive,
+        LeaseKeepAliveRequest::encode_to_vec(req),
+    );
+    raft_machine_apply.client_write(data).await?;
+
+    Ok(LeaseKeepAliveReply {
+        lease_id: lease.lease_id,
+        ttl: lease.ttl,
+    })
+}
+
+fn check_lease_exists(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    lease_id: u64,
+) -> Result<(), MetaServiceError> {
+    if lease_id == 0 {
+        return Ok(());
+    }
+    let lease_storage = KvLeaseStorage::new(rocksdb_engine_handler.clone());
+    if lease_storage.get(lease_id)?.is_none() {
+        return Err(MetaServiceError::KvLeaseDoesNotExist(lease_id));
+    }
+    Ok(())
+}
+
+pub fn watch_by_req(
+    watch_manager: &Arc<KvWatchManager>,
+    req: &WatchRequest,
+) -> Result<Pin<Box<dyn Stream<Item = Result<WatchReply, Status>> + Send>>, MetaServiceError> {
+    let (history, mut recv) = watch_manager.watch(&req.prefix, req.start_revision)?;
+    let watch_manager = watch_manager.clone();
+    let prefix = req.prefix.clone();
+
+    let output = async_stream::try_stream! {
+        if !history.is_empty() {
+            yield build_watch_reply(&watch_manager, history);
+        }
+
+        loop {
+            match recv.recv().await {
+                Ok(event) => {
+                    if event.key.starts_with(&prefix) {
+                        yield build_watch_reply(&watch_manager, vec![event]);
+                    }
+                }
+                // the client is expected to resume from the last revision it received
+                Err(RecvError::Lagged(num)) => {
+                    Err(Status::data_loss(format!(
+                        "Watcher lagged behind by {num} events, please resume from the last received revision"
+                    )))?;
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    };
+
+    Ok(Box::pin(output))
+}
+
+fn build_watch_reply(watch_manager: &Arc<KvWatchManager>, events: Vec<WatchEvent>) -> WatchReply {
+    WatchReply {
+        events,
+        revision: watch_manager.revision(),
+        compact_revision: watch_manager.compact_revision(),
+    }
+}
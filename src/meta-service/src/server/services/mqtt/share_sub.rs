@@ -45,7 +45,7 @@ pub fn get_leader_node(
     ) -> Result<u64, CommonError> {
         let mut broker_ids = self
             .cache_manager
-            .get_broker_node_id_by_cluster(cluster_name);
+            .get_schedulable_broker_node_id_by_cluster(cluster_name);
 
         broker_ids.sort();
 
@@ -82,11 +82,12 @@ pub fn get_leader_node(
             return Err(CommonError::ClusterNoAvailableNode);
         }
 
+        // The group may still be recorded on a draining or departed broker
+        self.remove_group_by_node(cluster_name, group_name)?;
         self.save_node_sub_info(cluster_name, target_broker_id, group_name)?;
         Ok(target_broker_id)
     }
 
-    #[allow(dead_code)]
     pub fn remove_group_by_node(
         &self,
         cluster_name: &str,
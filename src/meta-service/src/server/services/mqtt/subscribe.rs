@@ -77,7 +77,11 @@ pub fn list_subscribe_by_req(
     req: &ListSubscribeRequest,
 ) -> Result<ListSubscribeReply, MetaServiceError> {
     let storage = MqttSubscribeStorage::new(rocksdb_engine_handler.clone());
-    let data = storage.list_by_cluster(&req.cluster_name)?;
+    let data = if req.client_id.is_empty() {
+        storage.list_by_cluster(&req.cluster_name)?
+    } else {
+        storage.list_by_client_id(&req.cluster_name, &req.client_id)?
+    };
     let subscribes = data.into_iter().map(|raw| raw.encode()).collect();
 
     Ok(ListSubscribeReply { subscribes })
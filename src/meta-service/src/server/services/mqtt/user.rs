@@ -28,7 +28,7 @@
 use prost::Message;
 use protocol::meta::meta_service_mqtt::{
     CreateUserReply, CreateUserRequest, DeleteUserReply, DeleteUserRequest, ListUserReply,
-    ListUserRequest,
+    ListUserRequest, UpdateUserReply, UpdateUserRequest,
 };
 use rocksdb_engine::RocksDBEngine;
 use std::sync::Arc;
@@ -78,6 +78,35 @@ pub async fn create_user_by_req(
     Ok(CreateUserReply {})
 }
 
+pub async fn update_user_by_req(
+    raft_machine_apply: &Arc<StorageDriver>,
+    call_manager: &Arc<MQTTInnerCallManager>,
+    client_pool: &Arc<ClientPool>,
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    req: &UpdateUserRequest,
+) -> Result<UpdateUserReply, MetaServiceError> {
+    let storage = MqttUserStorage::new(rocksdb_engine_handler.clone());
+    if storage.get(&req.cluster_name, &req.user_name)?.is_none() {
+        return Err(MetaServiceError::UserDoesNotExist(req.user_name.clone()));
+    }
+
+    let create_req = CreateUserRequest {
+        cluster_name: req.cluster_name.clone(),
+        user_name: req.user_name.clone(),
+        content: req.content.clone(),
+    };
+    let data = StorageData::new(
+        StorageDataType::MqttSetUser,
+        CreateUserRequest::encode_to_vec(&create_req),
+    );
+
+    raft_machine_apply.client_write(data).await?;
+    let user = serde_json::from_slice::<MqttUser>(&req.content)?;
+    update_cache_by_add_user(&req.cluster_name, call_manager, client_pool, user).await?;
+
+    Ok(UpdateUserReply {})
+}
+
 pub async fn delete_user_by_req(
     raft_machine_apply: &Arc<StorageDriver>,
     call_manager: &Arc<MQTTInnerCallManager>,
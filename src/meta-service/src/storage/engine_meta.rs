@@ -14,6 +14,8 @@
 
 use broker_core::rocksdb::DB_COLUMN_FAMILY_META;
 use common_base::error::common::CommonError;
+use common_security::encryption::seal_value;
+use rocksdb::WriteBatch;
 use rocksdb_engine::storage::{
     engine_delete, engine_exists, engine_get, engine_prefix_list, engine_save,
 };
@@ -75,6 +77,37 @@ pub fn engine_delete_by_cluster(
     )
 }
 
+// Encode a value the way engine_save_by_meta stores it, for engine_batch_by_meta
+pub fn encode_meta_value<T>(value: T) -> Result<Vec<u8>, CommonError>
+where
+    T: Serialize,
+{
+    let wrap = StorageDataWrap::new(serde_json::to_string(&value)?);
+    Ok(serde_json::to_vec(&wrap)?)
+}
+
+// Write all (key, encoded value) pairs at once, a pair without a value deletes the key
+pub fn engine_batch_by_meta(
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+    records: Vec<(String, Option<Vec<u8>>)>,
+) -> Result<(), CommonError> {
+    let Some(cf) = rocksdb_engine_handler.cf_handle(DB_COLUMN_FAMILY_META) else {
+        return Err(CommonError::RocksDBFamilyNotAvailable(
+            DB_COLUMN_FAMILY_META.to_string(),
+        ));
+    };
+
+    let mut batch = WriteBatch::default();
+    for (key, value) in records {
+        match value {
+            Some(value) => batch.put_cf(&cf, key, seal_value(value)?),
+            None => batch.delete_cf(&cf, key),
+        }
+    }
+    rocksdb_engine_handler.db.write(batch)?;
+    Ok(())
+}
+
 pub fn engine_prefix_list_by_cluster(
     rocksdb_engine_handler: Arc<RocksDBEngine>,
     prefix_key_name: String,
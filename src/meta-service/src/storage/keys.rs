@@ -51,6 +51,18 @@ pub fn key_offset_by_group(cluster_name: &str, group: &str) -> String {
     prefix_key(format!("/offset/{cluster_name}/{group}"))
 }
 
+pub fn key_kv_lease(lease_id: u64) -> String {
+    prefix_key(format!("/kv/lease/{lease_id}"))
+}
+
+pub fn key_kv_lease_prefix() -> String {
+    prefix_key("/kv/lease/".to_string())
+}
+
+pub fn key_raft_applied_state() -> String {
+    prefix_key("/raft/applied_state".to_string())
+}
+
 /** ===========Journal========== */
 pub fn key_shard(cluster_name: &str, namespace: &str, shard_name: &str) -> String {
     prefix_key(format!(
@@ -21,6 +21,37 @@
     engine_prefix_list_by_cluster, engine_save_by_meta,
 };
 use rocksdb_engine::RocksDBEngine;
+use serde::{Deserialize, Serialize};
+
+// Values written before revisions were tracked are stored as plain strings, their
+// revisions are unknown and are read back as LEGACY_REVISION. It is older than any
+// revision assigned since and keeps them apart from missing keys, which compare with
+// all revisions set to 0.
+pub const LEGACY_REVISION: u64 = 1;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KvEntry {
+    pub value: String,
+    pub create_revision: u64,
+    pub mod_revision: u64,
+    pub version: u64,
+    pub lease_id: u64,
+}
+
+impl KvEntry {
+    fn decode(data: &str) -> Result<KvEntry, CommonError> {
+        if let Ok(entry) = serde_json::from_str::<KvEntry>(data) {
+            return Ok(entry);
+        }
+        Ok(KvEntry {
+            value: serde_json::from_str::<String>(data)?,
+            create_revision: LEGACY_REVISION,
+            mod_revision: LEGACY_REVISION,
+            version: 1,
+            lease_id: 0,
+        })
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct KvStorage {
@@ -38,13 +69,21 @@ pub fn set(&self, key: String, value: String) -> Result<(), CommonError> {
         engine_save_by_meta(self.rocksdb_engine_handler.clone(), key, value)
     }
 
+    pub fn put(&self, key: String, entry: KvEntry) -> Result<(), CommonError> {
+        engine_save_by_meta(self.rocksdb_engine_handler.clone(), key, entry)
+    }
+
     pub fn delete(&self, key: String) -> Result<(), CommonError> {
         engine_delete_by_cluster(self.rocksdb_engine_handler.clone(), key)
     }
 
     pub fn get(&self, key: String) -> Result<Option<String>, CommonError> {
+        Ok(self.get_entry(key)?.map(|entry| entry.value))
+    }
+
+    pub fn get_entry(&self, key: String) -> Result<Option<KvEntry>, CommonError> {
         if let Some(data) = engine_get_by_cluster(self.rocksdb_engine_handler.clone(), key)? {
-            return Ok(Some(serde_json::from_str::<String>(&data.data)?));
+            return Ok(Some(KvEntry::decode(&data.data)?));
         }
         Ok(None)
     }
@@ -58,7 +97,7 @@ pub fn get_prefix(&self, prefix: String) -> Result<Vec<String>, CommonError> {
             Ok(data) => {
                 let mut result = Vec::new();
                 for item in data {
-                    result.push(serde_json::from_str(&item.data)?);
+                    result.push(KvEntry::decode(&item.data)?.value);
                 }
                 Ok(result)
             }
@@ -144,6 +183,32 @@ fn test_get_prefix() {
         assert_eq!(result, vec!["value1".to_string(), "value2".to_string()]);
     }
 
+    #[test]
+    fn test_put_and_get_entry() {
+        let kv = setup_kv_storage();
+        kv.set("legacy".to_string(), "value0".to_string()).unwrap();
+        let entry = kv.get_entry("legacy".to_string()).unwrap().unwrap();
+        assert_eq!(entry.value, "value0");
+        assert_eq!(entry.create_revision, LEGACY_REVISION);
+        assert_eq!(entry.mod_revision, LEGACY_REVISION);
+        assert_eq!(entry.version, 1);
+
+        let entry = KvEntry {
+            value: "value1".to_string(),
+            create_revision: 3,
+            mod_revision: 5,
+            version: 2,
+            lease_id: 7,
+        };
+        kv.put("key1".to_string(), entry.clone()).unwrap();
+        assert_eq!(kv.get_entry("key1".to_string()).unwrap(), Some(entry));
+        assert_eq!(
+            kv.get("key1".to_string()).unwrap(),
+            Some("value1".to_string())
+        );
+        assert_eq!(kv.get_prefix("key".to_string()).unwrap(), vec!["value1"]);
+    }
+
     #[test]
     fn test_get_prefix_non_existent() {
         let kv = setup_kv_storage();
@@ -0,0 +1,106 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_base::error::common::CommonError;
+use serde::{Deserialize, Serialize};
+
+use crate::storage::engine_meta::{
+    engine_delete_by_cluster, engine_get_by_cluster, engine_prefix_list_by_cluster,
+    engine_save_by_meta,
+};
+use crate::storage::keys::{key_kv_lease, key_kv_lease_prefix};
+use rocksdb_engine::RocksDBEngine;
+
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KvLease {
+    pub lease_id: u64,
+    pub ttl: u64,
+    pub keys: Vec<String>,
+    pub create_time: u64,
+}
+
+pub struct KvLeaseStorage {
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+}
+
+impl KvLeaseStorage {
+    pub fn new(rocksdb_engine_handler: Arc<RocksDBEngine>) -> Self {
+        KvLeaseStorage {
+            rocksdb_engine_handler,
+        }
+    }
+
+    pub fn save(&self, lease: &KvLease) -> Result<(), CommonError> {
+        let key = key_kv_lease(lease.lease_id);
+        engine_save_by_meta(self.rocksdb_engine_handler.clone(), key, lease)
+    }
+
+    pub fn get(&self, lease_id: u64) -> Result<Option<KvLease>, CommonError> {
+        let key = key_kv_lease(lease_id);
+        if let Some(data) = engine_get_by_cluster(self.rocksdb_engine_handler.clone(), key)? {
+            return Ok(Some(serde_json::from_str::<KvLease>(&data.data)?));
+        }
+        Ok(None)
+    }
+
+    pub fn list(&self) -> Result<Vec<KvLease>, CommonError> {
+        let prefix_key = key_kv_lease_prefix();
+        let data = engine_prefix_list_by_cluster(self.rocksdb_engine_handler.clone(), prefix_key)?;
+        let mut results = Vec::new();
+        for raw in data {
+            results.push(serde_json::from_str::<KvLease>(&raw.data)?);
+        }
+        Ok(results)
+    }
+
+    pub fn delete(&self, lease_id: u64) -> Result<(), CommonError> {
+        let key = key_kv_lease(lease_id);
+        engine_delete_by_cluster(self.rocksdb_engine_handler.clone(), key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{KvLease, KvLeaseStorage};
+    use broker_core::rocksdb::column_family_list;
+    use rocksdb_engine::RocksDBEngine;
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    #[test]
+    fn lease_storage_test() {
+        let engine = Arc::new(RocksDBEngine::new(
+            tempdir().unwrap().path().to_str().unwrap(),
+            100,
+            column_family_list(),
+        ));
+        let storage = KvLeaseStorage::new(engine);
+
+        let lease = KvLease {
+            lease_id: 1,
+            ttl: 10,
+            keys: vec!["/a".to_string()],
+            create_time: 100,
+        };
+        storage.save(&lease).unwrap();
+        assert_eq!(storage.get(1).unwrap(), Some(lease));
+        assert_eq!(storage.list().unwrap().len(), 1);
+
+        storage.delete(1).unwrap();
+        assert!(storage.get(1).unwrap().is_none());
+        assert!(storage.list().unwrap().is_empty());
+    }
+}
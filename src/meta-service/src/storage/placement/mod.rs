@@ -16,6 +16,7 @@
 pub mod config;
 pub mod idempotent;
 pub mod kv;
+pub mod lease;
 pub mod node;
 pub mod offset;
 pub mod schema;
@@ -155,7 +155,7 @@ mod tests {
     use std::sync::Arc;
 
     use broker_core::rocksdb::column_family_list;
-    use metadata_struct::schema::SchemaType;
+    use metadata_struct::schema::{SchemaCompatibility, SchemaType};
     use metadata_struct::schema::{SchemaData, SchemaResourceBind};
     use tempfile::tempdir;
 
@@ -183,6 +183,9 @@ async fn schema_storage_test() {
             schema_type: SchemaType::JSON,
             desc: desc.to_string(),
             schema: schema.to_string(),
+            message_name: "".to_string(),
+            version: 1,
+            compatibility: SchemaCompatibility::None,
         };
 
         //test func save()
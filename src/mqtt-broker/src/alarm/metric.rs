@@ -0,0 +1,201 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use common_base::tools::now_mills;
+use common_config::broker::broker_config;
+use common_metrics::mqtt::auth::get_mqtt_auth_failed;
+use common_metrics::mqtt::statistics::record_mqtt_connections_get;
+use metadata_struct::mqtt::alarm::AlarmMetric;
+use storage_adapter::storage::ArcStorageAdapter;
+use sysinfo::{DiskExt, System, SystemExt};
+use tokio::task::spawn_blocking;
+
+use crate::bridge::manager::ConnectorManager;
+use crate::handler::consumer_lag::max_consumer_lag;
+use crate::handler::system_alarm::{get_process_every_cpu_usage, get_process_memory_usage};
+use crate::storage::message::MessageStorage;
+use crate::subscribe::manager::SubscribeManager;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlarmMetricValue {
+    pub value: f64,
+    // What the value was taken from, when the metric covers several sources
+    pub source: Option<String>,
+}
+
+impl AlarmMetricValue {
+    fn new(value: f64) -> Self {
+        AlarmMetricValue {
+            value,
+            source: None,
+        }
+    }
+}
+
+pub struct AlarmMetricCollector {
+    subscribe_manager: Arc<SubscribeManager>,
+    connector_manager: Arc<ConnectorManager>,
+    message_storage: MessageStorage,
+    // (failed authentication count, time in ms) of the previous sample
+    auth_failed_sample: Mutex<Option<(u64, u128)>>,
+}
+
+impl AlarmMetricCollector {
+    pub fn new(
+        subscribe_manager: Arc<SubscribeManager>,
+        connector_manager: Arc<ConnectorManager>,
+        message_storage_adapter: ArcStorageAdapter,
+    ) -> Self {
+        AlarmMetricCollector {
+            subscribe_manager,
+            connector_manager,
+            message_storage: MessageStorage::new(message_storage_adapter),
+            auth_failed_sample: Mutex::new(None),
+        }
+    }
+
+    // None when the metric has no value on this broker yet
+    pub async fn collect(&self, metric: AlarmMetric) -> Option<AlarmMetricValue> {
+        let conf = broker_config();
+        match metric {
+            AlarmMetric::ConnectionNum => {
+                Some(AlarmMetricValue::new(record_mqtt_connections_get() as f64))
+            }
+            AlarmMetric::ConsumerLag => max_consumer_lag(
+                &self.subscribe_manager,
+                &self.connector_manager,
+                &self.message_storage,
+            )
+            .await
+            .map(|(source, lag)| AlarmMetricValue {
+                value: lag as f64,
+                source: Some(source),
+            }),
+            // walking the directory and reading the disks block, keep them off the workers
+            AlarmMetric::RocksDBSize => {
+                let path = PathBuf::from(&conf.rocksdb.data_path);
+                spawn_blocking(move || dir_size(&path))
+                    .await
+                    .ok()
+                    .map(|size| AlarmMetricValue::new(size as f64))
+            }
+            AlarmMetric::JournalDiskUsage => {
+                let paths = conf.journal_storage.data_path.clone();
+                spawn_blocking(move || disk_usage(&paths))
+                    .await
+                    .ok()
+                    .flatten()
+            }
+            AlarmMetric::AuthFailureRate => self.auth_failure_rate(),
+            AlarmMetric::CpuUsage => Some(AlarmMetricValue::new(
+                get_process_every_cpu_usage().await as f64,
+            )),
+            AlarmMetric::MemoryUsage => {
+                Some(AlarmMetricValue::new(get_process_memory_usage() as f64))
+            }
+        }
+    }
+
+    fn auth_failure_rate(&self) -> Option<AlarmMetricValue> {
+        let count = get_mqtt_auth_failed();
+        let now = now_mills();
+        // a panic while the lock was held leaves a valid sample, keep using it
+        let mut sample = self
+            .auth_failed_sample
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        let rate = (*sample).and_then(|(last_count, last_time)| {
+            let elapsed_ms = now.saturating_sub(last_time);
+            if elapsed_ms == 0 {
+                return None;
+            }
+            Some(count.saturating_sub(last_count) as f64 * 1000.0 / elapsed_ms as f64)
+        });
+        *sample = Some((count, now));
+        rate.map(AlarmMetricValue::new)
+    }
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    let mut size = 0;
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            size += dir_size(&entry.path());
+        } else {
+            size += metadata.len();
+        }
+    }
+    size
+}
+
+// Usage percentage of the fullest disk holding one of the paths
+fn disk_usage(paths: &[String]) -> Option<AlarmMetricValue> {
+    let mut system = System::new();
+    system.refresh_disks_list();
+
+    let mut result: Option<AlarmMetricValue> = None;
+    for path in paths {
+        // the disk mounted closest to the path holds it
+        let Some(disk) = system
+            .disks()
+            .iter()
+            .filter(|disk| Path::new(path).starts_with(disk.mount_point()))
+            .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        else {
+            continue;
+        };
+        if disk.total_space() == 0 {
+            continue;
+        }
+
+        let used = disk.total_space().saturating_sub(disk.available_space());
+        let usage = used as f64 * 100.0 / disk.total_space() as f64;
+        if result.as_ref().is_none_or(|raw| usage > raw.value) {
+            result = Some(AlarmMetricValue {
+                value: usage,
+                source: Some(path.clone()),
+            });
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::dir_size;
+    use common_base::tools::unique_id;
+    use std::path::Path;
+
+    #[test]
+    fn dir_size_test() {
+        let path = format!("/tmp/robustmq-alarm-{}", unique_id());
+        std::fs::create_dir_all(format!("{path}/sub")).unwrap();
+        std::fs::write(format!("{path}/a"), vec![0u8; 10]).unwrap();
+        std::fs::write(format!("{path}/sub/b"), vec![0u8; 5]).unwrap();
+
+        assert_eq!(dir_size(Path::new(&path)), 15);
+        assert_eq!(dir_size(Path::new(&format!("{path}/none"))), 0);
+
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+}
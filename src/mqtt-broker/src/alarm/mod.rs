@@ -0,0 +1,334 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use broker_core::rocksdb::RocksDBEngine;
+use common_base::error::ResultCommonError;
+use common_base::tools::{loop_select_ticket, now_second};
+use common_config::broker::broker_config;
+use dashmap::DashMap;
+use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::alarm::{AlarmMetric, AlarmSink, MqttAlarmHistory, MqttAlarmRule};
+use storage_adapter::storage::ArcStorageAdapter;
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+use crate::bridge::manager::ConnectorManager;
+use crate::common::types::ResultMqttBrokerError;
+use crate::handler::cache::MQTTCacheManager;
+use crate::handler::error::MqttBrokerError;
+use crate::storage::alarm::AlarmStorage;
+use crate::subscribe::manager::SubscribeManager;
+use metric::{AlarmMetricCollector, AlarmMetricValue};
+use notify::AlarmNotifier;
+
+pub mod metric;
+pub mod notify;
+
+pub const ALARM_HIGH_CPU_USAGE: &str = "HighCpuUsage";
+pub const ALARM_HIGH_MEMORY_USAGE: &str = "HighMemoryUsage";
+pub const ALARM_HIGH_CONSUMER_LAG: &str = "HighConsumerLag";
+
+const ALARM_CHECK_INTERVAL_SEC: u64 = 60;
+
+// Built-in rules deactivate once the value is back under 90% of the watermark
+const BUILTIN_DEACTIVATE_RATIO: f64 = 0.9;
+
+// Rules derived from `mqtt_system_monitor`, they are evaluated next to the
+// rules created through admin-server.
+pub fn builtin_alarm_rules() -> Vec<MqttAlarmRule> {
+    let conf = broker_config();
+    let monitor = &conf.mqtt_system_monitor;
+    if !monitor.enable {
+        return Vec::new();
+    }
+
+    let build = |name: &str, metric: AlarmMetric, watermark: f64| MqttAlarmRule {
+        cluster_name: conf.cluster_name.clone(),
+        name: name.to_string(),
+        metric,
+        activate_threshold: watermark,
+        deactivate_threshold: watermark * BUILTIN_DEACTIVATE_RATIO,
+        sinks: Vec::new(),
+        enable: true,
+        create_time: 0,
+    };
+
+    let mut rules = vec![
+        build(
+            ALARM_HIGH_CPU_USAGE,
+            AlarmMetric::CpuUsage,
+            monitor.os_cpu_high_watermark as f64,
+        ),
+        build(
+            ALARM_HIGH_MEMORY_USAGE,
+            AlarmMetric::MemoryUsage,
+            monitor.os_memory_high_watermark as f64,
+        ),
+    ];
+    if monitor.consumer_lag_watermark > 0 {
+        rules.push(build(
+            ALARM_HIGH_CONSUMER_LAG,
+            AlarmMetric::ConsumerLag,
+            monitor.consumer_lag_watermark as f64,
+        ));
+    }
+    rules
+}
+
+pub fn validate_alarm_rule(rule: &MqttAlarmRule) -> ResultMqttBrokerError {
+    if rule.name.is_empty() {
+        return Err(MqttBrokerError::CommonError(
+            "Alarm rule name cannot be empty".to_string(),
+        ));
+    }
+
+    if [
+        ALARM_HIGH_CPU_USAGE,
+        ALARM_HIGH_MEMORY_USAGE,
+        ALARM_HIGH_CONSUMER_LAG,
+    ]
+    .contains(&rule.name.as_str())
+    {
+        return Err(MqttBrokerError::CommonError(format!(
+            "Alarm rule name {} is reserved for a built-in rule",
+            rule.name
+        )));
+    }
+
+    if !rule.activate_threshold.is_finite()
+        || !rule.deactivate_threshold.is_finite()
+        || rule.deactivate_threshold > rule.activate_threshold
+    {
+        return Err(MqttBrokerError::CommonError(
+            "deactivate_threshold must not be greater than activate_threshold".to_string(),
+        ));
+    }
+
+    for sink in rule.sinks.iter() {
+        let valid = match sink {
+            AlarmSink::Webhook { url } => url.starts_with("http://") || url.starts_with("https://"),
+            AlarmSink::MqttTopic { topic_name } => !topic_name.is_empty(),
+            AlarmSink::Connector { connector_name } => !connector_name.is_empty(),
+        };
+        if !valid {
+            return Err(MqttBrokerError::CommonError(format!(
+                "Invalid alarm sink {sink:?}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+// The new state of the alarm when the value makes it change, the gap between
+// the two thresholds keeps a value hovering around one of them from flapping.
+pub fn evaluate_alarm(active: bool, value: f64, rule: &MqttAlarmRule) -> Option<bool> {
+    if !active && value >= rule.activate_threshold {
+        return Some(true);
+    }
+    if active && value <= rule.deactivate_threshold {
+        return Some(false);
+    }
+    None
+}
+
+pub struct AlarmManager {
+    client_pool: Arc<ClientPool>,
+    collector: AlarmMetricCollector,
+    notifier: AlarmNotifier,
+    // (rule name, active)
+    states: DashMap<String, bool>,
+    stop_send: broadcast::Sender<bool>,
+}
+
+impl AlarmManager {
+    pub fn new(
+        client_pool: Arc<ClientPool>,
+        cache_manager: Arc<MQTTCacheManager>,
+        subscribe_manager: Arc<SubscribeManager>,
+        connector_manager: Arc<ConnectorManager>,
+        message_storage_adapter: ArcStorageAdapter,
+        rocksdb_engine_handler: Arc<RocksDBEngine>,
+        stop_send: broadcast::Sender<bool>,
+    ) -> Self {
+        let collector = AlarmMetricCollector::new(
+            subscribe_manager,
+            connector_manager.clone(),
+            message_storage_adapter.clone(),
+        );
+        let notifier = AlarmNotifier::new(
+            client_pool.clone(),
+            cache_manager,
+            connector_manager,
+            message_storage_adapter,
+            rocksdb_engine_handler,
+        );
+        AlarmManager {
+            client_pool,
+            collector,
+            notifier,
+            states: DashMap::with_capacity(8),
+            stop_send,
+        }
+    }
+
+    pub async fn start(&self) {
+        let ac_fn = async || -> ResultCommonError {
+            self.check().await;
+            Ok(())
+        };
+
+        info!("Alarm thread start successfully");
+        loop_select_ticket(ac_fn, ALARM_CHECK_INTERVAL_SEC, &self.stop_send).await;
+    }
+
+    async fn check(&self) {
+        // Rules are read from meta-service on every round so that changes made
+        // through any broker take effect everywhere
+        let mut rules = builtin_alarm_rules();
+        match AlarmStorage::new(self.client_pool.clone())
+            .list_rule()
+            .await
+        {
+            Ok(list) => rules.extend(list.into_iter().filter(|rule| rule.enable)),
+            Err(e) => warn!("Failed to list alarm rules, error: {}", e),
+        }
+
+        let names: HashSet<String> = rules.iter().map(|rule| rule.name.clone()).collect();
+        self.states.retain(|name, _| names.contains(name));
+
+        // each metric is collected once per round
+        let mut values: HashMap<AlarmMetric, Option<AlarmMetricValue>> = HashMap::new();
+        for rule in rules {
+            let value = match values.get(&rule.metric) {
+                Some(value) => value.clone(),
+                None => {
+                    let value = self.collector.collect(rule.metric).await;
+                    values.insert(rule.metric, value.clone());
+                    value
+                }
+            };
+            let Some(value) = value else {
+                continue;
+            };
+
+            let active = self.states.get(&rule.name).map(|v| *v).unwrap_or(false);
+            let Some(activated) = evaluate_alarm(active, value.value, &rule) else {
+                continue;
+            };
+            self.states.insert(rule.name.clone(), activated);
+
+            let history = build_alarm_history(&rule, &value, activated);
+            self.notifier.notify(&rule, &history).await;
+        }
+    }
+}
+
+fn build_alarm_history(
+    rule: &MqttAlarmRule,
+    value: &AlarmMetricValue,
+    activated: bool,
+) -> MqttAlarmHistory {
+    let conf = broker_config();
+    let source = value
+        .source
+        .as_ref()
+        .map(|source| format!(" ({source})"))
+        .unwrap_or_default();
+    let (threshold, message) = if activated {
+        (
+            rule.activate_threshold,
+            format!(
+                "{} is {:.2}{}, reached the threshold {}",
+                rule.metric, value.value, source, rule.activate_threshold
+            ),
+        )
+    } else {
+        (
+            rule.deactivate_threshold,
+            format!(
+                "{} is {:.2}{}, back under the threshold {}",
+                rule.metric, value.value, source, rule.deactivate_threshold
+            ),
+        )
+    };
+
+    MqttAlarmHistory {
+        cluster_name: conf.cluster_name.clone(),
+        rule_name: rule.name.clone(),
+        broker_id: conf.broker_id,
+        metric: rule.metric,
+        value: value.value,
+        threshold,
+        activated,
+        message,
+        create_time: now_second(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{evaluate_alarm, validate_alarm_rule};
+    use metadata_struct::mqtt::alarm::{AlarmMetric, AlarmSink, MqttAlarmRule};
+
+    fn build_rule(activate_threshold: f64, deactivate_threshold: f64) -> MqttAlarmRule {
+        MqttAlarmRule {
+            cluster_name: "test".to_string(),
+            name: "conn".to_string(),
+            metric: AlarmMetric::ConnectionNum,
+            activate_threshold,
+            deactivate_threshold,
+            sinks: Vec::new(),
+            enable: true,
+            create_time: 0,
+        }
+    }
+
+    #[test]
+    fn evaluate_alarm_test() {
+        let rule = build_rule(100.0, 80.0);
+
+        assert_eq!(evaluate_alarm(false, 90.0, &rule), None);
+        assert_eq!(evaluate_alarm(false, 100.0, &rule), Some(true));
+
+        // stays active between the two thresholds
+        assert_eq!(evaluate_alarm(true, 90.0, &rule), None);
+        assert_eq!(evaluate_alarm(true, 120.0, &rule), None);
+        assert_eq!(evaluate_alarm(true, 80.0, &rule), Some(false));
+    }
+
+    #[test]
+    fn validate_alarm_rule_test() {
+        assert!(validate_alarm_rule(&build_rule(100.0, 80.0)).is_ok());
+        assert!(validate_alarm_rule(&build_rule(80.0, 100.0)).is_err());
+
+        let mut rule = build_rule(100.0, 80.0);
+        rule.name = "HighCpuUsage".to_string();
+        assert!(validate_alarm_rule(&rule).is_err());
+
+        let mut rule = build_rule(100.0, 80.0);
+        rule.sinks.push(AlarmSink::Webhook {
+            url: "localhost:8080".to_string(),
+        });
+        assert!(validate_alarm_rule(&rule).is_err());
+
+        let mut rule = build_rule(100.0, 80.0);
+        rule.sinks.push(AlarmSink::Webhook {
+            url: "http://localhost:8080/alarm".to_string(),
+        });
+        assert!(validate_alarm_rule(&rule).is_ok());
+    }
+}
@@ -0,0 +1,149 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use broker_core::rocksdb::RocksDBEngine;
+use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::alarm::{AlarmSink, MqttAlarmHistory, MqttAlarmRule};
+use metadata_struct::mqtt::message::MqttMessage;
+use reqwest::Client;
+use storage_adapter::storage::ArcStorageAdapter;
+use tracing::warn;
+
+use crate::bridge::manager::ConnectorManager;
+use crate::handler::cache::MQTTCacheManager;
+use crate::handler::error::MqttBrokerError;
+use crate::handler::system_alarm::report_system_alarm;
+use crate::storage::alarm::AlarmStorage;
+use crate::system_topic::write_topic_data;
+
+const WEBHOOK_TIMEOUT_SEC: u64 = 5;
+
+pub struct AlarmNotifier {
+    client_pool: Arc<ClientPool>,
+    cache_manager: Arc<MQTTCacheManager>,
+    connector_manager: Arc<ConnectorManager>,
+    message_storage_adapter: ArcStorageAdapter,
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+    http_client: Client,
+}
+
+impl AlarmNotifier {
+    pub fn new(
+        client_pool: Arc<ClientPool>,
+        cache_manager: Arc<MQTTCacheManager>,
+        connector_manager: Arc<ConnectorManager>,
+        message_storage_adapter: ArcStorageAdapter,
+        rocksdb_engine_handler: Arc<RocksDBEngine>,
+    ) -> Self {
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(WEBHOOK_TIMEOUT_SEC))
+            .build()
+            .unwrap_or_default();
+        AlarmNotifier {
+            client_pool,
+            cache_manager,
+            connector_manager,
+            message_storage_adapter,
+            rocksdb_engine_handler,
+            http_client,
+        }
+    }
+
+    // Every state change goes to the $SYS alarm topics, the local event list
+    // and the history in meta-service, then to the sinks of the rule.
+    pub async fn notify(&self, rule: &MqttAlarmRule, history: &MqttAlarmHistory) {
+        if let Err(e) = report_system_alarm(
+            &self.client_pool,
+            &self.cache_manager,
+            &self.message_storage_adapter,
+            &self.rocksdb_engine_handler,
+            &history.rule_name,
+            history.message.clone(),
+            history.activated,
+        )
+        .await
+        {
+            warn!("Failed to report system alarm {}, error: {}", rule.name, e);
+        }
+
+        let alarm_storage = AlarmStorage::new(self.client_pool.clone());
+        if let Err(e) = alarm_storage.save_history(history).await {
+            warn!(
+                "Failed to save alarm history of {}, error: {}",
+                rule.name, e
+            );
+        }
+
+        for sink in rule.sinks.iter() {
+            if let Err(e) = self.send_to_sink(sink, history).await {
+                warn!(
+                    "Failed to send alarm {} to sink {:?}, error: {}",
+                    rule.name, sink, e
+                );
+            }
+        }
+    }
+
+    async fn send_to_sink(
+        &self,
+        sink: &AlarmSink,
+        history: &MqttAlarmHistory,
+    ) -> Result<(), MqttBrokerError> {
+        match sink {
+            AlarmSink::Webhook { url } => {
+                self.http_client
+                    .post(url)
+                    .json(history)
+                    .send()
+                    .await?
+                    .error_for_status()?;
+            }
+            AlarmSink::MqttTopic { topic_name } => {
+                self.publish(topic_name, history).await?;
+            }
+            AlarmSink::Connector { connector_name } => {
+                let Some(connector) = self.connector_manager.get_connector(connector_name) else {
+                    return Err(MqttBrokerError::CommonError(format!(
+                        "Connector {connector_name} does not exist"
+                    )));
+                };
+                self.publish(&connector.topic_name, history).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn publish(
+        &self,
+        topic_name: &str,
+        history: &MqttAlarmHistory,
+    ) -> Result<(), MqttBrokerError> {
+        let data = serde_json::to_string(history)?;
+        if let Some(record) = MqttMessage::build_system_topic_message(topic_name.to_string(), data)
+        {
+            write_topic_data(
+                &self.message_storage_adapter,
+                &self.cache_manager,
+                &self.client_pool,
+                topic_name.to_string(),
+                record,
+            )
+            .await;
+        }
+        Ok(())
+    }
+}
@@ -13,16 +13,27 @@
 // limitations under the License.
 
 use crate::common::types::ResultMqttBrokerError;
+use crate::handler::message::transcode_message_payload;
+use crate::storage::message::MessageStorage;
 use axum::async_trait;
 
-use common_base::{error::ResultCommonError, tools::loop_select_ticket};
+use common_base::telemetry::trace::{extract_context, is_tracing_enable, start_span};
+use common_base::{
+    error::{common::CommonError, ResultCommonError},
+    tools::loop_select_ticket,
+};
 use common_config::broker::broker_config;
+use metadata_struct::adapter::record::Record;
 use metadata_struct::mqtt::bridge::{
     config_local_file::LocalFileConnectorConfig, config_mongodb::MongoDBConnectorConfig,
     config_mysql::MySQLConnectorConfig, config_postgres::PostgresConnectorConfig,
     config_pulsar::PulsarConnectorConfig, config_rabbitmq::RabbitMQConnectorConfig,
     connector::MQTTConnector, connector_type::ConnectorType, status::MQTTStatus,
 };
+use metadata_struct::mqtt::message::MqttMessage;
+use opentelemetry::trace::SpanKind;
+use opentelemetry::{Context, KeyValue};
+use schema_register::schema::SchemaRegisterManager;
 use std::{sync::Arc, time::Duration};
 use storage_adapter::storage::ArcStorageAdapter;
 use tokio::{sync::broadcast, time::sleep};
@@ -38,6 +49,8 @@
 pub struct BridgePluginReadConfig {
     pub topic_name: String,
     pub record_num: u64,
+    pub payload_format: Option<String>,
+    pub schema_manager: Arc<SchemaRegisterManager>,
 }
 
 #[derive(Clone)]
@@ -51,13 +64,62 @@ pub trait BridgePlugin {
     async fn exec(&self, config: BridgePluginReadConfig) -> ResultMqttBrokerError;
 }
 
+// Read the next batch for a connector, transcoding payloads when the connector
+// asks for a different format than the one the topic was published with.
+pub async fn read_bridge_records(
+    message_storage: &MessageStorage,
+    config: &BridgePluginReadConfig,
+    offset: u64,
+) -> Result<Vec<Record>, CommonError> {
+    let records = message_storage
+        .read_topic_message(&config.topic_name, offset, config.record_num)
+        .await?;
+
+    let Some(format) = &config.payload_format else {
+        return Ok(records);
+    };
+
+    let mut results = Vec::with_capacity(records.len());
+    for mut record in records {
+        let mut msg = MqttMessage::decode_record(record.clone())?;
+        transcode_message_payload(&config.schema_manager, &config.topic_name, format, &mut msg);
+        record.set_data(msg.encode());
+        results.push(record);
+    }
+    Ok(results)
+}
+
+// Span of one batch written by a connector, it continues the trace of the
+// first message in the batch.
+pub fn start_sink_span(connector_name: &str, records: &[Record]) -> Context {
+    let parent = if is_tracing_enable() {
+        records
+            .first()
+            .and_then(|record| MqttMessage::decode_record(record.clone()).ok())
+            .map(|msg| extract_context(&msg.user_properties))
+            .unwrap_or_default()
+    } else {
+        Context::new()
+    };
+    start_span(
+        "connector.sink",
+        &parent,
+        SpanKind::Producer,
+        vec![
+            KeyValue::new("connector.name", connector_name.to_owned()),
+            KeyValue::new("connector.record_num", records.len() as i64),
+        ],
+    )
+}
+
 pub async fn start_connector_thread(
     message_storage: ArcStorageAdapter,
     connector_manager: Arc<ConnectorManager>,
+    schema_manager: Arc<SchemaRegisterManager>,
     stop_send: broadcast::Sender<bool>,
 ) {
     let ac_fn = async || -> ResultCommonError {
-        check_connector(&message_storage, &connector_manager).await;
+        check_connector(&message_storage, &connector_manager, &schema_manager).await;
         sleep(Duration::from_secs(1)).await;
         Ok(())
     };
@@ -69,6 +131,7 @@ pub async fn start_connector_thread(
 async fn check_connector(
     message_storage: &ArcStorageAdapter,
     connector_manager: &Arc<ConnectorManager>,
+    schema_manager: &Arc<SchemaRegisterManager>,
 ) {
     let config = broker_config();
 
@@ -100,6 +163,7 @@ async fn check_connector(
         start_thread(
             connector_manager.clone(),
             message_storage.clone(),
+            schema_manager.clone(),
             raw.clone(),
             thread,
         );
@@ -135,6 +199,7 @@ async fn check_connector(
 fn start_thread(
     connector_manager: Arc<ConnectorManager>,
     message_storage: ArcStorageAdapter,
+    schema_manager: Arc<SchemaRegisterManager>,
     connector: MQTTConnector,
     thread: BridgePluginThread,
 ) {
@@ -165,6 +230,8 @@ fn start_thread(
                     .exec(BridgePluginReadConfig {
                         topic_name: connector.topic_name,
                         record_num: 100,
+                        payload_format: connector.payload_format,
+                        schema_manager: schema_manager.clone(),
                     })
                     .await
                 {
@@ -202,6 +269,8 @@ fn start_thread(
                     .exec(BridgePluginReadConfig {
                         topic_name: connector.topic_name,
                         record_num: 100,
+                        payload_format: connector.payload_format,
+                        schema_manager: schema_manager.clone(),
                     })
                     .await
                 {
@@ -237,6 +306,8 @@ fn start_thread(
                     .exec(BridgePluginReadConfig {
                         topic_name: connector.topic_name,
                         record_num: 100,
+                        payload_format: connector.payload_format,
+                        schema_manager: schema_manager.clone(),
                     })
                     .await
                 {
@@ -272,6 +343,8 @@ fn start_thread(
                     .exec(BridgePluginReadConfig {
                         topic_name: connector.topic_name,
                         record_num: 100,
+                        payload_format: connector.payload_format,
+                        schema_manager: schema_manager.clone(),
                     })
                     .await
                 {
@@ -307,6 +380,8 @@ fn start_thread(
                     .exec(BridgePluginReadConfig {
                         topic_name: connector.topic_name,
                         record_num: 100,
+                        payload_format: connector.payload_format,
+                        schema_manager: schema_manager.clone(),
                     })
                     .await
                 {
@@ -342,6 +417,8 @@ fn start_thread(
                     .exec(BridgePluginReadConfig {
                         topic_name: connector.topic_name,
                         record_num: 100,
+                        payload_format: connector.payload_format,
+                        schema_manager: schema_manager.clone(),
                     })
                     .await
                 {
@@ -356,7 +433,7 @@ fn start_thread(
     });
 }
 
-fn stop_thread(thread: BridgePluginThread) -> ResultMqttBrokerError {
+pub fn stop_thread(thread: BridgePluginThread) -> ResultMqttBrokerError {
     thread.stop_send.send(true)?;
     Ok(())
 }
@@ -392,6 +469,7 @@ fn create_test_connector() -> MQTTConnector {
             status: MQTTStatus::Running,
             broker_id: Some(1),
             cluster_name: "test_cluster".to_string(),
+            payload_format: None,
             create_time: now_second(),
             update_time: now_second(),
         }
@@ -402,10 +480,13 @@ fn test_bridge_plugin_read_config_creation() {
         let config = BridgePluginReadConfig {
             topic_name: "test_topic".to_string(),
             record_num: 100,
+            payload_format: Some("json".to_string()),
+            schema_manager: Arc::new(SchemaRegisterManager::new()),
         };
 
         assert_eq!(config.topic_name, "test_topic");
         assert_eq!(config.record_num, 100);
+        assert_eq!(config.payload_format, Some("json".to_string()));
     }
 
     #[test]
@@ -426,7 +507,13 @@ async fn test_start_connector_thread() {
         let (stop_send, _) = broadcast::channel::<bool>(1);
 
         let start_handle = tokio::spawn(async move {
-            start_connector_thread(storage_adapter, connector_manager, stop_send).await;
+            start_connector_thread(
+                storage_adapter,
+                connector_manager,
+                Arc::new(SchemaRegisterManager::new()),
+                stop_send,
+            )
+            .await;
         });
 
         sleep(Duration::from_millis(100)).await;
@@ -454,7 +541,8 @@ async fn test_check_connector() {
             .await
             .unwrap();
 
-        check_connector(&storage_adapter, &connector_manager).await;
+        let schema_manager = Arc::new(SchemaRegisterManager::new());
+        check_connector(&storage_adapter, &connector_manager, &schema_manager).await;
 
         sleep(Duration::from_millis(100)).await;
 
@@ -14,11 +14,12 @@
 
 use std::{sync::Arc, time::Duration};
 
-use super::core::{BridgePlugin, BridgePluginReadConfig};
+use super::core::{read_bridge_records, start_sink_span, BridgePlugin, BridgePluginReadConfig};
 use super::manager::ConnectorManager;
 use crate::common::types::ResultMqttBrokerError;
 use crate::storage::message::MessageStorage;
 use axum::async_trait;
+use common_base::telemetry::trace::record_span_error;
 use metadata_struct::{
     adapter::record::Record, mqtt::bridge::config_local_file::LocalFileConnectorConfig,
 };
@@ -91,7 +92,7 @@ async fn exec(&self, config: BridgePluginReadConfig) -> ResultMqttBrokerError {
                     }
                 },
 
-                val = message_storage.read_topic_message(&config.topic_name, offset, config.record_num) => {
+                val = read_bridge_records(&message_storage, &config, offset) => {
                     match val {
                         Ok(data) => {
                             self.connector_manager.report_heartbeat(&self.connector_name);
@@ -100,7 +101,9 @@ async fn exec(&self, config: BridgePluginReadConfig) -> ResultMqttBrokerError {
                                 continue;
                             }
 
+                            let sink_cx = start_sink_span(&self.connector_name, &data);
                             if let Err(e) = self.append(&data,&mut writer).await{
+                                record_span_error(&sink_cx, e.to_string());
                                 error!("Connector {} failed to write data to {}, error message :{}", self.connector_name,self.config.local_file_path, e);
                                 sleep(Duration::from_millis(100)).await;
                             }
@@ -141,6 +144,7 @@ mod tests {
         file::FileBridgePlugin,
         manager::ConnectorManager,
     };
+    use schema_register::schema::SchemaRegisterManager;
     use tempfile::tempdir;
 
     #[ignore]
@@ -225,6 +229,8 @@ async fn file_bridge_plugin_test() {
         let read_config = BridgePluginReadConfig {
             topic_name: shard_name.clone(),
             record_num: 100,
+            payload_format: None,
+            schema_manager: Arc::new(SchemaRegisterManager::new()),
         };
 
         let record_config_clone = read_config.clone();
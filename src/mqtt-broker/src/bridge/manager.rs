@@ -113,6 +113,7 @@ fn create_test_connector() -> MQTTConnector {
             status: MQTTStatus::Running,
             broker_id: Some(1),
             cluster_name: "test_cluster".to_string(),
+            payload_format: None,
             create_time: now_second(),
             update_time: now_second(),
         }
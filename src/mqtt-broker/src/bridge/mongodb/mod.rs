@@ -16,6 +16,7 @@
 
 use axum::async_trait;
 use bson::Document;
+use common_base::telemetry::trace::record_span_error;
 use metadata_struct::{
     adapter::record::Record, mqtt::bridge::config_mongodb::MongoDBConnectorConfig,
 };
@@ -29,7 +30,7 @@
 use crate::storage::message::MessageStorage;
 
 use super::{
-    core::{BridgePlugin, BridgePluginReadConfig},
+    core::{read_bridge_records, start_sink_span, BridgePlugin, BridgePluginReadConfig},
     manager::ConnectorManager,
 };
 
@@ -171,7 +172,7 @@ async fn exec(&self, config: BridgePluginReadConfig) -> ResultMqttBrokerError {
                     }
                 }
 
-                val = message_storage.read_topic_message(&config.topic_name, offset, config.record_num) => {
+                val = read_bridge_records(&message_storage, &config, offset) => {
                     match val {
                         Ok(data) => {
                             self.connector_manager.report_heartbeat(&self.connector_name);
@@ -181,7 +182,9 @@ async fn exec(&self, config: BridgePluginReadConfig) -> ResultMqttBrokerError {
                                 continue;
                             }
 
+                            let sink_cx = start_sink_span(&self.connector_name, &data);
                             if let Err(e) = self.append(&data, &collection).await {
+                                record_span_error(&sink_cx, e.to_string());
                                 error!(
                                     "Connector '{}' failed to write data to MongoDB collection '{}', error: {}",
                                     self.connector_name,
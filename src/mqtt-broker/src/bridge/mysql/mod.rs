@@ -15,6 +15,7 @@
 use std::{sync::Arc, time::Duration};
 
 use axum::async_trait;
+use common_base::telemetry::trace::record_span_error;
 use metadata_struct::{adapter::record::Record, mqtt::bridge::config_mysql::MySQLConnectorConfig};
 use sqlx::{mysql::MySqlPoolOptions, MySql, Pool};
 use storage_adapter::storage::ArcStorageAdapter;
@@ -25,7 +26,7 @@
 use crate::storage::message::MessageStorage;
 
 use super::{
-    core::{BridgePlugin, BridgePluginReadConfig},
+    core::{read_bridge_records, start_sink_span, BridgePlugin, BridgePluginReadConfig},
     manager::ConnectorManager,
 };
 
@@ -193,7 +194,7 @@ async fn exec(&self, config: BridgePluginReadConfig) -> ResultMqttBrokerError {
                     }
                 }
 
-                val = message_storage.read_topic_message(&config.topic_name, offset, config.record_num) => {
+                val = read_bridge_records(&message_storage, &config, offset) => {
                     match val {
                         Ok(data) => {
                             self.connector_manager.report_heartbeat(&self.connector_name);
@@ -202,7 +203,9 @@ async fn exec(&self, config: BridgePluginReadConfig) -> ResultMqttBrokerError {
                                 continue;
                             }
 
+                            let sink_cx = start_sink_span(&self.connector_name, &data);
                             if let Err(e) = self.append(&data, &pool).await {
+                                record_span_error(&sink_cx, e.to_string());
                                 error!("Connector {} failed to write data to MySQL table {}, error message: {}", self.connector_name, self.config.table, e);
                                 sleep(Duration::from_millis(100)).await;
                             }
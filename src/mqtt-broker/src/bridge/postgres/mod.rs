@@ -15,6 +15,7 @@
 use std::{sync::Arc, time::Duration};
 
 use axum::async_trait;
+use common_base::telemetry::trace::record_span_error;
 use metadata_struct::{
     adapter::record::Record, mqtt::bridge::config_postgres::PostgresConnectorConfig,
 };
@@ -27,7 +28,7 @@
 use crate::storage::message::MessageStorage;
 
 use super::{
-    core::{BridgePlugin, BridgePluginReadConfig},
+    core::{read_bridge_records, start_sink_span, BridgePlugin, BridgePluginReadConfig},
     manager::ConnectorManager,
 };
 
@@ -242,7 +243,7 @@ async fn exec(&self, config: BridgePluginReadConfig) -> ResultMqttBrokerError {
                     }
                 }
 
-                val = message_storage.read_topic_message(&config.topic_name, offset, config.record_num) => {
+                val = read_bridge_records(&message_storage, &config, offset) => {
                     match val {
                         Ok(data) => {
                             self.connector_manager.report_heartbeat(&self.connector_name);
@@ -252,7 +253,9 @@ async fn exec(&self, config: BridgePluginReadConfig) -> ResultMqttBrokerError {
                                 continue;
                             }
 
+                            let sink_cx = start_sink_span(&self.connector_name, &data);
                             if let Err(e) = self.append(&data, &client).await {
+                                record_span_error(&sink_cx, e.to_string());
                                 error!(
                                     "Connector {} failed to write data to PostgreSQL table {}, error: {}",
                                     self.connector_name, self.config.table, e
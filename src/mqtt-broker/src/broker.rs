@@ -13,17 +13,22 @@
 // limitations under the License.
 
 #![allow(clippy::result_large_err)]
+use crate::alarm::AlarmManager;
 use crate::bridge::core::start_connector_thread;
 use crate::bridge::manager::ConnectorManager;
 use crate::common::metrics_cache::{metrics_gc_thread, metrics_record_thread, MetricsCacheManager};
 use crate::common::types::ResultMqttBrokerError;
 use crate::handler::cache::MQTTCacheManager;
+use crate::handler::consumer_lag::start_consumer_lag_thread;
+use crate::handler::delivery_log::start_delivery_log_thread;
 use crate::handler::dynamic_cache::load_metadata_cache;
 use crate::handler::flapping_detect::clean_flapping_detect;
 use crate::handler::keep_alive::ClientKeepAlive;
+use crate::handler::packet_trace::start_packet_trace_thread;
 use crate::handler::sub_parse_topic::start_parse_subscribe_by_new_topic_thread;
-use crate::handler::system_alarm::SystemAlarm;
+use crate::handler::topic_metrics::start_topic_metrics_thread;
 use crate::handler::topic_rewrite::start_convert_thread;
+use crate::handler::webhook::start_webhook_thread;
 use crate::security::auth::super_user::init_system_user;
 use crate::security::storage::sync::sync_auth_storage_info;
 use crate::security::AuthDriver;
@@ -157,6 +162,60 @@ fn start_daemon_thread(&self) {
             clean_flapping_detect(cache_manager, stop_send).await;
         });
 
+        // packet trace
+        let stop_send = self.inner_stop.clone();
+        let cache_manager = self.cache_manager.clone();
+        let message_storage_adapter = self.message_storage_adapter.clone();
+        tokio::spawn(async move {
+            start_packet_trace_thread(cache_manager, message_storage_adapter, stop_send).await;
+        });
+
+        // delivery log
+        let stop_send = self.inner_stop.clone();
+        let cache_manager = self.cache_manager.clone();
+        let rocksdb_engine_handler = self.rocksdb_engine_handler.clone();
+        tokio::spawn(async move {
+            start_delivery_log_thread(cache_manager, rocksdb_engine_handler, stop_send).await;
+        });
+
+        // topic metrics
+        let stop_send = self.inner_stop.clone();
+        let cache_manager = self.cache_manager.clone();
+        let subscribe_manager = self.subscribe_manager.clone();
+        let rocksdb_engine_handler = self.rocksdb_engine_handler.clone();
+        tokio::spawn(async move {
+            start_topic_metrics_thread(
+                cache_manager,
+                subscribe_manager,
+                rocksdb_engine_handler,
+                stop_send,
+            )
+            .await;
+        });
+
+        // webhook
+        let stop_send = self.inner_stop.clone();
+        let cache_manager = self.cache_manager.clone();
+        let client_pool = self.client_pool.clone();
+        tokio::spawn(async move {
+            start_webhook_thread(cache_manager, client_pool, stop_send).await;
+        });
+
+        // consumer lag
+        let stop_send = self.inner_stop.clone();
+        let subscribe_manager = self.subscribe_manager.clone();
+        let connector_manager = self.connector_manager.clone();
+        let message_storage_adapter = self.message_storage_adapter.clone();
+        tokio::spawn(async move {
+            start_consumer_lag_thread(
+                subscribe_manager,
+                connector_manager,
+                message_storage_adapter,
+                stop_send,
+            )
+            .await;
+        });
+
         // observability
         let raw_stop_send = self.inner_stop.clone();
         let system_topic = SystemTopic::new(
@@ -187,18 +246,18 @@ fn start_daemon_thread(&self) {
             metrics_gc_thread(metrics_cache_manager.clone(), raw_stop_send.clone());
         });
 
-        // system alarm
-        let system_alarm = SystemAlarm::new(
+        // alarm
+        let alarm_manager = AlarmManager::new(
             self.client_pool.clone(),
             self.cache_manager.clone(),
+            self.subscribe_manager.clone(),
+            self.connector_manager.clone(),
             self.message_storage_adapter.clone(),
             self.rocksdb_engine_handler.clone(),
             self.inner_stop.clone(),
         );
         tokio::spawn(async move {
-            if let Err(e) = system_alarm.start().await {
-                error!("system alarm error:{}", e);
-            }
+            alarm_manager.start().await;
         });
     }
 
@@ -214,9 +273,16 @@ fn start_server(&self) {
     fn start_connector_thread(&self) {
         let message_storage = self.message_storage_adapter.clone();
         let connector_manager = self.connector_manager.clone();
+        let schema_manager = self.schema_manager.clone();
         let stop_send = self.inner_stop.clone();
         tokio::spawn(async move {
-            start_connector_thread(message_storage, connector_manager, stop_send).await;
+            start_connector_thread(
+                message_storage,
+                connector_manager,
+                schema_manager,
+                stop_send,
+            )
+            .await;
         });
     }
 
@@ -244,6 +310,7 @@ fn start_subscribe_push(&self) {
             self.connection_manager.clone(),
             self.metrics_cache_manager.clone(),
             self.rocksdb_engine_handler.clone(),
+            self.schema_manager.clone(),
             stop_send,
         );
 
@@ -258,6 +325,7 @@ fn start_subscribe_push(&self) {
             self.connection_manager.clone(),
             self.cache_manager.clone(),
             self.rocksdb_engine_handler.clone(),
+            self.schema_manager.clone(),
             stop_send,
         );
 
@@ -25,7 +25,7 @@
 use protocol::mqtt::common::QoS;
 use tokio::time::sleep;
 
-use crate::handler::cache::{ClientPkidData, QosAckPacketInfo};
+use crate::handler::cache::{ClientPkidData, OutboundInflight, QosAckPacketInfo};
 
 #[derive(Clone)]
 pub struct PkidManager {
@@ -35,9 +35,19 @@ pub struct PkidManager {
     //(client_id_pkid, AckPacketInfo)
     pub qos_ack_packet: DashMap<String, QosAckPacketInfo>,
 
+    //(client_id, number of entries in qos_ack_packet)
+    client_inflight: DashMap<String, usize>,
+
     // (client_id_pkid, QosPkidData)
     pub client_pkid_data: DashMap<String, ClientPkidData>,
 
+    // (client_id_pkid, (client_id, OutboundInflight)), messages pushed by this broker
+    outbound_inflight: DashMap<String, (String, OutboundInflight)>,
+
+    // (client_id_group_id_offset, OutboundInflight), messages taken over from the
+    // previous owner of the session that must be sent again with the same packet id
+    outbound_resend: DashMap<String, OutboundInflight>,
+
     pub pkid_atomic: Arc<AtomicU64>,
 }
 
@@ -52,7 +62,10 @@ pub fn new() -> Self {
         PkidManager {
             pkid_cache: DashMap::with_capacity(8),
             qos_ack_packet: DashMap::with_capacity(8),
+            client_inflight: DashMap::with_capacity(8),
             client_pkid_data: DashMap::with_capacity(8),
+            outbound_inflight: DashMap::with_capacity(8),
+            outbound_resend: DashMap::with_capacity(8),
             pkid_atomic: Arc::new(AtomicU64::new(1)),
         }
     }
@@ -63,12 +76,23 @@ pub fn remove_by_client_id(&self, client_id: &str) {
                 self.qos_ack_packet.remove(&key);
             }
         }
+        self.client_inflight.remove(client_id);
 
         for (key, _) in self.client_pkid_data.clone() {
             if key.starts_with(client_id) {
                 self.qos_ack_packet.remove(&key);
             }
         }
+
+        self.outbound_inflight
+            .retain(|_, (inflight_client_id, _)| inflight_client_id != client_id);
+        let resend_prefix = format!("{client_id}_");
+        for (key, inflight) in self.outbound_resend.clone() {
+            if key.starts_with(&resend_prefix) {
+                self.outbound_resend.remove(&key);
+                self.pkid_cache.remove(&self.key(client_id, inflight.pkid));
+            }
+        }
     }
 
     // sub => pub push pkid generate
@@ -99,13 +123,24 @@ pub async fn generate_pkid(&self, client_id: &str, qos: &QoS) -> u16 {
     // ack packet
     pub fn remove_ack_packet(&self, client_id: &str, pkid: u16) {
         let key = self.key(client_id, pkid);
-        self.qos_ack_packet.remove(&key);
+        if self.qos_ack_packet.remove(&key).is_some() {
+            self.client_inflight.remove_if_mut(client_id, |_, num| {
+                *num = num.saturating_sub(1);
+                *num == 0
+            });
+        }
+        self.outbound_inflight.remove(&key);
         self.pkid_cache.remove(&key);
     }
 
     pub fn add_ack_packet(&self, client_id: &str, pkid: u16, packet: QosAckPacketInfo) {
         let key = self.key(client_id, pkid);
-        self.qos_ack_packet.insert(key, packet);
+        if self.qos_ack_packet.insert(key, packet).is_none() {
+            *self
+                .client_inflight
+                .entry(client_id.to_string())
+                .or_insert(0) += 1;
+        }
     }
 
     pub fn get_ack_packet(&self, client_id: &str, pkid: u16) -> Option<QosAckPacketInfo> {
@@ -116,6 +151,14 @@ pub fn get_ack_packet(&self, client_id: &str, pkid: u16) -> Option<QosAckPacketI
         None
     }
 
+    // Number of QoS 1/2 messages sent to the client and still waiting for an ack
+    pub fn inflight_num(&self, client_id: &str) -> usize {
+        self.client_inflight
+            .get(client_id)
+            .map(|num| *num)
+            .unwrap_or(0)
+    }
+
     // client pkid
     pub fn add_client_pkid(&self, client_id: &str, pkid: u16) {
         let key = self.key(client_id, pkid);
@@ -141,6 +184,75 @@ pub fn get_client_pkid(&self, client_id: &str, pkid: u16) -> Option<ClientPkidDa
         None
     }
 
+    // Remove and return the QoS 2 packet ids still waiting for PUBREL from the client
+    pub fn take_client_pkids(&self, client_id: &str) -> Vec<u16> {
+        let mut pkids = Vec::new();
+        for (key, data) in self.client_pkid_data.clone() {
+            if data.client_id != *client_id {
+                continue;
+            }
+            if let Some(pkid) = key
+                .strip_prefix(&format!("{client_id}_"))
+                .and_then(|raw| raw.parse::<u16>().ok())
+            {
+                pkids.push(pkid);
+            }
+            self.client_pkid_data.remove(&key);
+        }
+        pkids
+    }
+
+    // outbound inflight
+    pub fn add_outbound_inflight(&self, client_id: &str, inflight: OutboundInflight) {
+        let key = self.key(client_id, inflight.pkid);
+        self.outbound_inflight
+            .insert(key, (client_id.to_owned(), inflight));
+    }
+
+    pub fn mark_outbound_pubrel(&self, client_id: &str, pkid: u16) {
+        let key = self.key(client_id, pkid);
+        if let Some(mut data) = self.outbound_inflight.get_mut(&key) {
+            data.1.pubrel = true;
+        }
+    }
+
+    // Remove and return the messages pushed to the client and still waiting for an ack
+    pub fn take_outbound_inflight(&self, client_id: &str) -> Vec<OutboundInflight> {
+        let mut inflights = Vec::new();
+        for (key, (inflight_client_id, inflight)) in self.outbound_inflight.clone() {
+            if inflight_client_id != *client_id {
+                continue;
+            }
+            self.outbound_inflight.remove(&key);
+            inflights.push(inflight);
+        }
+        inflights
+    }
+
+    // Keep the packet id of a taken over message so that it is sent again with it
+    pub fn add_outbound_resend(&self, client_id: &str, inflight: OutboundInflight) {
+        self.pkid_cache
+            .insert(self.key(client_id, inflight.pkid), now_second());
+        let key = self.resend_key(client_id, &inflight.group_id, inflight.offset);
+        self.outbound_resend.insert(key, inflight);
+    }
+
+    pub fn take_outbound_resend(
+        &self,
+        client_id: &str,
+        group_id: &str,
+        offset: u64,
+    ) -> Option<OutboundInflight> {
+        let key = self.resend_key(client_id, group_id, offset);
+        self.outbound_resend
+            .remove(&key)
+            .map(|(_, inflight)| inflight)
+    }
+
+    fn resend_key(&self, client_id: &str, group_id: &str, offset: u64) -> String {
+        format!("{client_id}_{group_id}_{offset}")
+    }
+
     fn key(&self, client_id: &str, pkid: u16) -> String {
         format!("{client_id}_{pkid}")
     }
@@ -0,0 +1,223 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::storage::audit_log::AuditLogStorage;
+use common_base::tools::now_second;
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use storage_adapter::storage::ArcStorageAdapter;
+use strum_macros::{Display, EnumString};
+use tracing::warn;
+
+// At most AUTH_FAILED_AUDIT_LIMIT auth failures per source ip are audited in
+// every window, so a client retrying with a wrong password cannot flood the log.
+const AUTH_FAILED_AUDIT_WINDOW_SEC: u64 = 60;
+const AUTH_FAILED_AUDIT_LIMIT: u32 = 10;
+const AUTH_FAILED_AUDIT_MAX_SOURCES: usize = 10000;
+
+lazy_static! {
+    // source ip -> (window start, entries audited in the window)
+    static ref AUTH_FAILED_AUDIT_WINDOWS: DashMap<String, (u64, u32)> = DashMap::new();
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, EnumString, Display)]
+pub enum AuditAction {
+    // admin
+    CreateUser,
+    DeleteUser,
+    CreateAcl,
+    DeleteAcl,
+    CreateBlacklist,
+    DeleteBlacklist,
+    CreateConnector,
+    DeleteConnector,
+    CreateSchema,
+    UpdateSchema,
+    DeleteSchema,
+    SetClusterConfig,
+    AddMetaNode,
+    PromoteMetaNode,
+    RemoveMetaNode,
+    TransferMetaLeader,
+    ExportMetadata,
+    ImportMetadata,
+    DrainBroker,
+    RebalanceConnections,
+    SetLogLevel,
+    ResetLogLevel,
+    CreatePacketTrace,
+    StopPacketTrace,
+    DeletePacketTrace,
+    CreateTopicMetrics,
+    DeleteTopicMetrics,
+    CreateAlarmRule,
+    DeleteAlarmRule,
+    CreateWebhook,
+    DeleteWebhook,
+
+    // security
+    AuthFailed,
+    FlappingBan,
+}
+
+impl AuditAction {
+    pub fn is_security_event(&self) -> bool {
+        matches!(self, AuditAction::AuthFailed | AuditAction::FlappingBan)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AuditLogEntry {
+    pub actor: String,
+    pub source_ip: String,
+    pub action: AuditAction,
+    pub target: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub success: bool,
+    pub reason: Option<String>,
+    pub create_time: u64,
+}
+
+impl AuditLogEntry {
+    pub fn new(actor: &str, source_ip: &str, action: AuditAction, target: &str) -> Self {
+        AuditLogEntry {
+            actor: actor.to_string(),
+            source_ip: source_ip.to_string(),
+            action,
+            target: target.to_string(),
+            before: None,
+            after: None,
+            success: true,
+            reason: None,
+            create_time: now_second(),
+        }
+    }
+
+    pub fn with_before(mut self, before: Option<String>) -> Self {
+        self.before = before;
+        self
+    }
+
+    pub fn with_after(mut self, after: Option<String>) -> Self {
+        self.after = after;
+        self
+    }
+
+    pub fn with_result<T, E: ToString>(mut self, result: &Result<T, E>) -> Self {
+        if let Err(e) = result {
+            self.success = false;
+            self.reason = Some(e.to_string());
+        }
+        self
+    }
+}
+
+/// Append an entry to the audit log shard. Auditing must never break the
+/// operation being audited, so failures are only logged.
+pub async fn record_audit_log(message_storage_adapter: &ArcStorageAdapter, entry: AuditLogEntry) {
+    let storage = AuditLogStorage::new(message_storage_adapter.clone());
+    if let Err(e) = storage.append(&entry).await {
+        warn!(
+            "Failed to write audit log, action:{}, target:{}, error:{}",
+            entry.action, entry.target, e
+        );
+    }
+}
+
+/// Record a failed authentication without blocking the CONNECT being handled.
+/// Entries beyond the per source ip limit of the current window are dropped.
+pub fn record_auth_failed_audit_log(
+    message_storage_adapter: &ArcStorageAdapter,
+    entry: AuditLogEntry,
+) {
+    if !try_acquire_auth_failed_audit(&entry.source_ip, now_second()) {
+        return;
+    }
+
+    let message_storage_adapter = message_storage_adapter.clone();
+    tokio::spawn(async move {
+        record_audit_log(&message_storage_adapter, entry).await;
+    });
+}
+
+fn try_acquire_auth_failed_audit(source_ip: &str, now: u64) -> bool {
+    if AUTH_FAILED_AUDIT_WINDOWS.len() >= AUTH_FAILED_AUDIT_MAX_SOURCES {
+        AUTH_FAILED_AUDIT_WINDOWS
+            .retain(|_, (start, _)| now.saturating_sub(*start) < AUTH_FAILED_AUDIT_WINDOW_SEC);
+    }
+
+    let mut window = AUTH_FAILED_AUDIT_WINDOWS
+        .entry(source_ip.to_string())
+        .or_insert((now, 0));
+    if now.saturating_sub(window.0) >= AUTH_FAILED_AUDIT_WINDOW_SEC {
+        *window = (now, 0);
+    }
+    if window.1 >= AUTH_FAILED_AUDIT_LIMIT {
+        return false;
+    }
+    window.1 += 1;
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        try_acquire_auth_failed_audit, AuditAction, AuditLogEntry, AUTH_FAILED_AUDIT_LIMIT,
+        AUTH_FAILED_AUDIT_WINDOW_SEC,
+    };
+    use common_base::error::common::CommonError;
+    use std::str::FromStr;
+
+    #[test]
+    fn audit_action_str_test() {
+        assert_eq!(AuditAction::CreateUser.to_string(), "CreateUser");
+        assert_eq!(
+            AuditAction::from_str("FlappingBan").unwrap(),
+            AuditAction::FlappingBan
+        );
+        assert!(AuditAction::AuthFailed.is_security_event());
+        assert!(!AuditAction::DeleteAcl.is_security_event());
+    }
+
+    #[test]
+    fn audit_entry_result_test() {
+        let ok: Result<(), CommonError> = Ok(());
+        let entry = AuditLogEntry::new("admin", "127.0.0.1", AuditAction::CreateUser, "u1")
+            .with_result(&ok);
+        assert!(entry.success);
+        assert!(entry.reason.is_none());
+
+        let err: Result<(), CommonError> = Err(CommonError::CommonError("exist".to_string()));
+        let entry = AuditLogEntry::new("admin", "127.0.0.1", AuditAction::CreateUser, "u1")
+            .with_result(&err);
+        assert!(!entry.success);
+        assert_eq!(entry.reason, Some("exist".to_string()));
+    }
+
+    #[test]
+    fn auth_failed_audit_limit_test() {
+        let source_ip = "10.0.0.26";
+        for _ in 0..AUTH_FAILED_AUDIT_LIMIT {
+            assert!(try_acquire_auth_failed_audit(source_ip, 100));
+        }
+        assert!(!try_acquire_auth_failed_audit(source_ip, 100));
+        assert!(try_acquire_auth_failed_audit("10.0.0.27", 100));
+        assert!(try_acquire_auth_failed_audit(
+            source_ip,
+            100 + AUTH_FAILED_AUDIT_WINDOW_SEC
+        ));
+    }
+}
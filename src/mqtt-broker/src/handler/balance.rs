@@ -0,0 +1,353 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_base::tools::now_second;
+use common_config::broker::broker_config;
+use common_config::config::BrokerConfig;
+use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::connection::MQTTConnection;
+use metadata_struct::mqtt::node_extend::NodeExtend;
+use network_server::common::connection_manager::ConnectionManager;
+use network_server::common::packet::ResponsePackage;
+use protocol::mqtt::common::DisconnectReasonCode;
+use protocol::robust::{RobustMQPacket, RobustMQProtocol};
+use tokio::time::sleep;
+use tracing::{info, warn};
+
+use super::cache::MQTTCacheManager;
+use super::error::MqttBrokerError;
+use super::response::response_packet_mqtt_distinct_by_server_reference;
+use crate::storage::session::SessionStorage;
+use crate::subscribe::push::send_message_to_client;
+
+// Clients are moved away in batches so that the other brokers are not hit by a
+// reconnect storm.
+pub const MOVE_CONNECTION_BATCH_SIZE: usize = 100;
+pub const MOVE_CONNECTION_INTERVAL_MS: u64 = 1000;
+
+static REBALANCING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone)]
+pub struct RebalanceContext {
+    pub cache_manager: Arc<MQTTCacheManager>,
+    pub client_pool: Arc<ClientPool>,
+    pub connection_manager: Arc<ConnectionManager>,
+}
+
+// Pick another broker for a client to move to. Only brokers that are not draining and
+// hold fewer than `below` connections qualify, clients are spread by client id over the
+// less loaded half of them so that one stale heartbeat does not send everyone to the
+// same broker.
+pub fn select_server_reference(
+    cache_manager: &Arc<MQTTCacheManager>,
+    client_id: &str,
+    below: u64,
+) -> Option<String> {
+    let broker_cache = &cache_manager.broker_cache;
+    let mut candidates: Vec<(u64, String)> = broker_cache
+        .node_list()
+        .into_iter()
+        .filter(|node| node.node_id != broker_config().broker_id)
+        .filter_map(|node| {
+            let load = broker_cache.get_node_load(node.node_id).unwrap_or_default();
+            if load.draining || load.connection_num >= below {
+                return None;
+            }
+            let extend = serde_json::from_str::<NodeExtend>(&node.extend).ok()?;
+            Some((load.connection_num, extend.mqtt.mqtt_addr))
+        })
+        .collect();
+    if candidates.is_empty() {
+        return None;
+    }
+    candidates.sort();
+    candidates.truncate(candidates.len().div_ceil(2));
+
+    let hash = client_id.bytes().fold(0usize, |acc, b| {
+        acc.wrapping_mul(31).wrapping_add(b as usize)
+    });
+    Some(candidates[hash % candidates.len()].1.clone())
+}
+
+// The broker is overloaded when it reached max_connection_num, or when balancing is
+// enabled and it holds more than overload_ratio times the cluster average.
+pub fn is_overloaded(cache_manager: &Arc<MQTTCacheManager>, cluster: &BrokerConfig) -> bool {
+    let broker_cache = &cache_manager.broker_cache;
+    let local = broker_cache.get_connection_num();
+    if local >= cluster.mqtt_runtime.max_connection_num as u64 {
+        return true;
+    }
+
+    let balance = &cluster.mqtt_connection_balance;
+    if !balance.enable || local < balance.min_connection_num {
+        return false;
+    }
+
+    let loads: Vec<u64> = broker_cache
+        .node_loads
+        .iter()
+        .filter(|load| !load.draining)
+        .map(|load| load.connection_num)
+        .collect();
+    if loads.len() < 2 {
+        return false;
+    }
+    let average = loads.iter().sum::<u64>() as f64 / loads.len() as f64;
+    local as f64 > average * balance.overload_ratio
+}
+
+// Release the session of a client and tell it to connect to another broker.
+pub async fn move_connection(
+    cache_manager: &Arc<MQTTCacheManager>,
+    session_storage: &SessionStorage,
+    connection_manager: &Arc<ConnectionManager>,
+    connection: &MQTTConnection,
+    reason_code: DisconnectReasonCode,
+    server_reference: Option<String>,
+) {
+    // Release the session before the client is told to move, otherwise a fast
+    // reconnect elsewhere could be overwritten by this update.
+    cache_manager.update_session_connect_id(&connection.client_id, None);
+    if let Err(e) = session_storage
+        .update_session(connection.client_id.clone(), 0, 0, 0, now_second())
+        .await
+    {
+        warn!(
+            "Failed to release session of client {} before moving it, {}",
+            connection.client_id, e
+        );
+    }
+
+    if let Some(protocol) = connection_manager.get_connect_protocol(connection.connect_id) {
+        let packet = response_packet_mqtt_distinct_by_server_reference(
+            &protocol.to_mqtt(),
+            reason_code,
+            server_reference,
+        );
+        let resp = ResponsePackage::new(
+            connection.connect_id,
+            RobustMQPacket::MQTT(packet),
+            0,
+            0,
+            0,
+            "MoveConnection".to_string(),
+        );
+        if let Err(e) = send_message_to_client(resp, connection_manager).await {
+            warn!(
+                "Failed to send {:?} to client {}, {}",
+                reason_code, connection.client_id, e
+            );
+        }
+    }
+
+    cache_manager.remove_connection(connection.connect_id);
+    connection_manager
+        .close_connect(connection.connect_id)
+        .await;
+}
+
+// Move `percent` percent of the clients of this broker to less loaded brokers. Only
+// MQTT 5 clients are moved, older clients would not learn where to reconnect.
+pub fn start_rebalance(context: RebalanceContext, percent: u32) -> Result<usize, MqttBrokerError> {
+    if percent == 0 || percent > 100 {
+        return Err(MqttBrokerError::RebalancePercentInvalid(percent));
+    }
+    if context.cache_manager.broker_cache.is_draining() {
+        return Err(MqttBrokerError::BrokerIsDraining);
+    }
+
+    let local = context.cache_manager.get_connection_count();
+    if select_server_reference(&context.cache_manager, "", local as u64).is_none() {
+        return Err(MqttBrokerError::NoBrokerAvailableForRebalance);
+    }
+    if REBALANCING.swap(true, Ordering::SeqCst) {
+        return Err(MqttBrokerError::RebalanceInProgress);
+    }
+
+    let connections: Vec<MQTTConnection> = context
+        .cache_manager
+        .connection_info
+        .iter()
+        .filter(|raw| {
+            context
+                .connection_manager
+                .get_connect_protocol(raw.connect_id)
+                == Some(RobustMQProtocol::MQTT5)
+        })
+        .take(local * percent as usize / 100)
+        .map(|raw| raw.value().clone())
+        .collect();
+    let num = connections.len();
+    info!(
+        "Broker {} starts moving {} of {} clients to other brokers",
+        broker_config().broker_id,
+        num,
+        local
+    );
+
+    tokio::spawn(async move {
+        let session_storage = SessionStorage::new(context.client_pool.clone());
+        for batch in connections.chunks(MOVE_CONNECTION_BATCH_SIZE) {
+            if context.cache_manager.broker_cache.is_draining() {
+                break;
+            }
+            for connection in batch {
+                if context
+                    .cache_manager
+                    .get_connection(connection.connect_id)
+                    .is_none()
+                {
+                    continue;
+                }
+                let local = context.cache_manager.get_connection_count() as u64;
+                let server_reference =
+                    select_server_reference(&context.cache_manager, &connection.client_id, local);
+                if server_reference.is_none() {
+                    continue;
+                }
+                move_connection(
+                    &context.cache_manager,
+                    &session_storage,
+                    &context.connection_manager,
+                    connection,
+                    DisconnectReasonCode::UseAnotherServer,
+                    server_reference,
+                )
+                .await;
+            }
+            sleep(Duration::from_millis(MOVE_CONNECTION_INTERVAL_MS)).await;
+        }
+        REBALANCING.store(false, Ordering::SeqCst);
+        info!("Broker {} finished rebalancing", broker_config().broker_id);
+    });
+
+    Ok(num)
+}
+
+pub fn is_rebalancing() -> bool {
+    REBALANCING.load(Ordering::SeqCst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::tool::test_build_mqtt_cache_manager;
+    use broker_core::cache::NodeLoad;
+    use common_config::broker::{default_broker_config, init_broker_conf_by_config};
+    use metadata_struct::mqtt::node_extend::MqttNodeExtend;
+    use metadata_struct::placement::node::BrokerNode;
+
+    fn build_node(node_id: u64) -> BrokerNode {
+        let extend = NodeExtend {
+            mqtt: MqttNodeExtend {
+                mqtt_addr: format!("127.0.0.{node_id}:1883"),
+                ..Default::default()
+            },
+        };
+        BrokerNode {
+            node_id,
+            extend: extend.encode(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn select_server_reference_test() {
+        init_broker_conf_by_config(default_broker_config());
+        let cache_manager = test_build_mqtt_cache_manager().await;
+        let broker_cache = &cache_manager.broker_cache;
+        assert!(select_server_reference(&cache_manager, "c1", u64::MAX).is_none());
+
+        for node_id in [2, 3, 4] {
+            broker_cache.add_node(build_node(node_id));
+        }
+        broker_cache.update_node_loads(vec![
+            (
+                2,
+                NodeLoad {
+                    connection_num: 10,
+                    draining: false,
+                },
+            ),
+            (
+                3,
+                NodeLoad {
+                    connection_num: 500,
+                    draining: false,
+                },
+            ),
+            (
+                4,
+                NodeLoad {
+                    connection_num: 0,
+                    draining: true,
+                },
+            ),
+        ]);
+
+        // draining brokers are skipped and the less loaded half is preferred
+        for client_id in ["c1", "c2", "c3"] {
+            assert_eq!(
+                select_server_reference(&cache_manager, client_id, u64::MAX),
+                Some("127.0.0.2:1883".to_string())
+            );
+        }
+
+        // nothing qualifies when every broker holds more than the limit
+        assert!(select_server_reference(&cache_manager, "c1", 10).is_none());
+    }
+
+    #[tokio::test]
+    async fn is_overloaded_test() {
+        let cache_manager = test_build_mqtt_cache_manager().await;
+        let broker_cache = &cache_manager.broker_cache;
+        let mut cluster = broker_cache.get_cluster_config().await;
+        cluster.mqtt_connection_balance.enable = true;
+        cluster.mqtt_connection_balance.overload_ratio = 1.2;
+        cluster.mqtt_connection_balance.min_connection_num = 100;
+
+        broker_cache.update_node_loads(vec![
+            (
+                1,
+                NodeLoad {
+                    connection_num: 300,
+                    draining: false,
+                },
+            ),
+            (
+                2,
+                NodeLoad {
+                    connection_num: 100,
+                    draining: false,
+                },
+            ),
+        ]);
+
+        broker_cache.set_connection_num(50);
+        assert!(!is_overloaded(&cache_manager, &cluster));
+
+        broker_cache.set_connection_num(300);
+        assert!(is_overloaded(&cache_manager, &cluster));
+
+        cluster.mqtt_connection_balance.enable = false;
+        assert!(!is_overloaded(&cache_manager, &cluster));
+
+        cluster.mqtt_runtime.max_connection_num = 300;
+        assert!(is_overloaded(&cache_manager, &cluster));
+    }
+}
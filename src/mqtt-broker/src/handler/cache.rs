@@ -13,8 +13,13 @@
 // limitations under the License.
 
 use crate::common::pkid_manager::PkidManager;
+use crate::handler::delivery_log::DeliveryLogManager;
+use crate::handler::packet_trace::PacketTraceManager;
+use crate::handler::topic_metrics::TopicMetricsManager;
+use crate::handler::webhook::WebhookManager;
 use crate::security::auth::metadata::AclMetadata;
 use broker_core::cache::BrokerCacheManager;
+use common_base::lock_profile::timed;
 use dashmap::DashMap;
 use grpc_clients::pool::ClientPool;
 use metadata_struct::acl::mqtt_acl::MqttAcl;
@@ -85,6 +90,16 @@ pub struct ClientPkidData {
     pub create_time: u64,
 }
 
+// A QoS 1/2 message read from a topic, sent to the client and not acked yet
+#[derive(Clone, Debug, PartialEq)]
+pub struct OutboundInflight {
+    pub group_id: String,
+    pub offset: u64,
+    pub pkid: u16,
+    // the client sent PUBREC, only PUBREL is left to send
+    pub pubrel: bool,
+}
+
 #[derive(Clone)]
 pub struct MQTTCacheManager {
     // broker cache
@@ -126,6 +141,18 @@ pub struct MQTTCacheManager {
 
     // Topic is Validator
     pub topic_is_validator: DashMap<String, bool>,
+
+    // Packet traces started from the admin API
+    pub packet_trace: Arc<PacketTraceManager>,
+
+    // Delivery events of subscribed messages, enabled by mqtt_delivery_log
+    pub delivery_log: Arc<DeliveryLogManager>,
+
+    // Topics opted in for per-topic metrics
+    pub topic_metrics: Arc<TopicMetricsManager>,
+
+    // Webhooks and the events waiting to be delivered to them
+    pub webhook: Arc<WebhookManager>,
 }
 
 impl MQTTCacheManager {
@@ -145,6 +172,10 @@ pub fn new(client_pool: Arc<ClientPool>, broker_cache: Arc<BrokerCacheManager>)
             topic_is_validator: DashMap::with_capacity(8),
             re_calc_topic_rewrite: Arc::new(RwLock::new(false)),
             topic_rewrite_new_name: DashMap::with_capacity(8),
+            packet_trace: Arc::new(PacketTraceManager::new()),
+            delivery_log: Arc::new(DeliveryLogManager::new()),
+            topic_metrics: Arc::new(TopicMetricsManager::new()),
+            webhook: Arc::new(WebhookManager::new()),
         }
     }
 
@@ -162,7 +193,8 @@ pub fn get_session_info(&self, client_id: &str) -> Option<MqttSession> {
     }
 
     pub fn update_session_connect_id(&self, client_id: &str, connect_id: Option<u64>) {
-        if let Some(mut session) = self.session_info.get_mut(client_id) {
+        if let Some(session) = self.session_info.get_mut(client_id) {
+            let mut session = timed(session);
             session.update_connnction_id(connect_id);
             if connect_id.is_none() {
                 session.update_distinct_time()
@@ -192,14 +224,19 @@ pub fn retain_users(&self, usernames: HashSet<String>) {
 
     // connection
     pub fn add_connection(&self, connect_id: u64, conn: MQTTConnection) {
-        if let Some(mut session) = self.session_info.get_mut(&conn.client_id) {
+        if let Some(session) = self.session_info.get_mut(&conn.client_id) {
+            let mut session = timed(session);
             session.connection_id = Some(connect_id);
             self.connection_info.insert(connect_id, conn);
         }
+        self.broker_cache
+            .set_connection_num(self.connection_info.len() as u64);
     }
 
     pub fn remove_connection(&self, connect_id: u64) {
         self.connection_info.remove(&connect_id);
+        self.broker_cache
+            .set_connection_num(self.connection_info.len() as u64);
     }
 
     pub fn get_connect_id(&self, client_id: &str) -> Option<u64> {
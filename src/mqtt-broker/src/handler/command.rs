@@ -16,6 +16,7 @@
 use super::mqtt::{MqttService, MqttServiceConnectContext, MqttServiceContext};
 use crate::handler::cache::MQTTCacheManager;
 use crate::handler::connection::disconnect_connection;
+use crate::handler::packet_trace::{PacketDirection, TraceClient};
 use crate::handler::response::{
     response_packet_mqtt_connect_fail, response_packet_mqtt_distinct_by_reason,
 };
@@ -87,6 +88,18 @@ async fn apply(
     ) -> Option<ResponsePackage> {
         let start = now_mills();
         let packet = robust_packet.get_mqtt_packet().unwrap();
+        let trace_client = || {
+            TraceClient::build(
+                &self.cache_manager,
+                tcp_connection.connection_id,
+                &addr,
+                &packet,
+            )
+        };
+        self.cache_manager
+            .packet_trace
+            .record(PacketDirection::In, &packet, trace_client);
+
         let mut is_connect_pkg = false;
         if let MqttPacket::Connect(_, _, _, _, _, _) = packet {
             is_connect_pkg = true;
@@ -206,6 +219,16 @@ async fn apply(
             }
         }
 
+        if let Some(pkg) = &resp_package {
+            if let RobustMQPacket::MQTT(resp_packet) = &pkg.packet {
+                self.cache_manager.packet_trace.record(
+                    PacketDirection::Out,
+                    resp_packet,
+                    trace_client,
+                );
+            }
+        }
+
         record_mqtt_packet_process_duration(
             tcp_connection.connection_type,
             mqtt_packet_to_string(&packet),
@@ -0,0 +1,217 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use common_base::error::common::CommonError;
+use common_base::error::ResultCommonError;
+use common_base::tools::loop_select_ticket;
+use common_metrics::mqtt::lag::{record_consumer_lag, remove_consumer_lag};
+use dashmap::DashMap;
+use storage_adapter::storage::ArcStorageAdapter;
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+
+use crate::bridge::manager::ConnectorManager;
+use crate::storage::message::MessageStorage;
+use crate::subscribe::manager::SubscribeManager;
+use crate::subscribe::share::leader::build_share_group_id;
+
+pub const CONSUMER_TYPE_SHARE_GROUP: &str = "share_group";
+pub const CONSUMER_TYPE_CONNECTOR: &str = "connector";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConsumerLag {
+    pub end_offset: u64,
+    pub committed_offset: u64,
+    pub lag: u64,
+}
+
+impl ConsumerLag {
+    pub fn new(end_offset: u64, committed_offset: u64) -> Self {
+        ConsumerLag {
+            end_offset,
+            committed_offset,
+            lag: end_offset.saturating_sub(committed_offset),
+        }
+    }
+}
+
+// Committed group offsets are the next offset to read, so the lag is the
+// number of messages written after it.
+pub async fn get_consumer_lag(
+    message_storage: &MessageStorage,
+    group_id: &str,
+    topic_name: &str,
+) -> Result<ConsumerLag, CommonError> {
+    let end_offset = message_storage.get_topic_end_offset(topic_name).await?;
+    let committed_offset = message_storage.get_group_offset(group_id).await?;
+    Ok(ConsumerLag::new(end_offset, committed_offset))
+}
+
+#[derive(Clone, Debug)]
+struct LagConsumer {
+    consumer_type: &'static str,
+    group: String,
+    group_id: String,
+    topic_name: String,
+}
+
+impl LagConsumer {
+    fn key(&self) -> String {
+        format!("{}_{}_{}", self.consumer_type, self.group, self.topic_name)
+    }
+}
+
+// Share groups led by this node and connectors running on it
+fn local_consumers(
+    subscribe_manager: &Arc<SubscribeManager>,
+    connector_manager: &Arc<ConnectorManager>,
+) -> Vec<LagConsumer> {
+    let mut results = Vec::new();
+    for raw in subscribe_manager.share_leader_push.iter() {
+        results.push(LagConsumer {
+            consumer_type: CONSUMER_TYPE_SHARE_GROUP,
+            group: raw.path.clone(),
+            group_id: build_share_group_id(&raw.group_name, &raw.sub_name, &raw.topic_name),
+            topic_name: raw.topic_name.clone(),
+        });
+    }
+
+    for thread in connector_manager.get_all_connector_thread() {
+        if let Some(connector) = connector_manager.get_connector(&thread.connector_name) {
+            results.push(LagConsumer {
+                consumer_type: CONSUMER_TYPE_CONNECTOR,
+                group: connector.connector_name.clone(),
+                group_id: connector.connector_name.clone(),
+                topic_name: connector.topic_name.clone(),
+            });
+        }
+    }
+    results
+}
+
+// The share group or connector on this broker that is furthest behind
+pub async fn max_consumer_lag(
+    subscribe_manager: &Arc<SubscribeManager>,
+    connector_manager: &Arc<ConnectorManager>,
+    message_storage: &MessageStorage,
+) -> Option<(String, u64)> {
+    let mut result: Option<(String, u64)> = None;
+    for consumer in local_consumers(subscribe_manager, connector_manager) {
+        let Ok(lag) =
+            get_consumer_lag(message_storage, &consumer.group_id, &consumer.topic_name).await
+        else {
+            continue;
+        };
+        if result.as_ref().is_none_or(|(_, max)| lag.lag > *max) {
+            let source = format!(
+                "{} {} of topic {}",
+                consumer.consumer_type, consumer.group, consumer.topic_name
+            );
+            result = Some((source, lag.lag));
+        }
+    }
+    result
+}
+
+pub async fn start_consumer_lag_thread(
+    subscribe_manager: Arc<SubscribeManager>,
+    connector_manager: Arc<ConnectorManager>,
+    message_storage_adapter: ArcStorageAdapter,
+    stop_send: broadcast::Sender<bool>,
+) {
+    let message_storage = MessageStorage::new(message_storage_adapter);
+
+    // (consumer key, consumer) for every exported series
+    let exported: DashMap<String, LagConsumer> = DashMap::with_capacity(8);
+    // The storage engine cannot report the end offset, e.g. the journal engine
+    let unsupported_warned = AtomicBool::new(false);
+
+    let ac_fn = async || -> ResultCommonError {
+        let mut current = HashSet::new();
+
+        for consumer in local_consumers(&subscribe_manager, &connector_manager) {
+            let lag =
+                match get_consumer_lag(&message_storage, &consumer.group_id, &consumer.topic_name)
+                    .await
+                {
+                    Ok(lag) => lag,
+                    Err(e @ CommonError::NotSupportFeature(..)) => {
+                        if !unsupported_warned.swap(true, Ordering::Relaxed) {
+                            warn!(
+                                "Consumer lag is not available with this storage engine, error: {}",
+                                e
+                            );
+                        }
+                        continue;
+                    }
+                    Err(e) => {
+                        debug!(
+                            "Failed to get consumer lag, group: {}, topic: {}, error: {}",
+                            consumer.group, consumer.topic_name, e
+                        );
+                        continue;
+                    }
+                };
+
+            record_consumer_lag(
+                consumer.consumer_type,
+                &consumer.group,
+                &consumer.topic_name,
+                lag.lag as i64,
+            );
+
+            current.insert(consumer.key());
+            exported.insert(consumer.key(), consumer);
+        }
+
+        // Stop exporting consumers that are gone from this node
+        exported.retain(|key, consumer| {
+            if current.contains(key) {
+                return true;
+            }
+            remove_consumer_lag(
+                consumer.consumer_type,
+                &consumer.group,
+                &consumer.topic_name,
+            );
+            false
+        });
+        Ok(())
+    };
+
+    info!("Consumer lag thread start successfully");
+    loop_select_ticket(ac_fn, 10, &stop_send).await;
+}
+
+#[cfg(test)]
+mod test {
+    use super::ConsumerLag;
+
+    #[test]
+    fn consumer_lag_test() {
+        let lag = ConsumerLag::new(100, 40);
+        assert_eq!(lag.lag, 60);
+
+        let lag = ConsumerLag::new(100, 100);
+        assert_eq!(lag.lag, 0);
+
+        // a group committed on a shard that was recreated
+        let lag = ConsumerLag::new(10, 40);
+        assert_eq!(lag.lag, 0);
+    }
+}
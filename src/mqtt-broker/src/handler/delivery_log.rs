@@ -0,0 +1,335 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use broker_core::rocksdb::RocksDBEngine;
+use common_base::tools::now_mills;
+use common_config::broker::broker_config;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+use tokio::select;
+use tokio::sync::broadcast;
+use tokio::sync::mpsc::{self, error::TrySendError, Receiver, Sender};
+use tokio::time::interval;
+use tracing::{info, warn};
+
+use super::cache::MQTTCacheManager;
+use super::error::MqttBrokerError;
+use crate::storage::local::LocalStorage;
+use crate::subscribe::common::Subscriber;
+
+const DELIVERY_LOG_CHANNEL_SIZE: usize = 10000;
+const DELIVERY_LOG_CLEAN_INTERVAL_SECS: u64 = 60;
+const DELIVERY_LOG_CLEAN_BATCH_SIZE: usize = 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, EnumString, Display)]
+pub enum DeliveryEventType {
+    // The message was read from storage for the subscriber
+    Enqueue,
+    // The PUBLISH packet was written to the client connection
+    Push,
+    // The client acknowledged a QoS 1/2 message
+    Ack,
+    // The message will not be delivered to the subscriber, see reason
+    Drop,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DeliveryEvent {
+    pub topic_name: String,
+    pub offset: u64,
+    pub client_id: String,
+    pub sub_path: String,
+    pub event_type: DeliveryEventType,
+    pub pkid: u16,
+    pub reason: Option<String>,
+    pub create_time: u128,
+}
+
+impl DeliveryEvent {
+    pub fn build(
+        event_type: DeliveryEventType,
+        subscriber: &Subscriber,
+        offset: u64,
+        pkid: u16,
+        reason: Option<String>,
+    ) -> Self {
+        DeliveryEvent {
+            topic_name: subscriber.topic_name.clone(),
+            offset,
+            client_id: subscriber.client_id.clone(),
+            sub_path: subscriber.sub_path.clone(),
+            event_type,
+            pkid,
+            reason,
+            create_time: now_mills(),
+        }
+    }
+}
+
+pub struct DeliveryLogManager {
+    enable: AtomicBool,
+    event_sx: Sender<DeliveryEvent>,
+    event_rx: Mutex<Option<Receiver<DeliveryEvent>>>,
+}
+
+impl Default for DeliveryLogManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DeliveryLogManager {
+    pub fn new() -> Self {
+        let (event_sx, event_rx) = mpsc::channel(DELIVERY_LOG_CHANNEL_SIZE);
+        DeliveryLogManager {
+            enable: AtomicBool::new(false),
+            event_sx,
+            event_rx: Mutex::new(Some(event_rx)),
+        }
+    }
+
+    pub fn set_enable(&self, enable: bool) {
+        self.enable.store(enable, Ordering::Relaxed);
+    }
+
+    pub fn is_enable(&self) -> bool {
+        self.enable.load(Ordering::Relaxed)
+    }
+
+    // Hand the event receiver to the writer thread, only the first caller gets it
+    pub fn take_event_receiver(&self) -> Option<Receiver<DeliveryEvent>> {
+        self.event_rx.lock().ok()?.take()
+    }
+
+    // Called on the push path, so the event is only built when the log is enabled.
+    // Events are dropped rather than slowing down delivery when the writer lags.
+    pub fn record<F>(&self, event: F)
+    where
+        F: FnOnce() -> DeliveryEvent,
+    {
+        if !self.is_enable() {
+            return;
+        }
+        if let Err(TrySendError::Closed(_)) = self.event_sx.try_send(event()) {
+            self.set_enable(false);
+        }
+    }
+}
+
+// Number of leading events to remove so that none is older than retention_sec and at most
+// max_events of the total_num stored events are kept. The events must be the oldest stored
+// events, sorted by create_time.
+pub fn expired_delivery_event_num(
+    events: &[DeliveryEvent],
+    total_num: u64,
+    now: u128,
+    retention_sec: u64,
+    max_events: u64,
+) -> usize {
+    let deadline = now.saturating_sub(retention_sec as u128 * 1000);
+    let expired_num = events
+        .iter()
+        .take_while(|event| event.create_time < deadline)
+        .count();
+    let over_num = total_num
+        .saturating_sub(max_events)
+        .min(events.len() as u64) as usize;
+    expired_num.max(over_num)
+}
+
+// Walk the time index from the oldest event and stop at the first one that is kept,
+// so a clean only reads the events it removes plus one batch. total_num is the number
+// of stored events and is updated with the removed ones.
+async fn clean_delivery_event(
+    local_storage: &LocalStorage,
+    total_num: &mut u64,
+    retention_sec: u64,
+    max_events: u64,
+) -> Result<(), MqttBrokerError> {
+    let now = now_mills();
+    loop {
+        let events = local_storage
+            .list_oldest_delivery_event(DELIVERY_LOG_CLEAN_BATCH_SIZE)
+            .await?;
+        // a batch shorter than asked for holds every stored event
+        if events.len() < DELIVERY_LOG_CLEAN_BATCH_SIZE {
+            *total_num = events.len() as u64;
+        }
+        let remove_num =
+            expired_delivery_event_num(&events, *total_num, now, retention_sec, max_events);
+        for event in &events[..remove_num] {
+            local_storage.delete_delivery_event(event).await?;
+            *total_num = total_num.saturating_sub(1);
+        }
+        if remove_num == 0 || remove_num < events.len() {
+            return Ok(());
+        }
+    }
+}
+
+// Persist delivery events and enforce the configured retention
+pub async fn start_delivery_log_thread(
+    cache_manager: Arc<MQTTCacheManager>,
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+    stop_send: broadcast::Sender<bool>,
+) {
+    let config = broker_config().mqtt_delivery_log.clone();
+    if !config.enable {
+        return;
+    }
+    let Some(mut event_rx) = cache_manager.delivery_log.take_event_receiver() else {
+        return;
+    };
+    cache_manager.delivery_log.set_enable(true);
+    info!(
+        "Delivery log enabled, retention {}s, at most {} events",
+        config.retention_sec, config.max_events
+    );
+
+    let local_storage = LocalStorage::new(rocksdb_engine_handler);
+    // counted once at startup, then kept up to date by the saves and cleans below
+    let mut total_num = match local_storage.count_delivery_event().await {
+        Ok(num) => num,
+        Err(e) => {
+            warn!("Failed to count delivery events, error: {}", e);
+            0
+        }
+    };
+    let mut clean_ticker = interval(Duration::from_secs(DELIVERY_LOG_CLEAN_INTERVAL_SECS));
+    let mut stop_rx = stop_send.subscribe();
+    loop {
+        select! {
+            val = stop_rx.recv() => {
+                if let Ok(flag) = val {
+                    if flag {
+                        break;
+                    }
+                }
+            }
+            val = event_rx.recv() => {
+                let Some(event) = val else {
+                    break;
+                };
+                match local_storage.save_delivery_event(&event).await {
+                    Ok(_) => total_num += 1,
+                    Err(e) => warn!("Failed to save delivery event, client_id: {}, error: {}", event.client_id, e),
+                }
+            }
+            _ = clean_ticker.tick() => {
+                if let Err(e) = clean_delivery_event(&local_storage, &mut total_num, config.retention_sec, config.max_events).await {
+                    warn!("Failed to clean delivery events, error: {}", e);
+                }
+            }
+        }
+    }
+    cache_manager.delivery_log.set_enable(false);
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        clean_delivery_event, expired_delivery_event_num, DeliveryEvent, DeliveryEventType,
+        DeliveryLogManager,
+    };
+    use crate::storage::local::LocalStorage;
+    use crate::subscribe::common::Subscriber;
+    use broker_core::rocksdb::{column_family_list, RocksDBEngine};
+    use common_base::tools::now_mills;
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    fn build_event(create_time: u128) -> DeliveryEvent {
+        let subscriber = Subscriber {
+            client_id: "c1".to_string(),
+            sub_path: "t/#".to_string(),
+            topic_name: "t/1".to_string(),
+            ..Default::default()
+        };
+        let mut event = DeliveryEvent::build(DeliveryEventType::Push, &subscriber, 7, 1, None);
+        event.create_time = create_time;
+        event
+    }
+
+    #[test]
+    fn expired_delivery_event_num_test() {
+        let events: Vec<DeliveryEvent> = (1..=5).map(|i| build_event(i * 1000)).collect();
+
+        // older than 2s at 4s
+        assert_eq!(expired_delivery_event_num(&events, 5, 4000, 2, 100), 1);
+
+        // only 2 events may be kept
+        assert_eq!(expired_delivery_event_num(&events, 5, 4000, 2, 2), 3);
+
+        // the batch is the head of 20 stored events, at most 10 may be kept
+        assert_eq!(expired_delivery_event_num(&events, 20, 4000, 2, 10), 5);
+        assert_eq!(expired_delivery_event_num(&events, 12, 4000, 2, 10), 2);
+
+        assert_eq!(expired_delivery_event_num(&events, 5, 5000, 3600, 100), 0);
+        assert_eq!(expired_delivery_event_num(&[], 0, 5000, 0, 0), 0);
+    }
+
+    #[tokio::test]
+    async fn clean_delivery_event_test() {
+        let engine = RocksDBEngine::new(
+            tempdir().unwrap().path().to_str().unwrap(),
+            100,
+            column_family_list(),
+        );
+        let local_storage = LocalStorage::new(Arc::new(engine));
+        let now = now_mills();
+        for i in 0..5 {
+            local_storage
+                .save_delivery_event(&build_event(now + i))
+                .await
+                .unwrap();
+        }
+
+        // the counter follows the removed events
+        let mut total_num = 5;
+        clean_delivery_event(&local_storage, &mut total_num, 3600, 3)
+            .await
+            .unwrap();
+        assert_eq!(total_num, 3);
+        assert_eq!(local_storage.count_delivery_event().await.unwrap(), 3);
+
+        // a counter that drifted is corrected once every event fits in one batch
+        let mut total_num = 100;
+        clean_delivery_event(&local_storage, &mut total_num, 3600, 2)
+            .await
+            .unwrap();
+        assert_eq!(total_num, 2);
+        assert_eq!(local_storage.count_delivery_event().await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn delivery_log_manager_test() {
+        let manager = DeliveryLogManager::new();
+        let mut event_rx = manager.take_event_receiver().unwrap();
+        assert!(manager.take_event_receiver().is_none());
+
+        manager.record(|| panic!("the event must not be built while disabled"));
+        assert!(event_rx.try_recv().is_err());
+
+        manager.set_enable(true);
+        manager.record(|| build_event(1));
+        let event = event_rx.recv().await.unwrap();
+        assert_eq!(event.event_type, DeliveryEventType::Push);
+        assert_eq!(event.topic_name, "t/1");
+        assert_eq!(event.offset, 7);
+    }
+}
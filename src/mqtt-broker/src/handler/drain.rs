@@ -0,0 +1,152 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use broker_core::cluster::ClusterStorage;
+use common_config::broker::broker_config;
+use grpc_clients::pool::ClientPool;
+use network_server::common::connection_manager::ConnectionManager;
+use protocol::mqtt::common::DisconnectReasonCode;
+use tokio::time::sleep;
+use tracing::{error, info, warn};
+
+use super::balance::{
+    move_connection, select_server_reference, MOVE_CONNECTION_BATCH_SIZE,
+    MOVE_CONNECTION_INTERVAL_MS,
+};
+use super::cache::MQTTCacheManager;
+use crate::bridge::core::stop_thread;
+use crate::bridge::manager::ConnectorManager;
+use crate::storage::session::SessionStorage;
+use crate::subscribe::manager::SubscribeManager;
+
+// Connectors are moved by the meta service, the ones still running here once the
+// timeout is reached are stopped so that the drain always finishes.
+pub const DRAIN_TIMEOUT_SEC: u64 = 300;
+
+#[derive(Clone)]
+pub struct DrainContext {
+    pub cache_manager: Arc<MQTTCacheManager>,
+    pub client_pool: Arc<ClientPool>,
+    pub connection_manager: Arc<ConnectionManager>,
+    pub subscribe_manager: Arc<SubscribeManager>,
+    pub connector_manager: Arc<ConnectorManager>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DrainStatus {
+    pub draining: bool,
+    pub connection_num: usize,
+    pub connector_num: usize,
+}
+
+pub fn drain_status(context: &DrainContext) -> DrainStatus {
+    DrainStatus {
+        draining: context.cache_manager.broker_cache.is_draining(),
+        connection_num: context.cache_manager.get_connection_count(),
+        connector_num: context.connector_manager.get_all_connector_thread().len(),
+    }
+}
+
+// Take the broker out of service. New connections are refused with a server
+// reference, the meta service moves connectors and shared subscription leaders to
+// other brokers, connected clients are told to move, and the broker stops once it
+// holds nothing anymore.
+pub fn start_drain(context: DrainContext) {
+    let broker_cache = &context.cache_manager.broker_cache;
+    if broker_cache.is_draining() {
+        return;
+    }
+    broker_cache.set_draining(true);
+    info!("Broker {} starts draining", broker_config().broker_id);
+
+    tokio::spawn(async move {
+        // Tell the meta service right away instead of waiting for the next heartbeat
+        let cluster_storage = ClusterStorage::new(context.client_pool.clone());
+        let broker_cache = &context.cache_manager.broker_cache;
+        match cluster_storage
+            .heartbeat(true, broker_cache.get_connection_num())
+            .await
+        {
+            Ok(loads) => broker_cache.update_node_loads(loads),
+            Err(e) => warn!("Failed to report draining state to meta service, {}", e),
+        }
+
+        context.subscribe_manager.stop_share_leader_push();
+
+        let deadline = Instant::now() + Duration::from_secs(DRAIN_TIMEOUT_SEC);
+        loop {
+            let status = drain_status(&context);
+            if status.connection_num == 0 && status.connector_num == 0 {
+                break;
+            }
+            if Instant::now() >= deadline {
+                warn!(
+                    "Broker {} is not drained after {}s, {} connections and {} connectors are left, stopping the connectors",
+                    broker_config().broker_id,
+                    DRAIN_TIMEOUT_SEC,
+                    status.connection_num,
+                    status.connector_num
+                );
+                stop_connectors(&context);
+                break;
+            }
+            disconnect_batch(&context).await;
+            sleep(Duration::from_millis(MOVE_CONNECTION_INTERVAL_MS)).await;
+        }
+
+        info!(
+            "Broker {} is drained and will stop",
+            broker_config().broker_id
+        );
+        context.cache_manager.broker_cache.notify_drained();
+    });
+}
+
+fn stop_connectors(context: &DrainContext) {
+    for thread in context.connector_manager.get_all_connector_thread() {
+        let connector_name = thread.connector_name.clone();
+        if let Err(e) = stop_thread(thread) {
+            error!(
+                "Stopping connector {} Thread failed with error message: {}",
+                connector_name, e
+            );
+        }
+    }
+}
+
+async fn disconnect_batch(context: &DrainContext) {
+    let session_storage = SessionStorage::new(context.client_pool.clone());
+    let connections: Vec<_> = context
+        .cache_manager
+        .connection_info
+        .iter()
+        .take(MOVE_CONNECTION_BATCH_SIZE)
+        .map(|raw| raw.value().clone())
+        .collect();
+
+    for connection in connections {
+        move_connection(
+            &context.cache_manager,
+            &session_storage,
+            &context.connection_manager,
+            &connection,
+            DisconnectReasonCode::ServerMoved,
+            select_server_reference(&context.cache_manager, &connection.client_id, u64::MAX),
+        )
+        .await;
+    }
+}
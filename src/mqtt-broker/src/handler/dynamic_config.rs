@@ -19,7 +19,7 @@
 use common_config::broker::broker_config;
 use common_config::config::{
     BrokerConfig, MqttFlappingDetect, MqttOfflineMessage, MqttProtocolConfig, MqttSchema,
-    MqttSecurity, MqttSlowSubscribeConfig, MqttSystemMonitor,
+    MqttSecurity, MqttSharedSubscription, MqttSlowSubscribeConfig, MqttSystemMonitor,
 };
 use grpc_clients::pool::ClientPool;
 use std::sync::Arc;
@@ -35,6 +35,7 @@ pub enum ClusterDynamicConfig {
     MqttSecurity,
     MqttSystemMonitor,
     MqttSchema,
+    MqttSharedSubscription,
 }
 
 impl MQTTCacheManager {
@@ -122,6 +123,19 @@ pub async fn update_security_config(&self, security: MqttSecurity) {
     pub async fn get_security_config(&self) -> MqttSecurity {
         self.broker_cache.get_cluster_config().await.mqtt_security
     }
+
+    // shared subscription
+    pub async fn update_shared_subscription_config(&self, shared: MqttSharedSubscription) {
+        let mut config = self.broker_cache.cluster_config.write().await;
+        config.mqtt_shared_subscription = shared;
+    }
+
+    pub async fn get_shared_subscription_config(&self) -> MqttSharedSubscription {
+        self.broker_cache
+            .get_cluster_config()
+            .await
+            .mqtt_shared_subscription
+    }
 }
 
 pub async fn build_cluster_config(
@@ -156,6 +170,10 @@ pub async fn build_cluster_config(
         conf.mqtt_system_monitor = data;
     }
 
+    if let Some(data) = get_shared_subscription(client_pool).await? {
+        conf.mqtt_shared_subscription = data;
+    }
+
     Ok(conf)
 }
 
@@ -204,6 +222,12 @@ pub async fn update_cluster_dynamic_config(
             let security_config = serde_json::from_slice(&config)?;
             cache_manager.update_security_config(security_config).await;
         }
+        ClusterDynamicConfig::MqttSharedSubscription => {
+            let shared_config = serde_json::from_slice(&config)?;
+            cache_manager
+                .update_shared_subscription_config(shared_config)
+                .await;
+        }
     }
     Ok(())
 }
@@ -347,3 +371,24 @@ async fn get_system_monitor(
 
     Ok(None)
 }
+
+async fn get_shared_subscription(
+    client_pool: &Arc<ClientPool>,
+) -> Result<Option<MqttSharedSubscription>, MqttBrokerError> {
+    let conf = broker_config();
+    let cluster_storage = ClusterStorage::new(client_pool.clone());
+    let data = cluster_storage
+        .get_dynamic_config(
+            &conf.cluster_name,
+            &ClusterDynamicConfig::MqttSharedSubscription.to_string(),
+        )
+        .await?;
+
+    if !data.is_empty() {
+        return Ok(Some(serde_json::from_slice::<MqttSharedSubscription>(
+            &data,
+        )?));
+    }
+
+    Ok(None)
+}
@@ -221,6 +221,24 @@ pub enum MqttBrokerError {
     #[error("Connection {0} is null, skip push message")]
     ConnectionNullSkipPushMessage(String),
 
+    #[error("Push thread was stopped during {0}, the message was not delivered")]
+    PushThreadStopped(String),
+
+    #[error("Rebalance percent must be between 1 and 100, current: {0}")]
+    RebalancePercentInvalid(u32),
+
+    #[error("A rebalance is already running on this broker")]
+    RebalanceInProgress,
+
+    #[error("Broker is draining, connections can not be rebalanced")]
+    BrokerIsDraining,
+
+    #[error("Failed to take over the session of client {0} from broker {1}, error: {2}")]
+    SessionTakeoverFailed(String, u64, String),
+
+    #[error("No other broker is less loaded than this one, nothing to rebalance")]
+    NoBrokerAvailableForRebalance,
+
     #[error("kafka error: {0}")]
     KafkaError(#[from] KafkaError),
 
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use crate::common::types::ResultMqttBrokerError;
+use crate::handler::audit_log::{record_audit_log, AuditAction, AuditLogEntry};
 use crate::handler::cache::MQTTCacheManager;
 use crate::storage::local::LocalStorage;
 use broker_core::rocksdb::RocksDBEngine;
@@ -25,6 +26,7 @@
 use metadata_struct::acl::mqtt_blacklist::MqttAclBlackList;
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use storage_adapter::storage::ArcStorageAdapter;
 use tokio::sync::broadcast;
 use tracing::debug;
 
@@ -64,6 +66,7 @@ pub async fn check_flapping_detect(
     client_id: String,
     cache_manager: &Arc<MQTTCacheManager>,
     rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    message_storage_adapter: &ArcStorageAdapter,
 ) -> ResultMqttBrokerError {
     // get metric
     let current_counter = event::get_client_connection_counter(client_id.clone());
@@ -105,8 +108,14 @@ pub async fn check_flapping_detect(
         config.max_client_connections,
     ) {
         debug!("add a new client_id: {client_id} into blacklist.");
-        add_blacklist_4_connection_jitter(cache_manager, rocksdb_engine_handler, config, client_id)
-            .await?;
+        add_blacklist_4_connection_jitter(
+            cache_manager,
+            rocksdb_engine_handler,
+            message_storage_adapter,
+            config,
+            client_id,
+        )
+        .await?;
     }
 
     cache_manager
@@ -118,6 +127,7 @@ pub async fn check_flapping_detect(
 async fn add_blacklist_4_connection_jitter(
     cache_manager: &Arc<MQTTCacheManager>,
     rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    message_storage_adapter: &ArcStorageAdapter,
     config: MqttFlappingDetect,
     client_id: String,
 ) -> ResultMqttBrokerError {
@@ -140,6 +150,10 @@ async fn add_blacklist_4_connection_jitter(
         create_time: now_second(),
     };
     local_storage.save_ban_log(log).await?;
+
+    let entry = AuditLogEntry::new("flapping_detect", "", AuditAction::FlappingBan, &client_id)
+        .with_after(Some(format!("banned until {end_time}")));
+    record_audit_log(message_storage_adapter, entry).await;
     Ok(())
 }
 
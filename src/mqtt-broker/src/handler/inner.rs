@@ -17,15 +17,19 @@
 use crate::handler::dynamic_cache::update_cache_metadata;
 use crate::handler::error::MqttBrokerError;
 use crate::handler::last_will::send_last_will_message;
+use crate::handler::session_takeover::release_session;
+use crate::handler::webhook::SessionExpiredEventData;
 use crate::subscribe::manager::SubscribeManager;
 use broker_core::tool::wait_cluster_running;
 use common_config::broker::broker_config;
 use common_metrics::mqtt::session::record_mqtt_session_deleted;
 use grpc_clients::pool::ClientPool;
 use metadata_struct::mqtt::lastwill::LastWillData;
+use metadata_struct::mqtt::webhook::WebhookEventType;
+use network_server::common::connection_manager::ConnectionManager;
 use protocol::broker::broker_mqtt_inner::{
     DeleteSessionReply, DeleteSessionRequest, SendLastWillMessageReply, SendLastWillMessageRequest,
-    UpdateMqttCacheReply, UpdateMqttCacheRequest,
+    TakeoverSessionReply, TakeoverSessionRequest, UpdateMqttCacheReply, UpdateMqttCacheRequest,
 };
 use schema_register::schema::SchemaRegisterManager;
 use std::sync::Arc;
@@ -75,6 +79,16 @@ pub async fn delete_session_by_req(
     }
 
     for client_id in req.client_id.iter() {
+        // every broker receives the request, only the one holding the session reports it
+        if cache_manager.get_session_info(client_id).is_some() {
+            cache_manager
+                .webhook
+                .report(WebhookEventType::SessionExpired, None, || {
+                    SessionExpiredEventData {
+                        client_id: client_id.clone(),
+                    }
+                });
+        }
         subscribe_manager.remove_client_id(client_id);
         cache_manager.remove_session(client_id);
     }
@@ -111,3 +125,34 @@ pub async fn send_last_will_message_by_req(
     .await?;
     Ok(SendLastWillMessageReply::default())
 }
+
+pub async fn takeover_session_by_req(
+    cache_manager: &Arc<MQTTCacheManager>,
+    connection_manager: &Arc<ConnectionManager>,
+    subscribe_manager: &Arc<SubscribeManager>,
+    req: &TakeoverSessionRequest,
+) -> Result<TakeoverSessionReply, MqttBrokerError> {
+    debug!(
+        "Received session takeover request, client id: {}, new broker id: {}",
+        req.client_id, req.broker_id
+    );
+    wait_cluster_running(&cache_manager.broker_cache).await;
+
+    if cache_manager.broker_cache.cluster_name != req.cluster_name {
+        return Err(MqttBrokerError::ClusterNotMatch(req.cluster_name.clone()));
+    }
+
+    if req.client_id.is_empty() {
+        return Err(MqttBrokerError::ClientIDIsEmpty);
+    }
+
+    let released = release_session(
+        cache_manager,
+        connection_manager,
+        subscribe_manager,
+        &req.client_id,
+        req.broker_id,
+    )
+    .await;
+    Ok(released.to_reply())
+}
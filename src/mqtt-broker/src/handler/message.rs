@@ -14,9 +14,12 @@
 
 use std::sync::Arc;
 
+use bytes::Bytes;
 use common_base::tools::now_second;
 use metadata_struct::mqtt::message::MqttMessage;
 use protocol::mqtt::common::PublishProperties;
+use schema_register::{schema::SchemaRegisterManager, transcode::TRANSCODE_TARGET_JSON};
+use tracing::warn;
 
 use super::cache::MQTTCacheManager;
 
@@ -40,6 +43,32 @@ pub async fn build_message_expire(
     now_second() + cluster.mqtt_protocol_config.max_message_expiry_interval
 }
 
+// Re-encode the payload into the format requested by a subscriber or connector. On failure the
+// original payload is delivered so a bad schema does not block the subscription.
+pub fn transcode_message_payload(
+    schema_manager: &Arc<SchemaRegisterManager>,
+    topic_name: &str,
+    format: &str,
+    msg: &mut MqttMessage,
+) {
+    match schema_manager.transcode(topic_name, &msg.payload, format) {
+        Ok(Some(payload)) => {
+            if format.eq_ignore_ascii_case(TRANSCODE_TARGET_JSON) {
+                msg.format_indicator = Some(1);
+                msg.content_type = Some("application/json".to_string());
+            }
+            msg.payload = Bytes::from(payload);
+        }
+        Ok(None) => {}
+        Err(e) => {
+            warn!(
+                "Failed to transcode payload to {}, topic_name: {}, error: {}",
+                format, topic_name, e
+            );
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::common::tool::test_build_mqtt_cache_manager;
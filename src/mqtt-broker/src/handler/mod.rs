@@ -12,12 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod audit_log;
+pub mod balance;
 pub mod cache;
 pub mod command;
 pub mod connection;
 pub mod constant;
+pub mod consumer_lag;
 pub mod content_type;
 pub mod delay_message;
+pub mod delivery_log;
+pub mod drain;
 pub mod dynamic_cache;
 pub mod dynamic_config;
 pub mod error;
@@ -29,9 +34,11 @@
 pub mod message;
 pub mod mqtt;
 pub mod offline_message;
+pub mod packet_trace;
 pub mod response;
 pub mod retain;
 pub mod session;
+pub mod session_takeover;
 pub mod slow_subscribe;
 pub mod sub_auto;
 pub mod sub_exclusive;
@@ -40,6 +47,8 @@
 pub mod subscribe;
 pub mod system_alarm;
 pub mod topic;
+pub mod topic_metrics;
 pub mod topic_rewrite;
 pub mod unsubscribe;
 pub mod validator;
+pub mod webhook;
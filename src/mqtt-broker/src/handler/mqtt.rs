@@ -15,17 +15,24 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
 
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
 use broker_core::rocksdb::RocksDBEngine;
+use common_base::telemetry::trace::{
+    extract_context, inject_context, is_tracing_enable, record_span_error, start_span, SpanGuard,
+};
 use common_base::tools::{now_mills, now_second};
 use common_metrics::mqtt::auth::{record_mqtt_auth_failed, record_mqtt_auth_success};
 use common_metrics::mqtt::publish::{
     record_mqtt_message_bytes_received, record_mqtt_messages_delayed_inc,
     record_mqtt_messages_received_inc,
 };
-use common_metrics::mqtt::topic::{record_topic_bytes_written, record_topic_messages_written};
 use delay_message::DelayMessageManager;
 use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::webhook::WebhookEventType;
 use network_server::common::connection_manager::ConnectionManager;
+use opentelemetry::trace::{SpanKind, TraceContextExt};
+use opentelemetry::KeyValue;
 use protocol::mqtt::common::{
     qos, Connect, ConnectProperties, ConnectReturnCode, Disconnect, DisconnectProperties,
     DisconnectReasonCode, LastWill, LastWillProperties, Login, MqttPacket, MqttProtocol, PingReq,
@@ -47,6 +54,8 @@
 use super::subscribe::{save_subscribe, SaveSubscribeContext};
 use super::unsubscribe::remove_subscribe;
 use crate::common::pkid_storage::{pkid_delete, pkid_exists, pkid_save};
+use crate::handler::audit_log::{record_auth_failed_audit_log, AuditAction, AuditLogEntry};
+use crate::handler::balance::{is_overloaded, select_server_reference};
 use crate::handler::cache::{
     ConnectionLiveTime, MQTTCacheManager, QosAckPackageData, QosAckPackageType,
 };
@@ -55,16 +64,21 @@
 use crate::handler::last_will::save_last_will_message;
 use crate::handler::response::{
     build_puback, build_pubrec, response_packet_mqtt_connect_fail,
-    response_packet_mqtt_connect_success, response_packet_mqtt_distinct_by_reason,
-    response_packet_mqtt_ping_resp, response_packet_mqtt_pubcomp_fail,
-    response_packet_mqtt_pubcomp_success, response_packet_mqtt_suback,
-    response_packet_mqtt_unsuback, ResponsePacketMqttConnectSuccessContext,
+    response_packet_mqtt_connect_redirect, response_packet_mqtt_connect_success,
+    response_packet_mqtt_distinct_by_reason, response_packet_mqtt_ping_resp,
+    response_packet_mqtt_pubcomp_fail, response_packet_mqtt_pubcomp_success,
+    response_packet_mqtt_suback, response_packet_mqtt_unsuback,
+    ResponsePacketMqttConnectSuccessContext,
 };
 use crate::handler::session::{build_session, save_session, BuildSessionContext};
+use crate::handler::session_takeover::{
+    restore_subscribes, takeover_session, SessionTakeoverContext,
+};
 use crate::handler::topic::{get_topic_name, try_init_topic};
 use crate::handler::validator::{
     connect_validator, publish_validator, subscribe_validator, un_subscribe_validator,
 };
+use crate::handler::webhook::MessagePublishEventData;
 use crate::security::AuthDriver;
 use crate::subscribe::common::min_qos;
 use crate::subscribe::manager::SubscribeManager;
@@ -130,8 +144,48 @@ pub fn new(context: MqttServiceContext) -> Self {
     }
 
     pub async fn connect(&self, context: MqttServiceConnectContext) -> MqttPacket {
+        let trace_parent = context
+            .connect_properties
+            .as_ref()
+            .map(|properties| extract_context(&properties.user_properties))
+            .unwrap_or_default();
+        let trace_cx = SpanGuard::new(start_span(
+            "mqtt.connect",
+            &trace_parent,
+            SpanKind::Server,
+            vec![KeyValue::new(
+                "mqtt.client_id",
+                context.connect.client_id.clone(),
+            )],
+        ));
+
+        // a draining broker accepts no new clients and points them to another broker
+        if self.cache_manager.broker_cache.is_draining() {
+            return response_packet_mqtt_connect_redirect(
+                &self.protocol,
+                ConnectReturnCode::ServerMoved,
+                select_server_reference(&self.cache_manager, &context.connect.client_id, u64::MAX),
+            );
+        }
+
         let cluster = self.cache_manager.broker_cache.get_cluster_config().await;
 
+        // an overloaded broker sends MQTT 5 clients to a less loaded one
+        if self.protocol.is_mqtt5() && is_overloaded(&self.cache_manager, &cluster) {
+            let server_reference = select_server_reference(
+                &self.cache_manager,
+                &context.connect.client_id,
+                self.cache_manager.broker_cache.get_connection_num(),
+            );
+            if server_reference.is_some() {
+                return response_packet_mqtt_connect_redirect(
+                    &self.protocol,
+                    ConnectReturnCode::UseAnotherServer,
+                    server_reference,
+                );
+            }
+        }
+
         // connect params validator
         if let Some(res) = connect_validator(
             &self.protocol,
@@ -167,7 +221,13 @@ pub async fn connect(&self, context: MqttServiceConnectContext) -> MqttPacket {
         }
 
         // login check
-        match self
+        let auth_cx = SpanGuard::new(start_span(
+            "mqtt.auth",
+            &trace_cx,
+            SpanKind::Internal,
+            Vec::new(),
+        ));
+        let login_result = self
             .auth_driver
             .auth_login_check(
                 &context.login,
@@ -175,11 +235,15 @@ pub async fn connect(&self, context: MqttServiceConnectContext) -> MqttPacket {
                 &context.addr,
                 Some(&context.connect.client_id),
             )
-            .await
-        {
+            .await;
+        drop(auth_cx);
+
+        match login_result {
             Ok(flag) => {
                 if !flag {
                     record_mqtt_auth_failed();
+                    record_span_error(&trace_cx, "not authorized".to_string());
+                    self.record_auth_failed(&context, "not authorized".to_string());
                     return response_packet_mqtt_connect_fail(
                         &self.protocol,
                         ConnectReturnCode::NotAuthorized,
@@ -190,6 +254,8 @@ pub async fn connect(&self, context: MqttServiceConnectContext) -> MqttPacket {
                 record_mqtt_auth_success();
             }
             Err(e) => {
+                record_span_error(&trace_cx, e.to_string());
+                self.record_auth_failed(&context, e.to_string());
                 return response_packet_mqtt_connect_fail(
                     &self.protocol,
                     ConnectReturnCode::UnspecifiedError,
@@ -205,6 +271,7 @@ pub async fn connect(&self, context: MqttServiceConnectContext) -> MqttPacket {
                 context.connect.client_id.clone(),
                 &self.cache_manager,
                 &self.rocksdb_engine_handler,
+                &self.message_storage_adapter,
             )
             .await
             {
@@ -217,7 +284,7 @@ pub async fn connect(&self, context: MqttServiceConnectContext) -> MqttPacket {
             }
         }
 
-        let (session, new_session) = match build_session(BuildSessionContext {
+        let (session, new_session, previous_broker_id) = match build_session(BuildSessionContext {
             connect_id: context.connect_id,
             client_id: client_id.clone(),
             connect: context.connect.clone(),
@@ -240,6 +307,30 @@ pub async fn connect(&self, context: MqttServiceConnectContext) -> MqttPacket {
             }
         };
 
+        // kick the previous connection and stop pushing to it, wherever it lives
+        let takeover_subscribes = match takeover_session(&SessionTakeoverContext {
+            client_id: client_id.clone(),
+            previous_broker_id,
+            new_session,
+            cache_manager: self.cache_manager.clone(),
+            client_pool: self.client_pool.clone(),
+            connection_manager: self.connection_manager.clone(),
+            subscribe_manager: self.subscribe_manager.clone(),
+        })
+        .await
+        {
+            Ok(data) => data,
+            Err(e) => {
+                // the previous owner may still serve the client, it has to retry later
+                return response_packet_mqtt_connect_fail(
+                    &self.protocol,
+                    ConnectReturnCode::ServerUnavailable,
+                    &context.connect_properties,
+                    Some(e.to_string()),
+                );
+            }
+        };
+
         if let Err(e) = save_session(
             context.connect_id,
             session.clone(),
@@ -302,6 +393,28 @@ pub async fn connect(&self, context: MqttServiceConnectContext) -> MqttPacket {
         self.cache_manager.add_session(&client_id, &session);
         self.cache_manager
             .add_connection(context.connect_id, connection.clone());
+
+        if !takeover_subscribes.is_empty() {
+            let cache_manager = self.cache_manager.clone();
+            let client_pool = self.client_pool.clone();
+            let subscribe_manager = self.subscribe_manager.clone();
+            tokio::spawn(async move {
+                if let Err(e) = restore_subscribes(
+                    &cache_manager,
+                    &client_pool,
+                    &subscribe_manager,
+                    takeover_subscribes,
+                )
+                .await
+                {
+                    warn!(
+                        "Failed to restore subscribes after session takeover, error: {}",
+                        e
+                    );
+                }
+            });
+        }
+
         st_report_connected_event(StReportConnectedEventContext {
             message_storage_adapter: self.message_storage_adapter.clone(),
             metadata_cache: self.cache_manager.clone(),
@@ -324,6 +437,23 @@ pub async fn connect(&self, context: MqttServiceConnectContext) -> MqttPacket {
         })
     }
 
+    fn record_auth_failed(&self, context: &MqttServiceConnectContext, reason: String) {
+        let username = context
+            .login
+            .as_ref()
+            .map(|login| login.username.clone())
+            .unwrap_or_default();
+        let mut entry = AuditLogEntry::new(
+            &username,
+            &context.addr.ip().to_string(),
+            AuditAction::AuthFailed,
+            &context.connect.client_id,
+        );
+        entry.success = false;
+        entry.reason = Some(reason);
+        record_auth_failed_audit_log(&self.message_storage_adapter, entry);
+    }
+
     pub async fn publish(
         &self,
         connect_id: u64,
@@ -339,6 +469,20 @@ pub async fn publish(
             ));
         };
 
+        let trace_parent = publish_properties
+            .as_ref()
+            .map(|properties| extract_context(&properties.user_properties))
+            .unwrap_or_default();
+        let trace_cx = SpanGuard::new(start_span(
+            "mqtt.publish",
+            &trace_parent,
+            SpanKind::Server,
+            vec![KeyValue::new(
+                "mqtt.client_id",
+                connection.client_id.clone(),
+            )],
+        ));
+
         if let Some(pkg) = publish_validator(
             &self.protocol,
             &self.cache_manager,
@@ -453,6 +597,7 @@ pub async fn publish(
 
         if self.schema_manager.is_check_schema(&topic_name) {
             if let Err(e) = self.schema_manager.validate(&topic_name, &publish.payload) {
+                self.cache_manager.topic_metrics.record_dropped(&topic_name);
                 return Some(build_pub_ack_fail(
                     &self.protocol,
                     &connection,
@@ -465,14 +610,31 @@ pub async fn publish(
 
         let client_id = connection.client_id.clone();
 
+        // carry the publish span in the stored message so that subscribers,
+        // possibly on other brokers, continue the same trace
+        let mut trace_publish_properties = publish_properties.clone();
+        if is_tracing_enable() {
+            trace_cx
+                .span()
+                .set_attribute(KeyValue::new("mqtt.topic", topic_name.clone()));
+            let properties = trace_publish_properties.get_or_insert_with(Default::default);
+            inject_context(&trace_cx, &mut properties.user_properties);
+        }
+
         // Persisting stores message data
+        let storage_cx = SpanGuard::new(start_span(
+            "storage.write",
+            &trace_cx,
+            SpanKind::Client,
+            vec![KeyValue::new("mqtt.topic", topic_name.clone())],
+        ));
         let offset = match save_message(SaveMessageContext {
             message_storage_adapter: self.message_storage_adapter.clone(),
             delay_message_manager: self.delay_message_manager.clone(),
             cache_manager: self.cache_manager.clone(),
             client_pool: self.client_pool.clone(),
             publish: publish.clone(),
-            publish_properties: publish_properties.clone(),
+            publish_properties: trace_publish_properties,
             subscribe_manager: self.subscribe_manager.clone(),
             client_id: client_id.clone(),
             topic: topic.clone(),
@@ -484,20 +646,38 @@ pub async fn publish(
                 format!("{da:?}")
             }
             Err(e) => {
+                record_span_error(&storage_cx, e.to_string());
                 return Some(build_pub_ack_fail(
                     &self.protocol,
                     &connection,
                     publish.p_kid,
                     Some(e.to_string()),
                     is_pub_ack,
-                ))
+                ));
             }
         };
 
+        drop(storage_cx);
+
         record_mqtt_messages_received_inc();
         record_mqtt_message_bytes_received(publish.payload.len() as u64);
-        record_topic_messages_written(&topic_name);
-        record_topic_bytes_written(&topic_name, publish.payload.len() as u64);
+        self.cache_manager.topic_metrics.record_written(
+            &topic_name,
+            publish.payload.len() as u64,
+            publish.retain,
+        );
+        self.cache_manager.webhook.report(
+            WebhookEventType::MessagePublish,
+            Some(&topic_name),
+            || MessagePublishEventData {
+                client_id: client_id.clone(),
+                username: connection.login_user.clone(),
+                topic: topic_name.clone(),
+                qos: publish.qos.into(),
+                retain: publish.retain,
+                payload: BASE64_STANDARD.encode(&publish.payload),
+            },
+        );
 
         let user_properties: Vec<(String, String)> = vec![("offset".to_string(), offset)];
 
@@ -770,6 +950,7 @@ pub async fn subscribe(
             client_pool: self.client_pool.clone(),
             cache_manager: self.cache_manager.clone(),
             connection_manager: self.connection_manager.clone(),
+            schema_manager: self.schema_manager.clone(),
             is_new_subs: new_subs,
         })
         .await;
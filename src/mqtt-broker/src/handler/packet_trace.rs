@@ -0,0 +1,644 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use common_base::tools::{now_mills, now_second, try_create_fold};
+use common_config::broker::broker_config;
+use dashmap::DashMap;
+use metadata_struct::mqtt::connection::MQTTConnection;
+use protocol::mqtt::common::{mqtt_packet_to_string, MqttPacket};
+use serde::{Deserialize, Serialize};
+use storage_adapter::storage::ArcStorageAdapter;
+use strum_macros::{Display, EnumString};
+use tokio::fs::{File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::select;
+use tokio::sync::broadcast;
+use tokio::sync::mpsc::{self, error::TrySendError, Receiver, Sender};
+use tracing::warn;
+
+use super::cache::MQTTCacheManager;
+use super::error::MqttBrokerError;
+use crate::security::auth::common::ip_match;
+use crate::storage::packet_trace::PacketTraceStorage;
+use crate::subscribe::common::is_match_sub_and_topic;
+
+const PACKET_TRACE_CHANNEL_SIZE: usize = 10000;
+const PACKET_TRACE_MAX_NUM: usize = 30;
+const PACKET_TRACE_MAX_DURATION_SECS: u64 = 7 * 24 * 3600;
+const PACKET_TRACE_MAX_READ_NUM: u64 = 100000;
+const MASKED_PASSWORD: &str = "******";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, EnumString, Display)]
+pub enum PacketTraceType {
+    ClientId,
+    Username,
+    Topic,
+    Ip,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, EnumString, Display)]
+pub enum PacketTraceOutput {
+    File,
+    Journal,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, EnumString, Display)]
+pub enum PacketTracePayloadEncode {
+    Text,
+    Hex,
+    Hidden,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize, Display)]
+pub enum PacketDirection {
+    In,
+    Out,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PacketTrace {
+    pub name: String,
+    pub trace_type: PacketTraceType,
+    // client id, username, topic filter or ip/cidr depending on trace_type
+    pub target: String,
+    pub output: PacketTraceOutput,
+    pub payload_encode: PacketTracePayloadEncode,
+    // Publish payloads longer than this are truncated, 0 keeps the whole payload
+    pub payload_limit: usize,
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+impl PacketTrace {
+    pub fn validate(&self) -> Result<(), MqttBrokerError> {
+        if self.name.is_empty()
+            || !self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(MqttBrokerError::CommonError(format!(
+                "Trace name {} may only contain letters, digits, '-' and '_'",
+                self.name
+            )));
+        }
+        if self.target.is_empty() {
+            return Err(MqttBrokerError::CommonError(
+                "Trace target cannot be empty".to_string(),
+            ));
+        }
+        if self.end_time <= self.start_time
+            || self.end_time - self.start_time > PACKET_TRACE_MAX_DURATION_SECS
+        {
+            return Err(MqttBrokerError::CommonError(format!(
+                "Trace duration must be between 1 and {PACKET_TRACE_MAX_DURATION_SECS} seconds"
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn is_running(&self) -> bool {
+        let now = now_second();
+        self.start_time <= now && now < self.end_time
+    }
+
+    pub fn file_path(&self) -> String {
+        format!("{}/{}.log", packet_trace_dir(), self.name)
+    }
+
+    fn is_match(&self, client: &TraceClient, packet: &MqttPacket) -> bool {
+        match self.trace_type {
+            PacketTraceType::ClientId => client.client_id == self.target,
+            PacketTraceType::Username => client.username == self.target,
+            PacketTraceType::Ip => ip_match(&client.source_ip, &self.target),
+            PacketTraceType::Topic => packet_topics(packet).iter().any(|topic| {
+                *topic == self.target || is_match_sub_and_topic(&self.target, topic).is_ok()
+            }),
+        }
+    }
+
+    fn encode_payload(&self, payload: &[u8]) -> Option<String> {
+        let payload = if self.payload_limit > 0 && payload.len() > self.payload_limit {
+            &payload[..self.payload_limit]
+        } else {
+            payload
+        };
+        match self.payload_encode {
+            PacketTracePayloadEncode::Text => Some(String::from_utf8_lossy(payload).to_string()),
+            PacketTracePayloadEncode::Hex => Some(hex::encode(payload)),
+            PacketTracePayloadEncode::Hidden => None,
+        }
+    }
+}
+
+// The client side of a traced packet
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TraceClient {
+    pub connect_id: u64,
+    pub client_id: String,
+    pub username: String,
+    pub source_ip: String,
+}
+
+impl TraceClient {
+    // Before the connection is cached (CONNECT and a refused CONNACK) the client is
+    // described by the CONNECT packet itself.
+    pub fn build(
+        cache_manager: &MQTTCacheManager,
+        connect_id: u64,
+        addr: &SocketAddr,
+        request: &MqttPacket,
+    ) -> TraceClient {
+        if let MqttPacket::Connect(_, connect, _, _, _, login) = request {
+            return TraceClient {
+                connect_id,
+                client_id: connect.client_id.clone(),
+                username: login
+                    .as_ref()
+                    .map(|login| login.username.clone())
+                    .unwrap_or_default(),
+                source_ip: addr.ip().to_string(),
+            };
+        }
+        if let Some(connection) = cache_manager.get_connection(connect_id) {
+            return TraceClient::from_connection(&connection);
+        }
+        TraceClient {
+            connect_id,
+            source_ip: addr.ip().to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn from_connection(connection: &MQTTConnection) -> TraceClient {
+        let source_ip = match connection.source_ip_addr.parse::<SocketAddr>() {
+            Ok(addr) => addr.ip().to_string(),
+            Err(_) => connection.source_ip_addr.clone(),
+        };
+        TraceClient {
+            connect_id: connection.connect_id,
+            client_id: connection.client_id.clone(),
+            username: connection.login_user.clone(),
+            source_ip,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PacketTraceRecord {
+    pub trace_name: String,
+    pub direction: PacketDirection,
+    pub connect_id: u64,
+    pub client_id: String,
+    pub username: String,
+    pub source_ip: String,
+    pub packet_type: String,
+    pub packet: String,
+    pub payload: Option<String>,
+    pub payload_size: usize,
+    pub create_time: u128,
+}
+
+pub struct PacketTraceManager {
+    // (trace_name, PacketTrace)
+    traces: DashMap<String, PacketTrace>,
+    record_sx: Sender<PacketTraceRecord>,
+    record_rx: Mutex<Option<Receiver<PacketTraceRecord>>>,
+}
+
+impl Default for PacketTraceManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PacketTraceManager {
+    pub fn new() -> Self {
+        let (record_sx, record_rx) = mpsc::channel(PACKET_TRACE_CHANNEL_SIZE);
+        PacketTraceManager {
+            traces: DashMap::with_capacity(2),
+            record_sx,
+            record_rx: Mutex::new(Some(record_rx)),
+        }
+    }
+
+    pub fn add_trace(&self, trace: PacketTrace) -> Result<(), MqttBrokerError> {
+        trace.validate()?;
+        if self.traces.contains_key(&trace.name) {
+            return Err(MqttBrokerError::CommonError(format!(
+                "Trace {} already exists",
+                trace.name
+            )));
+        }
+        if self.traces.len() >= PACKET_TRACE_MAX_NUM {
+            return Err(MqttBrokerError::CommonError(format!(
+                "At most {PACKET_TRACE_MAX_NUM} traces can exist at the same time"
+            )));
+        }
+        self.traces.insert(trace.name.clone(), trace);
+        Ok(())
+    }
+
+    pub fn stop_trace(&self, name: &str) -> Result<PacketTrace, MqttBrokerError> {
+        let Some(mut trace) = self.traces.get_mut(name) else {
+            return Err(MqttBrokerError::CommonError(format!(
+                "Trace {name} does not exist"
+            )));
+        };
+        trace.end_time = trace.end_time.min(now_second());
+        Ok(trace.clone())
+    }
+
+    pub fn remove_trace(&self, name: &str) -> Option<PacketTrace> {
+        self.traces.remove(name).map(|(_, trace)| trace)
+    }
+
+    pub fn get_trace(&self, name: &str) -> Option<PacketTrace> {
+        self.traces.get(name).map(|trace| trace.clone())
+    }
+
+    pub fn list_traces(&self) -> Vec<PacketTrace> {
+        self.traces.iter().map(|trace| trace.clone()).collect()
+    }
+
+    // Hand the record receiver to the writer thread, only the first caller gets it
+    pub fn take_record_receiver(&self) -> Option<Receiver<PacketTraceRecord>> {
+        self.record_rx.lock().ok()?.take()
+    }
+
+    // Called on every packet, so the client is only resolved once a trace is running.
+    // Records are dropped rather than slowing down the connection when the writer lags.
+    pub fn record<F>(&self, direction: PacketDirection, packet: &MqttPacket, client: F)
+    where
+        F: FnOnce() -> TraceClient,
+    {
+        if self.traces.is_empty() {
+            return;
+        }
+        let running: Vec<PacketTrace> = self
+            .traces
+            .iter()
+            .filter(|trace| trace.is_running())
+            .map(|trace| trace.clone())
+            .collect();
+        if running.is_empty() {
+            return;
+        }
+
+        let client = client();
+        for trace in running {
+            if !trace.is_match(&client, packet) {
+                continue;
+            }
+            let record = build_trace_record(&trace, direction, &client, packet);
+            if let Err(TrySendError::Closed(_)) = self.record_sx.try_send(record) {
+                return;
+            }
+        }
+    }
+}
+
+fn build_trace_record(
+    trace: &PacketTrace,
+    direction: PacketDirection,
+    client: &TraceClient,
+    packet: &MqttPacket,
+) -> PacketTraceRecord {
+    let mut payload = None;
+    let mut payload_size = 0;
+    let packet_str = match packet {
+        MqttPacket::Publish(publish, properties) => {
+            payload_size = publish.payload.len();
+            payload = trace.encode_payload(&publish.payload);
+            let mut publish = publish.clone();
+            publish.payload = Default::default();
+            format!("{:?}", MqttPacket::Publish(publish, properties.clone()))
+        }
+        MqttPacket::Connect(version, connect, properties, last_will, will_properties, login) => {
+            let login = login.clone().map(|mut login| {
+                login.password = MASKED_PASSWORD.to_string();
+                login
+            });
+            format!(
+                "{:?}",
+                MqttPacket::Connect(
+                    *version,
+                    connect.clone(),
+                    properties.clone(),
+                    last_will.clone(),
+                    will_properties.clone(),
+                    login
+                )
+            )
+        }
+        _ => format!("{packet:?}"),
+    };
+
+    PacketTraceRecord {
+        trace_name: trace.name.clone(),
+        direction,
+        connect_id: client.connect_id,
+        client_id: client.client_id.clone(),
+        username: client.username.clone(),
+        source_ip: client.source_ip.clone(),
+        packet_type: mqtt_packet_to_string(packet),
+        packet: packet_str,
+        payload,
+        payload_size,
+        create_time: now_mills(),
+    }
+}
+
+fn packet_topics(packet: &MqttPacket) -> Vec<String> {
+    match packet {
+        MqttPacket::Publish(publish, _) => {
+            vec![String::from_utf8_lossy(&publish.topic).to_string()]
+        }
+        MqttPacket::Subscribe(subscribe, _) => subscribe
+            .filters
+            .iter()
+            .map(|filter| filter.path.clone())
+            .collect(),
+        MqttPacket::Unsubscribe(unsubscribe, _) => unsubscribe.filters.clone(),
+        _ => Vec::new(),
+    }
+}
+
+pub fn packet_trace_dir() -> String {
+    format!("{}/trace", broker_config().log.log_path)
+}
+
+// Prepare the output of a new trace
+pub async fn init_packet_trace_output(
+    message_storage_adapter: &ArcStorageAdapter,
+    trace: &PacketTrace,
+) -> Result<(), MqttBrokerError> {
+    match trace.output {
+        PacketTraceOutput::File => {
+            try_create_fold(packet_trace_dir())?;
+        }
+        PacketTraceOutput::Journal => {
+            PacketTraceStorage::new(message_storage_adapter.clone())
+                .create_shard(&trace.name)
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+// Return the captured records of a trace as JSON lines
+pub async fn export_packet_trace(
+    message_storage_adapter: &ArcStorageAdapter,
+    trace: &PacketTrace,
+) -> Result<String, MqttBrokerError> {
+    match trace.output {
+        PacketTraceOutput::File => match tokio::fs::read_to_string(trace.file_path()).await {
+            Ok(data) => Ok(data),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+            Err(e) => Err(MqttBrokerError::CommonError(e.to_string())),
+        },
+        PacketTraceOutput::Journal => {
+            let records = PacketTraceStorage::new(message_storage_adapter.clone())
+                .read(&trace.name, 0, PACKET_TRACE_MAX_READ_NUM)
+                .await?;
+            let mut lines = Vec::with_capacity(records.len());
+            for record in records {
+                lines.push(serde_json::to_string(&record)?);
+            }
+            Ok(lines.join("\n"))
+        }
+    }
+}
+
+pub async fn clean_packet_trace_output(
+    message_storage_adapter: &ArcStorageAdapter,
+    trace: &PacketTrace,
+) -> Result<(), MqttBrokerError> {
+    match trace.output {
+        PacketTraceOutput::File => match tokio::fs::remove_file(trace.file_path()).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(MqttBrokerError::CommonError(e.to_string())),
+        },
+        PacketTraceOutput::Journal => {
+            PacketTraceStorage::new(message_storage_adapter.clone())
+                .delete_shard(&trace.name)
+                .await?;
+            Ok(())
+        }
+    }
+}
+
+// Persist captured records to the output of their trace
+pub async fn start_packet_trace_thread(
+    cache_manager: Arc<MQTTCacheManager>,
+    message_storage_adapter: ArcStorageAdapter,
+    stop_send: broadcast::Sender<bool>,
+) {
+    let Some(mut record_rx) = cache_manager.packet_trace.take_record_receiver() else {
+        return;
+    };
+    let storage = PacketTraceStorage::new(message_storage_adapter);
+    let mut files: HashMap<String, File> = HashMap::new();
+    let mut stop_rx = stop_send.subscribe();
+    loop {
+        select! {
+            val = stop_rx.recv() => {
+                if let Ok(flag) = val {
+                    if flag {
+                        break;
+                    }
+                }
+            }
+            val = record_rx.recv() => {
+                let Some(record) = val else {
+                    break;
+                };
+                let Some(trace) = cache_manager.packet_trace.get_trace(&record.trace_name) else {
+                    files.remove(&record.trace_name);
+                    continue;
+                };
+                if let Err(e) = write_trace_record(&storage, &mut files, &trace, &record).await {
+                    warn!("Failed to write packet trace {}, error: {}", trace.name, e);
+                }
+            }
+        }
+    }
+}
+
+async fn write_trace_record(
+    storage: &PacketTraceStorage,
+    files: &mut HashMap<String, File>,
+    trace: &PacketTrace,
+    record: &PacketTraceRecord,
+) -> Result<(), MqttBrokerError> {
+    match trace.output {
+        PacketTraceOutput::File => {
+            if !files.contains_key(&trace.name) {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(trace.file_path())
+                    .await
+                    .map_err(|e| MqttBrokerError::CommonError(e.to_string()))?;
+                files.insert(trace.name.clone(), file);
+            }
+            let mut line = serde_json::to_vec(record)?;
+            line.push(b'\n');
+            if let Some(file) = files.get_mut(&trace.name) {
+                file.write_all(&line)
+                    .await
+                    .map_err(|e| MqttBrokerError::CommonError(e.to_string()))?;
+            }
+        }
+        PacketTraceOutput::Journal => {
+            storage.append(record).await?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        build_trace_record, PacketDirection, PacketTrace, PacketTraceManager, PacketTraceOutput,
+        PacketTracePayloadEncode, PacketTraceType, TraceClient,
+    };
+    use bytes::Bytes;
+    use common_base::tools::now_second;
+    use protocol::mqtt::common::{Connect, Login, MqttPacket, Publish, QoS};
+
+    fn build_trace(name: &str, trace_type: PacketTraceType, target: &str) -> PacketTrace {
+        PacketTrace {
+            name: name.to_string(),
+            trace_type,
+            target: target.to_string(),
+            output: PacketTraceOutput::File,
+            payload_encode: PacketTracePayloadEncode::Text,
+            payload_limit: 4,
+            start_time: now_second(),
+            end_time: now_second() + 60,
+        }
+    }
+
+    fn build_client() -> TraceClient {
+        TraceClient {
+            connect_id: 1,
+            client_id: "c1".to_string(),
+            username: "u1".to_string(),
+            source_ip: "192.168.1.10".to_string(),
+        }
+    }
+
+    fn build_publish(topic: &str) -> MqttPacket {
+        MqttPacket::Publish(
+            Publish {
+                qos: QoS::AtLeastOnce,
+                p_kid: 1,
+                topic: Bytes::from(topic.to_string()),
+                payload: Bytes::from("hello world"),
+                ..Default::default()
+            },
+            None,
+        )
+    }
+
+    #[test]
+    fn packet_trace_match_test() {
+        let client = build_client();
+        let packet = build_publish("sensor/1/temp");
+
+        assert!(build_trace("t", PacketTraceType::ClientId, "c1").is_match(&client, &packet));
+        assert!(!build_trace("t", PacketTraceType::ClientId, "c2").is_match(&client, &packet));
+        assert!(build_trace("t", PacketTraceType::Username, "u1").is_match(&client, &packet));
+        assert!(build_trace("t", PacketTraceType::Ip, "192.168.1.0/24").is_match(&client, &packet));
+        assert!(!build_trace("t", PacketTraceType::Ip, "10.0.0.1").is_match(&client, &packet));
+        assert!(
+            build_trace("t", PacketTraceType::Topic, "sensor/+/temp").is_match(&client, &packet)
+        );
+        assert!(!build_trace("t", PacketTraceType::Topic, "sensor/2/#").is_match(&client, &packet));
+    }
+
+    #[test]
+    fn packet_trace_record_test() {
+        let client = build_client();
+        let mut trace = build_trace("t", PacketTraceType::ClientId, "c1");
+
+        let record = build_trace_record(&trace, PacketDirection::In, &client, &build_publish("t1"));
+        assert_eq!(record.packet_type, "Publish");
+        assert_eq!(record.payload, Some("hell".to_string()));
+        assert_eq!(record.payload_size, 11);
+        assert!(!record.packet.contains("hello"));
+
+        trace.payload_encode = PacketTracePayloadEncode::Hex;
+        let record =
+            build_trace_record(&trace, PacketDirection::Out, &client, &build_publish("t1"));
+        assert_eq!(record.payload, Some("68656c6c".to_string()));
+
+        let connect = MqttPacket::Connect(
+            4,
+            Connect {
+                keep_alive: 30,
+                client_id: "c1".to_string(),
+                clean_session: true,
+            },
+            None,
+            None,
+            None,
+            Some(Login {
+                username: "u1".to_string(),
+                password: "secret".to_string(),
+            }),
+        );
+        let record = build_trace_record(&trace, PacketDirection::In, &client, &connect);
+        assert!(!record.packet.contains("secret"));
+    }
+
+    #[test]
+    fn packet_trace_manager_test() {
+        let manager = PacketTraceManager::new();
+        let mut receiver = manager.take_record_receiver().unwrap();
+        assert!(manager.take_record_receiver().is_none());
+
+        let mut invalid = build_trace("bad/name", PacketTraceType::ClientId, "c1");
+        assert!(manager.add_trace(invalid.clone()).is_err());
+        invalid.name = "ok".to_string();
+        invalid.end_time = invalid.start_time;
+        assert!(manager.add_trace(invalid).is_err());
+
+        manager
+            .add_trace(build_trace("t1", PacketTraceType::ClientId, "c1"))
+            .unwrap();
+        assert!(manager
+            .add_trace(build_trace("t1", PacketTraceType::ClientId, "c1"))
+            .is_err());
+
+        manager.record(PacketDirection::In, &build_publish("t1"), build_client);
+        let record = receiver.try_recv().unwrap();
+        assert_eq!(record.trace_name, "t1");
+        assert_eq!(record.direction, PacketDirection::In);
+
+        let trace = manager.stop_trace("t1").unwrap();
+        assert!(!trace.is_running());
+        manager.record(PacketDirection::In, &build_publish("t1"), build_client);
+        assert!(receiver.try_recv().is_err());
+
+        assert!(manager.remove_trace("t1").is_some());
+        assert!(manager.list_traces().is_empty());
+    }
+}
@@ -151,6 +151,54 @@ pub fn response_packet_mqtt_connect_fail(
     )
 }
 
+// Refuse the connection and point an MQTT 5 client at another broker
+pub fn response_packet_mqtt_connect_redirect(
+    protocol: &MqttProtocol,
+    code: ConnectReturnCode,
+    server_reference: Option<String>,
+) -> MqttPacket {
+    if !protocol.is_mqtt5() {
+        return MqttPacket::ConnAck(
+            ConnAck {
+                session_present: false,
+                code: ConnectReturnCode::ServiceUnavailable,
+            },
+            None,
+        );
+    }
+    let properties = ConnAckProperties {
+        server_reference,
+        ..Default::default()
+    };
+    MqttPacket::ConnAck(
+        ConnAck {
+            session_present: false,
+            code,
+        },
+        Some(properties),
+    )
+}
+
+pub fn response_packet_mqtt_distinct_by_server_reference(
+    protocol: &MqttProtocol,
+    code: DisconnectReasonCode,
+    server_reference: Option<String>,
+) -> MqttPacket {
+    if !protocol.is_mqtt5() {
+        return MqttPacket::Disconnect(Disconnect { reason_code: None }, None);
+    }
+
+    MqttPacket::Disconnect(
+        Disconnect {
+            reason_code: Some(code),
+        },
+        Some(DisconnectProperties {
+            server_reference,
+            ..Default::default()
+        }),
+    )
+}
+
 pub fn response_packet_mqtt_distinct(
     protocol: &MqttProtocol,
     code: Option<DisconnectReasonCode>,
@@ -14,7 +14,7 @@
 
 use super::cache::MQTTCacheManager;
 use super::constant::{SUB_RETAIN_MESSAGE_PUSH_FLAG, SUB_RETAIN_MESSAGE_PUSH_FLAG_VALUE};
-use super::message::build_message_expire;
+use super::message::{build_message_expire, transcode_message_payload};
 use crate::common::types::ResultMqttBrokerError;
 use crate::handler::sub_option::{
     get_retain_flag_by_retain_as_published, is_send_msg_by_bo_local,
@@ -22,7 +22,7 @@
 };
 use crate::storage::topic::TopicStorage;
 use crate::subscribe::common::min_qos;
-use crate::subscribe::common::{get_sub_topic_name_list, Subscriber};
+use crate::subscribe::common::{get_sub_payload_format, get_sub_topic_name_list, Subscriber};
 use crate::subscribe::common::{is_ignore_push_error, SubPublishParam};
 use crate::subscribe::manager::SubscribeManager;
 use crate::subscribe::push::send_publish_packet_to_client;
@@ -36,6 +36,7 @@
 use protocol::mqtt::common::{
     qos, MqttPacket, MqttProtocol, Publish, PublishProperties, Subscribe, SubscribeProperties,
 };
+use schema_register::schema::SchemaRegisterManager;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::broadcast;
@@ -99,6 +100,7 @@ pub struct TrySendRetainMessageContext {
     pub client_pool: Arc<ClientPool>,
     pub cache_manager: Arc<MQTTCacheManager>,
     pub connection_manager: Arc<ConnectionManager>,
+    pub schema_manager: Arc<SchemaRegisterManager>,
     pub is_new_subs: DashMap<String, bool>,
 }
 
@@ -118,6 +120,7 @@ pub async fn try_send_retain_message(context: TrySendRetainMessageContext) {
             client_pool: context.client_pool.clone(),
             cache_manager: context.cache_manager.clone(),
             connection_manager: context.connection_manager.clone(),
+            schema_manager: context.schema_manager.clone(),
             stop_sx,
             is_new_subs: context.is_new_subs.clone(),
         })
@@ -142,12 +145,14 @@ pub struct SendRetainMessageContext {
     pub client_pool: Arc<ClientPool>,
     pub cache_manager: Arc<MQTTCacheManager>,
     pub connection_manager: Arc<ConnectionManager>,
+    pub schema_manager: Arc<SchemaRegisterManager>,
     pub stop_sx: broadcast::Sender<bool>,
     pub is_new_subs: DashMap<String, bool>,
 }
 
 async fn send_retain_message(context: SendRetainMessageContext) -> ResultMqttBrokerError {
     let mut sub_ids = Vec::new();
+    let payload_format = get_sub_payload_format(&context.subscribe_properties);
     if let Some(properties) = context.subscribe_properties {
         if let Some(id) = properties.subscription_identifier {
             sub_ids.push(id);
@@ -179,7 +184,10 @@ async fn send_retain_message(context: SendRetainMessageContext) -> ResultMqttBro
                 continue;
             }
 
-            let msg = serde_json::from_str::<MqttMessage>(&message.unwrap())?;
+            let mut msg = serde_json::from_str::<MqttMessage>(&message.unwrap())?;
+            if let Some(format) = &payload_format {
+                transcode_message_payload(&context.schema_manager, topic_name, format, &mut msg);
+            }
 
             if !is_send_msg_by_bo_local(filter.nolocal, &context.client_id, &msg.client_id) {
                 debug!("retain messages: Determine whether to send retained messages based on the no local strategy. Client ID: {}", context.client_id);
@@ -39,33 +39,29 @@ pub struct BuildSessionContext {
     pub cache_manager: Arc<MQTTCacheManager>,
 }
 
+// Returns the session, whether it is new, and the broker that held the stored session
+// before this connection. That broker has to release the client even when the stored
+// session is not resumed.
 pub async fn build_session(
     context: BuildSessionContext,
-) -> Result<(MqttSession, bool), MqttBrokerError> {
+) -> Result<(MqttSession, bool, Option<u64>), MqttBrokerError> {
     let session_expiry =
         session_expiry_interval(&context.cache_manager, &context.connect_properties).await;
     let is_contain_last_will = context.last_will.is_some();
     let last_will_delay_interval = last_will_delay_interval(&context.last_will_properties);
 
-    let (mut session, new_session) = if context.connect.clean_session {
-        let session_storage = SessionStorage::new(context.client_pool.clone());
-        match session_storage.get_session(context.client_id.clone()).await {
-            Ok(Some(session)) => (session, false),
-            Ok(None) => (
-                MqttSession::new(
-                    context.client_id,
-                    session_expiry,
-                    is_contain_last_will,
-                    last_will_delay_interval,
-                ),
-                true,
-            ),
-            Err(e) => {
-                return Err(MqttBrokerError::CommonError(e.to_string()));
-            }
-        }
-    } else {
-        (
+    let session_storage = SessionStorage::new(context.client_pool.clone());
+    let stored_session = session_storage
+        .get_session(context.client_id.clone())
+        .await
+        .map_err(|e| MqttBrokerError::CommonError(e.to_string()))?;
+    let previous_broker_id = stored_session
+        .as_ref()
+        .and_then(|session| session.broker_id);
+
+    let (mut session, new_session) = match stored_session {
+        Some(session) if context.connect.clean_session => (session, false),
+        _ => (
             MqttSession::new(
                 context.client_id,
                 session_expiry,
@@ -73,14 +69,14 @@ pub async fn build_session(
                 last_will_delay_interval,
             ),
             true,
-        )
+        ),
     };
 
     let conf = broker_config();
     session.update_connnction_id(Some(context.connect_id));
     session.update_broker_id(Some(conf.broker_id));
     session.update_reconnect_time();
-    Ok((session, new_session))
+    Ok((session, new_session, previous_broker_id))
 }
 
 pub async fn save_session(
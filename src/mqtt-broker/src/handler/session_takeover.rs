@@ -0,0 +1,488 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_config::broker::broker_config;
+use common_metrics::mqtt::session::record_mqtt_session_taken_over;
+use grpc_clients::mqtt::inner::call::broker_mqtt_takeover_session;
+use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::subscribe_data::MqttSubscribe;
+use network_server::common::connection_manager::ConnectionManager;
+use network_server::common::packet::ResponsePackage;
+use protocol::broker::broker_mqtt_inner::{
+    OutboundInflight as OutboundInflightPacket, TakeoverSessionReply, TakeoverSessionRequest,
+};
+use protocol::mqtt::common::DisconnectReasonCode;
+use protocol::robust::RobustMQPacket;
+use tokio::time::{sleep, timeout};
+use tracing::{info, warn};
+
+use super::cache::{MQTTCacheManager, OutboundInflight};
+use super::error::MqttBrokerError;
+use super::response::response_packet_mqtt_distinct_by_reason;
+use super::subscribe::{parse_subscribe, ParseSubscribeContext};
+use crate::common::types::ResultMqttBrokerError;
+use crate::storage::subscribe::SubscribeStorage;
+use crate::subscribe::manager::SubscribeManager;
+use crate::subscribe::push::send_message_to_client;
+
+const TAKEOVER_REQUEST_TIMEOUT_MS: u64 = 2000;
+const TAKEOVER_REQUEST_RETRY_TIMES: u32 = 3;
+const TAKEOVER_REQUEST_RETRY_INTERVAL_MS: u64 = 200;
+const PUSH_THREAD_STOP_TIMEOUT_MS: u64 = 3000;
+
+#[derive(Clone)]
+pub struct SessionTakeoverContext {
+    pub client_id: String,
+    // The broker recorded in the session before this connection arrived
+    pub previous_broker_id: Option<u64>,
+    pub new_session: bool,
+    pub cache_manager: Arc<MQTTCacheManager>,
+    pub client_pool: Arc<ClientPool>,
+    pub connection_manager: Arc<ConnectionManager>,
+    pub subscribe_manager: Arc<SubscribeManager>,
+}
+
+// What the previous owner of a session hands over to the new one
+#[derive(Default, Debug, PartialEq)]
+pub struct ReleasedSession {
+    // QoS 2 packet ids received from the client that are still waiting for PUBREL
+    pub client_pkids: Vec<u16>,
+    // QoS 1/2 messages sent to the client that are still waiting for an ack
+    pub outbound_inflight: Vec<OutboundInflight>,
+}
+
+impl ReleasedSession {
+    pub fn to_reply(&self) -> TakeoverSessionReply {
+        TakeoverSessionReply {
+            client_pkids: self.client_pkids.iter().map(|pkid| *pkid as u32).collect(),
+            outbound_inflight: self
+                .outbound_inflight
+                .iter()
+                .map(|inflight| OutboundInflightPacket {
+                    group_id: inflight.group_id.clone(),
+                    offset: inflight.offset,
+                    pkid: inflight.pkid as u32,
+                    pubrel: inflight.pubrel,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn from_reply(reply: TakeoverSessionReply) -> Self {
+        ReleasedSession {
+            client_pkids: reply.client_pkids.iter().map(|pkid| *pkid as u16).collect(),
+            outbound_inflight: reply
+                .outbound_inflight
+                .into_iter()
+                .map(|inflight| OutboundInflight {
+                    group_id: inflight.group_id,
+                    offset: inflight.offset,
+                    pkid: inflight.pkid as u16,
+                    pubrel: inflight.pubrel,
+                })
+                .collect(),
+        }
+    }
+}
+
+// Called while a client is connecting, before its session is saved. Every broker that
+// still holds the client (its old connection or its subscriptions) is asked to kick the
+// old connection and stop pushing, and its in-flight state is moved here: the QoS 2
+// packet ids received from the client, and the QoS 1/2 messages sent to the client and
+// not acked yet. Their offsets are committed only after the ack, so the push threads
+// read them again and send them with the original packet id and DUP set, or only send
+// PUBREL if the client already sent PUBREC.
+// Returns the subscriptions that must be re-attached to this broker once the new
+// connection is registered, see `restore_subscribes`.
+pub async fn takeover_session(
+    context: &SessionTakeoverContext,
+) -> Result<Vec<MqttSubscribe>, MqttBrokerError> {
+    let conf = broker_config();
+    let subscribes = if context.new_session {
+        Vec::new()
+    } else {
+        SubscribeStorage::new(context.client_pool.clone())
+            .list_by_client_id(&context.client_id)
+            .await?
+    };
+
+    let mut broker_ids = HashSet::new();
+    if let Some(broker_id) = context.previous_broker_id {
+        broker_ids.insert(broker_id);
+    }
+    for subscribe in subscribes.iter() {
+        broker_ids.insert(subscribe.broker_id);
+    }
+    broker_ids.remove(&0);
+
+    let mut release_local = false;
+    for broker_id in broker_ids {
+        let released = if broker_id == conf.broker_id {
+            release_local = true;
+            release_session(
+                &context.cache_manager,
+                &context.connection_manager,
+                &context.subscribe_manager,
+                &context.client_id,
+                conf.broker_id,
+            )
+            .await
+        } else {
+            takeover_remote_session(context, broker_id).await?
+        };
+
+        restore_released_session(&context.cache_manager, &context.client_id, released);
+    }
+
+    Ok(subscribes
+        .into_iter()
+        .filter(|subscribe| release_local || subscribe.broker_id != conf.broker_id)
+        .collect())
+}
+
+// A broker that has left the cluster holds nothing for the client any more. A broker
+// that is still registered but does not answer may keep serving the old connection,
+// so the takeover fails and the client is refused rather than served twice.
+async fn takeover_remote_session(
+    context: &SessionTakeoverContext,
+    broker_id: u64,
+) -> Result<ReleasedSession, MqttBrokerError> {
+    let conf = broker_config();
+    let request = TakeoverSessionRequest {
+        cluster_name: conf.cluster_name.clone(),
+        client_id: context.client_id.clone(),
+        broker_id: conf.broker_id,
+    };
+
+    let mut attempt = 0;
+    loop {
+        let Some(node) = context
+            .cache_manager
+            .broker_cache
+            .node_lists
+            .get(&broker_id)
+            .map(|node| node.clone())
+        else {
+            info!(
+                "Broker {} holding the session of client {} has left the cluster, skip the takeover",
+                broker_id, context.client_id
+            );
+            return Ok(ReleasedSession::default());
+        };
+
+        attempt += 1;
+        let addrs = [node.node_inner_addr.clone()];
+        let error = match timeout(
+            Duration::from_millis(TAKEOVER_REQUEST_TIMEOUT_MS),
+            broker_mqtt_takeover_session(&context.client_pool, &addrs, request.clone()),
+        )
+        .await
+        {
+            Ok(Ok(reply)) => {
+                info!(
+                    "Session of client {} was taken over from broker {}",
+                    context.client_id, broker_id
+                );
+                return Ok(ReleasedSession::from_reply(reply));
+            }
+            Ok(Err(e)) => e.to_string(),
+            Err(_) => format!("request timed out after {TAKEOVER_REQUEST_TIMEOUT_MS}ms"),
+        };
+
+        if attempt >= TAKEOVER_REQUEST_RETRY_TIMES {
+            return Err(MqttBrokerError::SessionTakeoverFailed(
+                context.client_id.clone(),
+                broker_id,
+                error,
+            ));
+        }
+        warn!(
+            "Failed to take over session of client {} from broker {}, attempt {}, error: {}",
+            context.client_id, broker_id, attempt, error
+        );
+        sleep(Duration::from_millis(TAKEOVER_REQUEST_RETRY_INTERVAL_MS)).await;
+    }
+}
+
+// Release everything this broker holds for the client so that another connection can
+// own the session: the old connection is kicked with SessionTakenOver, push threads are
+// stopped and the in-flight state is returned. The session and subscriptions in the
+// meta service are left untouched, the new owner updates them.
+pub async fn release_session(
+    cache_manager: &Arc<MQTTCacheManager>,
+    connection_manager: &Arc<ConnectionManager>,
+    subscribe_manager: &Arc<SubscribeManager>,
+    client_id: &str,
+    new_broker_id: u64,
+) -> ReleasedSession {
+    for (connect_id, connection) in cache_manager.connection_info.clone() {
+        if connection.client_id != *client_id {
+            continue;
+        }
+
+        if let Some(protocol) = connection_manager.get_connect_protocol(connect_id) {
+            let packet = response_packet_mqtt_distinct_by_reason(
+                &protocol.to_mqtt(),
+                Some(DisconnectReasonCode::SessionTakenOver),
+            );
+            let resp = ResponsePackage::new(
+                connect_id,
+                RobustMQPacket::MQTT(packet),
+                0,
+                0,
+                0,
+                "SessionTakeover".to_string(),
+            );
+            if let Err(e) = send_message_to_client(resp, connection_manager).await {
+                warn!(
+                    "Failed to send SessionTakenOver to client {}, error: {}",
+                    client_id, e
+                );
+            }
+        }
+
+        // The connection is dropped from the cache before it is closed so that the
+        // keep alive check does not treat it as an expired session.
+        cache_manager.remove_connection(connect_id);
+        connection_manager.close_connect(connect_id).await;
+        record_mqtt_session_taken_over();
+    }
+
+    // Hand the subscriptions over first so that new topics are no longer parsed
+    // for the client on this broker.
+    for mut subscribe in subscribe_manager.subscribe_list.iter_mut() {
+        if subscribe.client_id == *client_id {
+            subscribe.broker_id = new_broker_id;
+        }
+    }
+
+    let thread_keys = subscribe_manager.stop_push_by_client_id(client_id);
+    let wait_stop = async {
+        while thread_keys
+            .iter()
+            .any(|key| subscribe_manager.contain_push_thread(key))
+        {
+            sleep(Duration::from_millis(50)).await;
+        }
+    };
+    if timeout(
+        Duration::from_millis(PUSH_THREAD_STOP_TIMEOUT_MS),
+        wait_stop,
+    )
+    .await
+    .is_err()
+    {
+        warn!(
+            "Push threads of client {} did not stop within {}ms",
+            client_id, PUSH_THREAD_STOP_TIMEOUT_MS
+        );
+    }
+
+    let released = ReleasedSession {
+        client_pkids: cache_manager.pkid_metadata.take_client_pkids(client_id),
+        outbound_inflight: cache_manager
+            .pkid_metadata
+            .take_outbound_inflight(client_id),
+    };
+    cache_manager.remove_session(client_id);
+    released
+}
+
+fn restore_released_session(
+    cache_manager: &Arc<MQTTCacheManager>,
+    client_id: &str,
+    released: ReleasedSession,
+) {
+    for pkid in released.client_pkids {
+        cache_manager.pkid_metadata.add_client_pkid(client_id, pkid);
+    }
+    for inflight in released.outbound_inflight {
+        cache_manager
+            .pkid_metadata
+            .add_outbound_resend(client_id, inflight);
+    }
+}
+
+// Attach the subscriptions returned by `takeover_session` to this broker and start
+// pushing them. Delivery continues from the offsets committed by the previous owner.
+pub async fn restore_subscribes(
+    cache_manager: &Arc<MQTTCacheManager>,
+    client_pool: &Arc<ClientPool>,
+    subscribe_manager: &Arc<SubscribeManager>,
+    subscribes: Vec<MqttSubscribe>,
+) -> ResultMqttBrokerError {
+    let conf = broker_config();
+    let subscribe_storage = SubscribeStorage::new(client_pool.clone());
+    for mut subscribe in subscribes {
+        if subscribe.broker_id != conf.broker_id {
+            subscribe.broker_id = conf.broker_id;
+            subscribe_storage.set_subscribe(&subscribe).await?;
+        }
+        subscribe_manager.add_subscribe(subscribe.clone());
+
+        let rewrite_sub_path = cache_manager.get_new_rewrite_name(&subscribe.path);
+        for (_, topic) in cache_manager.topic_info.clone() {
+            parse_subscribe(ParseSubscribeContext {
+                client_pool: client_pool.clone(),
+                subscribe_manager: subscribe_manager.clone(),
+                client_id: subscribe.client_id.clone(),
+                topic,
+                protocol: subscribe.protocol.clone(),
+                pkid: subscribe.pkid,
+                filter: subscribe.filter.clone(),
+                subscribe_properties: subscribe.subscribe_properties.clone(),
+                rewrite_sub_path: rewrite_sub_path.clone(),
+            })
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{release_session, restore_released_session, ReleasedSession};
+    use crate::common::tool::test_build_mqtt_cache_manager;
+    use crate::handler::cache::{MQTTCacheManager, OutboundInflight};
+    use crate::subscribe::common::{SubPublishParam, Subscriber};
+    use crate::subscribe::manager::SubscribeManager;
+    use crate::subscribe::push::{build_publish_message, BuildPublishMessageContext};
+    use bytes::Bytes;
+    use common_base::tools::now_second;
+    use metadata_struct::mqtt::message::MqttMessage;
+    use metadata_struct::mqtt::session::MqttSession;
+    use network_server::common::connection_manager::ConnectionManager;
+    use protocol::mqtt::common::{MqttPacket, Publish, QoS};
+    use schema_register::schema::SchemaRegisterManager;
+    use std::sync::Arc;
+
+    const CLIENT_ID: &str = "takeover_client";
+    const GROUP_ID: &str = "system_sub_takeover_client_/t_/t";
+
+    async fn build_cache_manager() -> Arc<MQTTCacheManager> {
+        let cache_manager = test_build_mqtt_cache_manager().await;
+        cache_manager.add_session(
+            CLIENT_ID,
+            &MqttSession {
+                client_id: CLIENT_ID.to_string(),
+                connection_id: Some(1),
+                ..Default::default()
+            },
+        );
+        cache_manager
+    }
+
+    async fn build_publish(cache_manager: &Arc<MQTTCacheManager>, offset: u64) -> SubPublishParam {
+        let publish = Publish {
+            qos: QoS::ExactlyOnce,
+            topic: Bytes::from("/t"),
+            payload: Bytes::from("data"),
+            ..Default::default()
+        };
+        let mut record =
+            MqttMessage::build_record("producer", &publish, &None, now_second() + 3600).unwrap();
+        record.offset = Some(offset);
+
+        build_publish_message(BuildPublishMessageContext {
+            cache_manager: cache_manager.clone(),
+            connection_manager: Arc::new(ConnectionManager::new(3, 1000)),
+            schema_manager: Arc::new(SchemaRegisterManager::new()),
+            client_id: CLIENT_ID.to_string(),
+            record,
+            group_id: GROUP_ID.to_string(),
+            qos: QoS::ExactlyOnce,
+            subscriber: Subscriber {
+                client_id: CLIENT_ID.to_string(),
+                topic_name: "/t".to_string(),
+                sub_path: "/t".to_string(),
+                qos: QoS::ExactlyOnce,
+                ..Default::default()
+            },
+            sub_ids: Vec::new(),
+        })
+        .await
+        .unwrap()
+        .unwrap()
+    }
+
+    fn publish_of(param: &SubPublishParam) -> Publish {
+        match &param.packet {
+            MqttPacket::Publish(publish, _) => publish.clone(),
+            _ => panic!("not a publish packet"),
+        }
+    }
+
+    #[tokio::test]
+    async fn takeover_outbound_inflight_test() {
+        // the old owner pushed two messages, the client sent PUBREC for the second one
+        let old_cache = build_cache_manager().await;
+        let mut pushed = Vec::new();
+        for offset in [5, 6] {
+            let param = build_publish(&old_cache, offset).await;
+            old_cache.pkid_metadata.add_outbound_inflight(
+                CLIENT_ID,
+                OutboundInflight {
+                    group_id: GROUP_ID.to_string(),
+                    offset,
+                    pkid: param.pkid,
+                    pubrel: false,
+                },
+            );
+            pushed.push(param);
+        }
+        old_cache
+            .pkid_metadata
+            .mark_outbound_pubrel(CLIENT_ID, pushed[1].pkid);
+
+        let released = release_session(
+            &old_cache,
+            &Arc::new(ConnectionManager::new(3, 1000)),
+            &Arc::new(SubscribeManager::new()),
+            CLIENT_ID,
+            2,
+        )
+        .await;
+        assert_eq!(released.outbound_inflight.len(), 2);
+        assert!(old_cache
+            .pkid_metadata
+            .take_outbound_inflight(CLIENT_ID)
+            .is_empty());
+
+        // the state goes through the takeover reply unchanged
+        let released = ReleasedSession::from_reply(released.to_reply());
+        let new_cache = build_cache_manager().await;
+        restore_released_session(&new_cache, CLIENT_ID, released);
+
+        // the unacked message is sent again with its packet id and DUP set, so the
+        // client recognises it instead of receiving a second copy
+        let resent = build_publish(&new_cache, 5).await;
+        assert_eq!(resent.pkid, pushed[0].pkid);
+        assert!(publish_of(&resent).dup);
+        assert!(!resent.resend_pubrel);
+
+        // the client already has the second message, only PUBREL is sent for it
+        let resent = build_publish(&new_cache, 6).await;
+        assert_eq!(resent.pkid, pushed[1].pkid);
+        assert!(resent.resend_pubrel);
+
+        // later messages get new packet ids
+        let next = build_publish(&new_cache, 7).await;
+        assert!(!publish_of(&next).dup);
+        assert!(next.pkid != pushed[0].pkid && next.pkid != pushed[1].pkid);
+    }
+}
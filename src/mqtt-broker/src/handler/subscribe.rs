@@ -33,7 +33,8 @@
 
 use crate::subscribe::{
     common::{
-        decode_share_group_and_path, get_share_sub_leader, is_match_sub_and_topic, Subscriber,
+        decode_share_group_and_path, get_share_sub_leader, get_sub_payload_format,
+        is_match_sub_and_topic, Subscriber,
     },
     manager::{ShareSubShareSub, SubscribeManager},
 };
@@ -61,6 +62,7 @@ pub struct AddExclusivePushContext {
     pub sub_identifier: Option<usize>,
     pub filter: Filter,
     pub rewrite_sub_path: Option<String>,
+    pub payload_format: Option<String>,
 }
 
 #[derive(Clone, Deserialize, Serialize)]
@@ -69,6 +71,7 @@ pub struct ParseShareQueueSubscribeRequest {
     pub client_id: String,
     pub protocol: MqttProtocol,
     pub sub_identifier: Option<usize>,
+    pub payload_format: Option<String>,
     pub filter: Filter,
     pub sub_name: String,
     pub group_name: String,
@@ -167,6 +170,7 @@ pub async fn parse_subscribe(context: ParseSubscribeContext) -> ResultMqttBroker
     } else {
         None
     };
+    let payload_format = get_sub_payload_format(&context.subscribe_properties);
 
     // share sub
     if is_mqtt_share_subscribe(&context.filter.path) {
@@ -178,6 +182,7 @@ pub async fn parse_subscribe(context: ParseSubscribeContext) -> ResultMqttBroker
                 client_id: context.client_id.to_owned(),
                 protocol: context.protocol.clone(),
                 sub_identifier,
+                payload_format,
                 filter: context.filter.clone(),
                 pkid: context.pkid,
                 sub_name: "".to_string(),
@@ -194,6 +199,7 @@ pub async fn parse_subscribe(context: ParseSubscribeContext) -> ResultMqttBroker
             sub_identifier,
             filter: context.filter.clone(),
             rewrite_sub_path: context.rewrite_sub_path.clone(),
+            payload_format,
         })
     }
 }
@@ -233,6 +239,7 @@ pub async fn add_share_push_leader(
         preserve_retain: req.filter.preserve_retain,
         retain_forward_rule: req.filter.retain_handling.clone(),
         subscription_identifier: req.sub_identifier,
+        payload_format: req.payload_format.clone(),
         sub_path: req.filter.path.clone(),
         rewrite_sub_path: None,
         create_time: now_second(),
@@ -254,6 +261,7 @@ async fn add_share_push_follower(
         group_name: req.group_name.clone(),
         sub_name: req.sub_name.clone(),
         subscription_identifier: req.sub_identifier,
+        payload_format: req.payload_format.clone(),
         topic_name: req.topic_name.clone(),
     };
 
@@ -289,6 +297,7 @@ fn add_exclusive_push(context: AddExclusivePushContext) -> ResultMqttBrokerError
             preserve_retain: context.filter.preserve_retain,
             retain_forward_rule: context.filter.retain_handling.to_owned(),
             subscription_identifier: context.sub_identifier.to_owned(),
+            payload_format: context.payload_format.clone(),
             sub_path: context.filter.path.clone(),
             rewrite_sub_path: context.rewrite_sub_path.clone(),
             create_time: now_second(),
@@ -338,6 +347,7 @@ fn add_exclusive_push_test() {
             sub_identifier: Some(1),
             filter: filter.clone(),
             rewrite_sub_path: None,
+            payload_format: None,
         });
         assert!(res.is_ok());
         println!("{:?}", subscribe_manager.topic_subscribe_list);
@@ -12,128 +12,56 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::handler::cache::MQTTCacheManager;
 use crate::storage::local::LocalStorage;
 use crate::system_topic::sysmon::st_report_system_alarm_event;
-use crate::{common::types::ResultMqttBrokerError, handler::cache::MQTTCacheManager};
 use broker_core::rocksdb::RocksDBEngine;
 use common_base::error::ResultCommonError;
-use common_base::tools::{loop_select_ticket, now_second};
-use common_config::broker::broker_config;
+use common_base::tools::now_second;
 use grpc_clients::pool::ClientPool;
 use serde::{Deserialize, Serialize};
-use std::fmt;
 use std::sync::Arc;
 use std::time::Duration;
 use storage_adapter::storage::ArcStorageAdapter;
 use sysinfo::{Pid, ProcessExt, System, SystemExt};
-use tokio::sync::broadcast;
 use tokio::time::sleep;
-use tracing::info;
-
-#[allow(clippy::enum_variant_names)]
-enum AlarmType {
-    HighCpuUsage,
-    HighMemoryUsage,
-}
-
-impl fmt::Display for AlarmType {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            AlarmType::HighCpuUsage => write!(f, "HighCpuUsage"),
-            AlarmType::HighMemoryUsage => write!(f, "HighMemoryUsage"),
-        }
-    }
-}
 
 // sysmon topic
 #[derive(Default, Serialize, Deserialize, Clone)]
 pub struct SystemAlarmEventMessage {
     pub name: String,
     pub message: String,
+    #[serde(default)]
+    pub activated: bool,
     pub create_time: u64,
 }
 
-pub struct SystemAlarm {
-    client_pool: Arc<ClientPool>,
-    metadata_cache: Arc<MQTTCacheManager>,
-    message_storage_adapter: ArcStorageAdapter,
-    rocksdb_engine_handler: Arc<RocksDBEngine>,
-    stop_send: broadcast::Sender<bool>,
-}
-
-impl SystemAlarm {
-    pub fn new(
-        client_pool: Arc<ClientPool>,
-        metadata_cache: Arc<MQTTCacheManager>,
-        message_storage_adapter: ArcStorageAdapter,
-        rocksdb_engine_handler: Arc<RocksDBEngine>,
-        stop_send: broadcast::Sender<bool>,
-    ) -> Self {
-        SystemAlarm {
-            client_pool,
-            metadata_cache,
-            message_storage_adapter,
-            rocksdb_engine_handler,
-            stop_send,
-        }
-    }
-
-    pub async fn start(&self) -> ResultMqttBrokerError {
-        let config = broker_config();
-        if !config.mqtt_system_monitor.enable {
-            return Ok(());
-        }
-
-        let record_func = async || -> ResultCommonError {
-            let mqtt_conf = broker_config();
-            let cpu_usage = get_process_every_cpu_usage().await;
-
-            self.try_send_a_new_system_event(
-                AlarmType::HighCpuUsage,
-                cpu_usage,
-                mqtt_conf.mqtt_system_monitor.os_cpu_high_watermark,
-            )
-            .await?;
-
-            let memory_usage = get_process_memory_usage();
-            self.try_send_a_new_system_event(
-                AlarmType::HighMemoryUsage,
-                memory_usage,
-                mqtt_conf.mqtt_system_monitor.os_memory_high_watermark,
-            )
-            .await?;
-            Ok(())
-        };
-
-        info!("System alarm thread start successfully");
-        loop_select_ticket(record_func, 60, &self.stop_send).await;
-        Ok(())
-    }
-
-    async fn try_send_a_new_system_event(
-        &self,
-        alarm_type: AlarmType,
-        current_usage: f32,
-        config_usage: f32,
-    ) -> ResultCommonError {
-        if current_usage > config_usage {
-            let message = SystemAlarmEventMessage {
-                name: alarm_type.to_string(),
-                message: format!("{alarm_type} is {current_usage}%, but config is {config_usage}%"),
-                create_time: now_second(),
-            };
-            st_report_system_alarm_event(
-                &self.client_pool,
-                &self.metadata_cache,
-                &self.message_storage_adapter,
-                &message,
-            )
-            .await?;
-            let log_storage = LocalStorage::new(self.rocksdb_engine_handler.clone());
-            log_storage.save_system_event(message).await?;
-        }
-        Ok(())
-    }
+// Publish the alarm to the sysmon topic and keep it in the local event list
+pub async fn report_system_alarm(
+    client_pool: &Arc<ClientPool>,
+    metadata_cache: &Arc<MQTTCacheManager>,
+    message_storage_adapter: &ArcStorageAdapter,
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    name: &str,
+    message: String,
+    activated: bool,
+) -> ResultCommonError {
+    let message = SystemAlarmEventMessage {
+        name: name.to_string(),
+        message,
+        activated,
+        create_time: now_second(),
+    };
+    st_report_system_alarm_event(
+        client_pool,
+        metadata_cache,
+        message_storage_adapter,
+        &message,
+    )
+    .await?;
+    let log_storage = LocalStorage::new(rocksdb_engine_handler.clone());
+    log_storage.save_system_event(message).await?;
+    Ok(())
 }
 
 // Get CPU usage percentage of the current process
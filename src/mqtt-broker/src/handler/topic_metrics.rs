@@ -0,0 +1,277 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use broker_core::rocksdb::RocksDBEngine;
+use common_base::error::ResultCommonError;
+use common_base::tools::loop_select_ticket;
+use common_config::broker::broker_config;
+use common_metrics::mqtt::topic::{
+    record_topic_bytes_sent, record_topic_bytes_written, record_topic_messages_dropped,
+    record_topic_messages_retained, record_topic_messages_sent, record_topic_messages_written,
+    record_topic_subscriber_num, remove_topic_metrics,
+};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tracing::{error, warn};
+
+use super::cache::MQTTCacheManager;
+use super::error::MqttBrokerError;
+use crate::storage::local::LocalStorage;
+use crate::subscribe::common::{is_match_sub_and_topic, sub_path_validator};
+use crate::subscribe::manager::SubscribeManager;
+
+const DEFAULT_TOPIC_METRICS_MAX_TOPICS: usize = 1000;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TopicMetricsRule {
+    // topic name or topic filter with wildcards
+    pub topic_filter: String,
+    pub create_time: u64,
+}
+
+// Per-topic series are only exported for topics matching a rule, and for at most
+// max_topics of them, so that Prometheus cardinality stays bounded.
+pub struct TopicMetricsManager {
+    // (topic_filter, TopicMetricsRule)
+    rules: DashMap<String, TopicMetricsRule>,
+    // (topic_name, topic_filter) of the topics exported
+    topics: DashMap<String, String>,
+    max_topics: AtomicUsize,
+    track_lock: Mutex<()>,
+    limit_warned: AtomicBool,
+}
+
+impl Default for TopicMetricsManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TopicMetricsManager {
+    pub fn new() -> Self {
+        TopicMetricsManager {
+            rules: DashMap::with_capacity(2),
+            topics: DashMap::with_capacity(2),
+            max_topics: AtomicUsize::new(DEFAULT_TOPIC_METRICS_MAX_TOPICS),
+            track_lock: Mutex::new(()),
+            limit_warned: AtomicBool::new(false),
+        }
+    }
+
+    pub fn set_max_topics(&self, max_topics: usize) {
+        self.max_topics.store(max_topics, Ordering::Relaxed);
+    }
+
+    pub fn max_topics(&self) -> usize {
+        self.max_topics.load(Ordering::Relaxed)
+    }
+
+    pub fn add_rule(&self, rule: TopicMetricsRule) -> Result<(), MqttBrokerError> {
+        sub_path_validator(&rule.topic_filter)?;
+        if self.rules.contains_key(&rule.topic_filter) {
+            return Err(MqttBrokerError::CommonError(format!(
+                "Topic metrics rule {} already exists",
+                rule.topic_filter
+            )));
+        }
+        self.rules.insert(rule.topic_filter.clone(), rule);
+        Ok(())
+    }
+
+    // Removing a rule also removes the series of the topics it matched
+    pub fn remove_rule(&self, topic_filter: &str) -> Option<TopicMetricsRule> {
+        let (_, rule) = self.rules.remove(topic_filter)?;
+        let removed: Vec<String> = self
+            .topics
+            .iter()
+            .filter(|entry| entry.value() == topic_filter)
+            .map(|entry| entry.key().clone())
+            .collect();
+        for topic in removed {
+            self.topics.remove(&topic);
+            remove_topic_metrics(&topic);
+        }
+        self.limit_warned.store(false, Ordering::Relaxed);
+        Some(rule)
+    }
+
+    pub fn list_rules(&self) -> Vec<TopicMetricsRule> {
+        self.rules.iter().map(|rule| rule.clone()).collect()
+    }
+
+    // (topic_name, topic_filter) of the topics exported
+    pub fn list_topics(&self) -> Vec<(String, String)> {
+        self.topics
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect()
+    }
+
+    pub fn rule_topic_num(&self, topic_filter: &str) -> usize {
+        self.topics
+            .iter()
+            .filter(|entry| entry.value() == topic_filter)
+            .count()
+    }
+
+    // Called for every message, returns early while no rule exists
+    pub fn is_tracked(&self, topic_name: &str) -> bool {
+        if self.rules.is_empty() {
+            return false;
+        }
+        if self.topics.contains_key(topic_name) {
+            return true;
+        }
+
+        let Some(topic_filter) = self
+            .rules
+            .iter()
+            .find(|rule| is_match_sub_and_topic(&rule.topic_filter, topic_name).is_ok())
+            .map(|rule| rule.topic_filter.clone())
+        else {
+            return false;
+        };
+
+        let Ok(_guard) = self.track_lock.lock() else {
+            return false;
+        };
+        if self.topics.contains_key(topic_name) {
+            return true;
+        }
+        if self.topics.len() >= self.max_topics() {
+            if !self.limit_warned.swap(true, Ordering::Relaxed) {
+                warn!(
+                    "Topic metrics reached the limit of {} topics, topic {} is not tracked",
+                    self.max_topics(),
+                    topic_name
+                );
+            }
+            return false;
+        }
+        self.topics.insert(topic_name.to_string(), topic_filter);
+        true
+    }
+
+    pub fn record_written(&self, topic_name: &str, bytes: u64, retain: bool) {
+        if !self.is_tracked(topic_name) {
+            return;
+        }
+        record_topic_messages_written(topic_name);
+        record_topic_bytes_written(topic_name, bytes);
+        if retain {
+            record_topic_messages_retained(topic_name);
+        }
+    }
+
+    pub fn record_sent(&self, topic_name: &str, bytes: u64) {
+        if !self.is_tracked(topic_name) {
+            return;
+        }
+        record_topic_messages_sent(topic_name);
+        record_topic_bytes_sent(topic_name, bytes);
+    }
+
+    pub fn record_dropped(&self, topic_name: &str) {
+        if !self.is_tracked(topic_name) {
+            return;
+        }
+        record_topic_messages_dropped(topic_name);
+    }
+}
+
+// Load the persisted rules and keep the subscriber count of the tracked topics up to date
+pub async fn start_topic_metrics_thread(
+    cache_manager: Arc<MQTTCacheManager>,
+    subscribe_manager: Arc<SubscribeManager>,
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+    stop_send: broadcast::Sender<bool>,
+) {
+    let topic_metrics = cache_manager.topic_metrics.clone();
+    topic_metrics.set_max_topics(broker_config().mqtt_topic_metrics.max_topics);
+
+    let local_storage = LocalStorage::new(rocksdb_engine_handler);
+    match local_storage.list_topic_metrics_rule().await {
+        Ok(rules) => {
+            for rule in rules {
+                if let Err(e) = topic_metrics.add_rule(rule) {
+                    warn!("Failed to load topic metrics rule, error: {}", e);
+                }
+            }
+        }
+        Err(e) => {
+            error!("Failed to list topic metrics rules, error: {}", e);
+        }
+    }
+
+    let ac_fn = async || -> ResultCommonError {
+        for (topic_name, _) in topic_metrics.list_topics() {
+            let num = subscribe_manager
+                .topic_subscribe_list
+                .get(&topic_name)
+                .map(|list| list.len())
+                .unwrap_or(0);
+            record_topic_subscriber_num(&topic_name, num as i64);
+        }
+        Ok(())
+    };
+    loop_select_ticket(ac_fn, 5, &stop_send).await;
+}
+
+#[cfg(test)]
+mod test {
+    use super::{TopicMetricsManager, TopicMetricsRule};
+
+    fn build_rule(topic_filter: &str) -> TopicMetricsRule {
+        TopicMetricsRule {
+            topic_filter: topic_filter.to_string(),
+            create_time: 0,
+        }
+    }
+
+    #[test]
+    fn topic_metrics_track_test() {
+        let manager = TopicMetricsManager::new();
+        assert!(!manager.is_tracked("sensor/1/temp"));
+
+        manager.add_rule(build_rule("sensor/+/temp")).unwrap();
+        assert!(manager.add_rule(build_rule("sensor/+/temp")).is_err());
+        assert!(manager.add_rule(build_rule("sensor/a+")).is_err());
+
+        assert!(manager.is_tracked("sensor/1/temp"));
+        assert!(!manager.is_tracked("sensor/1/humidity"));
+        assert_eq!(manager.rule_topic_num("sensor/+/temp"), 1);
+
+        manager.remove_rule("sensor/+/temp").unwrap();
+        assert!(!manager.is_tracked("sensor/1/temp"));
+        assert!(manager.list_topics().is_empty());
+    }
+
+    #[test]
+    fn topic_metrics_limit_test() {
+        let manager = TopicMetricsManager::new();
+        manager.set_max_topics(2);
+        manager.add_rule(build_rule("#")).unwrap();
+
+        assert!(manager.is_tracked("t1"));
+        assert!(manager.is_tracked("t2"));
+        assert!(!manager.is_tracked("t3"));
+        // already tracked topics are not affected by the limit
+        assert!(manager.is_tracked("t1"));
+        assert_eq!(manager.list_topics().len(), 2);
+    }
+}
@@ -0,0 +1,635 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_base::lock_profile::timed;
+use common_base::tools::{now_mills, now_second};
+use common_config::broker::broker_config;
+use dashmap::DashMap;
+use grpc_clients::pool::ClientPool;
+use hmac::{Hmac, Mac};
+use metadata_struct::mqtt::webhook::{MqttWebhook, WebhookEventType};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use strum_macros::Display;
+use tokio::select;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, sleep};
+use tracing::{info, warn};
+
+use super::cache::MQTTCacheManager;
+use super::error::MqttBrokerError;
+use crate::storage::webhook::WebhookStorage;
+use crate::subscribe::common::{is_match_sub_and_topic, sub_path_validator};
+
+const WEBHOOK_RELOAD_INTERVAL_SECS: u64 = 10;
+const WEBHOOK_RETRY_BACKOFF_MS: u64 = 200;
+const WEBHOOK_RETRY_MAX_BACKOFF_MS: u64 = 30000;
+
+pub const WEBHOOK_SIGNATURE_HEADER: &str = "X-RobustMQ-Signature";
+pub const WEBHOOK_TIMESTAMP_HEADER: &str = "X-RobustMQ-Timestamp";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WebhookEvent {
+    pub event: WebhookEventType,
+    pub broker_id: u64,
+    pub data: serde_json::Value,
+    pub timestamp: u128,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MessagePublishEventData {
+    pub client_id: String,
+    pub username: String,
+    pub topic: String,
+    pub qos: u8,
+    pub retain: bool,
+    // Base64 encoded
+    pub payload: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SessionExpiredEventData {
+    pub client_id: String,
+}
+
+// Body of one webhook request
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WebhookBatch {
+    pub webhook: String,
+    pub events: Vec<WebhookEvent>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, Display)]
+pub enum WebhookStatus {
+    // Nothing has been sent to the endpoint yet
+    #[default]
+    Unknown,
+    Healthy,
+    // The last request failed after all retries
+    Unhealthy,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WebhookHealth {
+    pub status: WebhookStatus,
+    pub delivered: u64,
+    pub failed: u64,
+    // Events dropped because the pending queue was full
+    pub dropped: u64,
+    pub consecutive_failures: u64,
+    pub last_success_time: u64,
+    pub last_failure_time: u64,
+    pub last_error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct WebhookManager {
+    // (name, webhook)
+    webhooks: DashMap<String, MqttWebhook>,
+    // (name, events waiting to be sent)
+    queues: DashMap<String, VecDeque<WebhookEvent>>,
+    // (name, delivery health on this broker)
+    health: DashMap<String, WebhookHealth>,
+}
+
+impl WebhookManager {
+    pub fn new() -> Self {
+        WebhookManager::default()
+    }
+
+    pub fn add_webhook(&self, webhook: MqttWebhook) {
+        self.webhooks.insert(webhook.name.clone(), webhook);
+    }
+
+    pub fn remove_webhook(&self, name: &str) {
+        self.webhooks.remove(name);
+        self.queues.remove(name);
+        self.health.remove(name);
+    }
+
+    pub fn get_webhook(&self, name: &str) -> Option<MqttWebhook> {
+        self.webhooks.get(name).map(|raw| raw.value().clone())
+    }
+
+    pub fn list_webhooks(&self) -> Vec<MqttWebhook> {
+        self.webhooks
+            .iter()
+            .map(|raw| raw.value().clone())
+            .collect()
+    }
+
+    // Replace the webhooks with the ones stored in meta-service
+    pub fn set_webhooks(&self, webhooks: Vec<MqttWebhook>) {
+        let names: Vec<String> = webhooks
+            .iter()
+            .map(|webhook| webhook.name.clone())
+            .collect();
+        let removed: Vec<String> = self
+            .webhooks
+            .iter()
+            .filter(|raw| !names.contains(raw.key()))
+            .map(|raw| raw.key().clone())
+            .collect();
+        for name in removed {
+            self.remove_webhook(&name);
+        }
+        for webhook in webhooks {
+            self.add_webhook(webhook);
+        }
+    }
+
+    // Called on the connection and publish paths, so the event data is only
+    // built when at least one webhook wants it
+    pub fn report<F, T>(&self, event_type: WebhookEventType, topic_name: Option<&str>, data: F)
+    where
+        F: FnOnce() -> T,
+        T: Serialize,
+    {
+        if self.webhooks.is_empty() {
+            return;
+        }
+
+        let names: Vec<String> = self
+            .webhooks
+            .iter()
+            .filter(|raw| is_match_webhook(raw.value(), event_type, topic_name))
+            .map(|raw| raw.key().clone())
+            .collect();
+        if names.is_empty() {
+            return;
+        }
+
+        let data = match serde_json::to_value(data()) {
+            Ok(data) => data,
+            Err(e) => {
+                warn!("Failed to build {} webhook event, error: {}", event_type, e);
+                return;
+            }
+        };
+        let event = WebhookEvent {
+            event: event_type,
+            broker_id: broker_config().broker_id,
+            data,
+            timestamp: now_mills(),
+        };
+
+        let capacity = broker_config().mqtt_webhook.queue_capacity;
+        for name in names {
+            let mut queue = timed(self.queues.entry(name.clone()).or_default());
+            if queue.len() >= capacity {
+                queue.pop_front();
+                self.health.entry(name).or_default().dropped += 1;
+            }
+            queue.push_back(event.clone());
+        }
+    }
+
+    pub fn take_batch(&self, name: &str, batch_size: usize) -> Vec<WebhookEvent> {
+        let Some(queue) = self.queues.get_mut(name) else {
+            return Vec::new();
+        };
+        let mut queue = timed(queue);
+        let len = queue.len().min(batch_size);
+        queue.drain(..len).collect()
+    }
+
+    // Put a batch that failed after the retries back at the head of the queue. As in report,
+    // the oldest events are dropped when the queue would go over its capacity.
+    pub fn requeue_batch(&self, name: &str, events: Vec<WebhookEvent>) {
+        if !self.webhooks.contains_key(name) {
+            return;
+        }
+        let capacity = broker_config().mqtt_webhook.queue_capacity;
+        let mut queue = timed(self.queues.entry(name.to_string()).or_default());
+        let dropped = events
+            .len()
+            .saturating_sub(capacity.saturating_sub(queue.len()));
+        for event in events.into_iter().skip(dropped).rev() {
+            queue.push_front(event);
+        }
+        if dropped > 0 {
+            self.health.entry(name.to_string()).or_default().dropped += dropped as u64;
+        }
+    }
+
+    pub fn pending_num(&self, name: &str) -> usize {
+        self.queues.get(name).map(|queue| queue.len()).unwrap_or(0)
+    }
+
+    pub fn get_health(&self, name: &str) -> WebhookHealth {
+        self.health
+            .get(name)
+            .map(|health| health.clone())
+            .unwrap_or_default()
+    }
+
+    fn record_success(&self, name: &str, event_num: usize) {
+        let mut health = self.health.entry(name.to_string()).or_default();
+        health.status = WebhookStatus::Healthy;
+        health.delivered += event_num as u64;
+        health.consecutive_failures = 0;
+        health.last_success_time = now_second();
+    }
+
+    fn record_failure(&self, name: &str, event_num: usize, error: String) {
+        let mut health = self.health.entry(name.to_string()).or_default();
+        health.status = WebhookStatus::Unhealthy;
+        health.failed += event_num as u64;
+        health.consecutive_failures += 1;
+        health.last_failure_time = now_second();
+        health.last_error = Some(error);
+    }
+}
+
+pub fn is_match_webhook(
+    webhook: &MqttWebhook,
+    event_type: WebhookEventType,
+    topic_name: Option<&str>,
+) -> bool {
+    if !webhook.enable || !webhook.events.contains(&event_type) {
+        return false;
+    }
+    let Some(topic_name) = topic_name else {
+        return true;
+    };
+    webhook.topic_filters.is_empty()
+        || webhook
+            .topic_filters
+            .iter()
+            .any(|filter| is_match_sub_and_topic(filter, topic_name).is_ok())
+}
+
+pub fn validate_webhook(webhook: &MqttWebhook) -> Result<(), MqttBrokerError> {
+    if webhook.name.is_empty() {
+        return Err(MqttBrokerError::CommonError(
+            "Webhook name cannot be empty".to_string(),
+        ));
+    }
+    if !webhook.url.starts_with("http://") && !webhook.url.starts_with("https://") {
+        return Err(MqttBrokerError::CommonError(format!(
+            "Webhook url {} must start with http:// or https://",
+            webhook.url
+        )));
+    }
+    if webhook.events.is_empty() {
+        return Err(MqttBrokerError::CommonError(
+            "Webhook must subscribe to at least one event".to_string(),
+        ));
+    }
+    for filter in webhook.topic_filters.iter() {
+        sub_path_validator(filter)?;
+    }
+    Ok(())
+}
+
+// Hex encoded HMAC-SHA256 of "{timestamp}.{body}", the timestamp is sent in
+// its own header so that receivers can reject replayed requests
+pub fn sign_webhook_body(secret: &str, timestamp: u128, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+// Exponential backoff before the retry after the given attempt
+pub fn retry_backoff(attempt: u32) -> Duration {
+    Duration::from_millis(
+        WEBHOOK_RETRY_BACKOFF_MS
+            .saturating_mul(2u64.saturating_pow(attempt))
+            .min(WEBHOOK_RETRY_MAX_BACKOFF_MS),
+    )
+}
+
+async fn send_batch(
+    http_client: &Client,
+    webhook: &MqttWebhook,
+    events: &[WebhookEvent],
+) -> Result<(), MqttBrokerError> {
+    let body = serde_json::to_vec(&WebhookBatch {
+        webhook: webhook.name.clone(),
+        events: events.to_vec(),
+    })?;
+
+    let max_retries = broker_config().mqtt_webhook.max_retries;
+    let mut attempt = 0;
+    loop {
+        let timestamp = now_mills();
+        let mut request = http_client
+            .post(&webhook.url)
+            .header("Content-Type", "application/json")
+            .header(WEBHOOK_TIMESTAMP_HEADER, timestamp.to_string());
+        if !webhook.secret.is_empty() {
+            request = request.header(
+                WEBHOOK_SIGNATURE_HEADER,
+                format!(
+                    "sha256={}",
+                    sign_webhook_body(&webhook.secret, timestamp, &body)
+                ),
+            );
+        }
+
+        let result = match request.body(body.clone()).send().await {
+            Ok(response) => response.error_for_status().map(|_| ()),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt >= max_retries => return Err(e.into()),
+            Err(_) => {
+                sleep(retry_backoff(attempt)).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+// Send everything pending for the webhook, stops at the first batch that
+// still fails after the retries and keeps it for the next flush
+async fn flush_webhook(manager: &WebhookManager, http_client: &Client, webhook: MqttWebhook) {
+    let batch_size = broker_config().mqtt_webhook.batch_size.max(1);
+    loop {
+        let events = manager.take_batch(&webhook.name, batch_size);
+        if events.is_empty() {
+            return;
+        }
+        let event_num = events.len();
+        match send_batch(http_client, &webhook, &events).await {
+            Ok(()) => manager.record_success(&webhook.name, event_num),
+            Err(e) => {
+                warn!(
+                    "Failed to deliver {} events to webhook {}, error: {}",
+                    event_num, webhook.name, e
+                );
+                manager.record_failure(&webhook.name, event_num, e.to_string());
+                manager.requeue_batch(&webhook.name, events);
+                return;
+            }
+        }
+    }
+}
+
+// Deliver the events of one webhook until it is removed, so that the retries of a
+// slow or failing endpoint do not hold back the others
+async fn start_webhook_delivery(
+    cache_manager: Arc<MQTTCacheManager>,
+    http_client: Client,
+    name: String,
+) {
+    let batch_interval_ms = broker_config().mqtt_webhook.batch_interval_ms.max(1);
+    let mut flush_ticker = interval(Duration::from_millis(batch_interval_ms));
+    loop {
+        flush_ticker.tick().await;
+        let Some(webhook) = cache_manager.webhook.get_webhook(&name) else {
+            return;
+        };
+        flush_webhook(&cache_manager.webhook, &http_client, webhook).await;
+    }
+}
+
+async fn reload_webhooks(cache_manager: &Arc<MQTTCacheManager>, client_pool: &Arc<ClientPool>) {
+    match WebhookStorage::new(client_pool.clone()).list().await {
+        Ok(webhooks) => cache_manager.webhook.set_webhooks(webhooks),
+        Err(e) => warn!("Failed to load webhooks, error: {}", e),
+    }
+}
+
+pub async fn start_webhook_thread(
+    cache_manager: Arc<MQTTCacheManager>,
+    client_pool: Arc<ClientPool>,
+    stop_send: broadcast::Sender<bool>,
+) {
+    let config = broker_config().mqtt_webhook.clone();
+    let http_client = match Client::builder()
+        .timeout(Duration::from_millis(config.request_timeout_ms))
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            warn!("Failed to build webhook http client, error: {}", e);
+            return;
+        }
+    };
+    info!("Webhook thread start successfully");
+
+    // (name, delivery task)
+    let mut delivery_tasks: HashMap<String, JoinHandle<()>> = HashMap::new();
+    let mut reload_ticker = interval(Duration::from_secs(WEBHOOK_RELOAD_INTERVAL_SECS));
+    let mut task_ticker = interval(Duration::from_millis(config.batch_interval_ms.max(1)));
+    let mut stop_rx = stop_send.subscribe();
+    loop {
+        select! {
+            val = stop_rx.recv() => {
+                if let Ok(flag) = val {
+                    if flag {
+                        break;
+                    }
+                }
+            }
+            _ = reload_ticker.tick() => {
+                reload_webhooks(&cache_manager, &client_pool).await;
+            }
+            _ = task_ticker.tick() => {
+                // tasks of removed webhooks have returned
+                delivery_tasks.retain(|_, task| !task.is_finished());
+                for webhook in cache_manager.webhook.list_webhooks() {
+                    if delivery_tasks.contains_key(&webhook.name) {
+                        continue;
+                    }
+                    let task = tokio::spawn(start_webhook_delivery(
+                        cache_manager.clone(),
+                        http_client.clone(),
+                        webhook.name.clone(),
+                    ));
+                    delivery_tasks.insert(webhook.name, task);
+                }
+            }
+        }
+    }
+
+    // a task may be sleeping between retries
+    for task in delivery_tasks.values() {
+        task.abort();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        is_match_webhook, retry_backoff, sign_webhook_body, validate_webhook, WebhookManager,
+        WEBHOOK_RETRY_BACKOFF_MS, WEBHOOK_RETRY_MAX_BACKOFF_MS,
+    };
+    use common_config::broker::broker_config;
+    use common_config::broker::{default_broker_config, init_broker_conf_by_config};
+    use metadata_struct::mqtt::webhook::{MqttWebhook, WebhookEventType};
+    use std::time::Duration;
+
+    fn build_webhook(name: &str, topic_filters: Vec<String>) -> MqttWebhook {
+        MqttWebhook {
+            cluster_name: "test".to_string(),
+            name: name.to_string(),
+            url: "http://127.0.0.1:8080/hook".to_string(),
+            secret: "secret".to_string(),
+            events: vec![
+                WebhookEventType::ClientConnected,
+                WebhookEventType::MessagePublish,
+            ],
+            topic_filters,
+            enable: true,
+            create_time: 0,
+        }
+    }
+
+    #[test]
+    fn is_match_webhook_test() {
+        let webhook = build_webhook("w1", vec!["sensor/+/temp".to_string()]);
+        assert!(is_match_webhook(
+            &webhook,
+            WebhookEventType::ClientConnected,
+            None
+        ));
+        assert!(!is_match_webhook(
+            &webhook,
+            WebhookEventType::ClientDisconnected,
+            None
+        ));
+        assert!(is_match_webhook(
+            &webhook,
+            WebhookEventType::MessagePublish,
+            Some("sensor/1/temp")
+        ));
+        assert!(!is_match_webhook(
+            &webhook,
+            WebhookEventType::MessagePublish,
+            Some("sensor/1/humidity")
+        ));
+
+        let webhook = build_webhook("w1", Vec::new());
+        assert!(is_match_webhook(
+            &webhook,
+            WebhookEventType::MessagePublish,
+            Some("sensor/1/humidity")
+        ));
+    }
+
+    #[test]
+    fn validate_webhook_test() {
+        assert!(validate_webhook(&build_webhook("w1", Vec::new())).is_ok());
+        assert!(validate_webhook(&build_webhook("", Vec::new())).is_err());
+
+        let mut webhook = build_webhook("w1", Vec::new());
+        webhook.url = "127.0.0.1:8080".to_string();
+        assert!(validate_webhook(&webhook).is_err());
+
+        let mut webhook = build_webhook("w1", Vec::new());
+        webhook.events.clear();
+        assert!(validate_webhook(&webhook).is_err());
+    }
+
+    #[test]
+    fn sign_webhook_body_test() {
+        let signature = sign_webhook_body("secret", 1700000000000, b"{}");
+        assert_eq!(signature.len(), 64);
+        assert_eq!(signature, sign_webhook_body("secret", 1700000000000, b"{}"));
+        assert_ne!(signature, sign_webhook_body("other", 1700000000000, b"{}"));
+        assert_ne!(signature, sign_webhook_body("secret", 1700000000001, b"{}"));
+    }
+
+    #[test]
+    fn webhook_manager_test() {
+        init_broker_conf_by_config(default_broker_config());
+        let manager = WebhookManager::new();
+        manager.add_webhook(build_webhook("w1", vec!["t/#".to_string()]));
+        manager.add_webhook(build_webhook("w2", Vec::new()));
+
+        manager.report(WebhookEventType::MessagePublish, Some("t/1"), || "m1");
+        manager.report(WebhookEventType::MessagePublish, Some("x/1"), || "m2");
+        manager.report(WebhookEventType::SessionExpired, None, || -> &str {
+            panic!("no webhook wants session.expired")
+        });
+        assert_eq!(manager.pending_num("w1"), 1);
+        assert_eq!(manager.pending_num("w2"), 2);
+
+        let batch = manager.take_batch("w2", 1);
+        assert_eq!(batch.len(), 1);
+        assert_eq!(batch[0].data, "m1");
+        assert_eq!(manager.pending_num("w2"), 1);
+
+        // a failed batch goes back to the head of the queue
+        manager.requeue_batch("w2", batch);
+        assert_eq!(manager.pending_num("w2"), 2);
+        assert_eq!(manager.take_batch("w2", 1)[0].data, "m1");
+
+        manager.set_webhooks(vec![build_webhook("w2", Vec::new())]);
+        assert_eq!(manager.pending_num("w1"), 0);
+        assert_eq!(manager.list_webhooks().len(), 1);
+
+        // the events of a removed webhook are not queued again
+        let batch = manager.take_batch("w2", 1);
+        manager.remove_webhook("w2");
+        manager.requeue_batch("w2", batch);
+        assert_eq!(manager.pending_num("w2"), 0);
+    }
+
+    #[test]
+    fn requeue_batch_test() {
+        init_broker_conf_by_config(default_broker_config());
+        let capacity = broker_config().mqtt_webhook.queue_capacity;
+        let manager = WebhookManager::new();
+        manager.add_webhook(build_webhook("w1", Vec::new()));
+
+        for i in 0..capacity {
+            manager.report(WebhookEventType::MessagePublish, Some("t/1"), || i);
+        }
+        let batch = manager.take_batch("w1", 3);
+        for i in 0..2 {
+            manager.report(WebhookEventType::MessagePublish, Some("t/1"), || {
+                capacity + i
+            });
+        }
+
+        // only the newest event of the batch still fits
+        manager.requeue_batch("w1", batch);
+        assert_eq!(manager.pending_num("w1"), capacity);
+        assert_eq!(manager.get_health("w1").dropped, 2);
+        assert_eq!(manager.take_batch("w1", 1)[0].data, 2);
+    }
+
+    #[test]
+    fn retry_backoff_test() {
+        assert_eq!(
+            retry_backoff(0),
+            Duration::from_millis(WEBHOOK_RETRY_BACKOFF_MS)
+        );
+        assert_eq!(
+            retry_backoff(2),
+            Duration::from_millis(WEBHOOK_RETRY_BACKOFF_MS * 4)
+        );
+        assert_eq!(
+            retry_backoff(64),
+            Duration::from_millis(WEBHOOK_RETRY_MAX_BACKOFF_MS)
+        );
+        assert_eq!(
+            retry_backoff(u32::MAX),
+            Duration::from_millis(WEBHOOK_RETRY_MAX_BACKOFF_MS)
+        );
+    }
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 #![allow(clippy::result_large_err)]
+pub mod alarm;
 pub mod bridge;
 pub mod broker;
 pub mod common;
@@ -14,6 +14,9 @@
 
 use crate::common::types::ResultMqttBrokerError;
 use crate::handler::cache::MQTTCacheManager;
+use crate::security::password::{
+    hash_user_password, placement_password_config, validate_password_config,
+};
 use crate::storage::user::UserStorage;
 use common_config::broker::broker_config;
 use grpc_clients::pool::ClientPool;
@@ -25,7 +28,12 @@ pub async fn init_system_user(
     client_pool: &Arc<ClientPool>,
 ) -> ResultMqttBrokerError {
     let conf = broker_config();
-    let system_user_info = MqttUser {
+    let password_config = placement_password_config();
+    if let Some(password_config) = &password_config {
+        validate_password_config(password_config)?;
+    }
+
+    let mut system_user_info = MqttUser {
         username: conf.mqtt_runtime.default_user.clone(),
         password: conf.mqtt_runtime.default_password.clone(),
         salt: None,
@@ -38,6 +46,9 @@ pub async fn init_system_user(
     if res.is_some() {
         return Ok(());
     }
+    if let Some(password_config) = password_config {
+        hash_user_password(&password_config, &mut system_user_info)?;
+    }
     user_storage.save_user(system_user_info.clone()).await?;
     cache_manager.add_user(system_user_info);
     Ok(())
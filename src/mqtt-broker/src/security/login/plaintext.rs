@@ -15,9 +15,12 @@
 use super::Authentication;
 use crate::handler::cache::MQTTCacheManager;
 use crate::handler::error::MqttBrokerError;
+use crate::security::password::{hash_user_password, is_hashed_password, verify_user_password};
 use crate::security::storage::storage_trait::AuthStorageAdapter;
 use axum::async_trait;
+use common_config::security::PasswordConfig;
 use std::sync::Arc;
+use tracing::warn;
 
 pub struct Plaintext {
     username: String,
@@ -38,6 +41,7 @@ pub fn new(username: String, password: String, cache_manager: Arc<MQTTCacheManag
 pub async fn plaintext_check_login(
     driver: &Arc<dyn AuthStorageAdapter + Send + 'static + Sync>,
     cache_manager: &Arc<MQTTCacheManager>,
+    password_config: &PasswordConfig,
     username: &str,
     password: &str,
 ) -> Result<bool, MqttBrokerError> {
@@ -46,22 +50,64 @@ pub async fn plaintext_check_login(
         password.to_owned(),
         cache_manager.clone(),
     );
-    match plaintext.apply().await {
-        Ok(flag) => {
-            if flag {
-                return Ok(true);
-            }
-        }
+    let flag = match plaintext.apply().await {
+        Ok(flag) => flag,
         Err(e) => {
             // If the user does not exist, try to get the user information from the storage layer
             if e.to_string() == MqttBrokerError::UserDoesNotExist.to_string() {
-                return try_get_check_user_by_driver(driver, cache_manager, username, password)
-                    .await;
+                try_get_check_user_by_driver(driver, cache_manager, username, password).await?
+            } else {
+                return Err(e);
             }
-            return Err(e);
         }
+    };
+
+    if flag {
+        try_migrate_plaintext_password(driver, cache_manager, password_config, username, password)
+            .await;
     }
-    Ok(false)
+    Ok(flag)
+}
+
+// Users created before password hashing was enabled are rehashed on their next successful login
+async fn try_migrate_plaintext_password(
+    driver: &Arc<dyn AuthStorageAdapter + Send + 'static + Sync>,
+    cache_manager: &Arc<MQTTCacheManager>,
+    password_config: &PasswordConfig,
+    username: &str,
+    password: &str,
+) {
+    if password_config.algorithm == "plain" {
+        return;
+    }
+
+    let mut user = if let Some(user) = cache_manager.user_info.get(username) {
+        user.clone()
+    } else {
+        return;
+    };
+
+    if is_hashed_password(&user.password) {
+        return;
+    }
+
+    user.password = password.to_owned();
+    if let Err(e) = hash_user_password(password_config, &mut user) {
+        warn!(
+            "Failed to hash password for user {}, error: {}",
+            username, e
+        );
+        return;
+    }
+
+    if let Err(e) = driver.update_user(user.clone()).await {
+        warn!(
+            "Failed to migrate plaintext password for user {}, error: {}",
+            username, e
+        );
+        return;
+    }
+    cache_manager.add_user(user);
 }
 
 async fn try_get_check_user_by_driver(
@@ -91,7 +137,7 @@ async fn try_get_check_user_by_driver(
 impl Authentication for Plaintext {
     async fn apply(&self) -> Result<bool, MqttBrokerError> {
         if let Some(user) = self.cache_manager.user_info.get(&self.username) {
-            return Ok(user.password == self.password);
+            return verify_user_password(&user, &self.password);
         }
         return Err(MqttBrokerError::UserDoesNotExist);
     }
@@ -107,6 +153,8 @@ mod test {
     use crate::common::tool::test_build_mqtt_cache_manager;
     use crate::handler::cache::MQTTCacheManager;
     use crate::security::login::Authentication;
+    use crate::security::password::hash_user_password;
+    use common_config::security::PasswordConfig;
 
     #[tokio::test]
     pub async fn plaintext_test() {
@@ -137,4 +185,30 @@ pub async fn plaintext_test() {
         let res = pt.apply().await.unwrap();
         assert!(!res);
     }
+
+    #[tokio::test]
+    pub async fn hashed_password_test() {
+        let cache_manager: Arc<MQTTCacheManager> = test_build_mqtt_cache_manager().await;
+        let username = "lobo_hash".to_string();
+        let password = "pwd123".to_string();
+        let mut user = MqttUser {
+            username: username.clone(),
+            password: password.clone(),
+            salt: None,
+            is_superuser: false,
+        };
+        let config = PasswordConfig {
+            algorithm: "bcrypt".to_string(),
+            salt_rounds: Some(4),
+            ..Default::default()
+        };
+        hash_user_password(&config, &mut user).unwrap();
+        cache_manager.add_user(user);
+
+        let pt = Plaintext::new(username.clone(), password, cache_manager.clone());
+        assert!(pt.apply().await.unwrap());
+
+        let pt = Plaintext::new(username, "pwd1111".to_string(), cache_manager.clone());
+        assert!(!pt.apply().await.unwrap());
+    }
 }
@@ -23,6 +23,7 @@
 use crate::security::login::plaintext::plaintext_check_login;
 use crate::security::login::postgresql::postgresql_check_login;
 use crate::security::login::redis::redis_check_login;
+use crate::security::password::{hash_user_password, placement_password_config};
 use crate::security::storage::storage_trait::AuthStorageAdapter;
 use crate::security::storage::AuthType;
 use crate::subscribe::common::get_sub_topic_name_list;
@@ -39,6 +40,7 @@
 use metadata_struct::acl::mqtt_blacklist::MqttAclBlackList;
 use metadata_struct::mqtt::connection::MQTTConnection;
 use metadata_struct::mqtt::user::MqttUser;
+use protocol::meta::meta_service_mqtt::ReadConsistency;
 use protocol::mqtt::common::{ConnectProperties, Login, QoS, Subscribe};
 use std::collections::HashSet;
 use std::net::SocketAddr;
@@ -52,6 +54,7 @@
 
 pub mod auth;
 pub mod login;
+pub mod password;
 pub mod storage;
 
 #[derive(Clone)]
@@ -62,9 +65,30 @@ pub struct AuthDriver {
 
 impl AuthDriver {
     pub fn new(cache_manager: Arc<MQTTCacheManager>, client_pool: Arc<ClientPool>) -> AuthDriver {
+        Self::new_with_read_consistency(cache_manager, client_pool, ReadConsistency::Linearizable)
+    }
+
+    // Listing calls made through this driver may be served by any meta node without
+    // confirming it is up to date, which is enough for admin and dashboard views.
+    pub fn new_with_stale_read(
+        cache_manager: Arc<MQTTCacheManager>,
+        client_pool: Arc<ClientPool>,
+    ) -> AuthDriver {
+        Self::new_with_read_consistency(cache_manager, client_pool, ReadConsistency::Stale)
+    }
+
+    fn new_with_read_consistency(
+        cache_manager: Arc<MQTTCacheManager>,
+        client_pool: Arc<ClientPool>,
+        read_consistency: ReadConsistency,
+    ) -> AuthDriver {
         let conf = broker_config();
 
-        let driver = match build_driver(client_pool.clone(), &conf.mqtt_auth_config.authn_config) {
+        let driver = match build_driver(
+            client_pool.clone(),
+            &conf.mqtt_auth_config.authn_config,
+            read_consistency,
+        ) {
             Ok(driver) => driver,
             Err(e) => {
                 panic!("{}, auth config:{:?}", e, conf.mqtt_auth_config);
@@ -140,6 +164,7 @@ pub async fn auth_login_check(
                                 plaintext_check_login(
                                     &self.driver,
                                     &self.cache_manager,
+                                    password_config,
                                     &info.username,
                                     &info.password,
                                 )
@@ -263,11 +288,14 @@ pub async fn read_all_blacklist(&self) -> Result<Vec<MqttAclBlackList>, MqttBrok
         self.driver.read_all_blacklist().await
     }
 
-    pub async fn save_user(&self, user_info: MqttUser) -> ResultMqttBrokerError {
+    pub async fn save_user(&self, mut user_info: MqttUser) -> ResultMqttBrokerError {
         let username = user_info.username.clone();
         if let Some(_user) = self.cache_manager.user_info.get(&username) {
             return Err(MqttBrokerError::UserAlreadyExist);
         }
+        if let Some(password_config) = placement_password_config() {
+            hash_user_password(&password_config, &mut user_info)?;
+        }
         self.cache_manager.add_user(user_info.clone());
         self.driver.save_user(user_info).await
     }
@@ -355,12 +383,13 @@ pub async fn update_blacklist_cache(&self) -> ResultMqttBrokerError {
 pub fn build_driver(
     client_pool: Arc<ClientPool>,
     authn_config: &AuthnConfig,
+    read_consistency: ReadConsistency,
 ) -> Result<Arc<dyn AuthStorageAdapter + Send + 'static + Sync>, MqttBrokerError> {
     match authn_config.authn_type.as_str() {
         "password_based" => {
             if let Some(password_based_config) = &authn_config.password_based_config {
                 let storage_config = &password_based_config.storage_config;
-                build_storage_driver(client_pool, storage_config)
+                build_storage_driver(client_pool, storage_config, read_consistency)
             } else {
                 Err(MqttBrokerError::PasswordConfigNotFound)
             }
@@ -368,7 +397,7 @@ pub fn build_driver(
         "jwt" => {
             // JWT authentication doesn't need specific storage adapter (pure token validation)
             // But we still create a minimal adapter to maintain API compatibility
-            let driver = PlacementAuthStorageAdapter::new(client_pool);
+            let driver = PlacementAuthStorageAdapter::new(client_pool, read_consistency);
             Ok(Arc::new(driver))
         }
         _ => Err(MqttBrokerError::UnsupportedAuthType(
@@ -380,6 +409,7 @@ pub fn build_driver(
 fn build_storage_driver(
     client_pool: Arc<ClientPool>,
     storage_config: &StorageConfig,
+    read_consistency: ReadConsistency,
 ) -> Result<Arc<dyn AuthStorageAdapter + Send + 'static + Sync>, MqttBrokerError> {
     let storage_type = AuthType::from_str(&storage_config.storage_type)
         .map_err(|_| MqttBrokerError::UnavailableStorageType)?;
@@ -387,7 +417,7 @@ fn build_storage_driver(
     match storage_type {
         AuthType::Placement => {
             // Placement adapter only needs client_pool parameter
-            let driver = PlacementAuthStorageAdapter::new(client_pool);
+            let driver = PlacementAuthStorageAdapter::new(client_pool, read_consistency);
             Ok(Arc::new(driver))
         }
         AuthType::Mysql => {
@@ -0,0 +1,348 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::handler::error::MqttBrokerError;
+use argon2::password_hash::rand_core::OsRng;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use common_config::broker::broker_config;
+use common_config::security::PasswordConfig;
+use hmac::Hmac;
+use metadata_struct::mqtt::user::MqttUser;
+use pbkdf2::pbkdf2;
+use sha1::{Digest, Sha1};
+use sha2::{Sha256, Sha512};
+
+const BCRYPT_PREFIXES: [&str; 3] = ["$2a$", "$2b$", "$2y$"];
+const ARGON2_PREFIX: &str = "$argon2";
+const PBKDF2_PREFIX: &str = "$pbkdf2-";
+// $digest-<algorithm>$<salt position>$<hex digest>
+const DIGEST_PREFIX: &str = "$digest-";
+
+/// Password configuration of the built-in user store, None if authentication uses another storage.
+pub fn placement_password_config() -> Option<PasswordConfig> {
+    let conf = broker_config();
+    conf.mqtt_auth_config
+        .authn_config
+        .password_based_config
+        .as_ref()
+        .filter(|config| config.storage_config.storage_type == "placement")
+        .map(|config| config.password_config.clone())
+}
+
+/// Hash the password of a user stored in the built-in (placement) user store.
+/// The stored value is self-describing, so users hashed with a previous algorithm
+/// can still be verified after the configuration changes.
+pub fn hash_user_password(
+    password_config: &PasswordConfig,
+    user: &mut MqttUser,
+) -> Result<(), MqttBrokerError> {
+    match password_config.algorithm.as_str() {
+        "plain" => {}
+        "md5" | "sha" | "sha1" | "sha256" | "sha512" => {
+            let salt_position = password_config
+                .salt_position
+                .as_deref()
+                .unwrap_or("disable");
+            let salt = match salt_position {
+                "prefix" | "suffix" => SaltString::generate(&mut OsRng).as_str().to_string(),
+                _ => String::new(),
+            };
+            let hash = digest_hex(
+                &password_config.algorithm,
+                &salt_password(salt_position, &user.password, &salt),
+            )?;
+            user.password = format!(
+                "{}{}${}${}",
+                DIGEST_PREFIX, password_config.algorithm, salt_position, hash
+            );
+            user.salt = if salt.is_empty() { None } else { Some(salt) };
+        }
+        "bcrypt" => {
+            let cost = password_config.salt_rounds.unwrap_or(bcrypt::DEFAULT_COST);
+            user.password = bcrypt::hash(&user.password, cost)
+                .map_err(|e| MqttBrokerError::PasswordVerificationError(e.to_string()))?;
+            user.salt = None;
+        }
+        "pbkdf2" => {
+            let mac_fun = password_config.mac_fun.as_deref().unwrap_or("sha256");
+            let iterations = password_config.iterations.unwrap_or(4096);
+            let dk_length = password_config.dk_length.unwrap_or(32) as usize;
+            let salt = SaltString::generate(&mut OsRng).as_str().to_string();
+            let hash = pbkdf2_hex(mac_fun, &user.password, &salt, iterations, dk_length)?;
+            user.password = format!("{}{}${}${}", PBKDF2_PREFIX, mac_fun, iterations, hash);
+            user.salt = Some(salt);
+        }
+        "argon2" => {
+            let salt = SaltString::generate(&mut OsRng);
+            user.password = Argon2::default()
+                .hash_password(user.password.as_bytes(), &salt)
+                .map_err(|e| MqttBrokerError::PasswordVerificationError(e.to_string()))?
+                .to_string();
+            user.salt = None;
+        }
+        _ => {
+            return Err(MqttBrokerError::UnsupportedHashAlgorithm(
+                password_config.algorithm.clone(),
+            ))
+        }
+    }
+    Ok(())
+}
+
+pub fn is_hashed_password(stored: &str) -> bool {
+    is_bcrypt_hash(stored)
+        || stored.starts_with(ARGON2_PREFIX)
+        || stored.starts_with(PBKDF2_PREFIX)
+        || stored.starts_with(DIGEST_PREFIX)
+}
+
+/// Check that the configured algorithm of the built-in user store can hash
+/// passwords, so a typo is reported at startup instead of on the first user.
+pub fn validate_password_config(password_config: &PasswordConfig) -> Result<(), MqttBrokerError> {
+    match password_config.algorithm.as_str() {
+        "plain" | "md5" | "sha" | "sha1" | "sha256" | "sha512" | "bcrypt" | "argon2" => Ok(()),
+        "pbkdf2" => {
+            let mac_fun = password_config.mac_fun.as_deref().unwrap_or("sha256");
+            match mac_fun {
+                "sha1" | "sha256" | "sha512" => Ok(()),
+                _ => Err(MqttBrokerError::UnsupportedMacFunction(mac_fun.to_string())),
+            }
+        }
+        _ => Err(MqttBrokerError::UnsupportedHashAlgorithm(
+            password_config.algorithm.clone(),
+        )),
+    }
+}
+
+/// Verify the input password against the stored user, falling back to a plaintext
+/// comparison for users created before hashing was enabled.
+pub fn verify_user_password(user: &MqttUser, password: &str) -> Result<bool, MqttBrokerError> {
+    let stored = user.password.as_str();
+
+    if stored.starts_with(ARGON2_PREFIX) {
+        let hash = PasswordHash::new(stored)
+            .map_err(|e| MqttBrokerError::PasswordVerificationError(e.to_string()))?;
+        return Ok(Argon2::default()
+            .verify_password(password.as_bytes(), &hash)
+            .is_ok());
+    }
+
+    if let Some(raw) = stored.strip_prefix(PBKDF2_PREFIX) {
+        let parts: Vec<&str> = raw.splitn(3, '$').collect();
+        if parts.len() != 3 {
+            return Err(MqttBrokerError::PasswordVerificationError(format!(
+                "invalid pbkdf2 hash for user {}",
+                user.username
+            )));
+        }
+        let iterations = parts[1]
+            .parse::<u32>()
+            .map_err(|e| MqttBrokerError::PasswordVerificationError(e.to_string()))?;
+        let salt = user.salt.clone().unwrap_or_default();
+        let hash = pbkdf2_hex(parts[0], password, &salt, iterations, parts[2].len() / 2)?;
+        return Ok(constant_time_eq(hash.as_bytes(), parts[2].as_bytes()));
+    }
+
+    if let Some(raw) = stored.strip_prefix(DIGEST_PREFIX) {
+        let parts: Vec<&str> = raw.splitn(3, '$').collect();
+        if parts.len() != 3 {
+            return Err(MqttBrokerError::PasswordVerificationError(format!(
+                "invalid digest hash for user {}",
+                user.username
+            )));
+        }
+        let salt = user.salt.clone().unwrap_or_default();
+        let hash = digest_hex(parts[0], &salt_password(parts[1], password, &salt))?;
+        return Ok(constant_time_eq(hash.as_bytes(), parts[2].as_bytes()));
+    }
+
+    if is_bcrypt_hash(stored) {
+        return bcrypt::verify(password, stored)
+            .map_err(|e| MqttBrokerError::PasswordVerificationError(e.to_string()));
+    }
+
+    Ok(constant_time_eq(stored.as_bytes(), password.as_bytes()))
+}
+
+// Compare without returning early on the first different byte, so the time taken
+// does not reveal how much of a hash matched.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn salt_password(salt_position: &str, password: &str, salt: &str) -> String {
+    match salt_position {
+        "prefix" => format!("{}{}", salt, password),
+        "suffix" => format!("{}{}", password, salt),
+        _ => password.to_string(),
+    }
+}
+
+fn digest_hex(algorithm: &str, input: &str) -> Result<String, MqttBrokerError> {
+    let hash = match algorithm {
+        "md5" => format!("{:x}", md5::compute(input.as_bytes())),
+        "sha" | "sha1" => format!("{:x}", Sha1::digest(input.as_bytes())),
+        "sha256" => format!("{:x}", Sha256::digest(input.as_bytes())),
+        "sha512" => format!("{:x}", Sha512::digest(input.as_bytes())),
+        _ => {
+            return Err(MqttBrokerError::UnsupportedHashAlgorithm(
+                algorithm.to_string(),
+            ))
+        }
+    };
+    Ok(hash)
+}
+
+fn is_bcrypt_hash(stored: &str) -> bool {
+    BCRYPT_PREFIXES
+        .iter()
+        .any(|prefix| stored.starts_with(prefix))
+}
+
+fn pbkdf2_hex(
+    mac_fun: &str,
+    password: &str,
+    salt: &str,
+    iterations: u32,
+    dk_length: usize,
+) -> Result<String, MqttBrokerError> {
+    let mut derived_key = vec![0u8; dk_length];
+    let res = match mac_fun {
+        "sha1" => pbkdf2::<Hmac<Sha1>>(
+            password.as_bytes(),
+            salt.as_bytes(),
+            iterations,
+            &mut derived_key,
+        ),
+        "sha256" => pbkdf2::<Hmac<Sha256>>(
+            password.as_bytes(),
+            salt.as_bytes(),
+            iterations,
+            &mut derived_key,
+        ),
+        "sha512" => pbkdf2::<Hmac<Sha512>>(
+            password.as_bytes(),
+            salt.as_bytes(),
+            iterations,
+            &mut derived_key,
+        ),
+        _ => return Err(MqttBrokerError::UnsupportedMacFunction(mac_fun.to_string())),
+    };
+    res.map_err(|e| MqttBrokerError::PasswordVerificationError(e.to_string()))?;
+    Ok(hex::encode(derived_key))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        constant_time_eq, hash_user_password, is_hashed_password, validate_password_config,
+        verify_user_password,
+    };
+    use common_config::security::PasswordConfig;
+    use metadata_struct::mqtt::user::MqttUser;
+
+    fn build_user(password: &str) -> MqttUser {
+        MqttUser {
+            username: "lobo".to_string(),
+            password: password.to_string(),
+            salt: None,
+            is_superuser: false,
+        }
+    }
+
+    #[test]
+    fn plain_password_test() {
+        let mut user = build_user("pwd123");
+        hash_user_password(&PasswordConfig::default(), &mut user).unwrap();
+        assert_eq!(user.password, "pwd123");
+        assert!(!is_hashed_password(&user.password));
+        assert!(verify_user_password(&user, "pwd123").unwrap());
+        assert!(!verify_user_password(&user, "pwd1111").unwrap());
+    }
+
+    #[test]
+    fn hashed_password_test() {
+        for (algorithm, salt_position) in [
+            ("bcrypt", "disable"),
+            ("pbkdf2", "disable"),
+            ("argon2", "disable"),
+            ("md5", "prefix"),
+            ("sha", "suffix"),
+            ("sha256", "prefix"),
+            ("sha512", "suffix"),
+        ] {
+            let config = PasswordConfig {
+                algorithm: algorithm.to_string(),
+                salt_position: Some(salt_position.to_string()),
+                salt_rounds: Some(4),
+                iterations: Some(10),
+                ..Default::default()
+            };
+            let mut user = build_user("pwd123");
+            hash_user_password(&config, &mut user).unwrap();
+            assert_ne!(user.password, "pwd123");
+            assert!(is_hashed_password(&user.password));
+            assert!(verify_user_password(&user, "pwd123").unwrap());
+            assert!(!verify_user_password(&user, "pwd1111").unwrap());
+
+            // the same password must not produce the same hash for two users
+            let mut other = build_user("pwd123");
+            hash_user_password(&config, &mut other).unwrap();
+            assert_ne!(user.password, other.password);
+        }
+    }
+
+    #[test]
+    fn unsalted_digest_test() {
+        let config = PasswordConfig {
+            algorithm: "sha256".to_string(),
+            ..Default::default()
+        };
+        let mut user = build_user("pwd123");
+        hash_user_password(&config, &mut user).unwrap();
+        assert!(user.salt.is_none());
+        assert!(verify_user_password(&user, "pwd123").unwrap());
+        assert!(!verify_user_password(&user, "pwd1111").unwrap());
+    }
+
+    #[test]
+    fn unsupported_algorithm_test() {
+        let config = PasswordConfig {
+            algorithm: "sha384".to_string(),
+            ..Default::default()
+        };
+        let mut user = build_user("pwd123");
+        assert!(hash_user_password(&config, &mut user).is_err());
+        assert!(validate_password_config(&config).is_err());
+
+        let config = PasswordConfig {
+            algorithm: "pbkdf2".to_string(),
+            mac_fun: Some("md5".to_string()),
+            ..Default::default()
+        };
+        assert!(validate_password_config(&config).is_err());
+        assert!(validate_password_config(&PasswordConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn constant_time_eq_test() {
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abd"));
+        assert!(!constant_time_eq(b"abc", b"abcd"));
+    }
+}
@@ -24,37 +24,51 @@
 use metadata_struct::acl::mqtt_acl::MqttAcl;
 use metadata_struct::acl::mqtt_blacklist::MqttAclBlackList;
 use metadata_struct::mqtt::user::MqttUser;
+use protocol::meta::meta_service_mqtt::ReadConsistency;
 use std::sync::Arc;
 
 pub struct PlacementAuthStorageAdapter {
     client_pool: Arc<ClientPool>,
+    read_consistency: ReadConsistency,
 }
 
 impl PlacementAuthStorageAdapter {
-    pub fn new(client_pool: Arc<ClientPool>) -> PlacementAuthStorageAdapter {
-        PlacementAuthStorageAdapter { client_pool }
+    pub fn new(
+        client_pool: Arc<ClientPool>,
+        read_consistency: ReadConsistency,
+    ) -> PlacementAuthStorageAdapter {
+        PlacementAuthStorageAdapter {
+            client_pool,
+            read_consistency,
+        }
     }
 }
 
 #[async_trait]
 impl AuthStorageAdapter for PlacementAuthStorageAdapter {
     async fn read_all_user(&self) -> Result<DashMap<String, MqttUser>, MqttBrokerError> {
-        let user_storage = UserStorage::new(self.client_pool.clone());
+        let user_storage =
+            UserStorage::new_with_read_consistency(self.client_pool.clone(), self.read_consistency);
         return user_storage.user_list().await;
     }
 
     async fn read_all_acl(&self) -> Result<Vec<MqttAcl>, MqttBrokerError> {
-        let acl_storage = AclStorage::new(self.client_pool.clone());
+        let acl_storage =
+            AclStorage::new_with_read_consistency(self.client_pool.clone(), self.read_consistency);
         return acl_storage.list_acl().await;
     }
 
     async fn read_all_blacklist(&self) -> Result<Vec<MqttAclBlackList>, MqttBrokerError> {
-        let blacklist_storage = BlackListStorage::new(self.client_pool.clone());
+        let blacklist_storage = BlackListStorage::new_with_read_consistency(
+            self.client_pool.clone(),
+            self.read_consistency,
+        );
         return blacklist_storage.list_blacklist().await;
     }
 
     async fn get_user(&self, username: String) -> Result<Option<MqttUser>, MqttBrokerError> {
-        let user_storage = UserStorage::new(self.client_pool.clone());
+        let user_storage =
+            UserStorage::new_with_read_consistency(self.client_pool.clone(), self.read_consistency);
         return user_storage.get_user(username).await;
     }
 
@@ -63,6 +77,11 @@ async fn save_user(&self, user_info: MqttUser) -> ResultMqttBrokerError {
         return user_storage.save_user(user_info).await;
     }
 
+    async fn update_user(&self, user_info: MqttUser) -> ResultMqttBrokerError {
+        let user_storage = UserStorage::new(self.client_pool.clone());
+        return user_storage.update_user(user_info).await;
+    }
+
     async fn delete_user(&self, username: String) -> ResultMqttBrokerError {
         let user_storage = UserStorage::new(self.client_pool.clone());
         return user_storage.delete_user(username).await;
@@ -33,6 +33,13 @@ pub trait AuthStorageAdapter {
 
     async fn save_user(&self, user_info: MqttUser) -> ResultMqttBrokerError;
 
+    async fn update_user(&self, user_info: MqttUser) -> ResultMqttBrokerError {
+        Err(MqttBrokerError::CommonError(format!(
+            "update user {} is not supported by this storage",
+            user_info.username
+        )))
+    }
+
     async fn delete_user(&self, username: String) -> ResultMqttBrokerError;
 
     async fn save_acl(&self, acl: MqttAcl) -> ResultMqttBrokerError;
@@ -15,14 +15,16 @@
 use crate::bridge::manager::ConnectorManager;
 use crate::handler::cache::MQTTCacheManager;
 use crate::handler::inner::{
-    delete_session_by_req, send_last_will_message_by_req, update_cache_by_req,
+    delete_session_by_req, send_last_will_message_by_req, takeover_session_by_req,
+    update_cache_by_req,
 };
 use crate::subscribe::manager::SubscribeManager;
 use grpc_clients::pool::ClientPool;
+use network_server::common::connection_manager::ConnectionManager;
 use protocol::broker::broker_mqtt_inner::mqtt_broker_inner_service_server::MqttBrokerInnerService;
 use protocol::broker::broker_mqtt_inner::{
     DeleteSessionReply, DeleteSessionRequest, SendLastWillMessageReply, SendLastWillMessageRequest,
-    UpdateMqttCacheReply, UpdateMqttCacheRequest,
+    TakeoverSessionReply, TakeoverSessionRequest, UpdateMqttCacheReply, UpdateMqttCacheRequest,
 };
 use schema_register::schema::SchemaRegisterManager;
 use std::sync::Arc;
@@ -35,6 +37,7 @@ pub struct GrpcInnerServices {
     subscribe_manager: Arc<SubscribeManager>,
     schema_manager: Arc<SchemaRegisterManager>,
     client_pool: Arc<ClientPool>,
+    connection_manager: Arc<ConnectionManager>,
     message_storage_adapter: ArcStorageAdapter,
 }
 
@@ -45,6 +48,7 @@ pub fn new(
         connector_manager: Arc<ConnectorManager>,
         schema_manager: Arc<SchemaRegisterManager>,
         client_pool: Arc<ClientPool>,
+        connection_manager: Arc<ConnectionManager>,
         message_storage_adapter: ArcStorageAdapter,
     ) -> Self {
         GrpcInnerServices {
@@ -52,6 +56,7 @@ pub fn new(
             subscribe_manager,
             connector_manager,
             client_pool,
+            connection_manager,
             message_storage_adapter,
             schema_manager,
         }
@@ -103,4 +108,20 @@ async fn send_last_will_message(
         .map_err(|e| Status::internal(e.to_string()))
         .map(Response::new)
     }
+
+    async fn takeover_session(
+        &self,
+        request: Request<TakeoverSessionRequest>,
+    ) -> Result<Response<TakeoverSessionReply>, Status> {
+        let req = request.into_inner();
+        takeover_session_by_req(
+            &self.cache_manager,
+            &self.connection_manager,
+            &self.subscribe_manager,
+            &req,
+        )
+        .await
+        .map_err(|e| Status::internal(e.to_string()))
+        .map(Response::new)
+    }
 }
@@ -18,24 +18,38 @@
 use grpc_clients::meta::mqtt::call::{create_acl, delete_acl, list_acl};
 use grpc_clients::pool::ClientPool;
 use metadata_struct::acl::mqtt_acl::MqttAcl;
-use protocol::meta::meta_service_mqtt::{CreateAclRequest, DeleteAclRequest, ListAclRequest};
+use protocol::meta::meta_service_mqtt::{
+    CreateAclRequest, DeleteAclRequest, ListAclRequest, ReadConsistency,
+};
 
 use crate::common::types::ResultMqttBrokerError;
 use crate::handler::error::MqttBrokerError;
 
 pub struct AclStorage {
     client_pool: Arc<ClientPool>,
+    read_consistency: ReadConsistency,
 }
 
 impl AclStorage {
     pub fn new(client_pool: Arc<ClientPool>) -> Self {
-        AclStorage { client_pool }
+        Self::new_with_read_consistency(client_pool, ReadConsistency::Linearizable)
+    }
+
+    pub fn new_with_read_consistency(
+        client_pool: Arc<ClientPool>,
+        read_consistency: ReadConsistency,
+    ) -> Self {
+        AclStorage {
+            client_pool,
+            read_consistency,
+        }
     }
 
     pub async fn list_acl(&self) -> Result<Vec<MqttAcl>, MqttBrokerError> {
         let config = broker_config();
         let request = ListAclRequest {
             cluster_name: config.cluster_name.clone(),
+            read_consistency: self.read_consistency.into(),
         };
         let reply = list_acl(&self.client_pool, &config.get_meta_service_addr(), request).await?;
         let mut list = Vec::new();
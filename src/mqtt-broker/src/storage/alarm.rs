@@ -0,0 +1,133 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_config::broker::broker_config;
+use grpc_clients::meta::kv::call::{
+    placement_delete, placement_get_prefix, placement_lease_grant, placement_set,
+};
+use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::alarm::{MqttAlarmHistory, MqttAlarmRule};
+use protocol::meta::meta_service_kv::{
+    DeleteRequest, GetPrefixRequest, LeaseGrantRequest, SetRequest,
+};
+
+use crate::common::types::ResultMqttBrokerError;
+use crate::handler::error::MqttBrokerError;
+
+// Alarm rules and history live in the meta-service key-value store so that
+// every broker of the cluster sees the same rules.
+pub struct AlarmStorage {
+    client_pool: Arc<ClientPool>,
+}
+
+impl AlarmStorage {
+    pub fn new(client_pool: Arc<ClientPool>) -> Self {
+        AlarmStorage { client_pool }
+    }
+
+    pub async fn save_rule(&self, rule: &MqttAlarmRule) -> ResultMqttBrokerError {
+        let config = broker_config();
+        let request = SetRequest {
+            key: alarm_rule_key(&rule.cluster_name, &rule.name),
+            value: serde_json::to_string(rule)?,
+            lease_id: 0,
+        };
+        placement_set(&self.client_pool, &config.get_meta_service_addr(), request).await?;
+        Ok(())
+    }
+
+    pub async fn delete_rule(&self, name: &str) -> ResultMqttBrokerError {
+        let config = broker_config();
+        let request = DeleteRequest {
+            key: alarm_rule_key(&config.cluster_name, name),
+        };
+        placement_delete(&self.client_pool, &config.get_meta_service_addr(), request).await?;
+        Ok(())
+    }
+
+    pub async fn list_rule(&self) -> Result<Vec<MqttAlarmRule>, MqttBrokerError> {
+        let config = broker_config();
+        let request = GetPrefixRequest {
+            prefix: alarm_rule_prefix_key(&config.cluster_name),
+        };
+        let reply =
+            placement_get_prefix(&self.client_pool, &config.get_meta_service_addr(), request)
+                .await?;
+        let mut results = Vec::new();
+        for raw in reply.values {
+            results.push(serde_json::from_str::<MqttAlarmRule>(&raw)?);
+        }
+        Ok(results)
+    }
+
+    // History entries carry a lease so meta-service drops them once the
+    // retention has passed
+    pub async fn save_history(&self, history: &MqttAlarmHistory) -> ResultMqttBrokerError {
+        let config = broker_config();
+        let addrs = config.get_meta_service_addr();
+        let lease = placement_lease_grant(
+            &self.client_pool,
+            &addrs,
+            LeaseGrantRequest {
+                ttl: config.mqtt_system_monitor.alarm_history_retention_sec,
+                lease_id: 0,
+            },
+        )
+        .await?;
+
+        let request = SetRequest {
+            key: alarm_history_key(history),
+            value: serde_json::to_string(history)?,
+            lease_id: lease.lease_id,
+        };
+        placement_set(&self.client_pool, &addrs, request).await?;
+        Ok(())
+    }
+
+    pub async fn list_history(&self) -> Result<Vec<MqttAlarmHistory>, MqttBrokerError> {
+        let config = broker_config();
+        let request = GetPrefixRequest {
+            prefix: alarm_history_prefix_key(&config.cluster_name),
+        };
+        let reply =
+            placement_get_prefix(&self.client_pool, &config.get_meta_service_addr(), request)
+                .await?;
+        let mut results = Vec::new();
+        for raw in reply.values {
+            results.push(serde_json::from_str::<MqttAlarmHistory>(&raw)?);
+        }
+        Ok(results)
+    }
+}
+
+fn alarm_rule_key(cluster_name: &str, name: &str) -> String {
+    format!("/mqtt/alarm/rule/{cluster_name}/{name}")
+}
+
+fn alarm_rule_prefix_key(cluster_name: &str) -> String {
+    format!("/mqtt/alarm/rule/{cluster_name}/")
+}
+
+fn alarm_history_key(history: &MqttAlarmHistory) -> String {
+    format!(
+        "/mqtt/alarm/history/{}/{:020}/{}/{}",
+        history.cluster_name, history.create_time, history.broker_id, history.rule_name
+    )
+}
+
+fn alarm_history_prefix_key(cluster_name: &str) -> String {
+    format!("/mqtt/alarm/history/{cluster_name}/")
+}
@@ -0,0 +1,391 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{handler::audit_log::AuditLogEntry, storage::message::cluster_name};
+use common_base::error::common::CommonError;
+use metadata_struct::adapter::{read_config::ReadConfig, record::Record};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use storage_adapter::storage::{ArcStorageAdapter, ShardInfo};
+
+pub const AUDIT_LOG_SHARD_NAME: &str = "$audit-log";
+
+const AUDIT_LOG_READ_BATCH: u64 = 1000;
+
+static AUDIT_LOG_SHARD_READY: AtomicBool = AtomicBool::new(false);
+
+pub struct AuditLogStorage {
+    storage_adapter: ArcStorageAdapter,
+    namespace: String,
+}
+
+impl AuditLogStorage {
+    pub fn new(storage_adapter: ArcStorageAdapter) -> Self {
+        AuditLogStorage {
+            storage_adapter,
+            namespace: cluster_name(),
+        }
+    }
+
+    pub async fn append(&self, entry: &AuditLogEntry) -> Result<u64, CommonError> {
+        self.try_init_shard().await?;
+
+        let mut record = Record::build_byte(serde_json::to_vec(entry)?);
+        record.set_key(entry.action.to_string());
+        record.set_tags(vec![entry.actor.clone(), entry.target.clone()]);
+
+        self.storage_adapter
+            .write(
+                self.namespace.clone(),
+                AUDIT_LOG_SHARD_NAME.to_string(),
+                record,
+            )
+            .await
+    }
+
+    /// Read up to `max_num` entries starting at `offset`, in append order.
+    pub async fn read(
+        &self,
+        offset: u64,
+        max_num: u64,
+    ) -> Result<Vec<(u64, AuditLogEntry)>, CommonError> {
+        let mut results = Vec::new();
+        let mut next_offset = offset;
+        while (results.len() as u64) < max_num {
+            let mut read_config = ReadConfig::new();
+            read_config.max_record_num = AUDIT_LOG_READ_BATCH.min(max_num - results.len() as u64);
+            let records = self
+                .storage_adapter
+                .read_by_offset(
+                    self.namespace.clone(),
+                    AUDIT_LOG_SHARD_NAME.to_string(),
+                    next_offset,
+                    read_config,
+                )
+                .await?;
+
+            if records.is_empty() {
+                break;
+            }
+
+            for record in records {
+                let record_offset = record.offset.unwrap_or(next_offset);
+                next_offset = record_offset + 1;
+                if !record.crc32_check() {
+                    return Err(CommonError::CrcCheckByMessage);
+                }
+                let entry = serde_json::from_slice::<AuditLogEntry>(&record.data)?;
+                results.push((record_offset, entry));
+            }
+        }
+        Ok(results)
+    }
+
+    /// Read the latest `max_num` entries, in append order.
+    pub async fn read_latest(
+        &self,
+        max_num: u64,
+    ) -> Result<Vec<(u64, AuditLogEntry)>, CommonError> {
+        match self
+            .storage_adapter
+            .get_shard_end_offset(self.namespace.clone(), AUDIT_LOG_SHARD_NAME.to_string())
+            .await
+        {
+            Ok(end_offset) => self.read(end_offset.saturating_sub(max_num), max_num).await,
+            // e.g. the journal engine, scan the whole shard instead
+            Err(CommonError::NotSupportFeature(..)) => self.scan_latest(max_num).await,
+            Err(e) => Err(e),
+        }
+    }
+
+    // Read the shard from the start and keep the last `max_num` entries
+    async fn scan_latest(&self, max_num: u64) -> Result<Vec<(u64, AuditLogEntry)>, CommonError> {
+        let mut results = VecDeque::new();
+        if max_num == 0 {
+            return Ok(Vec::new());
+        }
+        let mut next_offset = 0;
+        loop {
+            let entries = self.read(next_offset, AUDIT_LOG_READ_BATCH).await?;
+            let Some((last_offset, _)) = entries.last() else {
+                break;
+            };
+            next_offset = last_offset + 1;
+            for entry in entries {
+                if results.len() as u64 >= max_num {
+                    results.pop_front();
+                }
+                results.push_back(entry);
+            }
+        }
+        Ok(results.into())
+    }
+
+    /// Read up to `max_num` entries created between `start_time` and `end_time`,
+    /// starting at the first record written at or after `start_time`.
+    pub async fn read_by_time(
+        &self,
+        start_time: u64,
+        end_time: u64,
+        max_num: u64,
+    ) -> Result<Vec<(u64, AuditLogEntry)>, CommonError> {
+        let Some(start) = self
+            .storage_adapter
+            .get_offset_by_timestamp(
+                self.namespace.clone(),
+                AUDIT_LOG_SHARD_NAME.to_string(),
+                start_time,
+            )
+            .await?
+        else {
+            return Ok(Vec::new());
+        };
+
+        let mut results = Vec::new();
+        let mut next_offset = start.offset;
+        while (results.len() as u64) < max_num {
+            let entries = self.read(next_offset, AUDIT_LOG_READ_BATCH).await?;
+            let Some((last_offset, _)) = entries.last() else {
+                break;
+            };
+            next_offset = last_offset + 1;
+            for (offset, entry) in entries {
+                if entry.create_time > end_time || results.len() as u64 >= max_num {
+                    return Ok(results);
+                }
+                if entry.create_time >= start_time {
+                    results.push((offset, entry));
+                }
+            }
+        }
+        Ok(results)
+    }
+
+    async fn try_init_shard(&self) -> Result<(), CommonError> {
+        if AUDIT_LOG_SHARD_READY.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+
+        let shards = self
+            .storage_adapter
+            .list_shard(self.namespace.clone(), AUDIT_LOG_SHARD_NAME.to_string())
+            .await?;
+        if shards.is_empty() {
+            self.storage_adapter
+                .create_shard(ShardInfo {
+                    namespace: self.namespace.clone(),
+                    shard_name: AUDIT_LOG_SHARD_NAME.to_string(),
+                    replica_num: 1,
+                })
+                .await?;
+        }
+        AUDIT_LOG_SHARD_READY.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::AuditLogStorage;
+    use crate::handler::audit_log::{AuditAction, AuditLogEntry};
+    use axum::async_trait;
+    use common_base::error::common::CommonError;
+    use common_config::broker::{default_broker_config, init_broker_conf_by_config};
+    use metadata_struct::adapter::{read_config::ReadConfig, record::Record};
+    use std::{collections::HashMap, sync::Arc};
+    use storage_adapter::memory::MemoryStorageAdapter;
+    use storage_adapter::storage::{
+        build_memory_storage_driver, ShardInfo, ShardOffset, StorageAdapter,
+    };
+
+    // A memory adapter that cannot report the end offset of a shard, like the journal engine
+    struct NoEndOffsetAdapter(MemoryStorageAdapter);
+
+    #[async_trait]
+    impl StorageAdapter for NoEndOffsetAdapter {
+        async fn create_shard(&self, shard: ShardInfo) -> Result<(), CommonError> {
+            self.0.create_shard(shard).await
+        }
+
+        async fn list_shard(
+            &self,
+            namespace: String,
+            shard_name: String,
+        ) -> Result<Vec<ShardInfo>, CommonError> {
+            self.0.list_shard(namespace, shard_name).await
+        }
+
+        async fn delete_shard(
+            &self,
+            namespace: String,
+            shard_name: String,
+        ) -> Result<(), CommonError> {
+            self.0.delete_shard(namespace, shard_name).await
+        }
+
+        async fn write(
+            &self,
+            namespace: String,
+            shard_name: String,
+            data: Record,
+        ) -> Result<u64, CommonError> {
+            self.0.write(namespace, shard_name, data).await
+        }
+
+        async fn batch_write(
+            &self,
+            namespace: String,
+            shard_name: String,
+            data: Vec<Record>,
+        ) -> Result<Vec<u64>, CommonError> {
+            self.0.batch_write(namespace, shard_name, data).await
+        }
+
+        async fn read_by_offset(
+            &self,
+            namespace: String,
+            shard_name: String,
+            offset: u64,
+            read_config: ReadConfig,
+        ) -> Result<Vec<Record>, CommonError> {
+            self.0
+                .read_by_offset(namespace, shard_name, offset, read_config)
+                .await
+        }
+
+        async fn read_by_tag(
+            &self,
+            namespace: String,
+            shard_name: String,
+            offset: u64,
+            tag: String,
+            read_config: ReadConfig,
+        ) -> Result<Vec<Record>, CommonError> {
+            self.0
+                .read_by_tag(namespace, shard_name, offset, tag, read_config)
+                .await
+        }
+
+        async fn read_by_key(
+            &self,
+            namespace: String,
+            shard_name: String,
+            offset: u64,
+            key: String,
+            read_config: ReadConfig,
+        ) -> Result<Vec<Record>, CommonError> {
+            self.0
+                .read_by_key(namespace, shard_name, offset, key, read_config)
+                .await
+        }
+
+        async fn get_offset_by_timestamp(
+            &self,
+            namespace: String,
+            shard_name: String,
+            timestamp: u64,
+        ) -> Result<Option<ShardOffset>, CommonError> {
+            self.0
+                .get_offset_by_timestamp(namespace, shard_name, timestamp)
+                .await
+        }
+
+        async fn get_shard_end_offset(
+            &self,
+            _namespace: String,
+            _shard_name: String,
+        ) -> Result<u64, CommonError> {
+            Err(CommonError::NotSupportFeature(
+                "NoEndOffsetAdapter".to_string(),
+                "get_shard_end_offset".to_string(),
+            ))
+        }
+
+        async fn get_offset_by_group(
+            &self,
+            group_name: String,
+        ) -> Result<Vec<ShardOffset>, CommonError> {
+            self.0.get_offset_by_group(group_name).await
+        }
+
+        async fn commit_offset(
+            &self,
+            group_name: String,
+            namespace: String,
+            offset: HashMap<String, u64>,
+        ) -> Result<(), CommonError> {
+            self.0.commit_offset(group_name, namespace, offset).await
+        }
+
+        async fn close(&self) -> Result<(), CommonError> {
+            self.0.close().await
+        }
+    }
+
+    async fn append_entries(storage: &AuditLogStorage, num: u64) {
+        for i in 0..num {
+            let entry = AuditLogEntry::new(
+                "admin",
+                "127.0.0.1",
+                AuditAction::CreateUser,
+                &format!("user-{i}"),
+            )
+            .with_after(Some(format!("user-{i}")));
+            let offset = storage.append(&entry).await.unwrap();
+            assert_eq!(offset, i);
+        }
+    }
+
+    #[tokio::test]
+    async fn audit_log_read_latest_without_end_offset_test() {
+        init_broker_conf_by_config(default_broker_config());
+        let storage = AuditLogStorage::new(Arc::new(Box::new(NoEndOffsetAdapter(
+            MemoryStorageAdapter::new(),
+        ))));
+        append_entries(&storage, 5).await;
+
+        let entries = storage.read_latest(2).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].0, 3);
+        assert_eq!(entries[1].1.target, "user-4");
+
+        assert_eq!(storage.read_latest(10).await.unwrap().len(), 5);
+        assert!(storage.read_latest(0).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn audit_log_append_read_test() {
+        init_broker_conf_by_config(default_broker_config());
+        let storage = AuditLogStorage::new(build_memory_storage_driver());
+        append_entries(&storage, 3).await;
+
+        let entries = storage.read(0, 10).await.unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[2].0, 2);
+        assert_eq!(entries[2].1.target, "user-2");
+
+        let entries = storage.read(1, 1).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].1.target, "user-1");
+
+        let entries = storage.read_latest(2).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].0, 1);
+        assert_eq!(entries[1].1.target, "user-2");
+
+        let entries = storage.read_by_time(0, u64::MAX, 2).await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].1.target, "user-0");
+    }
+}
@@ -19,7 +19,7 @@
 use grpc_clients::pool::ClientPool;
 use metadata_struct::acl::mqtt_blacklist::MqttAclBlackList;
 use protocol::meta::meta_service_mqtt::{
-    CreateBlacklistRequest, DeleteBlacklistRequest, ListBlacklistRequest,
+    CreateBlacklistRequest, DeleteBlacklistRequest, ListBlacklistRequest, ReadConsistency,
 };
 
 use crate::common::types::ResultMqttBrokerError;
@@ -27,17 +27,29 @@
 
 pub struct BlackListStorage {
     client_pool: Arc<ClientPool>,
+    read_consistency: ReadConsistency,
 }
 
 impl BlackListStorage {
     pub fn new(client_pool: Arc<ClientPool>) -> Self {
-        BlackListStorage { client_pool }
+        Self::new_with_read_consistency(client_pool, ReadConsistency::Linearizable)
+    }
+
+    pub fn new_with_read_consistency(
+        client_pool: Arc<ClientPool>,
+        read_consistency: ReadConsistency,
+    ) -> Self {
+        BlackListStorage {
+            client_pool,
+            read_consistency,
+        }
     }
 
     pub async fn list_blacklist(&self) -> Result<Vec<MqttAclBlackList>, MqttBrokerError> {
         let config = broker_config();
         let request = ListBlacklistRequest {
             cluster_name: config.cluster_name.clone(),
+            read_consistency: self.read_consistency.into(),
         };
         let reply =
             list_blacklist(&self.client_pool, &config.get_meta_service_addr(), request).await?;
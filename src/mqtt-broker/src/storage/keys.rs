@@ -13,8 +13,8 @@
 // limitations under the License.
 
 use crate::handler::{
-    flapping_detect::BanLog, slow_subscribe::SlowSubscribeData,
-    system_alarm::SystemAlarmEventMessage,
+    delivery_log::DeliveryEvent, flapping_detect::BanLog, slow_subscribe::SlowSubscribeData,
+    system_alarm::SystemAlarmEventMessage, topic_metrics::TopicMetricsRule,
 };
 
 pub fn system_event_key(alarm: &SystemAlarmEventMessage) -> String {
@@ -50,6 +50,63 @@ pub fn slow_sub_log_prefix_key() -> String {
     prefix_key("/slow_sub_log/".to_string())
 }
 
+// Offsets and times are zero padded so that the keys sort in numeric order
+pub fn delivery_message_key(event: &DeliveryEvent) -> String {
+    prefix_key(format!(
+        "/delivery_log/message/{}/{:020}/{:020}/{}/{}/{}",
+        event.topic_name,
+        event.offset,
+        event.create_time,
+        event.client_id,
+        event.sub_path,
+        event.event_type
+    ))
+}
+
+pub fn delivery_message_prefix_key(topic_name: &str, offset: u64) -> String {
+    prefix_key(format!("/delivery_log/message/{topic_name}/{offset:020}/"))
+}
+
+pub fn delivery_client_key(event: &DeliveryEvent) -> String {
+    prefix_key(format!(
+        "/delivery_log/client/{}/{:020}/{}/{:020}/{}/{}",
+        event.client_id,
+        event.create_time,
+        event.topic_name,
+        event.offset,
+        event.sub_path,
+        event.event_type
+    ))
+}
+
+pub fn delivery_time_key(event: &DeliveryEvent) -> String {
+    prefix_key(format!(
+        "/delivery_log/time/{:020}/{}/{}/{:020}/{}/{}",
+        event.create_time,
+        event.client_id,
+        event.topic_name,
+        event.offset,
+        event.sub_path,
+        event.event_type
+    ))
+}
+
+pub fn delivery_time_prefix_key() -> String {
+    prefix_key("/delivery_log/time/".to_string())
+}
+
+pub fn delivery_client_prefix_key(client_id: &str) -> String {
+    prefix_key(format!("/delivery_log/client/{client_id}/"))
+}
+
+pub fn topic_metrics_rule_key(rule: &TopicMetricsRule) -> String {
+    prefix_key(format!("/topic_metrics_rule/{}", rule.topic_filter))
+}
+
+pub fn topic_metrics_rule_prefix_key() -> String {
+    prefix_key("/topic_metrics_rule/".to_string())
+}
+
 fn prefix_key(key: String) -> String {
     format!("/broker/mqtt/{key}")
 }
@@ -15,19 +15,25 @@
 use std::sync::Arc;
 
 use broker_core::{
-    engine::{engine_prefix_list_by_broker, engine_save_by_broker},
+    engine::{
+        engine_delete_by_broker, engine_prefix_count_by_broker, engine_prefix_list_by_broker,
+        engine_prefix_list_limit_by_broker, engine_save_by_broker,
+    },
     rocksdb::RocksDBEngine,
 };
 use common_base::error::ResultCommonError;
 
 use crate::{
     handler::{
-        error::MqttBrokerError, flapping_detect::BanLog, slow_subscribe::SlowSubscribeData,
-        system_alarm::SystemAlarmEventMessage,
+        delivery_log::DeliveryEvent, error::MqttBrokerError, flapping_detect::BanLog,
+        slow_subscribe::SlowSubscribeData, system_alarm::SystemAlarmEventMessage,
+        topic_metrics::TopicMetricsRule,
     },
     storage::keys::{
-        ban_log_key, ban_log_prefix_key, slow_sub_log_key, slow_sub_log_prefix_key,
-        system_event_key, system_event_prefix_key,
+        ban_log_key, ban_log_prefix_key, delivery_client_key, delivery_client_prefix_key,
+        delivery_message_key, delivery_message_prefix_key, delivery_time_key,
+        delivery_time_prefix_key, slow_sub_log_key, slow_sub_log_prefix_key, system_event_key,
+        system_event_prefix_key, topic_metrics_rule_key, topic_metrics_rule_prefix_key,
     },
 };
 
@@ -89,4 +95,131 @@ pub async fn list_slow_sub_log(&self) -> Result<Vec<SlowSubscribeData>, MqttBrok
         }
         Ok(results)
     }
+
+    pub async fn save_topic_metrics_rule(&self, rule: &TopicMetricsRule) -> ResultCommonError {
+        engine_save_by_broker(
+            self.rocksdb_engine_handler.clone(),
+            topic_metrics_rule_key(rule),
+            rule,
+        )
+    }
+
+    pub async fn delete_topic_metrics_rule(&self, rule: &TopicMetricsRule) -> ResultCommonError {
+        engine_delete_by_broker(
+            self.rocksdb_engine_handler.clone(),
+            topic_metrics_rule_key(rule),
+        )
+    }
+
+    pub async fn list_topic_metrics_rule(&self) -> Result<Vec<TopicMetricsRule>, MqttBrokerError> {
+        let prefix_key = topic_metrics_rule_prefix_key();
+        let mut results = Vec::new();
+        for raw in engine_prefix_list_by_broker(self.rocksdb_engine_handler.clone(), prefix_key)? {
+            if let Ok(data) = serde_json::from_str::<TopicMetricsRule>(&raw.data) {
+                results.push(data);
+            }
+        }
+        Ok(results)
+    }
+
+    // Every event is indexed by message and by client so both timelines are a prefix scan,
+    // and by time so that retention only reads the oldest events
+    pub async fn save_delivery_event(&self, event: &DeliveryEvent) -> ResultCommonError {
+        engine_save_by_broker(
+            self.rocksdb_engine_handler.clone(),
+            delivery_message_key(event),
+            event,
+        )?;
+        engine_save_by_broker(
+            self.rocksdb_engine_handler.clone(),
+            delivery_client_key(event),
+            event,
+        )?;
+        engine_save_by_broker(
+            self.rocksdb_engine_handler.clone(),
+            delivery_time_key(event),
+            event,
+        )
+    }
+
+    pub async fn delete_delivery_event(&self, event: &DeliveryEvent) -> ResultCommonError {
+        engine_delete_by_broker(
+            self.rocksdb_engine_handler.clone(),
+            delivery_message_key(event),
+        )?;
+        engine_delete_by_broker(
+            self.rocksdb_engine_handler.clone(),
+            delivery_client_key(event),
+        )?;
+        engine_delete_by_broker(
+            self.rocksdb_engine_handler.clone(),
+            delivery_time_key(event),
+        )
+    }
+
+    pub async fn list_delivery_event_by_message(
+        &self,
+        topic_name: &str,
+        offset: u64,
+    ) -> Result<Vec<DeliveryEvent>, MqttBrokerError> {
+        let prefix_key = delivery_message_prefix_key(topic_name, offset);
+        let results = self
+            .list_delivery_event_by_prefix(prefix_key)?
+            .into_iter()
+            .filter(|event| event.topic_name == topic_name && event.offset == offset)
+            .collect();
+        Ok(results)
+    }
+
+    pub async fn list_delivery_event_by_client(
+        &self,
+        client_id: &str,
+    ) -> Result<Vec<DeliveryEvent>, MqttBrokerError> {
+        let prefix_key = delivery_client_prefix_key(client_id);
+        let results = self
+            .list_delivery_event_by_prefix(prefix_key)?
+            .into_iter()
+            .filter(|event| event.client_id == client_id)
+            .collect();
+        Ok(results)
+    }
+
+    // The oldest events first
+    pub async fn list_oldest_delivery_event(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<DeliveryEvent>, MqttBrokerError> {
+        let mut results = Vec::new();
+        for raw in engine_prefix_list_limit_by_broker(
+            self.rocksdb_engine_handler.clone(),
+            delivery_time_prefix_key(),
+            limit,
+        )? {
+            if let Ok(data) = serde_json::from_str::<DeliveryEvent>(&raw.data) {
+                results.push(data);
+            }
+        }
+        Ok(results)
+    }
+
+    pub async fn count_delivery_event(&self) -> Result<u64, MqttBrokerError> {
+        Ok(engine_prefix_count_by_broker(
+            self.rocksdb_engine_handler.clone(),
+            delivery_time_prefix_key(),
+        )?)
+    }
+
+    fn list_delivery_event_by_prefix(
+        &self,
+        prefix_key: String,
+    ) -> Result<Vec<DeliveryEvent>, MqttBrokerError> {
+        let mut results = Vec::new();
+        for raw in engine_prefix_list_by_broker(self.rocksdb_engine_handler.clone(), prefix_key)? {
+            if let Ok(data) = serde_json::from_str::<DeliveryEvent>(&raw.data) {
+                results.push(data);
+            }
+        }
+        results.sort_by_key(|event| event.create_time);
+        Ok(results)
+    }
 }
@@ -78,6 +78,15 @@ pub async fn read_topic_message(
         Ok(records)
     }
 
+    // The offset the next message of the topic will be written at
+    pub async fn get_topic_end_offset(&self, topic_name: &str) -> Result<u64, CommonError> {
+        let shard_name = topic_name;
+        let namespace = cluster_name();
+        self.storage_adapter
+            .get_shard_end_offset(namespace, shard_name.to_owned())
+            .await
+    }
+
     pub async fn get_group_offset(&self, group_id: &str) -> Result<u64, CommonError> {
         let offset_data = self
             .storage_adapter
@@ -13,13 +13,18 @@
 // limitations under the License.
 
 pub mod acl;
+pub mod alarm;
+pub mod audit_log;
 pub mod auto_subscribe;
 pub mod blacklist;
 pub mod connector;
 pub mod keys;
 pub mod local;
 pub mod message;
+pub mod packet_trace;
 pub mod schema;
 pub mod session;
+pub mod subscribe;
 pub mod topic;
 pub mod user;
+pub mod webhook;
@@ -0,0 +1,153 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{handler::packet_trace::PacketTraceRecord, storage::message::cluster_name};
+use common_base::error::common::CommonError;
+use metadata_struct::adapter::{read_config::ReadConfig, record::Record};
+use storage_adapter::storage::{ArcStorageAdapter, ShardInfo};
+
+const PACKET_TRACE_READ_BATCH: u64 = 1000;
+
+pub fn packet_trace_shard_name(trace_name: &str) -> String {
+    format!("$trace-{trace_name}")
+}
+
+pub struct PacketTraceStorage {
+    storage_adapter: ArcStorageAdapter,
+    namespace: String,
+}
+
+impl PacketTraceStorage {
+    pub fn new(storage_adapter: ArcStorageAdapter) -> Self {
+        PacketTraceStorage {
+            storage_adapter,
+            namespace: cluster_name(),
+        }
+    }
+
+    pub async fn create_shard(&self, trace_name: &str) -> Result<(), CommonError> {
+        let shard_name = packet_trace_shard_name(trace_name);
+        let shards = self
+            .storage_adapter
+            .list_shard(self.namespace.clone(), shard_name.clone())
+            .await?;
+        if shards.is_empty() {
+            self.storage_adapter
+                .create_shard(ShardInfo {
+                    namespace: self.namespace.clone(),
+                    shard_name,
+                    replica_num: 1,
+                })
+                .await?;
+        }
+        Ok(())
+    }
+
+    pub async fn delete_shard(&self, trace_name: &str) -> Result<(), CommonError> {
+        self.storage_adapter
+            .delete_shard(self.namespace.clone(), packet_trace_shard_name(trace_name))
+            .await
+    }
+
+    pub async fn append(&self, record: &PacketTraceRecord) -> Result<u64, CommonError> {
+        let mut data = Record::build_byte(serde_json::to_vec(record)?);
+        data.set_key(record.client_id.clone());
+        data.set_tags(vec![record.packet_type.clone()]);
+
+        self.storage_adapter
+            .write(
+                self.namespace.clone(),
+                packet_trace_shard_name(&record.trace_name),
+                data,
+            )
+            .await
+    }
+
+    /// Read up to `max_num` records of a trace starting at `offset`, in capture order.
+    pub async fn read(
+        &self,
+        trace_name: &str,
+        offset: u64,
+        max_num: u64,
+    ) -> Result<Vec<PacketTraceRecord>, CommonError> {
+        let shard_name = packet_trace_shard_name(trace_name);
+        let mut results = Vec::new();
+        let mut next_offset = offset;
+        while (results.len() as u64) < max_num {
+            let mut read_config = ReadConfig::new();
+            read_config.max_record_num =
+                PACKET_TRACE_READ_BATCH.min(max_num - results.len() as u64);
+            let records = self
+                .storage_adapter
+                .read_by_offset(
+                    self.namespace.clone(),
+                    shard_name.clone(),
+                    next_offset,
+                    read_config,
+                )
+                .await?;
+
+            if records.is_empty() {
+                break;
+            }
+
+            for record in records {
+                next_offset = record.offset.unwrap_or(next_offset) + 1;
+                if !record.crc32_check() {
+                    return Err(CommonError::CrcCheckByMessage);
+                }
+                results.push(serde_json::from_slice::<PacketTraceRecord>(&record.data)?);
+            }
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PacketTraceStorage;
+    use crate::handler::packet_trace::{PacketDirection, PacketTraceRecord};
+    use common_config::broker::{default_broker_config, init_broker_conf_by_config};
+    use storage_adapter::storage::build_memory_storage_driver;
+
+    #[tokio::test]
+    async fn packet_trace_append_read_test() {
+        init_broker_conf_by_config(default_broker_config());
+        let storage = PacketTraceStorage::new(build_memory_storage_driver());
+        storage.create_shard("t1").await.unwrap();
+
+        for i in 0..3 {
+            let record = PacketTraceRecord {
+                trace_name: "t1".to_string(),
+                direction: PacketDirection::In,
+                connect_id: 1,
+                client_id: "c1".to_string(),
+                username: "u1".to_string(),
+                source_ip: "127.0.0.1".to_string(),
+                packet_type: "PingReq".to_string(),
+                packet: "PingReq(PingReq)".to_string(),
+                payload: None,
+                payload_size: 0,
+                create_time: i,
+            };
+            storage.append(&record).await.unwrap();
+        }
+
+        let records = storage.read("t1", 0, 10).await.unwrap();
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[2].create_time, 2);
+
+        storage.delete_shard("t1").await.unwrap();
+    }
+}
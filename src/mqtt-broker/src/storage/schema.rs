@@ -15,13 +15,15 @@
 use common_base::error::{common::CommonError, ResultCommonError};
 use common_config::broker::broker_config;
 use grpc_clients::{
-    meta::inner::call::{bind_schema, create_schema, delete_schema, list_schema, un_bind_schema},
+    meta::inner::call::{
+        bind_schema, create_schema, delete_schema, list_schema, un_bind_schema, update_schema,
+    },
     pool::ClientPool,
 };
 use metadata_struct::schema::SchemaData;
 use protocol::meta::meta_service_inner::{
     BindSchemaRequest, CreateSchemaRequest, DeleteSchemaRequest, ListSchemaRequest,
-    UnBindSchemaRequest,
+    UnBindSchemaRequest, UpdateSchemaRequest,
 };
 use std::sync::Arc;
 
@@ -63,6 +65,19 @@ pub async fn create(&self, schema_data: SchemaData) -> ResultCommonError {
         Ok(())
     }
 
+    pub async fn update(&self, schema_data: SchemaData) -> ResultCommonError {
+        let config = broker_config();
+        let request = UpdateSchemaRequest {
+            cluster_name: config.cluster_name.clone(),
+            schema_name: schema_data.name.clone(),
+            schema: schema_data.encode(),
+        };
+
+        update_schema(&self.client_pool, &config.get_meta_service_addr(), request).await?;
+
+        Ok(())
+    }
+
     pub async fn delete(&self, schema_name: String) -> ResultCommonError {
         let config = broker_config();
         let request = DeleteSchemaRequest {
@@ -94,6 +94,7 @@ pub async fn get_session(&self, client_id: String) -> Result<Option<MqttSession>
         let request = ListSessionRequest {
             cluster_name: config.cluster_name.clone(),
             client_id,
+            ..Default::default()
         };
 
         let reply =
@@ -113,6 +114,7 @@ pub async fn list_session(&self) -> Result<DashMap<String, MqttSession>, CommonE
         let request = ListSessionRequest {
             cluster_name: config.cluster_name.clone(),
             client_id: "".to_string(),
+            ..Default::default()
         };
 
         let reply =
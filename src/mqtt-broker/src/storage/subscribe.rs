@@ -0,0 +1,66 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_base::error::common::CommonError;
+use common_config::broker::broker_config;
+use grpc_clients::meta::mqtt::call::{placement_list_subscribe, placement_set_subscribe};
+use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::subscribe_data::MqttSubscribe;
+use protocol::meta::meta_service_mqtt::{ListSubscribeRequest, SetSubscribeRequest};
+
+pub struct SubscribeStorage {
+    client_pool: Arc<ClientPool>,
+}
+
+impl SubscribeStorage {
+    pub fn new(client_pool: Arc<ClientPool>) -> Self {
+        SubscribeStorage { client_pool }
+    }
+
+    pub async fn set_subscribe(&self, subscribe: &MqttSubscribe) -> Result<(), CommonError> {
+        let config = broker_config();
+        let request = SetSubscribeRequest {
+            cluster_name: config.cluster_name.clone(),
+            client_id: subscribe.client_id.clone(),
+            path: subscribe.path.clone(),
+            subscribe: subscribe.encode(),
+        };
+
+        placement_set_subscribe(&self.client_pool, &config.get_meta_service_addr(), request)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn list_by_client_id(
+        &self,
+        client_id: &str,
+    ) -> Result<Vec<MqttSubscribe>, CommonError> {
+        let config = broker_config();
+        let request = ListSubscribeRequest {
+            cluster_name: config.cluster_name.clone(),
+            client_id: client_id.to_string(),
+        };
+
+        let reply =
+            placement_list_subscribe(&self.client_pool, &config.get_meta_service_addr(), request)
+                .await?;
+        let mut results = Vec::new();
+        for raw in reply.subscribes {
+            results.push(serde_json::from_slice::<MqttSubscribe>(&raw)?);
+        }
+        Ok(results)
+    }
+}
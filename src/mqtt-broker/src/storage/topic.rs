@@ -67,6 +67,7 @@ pub async fn all(&self) -> Result<DashMap<String, MQTTTopic>, MqttBrokerError> {
         let request = ListTopicRequest {
             cluster_name: config.cluster_name.clone(),
             topic_name: "".to_string(),
+            ..Default::default()
         };
         let mut data_stream =
             placement_list_topic(&self.client_pool, &config.get_meta_service_addr(), request)
@@ -86,6 +87,7 @@ pub async fn get_topic(&self, topic_name: &str) -> Result<Option<MQTTTopic>, Mqt
         let request = ListTopicRequest {
             cluster_name: config.cluster_name.clone(),
             topic_name: topic_name.to_owned(),
+            ..Default::default()
         };
 
         let mut data_stream =
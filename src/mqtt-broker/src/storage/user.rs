@@ -17,21 +17,34 @@
 use common_config::broker::broker_config;
 use dashmap::DashMap;
 use grpc_clients::meta::mqtt::call::{
-    placement_create_user, placement_delete_user, placement_list_user,
+    placement_create_user, placement_delete_user, placement_list_user, placement_update_user,
 };
 use grpc_clients::pool::ClientPool;
 use metadata_struct::mqtt::user::MqttUser;
-use protocol::meta::meta_service_mqtt::{CreateUserRequest, DeleteUserRequest, ListUserRequest};
+use protocol::meta::meta_service_mqtt::{
+    CreateUserRequest, DeleteUserRequest, ListUserRequest, ReadConsistency, UpdateUserRequest,
+};
 
 use crate::common::types::ResultMqttBrokerError;
 use crate::handler::error::MqttBrokerError;
 
 pub struct UserStorage {
     client_pool: Arc<ClientPool>,
+    read_consistency: ReadConsistency,
 }
 impl UserStorage {
     pub fn new(client_pool: Arc<ClientPool>) -> Self {
-        UserStorage { client_pool }
+        Self::new_with_read_consistency(client_pool, ReadConsistency::Linearizable)
+    }
+
+    pub fn new_with_read_consistency(
+        client_pool: Arc<ClientPool>,
+        read_consistency: ReadConsistency,
+    ) -> Self {
+        UserStorage {
+            client_pool,
+            read_consistency,
+        }
     }
 
     pub async fn save_user(&self, user_info: MqttUser) -> ResultMqttBrokerError {
@@ -45,6 +58,17 @@ pub async fn save_user(&self, user_info: MqttUser) -> ResultMqttBrokerError {
         Ok(())
     }
 
+    pub async fn update_user(&self, user_info: MqttUser) -> ResultMqttBrokerError {
+        let config = broker_config();
+        let request = UpdateUserRequest {
+            cluster_name: config.cluster_name.clone(),
+            user_name: user_info.username.clone(),
+            content: user_info.encode(),
+        };
+        placement_update_user(&self.client_pool, &config.get_meta_service_addr(), request).await?;
+        Ok(())
+    }
+
     pub async fn delete_user(&self, user_name: String) -> ResultMqttBrokerError {
         let config = broker_config();
         let request = DeleteUserRequest {
@@ -61,6 +85,7 @@ pub async fn get_user(&self, username: String) -> Result<Option<MqttUser>, MqttB
         let request = ListUserRequest {
             cluster_name: config.cluster_name.clone(),
             user_name: username.clone(),
+            read_consistency: self.read_consistency.into(),
         };
 
         let reply =
@@ -78,6 +103,7 @@ pub async fn user_list(&self) -> Result<DashMap<String, MqttUser>, MqttBrokerErr
         let config = broker_config();
         let request = ListUserRequest {
             cluster_name: config.cluster_name.clone(),
+            read_consistency: self.read_consistency.into(),
             ..Default::default()
         };
 
@@ -0,0 +1,79 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_config::broker::broker_config;
+use grpc_clients::meta::kv::call::{placement_delete, placement_get_prefix, placement_set};
+use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::webhook::MqttWebhook;
+use protocol::meta::meta_service_kv::{DeleteRequest, GetPrefixRequest, SetRequest};
+
+use crate::common::types::ResultMqttBrokerError;
+use crate::handler::error::MqttBrokerError;
+
+// Webhooks are kept in the meta-service key-value store, every broker
+// delivers the events it produces to all of them.
+pub struct WebhookStorage {
+    client_pool: Arc<ClientPool>,
+}
+
+impl WebhookStorage {
+    pub fn new(client_pool: Arc<ClientPool>) -> Self {
+        WebhookStorage { client_pool }
+    }
+
+    pub async fn save(&self, webhook: &MqttWebhook) -> ResultMqttBrokerError {
+        let config = broker_config();
+        let request = SetRequest {
+            key: webhook_key(&webhook.cluster_name, &webhook.name),
+            value: serde_json::to_string(webhook)?,
+            lease_id: 0,
+        };
+        placement_set(&self.client_pool, &config.get_meta_service_addr(), request).await?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, name: &str) -> ResultMqttBrokerError {
+        let config = broker_config();
+        let request = DeleteRequest {
+            key: webhook_key(&config.cluster_name, name),
+        };
+        placement_delete(&self.client_pool, &config.get_meta_service_addr(), request).await?;
+        Ok(())
+    }
+
+    pub async fn list(&self) -> Result<Vec<MqttWebhook>, MqttBrokerError> {
+        let config = broker_config();
+        let request = GetPrefixRequest {
+            prefix: webhook_prefix_key(&config.cluster_name),
+        };
+        let reply =
+            placement_get_prefix(&self.client_pool, &config.get_meta_service_addr(), request)
+                .await?;
+        let mut results = Vec::new();
+        for raw in reply.values {
+            results.push(serde_json::from_str::<MqttWebhook>(&raw)?);
+        }
+        Ok(results)
+    }
+}
+
+fn webhook_key(cluster_name: &str, name: &str) -> String {
+    format!("/mqtt/webhook/{cluster_name}/{name}")
+}
+
+fn webhook_prefix_key(cluster_name: &str) -> String {
+    format!("/mqtt/webhook/{cluster_name}/")
+}
@@ -23,6 +23,7 @@
 use grpc_clients::meta::mqtt::call::placement_get_share_sub_leader;
 use grpc_clients::pool::ClientPool;
 use metadata_struct::mqtt::subscribe_data::{is_mqtt_queue_sub, is_mqtt_share_sub};
+use opentelemetry::Context;
 use protocol::meta::meta_service_mqtt::{GetShareSubLeaderReply, GetShareSubLeaderRequest};
 use protocol::mqtt::common::{
     Filter, MqttProtocol, RetainHandling, SubAck, SubscribeProperties, SubscribeReasonCode,
@@ -37,6 +38,9 @@
 const SUBSCRIBE_SPLIT_DELIMITER: &str = "/";
 const SUBSCRIBE_NAME_REGEX: &str = r"^[\$a-zA-Z0-9_#+/]+$";
 pub const SHARE_QUEUE_DEFAULT_GROUP_NAME: &str = "$queue_group_robustmq";
+// Subscription user property selecting the payload format delivered to the client,
+// either "json" or the name of a registered schema
+pub const SUBSCRIBE_PAYLOAD_FORMAT_PROPERTY: &str = "payload-format";
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct Subscriber {
@@ -51,9 +55,20 @@ pub struct Subscriber {
     pub preserve_retain: bool,
     pub retain_forward_rule: RetainHandling,
     pub subscription_identifier: Option<usize>,
+    pub payload_format: Option<String>,
     pub create_time: u64,
 }
 
+pub fn get_sub_payload_format(properties: &Option<SubscribeProperties>) -> Option<String> {
+    properties.as_ref().and_then(|props| {
+        props
+            .user_properties
+            .iter()
+            .find(|(key, _)| key == SUBSCRIBE_PAYLOAD_FORMAT_PROPERTY)
+            .map(|(_, value)| value.clone())
+    })
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SubscribeData {
     pub protocol: MqttProtocol,
@@ -68,6 +83,13 @@ pub struct SubPublishParam {
     pub create_time: u128,
     pub pkid: u16,
     pub group_id: String,
+    // offset of the record being delivered, None for messages not read from a topic
+    pub offset: Option<u64>,
+    // span of this delivery, ended once the packet is acked or the push failed
+    pub trace_context: Context,
+    // the client already sent PUBREC for this message to the previous owner of the
+    // session, only PUBREL is sent again
+    pub resend_pubrel: bool,
 }
 
 impl SubPublishParam {
@@ -84,6 +106,9 @@ pub fn new(
             create_time,
             pkid,
             group_id,
+            offset: None,
+            trace_context: Context::new(),
+            resend_pubrel: false,
         }
     }
 }
@@ -96,6 +121,7 @@ pub fn is_ignore_push_error(e: &MqttBrokerError) -> bool {
     match e {
         MqttBrokerError::SessionNullSkipPushMessage(_) => {}
         MqttBrokerError::ConnectionNullSkipPushMessage(_) => {}
+        MqttBrokerError::PushThreadStopped(_) => {}
         MqttBrokerError::NotObtainAvailableConnection(_, _) => {}
         MqttBrokerError::OperationTimeout(_, _) => {}
         _ => {
@@ -256,14 +282,17 @@ pub async fn get_share_sub_leader(
     placement_get_share_sub_leader(client_pool, &conf.get_meta_service_addr(), req).await
 }
 
+// Commit the offset after the acked record, the offset the group reads next the same way
+// connectors do. A push thread started after a session takeover or a restart reads from
+// the committed offset, so it must not start with a record the client already acked.
 pub async fn loop_commit_offset(
     message_storage: &MessageStorage,
     topic_name: &str,
     group_id: &str,
-    offset: u64,
+    record_offset: u64,
 ) -> ResultMqttBrokerError {
     message_storage
-        .commit_group_offset(group_id, topic_name, offset)
+        .commit_group_offset(group_id, topic_name, record_offset + 1)
         .await?;
     Ok(())
 }
@@ -286,13 +315,18 @@ pub fn is_error_by_suback(suback: &SubAck) -> bool {
 #[cfg(test)]
 mod tests {
     use crate::common::tool::test_build_mqtt_cache_manager;
+    use crate::storage::message::MessageStorage;
     use crate::subscribe::common::{
         build_sub_path_regex, decode_queue_info, decode_share_info, decode_sub_path,
-        get_sub_topic_name_list, is_match_sub_and_topic, is_wildcards, min_qos, sub_path_validator,
+        get_sub_payload_format, get_sub_topic_name_list, is_match_sub_and_topic, is_wildcards,
+        loop_commit_offset, min_qos, sub_path_validator, SUBSCRIBE_PAYLOAD_FORMAT_PROPERTY,
     };
+    use common_config::broker::{default_broker_config, init_broker_conf_by_config};
+    use metadata_struct::adapter::record::Record;
     use metadata_struct::mqtt::subscribe_data::{is_mqtt_queue_sub, is_mqtt_share_sub};
     use metadata_struct::mqtt::topic::MQTTTopic;
-    use protocol::mqtt::common::QoS;
+    use protocol::mqtt::common::{QoS, SubscribeProperties};
+    use storage_adapter::storage::build_memory_storage_driver;
 
     #[tokio::test]
     async fn is_wildcards_test() {
@@ -301,6 +335,35 @@ async fn is_wildcards_test() {
         assert!(is_wildcards("/test/#"));
     }
 
+    #[tokio::test]
+    async fn loop_commit_offset_test() {
+        init_broker_conf_by_config(default_broker_config());
+        let message_storage = MessageStorage::new(build_memory_storage_driver());
+        let topic_name = "loop_commit_offset_test";
+        let group_id = "system_sub_g1_s1_loop_commit_offset_test";
+        let records = (0..3)
+            .map(|i| Record::build_byte(format!("m{i}").into_bytes()))
+            .collect();
+        let offsets = message_storage
+            .append_topic_message(topic_name, records)
+            .await
+            .unwrap();
+
+        loop_commit_offset(&message_storage, topic_name, group_id, offsets[0])
+            .await
+            .unwrap();
+        let offset = message_storage.get_group_offset(group_id).await.unwrap();
+        assert_eq!(offset, offsets[1]);
+
+        // a restarted push thread resumes after the pushed record
+        let results = message_storage
+            .read_topic_message(topic_name, offset, 10)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].offset, Some(offsets[1]));
+    }
+
     #[tokio::test]
     async fn decode_queue_info_test() {
         let res = decode_queue_info("$queue/vvv/v1");
@@ -521,4 +584,24 @@ async fn decode_sub_path_sub_test() {
         let path = "$exclusive/topic1/1".to_string();
         assert_eq!(decode_sub_path(&path), "/topic1/1".to_string());
     }
+
+    #[test]
+    fn get_sub_payload_format_test() {
+        assert_eq!(get_sub_payload_format(&None), None);
+
+        let properties = SubscribeProperties {
+            subscription_identifier: None,
+            user_properties: vec![
+                ("k1".to_string(), "v1".to_string()),
+                (
+                    SUBSCRIBE_PAYLOAD_FORMAT_PROPERTY.to_string(),
+                    "json".to_string(),
+                ),
+            ],
+        };
+        assert_eq!(
+            get_sub_payload_format(&Some(properties)),
+            Some("json".to_string())
+        );
+    }
 }
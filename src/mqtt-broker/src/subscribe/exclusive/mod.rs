@@ -21,6 +21,7 @@
 use crate::common::metrics_cache::MetricsCacheManager;
 use crate::common::types::ResultMqttBrokerError;
 use crate::handler::cache::MQTTCacheManager;
+use crate::handler::delivery_log::{DeliveryEvent, DeliveryEventType};
 use crate::handler::error::MqttBrokerError;
 use crate::handler::slow_subscribe::record_slow_subscribe_data;
 use crate::storage::message::MessageStorage;
@@ -34,6 +35,7 @@
 use metadata_struct::adapter::record::Record;
 use network_server::common::connection_manager::ConnectionManager;
 use protocol::mqtt::common::QoS;
+use schema_register::schema::SchemaRegisterManager;
 use std::sync::Arc;
 use std::time::Duration;
 use storage_adapter::storage::ArcStorageAdapter;
@@ -51,10 +53,12 @@ pub struct ExclusivePush {
     message_storage: ArcStorageAdapter,
     metrics_cache_manager: Arc<MetricsCacheManager>,
     rocksdb_engine_handler: Arc<RocksDBEngine>,
+    schema_manager: Arc<SchemaRegisterManager>,
     stop_sx: broadcast::Sender<bool>,
 }
 
 impl ExclusivePush {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         message_storage: ArcStorageAdapter,
         cache_manager: Arc<MQTTCacheManager>,
@@ -62,6 +66,7 @@ pub fn new(
         connection_manager: Arc<ConnectionManager>,
         metrics_cache_manager: Arc<MetricsCacheManager>,
         rocksdb_engine_handler: Arc<RocksDBEngine>,
+        schema_manager: Arc<SchemaRegisterManager>,
         stop_sx: broadcast::Sender<bool>,
     ) -> Self {
         ExclusivePush {
@@ -71,6 +76,7 @@ pub fn new(
             connection_manager,
             metrics_cache_manager,
             rocksdb_engine_handler,
+            schema_manager,
             stop_sx,
         }
     }
@@ -126,6 +132,7 @@ async fn start_push_thread(&self) {
             let subscribe_manager = self.subscribe_manager.clone();
             let metrics_cache_manager = self.metrics_cache_manager.clone();
             let rocksdb_engine_handler = self.rocksdb_engine_handler.clone();
+            let schema_manager = self.schema_manager.clone();
 
             // Subscribe to the data push thread
             self.subscribe_manager.exclusive_push_thread.insert(
@@ -186,6 +193,7 @@ async fn start_push_thread(&self) {
                                 subscriber: subscriber.clone(),
                                 group_id: group_id.clone(),
                                 rocksdb_engine_handler: rocksdb_engine_handler.clone(),
+                                schema_manager: schema_manager.clone(),
                                 qos,
                                 sub_ids: sub_ids.clone(),
                                 offset,
@@ -227,6 +235,7 @@ pub struct ExclusivePushContext {
     pub cache_manager: Arc<MQTTCacheManager>,
     pub metrics_cache_manager: Arc<MetricsCacheManager>,
     pub rocksdb_engine_handler: Arc<RocksDBEngine>,
+    pub schema_manager: Arc<SchemaRegisterManager>,
     pub subscriber: Subscriber,
     pub group_id: String,
     pub qos: QoS,
@@ -255,6 +264,7 @@ async fn pub_message(context: ExclusivePushContext) -> Result<Option<u64>, MqttB
             build_publish_message(BuildPublishMessageContext {
                 cache_manager: context.cache_manager.clone(),
                 connection_manager: context.connection_manager.clone(),
+                schema_manager: context.schema_manager.clone(),
                 client_id: context.subscriber.client_id.clone(),
                 record: record.to_owned(),
                 group_id: context.group_id.clone(),
@@ -290,7 +300,7 @@ async fn pub_message(context: ExclusivePushContext) -> Result<Option<u64>, MqttB
         )
         .await?;
 
-        // commit offset
+        // commit the offset after the pushed record
         loop_commit_offset(
             &context.message_storage,
             &context.subscriber.topic_name,
@@ -309,8 +319,27 @@ async fn pub_message(context: ExclusivePushContext) -> Result<Option<u64>, MqttB
             Ok(_) => {
                 success_num += 1;
             }
+            // the session was released, its owner sends the rest from the committed offset
+            Err(MqttBrokerError::PushThreadStopped(_)) => {
+                break;
+            }
             Err(e) => {
                 error_num += 1;
+                context
+                    .cache_manager
+                    .topic_metrics
+                    .record_dropped(&context.subscriber.topic_name);
+                if let Some(offset) = record.offset {
+                    context.cache_manager.delivery_log.record(|| {
+                        DeliveryEvent::build(
+                            DeliveryEventType::Drop,
+                            &context.subscriber,
+                            offset,
+                            0,
+                            Some(e.to_string()),
+                        )
+                    });
+                }
                 if !is_ignore_push_error(&e) {
                     warn!(
                         "Exclusive push fail, offset [{:?}], error message:{},",
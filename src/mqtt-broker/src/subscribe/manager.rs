@@ -31,6 +31,7 @@ pub struct ShareSubShareSub {
     pub packet_identifier: u16,
     pub filter: Filter,
     pub subscription_identifier: Option<usize>,
+    pub payload_format: Option<String>,
 }
 
 #[derive(Clone, Deserialize, Serialize, Debug)]
@@ -85,6 +86,9 @@ pub struct SubscribeManager {
     // (group_name_topic_name, ShareLeaderSubscribeData)
     pub share_leader_push: DashMap<String, ShareLeaderSubscribeData>,
 
+    // (group_name_topic_name, client_id) member picked by the sticky strategy
+    pub share_leader_sticky: DashMap<String, String>,
+
     // (group_name_topic_name, SubPushThreadData)
     pub share_leader_push_thread: DashMap<String, SubPushThreadData>,
 
@@ -113,6 +117,7 @@ pub fn new() -> Self {
             subscribe_list: DashMap::with_capacity(8),
             exclusive_push: DashMap::with_capacity(8),
             share_leader_push: DashMap::with_capacity(8),
+            share_leader_sticky: DashMap::with_capacity(8),
             share_follower_resub: DashMap::with_capacity(8),
             exclusive_push_thread: DashMap::with_capacity(8),
             share_leader_push_thread: DashMap::with_capacity(8),
@@ -237,6 +242,17 @@ fn remove_share_subscribe_leader_by_client_id(&self, client_id: &str) {
         }
     }
 
+    pub fn get_share_sticky_member(&self, share_leader_key: &str) -> Option<String> {
+        self.share_leader_sticky
+            .get(share_leader_key)
+            .map(|raw| raw.value().clone())
+    }
+
+    pub fn set_share_sticky_member(&self, share_leader_key: &str, client_id: &str) {
+        self.share_leader_sticky
+            .insert(share_leader_key.to_owned(), client_id.to_owned());
+    }
+
     pub fn update_subscribe_leader_push_thread_info(
         &self,
         key: &str,
@@ -375,6 +391,58 @@ pub fn remove_client_id(&self, client_id: &str) {
         self.remove_not_push_client(client_id);
     }
 
+    // Stop pushing to the client on this broker but keep its subscriptions, so that
+    // another broker can take over the session. Returns the keys of the push threads
+    // that were asked to stop.
+    pub fn stop_push_by_client_id(&self, client_id: &str) -> Vec<String> {
+        let mut thread_keys = Vec::new();
+        for (key, subscriber) in self.exclusive_push.clone() {
+            if subscriber.client_id == *client_id {
+                thread_keys.push(key);
+            }
+        }
+
+        for (key, share_sub) in self.share_follower_resub.clone() {
+            if share_sub.client_id == *client_id {
+                thread_keys.push(key);
+            }
+        }
+
+        self.remove_exclusive_push_by_client_id(client_id);
+        self.remove_share_subscribe_leader_by_client_id(client_id);
+        self.remove_share_subscribe_follower_by_client_id(client_id);
+        self.remove_not_push_client(client_id);
+
+        for key in thread_keys.iter() {
+            if let Some(thread) = self.exclusive_push_thread.get(key) {
+                let _ = thread.sender.send(true);
+            }
+            if let Some(thread) = self.share_follower_resub_thread.get(key) {
+                let _ = thread.sender.send(true);
+            }
+        }
+        thread_keys
+    }
+
+    // Give up every shared subscription group led by this broker, the followers
+    // ask the meta service for a new leader.
+    pub fn stop_share_leader_push(&self) {
+        for (key, share_sub) in self.share_leader_push.clone() {
+            for (client_id, _) in share_sub.sub_list {
+                self.remove_topic_subscribe_by_client_id(&share_sub.topic_name, &client_id);
+            }
+            self.share_leader_push.remove(&key);
+            if let Some(thread) = self.share_leader_push_thread.get(&key) {
+                let _ = thread.sender.send(true);
+            }
+        }
+    }
+
+    pub fn contain_push_thread(&self, key: &str) -> bool {
+        self.exclusive_push_thread.contains_key(key)
+            || self.share_follower_resub_thread.contains_key(key)
+    }
+
     // info
     pub fn snapshot_info(&self) -> HashMap<String, Vec<String>> {
         let exclusive_push_key: Vec<String> = self
@@ -458,11 +526,13 @@ mod tests {
     use std::sync::Arc;
 
     use common_base::tools::{now_second, unique_id};
+    use metadata_struct::mqtt::subscribe_data::MqttSubscribe;
     use protocol::mqtt::common::{Filter, MqttProtocol, QoS, RetainHandling};
+    use tokio::sync::broadcast;
 
     use crate::subscribe::{
         common::Subscriber,
-        manager::{ShareSubShareSub, SubscribeManager},
+        manager::{ShareSubShareSub, SubPushThreadData, SubscribeManager},
     };
 
     #[test]
@@ -505,6 +575,7 @@ fn share_subscribe_leader_test() {
             preserve_retain: true,
             retain_forward_rule: RetainHandling::Never,
             subscription_identifier: None,
+            payload_format: None,
             sub_path: "/var/111".to_string(),
             rewrite_sub_path: None,
             create_time: now_second(),
@@ -525,6 +596,7 @@ fn share_subscribe_followe_test() {
             group_name: "g1".to_string(),
             sub_name: "s1".to_string(),
             subscription_identifier: None,
+            payload_format: None,
             topic_name: "tname".to_string(),
         };
         let subscribe_manager = Arc::new(SubscribeManager::new());
@@ -537,4 +609,62 @@ fn share_subscribe_followe_test() {
         subscribe_manager.remove_share_subscribe_follower_by_client_id(&share_sub.client_id);
         assert_eq!(subscribe_manager.share_follower_resub.len(), 0);
     }
+
+    #[test]
+    fn stop_push_by_client_id_test() {
+        let subscribe_manager = Arc::new(SubscribeManager::new());
+        let client_id = "client_id_1";
+        subscribe_manager.add_subscribe(MqttSubscribe {
+            client_id: client_id.to_string(),
+            path: "/var/111".to_string(),
+            ..Default::default()
+        });
+
+        let sub = Subscriber {
+            protocol: MqttProtocol::Mqtt5,
+            client_id: client_id.to_string(),
+            topic_name: "t_name_1".to_string(),
+            group_name: None,
+            qos: QoS::AtLeastOnce,
+            nolocal: true,
+            preserve_retain: true,
+            retain_forward_rule: RetainHandling::Never,
+            subscription_identifier: None,
+            payload_format: None,
+            sub_path: "/var/111".to_string(),
+            rewrite_sub_path: None,
+            create_time: now_second(),
+        };
+        subscribe_manager.add_exclusive_push(
+            client_id,
+            &sub.sub_path,
+            &sub.topic_name,
+            sub.clone(),
+        );
+        subscribe_manager.add_topic_subscribe(&sub.topic_name, client_id, &sub.sub_path);
+
+        let (sender, mut receiver) = broadcast::channel(1);
+        let key = subscribe_manager.exclusive_key(client_id, &sub.sub_path, &sub.topic_name);
+        subscribe_manager.exclusive_push_thread.insert(
+            key.clone(),
+            SubPushThreadData {
+                push_success_record_num: 0,
+                push_error_record_num: 0,
+                last_push_time: 0,
+                last_run_time: 0,
+                create_time: now_second(),
+                sender,
+            },
+        );
+
+        let keys = subscribe_manager.stop_push_by_client_id(client_id);
+        assert_eq!(keys, vec![key.clone()]);
+        assert!(receiver.try_recv().unwrap());
+        assert!(subscribe_manager.exclusive_push.is_empty());
+        assert!(!subscribe_manager.contain_topic_subscribe(&sub.topic_name));
+        assert!(subscribe_manager.contain_push_thread(&key));
+        assert!(subscribe_manager
+            .get_subscribe(client_id, &sub.sub_path)
+            .is_some());
+    }
 }
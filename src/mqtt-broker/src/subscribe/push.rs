@@ -16,27 +16,32 @@
 use super::common::Subscriber;
 use crate::common::types::ResultMqttBrokerError;
 use crate::handler::cache::{
-    MQTTCacheManager, QosAckPackageData, QosAckPackageType, QosAckPacketInfo,
+    MQTTCacheManager, OutboundInflight, QosAckPackageData, QosAckPackageType, QosAckPacketInfo,
 };
+use crate::handler::delivery_log::{DeliveryEvent, DeliveryEventType};
 use crate::handler::error::MqttBrokerError;
-use crate::handler::message::is_message_expire;
+use crate::handler::message::{is_message_expire, transcode_message_payload};
+use crate::handler::packet_trace::{PacketDirection, TraceClient};
 use crate::handler::sub_option::{get_retain_flag_by_retain_as_published, is_send_msg_by_bo_local};
 use crate::subscribe::common::{is_ignore_push_error, SubPublishParam};
 use axum::extract::ws::Message;
 use bytes::{Bytes, BytesMut};
 use common_base::network::broker_not_available;
+use common_base::telemetry::trace::{
+    end_span, extract_context, inject_context, record_span_error, start_span,
+};
 use common_base::tools::now_mills;
 use common_metrics::mqtt::packets::record_sent_metrics;
 use common_metrics::mqtt::publish::record_mqtt_message_bytes_sent;
 use common_metrics::mqtt::publish::record_mqtt_messages_sent_inc;
 use common_metrics::mqtt::time::record_mqtt_packet_send_duration;
-use common_metrics::mqtt::topic::record_topic_bytes_sent;
-use common_metrics::mqtt::topic::record_topic_messages_sent;
 use metadata_struct::adapter::record::Record;
 use metadata_struct::mqtt::message::MqttMessage;
 use network_server::common::connection_manager::ConnectionManager;
 use network_server::common::packet::build_mqtt_packet_wrapper;
 use network_server::common::packet::ResponsePackage;
+use opentelemetry::trace::SpanKind;
+use opentelemetry::KeyValue;
 use protocol::mqtt::codec::MqttCodec;
 use protocol::mqtt::codec::MqttPacketWrapper;
 use protocol::mqtt::common::mqtt_packet_to_string;
@@ -44,6 +49,7 @@
 use protocol::mqtt::common::{MqttPacket, PubRel, Publish, PublishProperties, QoS};
 use protocol::robust::RobustMQPacket;
 use protocol::robust::RobustMQProtocol;
+use schema_register::schema::SchemaRegisterManager;
 use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
@@ -56,6 +62,7 @@
 pub struct BuildPublishMessageContext {
     pub cache_manager: Arc<MQTTCacheManager>,
     pub connection_manager: Arc<ConnectionManager>,
+    pub schema_manager: Arc<SchemaRegisterManager>,
     pub client_id: String,
     pub record: Record,
     pub group_id: String,
@@ -67,10 +74,51 @@ pub struct BuildPublishMessageContext {
 pub async fn build_publish_message(
     context: BuildPublishMessageContext,
 ) -> Result<Option<SubPublishParam>, MqttBrokerError> {
-    let msg = MqttMessage::decode_record(context.record.clone())?;
+    let mut msg = MqttMessage::decode_record(context.record.clone())?;
+
+    let record_drop = |reason: &str| {
+        context
+            .cache_manager
+            .topic_metrics
+            .record_dropped(&context.subscriber.topic_name);
+        if let Some(offset) = context.record.offset {
+            context.cache_manager.delivery_log.record(|| {
+                DeliveryEvent::build(
+                    DeliveryEventType::Drop,
+                    &context.subscriber,
+                    offset,
+                    0,
+                    Some(reason.to_string()),
+                )
+            });
+        }
+    };
 
-    if is_message_expire(&msg) {
+    if let Some(offset) = context.record.offset {
+        context.cache_manager.delivery_log.record(|| {
+            DeliveryEvent::build(
+                DeliveryEventType::Enqueue,
+                &context.subscriber,
+                offset,
+                0,
+                None,
+            )
+        });
+    }
+
+    // a message taken over from the previous owner of the session is sent again with
+    // its packet id, its delivery has already started so it does not expire
+    let resend = context.record.offset.and_then(|offset| {
+        context.cache_manager.pkid_metadata.take_outbound_resend(
+            &context.client_id,
+            &context.group_id,
+            offset,
+        )
+    });
+
+    if resend.is_none() && is_message_expire(&msg) {
         debug!("Message dropping: message expires, is not pushed to the client, and is discarded");
+        record_drop("message expired");
         return Ok(None);
     }
 
@@ -83,6 +131,7 @@ pub async fn build_publish_message(
             "Message dropping: message is not pushed to the client, because the client_id is the same as the subscriber, client_id: {}, topic_name: {}",
             context.subscriber.client_id, context.subscriber.topic_name
         );
+        record_drop("no local");
         return Ok(None);
     }
 
@@ -104,10 +153,20 @@ pub async fn build_publish_message(
                     conn.max_packet_size
                 )
             );
+            record_drop("payload exceeds the maximum packet size of the client");
             return Ok(None);
         }
     }
 
+    if let Some(format) = &context.subscriber.payload_format {
+        transcode_message_payload(
+            &context.schema_manager,
+            &context.subscriber.topic_name,
+            format,
+            &mut msg,
+        );
+    }
+
     let mut contain_properties = false;
     if let Some(protocol) = context.connection_manager.get_connect_protocol(connect_id) {
         if protocol.is_mqtt5() {
@@ -115,17 +174,32 @@ pub async fn build_publish_message(
         }
     }
 
-    let pkid = context
-        .cache_manager
-        .pkid_metadata
-        .generate_pkid(&context.client_id, &context.qos)
-        .await;
+    let pkid = match &resend {
+        Some(inflight) => inflight.pkid,
+        None => {
+            context
+                .cache_manager
+                .pkid_metadata
+                .generate_pkid(&context.client_id, &context.qos)
+                .await
+        }
+    };
 
     let retain =
         get_retain_flag_by_retain_as_published(context.subscriber.preserve_retain, msg.retain);
 
+    let trace_cx = start_span(
+        "mqtt.push",
+        &extract_context(&msg.user_properties),
+        SpanKind::Producer,
+        vec![
+            KeyValue::new("mqtt.client_id", context.client_id.clone()),
+            KeyValue::new("mqtt.topic", context.subscriber.topic_name.clone()),
+        ],
+    );
+
     let publish = Publish {
-        dup: false,
+        dup: resend.is_some(),
         qos: context.qos,
         p_kid: pkid,
         retain,
@@ -134,13 +208,15 @@ pub async fn build_publish_message(
     };
 
     let properties = if contain_properties {
+        let mut user_properties = msg.user_properties;
+        inject_context(&trace_cx, &mut user_properties);
         Some(PublishProperties {
             payload_format_indicator: msg.format_indicator,
             message_expiry_interval: Some(msg.expiry_interval as u32),
             topic_alias: None,
             response_topic: msg.response_topic,
             correlation_data: msg.correlation_data,
-            user_properties: msg.user_properties,
+            user_properties,
             subscription_identifiers: context.sub_ids,
             content_type: msg.content_type,
         })
@@ -149,13 +225,16 @@ pub async fn build_publish_message(
     };
 
     let packet = MqttPacket::Publish(publish, properties);
-    let sub_pub_param = SubPublishParam::new(
+    let mut sub_pub_param = SubPublishParam::new(
         context.subscriber.clone(),
         packet,
         context.record.timestamp as u128,
         context.group_id.to_string(),
         pkid,
     );
+    sub_pub_param.offset = context.record.offset;
+    sub_pub_param.trace_context = trace_cx;
+    sub_pub_param.resend_pubrel = resend.is_some_and(|inflight| inflight.pubrel);
     Ok(Some(sub_pub_param))
 }
 
@@ -165,6 +244,56 @@ pub async fn send_publish_packet_to_client(
     sub_pub_param: &SubPublishParam,
     qos: &QoS,
     stop_sx: &Sender<bool>,
+) -> ResultMqttBrokerError {
+    let result = send_publish_packet_by_qos(
+        connection_manager,
+        cache_manager,
+        sub_pub_param,
+        qos,
+        stop_sx,
+    )
+    .await;
+    match &result {
+        Ok(_) => {
+            if *qos != QoS::AtMostOnce {
+                record_delivery_event(cache_manager, sub_pub_param, DeliveryEventType::Ack, None);
+            }
+        }
+        Err(e) => {
+            record_span_error(&sub_pub_param.trace_context, e.to_string());
+        }
+    }
+    end_span(&sub_pub_param.trace_context);
+    result
+}
+
+// Record a delivery event of a message read from a topic, other pushes are not logged
+pub fn record_delivery_event(
+    cache_manager: &Arc<MQTTCacheManager>,
+    sub_pub_param: &SubPublishParam,
+    event_type: DeliveryEventType,
+    reason: Option<String>,
+) {
+    let Some(offset) = sub_pub_param.offset else {
+        return;
+    };
+    cache_manager.delivery_log.record(|| {
+        DeliveryEvent::build(
+            event_type,
+            &sub_pub_param.subscribe,
+            offset,
+            sub_pub_param.pkid,
+            reason,
+        )
+    });
+}
+
+async fn send_publish_packet_by_qos(
+    connection_manager: &Arc<ConnectionManager>,
+    cache_manager: &Arc<MQTTCacheManager>,
+    sub_pub_param: &SubPublishParam,
+    qos: &QoS,
+    stop_sx: &Sender<bool>,
 ) -> ResultMqttBrokerError {
     match qos {
         QoS::AtMostOnce => {
@@ -184,6 +313,7 @@ pub async fn send_publish_packet_to_client(
                     create_time: now_mills(),
                 },
             );
+            add_outbound_inflight(cache_manager, sub_pub_param);
 
             exclusive_publish_message_qos1(
                 cache_manager,
@@ -211,6 +341,7 @@ pub async fn send_publish_packet_to_client(
                     create_time: now_mills(),
                 },
             );
+            add_outbound_inflight(cache_manager, sub_pub_param);
 
             exclusive_publish_message_qos2(
                 cache_manager,
@@ -229,6 +360,23 @@ pub async fn send_publish_packet_to_client(
     Ok(())
 }
 
+// Messages read from a topic are tracked until they are acked so that a session
+// takeover can hand them to the new owner, see `release_session`
+fn add_outbound_inflight(cache_manager: &Arc<MQTTCacheManager>, sub_pub_param: &SubPublishParam) {
+    let Some(offset) = sub_pub_param.offset else {
+        return;
+    };
+    cache_manager.pkid_metadata.add_outbound_inflight(
+        &sub_pub_param.subscribe.client_id,
+        OutboundInflight {
+            group_id: sub_pub_param.group_id.clone(),
+            offset,
+            pkid: sub_pub_param.pkid,
+            pubrel: sub_pub_param.resend_pubrel,
+        },
+    );
+}
+
 pub async fn build_pub_qos(cache_manager: &Arc<MQTTCacheManager>, subscriber: &Subscriber) -> QoS {
     let cluster_qos = cache_manager
         .broker_cache
@@ -271,7 +419,29 @@ pub async fn push_packet_to_client(
         let packet = RobustMQPacket::MQTT(sub_pub_param.packet.clone());
         let resp = ResponsePackage::new(connect_id, packet, 0, 0, 0, "Subscribe".to_string());
 
-        send_message_to_client(resp, connection_manager).await
+        send_message_to_client(resp, connection_manager).await?;
+        if let MqttPacket::Publish(publish, _) = &sub_pub_param.packet {
+            record_delivery_event(cache_manager, sub_pub_param, DeliveryEventType::Push, None);
+            cache_manager.topic_metrics.record_sent(
+                &sub_pub_param.subscribe.topic_name,
+                publish.payload.len() as u64,
+            );
+        }
+        cache_manager
+            .packet_trace
+            .record(
+                PacketDirection::Out,
+                &sub_pub_param.packet,
+                || match cache_manager.get_connection(connect_id) {
+                    Some(connection) => TraceClient::from_connection(&connection),
+                    None => TraceClient {
+                        connect_id,
+                        client_id,
+                        ..Default::default()
+                    },
+                },
+            );
+        Ok(())
     };
 
     retry_tool_fn_timeout(action_fn, stop_sx, "push_packet_to_client").await
@@ -314,18 +484,23 @@ pub async fn exclusive_publish_message_qos2(
     stop_sx: &broadcast::Sender<bool>,
     wait_ack_sx: &broadcast::Sender<QosAckPackageData>,
 ) -> ResultMqttBrokerError {
-    // 1. send Publish to Client
-    push_packet_to_client(metadata_cache, connection_manager, sub_pub_param, stop_sx).await?;
-
-    // 2. wait PubRec ack
-    wait_pub_rec(
-        metadata_cache,
-        connection_manager,
-        sub_pub_param,
-        stop_sx,
-        wait_ack_sx,
-    )
-    .await?;
+    if !sub_pub_param.resend_pubrel {
+        // 1. send Publish to Client
+        push_packet_to_client(metadata_cache, connection_manager, sub_pub_param, stop_sx).await?;
+
+        // 2. wait PubRec ack
+        wait_pub_rec(
+            metadata_cache,
+            connection_manager,
+            sub_pub_param,
+            stop_sx,
+            wait_ack_sx,
+        )
+        .await?;
+        metadata_cache
+            .pkid_metadata
+            .mark_outbound_pubrel(&sub_pub_param.subscribe.client_id, sub_pub_param.pkid);
+    }
 
     // 3. send PubRel to Client
     qos2_send_pubrel(metadata_cache, sub_pub_param, connection_manager, stop_sx).await?;
@@ -401,11 +576,8 @@ pub async fn send_message_to_client(
         packet: packet.clone(),
     };
     if let MqttPacket::Publish(publish, _) = packet.clone() {
-        let topic_name = String::from_utf8(publish.topic.to_vec()).unwrap();
         record_mqtt_messages_sent_inc();
         record_mqtt_message_bytes_sent(publish.payload.len() as u64);
-        record_topic_messages_sent(&topic_name);
-        record_topic_bytes_sent(&topic_name, publish.payload.len() as u64);
     }
 
     if let Some(network) = network_type.clone() {
@@ -561,14 +733,25 @@ async fn retry_tool_fn_timeout<F, Fut>(
     Fut: Future<Output = ResultMqttBrokerError>,
 {
     let to = 3;
-    match timeout(Duration::from_secs(to), retry_tool_fn(ac_fn, stop_sx)).await {
+    match timeout(
+        Duration::from_secs(to),
+        retry_tool_fn(ac_fn, stop_sx, action),
+    )
+    .await
+    {
         Ok(res) => res?,
         Err(_) => return Err(MqttBrokerError::OperationTimeout(to, action.to_string())),
     }
     Ok(())
 }
 
-async fn retry_tool_fn<F, Fut>(ac_fn: F, stop_sx: &broadcast::Sender<bool>) -> ResultMqttBrokerError
+// A stop is returned as an error so that a message that was not acked is neither
+// committed nor reported as delivered
+async fn retry_tool_fn<F, Fut>(
+    ac_fn: F,
+    stop_sx: &broadcast::Sender<bool>,
+    action: &str,
+) -> ResultMqttBrokerError
 where
     F: FnOnce() -> Fut + Copy,
     Fut: Future<Output = ResultMqttBrokerError>,
@@ -579,7 +762,7 @@ async fn retry_tool_fn<F, Fut>(ac_fn: F, stop_sx: &broadcast::Sender<bool>) -> R
             val = stop_recv.recv() => {
                 if let Ok(flag) = val {
                     if flag {
-                        return Ok(());
+                        return Err(MqttBrokerError::PushThreadStopped(action.to_string()));
                     }
                 }
             }
@@ -45,6 +45,7 @@
 use crate::subscribe::common::get_share_sub_leader;
 use crate::subscribe::common::SubPublishParam;
 use crate::subscribe::common::Subscriber;
+use crate::subscribe::common::SUBSCRIBE_PAYLOAD_FORMAT_PROPERTY;
 use crate::subscribe::manager::SubscribeManager;
 use crate::subscribe::manager::{ShareSubShareSub, SubPushThreadData};
 use crate::subscribe::push::{
@@ -163,6 +164,7 @@ async fn start_resub_thread(&self) -> ResultMqttBrokerError {
                     client_id: share_sub.client_id.to_owned(),
                     protocol: share_sub.protocol.clone(),
                     sub_identifier: share_sub.subscription_identifier,
+                    payload_format: share_sub.payload_format.clone(),
                     filter: share_sub.filter.clone(),
                     pkid: share_sub.packet_identifier,
                     sub_name: share_sub.sub_name,
@@ -820,9 +822,18 @@ async fn subscribe_to_leader(
         filters: vec![share_sub.filter.clone()],
     };
 
+    // let the leader transcode the payload before it is forwarded to the client
+    let mut user_properties = Vec::new();
+    if let Some(format) = &share_sub.payload_format {
+        user_properties.push((
+            SUBSCRIBE_PAYLOAD_FORMAT_PROPERTY.to_string(),
+            format.clone(),
+        ));
+    }
+
     let subscribe_properties = SubscribeProperties {
         subscription_identifier: share_sub.subscription_identifier,
-        user_properties: Vec::new(),
+        user_properties,
     };
 
     let pkg = MqttPacket::Subscribe(subscribe, Some(subscribe_properties));
@@ -14,18 +14,20 @@
 
 use crate::common::types::ResultMqttBrokerError;
 use crate::handler::cache::MQTTCacheManager;
+use crate::handler::delivery_log::{DeliveryEvent, DeliveryEventType};
 use crate::handler::error::MqttBrokerError;
 use crate::handler::slow_subscribe::record_slow_subscribe_data;
 use crate::storage::message::MessageStorage;
 use crate::subscribe::common::is_ignore_push_error;
 use crate::subscribe::common::loop_commit_offset;
-use crate::subscribe::common::Subscriber;
+use crate::subscribe::common::SubPublishParam;
 use crate::subscribe::manager::SubPushThreadData;
 use crate::subscribe::manager::{ShareLeaderSubscribeData, SubscribeManager};
 use crate::subscribe::push::{
-    build_pub_qos, build_publish_message, build_sub_ids, send_publish_packet_to_client,
-    BuildPublishMessageContext,
+    build_pub_qos, build_publish_message, build_sub_ids, record_delivery_event,
+    send_publish_packet_to_client, BuildPublishMessageContext,
 };
+use crate::subscribe::share::strategy::{select_share_member, SelectShareMemberContext};
 use broker_core::rocksdb::RocksDBEngine;
 use common_base::error::ResultCommonError;
 use common_base::network::broker_not_available;
@@ -33,6 +35,8 @@
 use common_base::tools::now_second;
 use metadata_struct::adapter::record::Record;
 use network_server::common::connection_manager::ConnectionManager;
+use protocol::mqtt::common::QoS;
+use schema_register::schema::SchemaRegisterManager;
 use std::sync::Arc;
 use std::time::Duration;
 use storage_adapter::storage::ArcStorageAdapter;
@@ -50,6 +54,7 @@ pub struct ShareLeaderPush {
     connection_manager: Arc<ConnectionManager>,
     pub rocksdb_engine_handler: Arc<RocksDBEngine>,
     cache_manager: Arc<MQTTCacheManager>,
+    schema_manager: Arc<SchemaRegisterManager>,
     stop_sx: broadcast::Sender<bool>,
 }
 
@@ -60,6 +65,7 @@ pub fn new(
         connection_manager: Arc<ConnectionManager>,
         cache_manager: Arc<MQTTCacheManager>,
         rocksdb_engine_handler: Arc<RocksDBEngine>,
+        schema_manager: Arc<SchemaRegisterManager>,
         stop_sx: broadcast::Sender<bool>,
     ) -> Self {
         ShareLeaderPush {
@@ -68,6 +74,7 @@ pub fn new(
             connection_manager,
             cache_manager,
             rocksdb_engine_handler,
+            schema_manager,
             stop_sx,
         }
     }
@@ -109,6 +116,11 @@ pub fn try_thread_gc(&self) {
                 self.subscribe_manager.share_leader_push.remove(&key);
             }
         }
+
+        let share_leader_push = &self.subscribe_manager.share_leader_push;
+        self.subscribe_manager
+            .share_leader_sticky
+            .retain(|key, _| share_leader_push.contains_key(key));
     }
 
     pub async fn start_push_thread(&self) {
@@ -134,22 +146,23 @@ pub async fn start_push_thread(&self) {
                 .share_leader_push_thread
                 .contains_key(&share_leader_key)
             {
-                if let Err(e) = self.push_by_round_robin(share_leader_key, sub_data).await {
+                if let Err(e) = self.start_share_push(share_leader_key, sub_data).await {
                     error!("{:?}", e);
                 }
             }
         }
     }
 
-    async fn push_by_round_robin(
+    async fn start_share_push(
         &self,
         share_leader_key: String,
         sub_data: ShareLeaderSubscribeData,
     ) -> ResultMqttBrokerError {
         let (sub_thread_stop_sx, mut sub_thread_stop_rx) = broadcast::channel(1);
-        let group_id = format!(
-            "system_sub_{}_{}_{}",
-            sub_data.group_name, sub_data.sub_name, sub_data.topic_name
+        let group_id = build_share_group_id(
+            &sub_data.group_name,
+            &sub_data.sub_name,
+            &sub_data.topic_name,
         );
 
         // get current offset by group
@@ -173,6 +186,7 @@ async fn push_by_round_robin(
         let cache_manager = self.cache_manager.clone();
         let subscribe_manager = self.subscribe_manager.clone();
         let rocksdb_engine_handler = self.rocksdb_engine_handler.clone();
+        let schema_manager = self.schema_manager.clone();
 
         tokio::spawn(async move {
             info!(
@@ -202,6 +216,7 @@ async fn push_by_round_robin(
                             subscribe_manager: subscribe_manager.clone(),
                             share_leader_key: share_leader_key.clone(),
                             rocksdb_engine_handler: rocksdb_engine_handler.clone(),
+                            schema_manager: schema_manager.clone(),
                             sub_data: sub_data.clone(),
                             group_id: group_id.clone(),
                             offset,
@@ -249,6 +264,7 @@ pub struct ShareLeaderPushContext {
     pub connection_manager: Arc<ConnectionManager>,
     pub cache_manager: Arc<MQTTCacheManager>,
     pub rocksdb_engine_handler: Arc<RocksDBEngine>,
+    pub schema_manager: Arc<SchemaRegisterManager>,
     pub message_storage: MessageStorage,
     pub subscribe_manager: Arc<SubscribeManager>,
     pub share_leader_key: String,
@@ -267,6 +283,13 @@ async fn read_message_process(
         .read_topic_message(&context.sub_data.topic_name, context.offset, 100)
         .await?;
 
+    // read per batch so that a strategy change takes effect without restarting the thread
+    let strategy = context
+        .cache_manager
+        .get_shared_subscription_config()
+        .await
+        .strategy(&context.sub_data.group_name);
+
     let mut push_fn = async |record: &Record| -> ResultMqttBrokerError {
         let record_offset = if let Some(offset) = record.offset {
             offset
@@ -277,26 +300,42 @@ async fn read_message_process(
         let mut times = 0;
         loop {
             context.seq += 1;
-            times += 1;
-            if times > 3 {
-                warn!("Shared subscription failed to send messages {} times and the messages were discarded,, offset: {:?}", times, record.offset);
-                break;
-            }
 
-            let subscriber = if let Some(subscribe) = get_subscribe_by_random(
-                &context.subscribe_manager,
-                &context.share_leader_key,
-                context.seq,
-            )
-            .await
-            {
+            let subscriber = if let Some(subscribe) =
+                select_share_member(SelectShareMemberContext {
+                    subscribe_manager: &context.subscribe_manager,
+                    cache_manager: &context.cache_manager,
+                    share_leader_key: &context.share_leader_key,
+                    strategy,
+                    record,
+                    seq: context.seq,
+                }) {
                 subscribe
             } else {
-                warn!("No available subscribers were obtained. Continue looking for the next one, , offset: {:?}", record.offset);
-                sleep(Duration::from_secs(1)).await;
+                // wait for a member to come back, the message is kept until then
+                sleep(Duration::from_millis(100)).await;
                 continue;
             };
 
+            times += 1;
+            if times > 3 {
+                warn!("Shared subscription failed to send messages {} times and the messages were discarded,, offset: {:?}", times, record.offset);
+                context
+                    .cache_manager
+                    .topic_metrics
+                    .record_dropped(&subscriber.topic_name);
+                context.cache_manager.delivery_log.record(|| {
+                    DeliveryEvent::build(
+                        DeliveryEventType::Drop,
+                        &subscriber,
+                        record_offset,
+                        0,
+                        Some("delivery to the share group failed 3 times".to_string()),
+                    )
+                });
+                break;
+            }
+
             let qos = build_pub_qos(&context.cache_manager, &subscriber).await;
             let sub_ids = build_sub_ids(&subscriber);
 
@@ -304,6 +343,7 @@ async fn read_message_process(
             let sub_pub_param = match build_publish_message(BuildPublishMessageContext {
                 cache_manager: context.cache_manager.clone(),
                 connection_manager: context.connection_manager.clone(),
+                schema_manager: context.schema_manager.clone(),
                 client_id: subscriber.client_id.clone(),
                 record: record.to_owned(),
                 group_id: context.group_id.clone(),
@@ -326,35 +366,67 @@ async fn read_message_process(
                         "Build message error. Error message : {}, offset: {:?}",
                         e, record.offset
                     );
+                    context
+                        .cache_manager
+                        .topic_metrics
+                        .record_dropped(&subscriber.topic_name);
+                    context.cache_manager.delivery_log.record(|| {
+                        DeliveryEvent::build(
+                            DeliveryEventType::Drop,
+                            &subscriber,
+                            record_offset,
+                            0,
+                            Some(e.to_string()),
+                        )
+                    });
                     break;
                 }
             };
 
             let send_time = now_second();
 
-            if let Err(e) = send_publish_packet_to_client(
-                &context.connection_manager,
-                &context.cache_manager,
-                &sub_pub_param,
-                &qos,
-                &context.stop_sx,
-            )
-            .await
-            {
-                if broker_not_available(&e.to_string()) {
-                    context
-                        .subscribe_manager
-                        .add_not_push_client(&subscriber.client_id);
+            match deliver_to_member(&context, &sub_pub_param, &qos).await {
+                Ok(true) => {}
+                Ok(false) => {
+                    // the member went away before acking, hand the message to another member
+                    debug!(
+                        "Shared subscription member {} disconnected before acking, redeliver to another member, offset: {:?}",
+                        subscriber.client_id, record.offset
+                    );
+                    record_delivery_event(
+                        &context.cache_manager,
+                        &sub_pub_param,
+                        DeliveryEventType::Drop,
+                        Some(
+                            "member disconnected before acking, redelivered to another member"
+                                .to_string(),
+                        ),
+                    );
+                    times -= 1;
+                    continue;
                 }
+                Err(e) => {
+                    if broker_not_available(&e.to_string()) {
+                        context
+                            .subscribe_manager
+                            .add_not_push_client(&subscriber.client_id);
+                    }
 
-                debug!(
-                    "Shared subscription failed to send a message to client {}. I attempted to
+                    debug!(
+                        "Shared subscription failed to send a message to client {}. I attempted to
                     send it to the next client. Error message :{}, offset: {:?}",
-                    subscriber.client_id, e, record.offset
-                );
+                        subscriber.client_id, e, record.offset
+                    );
+                    record_delivery_event(
+                        &context.cache_manager,
+                        &sub_pub_param,
+                        DeliveryEventType::Drop,
+                        Some(e.to_string()),
+                    );
 
-                continue;
-            };
+                    continue;
+                }
+            }
 
             record_slow_subscribe_data(
                 &context.cache_manager,
@@ -368,7 +440,7 @@ async fn read_message_process(
             break;
         }
 
-        // commit offset
+        // commit the offset after the pushed record
         loop_commit_offset(
             &context.message_storage,
             &context.sub_data.topic_name,
@@ -414,35 +486,58 @@ async fn read_message_process(
     Ok((results.last().unwrap().offset, context.seq))
 }
 
-async fn get_subscribe_by_random(
-    subscribe_manager: &Arc<SubscribeManager>,
-    share_leader_key: &str,
-    mut seq: u64,
-) -> Option<Subscriber> {
+// Send the message to the member and wait for its ack. Returns false if the member
+// disconnected before the delivery finished, the message can then go to another member.
+async fn deliver_to_member(
+    context: &ShareLeaderPushContext,
+    sub_pub_param: &SubPublishParam,
+    qos: &QoS,
+) -> Result<bool, MqttBrokerError> {
+    let client_id = &sub_pub_param.subscribe.client_id;
+    let connect_id = if let Some(id) = context.cache_manager.get_connect_id(client_id) {
+        id
+    } else {
+        return Ok(false);
+    };
+
+    select! {
+        res = send_publish_packet_to_client(
+            &context.connection_manager,
+            &context.cache_manager,
+            sub_pub_param,
+            qos,
+            &context.stop_sx,
+        ) => {
+            res?;
+            Ok(true)
+        }
+        _ = wait_member_disconnected(&context.cache_manager, client_id, connect_id) => {
+            context
+                .cache_manager
+                .pkid_metadata
+                .remove_ack_packet(client_id, sub_pub_param.pkid);
+            Ok(false)
+        }
+    }
+}
+
+async fn wait_member_disconnected(
+    cache_manager: &Arc<MQTTCacheManager>,
+    client_id: &str,
+    connect_id: u64,
+) {
     loop {
-        if let Some(sub_list) = subscribe_manager.share_leader_push.get(share_leader_key) {
-            let index = seq % (sub_list.sub_list.len() as u64);
-            let keys: Vec<String> = sub_list
-                .sub_list
-                .iter()
-                .map(|entry| entry.key().clone())
-                .collect();
-
-            if let Some(key) = keys.get(index as usize) {
-                if let Some(subscribe) = sub_list.sub_list.get(key) {
-                    if !subscribe_manager
-                        .not_push_client
-                        .contains_key(&subscribe.client_id)
-                    {
-                        return Some(subscribe.clone());
-                    }
-                }
-            }
+        if cache_manager.get_connect_id(client_id) != Some(connect_id) {
+            return;
         }
-        seq += 1;
         sleep(Duration::from_millis(100)).await;
     }
 }
 
+// Group the share leader commits its offsets under
+pub fn build_share_group_id(group_name: &str, sub_name: &str, topic_name: &str) -> String {
+    format!("system_sub_{group_name}_{sub_name}_{topic_name}")
+}
+
 #[cfg(test)]
 mod tests {}
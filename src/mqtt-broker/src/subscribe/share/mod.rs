@@ -14,4 +14,5 @@
 
 pub mod follower;
 pub mod leader;
+pub mod strategy;
 pub mod write;
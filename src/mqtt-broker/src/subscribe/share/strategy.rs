@@ -0,0 +1,288 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use common_config::config::ShareSubStrategy;
+use metadata_struct::adapter::record::Record;
+use metadata_struct::mqtt::message::MqttMessage;
+use rand::Rng;
+
+use crate::handler::cache::MQTTCacheManager;
+use crate::subscribe::common::Subscriber;
+use crate::subscribe::manager::SubscribeManager;
+
+pub struct SelectShareMemberContext<'a> {
+    pub subscribe_manager: &'a Arc<SubscribeManager>,
+    pub cache_manager: &'a Arc<MQTTCacheManager>,
+    pub share_leader_key: &'a str,
+    pub strategy: ShareSubStrategy,
+    pub record: &'a Record,
+    pub seq: u64,
+}
+
+// Pick the member of a shared subscription group that receives the next message.
+// Only members that are connected and not temporarily blocked can be picked, so a
+// member that goes away is skipped and its messages go to the others.
+//
+// Every topic matched by the group is pushed by its own leader, so HashTopic sends all
+// messages of a topic to one member and spreads the topics of a wildcard filter over
+// the members.
+pub fn select_share_member(context: SelectShareMemberContext) -> Option<Subscriber> {
+    let mut members: Vec<Subscriber> = context
+        .subscribe_manager
+        .share_leader_push
+        .get(context.share_leader_key)?
+        .sub_list
+        .iter()
+        .map(|raw| raw.value().clone())
+        .filter(|sub| {
+            !context
+                .subscribe_manager
+                .not_push_client
+                .contains_key(&sub.client_id)
+                && context
+                    .cache_manager
+                    .get_connect_id(&sub.client_id)
+                    .is_some()
+        })
+        .collect();
+    if members.is_empty() {
+        return None;
+    }
+    // hash based strategies need a stable order
+    members.sort_by(|a, b| a.client_id.cmp(&b.client_id));
+
+    let index = match context.strategy {
+        ShareSubStrategy::Random => rand::thread_rng().gen_range(0..members.len()),
+        ShareSubStrategy::RoundRobin => (context.seq % members.len() as u64) as usize,
+        ShareSubStrategy::Sticky => {
+            let sticky = context
+                .subscribe_manager
+                .get_share_sticky_member(context.share_leader_key);
+            match members
+                .iter()
+                .position(|sub| Some(&sub.client_id) == sticky.as_ref())
+            {
+                Some(index) => index,
+                None => {
+                    let index = rand::thread_rng().gen_range(0..members.len());
+                    context.subscribe_manager.set_share_sticky_member(
+                        context.share_leader_key,
+                        &members[index].client_id,
+                    );
+                    index
+                }
+            }
+        }
+        ShareSubStrategy::HashClientId => {
+            let client_id = MqttMessage::client_id_of_record(context.record).unwrap_or_default();
+            hash_index(&client_id, members.len())
+        }
+        ShareSubStrategy::HashTopic => hash_index(&members[0].topic_name, members.len()),
+        ShareSubStrategy::LeastInflight => members
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, sub)| {
+                context
+                    .cache_manager
+                    .pkid_metadata
+                    .inflight_num(&sub.client_id)
+            })
+            .map(|(index, _)| index)
+            .unwrap_or(0),
+    };
+    members.get(index).cloned()
+}
+
+fn hash_index(key: &str, len: usize) -> usize {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    (hasher.finish() % len as u64) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::tool::test_build_mqtt_cache_manager;
+    use crate::handler::cache::QosAckPacketInfo;
+    use common_base::tools::now_mills;
+    use metadata_struct::mqtt::connection::MQTTConnection;
+    use metadata_struct::mqtt::session::MqttSession;
+    use tokio::sync::broadcast;
+
+    async fn build_group(
+        members: &[&str],
+    ) -> (Arc<SubscribeManager>, Arc<MQTTCacheManager>, String) {
+        let cache_manager = test_build_mqtt_cache_manager().await;
+        let subscribe_manager = Arc::new(SubscribeManager::new());
+        for (i, client_id) in members.iter().enumerate() {
+            subscribe_manager.add_share_subscribe_leader(
+                "$share/g1/t1",
+                Subscriber {
+                    client_id: client_id.to_string(),
+                    group_name: Some("g1".to_string()),
+                    topic_name: "t1".to_string(),
+                    ..Default::default()
+                },
+            );
+            cache_manager.add_session(client_id, &MqttSession::default());
+            cache_manager.add_connection(
+                i as u64 + 1,
+                MQTTConnection {
+                    client_id: client_id.to_string(),
+                    ..Default::default()
+                },
+            );
+        }
+        (
+            subscribe_manager,
+            cache_manager,
+            "g1_$share/g1/t1_t1".to_string(),
+        )
+    }
+
+    fn select(
+        subscribe_manager: &Arc<SubscribeManager>,
+        cache_manager: &Arc<MQTTCacheManager>,
+        key: &str,
+        strategy: ShareSubStrategy,
+        seq: u64,
+    ) -> Option<String> {
+        let record = Record::build_byte(
+            MqttMessage {
+                client_id: "publisher".to_string(),
+                ..Default::default()
+            }
+            .encode(),
+        );
+        select_share_member(SelectShareMemberContext {
+            subscribe_manager,
+            cache_manager,
+            share_leader_key: key,
+            strategy,
+            record: &record,
+            seq,
+        })
+        .map(|sub| sub.client_id)
+    }
+
+    #[tokio::test]
+    async fn select_share_member_test() {
+        let (subscribe_manager, cache_manager, key) = build_group(&["c1", "c2", "c3"]).await;
+
+        // round robin walks through the members
+        let picked: Vec<Option<String>> = (0..3)
+            .map(|seq| {
+                select(
+                    &subscribe_manager,
+                    &cache_manager,
+                    &key,
+                    ShareSubStrategy::RoundRobin,
+                    seq,
+                )
+            })
+            .collect();
+        assert_eq!(
+            picked,
+            vec![
+                Some("c1".to_string()),
+                Some("c2".to_string()),
+                Some("c3".to_string())
+            ]
+        );
+
+        // hashing and sticky always return the same member
+        for strategy in [
+            ShareSubStrategy::Sticky,
+            ShareSubStrategy::HashClientId,
+            ShareSubStrategy::HashTopic,
+        ] {
+            let first = select(&subscribe_manager, &cache_manager, &key, strategy, 0);
+            assert!(first.is_some());
+            for seq in 1..10 {
+                assert_eq!(
+                    select(&subscribe_manager, &cache_manager, &key, strategy, seq),
+                    first
+                );
+            }
+        }
+
+        // least inflight avoids members waiting for acks
+        let (sx, _) = broadcast::channel(1);
+        for client_id in ["c1", "c2"] {
+            cache_manager.pkid_metadata.add_ack_packet(
+                client_id,
+                1,
+                QosAckPacketInfo {
+                    sx: sx.clone(),
+                    create_time: now_mills(),
+                },
+            );
+        }
+        assert_eq!(
+            select(
+                &subscribe_manager,
+                &cache_manager,
+                &key,
+                ShareSubStrategy::LeastInflight,
+                0
+            ),
+            Some("c3".to_string())
+        );
+        cache_manager.pkid_metadata.remove_ack_packet("c1", 1);
+        assert_eq!(cache_manager.pkid_metadata.inflight_num("c1"), 0);
+        assert_eq!(cache_manager.pkid_metadata.inflight_num("c2"), 1);
+    }
+
+    #[tokio::test]
+    async fn select_share_member_skip_disconnected_test() {
+        let (subscribe_manager, cache_manager, key) = build_group(&["c1", "c2"]).await;
+        let sticky = select(
+            &subscribe_manager,
+            &cache_manager,
+            &key,
+            ShareSubStrategy::Sticky,
+            0,
+        )
+        .unwrap();
+
+        // the sticky member disconnects, the group moves to the other one
+        let connect_id = cache_manager.get_connect_id(&sticky).unwrap();
+        cache_manager.update_session_connect_id(&sticky, None);
+        cache_manager.remove_connection(connect_id);
+        let other = select(
+            &subscribe_manager,
+            &cache_manager,
+            &key,
+            ShareSubStrategy::Sticky,
+            0,
+        )
+        .unwrap();
+        assert_ne!(other, sticky);
+
+        // nothing left to pick
+        cache_manager.update_session_connect_id(&other, None);
+        assert!(select(
+            &subscribe_manager,
+            &cache_manager,
+            &key,
+            ShareSubStrategy::RoundRobin,
+            0
+        )
+        .is_none());
+    }
+}
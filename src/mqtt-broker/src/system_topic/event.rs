@@ -20,6 +20,7 @@
 use metadata_struct::mqtt::connection::MQTTConnection;
 use metadata_struct::mqtt::message::MqttMessage;
 use metadata_struct::mqtt::session::MqttSession;
+use metadata_struct::mqtt::webhook::WebhookEventType;
 use network_server::common::connection_manager::ConnectionManager;
 use protocol::mqtt::common::{DisconnectReasonCode, MqttProtocol, Subscribe, Unsubscribe};
 use serde::{Deserialize, Serialize};
@@ -151,6 +152,10 @@ pub async fn st_report_connected_event(context: StReportConnectedEventContext) {
             client_id: context.session.client_id.to_string(),
             clean_start: false,
         };
+        context
+            .metadata_cache
+            .webhook
+            .report(WebhookEventType::ClientConnected, None, || &event_data);
         match serde_json::to_string(&event_data) {
             Ok(data) => {
                 let topic_name = replace_name(
@@ -192,6 +197,10 @@ pub async fn st_report_disconnected_event(context: StReportDisconnectedEventCont
             client_id: context.session.client_id.to_string(),
             disconnected_at: now_mills(),
         };
+        context
+            .metadata_cache
+            .webhook
+            .report(WebhookEventType::ClientDisconnected, None, || &event_data);
 
         match serde_json::to_string(&event_data) {
             Ok(data) => {
@@ -240,6 +249,11 @@ pub async fn st_report_subscribed_event(context: StReportSubscribedEventContext)
                 protocol: format!("{:?}", network_connection.protocol.clone()),
                 client_id: context.connection.client_id.to_string(),
             };
+            context.metadata_cache.webhook.report(
+                WebhookEventType::SessionSubscribed,
+                Some(&event_data.topic),
+                || &event_data,
+            );
             match serde_json::to_string(&event_data) {
                 Ok(data) => {
                     let topic_name = replace_name(
@@ -279,6 +293,11 @@ pub async fn st_report_unsubscribed_event(context: StReportUnsubscribedEventCont
                 protocol: format!("{:?}", network_connection.protocol.clone()),
                 client_id: context.connection.client_id.to_string(),
             };
+            context.metadata_cache.webhook.report(
+                WebhookEventType::SessionUnsubscribed,
+                Some(&event_data.topic),
+                || &event_data,
+            );
             match serde_json::to_string(&event_data) {
                 Ok(data) => {
                     let topic_name = replace_name(
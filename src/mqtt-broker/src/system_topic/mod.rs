@@ -71,7 +71,9 @@
     SYSTEM_TOPIC_BROKERS_STATS_SUBSCRIPTIONS_SHARED_COUNT,
     SYSTEM_TOPIC_BROKERS_STATS_SUBSCRIPTIONS_SHARED_MAX,
 };
-use crate::system_topic::sysmon::SYSTEM_TOPIC_BROKERS_ALARMS_ACTIVATE;
+use crate::system_topic::sysmon::{
+    SYSTEM_TOPIC_BROKERS_ALARMS_ACTIVATE, SYSTEM_TOPIC_BROKERS_ALARMS_DEACTIVATE,
+};
 use common_base::error::ResultCommonError;
 use common_base::tools::{get_local_ip, loop_select_ticket};
 use grpc_clients::pool::ClientPool;
@@ -262,6 +264,7 @@ fn get_all_system_topic(&self) -> Vec<String> {
             SYSTEM_TOPIC_BROKERS_METRICS_PACKETS_AUTH.to_string(),
             // ALARM
             SYSTEM_TOPIC_BROKERS_ALARMS_ACTIVATE.to_string(),
+            SYSTEM_TOPIC_BROKERS_ALARMS_DEACTIVATE.to_string(),
         ]
     }
 }
@@ -0,0 +1,339 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use apache_avro::{schema_compatibility::SchemaCompatibility as AvroCompatibility, Schema};
+use common_base::error::common::CommonError;
+use metadata_struct::schema::{SchemaCompatibility, SchemaData, SchemaType};
+use protofish::{context::ValueType, prelude::Context};
+use serde_json::Value;
+
+use crate::protobuf::parse_protobuf_schema;
+
+/// Checks that `schema` can be parsed, and for protobuf that the configured message exists.
+pub fn schema_check(schema: &SchemaData) -> Result<(), CommonError> {
+    match schema.schema_type {
+        SchemaType::JSON => {
+            let raw: Value = serde_json::from_str(&schema.schema)?;
+            let mut scope = valico::json_schema::Scope::new();
+            scope.compile_and_return(raw, false)?;
+        }
+        SchemaType::AVRO => {
+            Schema::parse_str(&schema.schema)?;
+        }
+        SchemaType::PROTOBUF => {
+            let context = parse_protobuf_schema(schema)?;
+            if schema.message_name.is_empty() {
+                return Err(CommonError::CommonError(format!(
+                    "Schema {} is protobuf, message_name cannot be empty",
+                    schema.name
+                )));
+            }
+            if context.get_message(&schema.message_name).is_none() {
+                return Err(CommonError::CommonError(format!(
+                    "Message {} not found in schema {}",
+                    schema.message_name, schema.name
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks whether `new` may replace `old` under the compatibility mode stored on `old`.
+/// The mode may only be strengthened by an update, dropping a guarantee needs the schema
+/// to be deleted and created again.
+pub fn check_compatibility(old: &SchemaData, new: &SchemaData) -> Result<(), CommonError> {
+    let backward = is_backward(&old.compatibility);
+    let forward = is_forward(&old.compatibility);
+    if (backward && !is_backward(&new.compatibility))
+        || (forward && !is_forward(&new.compatibility))
+    {
+        return Err(CommonError::CommonError(format!(
+            "Schema {} has {} compatibility, it cannot be weakened to {}",
+            old.name, old.compatibility, new.compatibility
+        )));
+    }
+
+    if old.compatibility == SchemaCompatibility::None {
+        return Ok(());
+    }
+
+    if old.schema_type != new.schema_type {
+        return Err(CommonError::CommonError(format!(
+            "Schema {} is {} with {} compatibility, the type cannot be changed to {}",
+            old.name, old.schema_type, old.compatibility, new.schema_type
+        )));
+    }
+
+    // backward: consumers on the new schema can read data written with the old one
+    if backward {
+        can_read(new, old).map_err(|e| {
+            CommonError::CommonError(format!(
+                "Schema {} is not backward compatible: {}",
+                new.name, e
+            ))
+        })?;
+    }
+
+    // forward: consumers on the old schema can read data written with the new one
+    if forward {
+        can_read(old, new).map_err(|e| {
+            CommonError::CommonError(format!(
+                "Schema {} is not forward compatible: {}",
+                new.name, e
+            ))
+        })?;
+    }
+    Ok(())
+}
+
+fn is_backward(mode: &SchemaCompatibility) -> bool {
+    matches!(
+        mode,
+        SchemaCompatibility::Backward | SchemaCompatibility::Full
+    )
+}
+
+fn is_forward(mode: &SchemaCompatibility) -> bool {
+    matches!(
+        mode,
+        SchemaCompatibility::Forward | SchemaCompatibility::Full
+    )
+}
+
+fn can_read(reader: &SchemaData, writer: &SchemaData) -> Result<(), String> {
+    match reader.schema_type {
+        SchemaType::AVRO => avro_can_read(reader, writer),
+        SchemaType::JSON => json_can_read(reader, writer),
+        SchemaType::PROTOBUF => protobuf_can_read(reader, writer),
+    }
+}
+
+fn avro_can_read(reader: &SchemaData, writer: &SchemaData) -> Result<(), String> {
+    let reader_schema = Schema::parse_str(&reader.schema).map_err(|e| e.to_string())?;
+    let writer_schema = Schema::parse_str(&writer.schema).map_err(|e| e.to_string())?;
+    AvroCompatibility::can_read(&writer_schema, &reader_schema).map_err(|e| e.to_string())
+}
+
+// A reader accepts the writer's data when every property it requires is also
+// required by the writer, and shared properties keep the same type.
+fn json_can_read(reader: &SchemaData, writer: &SchemaData) -> Result<(), String> {
+    let reader_schema: Value = serde_json::from_str(&reader.schema).map_err(|e| e.to_string())?;
+    let writer_schema: Value = serde_json::from_str(&writer.schema).map_err(|e| e.to_string())?;
+
+    if reader_schema.get("type") != writer_schema.get("type") {
+        return Err("root type changed".to_string());
+    }
+
+    let writer_required = json_required(&writer_schema);
+    for name in json_required(&reader_schema) {
+        if !writer_required.contains(&name) {
+            return Err(format!("property {name} is required but may be missing"));
+        }
+    }
+
+    if let (Some(Value::Object(reader_props)), Some(Value::Object(writer_props))) = (
+        reader_schema.get("properties"),
+        writer_schema.get("properties"),
+    ) {
+        for (name, reader_prop) in reader_props {
+            if let Some(writer_prop) = writer_props.get(name) {
+                if reader_prop.get("type") != writer_prop.get("type") {
+                    return Err(format!("property {name} changed type"));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn json_required(schema: &Value) -> Vec<String> {
+    match schema.get("required") {
+        Some(Value::Array(list)) => list
+            .iter()
+            .filter_map(|v| v.as_str().map(|s| s.to_string()))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// Protobuf decoding ignores unknown fields, so the only break is a field number
+// that is reused with a different type.
+fn protobuf_can_read(reader: &SchemaData, writer: &SchemaData) -> Result<(), String> {
+    let reader_ctx = parse_protobuf_schema(reader).map_err(|e| e.to_string())?;
+    let writer_ctx = parse_protobuf_schema(writer).map_err(|e| e.to_string())?;
+
+    let reader_msg = reader_ctx
+        .get_message(&reader.message_name)
+        .ok_or_else(|| format!("message {} not found", reader.message_name))?;
+    let writer_msg = writer_ctx
+        .get_message(&writer.message_name)
+        .ok_or_else(|| format!("message {} not found", writer.message_name))?;
+
+    for reader_field in reader_msg.iter_fields() {
+        if let Some(writer_field) = writer_msg.get_field(reader_field.number) {
+            let reader_type = proto_type_name(&reader_ctx, &reader_field.field_type);
+            let writer_type = proto_type_name(&writer_ctx, &writer_field.field_type);
+            if reader_type != writer_type {
+                return Err(format!(
+                    "field {} changed type from {} to {}",
+                    reader_field.number, writer_type, reader_type
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn proto_type_name(context: &Context, value_type: &ValueType) -> String {
+    match value_type {
+        ValueType::Message(r) => context.resolve_message(*r).full_name.clone(),
+        ValueType::Enum(r) => context.resolve_enum(*r).full_name.clone(),
+        other => format!("{other:?}"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check_compatibility, schema_check};
+    use metadata_struct::schema::{SchemaCompatibility, SchemaData, SchemaType};
+
+    fn build(
+        schema_type: SchemaType,
+        schema: &str,
+        compatibility: SchemaCompatibility,
+    ) -> SchemaData {
+        SchemaData {
+            cluster_name: "test".to_string(),
+            name: "s1".to_string(),
+            schema_type,
+            desc: "".to_string(),
+            schema: schema.to_string(),
+            message_name: "test.Person".to_string(),
+            version: 1,
+            compatibility,
+        }
+    }
+
+    #[test]
+    pub fn avro_compatibility_test() {
+        let old = r#"{"type":"record","name":"t","fields":[{"name":"a","type":"long"}]}"#;
+        let with_default = r#"{"type":"record","name":"t","fields":[{"name":"a","type":"long"},{"name":"b","type":"string","default":""}]}"#;
+        let without_default = r#"{"type":"record","name":"t","fields":[{"name":"a","type":"long"},{"name":"b","type":"string"}]}"#;
+
+        let old = build(SchemaType::AVRO, old, SchemaCompatibility::Backward);
+        let ok = build(
+            SchemaType::AVRO,
+            with_default,
+            SchemaCompatibility::Backward,
+        );
+        let bad = build(
+            SchemaType::AVRO,
+            without_default,
+            SchemaCompatibility::Backward,
+        );
+        assert!(check_compatibility(&old, &ok).is_ok());
+        assert!(check_compatibility(&old, &bad).is_err());
+
+        let mut forward = old.clone();
+        forward.compatibility = SchemaCompatibility::Forward;
+        let mut bad_forward = bad.clone();
+        bad_forward.compatibility = SchemaCompatibility::Forward;
+        assert!(check_compatibility(&forward, &bad_forward).is_ok());
+    }
+
+    #[test]
+    pub fn json_compatibility_test() {
+        let old =
+            r#"{"type":"object","properties":{"name":{"type":"string"}},"required":["name"]}"#;
+        let add_required = r#"{"type":"object","properties":{"name":{"type":"string"},"age":{"type":"integer"}},"required":["name","age"]}"#;
+        let change_type =
+            r#"{"type":"object","properties":{"name":{"type":"integer"}},"required":["name"]}"#;
+
+        let old = build(SchemaType::JSON, old, SchemaCompatibility::Backward);
+        let new = build(
+            SchemaType::JSON,
+            add_required,
+            SchemaCompatibility::Backward,
+        );
+        assert!(check_compatibility(&old, &new).is_err());
+
+        let mut forward = old.clone();
+        forward.compatibility = SchemaCompatibility::Forward;
+        let mut new_forward = new.clone();
+        new_forward.compatibility = SchemaCompatibility::Forward;
+        assert!(check_compatibility(&forward, &new_forward).is_ok());
+
+        let mut full = old.clone();
+        full.compatibility = SchemaCompatibility::Full;
+        let new = build(SchemaType::JSON, change_type, SchemaCompatibility::Full);
+        assert!(check_compatibility(&full, &new).is_err());
+
+        let mut none = old.clone();
+        none.compatibility = SchemaCompatibility::None;
+        assert!(check_compatibility(&none, &new).is_ok());
+    }
+
+    #[test]
+    pub fn compatibility_mode_change_test() {
+        let schema = r#"{"type":"object","properties":{"name":{"type":"string"}}}"#;
+        let full = build(SchemaType::JSON, schema, SchemaCompatibility::Full);
+        let backward = build(SchemaType::JSON, schema, SchemaCompatibility::Backward);
+        let none = build(SchemaType::JSON, schema, SchemaCompatibility::None);
+
+        // a guarantee cannot be dropped by an update
+        assert!(check_compatibility(&full, &backward).is_err());
+        assert!(check_compatibility(&backward, &none).is_err());
+        assert!(check_compatibility(&full, &none).is_err());
+
+        // but the mode can be kept or strengthened
+        assert!(check_compatibility(&full, &full).is_ok());
+        assert!(check_compatibility(&backward, &full).is_ok());
+        assert!(check_compatibility(&none, &backward).is_ok());
+    }
+
+    #[test]
+    pub fn protobuf_compatibility_test() {
+        let old = r#"
+            syntax = "proto3";
+            package test;
+            message Person { string name = 1; uint32 age = 2; }
+        "#;
+        let add_field = r#"
+            syntax = "proto3";
+            package test;
+            message Person { string name = 1; uint32 age = 2; string email = 3; }
+        "#;
+        let reuse_number = r#"
+            syntax = "proto3";
+            package test;
+            message Person { string name = 1; string age = 2; }
+        "#;
+
+        let old = build(SchemaType::PROTOBUF, old, SchemaCompatibility::Full);
+        let ok = build(SchemaType::PROTOBUF, add_field, SchemaCompatibility::Full);
+        let bad = build(
+            SchemaType::PROTOBUF,
+            reuse_number,
+            SchemaCompatibility::Full,
+        );
+        assert!(schema_check(&ok).is_ok());
+        assert!(check_compatibility(&old, &ok).is_ok());
+        assert!(check_compatibility(&old, &bad).is_err());
+
+        let mut missing = ok.clone();
+        missing.message_name = "test.Unknown".to_string();
+        assert!(schema_check(&missing).is_err());
+    }
+}
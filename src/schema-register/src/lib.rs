@@ -14,6 +14,8 @@
 
 #![allow(clippy::result_large_err)]
 pub mod avro;
+pub mod compatibility;
 pub mod json;
 pub mod protobuf;
 pub mod schema;
+pub mod transcode;
@@ -21,14 +21,27 @@ pub fn protobuf_validate(
     data: &[u8],
     message_name: &str,
 ) -> Result<bool, CommonError> {
-    let context = Context::parse([schema_data.schema.as_str()]).map_err(|err| {
+    let context = parse_protobuf_schema(schema_data)?;
+    protobuf_validate_by_context(&context, schema_data, data, message_name)
+}
+
+pub fn parse_protobuf_schema(schema_data: &SchemaData) -> Result<Context, CommonError> {
+    Context::parse([schema_data.schema.as_str()]).map_err(|err| {
         CommonError::CommonError(format!(
             "Failed to parse schema {}: {}",
             schema_data.name.as_str(),
             err
         ))
-    })?;
+    })
+}
 
+/// Validate with a context already parsed from `schema_data`.
+pub fn protobuf_validate_by_context(
+    context: &Context,
+    schema_data: &SchemaData,
+    data: &[u8],
+    message_name: &str,
+) -> Result<bool, CommonError> {
     let message = context.get_message(message_name).ok_or_else(|| {
         CommonError::CommonError(format!(
             "Message {} not found in schema {}",
@@ -37,7 +50,7 @@ pub fn protobuf_validate(
         ))
     })?;
 
-    let decoded = message.decode(data, &context);
+    let decoded = message.decode(data, context);
 
     // Check if there are any unknown or incomplete fields
     for field in decoded.fields {
@@ -55,7 +68,7 @@ pub fn protobuf_validate(
 #[cfg(test)]
 mod test {
     use crate::protobuf::protobuf_validate;
-    use metadata_struct::schema::{SchemaData, SchemaType};
+    use metadata_struct::schema::{SchemaCompatibility, SchemaData, SchemaType};
 
     #[test]
     pub fn protobuf_validate_test() {
@@ -81,6 +94,9 @@ pub fn protofish_example_test() {
             schema_type: SchemaType::PROTOBUF,
             desc: "".to_string(),
             schema: schema.to_string(),
+            message_name: "".to_string(),
+            version: 1,
+            compatibility: SchemaCompatibility::None,
         };
 
         let res = protobuf_validate(&schema_data, b"\x0a\x05Perch", "Proto.Request");
@@ -126,6 +142,9 @@ pub fn protofish_schema_test() {
             schema_type: SchemaType::PROTOBUF,
             desc: "".to_string(),
             schema: schema.to_string(),
+            message_name: "".to_string(),
+            version: 1,
+            compatibility: SchemaCompatibility::None,
         };
 
         // ----- Experience -----
@@ -12,13 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::str::from_utf8;
+use std::{str::from_utf8, sync::Arc};
 
 use common_base::error::common::CommonError;
 use dashmap::DashMap;
 use metadata_struct::schema::{SchemaData, SchemaResourceBind, SchemaType};
+use tracing::warn;
 
-use crate::{avro::avro_validate, json::json_validate};
+use crate::{
+    avro::avro_validate,
+    json::json_validate,
+    protobuf::protobuf_validate_by_context,
+    transcode::{transcode, transcode_to_json, ParsedSchema, TRANSCODE_TARGET_JSON},
+};
 
 #[derive(Default)]
 pub struct SchemaRegisterManager {
@@ -28,6 +34,8 @@ pub struct SchemaRegisterManager {
     pub resource_schema_list: DashMap<String, Vec<String>>,
     // (Schema, Vec<Resource>)
     pub schema_resource_list: DashMap<String, Vec<String>>,
+    // (SchemaName, parsed schema definition)
+    parsed_schema_list: DashMap<String, Arc<ParsedSchema>>,
 }
 
 impl SchemaRegisterManager {
@@ -36,6 +44,7 @@ pub fn new() -> Self {
             schema_list: DashMap::with_capacity(2),
             resource_schema_list: DashMap::with_capacity(2),
             schema_resource_list: DashMap::with_capacity(2),
+            parsed_schema_list: DashMap::with_capacity(2),
         }
     }
 
@@ -52,10 +61,28 @@ pub fn validate(&self, resource: &str, data: &[u8]) -> Result<bool, CommonError>
                 if let Some(schema) = self.schema_list.get(schema_name) {
                     match schema.schema_type {
                         SchemaType::JSON => {
-                            let raw = from_utf8(data).unwrap();
+                            let Ok(raw) = from_utf8(data) else {
+                                return Ok(false);
+                            };
                             return json_validate(&schema.schema, raw);
                         }
-                        SchemaType::PROTOBUF => {}
+                        SchemaType::PROTOBUF => {
+                            // schemas created before the message name was required cannot
+                            // be validated, a warning is logged when they are loaded
+                            if schema.message_name.is_empty() {
+                                continue;
+                            }
+                            let parsed = self.get_parsed_schema(&schema)?;
+                            let Some(context) = parsed.proto_context() else {
+                                continue;
+                            };
+                            return protobuf_validate_by_context(
+                                context,
+                                &schema,
+                                data,
+                                &schema.message_name,
+                            );
+                        }
                         SchemaType::AVRO => {
                             return avro_validate(&schema.schema, data);
                         }
@@ -66,15 +93,66 @@ pub fn validate(&self, resource: &str, data: &[u8]) -> Result<bool, CommonError>
         Ok(true)
     }
 
+    // Re-encode a payload published on `resource` into `target`, which is either
+    // `json` or the name of a registered schema. Returns None when no schema is
+    // bound to the resource, so the payload should be delivered as-is.
+    pub fn transcode(
+        &self,
+        resource: &str,
+        data: &[u8],
+        target: &str,
+    ) -> Result<Option<Vec<u8>>, CommonError> {
+        let Some(source) = self
+            .get_bind_schema_by_resource(resource)
+            .into_iter()
+            .next()
+        else {
+            return Ok(None);
+        };
+
+        if target.eq_ignore_ascii_case(TRANSCODE_TARGET_JSON) {
+            let source = self.get_parsed_schema(&source)?;
+            return Ok(Some(transcode_to_json(&source, data)?));
+        }
+
+        let Some(target_schema) = self.get_schema(target) else {
+            return Err(CommonError::CommonError(format!(
+                "Transcode target schema {target} does not exist"
+            )));
+        };
+        let source = self.get_parsed_schema(&source)?;
+        let target_schema = self.get_parsed_schema(&target_schema)?;
+        Ok(Some(transcode(&source, &target_schema, data)?))
+    }
+
     // Schema
     pub fn add_schema(&self, schema: SchemaData) {
+        if schema.schema_type == SchemaType::PROTOBUF && schema.message_name.is_empty() {
+            warn!(
+                "Protobuf schema {} has no message name, payloads will not be validated against it. Update the schema with a message name to enable the validation.",
+                schema.name
+            );
+        }
+        self.parsed_schema_list.remove(&schema.name);
         self.schema_list.insert(schema.name.clone(), schema);
     }
 
     pub fn remove_schema(&self, schema_name: &str) {
+        self.parsed_schema_list.remove(schema_name);
         self.schema_list.remove(schema_name);
     }
 
+    // The schema is parsed once per schema version instead of once per message
+    fn get_parsed_schema(&self, schema: &SchemaData) -> Result<Arc<ParsedSchema>, CommonError> {
+        if let Some(parsed) = self.parsed_schema_list.get(&schema.name) {
+            return Ok(parsed.clone());
+        }
+        let parsed = Arc::new(ParsedSchema::parse(schema.clone())?);
+        self.parsed_schema_list
+            .insert(schema.name.clone(), parsed.clone());
+        Ok(parsed)
+    }
+
     pub fn get_schema(&self, schema_name: &str) -> Option<SchemaData> {
         if let Some(schema) = self.schema_list.get(schema_name) {
             return Some(schema.clone());
@@ -148,7 +226,9 @@ pub fn get_bind_resource_by_schema(&self, schema_name: &str) -> Vec<String> {
 mod test {
     use super::SchemaRegisterManager;
     use apache_avro::{Schema, Writer};
-    use metadata_struct::schema::{SchemaData, SchemaResourceBind, SchemaType};
+    use metadata_struct::schema::{
+        SchemaCompatibility, SchemaData, SchemaResourceBind, SchemaType,
+    };
     use serde::{Deserialize, Serialize};
     use serde_json::json;
 
@@ -171,6 +251,9 @@ pub fn json_schema_test() {
             schema: schema_json_content.to_string(),
             schema_type: SchemaType::JSON,
             desc: "test".to_string(),
+            message_name: "".to_string(),
+            version: 1,
+            compatibility: SchemaCompatibility::None,
         });
 
         let topic_name = "t1".to_string();
@@ -242,6 +325,9 @@ pub fn avro_schema_test() {
             schema: schema_avro_content.to_string(),
             schema_type: SchemaType::AVRO,
             desc: "test".to_string(),
+            message_name: "".to_string(),
+            version: 1,
+            compatibility: SchemaCompatibility::None,
         });
 
         let topic_name = "t1".to_string();
@@ -298,4 +384,91 @@ pub fn avro_schema_test() {
         println!("{result:?}");
         assert!(result.is_err());
     }
+
+    #[test]
+    pub fn json_schema_non_utf8_test() {
+        let schema_manager = SchemaRegisterManager::new();
+        schema_manager.add_schema(SchemaData {
+            cluster_name: "test1".to_string(),
+            name: "schema1".to_string(),
+            schema: r#"{"type": "object"}"#.to_string(),
+            schema_type: SchemaType::JSON,
+            desc: "test".to_string(),
+            message_name: "".to_string(),
+            version: 1,
+            compatibility: SchemaCompatibility::None,
+        });
+        schema_manager.add_bind(&SchemaResourceBind {
+            cluster_name: "test1".to_string(),
+            resource_name: "t1".to_string(),
+            schema_name: "schema1".to_string(),
+        });
+
+        let result = schema_manager.validate("t1", &[0xff, 0xfe, 0xfd]);
+        assert!(result.is_ok());
+        assert!(!result.unwrap());
+    }
+
+    #[test]
+    pub fn protobuf_schema_test() {
+        let schema_manager = SchemaRegisterManager::new();
+        schema_manager.add_schema(SchemaData {
+            cluster_name: "test1".to_string(),
+            name: "schema1".to_string(),
+            schema: r#"
+                syntax = "proto3";
+                package Proto;
+                message Request { string kind = 1; }
+            "#
+            .to_string(),
+            schema_type: SchemaType::PROTOBUF,
+            desc: "test".to_string(),
+            message_name: "Proto.Request".to_string(),
+            version: 1,
+            compatibility: SchemaCompatibility::None,
+        });
+        schema_manager.add_bind(&SchemaResourceBind {
+            cluster_name: "test1".to_string(),
+            resource_name: "t1".to_string(),
+            schema_name: "schema1".to_string(),
+        });
+
+        let result = schema_manager.validate("t1", b"\x0a\x05Perch");
+        assert!(result.is_ok());
+        assert!(result.unwrap());
+
+        let result = schema_manager.validate("t1", b"\x12\x07Unknown\x0a\x0fAtlantic ");
+        assert!(result.is_ok());
+        assert!(!result.unwrap());
+    }
+
+    #[test]
+    pub fn protobuf_schema_without_message_name_test() {
+        let schema_manager = SchemaRegisterManager::new();
+        schema_manager.add_schema(SchemaData {
+            cluster_name: "test1".to_string(),
+            name: "schema1".to_string(),
+            schema: r#"
+                syntax = "proto3";
+                package Proto;
+                message Request { string kind = 1; }
+            "#
+            .to_string(),
+            schema_type: SchemaType::PROTOBUF,
+            desc: "test".to_string(),
+            message_name: "".to_string(),
+            version: 1,
+            compatibility: SchemaCompatibility::None,
+        });
+        schema_manager.add_bind(&SchemaResourceBind {
+            cluster_name: "test1".to_string(),
+            resource_name: "t1".to_string(),
+            schema_name: "schema1".to_string(),
+        });
+
+        // legacy schemas are skipped instead of rejecting every message
+        let result = schema_manager.validate("t1", b"\x12\x07Unknown\x0a\x0fAtlantic ");
+        assert!(result.is_ok());
+        assert!(result.unwrap());
+    }
 }
@@ -0,0 +1,408 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use apache_avro::{Reader, Schema, Writer};
+use base64::{engine::general_purpose, Engine as _};
+use bytes::Bytes;
+use common_base::error::common::CommonError;
+use metadata_struct::schema::{SchemaData, SchemaType};
+use protofish::{
+    context::{MessageInfo, Multiplicity, ValueType},
+    decode::{EnumValue, FieldValue, MessageValue, PackedArray, Value as ProtoValue},
+    prelude::Context,
+};
+use serde_json::{Map, Number, Value};
+
+use crate::protobuf::parse_protobuf_schema;
+
+/// Target format name that asks for the payload to be delivered as JSON.
+pub const TRANSCODE_TARGET_JSON: &str = "json";
+
+/// A schema with its definition parsed, so it is parsed once and not for every payload.
+pub struct ParsedSchema {
+    pub schema: SchemaData,
+    definition: SchemaDefinition,
+}
+
+enum SchemaDefinition {
+    Json,
+    Avro(Schema),
+    Protobuf(Context),
+}
+
+impl ParsedSchema {
+    pub fn parse(schema: SchemaData) -> Result<Self, CommonError> {
+        let definition = match schema.schema_type {
+            SchemaType::JSON => SchemaDefinition::Json,
+            SchemaType::AVRO => SchemaDefinition::Avro(Schema::parse_str(&schema.schema)?),
+            SchemaType::PROTOBUF => SchemaDefinition::Protobuf(parse_protobuf_schema(&schema)?),
+        };
+        Ok(ParsedSchema { schema, definition })
+    }
+
+    /// The parsed .proto of a PROTOBUF schema.
+    pub fn proto_context(&self) -> Option<&Context> {
+        match &self.definition {
+            SchemaDefinition::Protobuf(context) => Some(context),
+            _ => None,
+        }
+    }
+}
+
+/// Converts a payload written with `source` into the wire format of `target`.
+/// JSON is used as the intermediate representation.
+pub fn transcode(
+    source: &ParsedSchema,
+    target: &ParsedSchema,
+    data: &[u8],
+) -> Result<Vec<u8>, CommonError> {
+    if source.schema.name == target.schema.name {
+        return Ok(data.to_vec());
+    }
+    let value = decode_to_json(source, data)?;
+    encode_from_json(target, &value)
+}
+
+/// Converts a payload written with `source` into a JSON document.
+pub fn transcode_to_json(source: &ParsedSchema, data: &[u8]) -> Result<Vec<u8>, CommonError> {
+    if source.schema.schema_type == SchemaType::JSON {
+        return Ok(data.to_vec());
+    }
+    let value = decode_to_json(source, data)?;
+    Ok(serde_json::to_vec(&value)?)
+}
+
+pub fn decode_to_json(parsed: &ParsedSchema, data: &[u8]) -> Result<Value, CommonError> {
+    match &parsed.definition {
+        SchemaDefinition::Json => Ok(serde_json::from_slice(data)?),
+        SchemaDefinition::Avro(avro_schema) => {
+            let reader = Reader::with_schema(avro_schema, data)?;
+            let mut records = Vec::new();
+            for record in reader {
+                records.push(Value::try_from(record?)?);
+            }
+            // a single-record container is the common case for MQTT payloads
+            if records.len() == 1 {
+                Ok(records.remove(0))
+            } else {
+                Ok(Value::Array(records))
+            }
+        }
+        SchemaDefinition::Protobuf(context) => {
+            let message = get_proto_message(context, &parsed.schema)?;
+            let decoded = message.decode(data, context);
+            Ok(proto_message_to_json(context, &decoded))
+        }
+    }
+}
+
+pub fn encode_from_json(parsed: &ParsedSchema, value: &Value) -> Result<Vec<u8>, CommonError> {
+    match &parsed.definition {
+        SchemaDefinition::Json => Ok(serde_json::to_vec(value)?),
+        SchemaDefinition::Avro(avro_schema) => {
+            let mut writer = Writer::new(avro_schema, Vec::new());
+            let records = match value {
+                Value::Array(list) => list.clone(),
+                other => vec![other.clone()],
+            };
+            for record in records {
+                let avro_value = apache_avro::to_value(record)?.resolve(avro_schema)?;
+                writer.append(avro_value)?;
+            }
+            Ok(writer.into_inner()?)
+        }
+        SchemaDefinition::Protobuf(context) => {
+            let message = get_proto_message(context, &parsed.schema)?;
+            let encoded = json_to_proto_message(context, message, value)?;
+            Ok(encoded.encode(context).to_vec())
+        }
+    }
+}
+
+fn get_proto_message<'a>(
+    context: &'a Context,
+    schema: &SchemaData,
+) -> Result<&'a MessageInfo, CommonError> {
+    context.get_message(&schema.message_name).ok_or_else(|| {
+        CommonError::CommonError(format!(
+            "Message {} not found in schema {}",
+            schema.message_name, schema.name
+        ))
+    })
+}
+
+fn is_repeated(multiplicity: &Multiplicity) -> bool {
+    matches!(
+        multiplicity,
+        Multiplicity::Repeated | Multiplicity::RepeatedPacked
+    )
+}
+
+fn proto_message_to_json(context: &Context, message: &MessageValue) -> Value {
+    let info = context.resolve_message(message.msg_ref);
+    let mut map = Map::new();
+    for field in message.fields.iter() {
+        // unknown fields have no name to map to
+        let Some(def) = info.get_field(field.number) else {
+            continue;
+        };
+        let value = proto_value_to_json(context, &field.value);
+        if is_repeated(&def.multiplicity) {
+            let entry = map
+                .entry(def.name.clone())
+                .or_insert_with(|| Value::Array(Vec::new()));
+            if let Value::Array(list) = entry {
+                match value {
+                    Value::Array(values) => list.extend(values),
+                    other => list.push(other),
+                }
+            }
+        } else {
+            map.insert(def.name.clone(), value);
+        }
+    }
+    Value::Object(map)
+}
+
+fn proto_value_to_json(context: &Context, value: &ProtoValue) -> Value {
+    match value {
+        ProtoValue::Double(v) => float_to_json(*v),
+        ProtoValue::Float(v) => float_to_json(*v as f64),
+        ProtoValue::Int32(v) | ProtoValue::SInt32(v) | ProtoValue::SFixed32(v) => Value::from(*v),
+        ProtoValue::Int64(v) | ProtoValue::SInt64(v) | ProtoValue::SFixed64(v) => Value::from(*v),
+        ProtoValue::UInt32(v) | ProtoValue::Fixed32(v) => Value::from(*v),
+        ProtoValue::UInt64(v) | ProtoValue::Fixed64(v) => Value::from(*v),
+        ProtoValue::Bool(v) => Value::Bool(*v),
+        ProtoValue::String(v) => Value::String(v.clone()),
+        ProtoValue::Bytes(v) => Value::String(general_purpose::STANDARD.encode(v)),
+        ProtoValue::Message(v) => proto_message_to_json(context, v),
+        ProtoValue::Enum(v) => {
+            let info = context.resolve_enum(v.enum_ref);
+            match info.get_field_by_value(v.value) {
+                Some(field) => Value::String(field.name.clone()),
+                None => Value::from(v.value),
+            }
+        }
+        ProtoValue::Packed(v) => packed_to_json(v),
+        ProtoValue::Unknown(_) | ProtoValue::Incomplete(_, _) => Value::Null,
+    }
+}
+
+fn packed_to_json(packed: &PackedArray) -> Value {
+    match packed {
+        PackedArray::Double(list) => Value::Array(list.iter().map(|v| float_to_json(*v)).collect()),
+        PackedArray::Float(list) => {
+            Value::Array(list.iter().map(|v| float_to_json(*v as f64)).collect())
+        }
+        PackedArray::Int32(list) | PackedArray::SInt32(list) | PackedArray::SFixed32(list) => {
+            Value::from(list.clone())
+        }
+        PackedArray::Int64(list) | PackedArray::SInt64(list) | PackedArray::SFixed64(list) => {
+            Value::from(list.clone())
+        }
+        PackedArray::UInt32(list) | PackedArray::Fixed32(list) => Value::from(list.clone()),
+        PackedArray::UInt64(list) | PackedArray::Fixed64(list) => Value::from(list.clone()),
+        PackedArray::Bool(list) => Value::from(list.clone()),
+    }
+}
+
+fn float_to_json(v: f64) -> Value {
+    Number::from_f64(v)
+        .map(Value::Number)
+        .unwrap_or(Value::Null)
+}
+
+fn json_to_proto_message(
+    context: &Context,
+    info: &MessageInfo,
+    value: &Value,
+) -> Result<MessageValue, CommonError> {
+    let Value::Object(map) = value else {
+        return Err(CommonError::CommonError(format!(
+            "Message {} expects a JSON object",
+            info.full_name
+        )));
+    };
+
+    let mut fields = Vec::new();
+    for (name, raw) in map {
+        let Some(def) = info.iter_fields().find(|field| &field.name == name) else {
+            return Err(CommonError::CommonError(format!(
+                "Field {} not found in message {}",
+                name, info.full_name
+            )));
+        };
+
+        if raw.is_null() {
+            continue;
+        }
+
+        let values = match raw {
+            Value::Array(list) if is_repeated(&def.multiplicity) => list.clone(),
+            other => vec![other.clone()],
+        };
+        for item in values {
+            fields.push(FieldValue {
+                number: def.number,
+                value: json_to_proto_value(context, &def.field_type, &item, name)?,
+            });
+        }
+    }
+
+    Ok(MessageValue {
+        msg_ref: info.self_ref,
+        garbage: None,
+        fields,
+    })
+}
+
+fn json_to_proto_value(
+    context: &Context,
+    value_type: &ValueType,
+    value: &Value,
+    name: &str,
+) -> Result<ProtoValue, CommonError> {
+    let type_err = || {
+        CommonError::CommonError(format!(
+            "Field {name} value {value} does not match type {value_type:?}"
+        ))
+    };
+    let as_i64 = || value.as_i64().ok_or_else(type_err);
+    let as_u64 = || value.as_u64().ok_or_else(type_err);
+
+    let result = match value_type {
+        ValueType::Double => ProtoValue::Double(value.as_f64().ok_or_else(type_err)?),
+        ValueType::Float => ProtoValue::Float(value.as_f64().ok_or_else(type_err)? as f32),
+        ValueType::Int32 => ProtoValue::Int32(as_i64()? as i32),
+        ValueType::Int64 => ProtoValue::Int64(as_i64()?),
+        ValueType::UInt32 => ProtoValue::UInt32(as_u64()? as u32),
+        ValueType::UInt64 => ProtoValue::UInt64(as_u64()?),
+        ValueType::SInt32 => ProtoValue::SInt32(as_i64()? as i32),
+        ValueType::SInt64 => ProtoValue::SInt64(as_i64()?),
+        ValueType::Fixed32 => ProtoValue::Fixed32(as_u64()? as u32),
+        ValueType::Fixed64 => ProtoValue::Fixed64(as_u64()?),
+        ValueType::SFixed32 => ProtoValue::SFixed32(as_i64()? as i32),
+        ValueType::SFixed64 => ProtoValue::SFixed64(as_i64()?),
+        ValueType::Bool => ProtoValue::Bool(value.as_bool().ok_or_else(type_err)?),
+        ValueType::String => ProtoValue::String(value.as_str().ok_or_else(type_err)?.to_string()),
+        ValueType::Bytes => {
+            let raw = value.as_str().ok_or_else(type_err)?;
+            let data = general_purpose::STANDARD
+                .decode(raw)
+                .map_err(|e| CommonError::CommonError(e.to_string()))?;
+            ProtoValue::Bytes(Bytes::from(data))
+        }
+        ValueType::Message(msg_ref) => {
+            let info = context.resolve_message(*msg_ref);
+            ProtoValue::Message(Box::new(json_to_proto_message(context, info, value)?))
+        }
+        ValueType::Enum(enum_ref) => {
+            let info = context.resolve_enum(*enum_ref);
+            let number = match value {
+                Value::String(s) => info.get_field_by_name(s).ok_or_else(type_err)?.value,
+                _ => as_i64()?,
+            };
+            ProtoValue::Enum(EnumValue {
+                enum_ref: *enum_ref,
+                value: number,
+            })
+        }
+    };
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decode_to_json, encode_from_json, transcode, transcode_to_json, ParsedSchema};
+    use metadata_struct::schema::{SchemaCompatibility, SchemaData, SchemaType};
+    use serde_json::json;
+
+    fn build(
+        name: &str,
+        schema_type: SchemaType,
+        schema: &str,
+        message_name: &str,
+    ) -> ParsedSchema {
+        ParsedSchema::parse(SchemaData {
+            cluster_name: "test".to_string(),
+            name: name.to_string(),
+            schema_type,
+            desc: "".to_string(),
+            schema: schema.to_string(),
+            message_name: message_name.to_string(),
+            version: 1,
+            compatibility: SchemaCompatibility::None,
+        })
+        .unwrap()
+    }
+
+    fn avro_schema() -> ParsedSchema {
+        build(
+            "avro",
+            SchemaType::AVRO,
+            r#"{"type":"record","name":"sensor","fields":[{"name":"id","type":"string"},{"name":"temp","type":"long"}]}"#,
+            "",
+        )
+    }
+
+    fn proto_schema() -> ParsedSchema {
+        build(
+            "proto",
+            SchemaType::PROTOBUF,
+            r#"
+                syntax = "proto3";
+                package iot;
+                message Sensor { string id = 1; int64 temp = 2; }
+            "#,
+            "iot.Sensor",
+        )
+    }
+
+    #[test]
+    pub fn avro_json_transcode_test() {
+        let avro = avro_schema();
+        let value = json!({"id": "s1", "temp": 21});
+
+        let encoded = encode_from_json(&avro, &value).unwrap();
+        let raw = transcode_to_json(&avro, &encoded).unwrap();
+        let decoded: serde_json::Value = serde_json::from_slice(&raw).unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    pub fn protobuf_json_transcode_test() {
+        let proto = proto_schema();
+        let value = json!({"id": "s1", "temp": 21});
+
+        let encoded = encode_from_json(&proto, &value).unwrap();
+        assert_eq!(decode_to_json(&proto, &encoded).unwrap(), value);
+
+        let bad = json!({"id": "s1", "unknown": 1});
+        assert!(encode_from_json(&proto, &bad).is_err());
+    }
+
+    #[test]
+    pub fn avro_protobuf_transcode_test() {
+        let avro = avro_schema();
+        let proto = proto_schema();
+        let value = json!({"id": "s1", "temp": 21});
+
+        let avro_data = encode_from_json(&avro, &value).unwrap();
+        let proto_data = transcode(&avro, &proto, &avro_data).unwrap();
+        assert_eq!(decode_to_json(&proto, &proto_data).unwrap(), value);
+
+        let back = transcode(&proto, &avro, &proto_data).unwrap();
+        assert_eq!(decode_to_json(&avro, &back).unwrap(), value);
+    }
+}
@@ -247,6 +247,17 @@ async fn get_offset_by_timestamp(
         }
     }
 
+    async fn get_shard_end_offset(
+        &self,
+        _namespace: String,
+        _shard_name: String,
+    ) -> Result<u64, CommonError> {
+        Err(CommonError::NotSupportFeature(
+            "JournalStorageAdapter".to_string(),
+            "get_shard_end_offset".to_string(),
+        ))
+    }
+
     async fn commit_offset(
         &self,
         group_name: String,
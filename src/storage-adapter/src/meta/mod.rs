@@ -284,6 +284,7 @@ async fn thread_batch_write(
                 SetRequest {
                     key: Self::shard_record_key(&namespace, &shard_name, start_offset),
                     value: serde_json::to_string(&msg)?,
+                    ..Default::default()
                 },
             )
             .await?;
@@ -296,6 +297,7 @@ async fn thread_batch_write(
                     SetRequest {
                         key: Self::key_offset_key(&namespace, &shard_name, &msg.key),
                         value: serde_json::to_string(&start_offset)?,
+                        ..Default::default()
                     },
                 )
                 .await?;
@@ -309,6 +311,7 @@ async fn thread_batch_write(
                     SetRequest {
                         key: Self::tag_offsets_key(&namespace, &shard_name, tag, start_offset),
                         value: serde_json::to_string(&start_offset)?,
+                        ..Default::default()
                     },
                 )
                 .await?;
@@ -324,6 +327,7 @@ async fn thread_batch_write(
             SetRequest {
                 key: Self::shard_offset_key(&namespace, &shard_name),
                 value: serde_json::to_string(&start_offset)?,
+                ..Default::default()
             },
         )
         .await?;
@@ -359,6 +363,7 @@ async fn create_shard(&self, shard: ShardInfo) -> Result<(), CommonError> {
             SetRequest {
                 key: Self::shard_offset_key(&namespace, &shard_name),
                 value: serde_json::to_string(&0_u64)?,
+                ..Default::default()
             },
         )
         .await?;
@@ -369,6 +374,7 @@ async fn create_shard(&self, shard: ShardInfo) -> Result<(), CommonError> {
             SetRequest {
                 key: Self::shard_info_key(&namespace, &shard_name),
                 value: serde_json::to_string(&shard)?,
+                ..Default::default()
             },
         )
         .await?;
@@ -630,6 +636,23 @@ async fn get_offset_by_timestamp(
         Ok(None)
     }
 
+    async fn get_shard_end_offset(
+        &self,
+        namespace: String,
+        shard_name: String,
+    ) -> Result<u64, CommonError> {
+        let reply = placement_get(
+            &self.client_pool,
+            &self.addrs,
+            GetRequest {
+                key: Self::shard_offset_key(&namespace, &shard_name),
+            },
+        )
+        .await?;
+
+        Ok(reply.value.parse::<u64>()?)
+    }
+
     async fn get_offset_by_group(
         &self,
         group_name: String,
@@ -670,6 +693,7 @@ async fn commit_offset(
                 SetRequest {
                     key: Self::group_record_offsets_key(&group_name, &namespace, &shard_name),
                     value: serde_json::to_string(&offset)?,
+                    ..Default::default()
                 },
             )
             .await?;
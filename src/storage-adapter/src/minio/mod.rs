@@ -509,6 +509,19 @@ async fn get_offset_by_timestamp(
         Ok(None)
     }
 
+    async fn get_shard_end_offset(
+        &self,
+        namespace: String,
+        shard_name: String,
+    ) -> Result<u64, CommonError> {
+        let offset_bytes = self
+            .op
+            .read(&Self::offsets_path(&namespace, &shard_name))
+            .await?
+            .to_vec();
+        Ok(serde_json::from_slice::<u64>(&offset_bytes)?)
+    }
+
     async fn get_offset_by_group(
         &self,
         group_name: String,
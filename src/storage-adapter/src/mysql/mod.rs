@@ -576,6 +576,23 @@ async fn get_offset_by_timestamp(
         .map_err(|e| CommonError::CommonError(format!("Failed to get offset by timestamp: {e}")))
     }
 
+    async fn get_shard_end_offset(
+        &self,
+        namespace: String,
+        shard_name: String,
+    ) -> Result<u64, CommonError> {
+        let mut conn = self.pool.get()?;
+
+        let sql = format!(
+            "SELECT MAX(`offset`) FROM `{}`",
+            Self::record_table_name(&namespace, &shard_name)
+        );
+
+        conn.query_first(sql)
+            .map(|offset: Option<Option<u64>>| offset.flatten().map(|v| v + 1).unwrap_or(0))
+            .map_err(|e| CommonError::CommonError(format!("Failed to get shard end offset: {e}")))
+    }
+
     async fn get_offset_by_group(
         &self,
         group_name: String,
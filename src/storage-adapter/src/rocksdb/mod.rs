@@ -568,6 +568,21 @@ async fn get_offset_by_timestamp(
         Ok(None)
     }
 
+    async fn get_shard_end_offset(
+        &self,
+        namespace: String,
+        shard_name: String,
+    ) -> Result<u64, CommonError> {
+        let cf = self.db.cf_handle(DB_COLUMN_FAMILY).unwrap();
+        let shard_offset_key = Self::shard_offset_key(&namespace, &shard_name);
+
+        self.db
+            .read::<u64>(cf.clone(), shard_offset_key.as_str())?
+            .ok_or(CommonError::CommonError(format!(
+                "shard {shard_name} under namespace {namespace} not exists"
+            )))
+    }
+
     async fn get_offset_by_group(
         &self,
         group_name: String,
@@ -97,6 +97,13 @@ async fn get_offset_by_timestamp(
         timestamp: u64,
     ) -> Result<Option<ShardOffset>, CommonError>;
 
+    // The offset the next record of the shard will be written at.
+    async fn get_shard_end_offset(
+        &self,
+        namespace: String,
+        shard_name: String,
+    ) -> Result<u64, CommonError>;
+
     async fn get_offset_by_group(
         &self,
         group_name: String,
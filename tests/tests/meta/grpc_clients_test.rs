@@ -122,6 +122,8 @@ async fn test_heartbeat() {
         let request = HeartbeatRequest {
             cluster_name: cluster_name(),
             node_id: node_id(),
+            draining: false,
+            connection_num: 0,
         };
         let res = client.heartbeat(tonic::Request::new(request)).await;
         assert!(res.is_err());
@@ -168,6 +168,8 @@ async fn create_schema(
             schema_type,
             schema,
             desc: "Test schema".to_string(),
+            message_name: "".to_string(),
+            compatibility: "none".to_string(),
         };
         let res = admin_client.create_schema(&create_request).await;
         assert!(res.is_ok());